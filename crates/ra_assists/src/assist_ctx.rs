@@ -1,6 +1,6 @@
 //! This module defines `AssistCtx` -- the API surface that is exposed to assists.
 use hir::Semantics;
-use ra_db::FileRange;
+use ra_db::{FileRange, RelativePathBuf, SourceRootId};
 use ra_fmt::{leading_indent, reindent};
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
@@ -10,7 +10,9 @@ use ra_syntax::{
 };
 use ra_text_edit::TextEditBuilder;
 
-use crate::{AssistAction, AssistId, AssistLabel, GroupLabel, ResolvedAssist};
+use crate::{
+    AssistAction, AssistConfig, AssistFile, AssistId, AssistLabel, GroupLabel, ResolvedAssist,
+};
 use algo::SyntaxRewriter;
 
 #[derive(Clone, Debug)]
@@ -79,6 +81,7 @@ pub(crate) type AssistHandler = fn(AssistCtx) -> Option<Assist>;
 pub(crate) struct AssistCtx<'a> {
     pub(crate) sema: &'a Semantics<'a, RootDatabase>,
     pub(crate) db: &'a RootDatabase,
+    pub(crate) config: &'a AssistConfig,
     pub(crate) frange: FileRange,
     source_file: SourceFile,
     should_compute_edit: bool,
@@ -87,11 +90,12 @@ pub(crate) struct AssistCtx<'a> {
 impl<'a> AssistCtx<'a> {
     pub fn new(
         sema: &'a Semantics<'a, RootDatabase>,
+        config: &'a AssistConfig,
         frange: FileRange,
         should_compute_edit: bool,
     ) -> AssistCtx<'a> {
         let source_file = sema.parse(frange.file_id);
-        AssistCtx { sema, db: sema.db, frange, source_file, should_compute_edit }
+        AssistCtx { sema, db: sema.db, config, frange, source_file, should_compute_edit }
     }
 
     pub(crate) fn add_assist(
@@ -180,6 +184,7 @@ pub(crate) struct ActionBuilder {
     edit: TextEditBuilder,
     cursor_position: Option<TextUnit>,
     target: Option<TextRange>,
+    new_files: Vec<AssistFile>,
 }
 
 impl ActionBuilder {
@@ -227,6 +232,16 @@ impl ActionBuilder {
         self.target = Some(target)
     }
 
+    /// Creates a new file with the given contents.
+    pub(crate) fn create_file(
+        &mut self,
+        source_root: SourceRootId,
+        path: RelativePathBuf,
+        contents: impl Into<String>,
+    ) {
+        self.new_files.push(AssistFile { source_root, path, contents: contents.into() })
+    }
+
     /// Get access to the raw `TextEditBuilder`.
     pub(crate) fn text_edit_builder(&mut self) -> &mut TextEditBuilder {
         &mut self.edit
@@ -246,6 +261,7 @@ impl ActionBuilder {
             edit: self.edit.finish(),
             cursor_position: self.cursor_position,
             target: self.target,
+            new_files: self.new_files,
         }
     }
 }
@@ -1,7 +1,7 @@
 //! `AstTransformer`s are functions that replace nodes in an AST and can be easily combined.
 use rustc_hash::FxHashMap;
 
-use hir::{PathResolution, SemanticsScope};
+use hir::{HasSource, PathResolution, SemanticsScope};
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
     algo::SyntaxRewriter,
@@ -38,26 +38,52 @@ pub struct SubstituteTypeParams<'a> {
 }
 
 impl<'a> SubstituteTypeParams<'a> {
+    /// Substitutes the type parameters of `trait_` with the type arguments of
+    /// `impl_def`. Parameters which are omitted in the impl are replaced with
+    /// their defaults, so that the copied items don't refer to parameters which
+    /// don't exist in the impl. `Self` is left as is: it's valid in the impl
+    /// and always means the same type as in the trait.
     pub fn for_trait_impl(
         source_scope: &'a SemanticsScope<'a, RootDatabase>,
         // FIXME: there's implicit invariant that `trait_` and  `source_scope` match...
         trait_: hir::Trait,
         impl_def: ast::ImplDef,
     ) -> SubstituteTypeParams<'a> {
+        let db = source_scope.db;
         let substs = get_syntactic_substs(impl_def).unwrap_or_default();
+        let defaults = trait_
+            .source(db)
+            .value
+            .type_param_list()
+            .into_iter()
+            .flat_map(|it| it.type_params())
+            .map(|it| it.default_type());
         let generic_def: hir::GenericDef = trait_.into();
-        let substs_by_param: FxHashMap<_, _> = generic_def
-            .params(source_scope.db)
+        let params = generic_def
+            .params(db)
             .into_iter()
-            // this is a trait impl, so we need to skip the first type parameter -- this is a bit hacky
-            .skip(1)
-            .zip(substs.into_iter())
-            .collect();
-        return SubstituteTypeParams {
+            // this is a trait impl, so we need to skip the first type parameter (`Self`) -- this is a bit hacky
+            .skip(1);
+
+        let mut res = SubstituteTypeParams {
             source_scope,
-            substs: substs_by_param,
+            substs: FxHashMap::default(),
             previous: Box::new(NullTransformer),
         };
+        let mut substs = substs.into_iter();
+        for (param, default) in params.zip(defaults) {
+            let subst = match substs.next() {
+                Some(it) => it,
+                // Defaults can only refer to the preceding parameters, which
+                // are already in `res`.
+                None => match default {
+                    Some(default) => apply(&res, default),
+                    None => break,
+                },
+            };
+            res.substs.insert(param, subst);
+        }
+        return res;
 
         // FIXME: It would probably be nicer if we could get this via HIR (i.e. get the
         // trait ref, and then go from the types in the substs back to the syntax)
@@ -8,21 +8,21 @@ mod generated;
 use ra_db::FileRange;
 use test_utils::{assert_eq_text, extract_range_or_offset};
 
-use crate::resolved_assists;
+use crate::{resolved_assists, AssistConfig};
 
 fn check(assist_id: &str, before: &str, after: &str) {
     let (selection, before) = extract_range_or_offset(before);
     let (db, file_id) = crate::helpers::with_single_file(&before);
     let frange = FileRange { file_id, range: selection.into() };
 
-    let assist = resolved_assists(&db, frange)
+    let assist = resolved_assists(&db, &AssistConfig::default(), frange)
         .into_iter()
         .find(|assist| assist.label.id.0 == assist_id)
         .unwrap_or_else(|| {
             panic!(
                 "\n\nAssist is not applicable: {}\nAvailable assists: {}",
                 assist_id,
-                resolved_assists(&db, frange)
+                resolved_assists(&db, &AssistConfig::default(), frange)
                     .into_iter()
                     .map(|assist| assist.label.id.0)
                     .collect::<Vec<_>>()
@@ -21,6 +21,31 @@ impl Debug for S {
     )
 }
 
+#[test]
+fn doctest_add_default_impl() {
+    check(
+        "add_default_impl",
+        r#####"
+struct Config {
+    verbose: bool,<|>
+    name: String,
+}
+"#####,
+        r#####"
+struct Config {
+    verbose: bool,
+    name: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { verbose: Default::default(), name: todo!() }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_derive() {
     check(
@@ -41,6 +66,119 @@ struct Point {
     )
 }
 
+#[test]
+fn doctest_add_display_and_from_str_for_enum() {
+    check(
+        "add_display_and_from_str_for_enum",
+        r#####"
+enum Color { <|>Red, DarkGreen }
+"#####,
+        r#####"
+enum Color { Red, DarkGreen }
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Color::Red => "red",
+            Color::DarkGreen => "dark_green",
+        };
+        f.write_str(s)
+    }
+}
+
+impl std::str::FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "red" => Ok(Color::Red),
+            "dark_green" => Ok(Color::DarkGreen),
+            _ => Err(ParseColorError),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParseColorError;
+
+impl std::fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid `Color`")
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_enum_discriminants() {
+    check(
+        "add_enum_discriminants",
+        r#####"
+enum Color { <|>Red, Green, Blue = 4 }
+"#####,
+        r#####"
+#[repr(u8)]
+enum Color { Red = 0, Green = 1, Blue = 4 }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_enum_value_consts() {
+    check(
+        "add_enum_value_consts",
+        r#####"
+enum Color { <|>Red, DarkGreen }
+"#####,
+        r#####"
+enum Color { Red, DarkGreen }
+
+impl Color {
+    pub const RED: u8 = Color::Red as u8;
+    pub const DARK_GREEN: u8 = Color::DarkGreen as u8;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_enum_variant_methods() {
+    check(
+        "add_enum_variant_methods",
+        r#####"
+enum Value { <|>Int(i64), Unit }
+"#####,
+        r#####"
+enum Value { Int(i64), Unit }
+
+impl Value {
+    fn is_int(&self) -> bool {
+        matches!(self, Self::Int(..))
+    }
+
+    fn as_int(&self) -> Option<&i64> {
+        if let Self::Int(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    fn try_into_int(self) -> Result<i64, Self> {
+        if let Self::Int(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_explicit_type() {
     check(
@@ -187,6 +325,42 @@ impl Trait<u32> for () {
     )
 }
 
+#[test]
+fn doctest_add_inline() {
+    check(
+        "add_inline",
+        r#####"
+fn double(<|>x: u32) -> u32 {
+    x * 2
+}
+"#####,
+        r#####"
+#[inline]
+fn double(x: u32) -> u32 {
+    x * 2
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_must_use() {
+    check(
+        "add_must_use",
+        r#####"
+fn area(<|>width: u32, height: u32) -> u32 {
+    width * height
+}
+"#####,
+        r#####"
+#[must_use = ""]
+fn area(width: u32, height: u32) -> u32 {
+    width * height
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_new() {
     check(
@@ -209,6 +383,131 @@ impl<T: Clone> Ctx<T> {
     )
 }
 
+#[test]
+fn doctest_add_partial_eq_and_hash_impl() {
+    check(
+        "add_partial_eq_and_hash_impl",
+        r#####"
+struct Point {
+    <|>x: u32,
+    y: u32,<|>
+    cache: u32,
+}
+"#####,
+        r#####"
+struct Point {
+    x: u32,
+    y: u32,
+    cache: u32,
+}
+
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x && self.y == other.y
+    }
+}
+
+impl std::hash::Hash for Point {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.x.hash(state);
+        self.y.hash(state);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_partial_eq_impl() {
+    check(
+        "add_partial_eq_impl",
+        r#####"
+struct Point {
+    <|>x: u32,
+    y: u32,<|>
+    cache: u32,
+}
+"#####,
+        r#####"
+struct Point {
+    x: u32,
+    y: u32,
+    cache: u32,
+}
+
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x && self.y == other.y
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_self_sized_bound() {
+    check(
+        "add_self_sized_bound",
+        r#####"
+trait Shape {
+    fn <|>new() -> Self;
+    fn area(&self) -> f64;
+}
+"#####,
+        r#####"
+trait Shape {
+    fn new() -> Self where Self: Sized;
+    fn area(&self) -> f64;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_track_caller() {
+    check(
+        "add_track_caller",
+        r#####"
+fn expect_positive(<|>x: i32) -> i32 {
+    assert!(x > 0);
+    x
+}
+"#####,
+        r#####"
+#[track_caller]
+fn expect_positive(x: i32) -> i32 {
+    assert!(x > 0);
+    x
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_try_from_impl_for_enum() {
+    check(
+        "add_try_from_impl_for_enum",
+        r#####"
+enum Color { <|>Red, Green }
+"#####,
+        r#####"
+enum Color { Red, Green }
+
+impl std::convert::TryFrom<u8> for Color {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Color::Red),
+            1 => Ok(Color::Green),
+            _ => Err(value),
+        }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_apply_demorgan() {
     check(
@@ -260,6 +559,40 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_assert_eq_to_assert() {
+    check(
+        "convert_assert_eq_to_assert",
+        r#####"
+fn main() {
+    <|>assert_ne!(x, 0, "x must not be zero");
+}
+"#####,
+        r#####"
+fn main() {
+    assert!(x != 0, "x must not be zero");
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_assert_to_assert_eq() {
+    check(
+        "convert_assert_to_assert_eq",
+        r#####"
+fn main() {
+    <|>assert!(1 + 1 == 2, "math is broken");
+}
+"#####,
+        r#####"
+fn main() {
+    assert_eq!(1 + 1, 2, "math is broken");
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check(
@@ -284,6 +617,98 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_to_method() {
+    check(
+        "convert_to_method",
+        r#####"
+struct Counter { count: u32 }
+
+fn <|>increment(counter: &mut Counter, by: u32) {
+    counter.count += by;
+}
+
+fn main() {
+    let mut counter = Counter { count: 0 };
+    increment(&mut counter, 2);
+}
+"#####,
+        r#####"
+struct Counter { count: u32 }
+
+impl Counter {
+    fn increment(&mut self, by: u32) {
+        self.count += by;
+    }
+}
+
+fn main() {
+    let mut counter = Counter { count: 0 };
+    counter.increment(2);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_destructure_tuple_binding() {
+    check(
+        "destructure_tuple_binding",
+        r#####"
+struct Point;
+fn main() {
+    let <|>x = (Point, 92);
+    let p = x.0;
+}
+"#####,
+        r#####"
+struct Point;
+fn main() {
+    let (point, _) = (Point, 92);
+    let p = point;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_boxed_fn_type_alias() {
+    check(
+        "extract_boxed_fn_type_alias",
+        r#####"
+struct Button {
+    on_click: <|>Box<dyn Fn(u32)>,
+}
+
+fn set_handler(button: &mut Button, handler: Box<dyn Fn(u32)>) {}
+"#####,
+        r#####"
+type OnClick = Box<dyn Fn(u32)>;
+
+struct Button {
+    on_click: OnClick,
+}
+
+fn set_handler(button: &mut Button, handler: OnClick) {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_module_to_file() {
+    check(
+        "extract_module_to_file",
+        r#####"
+mod <|>foo {
+    fn bar() {}
+}
+"#####,
+        r#####"
+mod foo;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_fill_match_arms() {
     check(
@@ -357,6 +782,44 @@ fn foo<T: Copy + Clone>() { }
     )
 }
 
+#[test]
+fn doctest_hoist_common_match_arm_code() {
+    check(
+        "hoist_common_match_arm_code",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    <|>match action {
+        Action::Move { distance } => {
+            start();
+            move_by(distance);
+            finish();
+        }
+        Action::Stop => {
+            start();
+            finish();
+        }
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    start();
+    match action {
+        Action::Move { distance } => {
+            move_by(distance);
+        }
+        Action::Stop => {}
+    }
+    finish();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_local_variable() {
     check(
@@ -410,6 +873,39 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_make_boxed_fn_field_generic() {
+    check(
+        "make_boxed_fn_field_generic",
+        r#####"
+struct Button {
+    on_click: <|>Box<dyn Fn(u32)>,
+}
+
+impl Button {
+    fn click(&self) { (self.on_click)(1) }
+}
+
+fn main() {
+    let button = Button { on_click: Box::new(|_| ()) };
+}
+"#####,
+        r#####"
+struct Button<F: Fn(u32)> {
+    on_click: F,
+}
+
+impl<F: Fn(u32)> Button<F> {
+    fn click(&self) { (self.on_click)(1) }
+}
+
+fn main() {
+    let button = Button { on_click: |_| () };
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_make_raw_string() {
     check(
@@ -634,6 +1130,69 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_replace_impl_trait_with_generic() {
+    check(
+        "replace_impl_trait_with_generic",
+        r#####"
+fn print(value: <|>impl Display) {}
+"#####,
+        r#####"
+fn print<T: Display>(value: T) {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_index_with_get() {
+    check(
+        "replace_index_with_get",
+        r#####"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+struct Point { x: i32 }
+fn first_x(points: &[Point]) -> Option<i32> {
+    Some(points[<|>0].x)
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+struct Point { x: i32 }
+fn first_x(points: &[Point]) -> Option<i32> {
+    Some(points.get(0)?.x)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_is_some_with_if_let() {
+    check(
+        "replace_is_some_with_if_let",
+        r#####"
+enum Option<T> { Some(T), None }
+fn main() {
+    let x: Option<i32> = Option::Some(92);
+    if x.<|>is_some() {
+        let y = x.unwrap() + 1;
+    }
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+fn main() {
+    let x: Option<i32> = Option::Some(92);
+    if let Some(x) = x {
+        let y = x + 1;
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_let_with_if_let() {
     check(
@@ -711,3 +1270,60 @@ use std::{collections::HashMap};
 "#####,
     )
 }
+
+#[test]
+fn doctest_split_reexports() {
+    check(
+        "split_reexports",
+        r#####"
+mod fmt {
+    pub struct Debug;
+    pub struct Display;
+    pub struct Unused;
+}
+mod io {
+    pub struct Read;
+}
+pub use self::<|>{io::Read, fmt::*};
+
+fn main() {
+    let _ = (Debug, Display);
+}
+"#####,
+        r#####"
+mod fmt {
+    pub struct Debug;
+    pub struct Display;
+    pub struct Unused;
+}
+mod io {
+    pub struct Read;
+}
+pub use self::io::Read;
+pub use self::fmt::{Debug, Display};
+
+fn main() {
+    let _ = (Debug, Display);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_fn_body() {
+    check(
+        "wrap_fn_body",
+        r#####"
+async fn <|>fetch() -> u32 {
+    request().await
+}
+"#####,
+        r#####"
+fn fetch() -> u32 {
+    futures::executor::block_on(async move {
+        request().await
+    })
+}
+"#####,
+    )
+}
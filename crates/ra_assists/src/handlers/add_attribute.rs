@@ -0,0 +1,259 @@
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner},
+    SyntaxKind::{
+        ATTR, BLOCK_EXPR, COMMENT, ENUM_VARIANT_LIST, EXTERN_ITEM_LIST, ITEM_LIST,
+        RECORD_FIELD_DEF_LIST, SOURCE_FILE, STRING, WHITESPACE,
+    },
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+const GROUP_LABEL: &str = "Add attribute";
+
+// Assist: add_must_use
+//
+// Adds a `#[must_use]` attribute with a note to a function returning a value,
+// or to a type or trait.
+//
+// ```
+// fn area(<|>width: u32, height: u32) -> u32 {
+//     width * height
+// }
+// ```
+// ->
+// ```
+// #[must_use = ""]
+// fn area(width: u32, height: u32) -> u32 {
+//     width * height
+// }
+// ```
+pub(crate) fn add_must_use(ctx: AssistCtx) -> Option<Assist> {
+    let item = item_at_header(&ctx)?;
+    let applicable = match &item {
+        ast::ModuleItem::FnDef(it) => match it.ret_type().and_then(|it| it.type_ref()) {
+            Some(ast::TypeRef::TupleType(ty)) => ty.fields().next().is_some(),
+            Some(_) => true,
+            None => false,
+        },
+        ast::ModuleItem::StructDef(_)
+        | ast::ModuleItem::EnumDef(_)
+        | ast::ModuleItem::UnionDef(_)
+        | ast::ModuleItem::TraitDef(_) => true,
+        _ => false,
+    };
+    if !applicable || has_attr(&item, "must_use") {
+        return None;
+    }
+    add_attr(ctx, &item, AssistId("add_must_use"), "must_use = \"\"")
+}
+
+// Assist: add_inline
+//
+// Adds an `#[inline]` attribute to a function with a body.
+//
+// ```
+// fn double(<|>x: u32) -> u32 {
+//     x * 2
+// }
+// ```
+// ->
+// ```
+// #[inline]
+// fn double(x: u32) -> u32 {
+//     x * 2
+// }
+// ```
+pub(crate) fn add_inline(ctx: AssistCtx) -> Option<Assist> {
+    let item = item_at_header(&ctx)?;
+    let fn_def = match &item {
+        ast::ModuleItem::FnDef(it) => it,
+        _ => return None,
+    };
+    // Inlining a function declaration in a trait or an extern block is an
+    // error.
+    if fn_def.body().is_none() || is_main(fn_def) || has_attr(&item, "inline") {
+        return None;
+    }
+    add_attr(ctx, &item, AssistId("add_inline"), "inline")
+}
+
+// Assist: add_track_caller
+//
+// Adds a `#[track_caller]` attribute to a function, so that panics inside it
+// report the location of the caller.
+//
+// ```
+// fn expect_positive(<|>x: i32) -> i32 {
+//     assert!(x > 0);
+//     x
+// }
+// ```
+// ->
+// ```
+// #[track_caller]
+// fn expect_positive(x: i32) -> i32 {
+//     assert!(x > 0);
+//     x
+// }
+// ```
+pub(crate) fn add_track_caller(ctx: AssistCtx) -> Option<Assist> {
+    let item = item_at_header(&ctx)?;
+    let fn_def = match &item {
+        ast::ModuleItem::FnDef(it) => it,
+        _ => return None,
+    };
+    let parent_kind = fn_def.syntax().parent()?.kind();
+    if parent_kind == EXTERN_ITEM_LIST
+        || is_main(fn_def)
+        || !has_rust_abi(fn_def)
+        || has_attr(&item, "track_caller")
+    {
+        return None;
+    }
+    add_attr(ctx, &item, AssistId("add_track_caller"), "track_caller")
+}
+
+/// Returns the item at the cursor, unless the cursor is inside the body of the
+/// item: we don't want to offer attributes everywhere in a function.
+fn item_at_header(ctx: &AssistCtx) -> Option<ast::ModuleItem> {
+    let item = ctx.find_node_at_offset::<ast::ModuleItem>()?;
+    let body = item.syntax().children().find(|it| {
+        matches!(it.kind(), BLOCK_EXPR | RECORD_FIELD_DEF_LIST | ENUM_VARIANT_LIST | ITEM_LIST)
+    });
+    match body {
+        Some(body) if ctx.frange.range.start() > body.text_range().start() => None,
+        _ => Some(item),
+    }
+}
+
+fn is_main(fn_def: &ast::FnDef) -> bool {
+    fn_def.syntax().parent().map_or(false, |it| it.kind() == SOURCE_FILE)
+        && fn_def.name().map_or(false, |it| it.text() == "main")
+}
+
+fn has_attr(item: &ast::ModuleItem, name: &str) -> bool {
+    item.attrs().any(|attr| attr.simple_name().map_or(false, |it| it == name))
+}
+
+/// `#[track_caller]` requires the Rust ABI, while `extern fn` defaults to the
+/// C one.
+fn has_rust_abi(fn_def: &ast::FnDef) -> bool {
+    let abi = match fn_def.abi() {
+        Some(it) => it,
+        None => return true,
+    };
+    abi.syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == STRING)
+        .map_or(false, |it| it.text() == "\"Rust\"")
+}
+
+/// Adds `#[attr]` after the existing attributes of the item. The cursor is put
+/// into the string of the attribute if there's one, so that the note can be
+/// typed right away.
+fn add_attr(ctx: AssistCtx, item: &ast::ModuleItem, id: AssistId, attr: &str) -> Option<Assist> {
+    let offset = item
+        .syntax()
+        .children_with_tokens()
+        .find(|it| !matches!(it.kind(), ATTR | COMMENT | WHITESPACE))?
+        .text_range()
+        .start();
+    let indent = leading_indent(item.syntax()).unwrap_or_default();
+    let label = format!("Add `#[{}]`", attr.split(' ').next().unwrap_or(attr));
+
+    let attr = format!("#[{}]", attr);
+    let cursor = match attr.find('"') {
+        Some(quote) => offset + TextUnit::of_str(&attr[..=quote]),
+        None => offset + TextUnit::of_str(&attr),
+    };
+
+    let mut group = ctx.add_assist_group(GROUP_LABEL);
+    group.add_assist(id, label, |edit| {
+        edit.target(item.syntax().text_range());
+        edit.insert(offset, format!("{}\n{}", attr, indent));
+        edit.set_cursor(cursor);
+    });
+    group.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    #[test]
+    fn add_must_use_to_fn() {
+        check_assist(
+            add_must_use,
+            "/// Doc\n#[cfg(test)]\npub fn <|>foo() -> u32 { 92 }",
+            "/// Doc\n#[cfg(test)]\n#[must_use = \"<|>\"]\npub fn foo() -> u32 { 92 }",
+        );
+    }
+
+    #[test]
+    fn add_must_use_to_types() {
+        check_assist(
+            add_must_use,
+            "struct <|>Foo { x: u32 }",
+            "#[must_use = \"<|>\"]\nstruct Foo { x: u32 }",
+        );
+        check_assist(
+            add_must_use,
+            "mod m {\n    trait <|>Foo {}\n}",
+            "mod m {\n    #[must_use = \"<|>\"]\n    trait Foo {}\n}",
+        );
+    }
+
+    #[test]
+    fn add_must_use_not_applicable() {
+        check_assist_not_applicable(add_must_use, "fn <|>foo() {}");
+        check_assist_not_applicable(add_must_use, "fn <|>foo() -> () {}");
+        check_assist_not_applicable(add_must_use, "#[must_use]\nfn <|>foo() -> u32 { 92 }");
+        check_assist_not_applicable(add_must_use, "struct Foo { <|>x: u32 }");
+        check_assist_not_applicable(add_must_use, "const <|>FOO: u32 = 92;");
+    }
+
+    #[test]
+    fn add_inline_to_method() {
+        check_assist(
+            add_inline,
+            "impl S {\n    fn <|>foo(&self) {}\n}",
+            "impl S {\n    #[inline]<|>\n    fn foo(&self) {}\n}",
+        );
+    }
+
+    #[test]
+    fn add_inline_not_applicable() {
+        check_assist_not_applicable(add_inline, "trait T {\n    fn <|>foo(&self);\n}");
+        check_assist_not_applicable(add_inline, "#[inline(always)]\nfn <|>foo() {}");
+        check_assist_not_applicable(add_inline, "fn foo() { <|>92; }");
+        check_assist_not_applicable(add_inline, "struct <|>S;");
+        check_assist_not_applicable(add_inline, "fn <|>main() {}");
+    }
+
+    #[test]
+    fn add_track_caller_to_fn() {
+        check_assist(
+            add_track_caller,
+            "extern \"Rust\" fn <|>foo() {}",
+            "#[track_caller]<|>\nextern \"Rust\" fn foo() {}",
+        );
+        check_assist(
+            add_track_caller,
+            "trait T {\n    fn <|>foo(&self);\n}",
+            "trait T {\n    #[track_caller]<|>\n    fn foo(&self);\n}",
+        );
+    }
+
+    #[test]
+    fn add_track_caller_not_applicable() {
+        check_assist_not_applicable(add_track_caller, "fn <|>main() {}");
+        check_assist_not_applicable(add_track_caller, "extern \"C\" fn <|>foo() {}");
+        check_assist_not_applicable(add_track_caller, "extern fn <|>foo() {}");
+        check_assist_not_applicable(add_track_caller, "extern \"C\" {\n    fn <|>foo();\n}");
+        check_assist_not_applicable(add_track_caller, "#[track_caller]\nfn <|>foo() {}");
+    }
+}
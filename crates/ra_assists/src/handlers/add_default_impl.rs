@@ -0,0 +1,181 @@
+use hir::{Adt, Type};
+use ra_ide_db::RootDatabase;
+use ra_syntax::ast::{self, AstNode, NameOwner, StructKind, TypeParamsOwner};
+use stdx::{format_to, SepBy};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_default_impl
+//
+// Adds a `Default` impl for a struct that doesn't have one yet. Fields whose
+// types implement `Default` are initialized with `Default::default()`.
+//
+// ```
+// struct Config {
+//     verbose: bool,<|>
+//     name: String,
+// }
+// ```
+// ->
+// ```
+// struct Config {
+//     verbose: bool,
+//     name: String,
+// }
+//
+// impl Default for Config {
+//     fn default() -> Self {
+//         Self { verbose: Default::default(), name: todo!() }
+//     }
+// }
+// ```
+pub(crate) fn add_default_impl(ctx: AssistCtx) -> Option<Assist> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    match strukt.kind() {
+        StructKind::Record(_) => {}
+        _ => return None,
+    };
+    let name = strukt.name()?;
+
+    let def = ctx.sema.to_def(&strukt)?;
+    if Adt::from(def).has_default_impl(ctx.db) {
+        return None;
+    }
+
+    let db = ctx.db;
+    ctx.add_assist(AssistId("add_default_impl"), "Implement `Default`", |edit| {
+        edit.target(strukt.syntax().text_range());
+
+        let fields = def
+            .fields(db)
+            .into_iter()
+            .map(|field| {
+                let value = if is_default(db, &field.signature_ty(db)) {
+                    "Default::default()"
+                } else {
+                    "todo!()"
+                };
+                format!("{}: {}", field.name(db), value)
+            })
+            .sep_by(", ");
+
+        let mut buf = String::from("\n\nimpl");
+        let type_params = strukt.type_param_list();
+        if let Some(type_params) = &type_params {
+            format_to!(buf, "{}", type_params.syntax());
+        }
+        format_to!(buf, " Default for {}", name.text());
+        if let Some(type_params) = type_params {
+            let lifetime_params = type_params
+                .lifetime_params()
+                .filter_map(|it| it.lifetime_token())
+                .map(|it| it.text().clone());
+            let type_params =
+                type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+            format_to!(buf, "<{}>", lifetime_params.chain(type_params).sep_by(", "))
+        }
+        format_to!(
+            buf,
+            " {{\n    fn default() -> Self {{\n        Self {{ {} }}\n    }}\n}}",
+            fields
+        );
+        edit.insert(strukt.syntax().text_range().end(), buf);
+    })
+}
+
+/// Primitives are known to implement `Default`, ADTs are checked for a derived
+/// or explicit impl without invoking the trait solver.
+fn is_default(db: &RootDatabase, ty: &Type) -> bool {
+    if ty.is_bool() || ty.is_integral() || ty.is_float() {
+        return true;
+    }
+    ty.as_adt().map_or(false, |it| it.has_default_impl(db))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn add_default_impl_uses_default_fields() {
+        check_assist(
+            add_default_impl,
+            "
+#[derive(Default)]
+struct Bar;
+struct Baz;
+
+struct Foo {
+    a: u32,<|>
+    b: Bar,
+    c: Baz,
+}
+",
+            "
+#[derive(Default)]
+struct Bar;
+struct Baz;
+
+struct Foo {
+    a: u32,<|>
+    b: Bar,
+    c: Baz,
+}
+
+impl Default for Foo {
+    fn default() -> Self {
+        Self { a: Default::default(), b: Default::default(), c: todo!() }
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_default_impl_generic() {
+        check_assist(
+            add_default_impl,
+            "
+struct Foo<'a, T: Clone> {
+    a: &'a T,<|>
+}
+",
+            "
+struct Foo<'a, T: Clone> {
+    a: &'a T,<|>
+}
+
+impl<'a, T: Clone> Default for Foo<'a, T> {
+    fn default() -> Self {
+        Self { a: todo!() }
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_default_impl_target() {
+        check_assist_target(add_default_impl, "struct Foo { <|>a: u32 }", "struct Foo { a: u32 }")
+    }
+
+    #[test]
+    fn add_default_impl_not_applicable() {
+        check_assist_not_applicable(add_default_impl, "struct Foo(u32<|>);");
+        check_assist_not_applicable(
+            add_default_impl,
+            "#[derive(Default)] struct Foo { <|>a: u32 }",
+        );
+        check_assist_not_applicable(
+            add_default_impl,
+            "
+struct Foo { <|>a: u32 }
+impl Default for Foo {
+    fn default() -> Self { Foo { a: 0 } }
+}
+",
+        );
+    }
+}
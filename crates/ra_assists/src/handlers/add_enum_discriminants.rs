@@ -0,0 +1,589 @@
+use ra_syntax::{
+    ast::{
+        self, edit::IndentLevel, AstNode, AttrsOwner, NameOwner, TypeParamsOwner, VisibilityOwner,
+    },
+    SmolStr,
+    SyntaxKind::{ATTR, COMMENT, IDENT, WHITESPACE},
+    TextUnit,
+};
+use stdx::format_to;
+
+use crate::{
+    utils::{to_lower_snake_case, to_upper_snake_case},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: add_enum_discriminants
+//
+// Adds a `#[repr]` attribute and explicit discriminants to an enum without
+// fields.
+//
+// ```
+// enum Color { <|>Red, Green, Blue = 4 }
+// ```
+// ->
+// ```
+// #[repr(u8)]
+// enum Color { Red = 0, Green = 1, Blue = 4 }
+// ```
+pub(crate) fn add_enum_discriminants(ctx: AssistCtx) -> Option<Assist> {
+    let enum_def = FieldlessEnum::at_cursor(&ctx)?;
+    let missing_repr = enum_def.repr.is_none();
+    if !missing_repr && enum_def.variants.iter().all(|it| it.has_discriminant) {
+        return None;
+    }
+    let label = format!("Add `#[repr({})]` and explicit discriminants", enum_def.repr_ty());
+
+    ctx.add_assist(AssistId("add_enum_discriminants"), label, |edit| {
+        edit.target(enum_def.node.syntax().text_range());
+        if missing_repr {
+            if let Some(item_start) = enum_def.item_start() {
+                let indent =
+                    " ".repeat(IndentLevel::from_node(enum_def.node.syntax()).0 as usize * 4);
+                edit.insert(item_start, format!("#[repr({})]\n{}", enum_def.repr_ty(), indent));
+            }
+        }
+        for variant in enum_def.variants.iter().filter(|it| !it.has_discriminant) {
+            if let Some(name) = variant.node.name() {
+                edit.insert(name.syntax().text_range().end(), format!(" = {}", variant.value));
+            }
+        }
+    })
+}
+
+// Assist: add_enum_value_consts
+//
+// Adds associated constants holding the discriminant values of an enum without
+// fields.
+//
+// ```
+// enum Color { <|>Red, DarkGreen }
+// ```
+// ->
+// ```
+// enum Color { Red, DarkGreen }
+//
+// impl Color {
+//     pub const RED: u8 = Color::Red as u8;
+//     pub const DARK_GREEN: u8 = Color::DarkGreen as u8;
+// }
+// ```
+pub(crate) fn add_enum_value_consts(ctx: AssistCtx) -> Option<Assist> {
+    let enum_def = FieldlessEnum::at_cursor(&ctx)?;
+
+    ctx.add_assist(AssistId("add_enum_value_consts"), "Add constants for enum values", |edit| {
+        edit.target(enum_def.node.syntax().text_range());
+
+        let repr_ty = enum_def.repr_ty();
+        let mut buf = String::new();
+        format_to!(buf, "\n\nimpl {} {{\n", enum_def.name);
+        for variant in enum_def.variants.iter() {
+            format_to!(
+                buf,
+                "    pub const {}: {} = {}::{} as {};\n",
+                to_upper_snake_case(&variant.name),
+                repr_ty,
+                enum_def.name,
+                variant.name,
+                repr_ty
+            );
+        }
+        buf.push_str("}");
+        edit.insert(enum_def.node.syntax().text_range().end(), buf);
+    })
+}
+
+// Assist: add_try_from_impl_for_enum
+//
+// Adds a `TryFrom` impl, which maps discriminant values of an enum without
+// fields back to its variants.
+//
+// ```
+// enum Color { <|>Red, Green }
+// ```
+// ->
+// ```
+// enum Color { Red, Green }
+//
+// impl std::convert::TryFrom<u8> for Color {
+//     type Error = u8;
+//
+//     fn try_from(value: u8) -> Result<Self, Self::Error> {
+//         match value {
+//             0 => Ok(Color::Red),
+//             1 => Ok(Color::Green),
+//             _ => Err(value),
+//         }
+//     }
+// }
+// ```
+pub(crate) fn add_try_from_impl_for_enum(ctx: AssistCtx) -> Option<Assist> {
+    let enum_def = FieldlessEnum::at_cursor(&ctx)?;
+    let label = format!("Add `TryFrom<{}>` impl for this enum", enum_def.repr_ty());
+
+    ctx.add_assist(AssistId("add_try_from_impl_for_enum"), label, |edit| {
+        edit.target(enum_def.node.syntax().text_range());
+
+        let repr_ty = enum_def.repr_ty();
+        let mut buf = String::new();
+        format_to!(buf, "\n\nimpl std::convert::TryFrom<{}> for {} {{\n", repr_ty, enum_def.name);
+        format_to!(buf, "    type Error = {};\n\n", repr_ty);
+        format_to!(buf, "    fn try_from(value: {}) -> Result<Self, Self::Error> {{\n", repr_ty);
+        buf.push_str("        match value {\n");
+        for variant in enum_def.variants.iter() {
+            format_to!(
+                buf,
+                "            {} => Ok({}::{}),\n",
+                variant.value,
+                enum_def.name,
+                variant.name
+            );
+        }
+        buf.push_str("            _ => Err(value),\n        }\n    }\n}");
+        edit.insert(enum_def.node.syntax().text_range().end(), buf);
+    })
+}
+
+// Assist: add_display_and_from_str_for_enum
+//
+// Adds `Display` and `FromStr` impls, which map the variants of an enum without
+// fields to strings and back, together with the error type for parsing.
+//
+// ```
+// enum Color { <|>Red, DarkGreen }
+// ```
+// ->
+// ```
+// enum Color { Red, DarkGreen }
+//
+// impl std::fmt::Display for Color {
+//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//         let s = match self {
+//             Color::Red => "red",
+//             Color::DarkGreen => "dark_green",
+//         };
+//         f.write_str(s)
+//     }
+// }
+//
+// impl std::str::FromStr for Color {
+//     type Err = ParseColorError;
+//
+//     fn from_str(s: &str) -> Result<Self, Self::Err> {
+//         match s {
+//             "red" => Ok(Color::Red),
+//             "dark_green" => Ok(Color::DarkGreen),
+//             _ => Err(ParseColorError),
+//         }
+//     }
+// }
+//
+// #[derive(Debug, Clone, PartialEq, Eq)]
+// struct ParseColorError;
+//
+// impl std::fmt::Display for ParseColorError {
+//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//         f.write_str("invalid `Color`")
+//     }
+// }
+//
+// impl std::error::Error for ParseColorError {}
+// ```
+pub(crate) fn add_display_and_from_str_for_enum(ctx: AssistCtx) -> Option<Assist> {
+    let enum_def = FieldlessEnum::at_cursor(&ctx)?;
+    let vis = enum_def.node.visibility().map(|it| format!("{} ", it.syntax())).unwrap_or_default();
+    let target = enum_def.node.syntax().text_range();
+
+    let mut group = ctx.add_assist_group("Add `Display` and `FromStr` impls");
+    for &case in [StringCase::Snake, StringCase::Kebab].iter() {
+        group.add_assist(
+            AssistId("add_display_and_from_str_for_enum"),
+            format!("Add `Display` and `FromStr` impls with {} strings", case.name()),
+            |edit| {
+                edit.target(target);
+
+                let name = &enum_def.name;
+                let error = format!("Parse{}Error", name);
+                let strings: Vec<(&SmolStr, String)> =
+                    enum_def.variants.iter().map(|it| (&it.name, case.apply(&it.name))).collect();
+                let mut buf = String::new();
+
+                format_to!(buf, "\n\nimpl std::fmt::Display for {} {{\n", name);
+                buf.push_str(FMT_SIGNATURE);
+                buf.push_str("        let s = match self {\n");
+                for (variant, string) in strings.iter() {
+                    format_to!(buf, "            {}::{} => \"{}\",\n", name, variant, string);
+                }
+                buf.push_str("        };\n        f.write_str(s)\n    }\n}\n\n");
+
+                format_to!(buf, "impl std::str::FromStr for {} {{\n", name);
+                format_to!(buf, "    type Err = {};\n\n", error);
+                buf.push_str("    fn from_str(s: &str) -> Result<Self, Self::Err> {\n");
+                buf.push_str("        match s {\n");
+                for (variant, string) in strings.iter() {
+                    format_to!(buf, "            \"{}\" => Ok({}::{}),\n", string, name, variant);
+                }
+                format_to!(buf, "            _ => Err({}),\n        }}\n    }}\n}}\n\n", error);
+
+                buf.push_str("#[derive(Debug, Clone, PartialEq, Eq)]\n");
+                format_to!(buf, "{}struct {};\n\n", vis, error);
+                format_to!(buf, "impl std::fmt::Display for {} {{\n", error);
+                buf.push_str(FMT_SIGNATURE);
+                format_to!(buf, "        f.write_str(\"invalid `{}`\")\n    }}\n}}\n\n", name);
+                format_to!(buf, "impl std::error::Error for {} {{}}", error);
+
+                edit.insert(target.end(), buf);
+            },
+        );
+    }
+    group.finish()
+}
+
+const FMT_SIGNATURE: &str =
+    "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n";
+
+/// How the names of variants are spelled as strings.
+#[derive(Clone, Copy)]
+enum StringCase {
+    Snake,
+    Kebab,
+}
+
+impl StringCase {
+    fn name(self) -> &'static str {
+        match self {
+            StringCase::Snake => "snake_case",
+            StringCase::Kebab => "kebab-case",
+        }
+    }
+
+    fn apply(self, variant: &str) -> String {
+        let snake_case = to_lower_snake_case(variant);
+        match self {
+            StringCase::Snake => snake_case,
+            StringCase::Kebab => snake_case.replace('_', "-"),
+        }
+    }
+}
+
+struct FieldlessEnum {
+    node: ast::EnumDef,
+    name: SmolStr,
+    /// Integer type from the `#[repr]` attribute, if any.
+    repr: Option<SmolStr>,
+    variants: Vec<FieldlessVariant>,
+}
+
+struct FieldlessVariant {
+    node: ast::EnumVariant,
+    name: SmolStr,
+    value: i128,
+    has_discriminant: bool,
+}
+
+impl FieldlessEnum {
+    /// Finds the enum under cursor, if it is non-generic, has at least one
+    /// variant, and all of its variants are unit variants whose discriminants
+    /// (if any) are integer literals.
+    fn at_cursor(ctx: &AssistCtx) -> Option<FieldlessEnum> {
+        let node = ctx.find_node_at_offset::<ast::EnumDef>()?;
+        let name = node.name()?.text().clone();
+        if node.type_param_list().is_some() {
+            return None;
+        }
+
+        let mut variants = Vec::new();
+        let mut next_value = 0;
+        for variant in node.variant_list()?.variants() {
+            if variant.field_def_list().is_some() {
+                return None;
+            }
+            let name = variant.name()?.text().clone();
+            let (value, has_discriminant) = match variant.expr() {
+                Some(expr) => (int_value(&expr)?, true),
+                None => (next_value, false),
+            };
+            next_value = value.saturating_add(1);
+            variants.push(FieldlessVariant { node: variant, name, value, has_discriminant });
+        }
+        if variants.is_empty() {
+            return None;
+        }
+
+        let repr = node.attrs().find_map(|attr| {
+            let (name, tt) = attr.as_simple_call()?;
+            if name != "repr" {
+                return None;
+            }
+            tt.syntax()
+                .children_with_tokens()
+                .filter_map(|it| it.into_token())
+                .filter(|it| it.kind() == IDENT)
+                .map(|it| it.text().clone())
+                .find(|it| INT_TYPES.contains(&it.as_str()))
+        });
+
+        Some(FieldlessEnum { node, name, repr, variants })
+    }
+
+    /// The integer type of the discriminants: either the one from the `#[repr]`
+    /// attribute or the smallest one which fits all values.
+    fn repr_ty(&self) -> SmolStr {
+        if let Some(repr) = &self.repr {
+            return repr.clone();
+        }
+        let min = self.variants.iter().map(|it| it.value).min().unwrap_or(0);
+        let max = self.variants.iter().map(|it| it.value).max().unwrap_or(0);
+        let candidates: [(&str, i128, i128); 8] = [
+            ("u8", 0, u8::max_value().into()),
+            ("u16", 0, u16::max_value().into()),
+            ("u32", 0, u32::max_value().into()),
+            ("u64", 0, u64::max_value().into()),
+            ("i8", i8::min_value().into(), i8::max_value().into()),
+            ("i16", i16::min_value().into(), i16::max_value().into()),
+            ("i32", i32::min_value().into(), i32::max_value().into()),
+            ("i64", i64::min_value().into(), i64::max_value().into()),
+        ];
+        let ty = candidates
+            .iter()
+            .find(|(_, lo, hi)| *lo <= min && max <= *hi)
+            .map_or("i128", |(ty, _, _)| *ty);
+        SmolStr::new(ty)
+    }
+
+    /// Offset of the first token after the attributes and doc comments of the
+    /// enum.
+    fn item_start(&self) -> Option<TextUnit> {
+        self.node
+            .syntax()
+            .children_with_tokens()
+            .find(|it| !matches!(it.kind(), ATTR | COMMENT | WHITESPACE))
+            .map(|it| it.text_range().start())
+    }
+}
+
+const INT_TYPES: [&str; 12] =
+    ["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"];
+
+/// Evaluates an integer literal, optionally negated, like `-0x1F_u8`.
+fn int_value(expr: &ast::Expr) -> Option<i128> {
+    match expr {
+        ast::Expr::PrefixExpr(prefix) if prefix.op_kind() == Some(ast::PrefixOp::Neg) => {
+            Some(-int_value(&prefix.expr()?)?)
+        }
+        ast::Expr::ParenExpr(paren) => int_value(&paren.expr()?),
+        ast::Expr::Literal(lit) => {
+            let suffix = match lit.kind() {
+                ast::LiteralKind::IntNumber { suffix } => suffix,
+                _ => return None,
+            };
+            let token = lit.token();
+            let mut text = token.text().as_str();
+            if let Some(suffix) = &suffix {
+                text = &text[..text.len() - suffix.len()];
+            }
+            let text = text.replace('_', "");
+            let (radix, digits) = match text.get(..2) {
+                Some("0x") => (16, &text[2..]),
+                Some("0o") => (8, &text[2..]),
+                Some("0b") => (2, &text[2..]),
+                _ => (10, &text[..]),
+            };
+            i128::from_str_radix(digits, radix).ok()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn add_enum_discriminants_all_variants() {
+        check_assist(
+            add_enum_discriminants,
+            "
+/// Doc comment
+#[derive(Debug)]
+pub enum Color {
+    <|>Red,
+    Green,
+}
+",
+            "
+/// Doc comment
+#[derive(Debug)]
+#[repr(u8)]
+pub enum Color {
+    <|>Red = 0,
+    Green = 1,
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_enum_discriminants_keeps_existing() {
+        check_assist(
+            add_enum_discriminants,
+            "enum Foo { <|>A = -2, B, C = 0x10, D }",
+            "#[repr(i8)]\nenum Foo { <|>A = -2, B = -1, C = 0x10, D = 17 }",
+        )
+    }
+
+    #[test]
+    fn add_enum_discriminants_existing_repr() {
+        check_assist(
+            add_enum_discriminants,
+            "
+mod m {
+    #[repr(u32)]
+    enum Foo { <|>A, B }
+}
+",
+            "
+mod m {
+    #[repr(u32)]
+    enum Foo { <|>A = 0, B = 1 }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_enum_discriminants_indents_repr() {
+        check_assist(
+            add_enum_discriminants,
+            "
+mod m {
+    enum Foo { <|>A = 300 }
+}
+",
+            "
+mod m {
+    #[repr(u16)]
+    enum Foo { <|>A = 300 }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_enum_discriminants_not_applicable() {
+        check_assist_not_applicable(add_enum_discriminants, "#[repr(u8)] enum Foo { <|>A = 0 }");
+        check_assist_not_applicable(add_enum_discriminants, "enum Foo { <|>A(u32), B }");
+        check_assist_not_applicable(add_enum_discriminants, "enum Foo { <|>A { x: u32 } }");
+        check_assist_not_applicable(add_enum_discriminants, "enum Foo { <|>A = X, B }");
+        check_assist_not_applicable(add_enum_discriminants, "enum Foo<T> { <|>A }");
+        check_assist_not_applicable(add_enum_discriminants, "enum Foo { <|> }");
+    }
+
+    #[test]
+    fn add_enum_value_consts_uses_repr() {
+        check_assist(
+            add_enum_value_consts,
+            "
+#[repr(i32)]
+enum Foo { <|>First, DarkGreen }
+",
+            "
+#[repr(i32)]
+enum Foo { <|>First, DarkGreen }
+
+impl Foo {
+    pub const FIRST: i32 = Foo::First as i32;
+    pub const DARK_GREEN: i32 = Foo::DarkGreen as i32;
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_try_from_impl_for_enum_explicit_values() {
+        check_assist(
+            add_try_from_impl_for_enum,
+            "enum Foo { <|>A = 1, B, C = 1_000 }",
+            "enum Foo { <|>A = 1, B, C = 1_000 }
+
+impl std::convert::TryFrom<u16> for Foo {
+    type Error = u16;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Foo::A),
+            2 => Ok(Foo::B),
+            1000 => Ok(Foo::C),
+            _ => Err(value),
+        }
+    }
+}",
+        )
+    }
+
+    #[test]
+    fn add_try_from_impl_for_enum_not_applicable() {
+        check_assist_not_applicable(add_try_from_impl_for_enum, "enum Foo { <|>A(u32) }");
+    }
+
+    #[test]
+    fn add_display_and_from_str_for_enum_pub() {
+        check_assist(
+            add_display_and_from_str_for_enum,
+            "pub enum Mode { <|>Read, ReadWrite }",
+            r#"pub enum Mode { <|>Read, ReadWrite }
+
+impl std::fmt::Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Mode::Read => "read",
+            Mode::ReadWrite => "read_write",
+        };
+        f.write_str(s)
+    }
+}
+
+impl std::str::FromStr for Mode {
+    type Err = ParseModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Mode::Read),
+            "read_write" => Ok(Mode::ReadWrite),
+            _ => Err(ParseModeError),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseModeError;
+
+impl std::fmt::Display for ParseModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid `Mode`")
+    }
+}
+
+impl std::error::Error for ParseModeError {}"#,
+        )
+    }
+
+    #[test]
+    fn add_display_and_from_str_for_enum_string_cases() {
+        assert_eq!(StringCase::Snake.apply("DarkGreen"), "dark_green");
+        assert_eq!(StringCase::Kebab.apply("DarkGreen"), "dark-green");
+    }
+
+    #[test]
+    fn add_display_and_from_str_for_enum_not_applicable() {
+        check_assist_not_applicable(add_display_and_from_str_for_enum, "enum Foo { <|>A(u32) }");
+        check_assist_not_applicable(add_display_and_from_str_for_enum, "enum Foo<T> { <|>A }");
+    }
+
+    #[test]
+    fn add_enum_value_consts_target() {
+        check_assist_target(add_enum_value_consts, "enum Foo { <|>A }", "enum Foo { A }");
+    }
+}
@@ -0,0 +1,352 @@
+use ra_syntax::{
+    ast::{
+        self, AstNode, NameOwner, StructKind, TypeAscriptionOwner, TypeBoundsOwner,
+        TypeParamsOwner, VisibilityOwner,
+    },
+    SmolStr,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{utils::to_lower_snake_case, Assist, AssistCtx, AssistId};
+
+// Assist: add_enum_variant_methods
+//
+// Adds `is_*`, `as_*` and `try_into_*` methods for the selected variants of an enum.
+//
+// ```
+// enum Value { <|>Int(i64), Unit }
+// ```
+// ->
+// ```
+// enum Value { Int(i64), Unit }
+//
+// impl Value {
+//     fn is_int(&self) -> bool {
+//         matches!(self, Self::Int(..))
+//     }
+//
+//     fn as_int(&self) -> Option<&i64> {
+//         if let Self::Int(v) = self {
+//             Some(v)
+//         } else {
+//             None
+//         }
+//     }
+//
+//     fn try_into_int(self) -> Result<i64, Self> {
+//         if let Self::Int(v) = self {
+//             Ok(v)
+//         } else {
+//             Err(self)
+//         }
+//     }
+// }
+// ```
+pub(crate) fn add_enum_variant_methods(ctx: AssistCtx) -> Option<Assist> {
+    let enum_def = ctx.find_node_at_offset::<ast::EnumDef>()?;
+    let enum_name = enum_def.name()?;
+    let selection = ctx.frange.range;
+    let variants: Vec<ast::EnumVariant> = enum_def
+        .variant_list()?
+        .variants()
+        .filter(|it| it.syntax().text_range().intersection(&selection).is_some())
+        .collect();
+    if variants.is_empty() {
+        return None;
+    }
+    let label = if variants.len() == 1 {
+        "Add methods for this variant"
+    } else {
+        "Add methods for the selected variants"
+    };
+
+    ctx.add_assist(AssistId("add_enum_variant_methods"), label, |edit| {
+        edit.target(enum_def.syntax().text_range());
+
+        let vis = enum_def.visibility().map(|v| format!("{} ", v));
+        let vis = vis.as_deref().unwrap_or("");
+
+        let mut methods = Vec::new();
+        for variant in variants.iter() {
+            if let Some(name) = variant.name() {
+                add_variant_methods(&mut methods, vis, name.text(), variant.kind());
+            }
+        }
+
+        let mut buf = String::new();
+        buf.push_str("\n\n");
+        buf.push_str(&impl_header(&enum_def, enum_name.text()));
+        format_to!(buf, " {{\n{}}}", methods.into_iter().sep_by("\n"));
+        edit.insert(enum_def.syntax().text_range().end(), buf);
+    })
+}
+
+fn add_variant_methods(acc: &mut Vec<String>, vis: &str, variant: &str, kind: StructKind) {
+    let suffix = to_lower_snake_case(variant);
+
+    // The pattern binding all fields, the one ignoring them, and the fields as
+    // `(binding, type)` pairs.
+    let (pat, wildcard_pat, fields) = match kind {
+        StructKind::Unit => {
+            (format!("Self::{}", variant), format!("Self::{}", variant), Vec::new())
+        }
+        StructKind::Tuple(field_list) => {
+            let types: Vec<String> =
+                field_list.fields().map(|it| type_text(it.type_ref())).collect();
+            let names: Vec<SmolStr> = if types.len() == 1 {
+                vec![SmolStr::new("v")]
+            } else {
+                (0..types.len()).map(|idx| SmolStr::new(format!("v{}", idx))).collect()
+            };
+            let pat = format!("Self::{}({})", variant, names.iter().sep_by(", "));
+            (pat, format!("Self::{}(..)", variant), names.into_iter().zip(types).collect())
+        }
+        StructKind::Record(field_list) => {
+            let fields: Vec<(SmolStr, String)> = field_list
+                .fields()
+                .filter_map(|it| Some((it.name()?.text().clone(), type_text(it.ascribed_type()))))
+                .collect();
+            let names = fields.iter().map(|(name, _)| name).sep_by(", ");
+            let pat = format!("Self::{} {{ {} }}", variant, names);
+            (pat, format!("Self::{} {{ .. }}", variant), fields)
+        }
+    };
+
+    acc.push(format!(
+        "    {}fn is_{}(&self) -> bool {{\n        matches!(self, {})\n    }}\n",
+        vis, suffix, wildcard_pat
+    ));
+    if fields.is_empty() {
+        return;
+    }
+
+    let (value, ty, ref_ty) = if fields.len() == 1 {
+        let (name, ty) = &fields[0];
+        (name.to_string(), ty.clone(), format!("&{}", ty))
+    } else {
+        (
+            format!("({})", fields.iter().map(|(name, _)| name).sep_by(", ")),
+            format!("({})", fields.iter().map(|(_, ty)| ty).sep_by(", ")),
+            format!("({})", fields.iter().map(|(_, ty)| format!("&{}", ty)).sep_by(", ")),
+        )
+    };
+    let mut buf = String::new();
+    format_to!(buf, "    {}fn as_{}(&self) -> Option<{}> {{\n", vis, suffix, ref_ty);
+    format_to!(buf, "        if let {} = self {{\n            Some({})\n", pat, value);
+    buf.push_str("        } else {\n            None\n        }\n    }\n");
+    acc.push(buf);
+
+    let mut buf = String::new();
+    format_to!(buf, "    {}fn try_into_{}(self) -> Result<{}, Self> {{\n", vis, suffix, ty);
+    format_to!(buf, "        if let {} = self {{\n            Ok({})\n", pat, value);
+    buf.push_str("        } else {\n            Err(self)\n        }\n    }\n");
+    acc.push(buf);
+}
+
+fn type_text(type_ref: Option<ast::TypeRef>) -> String {
+    type_ref.map_or_else(|| "_".to_string(), |it| it.syntax().text().to_string())
+}
+
+/// Generates `impl<T: Bound> Enum<T> where ...`, with the type and lifetime
+/// parameters of the enum. Defaults of the type parameters are not allowed on
+/// impls, so they are left out.
+fn impl_header(enum_def: &ast::EnumDef, name: &SmolStr) -> String {
+    let mut buf = String::from("impl");
+    let type_params = enum_def.type_param_list();
+    if let Some(type_params) = &type_params {
+        let lifetime_params = type_params.lifetime_params().map(|it| it.syntax().to_string());
+        let type_params = type_params.type_params().filter_map(|it| {
+            let mut param = it.name()?.text().to_string();
+            if let Some(bounds) = it.type_bound_list() {
+                format_to!(param, ": {}", bounds.syntax());
+            }
+            Some(param)
+        });
+        format_to!(buf, "<{}>", lifetime_params.chain(type_params).sep_by(", "));
+    }
+    format_to!(buf, " {}", name);
+    if let Some(type_params) = type_params {
+        let lifetime_params = type_params
+            .lifetime_params()
+            .filter_map(|it| it.lifetime_token())
+            .map(|it| it.text().clone());
+        let type_params =
+            type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+        format_to!(buf, "<{}>", lifetime_params.chain(type_params).sep_by(", "));
+    }
+    if let Some(where_clause) = enum_def.where_clause() {
+        format_to!(buf, " {}", where_clause.syntax());
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn add_methods_for_tuple_variant() {
+        check_assist(
+            add_enum_variant_methods,
+            "
+pub enum Value {
+    <|>Pair(u8, String),
+    Unit,
+}",
+            "
+pub enum Value {
+    <|>Pair(u8, String),
+    Unit,
+}
+
+impl Value {
+    pub fn is_pair(&self) -> bool {
+        matches!(self, Self::Pair(..))
+    }
+
+    pub fn as_pair(&self) -> Option<(&u8, &String)> {
+        if let Self::Pair(v0, v1) = self {
+            Some((v0, v1))
+        } else {
+            None
+        }
+    }
+
+    pub fn try_into_pair(self) -> Result<(u8, String), Self> {
+        if let Self::Pair(v0, v1) = self {
+            Ok((v0, v1))
+        } else {
+            Err(self)
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_methods_for_selected_variants() {
+        check_assist(
+            add_enum_variant_methods,
+            "
+enum Shape<'a, T> {
+    <|>Empty,
+    Named { name: &'a str },<|>
+    Other(T),
+}",
+            "
+enum Shape<'a, T> {
+    Empty,
+    Named { name: &'a str },
+    Other(T),
+}
+
+impl<'a, T> Shape<'a, T> {
+    fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    fn is_named(&self) -> bool {
+        matches!(self, Self::Named { .. })
+    }
+
+    fn as_named(&self) -> Option<&&'a str> {
+        if let Self::Named { name } = self {
+            Some(name)
+        } else {
+            None
+        }
+    }
+
+    fn try_into_named(self) -> Result<&'a str, Self> {
+        if let Self::Named { name } = self {
+            Ok(name)
+        } else {
+            Err(self)
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_methods_for_generic_enum_with_defaults_and_where_clause() {
+        check_assist(
+            add_enum_variant_methods,
+            "
+enum Either<L: Clone, R = i32> where R: Copy {
+    <|>Left(L),
+    Right(R),
+}",
+            "
+enum Either<L: Clone, R = i32> where R: Copy {
+    Left(L),
+    Right(R),
+}
+
+impl<L: Clone, R> Either<L, R> where R: Copy {
+    fn is_left(&self) -> bool {
+        matches!(self, Self::Left(..))
+    }
+
+    fn as_left(&self) -> Option<&L> {
+        if let Self::Left(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    fn try_into_left(self) -> Result<L, Self> {
+        if let Self::Left(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_methods_snake_cases_names() {
+        check_assist(
+            add_enum_variant_methods,
+            "enum E { <|>HttpResponse(u32) }",
+            "enum E { <|>HttpResponse(u32) }
+
+impl E {
+    fn is_http_response(&self) -> bool {
+        matches!(self, Self::HttpResponse(..))
+    }
+
+    fn as_http_response(&self) -> Option<&u32> {
+        if let Self::HttpResponse(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    fn try_into_http_response(self) -> Result<u32, Self> {
+        if let Self::HttpResponse(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_methods_not_applicable_outside_of_variants() {
+        check_assist_not_applicable(add_enum_variant_methods, "enum <|>E { A, B }");
+    }
+
+    #[test]
+    fn add_methods_target() {
+        check_assist_target(add_enum_variant_methods, "enum E { <|>A }", "enum E { A }");
+    }
+}
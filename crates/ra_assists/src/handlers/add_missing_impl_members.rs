@@ -419,6 +419,42 @@ impl foo::Foo<Param> for S {
         );
     }
 
+    #[test]
+    fn test_substitute_default_params() {
+        check_assist(
+            add_missing_impl_members,
+            "
+struct Wrap<T>(T);
+trait Foo<T, U = Wrap<T>, V = Self> { fn foo(&self, t: T, u: U) -> V; }
+struct S;
+impl Foo<u32> for S { <|> }",
+            "
+struct Wrap<T>(T);
+trait Foo<T, U = Wrap<T>, V = Self> { fn foo(&self, t: T, u: U) -> V; }
+struct S;
+impl Foo<u32> for S {
+    <|>fn foo(&self, t: u32, u: Wrap<u32>) -> Self { unimplemented!() }
+}",
+        );
+    }
+
+    #[test]
+    fn test_keep_self() {
+        check_assist(
+            add_missing_impl_members,
+            "
+trait Foo<T> { fn foo(&self, other: &Self, t: T) -> Option<Self> where Self: Sized; }
+struct S<T>(T);
+impl<T> Foo<T> for S<T> { <|> }",
+            "
+trait Foo<T> { fn foo(&self, other: &Self, t: T) -> Option<Self> where Self: Sized; }
+struct S<T>(T);
+impl<T> Foo<T> for S<T> {
+    <|>fn foo(&self, other: &Self, t: T) -> Option<Self> where Self: Sized { unimplemented!() }
+}",
+        );
+    }
+
     #[test]
     fn test_qualify_path_associated_item() {
         check_assist(
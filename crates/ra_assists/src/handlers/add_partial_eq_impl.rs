@@ -0,0 +1,392 @@
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner, StructKind, TypeParamsOwner},
+    SmolStr,
+    SyntaxKind::{COMMA, L_PAREN, R_PAREN, WHITESPACE},
+    TextRange, TextUnit,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{assist_ctx::ActionBuilder, Assist, AssistCtx, AssistId};
+
+// Assist: add_partial_eq_impl
+//
+// Adds a manual `PartialEq` impl, comparing only the selected fields (or all
+// fields, if there's no selection).
+//
+// ```
+// struct Point {
+//     <|>x: u32,
+//     y: u32,<|>
+//     cache: u32,
+// }
+// ```
+// ->
+// ```
+// struct Point {
+//     x: u32,
+//     y: u32,
+//     cache: u32,
+// }
+//
+// impl PartialEq for Point {
+//     fn eq(&self, other: &Self) -> bool {
+//         self.x == other.x && self.y == other.y
+//     }
+// }
+// ```
+pub(crate) fn add_partial_eq_impl(ctx: AssistCtx) -> Option<Assist> {
+    let (strukt, fields) = selected_fields(&ctx)?;
+    let label = format!("Implement `PartialEq` comparing {}", field_list_label(&fields));
+
+    ctx.add_assist(AssistId("add_partial_eq_impl"), label, |edit| {
+        edit.target(strukt.syntax().text_range());
+        remove_derives(edit, &strukt, &["PartialEq"]);
+
+        let mut buf = String::new();
+        format_to!(buf, "\n\n{}", partial_eq_impl_text(&strukt, &fields));
+        edit.insert(strukt.syntax().text_range().end(), buf);
+    })
+}
+
+// Assist: add_partial_eq_and_hash_impl
+//
+// Adds manual `PartialEq` and `Hash` impls, using only the selected fields (or
+// all fields, if there's no selection).
+//
+// ```
+// struct Point {
+//     <|>x: u32,
+//     y: u32,<|>
+//     cache: u32,
+// }
+// ```
+// ->
+// ```
+// struct Point {
+//     x: u32,
+//     y: u32,
+//     cache: u32,
+// }
+//
+// impl PartialEq for Point {
+//     fn eq(&self, other: &Self) -> bool {
+//         self.x == other.x && self.y == other.y
+//     }
+// }
+//
+// impl std::hash::Hash for Point {
+//     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+//         self.x.hash(state);
+//         self.y.hash(state);
+//     }
+// }
+// ```
+pub(crate) fn add_partial_eq_and_hash_impl(ctx: AssistCtx) -> Option<Assist> {
+    let (strukt, fields) = selected_fields(&ctx)?;
+    let label = format!("Implement `PartialEq` and `Hash` comparing {}", field_list_label(&fields));
+
+    ctx.add_assist(AssistId("add_partial_eq_and_hash_impl"), label, |edit| {
+        edit.target(strukt.syntax().text_range());
+        remove_derives(edit, &strukt, &["PartialEq", "Hash"]);
+
+        let mut buf = String::new();
+        format_to!(buf, "\n\n{}", partial_eq_impl_text(&strukt, &fields));
+        format_to!(buf, "\n\n{}", hash_impl_text(&strukt, &fields));
+        edit.insert(strukt.syntax().text_range().end(), buf);
+    })
+}
+
+/// Finds the struct under cursor and the names of the fields which should
+/// participate in the comparison.
+///
+/// If the selection is empty, all fields are used. Otherwise, only the fields
+/// which overlap with the selection are used.
+fn selected_fields(ctx: &AssistCtx) -> Option<(ast::StructDef, Vec<SmolStr>)> {
+    let strukt = ctx.covering_element().ancestors().find_map(ast::StructDef::cast)?;
+    let field_list = match strukt.kind() {
+        StructKind::Record(named) => named,
+        _ => return None,
+    };
+    strukt.name()?;
+
+    let selection = ctx.frange.range;
+    let fields = field_list
+        .fields()
+        .filter(|field| {
+            let range = field.syntax().text_range();
+            selection.is_empty()
+                || (range.start() < selection.end() && selection.start() < range.end())
+        })
+        .filter_map(|field| Some(field.name()?.text().clone()))
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    Some((strukt, fields))
+}
+
+fn field_list_label(fields: &[SmolStr]) -> String {
+    fields.iter().map(|it| format!("`{}`", it)).sep_by(", ").to_string()
+}
+
+fn partial_eq_impl_text(strukt: &ast::StructDef, fields: &[SmolStr]) -> String {
+    let cmp = fields.iter().map(|it| format!("self.{0} == other.{0}", it)).sep_by(" && ");
+    let mut buf = impl_header(strukt, "PartialEq");
+    format_to!(buf, "    fn eq(&self, other: &Self) -> bool {{\n        {}\n    }}\n}}", cmp);
+    buf
+}
+
+fn hash_impl_text(strukt: &ast::StructDef, fields: &[SmolStr]) -> String {
+    let mut buf = impl_header(strukt, "std::hash::Hash");
+    buf.push_str("    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {\n");
+    for field in fields {
+        format_to!(buf, "        self.{}.hash(state);\n", field);
+    }
+    buf.push_str("    }\n}");
+    buf
+}
+
+// Generates `impl<..> Trait for Type<..> {\n`, including type and lifetime
+// parameters
+fn impl_header(strukt: &ast::StructDef, trait_name: &str) -> String {
+    let type_params = strukt.type_param_list();
+    let mut buf = String::from("impl");
+    if let Some(type_params) = &type_params {
+        format_to!(buf, "{}", type_params.syntax());
+    }
+    format_to!(buf, " {} for {}", trait_name, strukt.name().unwrap().text());
+    if let Some(type_params) = type_params {
+        let lifetime_params = type_params
+            .lifetime_params()
+            .filter_map(|it| it.lifetime_token())
+            .map(|it| it.text().clone());
+        let type_params =
+            type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+        format_to!(buf, "<{}>", lifetime_params.chain(type_params).sep_by(", "))
+    }
+    buf.push_str(" {\n");
+    buf
+}
+
+/// Removes `traits` from the `#[derive]` attribute of the struct, if any, as
+/// the manual impl would conflict with the derived one.
+fn remove_derives(edit: &mut ActionBuilder, strukt: &ast::StructDef, traits: &[&str]) {
+    for attr in strukt.attrs() {
+        let tt = match attr.as_simple_call() {
+            Some((name, tt)) if name == "derive" => tt,
+            _ => continue,
+        };
+        // Derives may be paths, like `serde::Serialize`, so the list is split
+        // on commas and every entry is kept as a whole.
+        let mut derives = vec![String::new()];
+        for token in tt.syntax().children_with_tokens().filter_map(|it| it.into_token()) {
+            match token.kind() {
+                COMMA => derives.push(String::new()),
+                L_PAREN | R_PAREN | WHITESPACE => (),
+                _ => derives.last_mut().unwrap().push_str(token.text()),
+            }
+        }
+        derives.retain(|it| !it.is_empty());
+        let remaining =
+            derives.iter().filter(|it| !traits.contains(&it.as_str())).collect::<Vec<_>>();
+        if remaining.len() == derives.len() {
+            continue;
+        }
+        if remaining.is_empty() {
+            let attr_range = attr.syntax().text_range();
+            let line_break_range = attr
+                .syntax()
+                .next_sibling_or_token()
+                .filter(|it| it.kind() == WHITESPACE)
+                .map(|it| it.text_range())
+                .unwrap_or_else(|| TextRange::offset_len(attr_range.end(), TextUnit::from(0)));
+            edit.delete(TextRange::from_to(attr_range.start(), line_break_range.end()));
+        } else {
+            let new_tt = remaining.iter().sep_by(", ").surround_with("(", ")").to_string();
+            edit.replace(tt.syntax().text_range(), new_tt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn add_partial_eq_impl_all_fields() {
+        check_assist(
+            add_partial_eq_impl,
+            "
+struct Foo {
+    a: u32,<|>
+    b: String,
+}
+",
+            "
+struct Foo {
+    a: u32,<|>
+    b: String,
+}
+
+impl PartialEq for Foo {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a && self.b == other.b
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_partial_eq_impl_selected_fields() {
+        check_assist(
+            add_partial_eq_impl,
+            "
+struct Foo {
+    a: u32,
+    <|>b: String,<|>
+    cache: Cell<u32>,
+}
+",
+            "
+struct Foo {
+    a: u32,
+    b: String,
+    cache: Cell<u32>,
+}
+
+impl PartialEq for Foo {
+    fn eq(&self, other: &Self) -> bool {
+        self.b == other.b
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_partial_eq_impl_generic() {
+        check_assist(
+            add_partial_eq_impl,
+            "
+struct Foo<'a, T: Clone> {
+    a: &'a T,<|>
+}
+",
+            "
+struct Foo<'a, T: Clone> {
+    a: &'a T,<|>
+}
+
+impl<'a, T: Clone> PartialEq for Foo<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_partial_eq_impl_removes_derive() {
+        check_assist(
+            add_partial_eq_impl,
+            "
+#[derive(Debug, PartialEq)]
+struct Foo {
+    <|>a: u32,
+}
+",
+            "
+#[derive(Debug)]
+struct Foo {
+    <|>a: u32,
+}
+
+impl PartialEq for Foo {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_partial_eq_impl_keeps_path_derives() {
+        check_assist(
+            add_partial_eq_impl,
+            "
+#[derive(serde::Serialize, PartialEq, std::fmt::Debug)]
+struct Foo {
+    <|>a: u32,
+}
+",
+            "
+#[derive(serde::Serialize, std::fmt::Debug)]
+struct Foo {
+    <|>a: u32,
+}
+
+impl PartialEq for Foo {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_partial_eq_and_hash_impl_removes_derive() {
+        check_assist(
+            add_partial_eq_and_hash_impl,
+            "
+#[derive(PartialEq, Hash)]
+struct Foo {
+    <|>a: u32,
+    b: u32,<|>
+    c: u32,
+}
+",
+            "
+struct Foo {
+    a: u32,
+    b: u32,
+    c: u32,
+}
+
+impl PartialEq for Foo {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a && self.b == other.b
+    }
+}
+
+impl std::hash::Hash for Foo {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.a.hash(state);
+        self.b.hash(state);
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_partial_eq_impl_target() {
+        check_assist_target(
+            add_partial_eq_impl,
+            "struct Foo { <|>a: u32 }",
+            "struct Foo { a: u32 }",
+        )
+    }
+
+    #[test]
+    fn add_partial_eq_impl_not_applicable() {
+        check_assist_not_applicable(add_partial_eq_impl, "struct Foo(u32<|>);");
+        check_assist_not_applicable(add_partial_eq_impl, "struct Foo {<|>}");
+        check_assist_not_applicable(add_partial_eq_impl, "fn foo() { <|> }");
+    }
+}
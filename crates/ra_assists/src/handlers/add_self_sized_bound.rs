@@ -0,0 +1,188 @@
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner, TypeBoundsOwner, TypeParamsOwner},
+    SyntaxKind::WHITESPACE,
+    TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_self_sized_bound
+//
+// Adds `where Self: Sized` to a trait method which prevents the trait from
+// being used as a trait object, like a method without a `self` receiver or a
+// generic method. The method is then not available on trait objects.
+//
+// ```
+// trait Shape {
+//     fn <|>new() -> Self;
+//     fn area(&self) -> f64;
+// }
+// ```
+// ->
+// ```
+// trait Shape {
+//     fn new() -> Self where Self: Sized;
+//     fn area(&self) -> f64;
+// }
+// ```
+pub(crate) fn add_self_sized_bound(ctx: AssistCtx) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    // Only the signature, the body may contain other functions.
+    if let Some(body) = fn_def.body() {
+        if ctx.frange.range.start() > body.syntax().text_range().start() {
+            return None;
+        }
+    }
+    let in_trait = fn_def
+        .syntax()
+        .parent()
+        .and_then(|it| it.parent())
+        .map_or(false, |it| ast::TraitDef::can_cast(it.kind()));
+    if !in_trait || !violates_object_safety(&fn_def)? || has_self_sized_bound(&fn_def) {
+        return None;
+    }
+
+    let (offset, text) = match fn_def.where_clause() {
+        Some(where_clause) => {
+            let last = where_clause.syntax().last_token()?;
+            let text = if last.kind() == T![,] { " Self: Sized" } else { ", Self: Sized" };
+            (where_clause.syntax().text_range().end(), text)
+        }
+        None => match fn_def.body() {
+            Some(body) => {
+                let has_space = body
+                    .syntax()
+                    .prev_sibling_or_token()
+                    .map_or(false, |it| it.kind() == WHITESPACE);
+                let text = if has_space { "where Self: Sized " } else { " where Self: Sized " };
+                (body.syntax().text_range().start(), text)
+            }
+            None => (fn_def.semi_token()?.syntax().text_range().start(), " where Self: Sized"),
+        },
+    };
+
+    let name = fn_def.name()?;
+    ctx.add_assist(
+        AssistId("add_self_sized_bound"),
+        format!("Add `where Self: Sized` to `{}`", name.text()),
+        |edit| {
+            edit.target(name.syntax().text_range());
+            edit.insert(offset, text);
+            edit.set_cursor(offset + TextUnit::of_str(text.trim_end()));
+        },
+    )
+}
+
+/// Whether the method can't be called on a trait object, which makes the
+/// trait not object safe unless the method requires `Self: Sized`.
+fn violates_object_safety(fn_def: &ast::FnDef) -> Option<bool> {
+    let param_list = fn_def.param_list()?;
+    if param_list.self_param().is_none() {
+        return Some(true);
+    }
+    if fn_def.type_param_list().map_or(false, |it| it.type_params().next().is_some()) {
+        return Some(true);
+    }
+    // `Self` may only appear in the receiver, or as a qualifier like in `Self::Item`.
+    let types = param_list
+        .params()
+        .filter_map(|it| it.ascribed_type())
+        .chain(fn_def.ret_type().and_then(|it| it.type_ref()));
+    for ty in types {
+        for node in ty.syntax().descendants() {
+            if ast::ImplTraitType::can_cast(node.kind()) {
+                return Some(true);
+            }
+            if let Some(path) = ast::PathType::cast(node).and_then(|it| it.path()) {
+                if path.qualifier().is_none()
+                    && path.segment().map_or(false, |it| it.syntax().text() == "Self")
+                {
+                    return Some(true);
+                }
+            }
+        }
+    }
+    Some(false)
+}
+
+fn has_self_sized_bound(fn_def: &ast::FnDef) -> bool {
+    fn_def.where_clause().map_or(false, |where_clause| {
+        where_clause.predicates().any(|pred| {
+            pred.type_ref().map_or(false, |it| it.syntax().text() == "Self")
+                && pred
+                    .type_bound_list()
+                    .map_or(false, |it| it.bounds().any(|bound| bound.syntax().text() == "Sized"))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn add_self_sized_bound_to_constructor() {
+        check_assist(
+            add_self_sized_bound,
+            "trait Foo { fn <|>new() -> Self; }",
+            "trait Foo { fn new() -> Self where Self: Sized<|>; }",
+        );
+    }
+
+    #[test]
+    fn add_self_sized_bound_before_body() {
+        check_assist(
+            add_self_sized_bound,
+            r"
+trait Foo {
+    fn <|>eq(&self, other: &Self) -> bool {
+        true
+    }
+}",
+            r"
+trait Foo {
+    fn eq(&self, other: &Self) -> bool where Self: Sized<|> {
+        true
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_self_sized_bound_to_where_clause() {
+        check_assist(
+            add_self_sized_bound,
+            "trait Foo { fn <|>map<T>(&self, t: T) where T: Clone; }",
+            "trait Foo { fn map<T>(&self, t: T) where T: Clone, Self: Sized<|>; }",
+        );
+        check_assist(
+            add_self_sized_bound,
+            "trait Foo { fn <|>show(&self, t: impl Display) where T: Clone,; }",
+            "trait Foo { fn show(&self, t: impl Display) where T: Clone, Self: Sized<|>; }",
+        );
+    }
+
+    #[test]
+    fn add_self_sized_bound_not_applicable() {
+        // Object safe methods
+        check_assist_not_applicable(add_self_sized_bound, "trait Foo { fn <|>foo(&self); }");
+        check_assist_not_applicable(
+            add_self_sized_bound,
+            "trait Foo { type Item; fn <|>next(&mut self) -> Self::Item; }",
+        );
+        // Already bounded
+        check_assist_not_applicable(
+            add_self_sized_bound,
+            "trait Foo { fn <|>new() -> Self where Self: Sized; }",
+        );
+        // Not in a trait
+        check_assist_not_applicable(add_self_sized_bound, "impl S { fn <|>new() -> Self {} }");
+    }
+
+    #[test]
+    fn add_self_sized_bound_target() {
+        check_assist_target(add_self_sized_bound, "trait Foo { fn <|>new() -> Self; }", "new");
+    }
+}
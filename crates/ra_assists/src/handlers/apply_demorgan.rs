@@ -1,4 +1,4 @@
-use ra_syntax::ast::{self, AstNode};
+use ra_syntax::ast::{self, AstNode, AstToken};
 
 use crate::{utils::invert_boolean_expression, Assist, AssistCtx, AssistId};
 
@@ -24,7 +24,7 @@ use crate::{utils::invert_boolean_expression, Assist, AssistCtx, AssistId};
 pub(crate) fn apply_demorgan(ctx: AssistCtx) -> Option<Assist> {
     let expr = ctx.find_node_at_offset::<ast::BinExpr>()?;
     let op = expr.op_kind()?;
-    let op_range = expr.op_token()?.text_range();
+    let op_range = expr.op_token()?.syntax().text_range();
     let opposite_op = opposite_logic_op(op)?;
     let cursor_in_range = ctx.frange.range.is_subrange(&op_range);
     if !cursor_in_range {
@@ -39,6 +39,9 @@ use either::Either;
 // # pub mod std { pub mod collections { pub struct HashMap { } } }
 // ```
 pub(crate) fn auto_import(ctx: AssistCtx) -> Option<Assist> {
+    if !ctx.config.import_search {
+        return None;
+    }
     let auto_import_assets = AutoImportAssets::new(&ctx)?;
     let proposed_imports = auto_import_assets.search_for_imports(ctx.db);
     if proposed_imports.is_empty() {
@@ -280,7 +283,13 @@ impl ImportCandidate {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+    use crate::{
+        helpers::{
+            check_assist, check_assist_not_applicable, check_assist_not_applicable_with_config,
+            check_assist_target,
+        },
+        AssistConfig,
+    };
 
     #[test]
     fn applicable_when_found_an_import() {
@@ -305,6 +314,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn not_applicable_without_import_search() {
+        check_assist_not_applicable_with_config(
+            auto_import,
+            &AssistConfig { import_search: false, ..AssistConfig::default() },
+            r"
+            <|>PubStruct
+
+            pub mod PubMod {
+                pub struct PubStruct;
+            }
+            ",
+        );
+    }
+
     #[test]
     fn auto_imports_are_merged() {
         check_assist(
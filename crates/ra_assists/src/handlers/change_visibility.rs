@@ -1,6 +1,7 @@
+use hir::{ModuleDef, VisibleFrom};
 use ra_syntax::{
     ast::{self, NameOwner, VisibilityOwner},
-    AstNode,
+    match_ast, AstNode,
     SyntaxKind::{
         ATTR, COMMENT, CONST_DEF, ENUM_DEF, FN_DEF, MODULE, STRUCT_DEF, TRAIT_DEF, VISIBILITY,
         WHITESPACE,
@@ -80,13 +81,19 @@ fn vis_offset(node: &SyntaxNode) -> TextUnit {
 
 fn change_vis(ctx: AssistCtx, vis: ast::Visibility) -> Option<Assist> {
     if vis.syntax().text() == "pub" {
+        let new_vis = vis
+            .syntax()
+            .parent()
+            .and_then(|item| minimal_visibility(&ctx, &item))
+            .unwrap_or_else(|| "pub(crate)".to_string());
         return ctx.add_assist(
             AssistId("change_visibility"),
-            "Change Visibility to pub(crate)",
+            format!("Change Visibility to {}", new_vis),
             |edit| {
-                edit.target(vis.syntax().text_range());
-                edit.replace(vis.syntax().text_range(), "pub(crate)");
-                edit.set_cursor(vis.syntax().text_range().start())
+                let range = vis.syntax().text_range();
+                edit.target(range);
+                edit.replace(range, new_vis.clone());
+                edit.set_cursor(range.start())
             },
         );
     }
@@ -100,6 +107,52 @@ fn change_vis(ctx: AssistCtx, vis: ast::Visibility) -> Option<Assist> {
     None
 }
 
+/// The most restrictive visibility which keeps a `pub` item visible from
+/// everywhere it can be named now, possibly through re-exports. `None` if
+/// the item can be named from other crates.
+fn minimal_visibility(ctx: &AssistCtx, item: &SyntaxNode) -> Option<String> {
+    let sema = ctx.sema;
+    let def: ModuleDef = match_ast! {
+        match item {
+            ast::FnDef(it) => sema.to_def(&it)?.into(),
+            ast::StructDef(it) => sema.to_def(&it)?.into(),
+            ast::EnumDef(it) => sema.to_def(&it)?.into(),
+            ast::UnionDef(it) => sema.to_def(&it)?.into(),
+            ast::TraitDef(it) => sema.to_def(&it)?.into(),
+            ast::ConstDef(it) => sema.to_def(&it)?.into(),
+            ast::StaticDef(it) => sema.to_def(&it)?.into(),
+            ast::TypeAliasDef(it) => sema.to_def(&it)?.into(),
+            ast::Module(it) => sema.to_def(&it)?.into(),
+            _ => return None,
+        }
+    };
+    let visible_from = match def.visible_from(ctx.db) {
+        VisibleFrom::Modules(it) if !it.is_empty() => it,
+        _ => return None,
+    };
+    let module = def.module(ctx.db)?;
+    let ancestors = module.path_to_root(ctx.db);
+    let target = ancestors.iter().position(|ancestor| {
+        visible_from.iter().all(|it| it.path_to_root(ctx.db).contains(ancestor))
+    })?;
+    // A module is always visible from its parent, so `target` is never the
+    // module of the item itself.
+    let res = if target == ancestors.len() - 1 {
+        "pub(crate)".to_string()
+    } else if target == 1 {
+        "pub(super)".to_string()
+    } else {
+        let path = ancestors[target..]
+            .iter()
+            .rev()
+            .filter_map(|it| it.name(ctx.db))
+            .map(|it| it.to_string())
+            .collect::<Vec<_>>();
+        format!("pub(in crate::{})", path.join("::"))
+    };
+    Some(res)
+}
+
 #[cfg(test)]
 mod tests {
     use test_utils::covers;
@@ -146,6 +199,31 @@ mod tests {
         check_assist(change_visibility, "<|>pub fn foo() {}", "<|>pub(crate) fn foo() {}")
     }
 
+    #[test]
+    fn change_visibility_pub_to_minimal_visibility() {
+        check_assist(
+            change_visibility,
+            "mod a { mod b { <|>pub fn foo() {} } }",
+            "mod a { mod b { <|>pub(super) fn foo() {} } }",
+        );
+        check_assist(
+            change_visibility,
+            "mod a { mod b { pub(super) mod c { <|>pub struct Foo; } } }",
+            "mod a { mod b { pub(super) mod c { <|>pub(in crate::a) struct Foo; } } }",
+        );
+        check_assist(
+            change_visibility,
+            "mod a { <|>pub fn foo() {} }",
+            "mod a { <|>pub(crate) fn foo() {} }",
+        );
+        // Re-exports keep the item visible further
+        check_assist(
+            change_visibility,
+            "mod a { mod b { <|>pub fn foo() {} } pub(crate) use self::b::foo; }",
+            "mod a { mod b { <|>pub(crate) fn foo() {} } pub(crate) use self::b::foo; }",
+        );
+    }
+
     #[test]
     fn change_visibility_pub_crate_to_pub() {
         check_assist(change_visibility, "<|>pub(crate) fn foo() {}", "<|>pub fn foo() {}")
@@ -0,0 +1,292 @@
+use ra_syntax::{
+    ast::{self, AstNode, BinOp},
+    SourceFile, SyntaxElement, TextUnit, T,
+};
+use stdx::SepBy;
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: convert_assert_to_assert_eq
+//
+// Converts `assert!(a == b)` to `assert_eq!(a, b)`, and `assert!(a != b)` to `assert_ne!(a, b)`.
+//
+// ```
+// fn main() {
+//     <|>assert!(1 + 1 == 2, "math is broken");
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     assert_eq!(1 + 1, 2, "math is broken");
+// }
+// ```
+pub(crate) fn convert_assert_to_assert_eq(ctx: AssistCtx) -> Option<Assist> {
+    let (macro_call, name) = find_assert_call(&ctx, &["assert", "debug_assert"])?;
+    let args = split_args(&macro_call)?;
+    let (cond, rest) = args.split_first()?;
+    let cond = match parse_expr(cond)? {
+        ast::Expr::BinExpr(it) => it,
+        _ => return None,
+    };
+    let suffix = match cond.op_kind()? {
+        BinOp::EqualityTest => "eq",
+        BinOp::NegatedEqualityTest => "ne",
+        _ => return None,
+    };
+    let (lhs, rhs) = (cond.lhs()?, cond.rhs()?);
+    let new_name = format!("{}_{}", name, suffix);
+
+    let operands = vec![lhs.syntax().text().to_string(), rhs.syntax().text().to_string()];
+    let new_call = format!("{}!({})", new_name, operands.iter().chain(rest.iter()).sep_by(", "));
+    ctx.add_assist(
+        AssistId("convert_assert_to_assert_eq"),
+        format!("Convert to `{}!`", new_name),
+        |edit| {
+            let range = macro_call.syntax().text_range();
+            edit.target(range);
+            edit.replace(range, new_call);
+            edit.set_cursor(range.start());
+        },
+    )
+}
+
+// Assist: convert_assert_eq_to_assert
+//
+// Converts `assert_eq!(a, b)` to `assert!(a == b)`, and `assert_ne!(a, b)` to `assert!(a != b)`.
+//
+// ```
+// fn main() {
+//     <|>assert_ne!(x, 0, "x must not be zero");
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     assert!(x != 0, "x must not be zero");
+// }
+// ```
+pub(crate) fn convert_assert_eq_to_assert(ctx: AssistCtx) -> Option<Assist> {
+    let (macro_call, name) =
+        find_assert_call(&ctx, &["assert_eq", "assert_ne", "debug_assert_eq", "debug_assert_ne"])?;
+    let args = split_args(&macro_call)?;
+    if args.len() < 2 {
+        return None;
+    }
+    let lhs = operand_text(parse_expr(&args[0])?);
+    let rhs = operand_text(parse_expr(&args[1])?);
+    let (new_name, op) = if name.ends_with("_eq") {
+        (&name[..name.len() - "_eq".len()], "==")
+    } else {
+        (&name[..name.len() - "_ne".len()], "!=")
+    };
+
+    let cond = format!("{} {} {}", lhs, op, rhs);
+    let new_call =
+        format!("{}!({})", new_name, std::iter::once(&cond).chain(args[2..].iter()).sep_by(", "));
+    ctx.add_assist(
+        AssistId("convert_assert_eq_to_assert"),
+        format!("Convert to `{}!`", new_name),
+        |edit| {
+            let range = macro_call.syntax().text_range();
+            edit.target(range);
+            edit.replace(range, new_call);
+            edit.set_cursor(range.start());
+        },
+    )
+}
+
+/// Finds the innermost call of one of the given macros around the cursor.
+///
+/// Calls of a macro defined in the current crate are skipped, as a local
+/// `macro_rules! assert` shadows the standard one and may mean anything.
+fn find_assert_call(ctx: &AssistCtx, names: &[&str]) -> Option<(ast::MacroCall, String)> {
+    let macro_call = ctx
+        .covering_element()
+        .ancestors()
+        .filter_map(ast::MacroCall::cast)
+        .find(|it| macro_name(it).map_or(false, |name| names.contains(&name.as_str())))?;
+    let name = macro_name(&macro_call)?;
+
+    if let Some(def) = ctx.sema.resolve_macro_call(&macro_call) {
+        let current_crate = ctx.sema.scope(macro_call.syntax()).module()?.krate();
+        if def.module(ctx.db)?.krate() == current_crate {
+            return None;
+        }
+    }
+    Some((macro_call, name))
+}
+
+fn macro_name(macro_call: &ast::MacroCall) -> Option<String> {
+    let path = macro_call.path()?;
+    if path.qualifier().is_some() || macro_call.excl_token().is_none() {
+        return None;
+    }
+    Some(path.segment()?.name_ref()?.text().to_string())
+}
+
+/// Splits the arguments of a macro call at top-level commas. As commas may also
+/// appear in generic arguments, like `f::<A, B>()`, an argument extends until
+/// its text parses as an expression.
+fn split_args(macro_call: &ast::MacroCall) -> Option<Vec<String>> {
+    let token_tree = macro_call.token_tree()?;
+    let mut elements: Vec<SyntaxElement> = token_tree.syntax().children_with_tokens().collect();
+    match (elements.first()?.kind(), elements.last()?.kind()) {
+        (T!['('], T![')']) | (T!['['], T![']']) | (T!['{'], T!['}']) => {}
+        _ => return None,
+    }
+    let start = token_tree.syntax().text_range().start();
+    let text = token_tree.syntax().text().to_string();
+    let slice = |from: TextUnit, to: TextUnit| {
+        text[(from - start).to_usize()..(to - start).to_usize()].trim()
+    };
+
+    let r_delim = elements.pop()?;
+    let l_delim = elements.remove(0);
+    let mut args = Vec::new();
+    let mut arg_start = l_delim.text_range().end();
+    for comma in elements.iter().filter(|it| it.kind() == T![,]) {
+        let arg = slice(arg_start, comma.text_range().start());
+        if parse_expr(arg).is_some() {
+            args.push(arg.to_string());
+            arg_start = comma.text_range().end();
+        }
+    }
+    let last = slice(arg_start, r_delim.text_range().start());
+    if !last.is_empty() {
+        parse_expr(last)?;
+        args.push(last.to_string());
+    }
+    Some(args)
+}
+
+fn parse_expr(text: &str) -> Option<ast::Expr> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let parse = SourceFile::parse(&format!("fn f() {{ let _ = {}; }}", text));
+    if !parse.errors().is_empty() {
+        return None;
+    }
+    let let_stmt = parse.tree().syntax().descendants().find_map(ast::LetStmt::cast)?;
+    let expr = let_stmt.initializer()?;
+    if expr.syntax().to_string() != text {
+        return None;
+    }
+    Some(expr)
+}
+
+/// The text of an operand of `==`, parenthesized if it binds less tightly.
+fn operand_text(expr: ast::Expr) -> String {
+    let needs_parens = match &expr {
+        ast::Expr::BinExpr(it) => match it.op_kind() {
+            Some(op) => !is_arithmetic(op),
+            None => true,
+        },
+        ast::Expr::RangeExpr(_) | ast::Expr::LambdaExpr(_) => true,
+        _ => false,
+    };
+    if needs_parens {
+        format!("({})", expr.syntax())
+    } else {
+        expr.syntax().to_string()
+    }
+}
+
+fn is_arithmetic(op: BinOp) -> bool {
+    match op {
+        BinOp::Addition
+        | BinOp::Multiplication
+        | BinOp::Subtraction
+        | BinOp::Division
+        | BinOp::Remainder
+        | BinOp::LeftShift
+        | BinOp::RightShift
+        | BinOp::BitwiseXor
+        | BinOp::BitwiseOr
+        | BinOp::BitwiseAnd => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn assert_to_assert_eq() {
+        check_assist(
+            convert_assert_to_assert_eq,
+            "fn f() { <|>assert!(a == b); }",
+            "fn f() { <|>assert_eq!(a, b); }",
+        );
+    }
+
+    #[test]
+    fn assert_to_assert_ne_keeps_message() {
+        check_assist(
+            convert_assert_to_assert_eq,
+            r#"fn f() { debug_assert!(<|>foo::<A, B>() != x, "{} and {}", a, b); }"#,
+            r#"fn f() { <|>debug_assert_ne!(foo::<A, B>(), x, "{} and {}", a, b); }"#,
+        );
+    }
+
+    #[test]
+    fn assert_to_assert_eq_in_nested_macro() {
+        check_assist(
+            convert_assert_to_assert_eq,
+            "fn f() { assert!(vec![<|>1, 2] == v); }",
+            "fn f() { <|>assert_eq!(vec![1, 2], v); }",
+        );
+    }
+
+    #[test]
+    fn assert_to_assert_eq_not_applicable_for_other_conditions() {
+        check_assist_not_applicable(convert_assert_to_assert_eq, "fn f() { <|>assert!(a < b); }");
+        check_assist_not_applicable(
+            convert_assert_to_assert_eq,
+            "fn f() { <|>assert!(a == b && c == d); }",
+        );
+    }
+
+    #[test]
+    fn assert_to_assert_eq_not_applicable_for_local_macro() {
+        check_assist_not_applicable(
+            convert_assert_to_assert_eq,
+            r#"
+macro_rules! assert { ($e:expr) => { $e } }
+fn f() { <|>assert!(a == b); }
+"#,
+        );
+    }
+
+    #[test]
+    fn assert_eq_to_assert() {
+        check_assist(
+            convert_assert_eq_to_assert,
+            r#"fn f() { <|>assert_eq!(a + 1, b, "message: {}", c); }"#,
+            r#"fn f() { <|>assert!(a + 1 == b, "message: {}", c); }"#,
+        );
+    }
+
+    #[test]
+    fn assert_ne_to_assert_adds_parens() {
+        check_assist(
+            convert_assert_eq_to_assert,
+            "fn f() { <|>debug_assert_ne!(a < b, c && d); }",
+            "fn f() { <|>debug_assert!((a < b) != (c && d)); }",
+        );
+    }
+
+    #[test]
+    fn assert_eq_to_assert_target() {
+        check_assist_target(
+            convert_assert_eq_to_assert,
+            "fn f() { <|>assert_eq!(a, b); }",
+            "assert_eq!(a, b)",
+        );
+    }
+}
@@ -0,0 +1,498 @@
+use hir::{ModuleDef, PathResolution};
+use ra_ide_db::{
+    defs::Definition,
+    search::{Reference, ReferenceKind},
+};
+use ra_syntax::{
+    ast::{
+        self, edit::IndentLevel, ArgListOwner, AstNode, AstToken, NameOwner, TypeAscriptionOwner,
+        TypeParamsOwner,
+    },
+    SourceFile, SyntaxKind, TextRange, TextUnit,
+};
+use stdx::format_to;
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: convert_to_method
+//
+// Converts a free function, whose first parameter is (a reference to) a local
+// type, into a method of that type, and turns all calls into method calls.
+//
+// ```
+// struct Counter { count: u32 }
+//
+// fn <|>increment(counter: &mut Counter, by: u32) {
+//     counter.count += by;
+// }
+//
+// fn main() {
+//     let mut counter = Counter { count: 0 };
+//     increment(&mut counter, 2);
+// }
+// ```
+// ->
+// ```
+// struct Counter { count: u32 }
+//
+// impl Counter {
+//     fn increment(&mut self, by: u32) {
+//         self.count += by;
+//     }
+// }
+//
+// fn main() {
+//     let mut counter = Counter { count: 0 };
+//     counter.increment(2);
+// }
+// ```
+pub(crate) fn convert_to_method(ctx: AssistCtx) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if body.syntax().text_range().contains_inclusive(ctx.frange.range.start()) {
+        return None;
+    }
+    let parent = fn_def.syntax().parent()?;
+    let is_free_fn = match parent.kind() {
+        SyntaxKind::SOURCE_FILE => true,
+        SyntaxKind::ITEM_LIST => parent.parent().and_then(ast::Module::cast).is_some(),
+        _ => false,
+    };
+    if !is_free_fn {
+        return None;
+    }
+
+    let param_list = fn_def.param_list()?;
+    if param_list.self_param().is_some() {
+        return None;
+    }
+    let mut params = param_list.params();
+    let self_param = params.next()?;
+    let next_param = params.next();
+    let bind_pat = match self_param.pat()? {
+        ast::Pat::BindPat(it) => it,
+        _ => return None,
+    };
+    if bind_pat.ref_kw_token().is_some() || bind_pat.pat().is_some() {
+        return None;
+    }
+
+    let (self_ref, adt_path) = match self_param.ascribed_type()? {
+        ast::TypeRef::PathType(it) => (SelfRef::Value, it.path()?),
+        ast::TypeRef::ReferenceType(it) => {
+            let self_ref = if it.mut_kw_token().is_some() { SelfRef::MutRef } else { SelfRef::Ref };
+            match it.type_ref()? {
+                ast::TypeRef::PathType(it) => (self_ref, it.path()?),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    if adt_path.segment()?.type_arg_list().is_some() {
+        return None;
+    }
+    // `mut self: &S` is rarely what one wants, so keep it simple.
+    if bind_pat.mut_kw_token().is_some() && !matches!(self_ref, SelfRef::Value) {
+        return None;
+    }
+    let adt = match ctx.sema.resolve_path(&adt_path)? {
+        PathResolution::Def(ModuleDef::Adt(it)) => it,
+        _ => return None,
+    };
+    let module = ctx.sema.to_def(&fn_def)?.module(ctx.db);
+    if adt.module(ctx.db).krate() != module.krate() {
+        return None;
+    }
+
+    let fn_name = fn_def.name()?.text().clone();
+    let fn_range = fn_def.syntax().text_range();
+    let file_id = ctx.frange.file_id;
+
+    let call_refs = Definition::ModuleDef(ModuleDef::Function(ctx.sema.to_def(&fn_def)?))
+        .find_usages(ctx.db, None);
+    let mut call_edits = Vec::new();
+    for reference in call_refs {
+        if reference.file_range.file_id != file_id {
+            return None;
+        }
+        call_edits.extend(rewrite_call(&ctx, &reference, &fn_name, self_ref)?);
+    }
+
+    let local = ctx.sema.to_def(&bind_pat)?;
+    let param_name = bind_pat.name()?.text().clone();
+    let mut self_edits = Definition::Local(local)
+        .find_usages(ctx.db, None)
+        .into_iter()
+        .map(|reference| {
+            let replacement = if reference.kind == ReferenceKind::StructFieldShorthandForLocal {
+                format!("{}: self", param_name)
+            } else {
+                "self".to_string()
+            };
+            (reference.file_range.range, replacement)
+        })
+        .collect::<Vec<_>>();
+    let mut self_text = self_ref.prefix().to_string();
+    if bind_pat.mut_kw_token().is_some() {
+        self_text.push_str("mut ");
+    }
+    self_text.push_str("self");
+    let self_param_range = match &next_param {
+        Some(next) => {
+            self_text.push_str(", ");
+            TextRange::from_to(
+                self_param.syntax().text_range().start(),
+                next.syntax().text_range().start(),
+            )
+        }
+        None => self_param.syntax().text_range(),
+    };
+    self_edits.push((self_param_range, self_text));
+
+    let existing_impl = parent.children().filter_map(ast::ImplDef::cast).find(|impl_def| {
+        impl_def.target_trait().is_none()
+            && impl_def.type_param_list().is_none()
+            && ctx
+                .sema
+                .to_def(impl_def)
+                .map_or(false, |it| it.target_ty(ctx.db).as_adt() == Some(adt))
+    });
+    let adt_name = adt_path.syntax().text().to_string();
+
+    ctx.add_assist(
+        AssistId("convert_to_method"),
+        format!("Convert to method of `{}`", adt_name),
+        |edit| {
+            edit.target(fn_range);
+
+            let (method_edits, file_edits): (Vec<_>, Vec<_>) = call_edits
+                .into_iter()
+                .chain(self_edits.into_iter())
+                .partition(|(range, _)| range.is_subrange(&fn_range));
+            let method = method_text(&fn_def, method_edits);
+
+            let mut file_edits = file_edits;
+            let (insert_range, insert_text, method_offset) = match &existing_impl {
+                Some(impl_def) => {
+                    file_edits.push((extend_to_preceding_whitespace(&fn_def), String::new()));
+
+                    let item_list = match impl_def.item_list() {
+                        Some(it) => it,
+                        None => return,
+                    };
+                    let impl_indent = IndentLevel::from_node(impl_def.syntax()).0;
+                    let mut buf = String::new();
+                    let offset = match item_list.impl_items().last() {
+                        Some(last_item) => {
+                            buf.push_str("\n\n");
+                            last_item.syntax().text_range().end()
+                        }
+                        None => {
+                            buf.push('\n');
+                            match item_list.l_curly_token() {
+                                Some(it) => it.syntax().text_range().end(),
+                                None => return,
+                            }
+                        }
+                    };
+                    buf.push_str(&indent_text(impl_indent + 1));
+                    let method_offset = TextUnit::of_str(&buf);
+                    buf.push_str(&method);
+                    if item_list.impl_items().next().is_none() {
+                        format_to!(buf, "\n{}", indent_text(impl_indent));
+                    }
+                    (TextRange::offset_len(offset, 0.into()), buf, method_offset)
+                }
+                None => {
+                    let indent = IndentLevel::from_node(fn_def.syntax()).0;
+                    let mut buf = String::new();
+                    format_to!(buf, "impl {} {{\n{}", adt_name, indent_text(indent + 1));
+                    let method_offset = TextUnit::of_str(&buf);
+                    format_to!(buf, "{}\n{}}}", method, indent_text(indent));
+                    (fn_range, buf, method_offset)
+                }
+            };
+
+            // The cursor is placed in the resulting text, so account for all the
+            // edits before the new method.
+            let mut cursor = insert_range.start() + method_offset;
+            for (range, text) in file_edits.iter() {
+                if range.end() <= insert_range.start() {
+                    cursor = cursor + TextUnit::of_str(text) - range.len();
+                }
+            }
+
+            for (range, text) in file_edits {
+                edit.replace(range, text);
+            }
+            edit.replace(insert_range, insert_text);
+            edit.set_cursor(cursor);
+        },
+    )
+}
+
+#[derive(Clone, Copy)]
+enum SelfRef {
+    Value,
+    Ref,
+    MutRef,
+}
+
+impl SelfRef {
+    fn prefix(self) -> &'static str {
+        match self {
+            SelfRef::Value => "",
+            SelfRef::Ref => "&",
+            SelfRef::MutRef => "&mut ",
+        }
+    }
+}
+
+/// Turns `foo(a, b)` into `a.foo(b)`, removing the `&`/`&mut` of `a` if it is
+/// added by auto-ref anyway.
+fn rewrite_call(
+    ctx: &AssistCtx,
+    reference: &Reference,
+    fn_name: &str,
+    self_ref: SelfRef,
+) -> Option<Vec<(TextRange, String)>> {
+    let path_expr = ctx
+        .covering_node_for_range(reference.file_range.range)
+        .ancestors()
+        .find_map(ast::PathExpr::cast)?;
+    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+    let arg_list = call.arg_list()?;
+    let mut args = arg_list.args();
+    let receiver = args.next()?;
+    let next_arg_start = match args.next() {
+        Some(it) => it.syntax().text_range().start(),
+        None => arg_list.r_paren_token()?.syntax().text_range().start(),
+    };
+
+    let receiver_inner = match (&receiver, self_ref) {
+        (ast::Expr::RefExpr(ref_expr), SelfRef::Ref) if ref_expr.mut_kw_token().is_none() => {
+            ref_expr.expr()?
+        }
+        (ast::Expr::RefExpr(ref_expr), SelfRef::MutRef) if ref_expr.mut_kw_token().is_some() => {
+            ref_expr.expr()?
+        }
+        _ => receiver.clone(),
+    };
+    let needs_parens = !matches!(
+        receiver_inner,
+        ast::Expr::PathExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::TupleExpr(_)
+            | ast::Expr::ArrayExpr(_)
+            | ast::Expr::Literal(_)
+            | ast::Expr::MacroCall(_)
+            | ast::Expr::TryExpr(_)
+    );
+
+    let (open, close) = if needs_parens { ("(", ")") } else { ("", "") };
+    let head = TextRange::from_to(
+        path_expr.syntax().text_range().start(),
+        receiver_inner.syntax().text_range().start(),
+    );
+    let tail = TextRange::from_to(receiver.syntax().text_range().end(), next_arg_start);
+    Some(vec![(head, open.to_string()), (tail, format!("{}.{}(", close, fn_name))])
+}
+
+/// Applies `edits` to the text of `fn_def` and indents the result by one level.
+fn method_text(fn_def: &ast::FnDef, mut edits: Vec<(TextRange, String)>) -> String {
+    let fn_start = fn_def.syntax().text_range().start();
+    let mut text = fn_def.syntax().text().to_string();
+    edits.sort_by_key(|(range, _)| range.start());
+    for (range, replacement) in edits.into_iter().rev() {
+        let start = (range.start() - fn_start).to_usize();
+        let end = (range.end() - fn_start).to_usize();
+        text.replace_range(start..end, &replacement);
+    }
+
+    let method = SourceFile::parse(&text).tree().syntax().descendants().find_map(ast::FnDef::cast);
+    match method {
+        Some(method) => IndentLevel(1).increase_indent(method).syntax().to_string(),
+        None => text,
+    }
+}
+
+fn extend_to_preceding_whitespace(fn_def: &ast::FnDef) -> TextRange {
+    let range = fn_def.syntax().text_range();
+    match fn_def.syntax().prev_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => {
+            TextRange::from_to(ws.text_range().start(), range.end())
+        }
+        _ => range,
+    }
+}
+
+fn indent_text(level: u8) -> String {
+    "    ".repeat(level as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn convert_to_method_creates_impl() {
+        check_assist(
+            convert_to_method,
+            r"
+struct S { x: u32 }
+
+fn <|>get(s: &S) -> u32 {
+    s.x
+}
+
+fn main() {
+    let s = S { x: 92 };
+    get(&s);
+}
+",
+            r"
+struct S { x: u32 }
+
+impl S {
+    <|>fn get(&self) -> u32 {
+        self.x
+    }
+}
+
+fn main() {
+    let s = S { x: 92 };
+    s.get();
+}
+",
+        )
+    }
+
+    #[test]
+    fn convert_to_method_existing_impl() {
+        check_assist(
+            convert_to_method,
+            r"
+struct S { x: u32 }
+
+impl S {
+    fn new() -> S { S { x: 0 } }
+}
+
+fn main() {
+    let s = S::new();
+    into_x(s.clone());
+}
+
+fn <|>into_x(mut s: S) -> u32 {
+    s.x += 1;
+    s.x
+}
+",
+            r"
+struct S { x: u32 }
+
+impl S {
+    fn new() -> S { S { x: 0 } }
+
+    <|>fn into_x(mut self) -> u32 {
+        self.x += 1;
+        self.x
+    }
+}
+
+fn main() {
+    let s = S::new();
+    s.clone().into_x();
+}
+",
+        )
+    }
+
+    #[test]
+    fn convert_to_method_rewrites_nested_and_recursive_calls() {
+        check_assist(
+            convert_to_method,
+            r"
+struct S { x: u32 }
+
+fn <|>next(s: &mut S, by: u32) -> &mut S {
+    if by > 0 {
+        return next(s, by - 1);
+    }
+    s
+}
+
+fn main(s: &mut S, other: &mut S) {
+    next(next(&mut *s, 1), 2);
+    next(if true { s } else { other }, 3);
+}
+",
+            r"
+struct S { x: u32 }
+
+impl S {
+    <|>fn next(&mut self, by: u32) -> &mut S {
+        if by > 0 {
+            return self.next(by - 1);
+        }
+        self
+    }
+}
+
+fn main(s: &mut S, other: &mut S) {
+    (*s).next(1).next(2);
+    (if true { s } else { other }).next(3);
+}
+",
+        )
+    }
+
+    #[test]
+    fn convert_to_method_shorthand_field() {
+        check_assist(
+            convert_to_method,
+            r"
+struct S;
+struct Wrapper { s: S }
+
+fn <|>wrap(s: S) -> Wrapper {
+    Wrapper { s }
+}
+",
+            r"
+struct S;
+struct Wrapper { s: S }
+
+impl S {
+    <|>fn wrap(self) -> Wrapper {
+        Wrapper { s: self }
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn convert_to_method_target() {
+        check_assist_target(convert_to_method, "struct S; fn <|>f(s: S) {}", "fn f(s: S) {}")
+    }
+
+    #[test]
+    fn convert_to_method_not_applicable() {
+        check_assist_not_applicable(convert_to_method, "fn <|>f(x: u32) {}");
+        check_assist_not_applicable(convert_to_method, "struct S; fn <|>f() {}");
+        check_assist_not_applicable(convert_to_method, "struct S; fn f(s: S) { <|> }");
+        check_assist_not_applicable(convert_to_method, "struct S; impl S { fn <|>f(s: S) {} }");
+        check_assist_not_applicable(convert_to_method, "struct S<T>(T); fn <|>f(s: S<u32>) {}");
+        check_assist_not_applicable(
+            convert_to_method,
+            "struct S; fn <|>f(s: S) {} fn main() { let g = f; }",
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use ra_ide_db::defs::Definition;
+use ra_syntax::{
+    ast::{self, AstNode, FieldKind, NameOwner},
+    TextRange,
+};
+use rustc_hash::FxHashMap;
+use stdx::SepBy;
+
+use crate::{utils::to_lower_snake_case, Assist, AssistCtx, AssistId};
+
+// Assist: destructure_tuple_binding
+//
+// Destructures a tuple binding into named bindings, replacing all index accesses.
+//
+// ```
+// struct Point;
+// fn main() {
+//     let <|>x = (Point, 92);
+//     let p = x.0;
+// }
+// ```
+// ->
+// ```
+// struct Point;
+// fn main() {
+//     let (point, _) = (Point, 92);
+//     let p = point;
+// }
+// ```
+pub(crate) fn destructure_tuple_binding(ctx: AssistCtx) -> Option<Assist> {
+    let bind_pat = ctx.find_node_at_offset::<ast::BindPat>()?;
+    if bind_pat.ref_kw_token().is_some() || bind_pat.pat().is_some() {
+        return None;
+    }
+    let name = bind_pat.name()?;
+
+    let ty = ctx.sema.type_of_pat(&ast::Pat::BindPat(bind_pat.clone()))?;
+    let field_types = ty.tuple_fields(ctx.db);
+    if field_types.is_empty() {
+        return None;
+    }
+
+    let def = ctx.sema.to_def(&bind_pat)?;
+    let refs = Definition::Local(def).find_usages(ctx.db, None);
+    if refs.is_empty() {
+        return None;
+    }
+
+    // Every usage must be an index access, otherwise the tuple is still needed.
+    let mut accesses: Vec<(TextRange, usize)> = Vec::new();
+    for reference in refs.iter() {
+        let path_expr = ctx
+            .covering_node_for_range(reference.file_range.range)
+            .ancestors()
+            .find_map(ast::PathExpr::cast)?;
+        let field_expr = path_expr.syntax().parent().and_then(ast::FieldExpr::cast)?;
+        let index = match field_expr.field_access()? {
+            FieldKind::Index(token) => token.text().parse::<usize>().ok()?,
+            FieldKind::Name(_) => return None,
+        };
+        if index >= field_types.len() {
+            return None;
+        }
+        accesses.push((field_expr.syntax().text_range(), index));
+    }
+
+    let names = field_names(
+        name.text(),
+        field_types.iter().map(|ty| ty.as_adt().map(|adt| adt.name(ctx.db).to_string())),
+    );
+
+    ctx.add_assist(AssistId("destructure_tuple_binding"), "Destructure tuple binding", |edit| {
+        let is_mut = bind_pat.mut_kw_token().is_some();
+        let pat = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                if !accesses.iter().any(|&(_, it)| it == idx) {
+                    "_".to_string()
+                } else if is_mut {
+                    format!("mut {}", name)
+                } else {
+                    name.clone()
+                }
+            })
+            .sep_by(", ");
+        let range = bind_pat.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, format!("({})", pat));
+        for &(range, idx) in accesses.iter() {
+            edit.replace(range, names[idx].clone());
+        }
+        edit.set_cursor(range.start());
+    })
+}
+
+/// Suggests a name for each tuple element, based on the name of its type if it
+/// is an ADT, and on the binding name otherwise. Duplicates get the element
+/// index as a suffix.
+fn field_names(binding: &str, type_names: impl Iterator<Item = Option<String>>) -> Vec<String> {
+    let names: Vec<String> = type_names
+        .enumerate()
+        .map(|(idx, it)| match it {
+            Some(name) => to_lower_snake_case(&name),
+            None => format!("{}_{}", binding, idx),
+        })
+        .collect();
+    let mut counts = FxHashMap::default();
+    for name in names.iter() {
+        *counts.entry(name.clone()).or_insert(0) += 1;
+    }
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| if counts[&name] > 1 { format!("{}_{}", name, idx) } else { name })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn destructure_names_from_element_types() {
+        check_assist(
+            destructure_tuple_binding,
+            r"
+struct Point;
+struct HttpClient;
+fn main() {
+    let <|>pair = (Point, HttpClient, 1);
+    let p = pair.0;
+    pair.1;
+    pair.2 + pair.2;
+}",
+            r"
+struct Point;
+struct HttpClient;
+fn main() {
+    let <|>(point, http_client, pair_2) = (Point, HttpClient, 1);
+    let p = point;
+    http_client;
+    pair_2 + pair_2;
+}",
+        );
+    }
+
+    #[test]
+    fn destructure_mut_binding_and_unused_fields() {
+        check_assist(
+            destructure_tuple_binding,
+            r"
+fn main() {
+    let mut <|>x = (1, 2, 3);
+    x.1 += 1;
+}",
+            r"
+fn main() {
+    let <|>(_, mut x_1, _) = (1, 2, 3);
+    x_1 += 1;
+}",
+        );
+    }
+
+    #[test]
+    fn destructure_duplicate_type_names() {
+        check_assist(
+            destructure_tuple_binding,
+            r"
+struct S;
+fn foo(<|>s: (S, S)) -> S {
+    let _ = s.1;
+    s.0
+}",
+            r"
+struct S;
+fn foo(<|>(s_0, s_1): (S, S)) -> S {
+    let _ = s_1;
+    s_0
+}",
+        );
+    }
+
+    #[test]
+    fn destructure_not_applicable_when_tuple_used_whole() {
+        check_assist_not_applicable(
+            destructure_tuple_binding,
+            r"
+fn foo(x: (i32, i32)) {}
+fn main() {
+    let <|>x = (1, 2);
+    x.0;
+    foo(x);
+}",
+        );
+    }
+
+    #[test]
+    fn destructure_not_applicable_for_non_tuples() {
+        check_assist_not_applicable(
+            destructure_tuple_binding,
+            r"
+fn main() {
+    let <|>x = 92;
+    x;
+}",
+        );
+    }
+
+    #[test]
+    fn destructure_target() {
+        check_assist_target(
+            destructure_tuple_binding,
+            r"
+fn main() {
+    let <|>x = (1, 2);
+    x.0;
+}",
+            "x",
+        );
+    }
+}
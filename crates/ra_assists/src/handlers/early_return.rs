@@ -43,6 +43,9 @@ pub(crate) fn convert_to_guarded_return(ctx: AssistCtx) -> Option<Assist> {
     }
 
     let cond = if_expr.condition()?;
+    if cond.is_let_chain() {
+        return None;
+    }
 
     // Check if there is an IfLet that we can handle.
     let if_let_pat = match cond.pat() {
@@ -0,0 +1,218 @@
+use hir::ModuleSource;
+use ra_db::{RelativePath, RelativePathBuf, SourceDatabaseExt};
+use ra_ide_db::RootDatabase;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AttrsOwner, NameOwner},
+    AstNode, SyntaxKind, TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: extract_module_to_file
+//
+// Moves the body of an inline module to a separate file.
+//
+// ```
+// mod <|>foo {
+//     fn bar() {}
+// }
+// ```
+// ->
+// ```
+// mod foo;
+// ```
+pub(crate) fn extract_module_to_file(ctx: AssistCtx) -> Option<Assist> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    let item_list = module_ast.item_list()?;
+    // Only the header of the module, the body may contain other modules.
+    if ctx.frange.range.start() > item_list.syntax().text_range().start() {
+        return None;
+    }
+    if item_list.l_curly_token().is_none() || item_list.r_curly_token().is_none() {
+        return None;
+    }
+
+    // The module path of the new file, relative to the directory of the
+    // current file. Modules with a `#[path]` attribute live somewhere else.
+    let mut inline_modules: Vec<ast::Module> =
+        module_ast.syntax().ancestors().filter_map(ast::Module::cast).collect();
+    inline_modules.reverse();
+    let mut module_path = RelativePathBuf::new();
+    for it in inline_modules.iter() {
+        if has_path_attr(it) {
+            return None;
+        }
+        module_path.push(it.name()?.text().trim_start_matches("r#"));
+    }
+
+    let file_id = ctx.frange.file_id;
+    let file_path = ctx.db.file_relative_path(file_id);
+    let file_module = ctx.sema.to_module_def(file_id)?;
+    // Submodules of `foo.rs` live in `foo/`, those of `lib.rs` and `mod.rs`
+    // next to them.
+    let dir = file_path.parent().unwrap_or_else(|| RelativePath::new(""));
+    let dir = if file_module.parent(ctx.db).is_none() || file_path.file_stem() == Some("mod") {
+        dir.to_relative_path_buf()
+    } else {
+        dir.join(file_path.file_stem()?)
+    };
+
+    let source_root_id = ctx.db.file_source_root(file_id);
+    let source_root = ctx.db.source_root(source_root_id);
+    let candidates =
+        [dir.join(format!("{}.rs", module_path)), dir.join(format!("{}/mod.rs", module_path))];
+    if candidates.iter().any(|it| source_root.file_by_relative_path(it).is_some()) {
+        return None;
+    }
+    let parent = ctx.sema.to_def(&module_ast)?.parent(ctx.db)?;
+    let path = if siblings_use_mod_rs(ctx.db, parent) {
+        candidates[1].clone()
+    } else {
+        candidates[0].clone()
+    };
+
+    // Everything between the braces, including inner attributes, moves.
+    let indent = IndentLevel::from_node(module_ast.syntax());
+    let body = IndentLevel(indent.0 + 1).decrease_indent(item_list.clone());
+    let body_text = body.syntax().text().to_string();
+    let contents = body_text["{".len()..body_text.len() - "}".len()].trim();
+    let contents = if contents.is_empty() { String::new() } else { format!("{}\n", contents) };
+
+    ctx.add_assist(AssistId("extract_module_to_file"), "Extract module to file", |edit| {
+        edit.target(module_ast.syntax().text_range());
+        let body_range = item_list.syntax().text_range();
+        let start = match item_list.syntax().prev_sibling_or_token() {
+            Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+            _ => body_range.start(),
+        };
+        edit.replace(TextRange::from_to(start, body_range.end()), ";");
+        edit.create_file(source_root_id, path, contents);
+    })
+}
+
+fn has_path_attr(module: &ast::Module) -> bool {
+    module.attrs().filter_map(|it| it.simple_name()).any(|name| name == "path")
+}
+
+/// Whether the other file modules next to the new one are all `mod.rs` files.
+fn siblings_use_mod_rs(db: &RootDatabase, parent: hir::Module) -> bool {
+    let mut has_mod_rs = false;
+    for child in parent.children(db) {
+        let src = child.definition_source(db);
+        if let ModuleSource::SourceFile(_) = src.value {
+            let path = db.file_relative_path(src.file_id.original_file(db));
+            if path.file_name() != Some("mod.rs") {
+                return false;
+            }
+            has_mod_rs = true;
+        }
+    }
+    has_mod_rs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{
+        check_assist, check_assist_new_file, check_assist_not_applicable, check_assist_target,
+    };
+
+    use super::*;
+
+    #[test]
+    fn extract_module_leaves_declaration() {
+        check_assist(
+            extract_module_to_file,
+            r#"
+#[cfg(test)]
+mod <|>tests {
+    #![allow(unused)]
+    fn foo() {}
+}
+"#,
+            r#"
+#[cfg(test)]
+mod <|>tests;
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_module_moves_body_with_inner_attributes() {
+        check_assist_new_file(
+            extract_module_to_file,
+            r#"
+#[cfg(test)]
+mod <|>tests {
+    #![allow(unused)]
+
+    fn foo() {
+        let x = 92;
+    }
+}
+"#,
+            "tests.rs",
+            r#"#![allow(unused)]
+
+fn foo() {
+    let x = 92;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_nested_module() {
+        check_assist_new_file(
+            extract_module_to_file,
+            r#"
+//- /main.rs
+mod a;
+//- /a.rs
+mod b {
+    mod <|>c {
+        struct S;
+    }
+}
+"#,
+            "a/b/c.rs",
+            "struct S;\n",
+        );
+    }
+
+    #[test]
+    fn extract_module_detects_mod_rs_layout() {
+        check_assist_new_file(
+            extract_module_to_file,
+            r#"
+//- /lib.rs
+mod foo;
+mod <|>bar {}
+//- /foo/mod.rs
+"#,
+            "bar/mod.rs",
+            "",
+        );
+    }
+
+    #[test]
+    fn extract_module_not_applicable() {
+        check_assist_not_applicable(extract_module_to_file, "mod foo {<|> fn bar() {} }");
+        check_assist_not_applicable(
+            extract_module_to_file,
+            r#"#[path = "baz.rs"] mod <|>foo { fn bar() {} }"#,
+        );
+        check_assist_not_applicable(
+            extract_module_to_file,
+            r#"
+//- /main.rs
+mod <|>foo {}
+//- /foo.rs
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_module_target() {
+        check_assist_target(extract_module_to_file, "mod <|>foo { }", "mod foo { }");
+    }
+}
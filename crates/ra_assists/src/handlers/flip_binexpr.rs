@@ -1,4 +1,4 @@
-use ra_syntax::ast::{AstNode, BinExpr, BinOp};
+use ra_syntax::ast::{AstNode, AstToken, BinExpr, BinOp};
 
 use crate::{Assist, AssistCtx, AssistId};
 
@@ -21,7 +21,7 @@ pub(crate) fn flip_binexpr(ctx: AssistCtx) -> Option<Assist> {
     let expr = ctx.find_node_at_offset::<BinExpr>()?;
     let lhs = expr.lhs()?.syntax().clone();
     let rhs = expr.rhs()?.syntax().clone();
-    let op_range = expr.op_token()?.text_range();
+    let op_range = expr.op_token()?.syntax().text_range();
     // The assist should be applied only if the cursor is on the operator
     let cursor_in_range = ctx.frange.range.is_subrange(&op_range);
     if !cursor_in_range {
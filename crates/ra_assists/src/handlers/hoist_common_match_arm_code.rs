@@ -0,0 +1,415 @@
+use hir::{PathResolution, Semantics};
+use ra_fmt::leading_indent;
+use ra_ide_db::RootDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxNode, TextRange, TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: hoist_common_match_arm_code
+//
+// Moves the statements all arms of a `match` begin or end with out of it.
+//
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn handle(action: Action) {
+//     <|>match action {
+//         Action::Move { distance } => {
+//             start();
+//             move_by(distance);
+//             finish();
+//         }
+//         Action::Stop => {
+//             start();
+//             finish();
+//         }
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn handle(action: Action) {
+//     start();
+//     match action {
+//         Action::Move { distance } => {
+//             move_by(distance);
+//         }
+//         Action::Stop => {}
+//     }
+//     finish();
+// }
+// ```
+pub(crate) fn hoist_common_match_arm_code(ctx: AssistCtx) -> Option<Assist> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let arm_list = match_expr.match_arm_list()?;
+    // Only on the `match` itself, the arms may contain other matches.
+    if ctx.frange.range.start() >= arm_list.syntax().text_range().start() {
+        return None;
+    }
+    // The hoisted code goes next to the statement with the match, so its
+    // value must not be used.
+    let parent = match_expr.syntax().parent()?;
+    let anchor = if ast::ExprStmt::can_cast(parent.kind()) {
+        parent
+    } else if ast::Block::can_cast(parent.kind()) {
+        match_expr.syntax().clone()
+    } else {
+        return None;
+    };
+
+    let arms = arm_list.arms().collect::<Vec<_>>();
+    if arms.len() < 2 {
+        return None;
+    }
+    let blocks = arms
+        .iter()
+        .map(|arm| match arm.expr()? {
+            ast::Expr::BlockExpr(it) => it.block(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let stmts = blocks.iter().map(|it| it.statements().collect::<Vec<_>>()).collect::<Vec<_>>();
+    let shortest = stmts.iter().map(Vec::len).min()?;
+
+    // Statements are hoisted as long as they are the same in all arms and
+    // don't use the bindings of the arms. `let`s are never hoisted, their
+    // bindings would be visible after the match.
+    let can_hoist = |idx: &dyn Fn(&[ast::Stmt]) -> usize| {
+        let first = &stmts[0][idx(&stmts[0])];
+        if let ast::Stmt::LetStmt(_) = first {
+            return false;
+        }
+        arms.iter().zip(&stmts).all(|(arm, stmts)| {
+            let stmt = &stmts[idx(stmts)];
+            stmt.syntax().text() == first.syntax().text() && !uses_arm_bindings(ctx.sema, arm, stmt)
+        })
+    };
+
+    // Hoisted code runs before the scrutinee and the guards are evaluated,
+    // so it must not touch the locals they use.
+    let mut prefix_len = 0;
+    if arms.iter().all(|arm| arm.guard().is_none()) {
+        let scrutinee_locals = match_expr
+            .expr()
+            .map(|it| locals_used(ctx.sema, it.syntax()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        while prefix_len < shortest
+            && can_hoist(&|_| prefix_len)
+            && !locals_used(ctx.sema, stmts[0][prefix_len].syntax())
+                .any(|it| scrutinee_locals.contains(&it))
+        {
+            prefix_len += 1;
+        }
+    }
+
+    // The arms evaluate to their tail expression, so the hoisted code would
+    // run too late if there is one.
+    let mut suffix_len = 0;
+    if blocks.iter().all(|it| it.expr().is_none()) {
+        while prefix_len + suffix_len < shortest && can_hoist(&|stmts| stmts.len() - 1 - suffix_len)
+        {
+            suffix_len += 1;
+        }
+    }
+
+    if prefix_len == 0 && suffix_len == 0 {
+        return None;
+    }
+
+    let indent = leading_indent(&anchor).unwrap_or_default();
+    let prefix = &stmts[0][..prefix_len];
+    let suffix = &stmts[0][stmts[0].len() - suffix_len..];
+    ctx.add_assist(
+        AssistId("hoist_common_match_arm_code"),
+        "Hoist duplicated code out of match",
+        |edit| {
+            edit.target(match_expr.syntax().text_range());
+            let mut prefix_text = String::new();
+            if !prefix.is_empty() {
+                prefix_text = format!("{}\n{}", hoisted_text(prefix, &indent), indent);
+                edit.insert(anchor.text_range().start(), prefix_text.clone());
+            }
+            if !suffix.is_empty() {
+                let suffix_text = format!("\n{}{}", indent, hoisted_text(suffix, &indent));
+                edit.insert(anchor.text_range().end(), suffix_text);
+            }
+            for (block, stmts) in blocks.iter().zip(&stmts) {
+                for range in removed_ranges(block, stmts, prefix_len, suffix_len) {
+                    edit.delete(range);
+                }
+            }
+            edit.set_cursor(
+                match_expr.syntax().text_range().start() + TextUnit::of_str(&prefix_text),
+            );
+        },
+    )
+}
+
+/// Whether `stmt` uses a local bound by the pattern of `arm`, or anywhere
+/// else in the arm outside of `stmt`.
+fn uses_arm_bindings(
+    sema: &Semantics<RootDatabase>,
+    arm: &ast::MatchArm,
+    stmt: &ast::Stmt,
+) -> bool {
+    let stmt_range = stmt.syntax().text_range();
+    let arm_locals = arm
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter(|it| !it.syntax().text_range().is_subrange(&stmt_range))
+        .filter_map(|it| sema.to_def(&it))
+        .collect::<Vec<_>>();
+    locals_used(sema, stmt.syntax()).any(|it| arm_locals.contains(&it))
+}
+
+fn locals_used<'a>(
+    sema: &'a Semantics<RootDatabase>,
+    node: &SyntaxNode,
+) -> impl Iterator<Item = hir::Local> + 'a {
+    node.descendants().filter_map(ast::PathExpr::cast).filter_map(move |it| {
+        match sema.resolve_path(&it.path()?)? {
+            PathResolution::Local(local) => Some(local),
+            _ => None,
+        }
+    })
+}
+
+fn hoisted_text(stmts: &[ast::Stmt], indent: &str) -> String {
+    stmts
+        .iter()
+        .map(|stmt| {
+            // Statements spanning several lines are indented for the arm.
+            let arm_indent = leading_indent(stmt.syntax()).unwrap_or_default();
+            stmt.syntax()
+                .text()
+                .to_string()
+                .lines()
+                .map(|line| {
+                    if line.starts_with(arm_indent.as_str()) {
+                        &line[arm_indent.len()..]
+                    } else {
+                        line
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(&format!("\n{}", indent))
+        })
+        .collect::<Vec<_>>()
+        .join(&format!("\n{}", indent))
+}
+
+fn removed_ranges(
+    block: &ast::Block,
+    stmts: &[ast::Stmt],
+    prefix_len: usize,
+    suffix_len: usize,
+) -> Vec<TextRange> {
+    let range = |stmt: &ast::Stmt| stmt.syntax().text_range();
+    if prefix_len + suffix_len == stmts.len() && block.expr().is_none() {
+        let l_curly = block.l_curly_token().map(|it| it.syntax().text_range().end());
+        let r_curly = block.r_curly_token().map(|it| it.syntax().text_range().start());
+        return match (l_curly, r_curly) {
+            (Some(start), Some(end)) => vec![TextRange::from_to(start, end)],
+            _ => Vec::new(),
+        };
+    }
+    let mut res = Vec::new();
+    if prefix_len > 0 {
+        let end = match stmts.get(prefix_len) {
+            Some(stmt) => range(stmt).start(),
+            None => block
+                .expr()
+                .map_or(range(&stmts[prefix_len - 1]).end(), |it| it.syntax().text_range().start()),
+        };
+        res.push(TextRange::from_to(range(&stmts[0]).start(), end));
+    }
+    if suffix_len > 0 {
+        let last_kept = &stmts[stmts.len() - suffix_len - 1];
+        res.push(TextRange::from_to(range(last_kept).end(), range(&stmts[stmts.len() - 1]).end()));
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn hoist_common_prefix_and_suffix() {
+        check_assist(
+            hoist_common_match_arm_code,
+            r"
+fn foo(x: Option<u32>) {
+    <|>match x {
+        Some(n) => {
+            log(1);
+            bar(n);
+            done();
+        }
+        None => {
+            log(1);
+            baz();
+            done();
+        }
+    }
+}",
+            r"
+fn foo(x: Option<u32>) {
+    log(1);
+    <|>match x {
+        Some(n) => {
+            bar(n);
+        }
+        None => {
+            baz();
+        }
+    }
+    done();
+}",
+        );
+    }
+
+    #[test]
+    fn hoist_whole_arms_of_statement() {
+        check_assist(
+            hoist_common_match_arm_code,
+            r"
+fn foo(x: bool) {
+    <|>match x {
+        true => { a(); }
+        false => { a(); }
+    };
+    b();
+}",
+            r"
+fn foo(x: bool) {
+    a();
+    <|>match x {
+        true => {}
+        false => {}
+    };
+    b();
+}",
+        );
+    }
+
+    #[test]
+    fn hoist_code_using_outer_bindings() {
+        check_assist(
+            hoist_common_match_arm_code,
+            r"
+fn foo(x: Option<u32>, n: u32) {
+    <|>match x {
+        Some(m) => {
+            log(n);
+            bar(m);
+        }
+        None => {
+            log(n);
+            baz();
+        }
+    }
+}",
+            r"
+fn foo(x: Option<u32>, n: u32) {
+    log(n);
+    <|>match x {
+        Some(m) => {
+            bar(m);
+        }
+        None => {
+            baz();
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn hoist_suffix_after_arm_locals() {
+        check_assist(
+            hoist_common_match_arm_code,
+            r"
+fn foo(x: bool) {
+    <|>match x {
+        true => {
+            let y = 1;
+            use_it(y);
+            done();
+        }
+        false => {
+            let y = 2;
+            use_it(y);
+            done();
+        }
+    }
+}",
+            r"
+fn foo(x: bool) {
+    <|>match x {
+        true => {
+            let y = 1;
+            use_it(y);
+        }
+        false => {
+            let y = 2;
+            use_it(y);
+        }
+    }
+    done();
+}",
+        );
+    }
+
+    #[test]
+    fn hoist_common_code_not_applicable() {
+        // Uses the binding of the arm
+        check_assist_not_applicable(
+            hoist_common_match_arm_code,
+            r"
+fn foo(x: Option<u32>) {
+    <|>match x {
+        Some(n) => { bar(n); }
+        n => { bar(n); }
+    }
+}",
+        );
+        // The value of the match is used
+        check_assist_not_applicable(
+            hoist_common_match_arm_code,
+            "fn foo(x: bool) { let y = <|>match x { true => { a(); 1 } false => { a(); 2 } }; }",
+        );
+        // Runs before the guard
+        check_assist_not_applicable(
+            hoist_common_match_arm_code,
+            "fn foo(x: u32) { <|>match x { 0 if ok() => { a(); b() } _ => { a(); c() } } }",
+        );
+        // Changes the scrutinee
+        check_assist_not_applicable(
+            hoist_common_match_arm_code,
+            "fn foo(mut x: u32) { <|>match x { 0 => { x = 1; b() } _ => { x = 1; c() } } }",
+        );
+        // `let`s stay in the arms
+        check_assist_not_applicable(
+            hoist_common_match_arm_code,
+            "fn foo(x: bool) { <|>match x { true => { let y = 1; } false => { let y = 1; } } }",
+        );
+    }
+
+    #[test]
+    fn hoist_common_code_target() {
+        check_assist_target(
+            hoist_common_match_arm_code,
+            "fn foo(x: bool) { <|>match x { true => { a(); } false => { a(); } } }",
+            "match x { true => { a(); } false => { a(); } }",
+        );
+    }
+}
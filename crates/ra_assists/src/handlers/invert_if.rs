@@ -34,11 +34,12 @@ pub(crate) fn invert_if(ctx: AssistCtx) -> Option<Assist> {
     }
 
     // This assist should not apply for if-let.
-    if expr.condition()?.pat().is_some() {
+    let condition = expr.condition()?;
+    if condition.pat().is_some() || condition.is_let_chain() {
         return None;
     }
 
-    let cond = expr.condition()?.expr()?;
+    let cond = condition.expr()?;
     let then_node = expr.then_branch()?.syntax().clone();
 
     if let ast::ElseBranch::Block(else_block) = expr.else_branch()? {
@@ -103,4 +104,12 @@ mod tests {
             "fn f() { i<|>f let Some(_) = Some(1) { 1 } else { 0 } }",
         )
     }
+
+    #[test]
+    fn invert_if_doesnt_apply_with_let_chain() {
+        check_assist_not_applicable(
+            invert_if,
+            "fn f() { i<|>f let Some(x) = Some(1) && x > 0 { 1 } else { 0 } }",
+        )
+    }
 }
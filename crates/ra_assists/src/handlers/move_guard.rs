@@ -102,7 +102,7 @@ pub(crate) fn move_arm_cond_to_match_guard(ctx: AssistCtx) -> Option<Assist> {
         return None;
     }
     // Not support moving if let to arm guard
-    if cond.pat().is_some() {
+    if cond.pat().is_some() || cond.is_let_chain() {
         return None;
     }
 
@@ -0,0 +1,428 @@
+use hir::Adt;
+use ra_syntax::{
+    ast::{
+        self, edit::IndentLevel, ArgListOwner, AstNode, AstToken, NameOwner, TypeAscriptionOwner,
+        TypeBoundsOwner, TypeParamsOwner, VisibilityOwner,
+    },
+    SyntaxNode, TextRange, TextUnit,
+};
+use stdx::format_to;
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: extract_boxed_fn_type_alias
+//
+// Extracts the type of a `Box<dyn Fn(..)>` field into a type alias, and uses
+// the alias wherever the same type is written in the module.
+//
+// ```
+// struct Button {
+//     on_click: <|>Box<dyn Fn(u32)>,
+// }
+//
+// fn set_handler(button: &mut Button, handler: Box<dyn Fn(u32)>) {}
+// ```
+// ->
+// ```
+// type OnClick = Box<dyn Fn(u32)>;
+//
+// struct Button {
+//     on_click: OnClick,
+// }
+//
+// fn set_handler(button: &mut Button, handler: OnClick) {}
+// ```
+pub(crate) fn extract_boxed_fn_type_alias(ctx: AssistCtx) -> Option<Assist> {
+    let field = BoxedFnField::at_cursor(&ctx)?;
+    let alias_name = to_camel_case(field.field.name()?.text());
+    let box_text = field.box_type.syntax().text().to_string();
+
+    ctx.add_assist(
+        AssistId("extract_boxed_fn_type_alias"),
+        format!("Extract `{}` into a type alias", box_text),
+        |edit| {
+            edit.target(field.box_type.syntax().text_range());
+
+            // The alias is only visible in the module of the struct.
+            let module = containing_module(field.strukt.syntax());
+            let mut edits = field
+                .source_file()
+                .descendants()
+                .filter_map(ast::PathType::cast)
+                .filter(|it| it.syntax().text() == box_text.as_str())
+                .filter(|it| containing_module(it.syntax()) == module)
+                .map(|it| (it.syntax().text_range(), alias_name.clone()))
+                .collect::<Vec<_>>();
+
+            let insert_offset = field.strukt.syntax().text_range().start();
+            let indent = IndentLevel::from_node(field.strukt.syntax());
+            let cursor = shift_offset(insert_offset, &edits);
+            let mut buf = String::new();
+            if let Some(vis) = field.strukt.visibility() {
+                format_to!(buf, "{} ", vis.syntax());
+            }
+            format_to!(buf, "type {} = {};\n\n", alias_name, box_text);
+            buf.push_str(&"    ".repeat(indent.0 as usize));
+            edits.push((TextRange::offset_len(insert_offset, 0.into()), buf));
+
+            for (range, text) in edits {
+                edit.replace(range, text);
+            }
+            edit.set_cursor(cursor);
+        },
+    )
+}
+
+// Assist: make_boxed_fn_field_generic
+//
+// Replaces a `Box<dyn Fn(..)>` field with a new type parameter of the struct,
+// and updates the impls of the struct.
+//
+// ```
+// struct Button {
+//     on_click: <|>Box<dyn Fn(u32)>,
+// }
+//
+// impl Button {
+//     fn click(&self) { (self.on_click)(1) }
+// }
+//
+// fn main() {
+//     let button = Button { on_click: Box::new(|_| ()) };
+// }
+// ```
+// ->
+// ```
+// struct Button<F: Fn(u32)> {
+//     on_click: F,
+// }
+//
+// impl<F: Fn(u32)> Button<F> {
+//     fn click(&self) { (self.on_click)(1) }
+// }
+//
+// fn main() {
+//     let button = Button { on_click: |_| () };
+// }
+// ```
+pub(crate) fn make_boxed_fn_field_generic(ctx: AssistCtx) -> Option<Assist> {
+    let field = BoxedFnField::at_cursor(&ctx)?;
+    let strukt = ctx.sema.to_def(&field.strukt)?;
+    let field_def = ctx.sema.to_def(&field.field)?;
+    let bounds = field.dyn_type.type_bound_list()?.syntax().text().to_string();
+
+    let type_params = field.strukt.type_param_list();
+    let param_name = fresh_type_param_name(type_params.as_ref());
+    let param = format!("{}: {}", param_name, bounds);
+
+    let mut edits = Vec::new();
+    edits.push(add_generic_param(
+        type_params.as_ref(),
+        field.strukt.name()?.syntax().text_range().end(),
+        &param,
+    )?);
+    edits.push((field.box_type.syntax().text_range(), param_name.clone()));
+
+    let source_file = field.source_file();
+    for impl_def in source_file.descendants().filter_map(ast::ImplDef::cast) {
+        let is_impl_of_struct = ctx
+            .sema
+            .to_def(&impl_def)
+            .map_or(false, |it| it.target_ty(ctx.db).as_adt() == Some(Adt::Struct(strukt)));
+        if !is_impl_of_struct {
+            continue;
+        }
+        let target_segment = match impl_def.target_type()? {
+            ast::TypeRef::PathType(it) => it.path()?.segment()?,
+            _ => return None,
+        };
+        let impl_kw_end = impl_def.impl_kw_token()?.syntax().text_range().end();
+        edits.push(add_generic_param(impl_def.type_param_list().as_ref(), impl_kw_end, &param)?);
+        edits.push(add_generic_arg(&target_segment, &param_name)?);
+    }
+
+    for record_field in source_file.descendants().filter_map(ast::RecordField::cast) {
+        match ctx.sema.resolve_record_field(&record_field) {
+            Some((it, _)) if it == field_def => (),
+            _ => continue,
+        }
+        if let Some((range, inner)) = record_field.expr().and_then(unwrap_box_new) {
+            edits.push((range, inner));
+        }
+    }
+
+    let struct_name = field.strukt.name()?.text().clone();
+    ctx.add_assist(
+        AssistId("make_boxed_fn_field_generic"),
+        format!("Make `{}` generic over the field type", struct_name),
+        |edit| {
+            edit.target(field.box_type.syntax().text_range());
+            let cursor = shift_offset(field.strukt.syntax().text_range().start(), &edits);
+            for (range, text) in edits {
+                edit.replace(range, text);
+            }
+            edit.set_cursor(cursor);
+        },
+    )
+}
+
+struct BoxedFnField {
+    strukt: ast::StructDef,
+    field: ast::RecordFieldDef,
+    box_type: ast::PathType,
+    dyn_type: ast::DynTraitType,
+}
+
+impl BoxedFnField {
+    fn at_cursor(ctx: &AssistCtx) -> Option<BoxedFnField> {
+        let field = ctx.find_node_at_offset::<ast::RecordFieldDef>()?;
+        let strukt = field.syntax().ancestors().find_map(ast::StructDef::cast)?;
+        let box_type = match field.ascribed_type()? {
+            ast::TypeRef::PathType(it) => it,
+            _ => return None,
+        };
+        let box_segment = box_type.path()?.segment()?;
+        if box_segment.name_ref()?.text() != "Box" {
+            return None;
+        }
+        let mut type_args = box_segment.type_arg_list()?.type_args();
+        let dyn_type = match type_args.next()?.type_ref()? {
+            ast::TypeRef::DynTraitType(it) => it,
+            _ => return None,
+        };
+        if type_args.next().is_some() {
+            return None;
+        }
+
+        let is_fn_trait = dyn_type.type_bound_list()?.bounds().any(|bound| {
+            let segment = match bound.type_ref() {
+                Some(ast::TypeRef::PathType(it)) => it.path().and_then(|it| it.segment()),
+                _ => None,
+            };
+            segment.map_or(false, |it| {
+                it.param_list().is_some()
+                    && it
+                        .name_ref()
+                        .map_or(false, |it| matches!(it.text().as_str(), "Fn" | "FnMut" | "FnOnce"))
+            })
+        });
+        if !is_fn_trait {
+            return None;
+        }
+        Some(BoxedFnField { strukt, field, box_type, dyn_type })
+    }
+
+    fn source_file(&self) -> SyntaxNode {
+        self.strukt.syntax().ancestors().last().unwrap()
+    }
+}
+
+fn containing_module(node: &SyntaxNode) -> Option<ast::Module> {
+    node.ancestors().find_map(ast::Module::cast)
+}
+
+/// Adds `param` to `type_params`, or creates a parameter list at `offset`.
+fn add_generic_param(
+    type_params: Option<&ast::TypeParamList>,
+    offset: TextUnit,
+    param: &str,
+) -> Option<(TextRange, String)> {
+    let edit = match type_params {
+        Some(type_params) => {
+            let r_angle = type_params.r_angle_token()?.syntax().text_range().start();
+            let text = if type_params.generic_params().next().is_some() {
+                format!(", {}", param)
+            } else {
+                param.to_string()
+            };
+            (TextRange::offset_len(r_angle, 0.into()), text)
+        }
+        None => (TextRange::offset_len(offset, 0.into()), format!("<{}>", param)),
+    };
+    Some(edit)
+}
+
+fn add_generic_arg(segment: &ast::PathSegment, arg: &str) -> Option<(TextRange, String)> {
+    let edit = match segment.type_arg_list() {
+        Some(type_args) => {
+            let r_angle = type_args.r_angle_token()?.syntax().text_range().start();
+            (TextRange::offset_len(r_angle, 0.into()), format!(", {}", arg))
+        }
+        None => {
+            let end = segment.syntax().text_range().end();
+            (TextRange::offset_len(end, 0.into()), format!("<{}>", arg))
+        }
+    };
+    Some(edit)
+}
+
+/// Turns `Box::new(expr)` into `expr`.
+fn unwrap_box_new(expr: ast::Expr) -> Option<(TextRange, String)> {
+    let call = match &expr {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    let callee = match call.expr()? {
+        ast::Expr::PathExpr(it) => it,
+        _ => return None,
+    };
+    if callee.syntax().text() != "Box::new" {
+        return None;
+    }
+    let mut args = call.arg_list()?.args();
+    let inner = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some((expr.syntax().text_range(), inner.syntax().text().to_string()))
+}
+
+fn fresh_type_param_name(type_params: Option<&ast::TypeParamList>) -> String {
+    let existing = type_params
+        .into_iter()
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let mut name = "F".to_string();
+    let mut idx = 0;
+    while existing.contains(&name) {
+        idx += 1;
+        name = format!("F{}", idx);
+    }
+    name
+}
+
+/// Maps `offset` through the `edits` which come before it.
+fn shift_offset(offset: TextUnit, edits: &[(TextRange, String)]) -> TextUnit {
+    let mut res = offset;
+    for (range, text) in edits {
+        if range.end() <= offset && range.start() < offset {
+            res = res + TextUnit::of_str(text) - range.len();
+        }
+    }
+    res
+}
+
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|it| !it.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| {
+                first.to_uppercase().chain(chars).collect::<String>()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn extract_alias_replaces_all_uses() {
+        check_assist(
+            extract_boxed_fn_type_alias,
+            r"
+fn take(f: Box<dyn FnMut() -> u8 + Send>) {}
+
+mod m {
+    fn take(f: Box<dyn FnMut() -> u8 + Send>) {}
+
+    pub struct S {
+        on_event: <|>Box<dyn FnMut() -> u8 + Send>,
+        other: Box<dyn Fn()>,
+    }
+}
+",
+            r"
+fn take(f: Box<dyn FnMut() -> u8 + Send>) {}
+
+mod m {
+    fn take(f: OnEvent) {}
+
+    <|>pub type OnEvent = Box<dyn FnMut() -> u8 + Send>;
+
+    pub struct S {
+        on_event: OnEvent,
+        other: Box<dyn Fn()>,
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn extract_alias_target() {
+        check_assist_target(
+            extract_boxed_fn_type_alias,
+            "struct S { f: Box<dyn <|>Fn(u32)> }",
+            "Box<dyn Fn(u32)>",
+        )
+    }
+
+    #[test]
+    fn make_generic_with_existing_params() {
+        check_assist(
+            make_boxed_fn_field_generic,
+            r"
+struct S<'a, F> {
+    f: &'a F,
+    g: Box<dyn <|>FnOnce(&F) + 'static>,
+}
+
+impl<'a, F> S<'a, F> {}
+
+impl<F> Drop for S<'static, F> {
+    fn drop(&mut self) {}
+}
+",
+            r"
+<|>struct S<'a, F, F1: FnOnce(&F) + 'static> {
+    f: &'a F,
+    g: F1,
+}
+
+impl<'a, F, F1: FnOnce(&F) + 'static> S<'a, F, F1> {}
+
+impl<F, F1: FnOnce(&F) + 'static> Drop for S<'static, F, F1> {
+    fn drop(&mut self) {}
+}
+",
+        )
+    }
+
+    #[test]
+    fn make_generic_unwraps_box_new() {
+        check_assist(
+            make_boxed_fn_field_generic,
+            r"
+fn main() {
+    let s = S { f: Box::new(|| 92) };
+}
+
+struct S { f: <|>Box<dyn Fn() -> i32> }
+",
+            r"
+fn main() {
+    let s = S { f: || 92 };
+}
+
+<|>struct S<F: Fn() -> i32> { f: F }
+",
+        )
+    }
+
+    #[test]
+    fn not_applicable_to_other_boxes() {
+        check_assist_not_applicable(extract_boxed_fn_type_alias, "struct S { f: <|>Box<u32> }");
+        check_assist_not_applicable(
+            make_boxed_fn_field_generic,
+            "struct S { f: <|>Box<dyn Iterator<Item = u32>> }",
+        );
+        check_assist_not_applicable(make_boxed_fn_field_generic, "struct S { f: <|>fn() }");
+    }
+}
@@ -0,0 +1,123 @@
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeBoundsOwner, TypeParamsOwner},
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: replace_impl_trait_with_generic
+//
+// Replaces an `impl Trait` argument with a new type parameter of the function.
+//
+// ```
+// fn print(value: <|>impl Display) {}
+// ```
+// ->
+// ```
+// fn print<T: Display>(value: T) {}
+// ```
+pub(crate) fn replace_impl_trait_with_generic(ctx: AssistCtx) -> Option<Assist> {
+    let impl_trait = ctx.find_node_at_offset::<ast::ImplTraitType>()?;
+    let param = impl_trait.syntax().ancestors().find_map(ast::Param::cast)?;
+    let fn_def = param.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    // `impl Trait` in the arguments of an `fn(..)` type is not allowed.
+    if param.syntax().parent()?.parent()? != *fn_def.syntax() {
+        return None;
+    }
+    let bounds = impl_trait.type_bound_list()?.syntax().text().to_string();
+
+    let type_params = fn_def.type_param_list();
+    let name = fresh_type_param_name(type_params.as_ref());
+    let new_param = format!("{}: {}", name, bounds);
+    let (param_offset, param_text) = match &type_params {
+        Some(type_params) => {
+            let r_angle = type_params.r_angle_token()?.syntax().text_range().start();
+            if type_params.generic_params().next().is_some() {
+                (r_angle, format!(", {}", new_param))
+            } else {
+                (r_angle, new_param)
+            }
+        }
+        None => (fn_def.name()?.syntax().text_range().end(), format!("<{}>", new_param)),
+    };
+
+    ctx.add_assist(
+        AssistId("replace_impl_trait_with_generic"),
+        format!("Replace `impl {}` with a type parameter", bounds),
+        |edit| {
+            let range = impl_trait.syntax().text_range();
+            edit.target(range);
+            edit.insert(param_offset, param_text.clone());
+            edit.replace(range, name.clone());
+            let shift = TextUnit::of_str(&param_text);
+            edit.set_cursor(range.start() + shift);
+        },
+    )
+}
+
+fn fresh_type_param_name(type_params: Option<&ast::TypeParamList>) -> String {
+    let existing = type_params
+        .into_iter()
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let mut name = "T".to_string();
+    let mut idx = 0;
+    while existing.contains(&name) {
+        idx += 1;
+        name = format!("T{}", idx);
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn replace_impl_trait_without_type_params() {
+        check_assist(
+            replace_impl_trait_with_generic,
+            "fn foo(x: <|>impl Iterator<Item = u32> + Clone) {}",
+            "fn foo<T: Iterator<Item = u32> + Clone>(x: <|>T) {}",
+        );
+    }
+
+    #[test]
+    fn replace_impl_trait_with_existing_type_params() {
+        check_assist(
+            replace_impl_trait_with_generic,
+            "fn foo<'a, T>(t: T, x: &'a impl <|>Display) {}",
+            "fn foo<'a, T, T1: Display>(t: T, x: &'a <|>T1) {}",
+        );
+        check_assist(
+            replace_impl_trait_with_generic,
+            "trait Foo { fn foo<>(&self, x: <|>impl Display); }",
+            "trait Foo { fn foo<T: Display>(&self, x: <|>T); }",
+        );
+    }
+
+    #[test]
+    fn replace_impl_trait_not_applicable() {
+        check_assist_not_applicable(
+            replace_impl_trait_with_generic,
+            "fn foo() -> <|>impl Display {}",
+        );
+        check_assist_not_applicable(
+            replace_impl_trait_with_generic,
+            "fn foo(f: fn(<|>impl Display)) {}",
+        );
+    }
+
+    #[test]
+    fn replace_impl_trait_target() {
+        check_assist_target(
+            replace_impl_trait_with_generic,
+            "fn foo(x: <|>impl Display) {}",
+            "impl Display",
+        );
+    }
+}
@@ -0,0 +1,423 @@
+use hir::Mutability;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, TypeAscriptionOwner},
+    AstNode, SyntaxNode, TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: replace_index_with_get
+//
+// Replaces indexing, which panics when out of bounds, with a call to `get`.
+// The missing element is handled with `?` in functions returning an `Option`,
+// with `if let` when the element is bound by a `let`, and with `unwrap_or`
+// otherwise.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// #[lang = "slice"]
+// impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+// struct Point { x: i32 }
+// fn first_x(points: &[Point]) -> Option<i32> {
+//     Some(points[<|>0].x)
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// #[lang = "slice"]
+// impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+// struct Point { x: i32 }
+// fn first_x(points: &[Point]) -> Option<i32> {
+//     Some(points.get(0)?.x)
+// }
+// ```
+pub(crate) fn replace_index_with_get(ctx: AssistCtx) -> Option<Assist> {
+    let index_expr = ctx.find_node_at_offset::<ast::IndexExpr>()?;
+    let base = index_expr.base()?;
+    let index = index_expr.index()?;
+    if is_assignee(index_expr.syntax()) {
+        return None;
+    }
+
+    let usage = usage_of(&ctx, &index_expr)?;
+    let method = if usage.mutability() == Mutability::Mut { "get_mut" } else { "get" };
+    if !has_method(&ctx, &base, method) {
+        return None;
+    }
+    let call = format!("{}.{}({})", base.syntax(), method, index.syntax());
+    let replaced = match &usage {
+        Usage::Ref(ref_expr, _) => ref_expr.syntax().clone(),
+        Usage::Place(_) | Usage::Value => index_expr.syntax().clone(),
+    };
+
+    let strategy = if returns_option(&replaced) {
+        Strategy::Try
+    } else if let Some(strategy) = if_let_strategy(&replaced, &usage) {
+        strategy
+    } else {
+        unwrap_or_strategy(&ctx, &index_expr, &usage)?
+    };
+
+    let label = match &strategy {
+        Strategy::Try => "Replace index with `get` and `?`",
+        Strategy::IfLet { .. } => "Replace index with `get` and `if let`",
+        Strategy::UnwrapOr(_) => "Replace index with `get` and `unwrap_or`",
+    };
+    ctx.add_assist(AssistId("replace_index_with_get"), label, |edit| {
+        edit.target(index_expr.syntax().text_range());
+        match strategy {
+            Strategy::Try => {
+                let deref = if let Usage::Value = usage { "*" } else { "" };
+                edit.replace(replaced.text_range(), format!("{}{}?", deref, call));
+                edit.set_cursor(replaced.text_range().start());
+            }
+            Strategy::IfLet { range, pat, body } => {
+                edit.replace(range, format!("if let Some({}) = {} {}", pat, call, body));
+                edit.set_cursor(range.start());
+            }
+            Strategy::UnwrapOr(default) => {
+                let replacement = match usage {
+                    Usage::Value => format!("{}.copied().unwrap_or({})", call, default),
+                    _ => format!("{}.unwrap_or(&{})", call, default),
+                };
+                edit.replace(replaced.text_range(), replacement);
+                edit.set_cursor(replaced.text_range().start());
+            }
+        }
+    })
+}
+
+/// How the indexed element is used.
+enum Usage {
+    /// `&v[i]` or `&mut v[i]`, which is replaced as a whole.
+    Ref(ast::RefExpr, Mutability),
+    /// The receiver of a method call or a field access, which dereference the
+    /// reference returned by `get` automatically.
+    Place(Mutability),
+    /// The element is copied out.
+    Value,
+}
+
+impl Usage {
+    fn mutability(&self) -> Mutability {
+        match self {
+            Usage::Ref(_, mutability) | Usage::Place(mutability) => *mutability,
+            Usage::Value => Mutability::Shared,
+        }
+    }
+}
+
+enum Strategy {
+    Try,
+    /// Replaces `range`, the `let` and the rest of its block, by an `if let`.
+    IfLet {
+        range: TextRange,
+        pat: String,
+        body: String,
+    },
+    /// Falls back to a default value of the element type.
+    UnwrapOr(&'static str),
+}
+
+fn usage_of(ctx: &AssistCtx, index_expr: &ast::IndexExpr) -> Option<Usage> {
+    let parent = index_expr.syntax().parent()?;
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        if ref_expr.raw_kw_token().is_some() {
+            return None;
+        }
+        let mutability =
+            if ref_expr.mut_kw_token().is_some() { Mutability::Mut } else { Mutability::Shared };
+        return Some(Usage::Ref(ref_expr, mutability));
+    }
+    let is_receiver = parent.first_child().as_ref() == Some(index_expr.syntax());
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()).filter(|_| is_receiver) {
+        // The receiver is only borrowed mutably if the method takes
+        // `&mut self`.
+        let adjustments = ctx.sema.method_receiver_adjustments(&call)?;
+        return Some(Usage::Place(adjustments.autoref.unwrap_or(Mutability::Shared)));
+    }
+    if let Some(field) = ast::FieldExpr::cast(parent).filter(|_| is_receiver) {
+        let mutability =
+            if is_mutated(field.syntax()) { Mutability::Mut } else { Mutability::Shared };
+        return Some(Usage::Place(mutability));
+    }
+    Some(Usage::Value)
+}
+
+fn is_assignee(expr: &SyntaxNode) -> bool {
+    let bin_expr = match expr.parent().and_then(ast::BinExpr::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    bin_expr.lhs().map_or(false, |lhs| lhs.syntax() == expr)
+        && bin_expr.op_kind().map_or(false, |op| op.is_assignment())
+}
+
+fn is_mutated(expr: &SyntaxNode) -> bool {
+    is_assignee(expr)
+        || expr
+            .parent()
+            .and_then(ast::RefExpr::cast)
+            .map_or(false, |ref_expr| ref_expr.mut_kw_token().is_some())
+}
+
+fn has_method(ctx: &AssistCtx, base: &ast::Expr, method: &str) -> bool {
+    let has_method = || {
+        let ty = ctx.sema.type_of_expr(base)?;
+        let scope = ctx.sema.scope(base.syntax());
+        let krate = scope.module()?.krate();
+        ty.iterate_method_candidates(ctx.db, krate, &scope.traits_in_scope(), None, |_, f| {
+            if f.name(ctx.db).to_string() == method {
+                Some(())
+            } else {
+                None
+            }
+        })
+    };
+    has_method().is_some()
+}
+
+/// Whether `?` can be used, because the enclosing function returns an
+/// `Option`.
+fn returns_option(expr: &SyntaxNode) -> bool {
+    // `?` in a closure returns from the closure.
+    let fn_def = expr
+        .ancestors()
+        .find(|it| ast::FnDef::can_cast(it.kind()) || ast::LambdaExpr::can_cast(it.kind()))
+        .and_then(ast::FnDef::cast);
+    let fn_def = match fn_def {
+        Some(it) => it,
+        None => return false,
+    };
+    let path = match fn_def.ret_type().and_then(|it| it.type_ref()) {
+        Some(ast::TypeRef::PathType(it)) => it.path(),
+        _ => None,
+    };
+    path.and_then(|it| it.segment())
+        .and_then(|it| it.name_ref())
+        .map_or(false, |it| it.text() == "Option")
+}
+
+/// Moves the rest of the block into an `if let`, if the element is bound by a
+/// `let` in a block without a tail expression.
+fn if_let_strategy(replaced: &SyntaxNode, usage: &Usage) -> Option<Strategy> {
+    let let_stmt = replaced.parent().and_then(ast::LetStmt::cast)?;
+    if let_stmt.ascribed_type().is_some() {
+        return None;
+    }
+    let bind_pat = match let_stmt.pat()? {
+        ast::Pat::BindPat(it) => it,
+        _ => return None,
+    };
+    let pat = match usage {
+        Usage::Value if bind_pat.mut_kw_token().is_some() => return None,
+        Usage::Value => format!("&{}", bind_pat.syntax()),
+        _ => bind_pat.syntax().to_string(),
+    };
+    let block = let_stmt.syntax().parent().and_then(ast::Block::cast)?;
+    if block.expr().is_some() {
+        return None;
+    }
+
+    let let_range = let_stmt.syntax().text_range();
+    let last_stmt = block.statements().last()?;
+    let end = last_stmt.syntax().text_range().end();
+    let body = if last_stmt.syntax() == let_stmt.syntax() {
+        "{}".to_string()
+    } else {
+        let block_start = block.syntax().text_range().start();
+        let block_text = block.syntax().to_string();
+        let rest =
+            &block_text[(let_range.end() - block_start).to_usize()..(end - block_start).to_usize()];
+        let indent = " ".repeat(IndentLevel::from_node(let_stmt.syntax()).0 as usize * 4);
+        format!("{{{}\n{}}}", rest.replace('\n', "\n    "), indent)
+    };
+    let range = TextRange::from_to(let_range.start(), end);
+    Some(Strategy::IfLet { range, pat, body })
+}
+
+/// Falls back to the default value of numbers and booleans.
+fn unwrap_or_strategy(
+    ctx: &AssistCtx,
+    index_expr: &ast::IndexExpr,
+    usage: &Usage,
+) -> Option<Strategy> {
+    match usage {
+        Usage::Value | Usage::Ref(_, Mutability::Shared) => (),
+        _ => return None,
+    }
+    let ty = ctx.sema.type_of_expr(&ast::Expr::IndexExpr(index_expr.clone()))?;
+    let default = if ty.is_integral() {
+        "0"
+    } else if ty.is_float() {
+        "0.0"
+    } else if ty.is_bool() {
+        "false"
+    } else {
+        return None;
+    };
+    Some(Strategy::UnwrapOr(default))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn replace_index_with_get_and_try() {
+        check_assist(
+            replace_index_with_get,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+struct S { x: u32 }
+fn f(v: &[S], i: usize) -> Option<u32> {
+    let x = v[i<|>].x;
+    Some(x)
+}"#,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+struct S { x: u32 }
+fn f(v: &[S], i: usize) -> Option<u32> {
+    let x = <|>v.get(i)?.x;
+    Some(x)
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_ref_index_with_get_and_if_let() {
+        check_assist(
+            replace_index_with_get,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+fn f(v: &[String]) {
+    let s = &v[<|>1];
+    foo(s);
+    if true {
+        bar(s);
+    }
+}"#,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+fn f(v: &[String]) {
+    <|>if let Some(s) = v.get(1) {
+        foo(s);
+        if true {
+            bar(s);
+        }
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_mutably_borrowed_index_with_get_mut() {
+        check_assist(
+            replace_index_with_get,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] {
+    fn get(&self, index: usize) -> Option<&T> { loop {} }
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> { loop {} }
+}
+struct S;
+impl S { fn poke(&mut self) {} }
+fn f(v: &mut [S]) -> Option<()> {
+    v<|>[0].poke();
+    None
+}"#,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] {
+    fn get(&self, index: usize) -> Option<&T> { loop {} }
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> { loop {} }
+}
+struct S;
+impl S { fn poke(&mut self) {} }
+fn f(v: &mut [S]) -> Option<()> {
+    <|>v.get_mut(0)?.poke();
+    None
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_index_with_get_and_unwrap_or() {
+        check_assist(
+            replace_index_with_get,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+#[lang = "index"]
+trait Index<Idx> { type Output; }
+impl<T> Index<usize> for [T] { type Output = T; }
+fn f(v: &[u32]) -> u32 {
+    v[<|>0] + 1
+}"#,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+#[lang = "index"]
+trait Index<Idx> { type Output; }
+impl<T> Index<usize> for [T] { type Output = T; }
+fn f(v: &[u32]) -> u32 {
+    <|>v.get(0).copied().unwrap_or(0) + 1
+}"#,
+        );
+    }
+
+    #[test]
+    fn replace_index_with_get_target() {
+        check_assist_target(
+            replace_index_with_get,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+fn f(v: &[u32]) -> Option<&u32> {
+    Some(&v[<|>0])
+}"#,
+            "v[0]",
+        );
+    }
+
+    #[test]
+    fn replace_index_with_get_not_applicable() {
+        // Assignments can't be rewritten.
+        check_assist_not_applicable(
+            replace_index_with_get,
+            r#"
+enum Option<T> { Some(T), None }
+#[lang = "slice"]
+impl<T> [T] { fn get(&self, index: usize) -> Option<&T> { loop {} } }
+fn f(v: &mut [u32]) -> Option<()> {
+    v[<|>0] = 1;
+    None
+}"#,
+        );
+        // There is no `get` method.
+        check_assist_not_applicable(
+            replace_index_with_get,
+            r#"
+enum Option<T> { Some(T), None }
+fn f(v: &[u32]) -> Option<u32> {
+    Some(v[<|>0])
+}"#,
+        );
+    }
+}
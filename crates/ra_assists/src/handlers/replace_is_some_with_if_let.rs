@@ -0,0 +1,301 @@
+use hir::PathResolution;
+use ra_syntax::{ast, AstNode, SmolStr};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: replace_is_some_with_if_let
+//
+// Replaces an `is_some()` or `is_ok()` check whose branch unwraps the value with `if let`.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// fn main() {
+//     let x: Option<i32> = Option::Some(92);
+//     if x.<|>is_some() {
+//         let y = x.unwrap() + 1;
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// fn main() {
+//     let x: Option<i32> = Option::Some(92);
+//     if let Some(x) = x {
+//         let y = x + 1;
+//     }
+// }
+// ```
+pub(crate) fn replace_is_some_with_if_let(ctx: AssistCtx) -> Option<Assist> {
+    let if_expr = ctx.find_node_at_offset::<ast::IfExpr>()?;
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some()
+        || cond.is_let_chain()
+        || ctx.frange.range.start() > cond.syntax().text_range().end()
+    {
+        return None;
+    }
+    let check = match cond.expr()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    if check.arg_list().map_or(false, |it| it.args().next().is_some()) {
+        return None;
+    }
+    let receiver = check.expr()?;
+    let binding = binding_name(&receiver)?;
+
+    // Through a reference, `x.unwrap()` copies the value while the binding
+    // is a reference to it.
+    let ty = ctx.sema.type_of_expr(&receiver)?;
+    let (idx, adt) = ty
+        .autoderef(ctx.db)
+        .enumerate()
+        .find_map(|(idx, it)| Some((idx, it.as_adt()?)))
+        .filter(|&(idx, _)| idx == 0 || (idx == 1 && ty.as_adt().is_none()))?;
+    let is_ref = idx > 0;
+    let variant = match (adt.name(ctx.db).to_string().as_str(), check.name_ref()?.text().as_str()) {
+        ("Option", "is_some") => "Some",
+        ("Result", "is_ok") => "Ok",
+        _ => return None,
+    };
+
+    // Every other use of the value in the branch has to be unwrapped, as it
+    // is moved into the binding.
+    let receiver_text = receiver.syntax().text().to_string();
+    let receiver_def = local_of(&ctx, &receiver);
+    let mut unwraps = Vec::new();
+    for expr in if_expr.then_branch()?.syntax().descendants().filter_map(ast::Expr::cast) {
+        match &expr {
+            ast::Expr::PathExpr(_) | ast::Expr::FieldExpr(_) => (),
+            _ => continue,
+        }
+        if expr.syntax().text().to_string() != receiver_text
+            || local_of(&ctx, &expr) != receiver_def
+        {
+            continue;
+        }
+        let call = expr.syntax().parent().and_then(ast::MethodCallExpr::cast)?;
+        if call.name_ref()?.text() != "unwrap"
+            || call.arg_list().map_or(false, |it| it.args().next().is_some())
+        {
+            return None;
+        }
+        unwraps.push(call);
+    }
+    if unwraps.is_empty() {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("replace_is_some_with_if_let"), "Replace with `if let`", |edit| {
+        let cond_range = cond.syntax().text_range();
+        edit.target(cond_range);
+        edit.replace(cond_range, format!("let {}({}) = {}", variant, binding, receiver_text));
+        for call in unwraps {
+            // Method calls, field accesses and indexing dereference their
+            // receiver automatically.
+            let auto_derefs = call.syntax().parent().map_or(false, |parent| {
+                let kind = parent.kind();
+                let is_receiver = parent.first_child().as_ref() == Some(call.syntax());
+                is_receiver
+                    && (ast::MethodCallExpr::can_cast(kind)
+                        || ast::FieldExpr::can_cast(kind)
+                        || ast::IndexExpr::can_cast(kind))
+            });
+            let replacement =
+                if is_ref && !auto_derefs { format!("*{}", binding) } else { binding.to_string() };
+            edit.replace(call.syntax().text_range(), replacement);
+        }
+        edit.set_cursor(cond_range.start());
+    })
+}
+
+/// The name of the variable or field, which is reused for the binding.
+fn binding_name(receiver: &ast::Expr) -> Option<SmolStr> {
+    let name_ref = match receiver {
+        ast::Expr::PathExpr(it) => {
+            let path = it.path()?;
+            if path.qualifier().is_some() {
+                return None;
+            }
+            path.segment()?.name_ref()?
+        }
+        ast::Expr::FieldExpr(it) => it.name_ref()?,
+        _ => return None,
+    };
+    if name_ref.text() == "self" {
+        return None;
+    }
+    Some(name_ref.text().clone())
+}
+
+fn local_of(ctx: &AssistCtx, expr: &ast::Expr) -> Option<hir::Local> {
+    let path = match expr {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    match ctx.sema.resolve_path(&path)? {
+        PathResolution::Local(local) => Some(local),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn replace_is_some_with_if_let_option() {
+        check_assist(
+            replace_is_some_with_if_let,
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: Option<i32>) {
+    if x.is_some()<|> {
+        foo(x.unwrap());
+        let y = x.unwrap() + 1;
+    } else {
+        x.unwrap();
+    }
+}",
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: Option<i32>) {
+    if <|>let Some(x) = x {
+        foo(x);
+        let y = x + 1;
+    } else {
+        x.unwrap();
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn replace_is_ok_with_if_let_on_field() {
+        check_assist(
+            replace_is_some_with_if_let,
+            r"
+enum Result<T, E> { Ok(T), Err(E) }
+struct S { value: Result<u32, ()> }
+impl S {
+    fn f(&self) {
+        <|>if self.value.is_ok() {
+            self.value.unwrap().count_ones();
+        }
+    }
+}",
+            r"
+enum Result<T, E> { Ok(T), Err(E) }
+struct S { value: Result<u32, ()> }
+impl S {
+    fn f(&self) {
+        if <|>let Ok(value) = self.value {
+            value.count_ones();
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn replace_is_some_with_if_let_through_reference() {
+        check_assist(
+            replace_is_some_with_if_let,
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: &Option<u32>) {
+    if x.is_some<|>() {
+        let y = x.unwrap() * 2;
+        x.unwrap().count_ones();
+    }
+}",
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: &Option<u32>) {
+    if <|>let Some(x) = x {
+        let y = *x * 2;
+        x.count_ones();
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn replace_is_some_with_if_let_not_applicable() {
+        // The value is used without unwrapping it.
+        check_assist_not_applicable(
+            replace_is_some_with_if_let,
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: Option<i32>) {
+    if x.is_some()<|> {
+        foo(x.unwrap());
+        bar(x);
+    }
+}",
+        );
+        // `is_ok` is not a check for `Some`.
+        check_assist_not_applicable(
+            replace_is_some_with_if_let,
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: Option<i32>) {
+    if x.is_ok()<|> {
+        x.unwrap();
+    }
+}",
+        );
+        // Nothing to unwrap.
+        check_assist_not_applicable(
+            replace_is_some_with_if_let,
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: Option<i32>) {
+    if x.is_some()<|> {}
+}",
+        );
+    }
+
+    #[test]
+    fn replace_is_some_with_if_let_ignores_shadowing_variables() {
+        check_assist(
+            replace_is_some_with_if_let,
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: Option<i32>, y: Option<i32>) {
+    if x.is_some()<|> {
+        x.unwrap();
+        let x = y;
+        x;
+    }
+}",
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: Option<i32>, y: Option<i32>) {
+    if <|>let Some(x) = x {
+        x;
+        let x = y;
+        x;
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn replace_is_some_with_if_let_target() {
+        check_assist_target(
+            replace_is_some_with_if_let,
+            r"
+enum Option<T> { Some(T), None }
+fn f(x: Option<i32>) {
+    if x.is_some()<|> {
+        x.unwrap();
+    }
+}",
+            "x.is_some()",
+        );
+    }
+}
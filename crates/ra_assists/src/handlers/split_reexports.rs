@@ -0,0 +1,444 @@
+use std::iter::successors;
+
+use hir::{Module, ModuleDef, PathResolution, ScopeDef, Semantics, Visibility};
+use ra_db::FileId;
+use ra_fmt::leading_indent;
+use ra_ide_db::{defs::Definition, RootDatabase};
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, make, AstNode, AttrsOwner, VisibilityOwner},
+    TextRange,
+};
+use stdx::format_to;
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: split_reexports
+//
+// Splits a list of re-exports into one `use` per target module and visibility,
+// and replaces glob re-exports with the items which are used outside of their
+// module.
+//
+// ```
+// mod fmt {
+//     pub struct Debug;
+//     pub struct Display;
+//     pub struct Unused;
+// }
+// mod io {
+//     pub struct Read;
+// }
+// pub use self::<|>{io::Read, fmt::*};
+//
+// fn main() {
+//     let _ = (Debug, Display);
+// }
+// ```
+// ->
+// ```
+// mod fmt {
+//     pub struct Debug;
+//     pub struct Display;
+//     pub struct Unused;
+// }
+// mod io {
+//     pub struct Read;
+// }
+// pub use self::io::Read;
+// pub use self::fmt::{Debug, Display};
+//
+// fn main() {
+//     let _ = (Debug, Display);
+// }
+// ```
+pub(crate) fn split_reexports(ctx: AssistCtx) -> Option<Assist> {
+    let use_item = ctx.find_node_at_offset::<ast::UseItem>()?;
+    let visibility = use_item.visibility()?;
+    let use_tree = use_item.use_tree()?;
+    let prefix = use_tree.path()?;
+    let use_tree_list = use_tree.use_tree_list()?;
+    let module = ctx.sema.scope(use_item.syntax()).module()?;
+
+    let mut leaves = Vec::new();
+    for tree in use_tree_list.use_trees() {
+        flatten(&tree, &mut Vec::new(), &mut leaves)?;
+    }
+
+    let prefix = prefix.syntax().text().to_string();
+    let use_vis = visibility.syntax().text().to_string();
+    let mut groups: Vec<Group> = Vec::new();
+    // Globs are only expanded when the edit is computed, as finding the used
+    // items searches the whole workspace.
+    let mut globs = Vec::new();
+    for leaf in leaves {
+        let vis = match (&leaf.target, leaf.rel.as_str()) {
+            (Some(target), "*") => {
+                let target_module = resolve_module(&ctx, &use_item, &prefix, target)?;
+                globs.push((leaf.target.clone(), target_module));
+                use_vis.clone()
+            }
+            _ => match resolve_leaf(&ctx, &use_item, &prefix, &leaf) {
+                Some((target_module, def)) => match target_module.visibility_of(ctx.db, &def) {
+                    Some(vis) => narrow_visibility(ctx.db, module, &use_vis, vis),
+                    None => use_vis.clone(),
+                },
+                None => use_vis.clone(),
+            },
+        };
+        add_to_group(&mut groups, vis, &leaf.target, leaf.rel);
+    }
+    if groups.len() < 2 && globs.is_empty() {
+        return None;
+    }
+
+    let range = TextRange::from_to(
+        visibility.syntax().text_range().start(),
+        use_item.syntax().text_range().end(),
+    );
+    let (sema, file_id) = (ctx.sema, ctx.frange.file_id);
+    ctx.add_assist(AssistId("split_reexports"), "Split re-exports", |edit| {
+        for (target, target_module) in globs {
+            let used = used_items(sema, file_id, &use_item, module, target_module, &use_vis);
+            if used.is_empty() {
+                continue;
+            }
+            let mut idx = groups
+                .iter()
+                .position(|it| it.vis == use_vis && it.target == target)
+                .expect("glob was added to a group");
+            groups[idx].entries.retain(|it| it != "*");
+            if groups[idx].entries.is_empty() {
+                groups.remove(idx);
+            } else {
+                idx += 1;
+            }
+            // New groups take the place of the glob.
+            for (vis, entry) in used {
+                match groups.iter_mut().find(|it| it.vis == vis && it.target == target) {
+                    Some(group) => {
+                        if !group.entries.contains(&entry) {
+                            group.entries.push(entry);
+                        }
+                    }
+                    None => {
+                        let group = Group { vis, target: target.clone(), entries: vec![entry] };
+                        groups.insert(idx, group);
+                        idx += 1;
+                    }
+                }
+            }
+        }
+
+        let indent = leading_indent(use_item.syntax()).unwrap_or_default();
+        let attrs =
+            use_item.attrs().map(|it| format!("{}\n{}", it.syntax(), indent)).collect::<String>();
+        let mut buf = String::new();
+        for (idx, group) in groups.iter().enumerate() {
+            if idx > 0 {
+                format_to!(buf, "\n{}{}", indent, attrs);
+            }
+            if !group.vis.is_empty() {
+                format_to!(buf, "{} ", group.vis);
+            }
+            format_to!(buf, "use {}::", prefix);
+            if let Some(target) = &group.target {
+                format_to!(buf, "{}::", target);
+            }
+            match group.entries.as_slice() {
+                [entry] => format_to!(buf, "{};", entry),
+                entries => format_to!(buf, "{{{}}};", entries.join(", ")),
+            }
+        }
+
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+struct Group {
+    vis: String,
+    target: Option<String>,
+    entries: Vec<String>,
+}
+
+fn add_to_group(groups: &mut Vec<Group>, vis: String, target: &Option<String>, entry: String) {
+    let group = match groups.iter_mut().find(|it| it.vis == vis && it.target == *target) {
+        Some(it) => it,
+        None => {
+            groups.push(Group { vis, target: target.clone(), entries: Vec::new() });
+            groups.last_mut().unwrap()
+        }
+    };
+    if !group.entries.contains(&entry) {
+        group.entries.push(entry);
+    }
+}
+
+/// A single re-export from the list. `target` is the module directly below the
+/// prefix of the list, if any, and `rel` the rest of the path, with the alias.
+struct Leaf {
+    target: Option<String>,
+    rel: String,
+}
+
+fn flatten(tree: &ast::UseTree, prefix: &mut Vec<String>, acc: &mut Vec<Leaf>) -> Option<()> {
+    let depth = prefix.len();
+    if let Some(path) = tree.path() {
+        let mut segments = successors(Some(path), |it| it.qualifier())
+            .map(|it| Some(it.segment()?.syntax().text().to_string()))
+            .collect::<Option<Vec<_>>>()?;
+        segments.reverse();
+        prefix.extend(segments);
+    }
+    if let Some(list) = tree.use_tree_list() {
+        for tree in list.use_trees() {
+            flatten(&tree, prefix, acc)?;
+        }
+    } else {
+        let mut segments = prefix.clone();
+        if tree.star_token().is_some() {
+            segments.push("*".to_string());
+        }
+        let (target, mut rel) = match segments.as_slice() {
+            [] => return None,
+            [name] if name == "self" => return None,
+            [name] => (None, name.clone()),
+            [target, rest @ ..] => (Some(target.clone()), rest.join("::")),
+        };
+        if let Some(alias) = tree.alias() {
+            format_to!(rel, " {}", alias.syntax());
+        }
+        acc.push(Leaf { target, rel });
+    }
+    prefix.truncate(depth);
+    Some(())
+}
+
+fn resolve(ctx: &AssistCtx, use_item: &ast::UseItem, path: &str) -> Option<PathResolution> {
+    let path = hir::Path::from_ast(make::path_from_text(path))?;
+    ctx.sema.scope(use_item.syntax()).resolve_hir_path(&path)
+}
+
+fn resolve_module(
+    ctx: &AssistCtx,
+    use_item: &ast::UseItem,
+    prefix: &str,
+    target: &str,
+) -> Option<Module> {
+    match resolve(ctx, use_item, &format!("{}::{}", prefix, target))? {
+        PathResolution::Def(ModuleDef::Module(it)) => Some(it),
+        _ => None,
+    }
+}
+
+/// Resolves an explicit re-export to the item and the module it is taken from.
+fn resolve_leaf(
+    ctx: &AssistCtx,
+    use_item: &ast::UseItem,
+    prefix: &str,
+    leaf: &Leaf,
+) -> Option<(Module, ModuleDef)> {
+    let mut segments = vec![prefix];
+    if let Some(target) = &leaf.target {
+        segments.push(target);
+    }
+    segments.extend(leaf.rel.split(' ').next()?.split("::"));
+    let (name, module_path) = segments.split_last()?;
+    if *name == "self" {
+        return None;
+    }
+    let module = match resolve(ctx, use_item, &module_path.join("::"))? {
+        PathResolution::Def(ModuleDef::Module(it)) => it,
+        _ => return None,
+    };
+    match resolve(ctx, use_item, &segments.join("::"))? {
+        PathResolution::Def(def) => Some((module, def)),
+        _ => None,
+    }
+}
+
+/// Returns the items of `target` which a glob re-export from `module` would
+/// export and which are referenced outside of `target`, with their visibility.
+fn used_items(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    use_item: &ast::UseItem,
+    module: Module,
+    target: Module,
+    use_vis: &str,
+) -> Vec<(String, String)> {
+    let db = sema.db;
+    let use_range = use_item.syntax().text_range();
+    let mut res = Vec::new();
+    for (name, def) in target.scope(db, Some(module)) {
+        let (definition, vis) = match def {
+            ScopeDef::ModuleDef(def) => match target.visibility_of(db, &def) {
+                Some(vis) => (Definition::ModuleDef(def), vis),
+                None => continue,
+            },
+            ScopeDef::MacroDef(def) => (Definition::Macro(def), Visibility::Public),
+            _ => continue,
+        };
+        let is_used = definition.find_usages(db, None).into_iter().any(|reference| {
+            let range = reference.file_range;
+            if range.file_id == file_id && range.range.is_subrange(&use_range) {
+                return false;
+            }
+            let source_file = sema.parse(range.file_id);
+            let name_ref =
+                find_node_at_offset::<ast::NameRef>(source_file.syntax(), range.range.start());
+            name_ref.and_then(|it| sema.scope(it.syntax()).module()) != Some(target)
+        });
+        if is_used {
+            res.push((narrow_visibility(db, module, use_vis, vis), name.to_string()));
+        }
+    }
+    res.sort_by(|(_, lhs), (_, rhs)| lhs.cmp(rhs));
+    res
+}
+
+/// An item can't be re-exported with a visibility wider than its own, so
+/// crate-visible items are re-exported as `pub(crate)` and less visible ones
+/// privately.
+fn narrow_visibility(db: &RootDatabase, module: Module, use_vis: &str, vis: Visibility) -> String {
+    match vis {
+        Visibility::Public => use_vis.to_string(),
+        Visibility::Module(it) if Module::from(it) == module.crate_root(db) => {
+            if use_vis == "pub" {
+                "pub(crate)".to_string()
+            } else {
+                use_vis.to_string()
+            }
+        }
+        Visibility::Module(_) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn split_by_target_module() {
+        check_assist(
+            split_reexports,
+            r"
+mod a { pub struct A1; pub struct A2; }
+mod b { pub struct B; }
+pub use self::{a::A1, <|>b::B, a::A2 as Two};
+",
+            r"
+mod a { pub struct A1; pub struct A2; }
+mod b { pub struct B; }
+<|>pub use self::a::{A1, A2 as Two};
+pub use self::b::B;
+",
+        );
+    }
+
+    #[test]
+    fn split_nested_lists() {
+        check_assist(
+            split_reexports,
+            r"
+mod a { pub mod c { pub struct C; } pub struct A; }
+pub struct Root;
+pub use <|>self::{a::{self, A, c::C}, Root};
+",
+            r"
+mod a { pub mod c { pub struct C; } pub struct A; }
+pub struct Root;
+<|>pub use self::a::{self, A, c::C};
+pub use self::Root;
+",
+        );
+    }
+
+    #[test]
+    fn split_by_visibility() {
+        check_assist(
+            split_reexports,
+            r"
+mod a { pub struct Public; pub(crate) struct Internal; }
+pub use self::<|>{a::Public, a::Internal};
+",
+            r"
+mod a { pub struct Public; pub(crate) struct Internal; }
+<|>pub use self::a::Public;
+pub(crate) use self::a::Internal;
+",
+        );
+    }
+
+    #[test]
+    fn expand_glob_to_used_items() {
+        check_assist(
+            split_reexports,
+            r"
+//- /main.rs
+mod a;
+mod b;
+pub use self::<|>{a::*};
+//- /a.rs
+pub struct Used;
+pub struct Unused;
+pub fn local() -> Unused { Unused }
+//- /b.rs
+fn f(_: crate::Used) {}
+",
+            r"mod a;
+mod b;
+<|>pub use self::a::Used;
+",
+        );
+    }
+
+    #[test]
+    fn keeps_attributes() {
+        check_assist(
+            split_reexports,
+            r"
+mod a { pub struct A; }
+mod b { pub struct B; }
+mod m {
+    #[doc(inline)]
+    pub use <|>super::{a::A, b::B};
+}
+",
+            r"
+mod a { pub struct A; }
+mod b { pub struct B; }
+mod m {
+    #[doc(inline)]
+    <|>pub use super::a::A;
+    #[doc(inline)]
+    pub use super::b::B;
+}
+",
+        );
+    }
+
+    #[test]
+    fn split_reexports_not_applicable() {
+        check_assist_not_applicable(
+            split_reexports,
+            "mod a { pub struct A1; pub struct A2; }\npub use self::<|>a::{A1, A2};",
+        );
+        check_assist_not_applicable(
+            split_reexports,
+            "mod a { pub struct A; }\nmod b { pub struct B; }\nuse self::<|>{a::A, b::B};",
+        );
+    }
+
+    #[test]
+    fn keeps_unused_glob() {
+        check_assist(
+            split_reexports,
+            "mod a { pub struct A; }\npub use self::<|>{a::*};",
+            "mod a { pub struct A; }\n<|>pub use self::a::*;",
+        );
+    }
+}
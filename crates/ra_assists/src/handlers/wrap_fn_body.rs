@@ -0,0 +1,174 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, make, AstNode},
+    SyntaxKind::WHITESPACE,
+    TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: wrap_fn_body
+//
+// Wraps the body of a function with one of the configured templates, like
+// `futures::executor::block_on` to make an `async` function synchronous.
+//
+// ```
+// async fn <|>fetch() -> u32 {
+//     request().await
+// }
+// ```
+// ->
+// ```
+// fn fetch() -> u32 {
+//     futures::executor::block_on(async move {
+//         request().await
+//     })
+// }
+// ```
+pub(crate) fn wrap_fn_body(ctx: AssistCtx) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    // Only on the signature, the body may contain other functions.
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let async_kw = fn_def.async_kw_token();
+
+    // The body is moved one level deeper, into the block of the function.
+    let indent = IndentLevel::from_node(fn_def.syntax());
+    let inner_body = IndentLevel(1).increase_indent(indent.decrease_indent(body.clone()));
+    let wrapped_bodies = ctx
+        .config
+        .body_wrappers
+        .iter()
+        .filter(|it| it.template.contains("$BODY") && (!it.make_sync || async_kw.is_some()))
+        .filter_map(|wrapper| {
+            let text = wrapper.template.replace("$BODY", &inner_body.syntax().to_string());
+            // The parser recovers from errors, so check the whole template
+            // was taken.
+            let expr = make::try_parse_expr(&text).filter(|it| it.syntax().to_string() == text)?;
+            let new_body = indent.increase_indent(make::block_expr(None, Some(expr)));
+            Some((wrapper, new_body))
+        })
+        .collect::<Vec<_>>();
+    if wrapped_bodies.is_empty() {
+        return None;
+    }
+
+    let target = fn_def.syntax().text_range();
+    let mut group = ctx.add_assist_group("Wrap function body");
+    for (wrapper, new_body) in wrapped_bodies {
+        group.add_assist(
+            AssistId("wrap_fn_body"),
+            format!("Wrap body in `{}`", wrapper.name),
+            |edit| {
+                edit.target(target);
+                if let (true, Some(async_kw)) = (wrapper.make_sync, &async_kw) {
+                    let range = async_kw.syntax().text_range();
+                    let end = async_kw
+                        .syntax()
+                        .next_sibling_or_token()
+                        .filter(|it| it.kind() == WHITESPACE)
+                        .map_or(range.end(), |it| it.text_range().end());
+                    edit.delete(TextRange::from_to(range.start(), end));
+                }
+                edit.replace(body.syntax().text_range(), new_body.syntax().to_string());
+            },
+        );
+    }
+    group.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        helpers::{
+            check_assist, check_assist_not_applicable, check_assist_not_applicable_with_config,
+            check_assist_with_config,
+        },
+        AssistConfig, BodyWrapper,
+    };
+
+    use super::*;
+
+    fn config(template: &str, make_sync: bool) -> AssistConfig {
+        let wrapper =
+            BodyWrapper { name: "wrapper".to_string(), template: template.to_string(), make_sync };
+        AssistConfig { body_wrappers: vec![wrapper], ..AssistConfig::default() }
+    }
+
+    #[test]
+    fn wrap_async_body_in_block_on() {
+        check_assist(
+            wrap_fn_body,
+            r"
+mod api {
+    pub async fn <|>fetch(id: u32) -> u32 {
+        let response = request(id).await;
+        response.len()
+    }
+}",
+            r"
+mod api {
+    pub fn <|>fetch(id: u32) -> u32 {
+        futures::executor::block_on(async move {
+            let response = request(id).await;
+            response.len()
+        })
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn wrap_body_in_custom_template() {
+        check_assist_with_config(
+            wrap_fn_body,
+            &config("std::panic::catch_unwind(move || $BODY)", false),
+            r"
+fn <|>run() {
+    work();
+}",
+            r"
+fn <|>run() {
+    std::panic::catch_unwind(move || {
+        work();
+    })
+}",
+        );
+        check_assist_with_config(
+            wrap_fn_body,
+            &config("tokio::runtime::Runtime::new().unwrap().block_on(async $BODY)", true),
+            r"
+#[test]
+async fn <|>it_works() {
+    assert_eq!(add(1, 1).await, 2);
+}",
+            r"
+#[test]
+fn <|>it_works() {
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        assert_eq!(add(1, 1).await, 2);
+    })
+}",
+        );
+    }
+
+    #[test]
+    fn wrap_fn_body_not_applicable() {
+        // `block_on` is only offered for `async` functions
+        check_assist_not_applicable(wrap_fn_body, "fn <|>foo() { bar() }");
+        // Not in the body
+        check_assist_not_applicable(wrap_fn_body, "async fn foo() { <|>bar().await }");
+        // Templates which don't give an expression or don't use the body are skipped
+        check_assist_not_applicable_with_config(
+            wrap_fn_body,
+            &config("run($BODY", false),
+            "fn <|>foo() {}",
+        );
+        check_assist_not_applicable_with_config(
+            wrap_fn_body,
+            &config("run(move || {})", false),
+            "fn <|>foo() {}",
+        );
+    }
+}
@@ -17,12 +17,12 @@ mod doc_tests;
 pub mod utils;
 pub mod ast_transform;
 
-use ra_db::FileRange;
+use ra_db::{FileRange, RelativePathBuf, SourceRootId};
 use ra_ide_db::RootDatabase;
 use ra_syntax::{TextRange, TextUnit};
 use ra_text_edit::TextEdit;
 
-pub(crate) use crate::assist_ctx::{Assist, AssistCtx, AssistHandler};
+pub(crate) use crate::assist_ctx::{Assist, AssistCtx, AssistHandler, AssistInfo};
 use hir::Semantics;
 
 /// Unique identifier of the assist, should not be shown to the user
@@ -48,12 +48,55 @@ impl AssistLabel {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssistConfig {
+    /// Templates offered by the `wrap_fn_body` assist.
+    pub body_wrappers: Vec<BodyWrapper>,
+    /// Whether `auto_import` searches the whole workspace for the unresolved
+    /// name.
+    pub import_search: bool,
+}
+
+impl Default for AssistConfig {
+    fn default() -> Self {
+        AssistConfig {
+            body_wrappers: vec![BodyWrapper {
+                name: "block_on".to_string(),
+                template: "futures::executor::block_on(async move $BODY)".to_string(),
+                make_sync: true,
+            }],
+            import_search: true,
+        }
+    }
+}
+
+/// A template wrapping the body of a function, where `$BODY` stands for the
+/// original body block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BodyWrapper {
+    /// Shown in the label of the assist.
+    pub name: String,
+    pub template: String,
+    /// Whether the wrapper only applies to `async` functions, which become
+    /// synchronous.
+    pub make_sync: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct AssistAction {
     pub edit: TextEdit,
     pub cursor_position: Option<TextUnit>,
     // FIXME: This belongs to `AssistLabel`
     pub target: Option<TextRange>,
+    /// Files created by the assist, in addition to the edit of the current file.
+    pub new_files: Vec<AssistFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AssistFile {
+    pub source_root: SourceRootId,
+    pub path: RelativePathBuf,
+    pub contents: String,
 }
 
 #[derive(Debug, Clone)]
@@ -63,28 +106,63 @@ pub struct ResolvedAssist {
     pub action: AssistAction,
 }
 
+#[derive(Debug, Clone)]
+pub struct UnresolvedAssist {
+    pub label: AssistLabel,
+    pub group_label: Option<GroupLabel>,
+}
+
 /// Return all the assists applicable at the given position.
 ///
 /// Assists are returned in the "unresolved" state, that is only labels are
 /// returned, without actual edits.
-pub fn unresolved_assists(db: &RootDatabase, range: FileRange) -> Vec<AssistLabel> {
+pub fn unresolved_assists(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    range: FileRange,
+) -> Vec<UnresolvedAssist> {
     let sema = Semantics::new(db);
-    let ctx = AssistCtx::new(&sema, range, false);
+    let ctx = AssistCtx::new(&sema, config, range, false);
     handlers::all()
         .iter()
         .filter_map(|f| f(ctx.clone()))
         .flat_map(|it| it.0)
-        .map(|a| a.label)
+        .map(|a| UnresolvedAssist { label: a.label, group_label: a.group_label })
         .collect()
 }
 
+/// Computes the edit of a single assist, as returned by `unresolved_assists`.
+///
+/// Assists in a group can share the id, so the assist is identified by the
+/// label as well. Only the handler which offers the assist computes its edits.
+pub fn resolve_assist(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    range: FileRange,
+    id: &str,
+    label: &str,
+) -> Option<ResolvedAssist> {
+    let sema = Semantics::new(db);
+    let is_requested = |it: &AssistInfo| it.label.id.0 == id && it.label.label == label;
+    let ctx = AssistCtx::new(&sema, config, range, false);
+    let handler = handlers::all()
+        .iter()
+        .find(|f| f(ctx.clone()).map_or(false, |it| it.0.iter().any(is_requested)))?;
+    let ctx = AssistCtx::new(&sema, config, range, true);
+    handler(ctx)?.0.into_iter().find(is_requested)?.into_resolved()
+}
+
 /// Return all the assists applicable at the given position.
 ///
 /// Assists are returned in the "resolved" state, that is with edit fully
 /// computed.
-pub fn resolved_assists(db: &RootDatabase, range: FileRange) -> Vec<ResolvedAssist> {
+pub fn resolved_assists(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    range: FileRange,
+) -> Vec<ResolvedAssist> {
     let sema = Semantics::new(db);
-    let ctx = AssistCtx::new(&sema, range, true);
+    let ctx = AssistCtx::new(&sema, config, range, true);
     let mut a = handlers::all()
         .iter()
         .filter_map(|f| f(ctx.clone()))
@@ -98,21 +176,32 @@ pub fn resolved_assists(db: &RootDatabase, range: FileRange) -> Vec<ResolvedAssi
 mod handlers {
     use crate::AssistHandler;
 
+    mod add_attribute;
     mod add_custom_impl;
+    mod add_default_impl;
     mod add_derive;
     mod add_explicit_type;
     mod add_function;
     mod add_impl;
     mod add_missing_impl_members;
     mod add_new;
+    mod add_partial_eq_impl;
+    mod add_self_sized_bound;
+    mod add_enum_discriminants;
+    mod add_enum_variant_methods;
     mod apply_demorgan;
     mod auto_import;
     mod change_visibility;
+    mod convert_assert;
+    mod convert_to_method;
+    mod destructure_tuple_binding;
     mod early_return;
+    mod extract_module_to_file;
     mod fill_match_arms;
     mod flip_binexpr;
     mod flip_comma;
     mod flip_trait_bound;
+    mod hoist_common_match_arm_code;
     mod inline_local_variable;
     mod introduce_variable;
     mod invert_if;
@@ -123,30 +212,54 @@ mod handlers {
     mod raw_string;
     mod remove_dbg;
     mod remove_mut;
+    mod replace_boxed_fn_field;
     mod replace_if_let_with_match;
+    mod replace_impl_trait_with_generic;
+    mod replace_index_with_get;
+    mod replace_is_some_with_if_let;
     mod replace_let_with_if_let;
     mod replace_qualified_name_with_use;
     mod replace_unwrap_with_match;
     mod split_import;
+    mod split_reexports;
+    mod wrap_fn_body;
     mod add_from_impl_for_enum;
 
     pub(crate) fn all() -> &'static [AssistHandler] {
         &[
             // These are alphabetic for the foolish consistency
+            add_attribute::add_inline,
+            add_attribute::add_must_use,
+            add_attribute::add_track_caller,
             add_custom_impl::add_custom_impl,
+            add_default_impl::add_default_impl,
             add_derive::add_derive,
             add_explicit_type::add_explicit_type,
             add_function::add_function,
             add_impl::add_impl,
             add_new::add_new,
+            add_partial_eq_impl::add_partial_eq_impl,
+            add_partial_eq_impl::add_partial_eq_and_hash_impl,
+            add_self_sized_bound::add_self_sized_bound,
+            add_enum_discriminants::add_enum_discriminants,
+            add_enum_discriminants::add_enum_value_consts,
+            add_enum_discriminants::add_try_from_impl_for_enum,
+            add_enum_discriminants::add_display_and_from_str_for_enum,
+            add_enum_variant_methods::add_enum_variant_methods,
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
             change_visibility::change_visibility,
+            convert_assert::convert_assert_to_assert_eq,
+            convert_assert::convert_assert_eq_to_assert,
+            convert_to_method::convert_to_method,
+            destructure_tuple_binding::destructure_tuple_binding,
             early_return::convert_to_guarded_return,
+            extract_module_to_file::extract_module_to_file,
             fill_match_arms::fill_match_arms,
             flip_binexpr::flip_binexpr,
             flip_comma::flip_comma,
             flip_trait_bound::flip_trait_bound,
+            hoist_common_match_arm_code::hoist_common_match_arm_code,
             inline_local_variable::inline_local_variable,
             introduce_variable::introduce_variable,
             invert_if::invert_if,
@@ -161,11 +274,18 @@ mod handlers {
             raw_string::remove_hash,
             remove_dbg::remove_dbg,
             remove_mut::remove_mut,
+            replace_boxed_fn_field::extract_boxed_fn_type_alias,
+            replace_boxed_fn_field::make_boxed_fn_field_generic,
             replace_if_let_with_match::replace_if_let_with_match,
+            replace_impl_trait_with_generic::replace_impl_trait_with_generic,
+            replace_index_with_get::replace_index_with_get,
+            replace_is_some_with_if_let::replace_is_some_with_if_let,
             replace_let_with_if_let::replace_let_with_if_let,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             replace_unwrap_with_match::replace_unwrap_with_match,
             split_import::split_import,
+            split_reexports::split_reexports,
+            wrap_fn_body::wrap_fn_body,
             add_from_impl_for_enum::add_from_impl_for_enum,
             // These are manually sorted for better priorities
             add_missing_impl_members::add_missing_impl_members,
@@ -178,11 +298,11 @@ mod handlers {
 mod helpers {
     use std::sync::Arc;
 
-    use ra_db::{fixture::WithFixture, FileId, FileRange, SourceDatabaseExt};
+    use ra_db::{fixture::WithFixture, FileId, FileRange, RelativePathBuf, SourceDatabaseExt};
     use ra_ide_db::{symbol_index::SymbolsDatabase, RootDatabase};
     use test_utils::{add_cursor, assert_eq_text, extract_range_or_offset, RangeOrOffset};
 
-    use crate::{AssistCtx, AssistHandler};
+    use crate::{AssistConfig, AssistCtx, AssistHandler};
     use hir::Semantics;
 
     pub(crate) fn with_single_file(text: &str) -> (RootDatabase, FileId) {
@@ -201,6 +321,20 @@ mod helpers {
         check(assist, ra_fixture_before, ExpectedResult::After(ra_fixture_after));
     }
 
+    pub(crate) fn check_assist_with_config(
+        assist: AssistHandler,
+        config: &AssistConfig,
+        ra_fixture_before: &str,
+        ra_fixture_after: &str,
+    ) {
+        check_with_config(
+            assist,
+            config,
+            ra_fixture_before,
+            ExpectedResult::After(ra_fixture_after),
+        );
+    }
+
     // FIXME: instead of having a separate function here, maybe use
     // `extract_ranges` and mark the target as `<target> </target>` in the
     // fixuture?
@@ -212,13 +346,42 @@ mod helpers {
         check(assist, ra_fixture, ExpectedResult::NotApplicable);
     }
 
+    pub(crate) fn check_assist_not_applicable_with_config(
+        assist: AssistHandler,
+        config: &AssistConfig,
+        ra_fixture: &str,
+    ) {
+        check_with_config(assist, config, ra_fixture, ExpectedResult::NotApplicable);
+    }
+
+    /// Checks the single file created by the assist, with a path relative to
+    /// the source root.
+    pub(crate) fn check_assist_new_file(
+        assist: AssistHandler,
+        ra_fixture: &str,
+        path: &str,
+        contents: &str,
+    ) {
+        check(assist, ra_fixture, ExpectedResult::NewFile { path, contents });
+    }
+
     enum ExpectedResult<'a> {
         NotApplicable,
         After(&'a str),
         Target(&'a str),
+        NewFile { path: &'a str, contents: &'a str },
     }
 
     fn check(assist: AssistHandler, before: &str, expected: ExpectedResult) {
+        check_with_config(assist, &AssistConfig::default(), before, expected)
+    }
+
+    fn check_with_config(
+        assist: AssistHandler,
+        config: &AssistConfig,
+        before: &str,
+        expected: ExpectedResult,
+    ) {
         let (text_without_caret, file_with_caret_id, range_or_offset, db) =
             if before.contains("//-") {
                 let (mut db, position) = RootDatabase::with_position(before);
@@ -238,7 +401,7 @@ mod helpers {
         let frange = FileRange { file_id: file_with_caret_id, range: range_or_offset.into() };
 
         let sema = Semantics::new(&db);
-        let assist_ctx = AssistCtx::new(&sema, frange, true);
+        let assist_ctx = AssistCtx::new(&sema, config, frange, true);
 
         match (assist(assist_ctx), expected) {
             (Some(assist), ExpectedResult::After(after)) => {
@@ -265,10 +428,17 @@ mod helpers {
                 let range = action.target.expect("expected target on action");
                 assert_eq_text!(&text_without_caret[range], target);
             }
-            (Some(_), ExpectedResult::NotApplicable) => panic!("assist should not be applicable!"),
-            (None, ExpectedResult::After(_)) | (None, ExpectedResult::Target(_)) => {
-                panic!("code action is not applicable")
+            (Some(assist), ExpectedResult::NewFile { path, contents }) => {
+                let action = assist.0[0].action.clone().unwrap();
+                assert_eq!(action.new_files.len(), 1, "expected a single new file");
+                let file = &action.new_files[0];
+                assert_eq!(file.path, RelativePathBuf::from(path));
+                assert_eq_text!(contents, &file.contents);
             }
+            (Some(_), ExpectedResult::NotApplicable) => panic!("assist should not be applicable!"),
+            (None, ExpectedResult::After(_))
+            | (None, ExpectedResult::Target(_))
+            | (None, ExpectedResult::NewFile { .. }) => panic!("code action is not applicable"),
             (None, ExpectedResult::NotApplicable) => (),
         };
     }
@@ -280,7 +450,7 @@ mod tests {
     use ra_syntax::TextRange;
     use test_utils::{extract_offset, extract_range};
 
-    use crate::{helpers, resolved_assists};
+    use crate::{helpers, resolve_assist, resolved_assists, unresolved_assists, AssistConfig};
 
     #[test]
     fn assist_order_field_struct() {
@@ -289,7 +459,7 @@ mod tests {
         let (db, file_id) = helpers::with_single_file(&before);
         let frange =
             FileRange { file_id, range: TextRange::offset_len(before_cursor_pos, 0.into()) };
-        let assists = resolved_assists(&db, frange);
+        let assists = resolved_assists(&db, &AssistConfig::default(), frange);
         let mut assists = assists.iter();
 
         assert_eq!(
@@ -312,10 +482,42 @@ mod tests {
         let (range, before) = extract_range(before);
         let (db, file_id) = helpers::with_single_file(&before);
         let frange = FileRange { file_id, range };
-        let assists = resolved_assists(&db, frange);
+        let assists = resolved_assists(&db, &AssistConfig::default(), frange);
         let mut assists = assists.iter();
 
         assert_eq!(assists.next().expect("expected assist").label.label, "Extract into variable");
         assert_eq!(assists.next().expect("expected assist").label.label, "Replace with match");
     }
+
+    #[test]
+    fn resolve_assists_lazily() {
+        let before = "fn <|>foo() -> u32 { 92 }";
+        let (before_cursor_pos, before) = extract_offset(before);
+        let (db, file_id) = helpers::with_single_file(&before);
+        let frange =
+            FileRange { file_id, range: TextRange::offset_len(before_cursor_pos, 0.into()) };
+
+        let mut unresolved = unresolved_assists(&db, &AssistConfig::default(), frange)
+            .into_iter()
+            .map(|it| it.label.label)
+            .collect::<Vec<_>>();
+        unresolved.sort();
+        let mut resolved = resolved_assists(&db, &AssistConfig::default(), frange);
+        resolved.sort_by(|lhs, rhs| lhs.label.label.cmp(&rhs.label.label));
+        assert_eq!(
+            unresolved,
+            resolved.iter().map(|it| it.label.label.clone()).collect::<Vec<_>>()
+        );
+
+        for assist in resolved {
+            let label = &assist.label;
+            let lazy =
+                resolve_assist(&db, &AssistConfig::default(), frange, label.id.0, &label.label)
+                    .unwrap();
+            assert_eq!(lazy.action.edit.apply(&before), assist.action.edit.apply(&before));
+        }
+        assert!(
+            resolve_assist(&db, &AssistConfig::default(), frange, "add_inline", "Nope").is_none()
+        );
+    }
 }
@@ -99,3 +99,22 @@ fn invert_special_case(expr: &ast::Expr) -> Option<ast::Expr> {
         _ => None,
     }
 }
+
+/// Converts a `CamelCase` identifier to `snake_case`.
+pub(crate) fn to_lower_snake_case(name: &str) -> String {
+    let mut buf = String::with_capacity(name.len());
+    let mut prev_is_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && prev_is_lower {
+            buf.push('_');
+        }
+        prev_is_lower = c.is_lowercase() || c.is_ascii_digit();
+        buf.extend(c.to_lowercase());
+    }
+    buf
+}
+
+/// Converts a `CamelCase` identifier to `UPPER_SNAKE_CASE`.
+pub(crate) fn to_upper_snake_case(name: &str) -> String {
+    to_lower_snake_case(name).to_uppercase()
+}
@@ -2,7 +2,7 @@
 //!
 //! See: https://doc.rust-lang.org/reference/conditional-compilation.html#conditional-compilation
 
-use std::slice::Iter as SliceIter;
+use std::{fmt, slice::Iter as SliceIter};
 
 use ra_syntax::SmolStr;
 use tt::{Leaf, Subtree, TokenTree};
@@ -17,40 +17,133 @@ pub enum CfgExpr {
     Not(Box<CfgExpr>),
 }
 
+/// A syntax error in a cfg predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgError {
+    /// `#[cfg()]`
+    Empty,
+    /// `#[cfg(foo, bar)]`
+    MultiplePredicates,
+    /// `#[cfg(all(foo bar))]`
+    ExpectedComma,
+    /// `#[cfg("foo")]`
+    ExpectedIdent,
+    /// `#[cfg(feature = foo)]`
+    ExpectedString { key: SmolStr },
+    /// `#[cfg(foo(bar))]`
+    UnknownPredicate(SmolStr),
+    /// `#[cfg(not(foo, bar))]`
+    ExpectedOnePredicate,
+}
+
+impl fmt::Display for CfgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CfgError::Empty => write!(f, "`cfg` predicate is not specified"),
+            CfgError::MultiplePredicates => write!(f, "multiple `cfg` predicates are specified"),
+            CfgError::ExpectedComma => write!(f, "expected `,` between `cfg` predicates"),
+            CfgError::ExpectedIdent => write!(f, "`cfg` predicate key must be an identifier"),
+            CfgError::ExpectedString { key } => {
+                write!(f, "value of `cfg` predicate `{}` must be a string literal", key)
+            }
+            CfgError::UnknownPredicate(name) => write!(f, "invalid `cfg` predicate `{}`", name),
+            CfgError::ExpectedOnePredicate => write!(f, "`not` expects exactly one predicate"),
+        }
+    }
+}
+
 impl CfgExpr {
+    /// Parses a cfg predicate, failing with the first syntax error in it.
+    pub fn parse(tt: &Subtree) -> Result<CfgExpr, CfgError> {
+        let mut error = None;
+        let expr = parse(tt, &mut error);
+        match error {
+            Some(error) => Err(error),
+            None => Ok(expr),
+        }
+    }
+
     /// Fold the cfg by querying all basic `Atom` and `KeyValue` predicates.
-    pub fn fold(&self, query: &dyn Fn(&SmolStr, Option<&SmolStr>) -> bool) -> Option<bool> {
+    ///
+    /// The query returns `None` for predicates whose value is unknown. Like
+    /// for `Invalid` predicates, the result is then only known if it doesn't
+    /// depend on them, so `any(unknown, true)` is still `true`.
+    pub fn fold(&self, query: &dyn Fn(&SmolStr, Option<&SmolStr>) -> Option<bool>) -> Option<bool> {
         match self {
             CfgExpr::Invalid => None,
-            CfgExpr::Atom(name) => Some(query(name, None)),
-            CfgExpr::KeyValue { key, value } => Some(query(key, Some(value))),
-            CfgExpr::All(preds) => {
-                preds.iter().try_fold(true, |s, pred| Some(s && pred.fold(query)?))
-            }
-            CfgExpr::Any(preds) => {
-                preds.iter().try_fold(false, |s, pred| Some(s || pred.fold(query)?))
-            }
+            CfgExpr::Atom(name) => query(name, None),
+            CfgExpr::KeyValue { key, value } => query(key, Some(value)),
+            CfgExpr::All(preds) => fold_many(preds, query, false),
+            CfgExpr::Any(preds) => fold_many(preds, query, true),
             CfgExpr::Not(pred) => pred.fold(query).map(|s| !s),
         }
     }
 }
 
+/// Folds `all` and `any`, which are decided by the first predicate evaluating
+/// to `decisive`.
+fn fold_many(
+    preds: &[CfgExpr],
+    query: &dyn Fn(&SmolStr, Option<&SmolStr>) -> Option<bool>,
+    decisive: bool,
+) -> Option<bool> {
+    let mut res = Some(!decisive);
+    for pred in preds {
+        match pred.fold(query) {
+            Some(it) if it == decisive => return Some(decisive),
+            Some(_) => {}
+            None => res = None,
+        }
+    }
+    res
+}
+
+/// Parses a cfg predicate, recovering from syntax errors by replacing the
+/// broken parts with `CfgExpr::Invalid`.
 pub fn parse_cfg(tt: &Subtree) -> CfgExpr {
-    next_cfg_expr(&mut tt.token_trees.iter()).unwrap_or(CfgExpr::Invalid)
+    parse(tt, &mut None)
+}
+
+fn parse(tt: &Subtree, error: &mut Option<CfgError>) -> CfgExpr {
+    let mut it = tt.token_trees.iter();
+    let expr = match next_cfg_expr(&mut it, error) {
+        Some(it) => it,
+        None => {
+            report(error, CfgError::Empty);
+            CfgExpr::Invalid
+        }
+    };
+    // Only take the first
+    if !it.as_slice().is_empty() {
+        report(error, CfgError::MultiplePredicates);
+    }
+    expr
 }
 
-fn next_cfg_expr(it: &mut SliceIter<tt::TokenTree>) -> Option<CfgExpr> {
+fn report(slot: &mut Option<CfgError>, error: CfgError) {
+    if slot.is_none() {
+        *slot = Some(error);
+    }
+}
+
+fn next_cfg_expr(
+    it: &mut SliceIter<tt::TokenTree>,
+    error: &mut Option<CfgError>,
+) -> Option<CfgExpr> {
     let name = match it.next() {
         None => return None,
         Some(TokenTree::Leaf(Leaf::Ident(ident))) => ident.text.clone(),
-        Some(_) => return Some(CfgExpr::Invalid),
+        Some(_) => {
+            report(error, CfgError::ExpectedIdent);
+            return Some(CfgExpr::Invalid);
+        }
     };
 
     // Peek
     let ret = match it.as_slice().first() {
         Some(TokenTree::Leaf(Leaf::Punct(punct))) if punct.char == '=' => {
             match it.as_slice().get(1) {
-                Some(TokenTree::Leaf(Leaf::Literal(literal))) => {
+                Some(TokenTree::Leaf(Leaf::Literal(literal))) if literal.text.starts_with('"') => {
                     it.next();
                     it.next();
                     // FIXME: escape? raw string?
@@ -58,28 +151,42 @@ fn next_cfg_expr(it: &mut SliceIter<tt::TokenTree>) -> Option<CfgExpr> {
                         SmolStr::new(literal.text.trim_start_matches('"').trim_end_matches('"'));
                     CfgExpr::KeyValue { key: name, value }
                 }
-                _ => return Some(CfgExpr::Invalid),
+                _ => {
+                    report(error, CfgError::ExpectedString { key: name });
+                    return Some(CfgExpr::Invalid);
+                }
             }
         }
         Some(TokenTree::Subtree(subtree)) => {
             it.next();
             let mut sub_it = subtree.token_trees.iter();
-            let mut subs = std::iter::from_fn(|| next_cfg_expr(&mut sub_it)).collect();
+            let mut subs: Vec<_> =
+                std::iter::from_fn(|| next_cfg_expr(&mut sub_it, error)).collect();
             match name.as_str() {
                 "all" => CfgExpr::All(subs),
                 "any" => CfgExpr::Any(subs),
-                "not" => CfgExpr::Not(Box::new(subs.pop().unwrap_or(CfgExpr::Invalid))),
-                _ => CfgExpr::Invalid,
+                "not" => {
+                    if subs.len() != 1 {
+                        report(error, CfgError::ExpectedOnePredicate);
+                    }
+                    CfgExpr::Not(Box::new(subs.pop().unwrap_or(CfgExpr::Invalid)))
+                }
+                _ => {
+                    report(error, CfgError::UnknownPredicate(name));
+                    CfgExpr::Invalid
+                }
             }
         }
         _ => CfgExpr::Atom(name),
     };
 
     // Eat comma separator
-    if let Some(TokenTree::Leaf(Leaf::Punct(punct))) = it.as_slice().first() {
-        if punct.char == ',' {
+    match it.as_slice().first() {
+        Some(TokenTree::Leaf(Leaf::Punct(punct))) if punct.char == ',' => {
             it.next();
         }
+        Some(_) => report(error, CfgError::ExpectedComma),
+        None => {}
     }
     Some(ret)
 }
@@ -98,6 +205,13 @@ mod tests {
         assert_eq!(parse_cfg(&tt), expected);
     }
 
+    fn assert_parse_error(input: &str, expected: CfgError) {
+        let source_file = ast::SourceFile::parse(input).ok().unwrap();
+        let tt = source_file.syntax().descendants().find_map(ast::TokenTree::cast).unwrap();
+        let (tt, _) = ast_to_token_tree(&tt).unwrap();
+        assert_eq!(CfgExpr::parse(&tt), Err(expected));
+    }
+
     #[test]
     fn test_cfg_expr_parser() {
         assert_parse_result("#![cfg(foo)]", CfgExpr::Atom("foo".into()));
@@ -129,4 +243,39 @@ mod tests {
             ]),
         );
     }
+
+    #[test]
+    fn test_cfg_expr_parse_errors() {
+        assert_parse_error("#![cfg()]", CfgError::Empty);
+        assert_parse_error("#![cfg(foo, bar)]", CfgError::MultiplePredicates);
+        assert_parse_error("#![cfg(all(foo bar))]", CfgError::ExpectedComma);
+        assert_parse_error(r#"#![cfg("foo")]"#, CfgError::ExpectedIdent);
+        assert_parse_error(
+            "#![cfg(feature = 1)]",
+            CfgError::ExpectedString { key: "feature".into() },
+        );
+        assert_parse_error("#![cfg(foo(bar))]", CfgError::UnknownPredicate("foo".into()));
+        assert_parse_error("#![cfg(not(foo, bar))]", CfgError::ExpectedOnePredicate);
+        assert_parse_error("#![cfg(any(not(), bar))]", CfgError::ExpectedOnePredicate);
+    }
+
+    #[test]
+    fn test_cfg_expr_fold_unknown() {
+        let source_file =
+            ast::SourceFile::parse("#![cfg(any(foo, all(bar, unknown)))]").ok().unwrap();
+        let tt = source_file.syntax().descendants().find_map(ast::TokenTree::cast).unwrap();
+        let (tt, _) = ast_to_token_tree(&tt).unwrap();
+        let cfg = CfgExpr::parse(&tt).unwrap();
+
+        let eval = |foo: bool, bar: bool| {
+            cfg.fold(&|key, _| match key.as_str() {
+                "foo" => Some(foo),
+                "bar" => Some(bar),
+                _ => None,
+            })
+        };
+        assert_eq!(eval(true, true), Some(true));
+        assert_eq!(eval(false, true), None);
+        assert_eq!(eval(false, false), Some(false));
+    }
 }
@@ -7,7 +7,7 @@ use std::iter::IntoIterator;
 use ra_syntax::SmolStr;
 use rustc_hash::FxHashSet;
 
-pub use cfg_expr::{parse_cfg, CfgExpr};
+pub use cfg_expr::{parse_cfg, CfgError, CfgExpr};
 
 /// Configuration options used for conditional compilition on items with `cfg` attributes.
 /// We have two kind of options in different namespaces: atomic options like `unix`, and
@@ -23,20 +23,60 @@ pub use cfg_expr::{parse_cfg, CfgExpr};
 pub struct CfgOptions {
     atoms: FxHashSet<SmolStr>,
     key_values: FxHashSet<(SmolStr, SmolStr)>,
+    unknown_keys: UnknownKeyPolicy,
+}
+
+/// How to evaluate predicates on keys which are set neither as an atom nor
+/// with any value, like `feature = "foo"` in a crate without features.
+///
+/// rustc treats them as disabled, but when the options are incomplete, for
+/// example because the target isn't known, this would hide code which is
+/// actually compiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownKeyPolicy {
+    Disabled,
+    Enabled,
+    /// Don't assume anything, which leaves predicates depending on unknown
+    /// keys undecided.
+    Unknown,
+}
+
+impl Default for UnknownKeyPolicy {
+    fn default() -> UnknownKeyPolicy {
+        UnknownKeyPolicy::Disabled
+    }
 }
 
 impl CfgOptions {
     pub fn check(&self, cfg: &CfgExpr) -> Option<bool> {
-        cfg.fold(&|key, value| match value {
-            None => self.atoms.contains(key),
-            Some(value) => self.key_values.contains(&(key.clone(), value.clone())),
+        cfg.fold(&|key, value| {
+            if !self.is_known_key(key) {
+                return match self.unknown_keys {
+                    UnknownKeyPolicy::Disabled => Some(false),
+                    UnknownKeyPolicy::Enabled => Some(true),
+                    UnknownKeyPolicy::Unknown => None,
+                };
+            }
+            let res = match value {
+                None => self.atoms.contains(key),
+                Some(value) => self.key_values.contains(&(key.clone(), value.clone())),
+            };
+            Some(res)
         })
     }
 
+    fn is_known_key(&self, key: &SmolStr) -> bool {
+        self.atoms.contains(key) || self.key_values.iter().any(|(it, _)| it == key)
+    }
+
     pub fn is_cfg_enabled(&self, attr: &tt::Subtree) -> Option<bool> {
         self.check(&parse_cfg(attr))
     }
 
+    pub fn set_unknown_key_policy(&mut self, policy: UnknownKeyPolicy) {
+        self.unknown_keys = policy;
+    }
+
     pub fn insert_atom(&mut self, key: SmolStr) {
         self.atoms.insert(key);
     }
@@ -54,3 +94,36 @@ impl CfgOptions {
         iter.into_iter().for_each(|feat| self.insert_key_value("feature".into(), feat));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_key_policy() {
+        let mut options = CfgOptions::default();
+        options.insert_atom("unix".into());
+        options.insert_features(vec!["foo".into()]);
+
+        let cfg = CfgExpr::All(vec![
+            CfgExpr::Not(Box::new(CfgExpr::Atom("unix".into()))),
+            CfgExpr::KeyValue { key: "target_os".into(), value: "linux".into() },
+        ]);
+        let feature = CfgExpr::KeyValue { key: "feature".into(), value: "bar".into() };
+        let os = CfgExpr::KeyValue { key: "target_os".into(), value: "linux".into() };
+
+        for &policy in
+            &[UnknownKeyPolicy::Disabled, UnknownKeyPolicy::Enabled, UnknownKeyPolicy::Unknown]
+        {
+            options.set_unknown_key_policy(policy);
+            assert_eq!(options.check(&cfg), Some(false));
+            assert_eq!(options.check(&feature), Some(false));
+        }
+        options.set_unknown_key_policy(UnknownKeyPolicy::Disabled);
+        assert_eq!(options.check(&os), Some(false));
+        options.set_unknown_key_policy(UnknownKeyPolicy::Enabled);
+        assert_eq!(options.check(&os), Some(true));
+        options.set_unknown_key_policy(UnknownKeyPolicy::Unknown);
+        assert_eq!(options.check(&os), None);
+    }
+}
@@ -1,15 +1,32 @@
-//! FIXME: write short doc here
-
+//! Sets up a database from a fixture, a set of files each preceded by a
+//! `//-` line with its path and metadata:
+//!
+//! ```not_rust
+//! //- /main.rs crate:main deps:dep edition:2015 cfg:feature=x env:KEY=value
+//! //- /dep.rs crate:dep proc_macros:Serialize out_dir:/out
+//! //- root /out/
+//! //- /main.rs step:1
+//! ```
+//!
+//! `proc_macros` declares derives, expanding to nothing, that are exported
+//! from the crate. `out_dir` sets `OUT_DIR` the way a build script does, and
+//! makes the files under that root visible to `include!`. Entries with
+//! `step:N` replace the text of a file declared earlier once the step is
+//! applied with `FixtureSteps::apply`.
+
+use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use ra_cfg::CfgOptions;
+use ra_syntax::SmolStr;
+use ra_tt::{ExpansionError, Subtree, TokenExpander};
 use rustc_hash::FxHashMap;
 use test_utils::{extract_offset, parse_fixture, parse_single_fixture, CURSOR_MARKER};
 
 use crate::{
-    input::CrateName, CrateGraph, CrateId, Edition, Env, FileId, FilePosition, RelativePathBuf,
-    SourceDatabaseExt, SourceRoot, SourceRootId,
+    input::CrateName, CrateGraph, CrateId, Edition, Env, ExternSource, ExternSourceId, FileId,
+    FilePosition, RelativePathBuf, SourceDatabaseExt, SourceRoot, SourceRootId,
 };
 
 pub const WORKSPACE: SourceRootId = SourceRootId(0);
@@ -22,18 +39,25 @@ pub trait WithFixture: Default + SourceDatabaseExt + 'static {
     }
 
     fn with_files(ra_fixture: &str) -> Self {
-        let mut db = Self::default();
-        let pos = with_files(&mut db, ra_fixture);
-        assert!(pos.is_none());
+        let (db, steps) = Self::with_files_and_steps(ra_fixture);
+        assert!(steps.steps.is_empty());
         db
     }
 
     fn with_position(ra_fixture: &str) -> (Self, FilePosition) {
         let mut db = Self::default();
-        let pos = with_files(&mut db, ra_fixture);
+        let (pos, steps) = with_files(&mut db, ra_fixture);
+        assert!(steps.steps.is_empty());
         (db, pos.unwrap())
     }
 
+    fn with_files_and_steps(ra_fixture: &str) -> (Self, FixtureSteps) {
+        let mut db = Self::default();
+        let (pos, steps) = with_files(&mut db, ra_fixture);
+        assert!(pos.is_none());
+        (db, steps)
+    }
+
     fn test_crate(&self) -> CrateId {
         let crate_graph = self.crate_graph();
         let mut it = crate_graph.iter();
@@ -45,6 +69,40 @@ pub trait WithFixture: Default + SourceDatabaseExt + 'static {
 
 impl<DB: SourceDatabaseExt + Default + 'static> WithFixture for DB {}
 
+/// The file changes declared with `step:N` in a fixture.
+#[derive(Debug, Default)]
+pub struct FixtureSteps {
+    steps: Vec<Vec<(FileId, Arc<String>)>>,
+}
+
+impl FixtureSteps {
+    /// Sets the new texts of the files changed in `step`, counting from 1.
+    pub fn apply(&self, db: &mut dyn SourceDatabaseExt, step: usize) {
+        for (file_id, text) in self.steps[step - 1].iter() {
+            db.set_file_text(*file_id, Arc::clone(text));
+        }
+    }
+
+    fn add(&mut self, step: usize, file_id: FileId, text: String) {
+        assert!(step > 0, "steps are counted from 1");
+        if self.steps.len() < step {
+            self.steps.resize_with(step, Vec::new);
+        }
+        self.steps[step - 1].push((file_id, Arc::new(text)));
+    }
+}
+
+/// The expander of the proc macros declared in fixtures, which are only used
+/// to check name resolution.
+#[derive(Debug)]
+struct EmptyDeriveExpander;
+
+impl TokenExpander for EmptyDeriveExpander {
+    fn expand(&self, _: &Subtree, _: Option<&Subtree>) -> Result<Subtree, ExpansionError> {
+        Ok(Subtree::default())
+    }
+}
+
 fn with_single_file(db: &mut dyn SourceDatabaseExt, ra_fixture: &str) -> FileId {
     let file_id = FileId(0);
     let rel_path: RelativePathBuf = "/main.rs".into();
@@ -59,19 +117,8 @@ fn with_single_file(db: &mut dyn SourceDatabaseExt, ra_fixture: &str) -> FileId
             ParsedMeta::File(it) => it,
             _ => panic!("with_single_file only support file meta"),
         };
-
         let mut crate_graph = CrateGraph::default();
-        crate_graph.add_crate_root(
-            file_id,
-            meta.edition,
-            meta.krate.map(|name| {
-                CrateName::new(&name).expect("Fixture crate name should not contain dashes")
-            }),
-            meta.cfg,
-            meta.env,
-            Default::default(),
-            Default::default(),
-        );
+        add_crate_root(&mut crate_graph, file_id, meta, &FxHashMap::default());
         crate_graph
     } else {
         let mut crate_graph = CrateGraph::default();
@@ -96,23 +143,41 @@ fn with_single_file(db: &mut dyn SourceDatabaseExt, ra_fixture: &str) -> FileId
     file_id
 }
 
-fn with_files(db: &mut dyn SourceDatabaseExt, fixture: &str) -> Option<FilePosition> {
+fn with_files(
+    db: &mut dyn SourceDatabaseExt,
+    fixture: &str,
+) -> (Option<FilePosition>, FixtureSteps) {
     let fixture = parse_fixture(fixture);
+    let metas = fixture.iter().map(|entry| parse_meta(&entry.meta)).collect::<Vec<_>>();
+
+    // `out_dir` may point at a root declared further down, so the roots are
+    // numbered upfront.
+    let extern_roots = metas
+        .iter()
+        .filter_map(|meta| match meta {
+            ParsedMeta::Root { path } => Some(path.as_str().trim_end_matches('/').to_string()),
+            ParsedMeta::File(_) => None,
+        })
+        .enumerate()
+        .map(|(idx, path)| (path, ExternSourceId(idx as u32 + 1)))
+        .collect::<FxHashMap<_, _>>();
 
     let mut crate_graph = CrateGraph::default();
     let mut crates = FxHashMap::default();
     let mut crate_deps = Vec::new();
-    let mut default_crate_root: Option<FileId> = None;
+    let mut default_crate: Option<(FileId, FileMeta)> = None;
 
     let mut source_root = SourceRoot::new_local();
     let mut source_root_id = WORKSPACE;
     let mut source_root_prefix: RelativePathBuf = "/".into();
     let mut file_id = FileId(0);
+    let mut file_ids = FxHashMap::default();
 
     let mut file_position = None;
+    let mut steps = FixtureSteps::default();
 
-    for entry in fixture.iter() {
-        let meta = match parse_meta(&entry.meta) {
+    for (entry, meta) in fixture.iter().zip(metas) {
+        let meta = match meta {
             ParsedMeta::Root { path } => {
                 let source_root = std::mem::replace(&mut source_root, SourceRoot::new_local());
                 db.set_source_root(source_root_id, Arc::new(source_root));
@@ -122,26 +187,37 @@ fn with_files(db: &mut dyn SourceDatabaseExt, fixture: &str) -> Option<FilePosit
             }
             ParsedMeta::File(it) => it,
         };
+
+        if let Some(step) = meta.step {
+            assert!(!entry.text.contains(CURSOR_MARKER), "steps can't contain a marker (<|>)");
+            let changed_file = match file_ids.get(&meta.path) {
+                Some(&it) => it,
+                None => panic!("{} is changed before being declared", meta.path),
+            };
+            steps.add(step, changed_file, entry.text.to_string());
+            continue;
+        }
         assert!(meta.path.starts_with(&source_root_prefix));
 
-        if let Some(krate) = meta.krate {
-            let crate_id = crate_graph.add_crate_root(
-                file_id,
-                meta.edition,
-                Some(CrateName::new(&krate).unwrap()),
-                meta.cfg,
-                meta.env,
-                Default::default(),
-                Default::default(),
-            );
-            let prev = crates.insert(krate.clone(), crate_id);
-            assert!(prev.is_none());
-            for dep in meta.deps {
-                crate_deps.push((krate.clone(), dep))
+        // Like in the real VFS, files outside of the workspace are relative to
+        // their root.
+        let path = if source_root_id == WORKSPACE {
+            meta.path.clone()
+        } else {
+            RelativePathBuf::from(&meta.path.as_str()[source_root_prefix.as_str().len()..])
+        };
+        file_ids.insert(meta.path.clone(), file_id);
+
+        if let Some(krate) = meta.krate.clone() {
+            for dep in meta.deps.iter() {
+                crate_deps.push((krate.clone(), dep.clone()))
             }
+            let crate_id = add_crate_root(&mut crate_graph, file_id, meta, &extern_roots);
+            let prev = crates.insert(krate, crate_id);
+            assert!(prev.is_none());
         } else if meta.path == "/main.rs" || meta.path == "/lib.rs" {
-            assert!(default_crate_root.is_none());
-            default_crate_root = Some(file_id);
+            assert!(default_crate.is_none());
+            default_crate = Some((file_id, meta));
         }
 
         let text = if entry.text.contains(CURSOR_MARKER) {
@@ -154,24 +230,16 @@ fn with_files(db: &mut dyn SourceDatabaseExt, fixture: &str) -> Option<FilePosit
         };
 
         db.set_file_text(file_id, Arc::new(text));
-        db.set_file_relative_path(file_id, meta.path.clone());
+        db.set_file_relative_path(file_id, path.clone());
         db.set_file_source_root(file_id, source_root_id);
-        source_root.insert_file(meta.path, file_id);
+        source_root.insert_file(path, file_id);
 
         file_id.0 += 1;
     }
 
     if crates.is_empty() {
-        let crate_root = default_crate_root.unwrap();
-        crate_graph.add_crate_root(
-            crate_root,
-            Edition::Edition2018,
-            None,
-            CfgOptions::default(),
-            Env::default(),
-            Default::default(),
-            Default::default(),
-        );
+        let (crate_root, meta) = default_crate.unwrap();
+        add_crate_root(&mut crate_graph, crate_root, meta, &extern_roots);
     } else {
         for (from, to) in crate_deps {
             let from_id = crates[&from];
@@ -183,7 +251,42 @@ fn with_files(db: &mut dyn SourceDatabaseExt, fixture: &str) -> Option<FilePosit
     db.set_source_root(source_root_id, Arc::new(source_root));
     db.set_crate_graph(Arc::new(crate_graph));
 
-    file_position
+    (file_position, steps)
+}
+
+fn add_crate_root(
+    crate_graph: &mut CrateGraph,
+    file_id: FileId,
+    meta: FileMeta,
+    extern_roots: &FxHashMap<String, ExternSourceId>,
+) -> CrateId {
+    let mut env = meta.env;
+    let mut extern_source = ExternSource::default();
+    if let Some(out_dir) = meta.out_dir {
+        let out_dir = out_dir.trim_end_matches('/');
+        let root = match extern_roots.get(out_dir) {
+            Some(&it) => it,
+            None => panic!("out_dir {} is not a root", out_dir),
+        };
+        extern_source.set_extern_path(Path::new(out_dir), root);
+        env.set("OUT_DIR", out_dir.to_string());
+    }
+    crate_graph.add_crate_root(
+        file_id,
+        meta.edition,
+        meta.krate.map(|name| {
+            CrateName::new(&name).expect("Fixture crate name should not contain dashes")
+        }),
+        meta.cfg,
+        env,
+        extern_source,
+        meta.proc_macros
+            .into_iter()
+            .map(|name| {
+                (SmolStr::new(name), Arc::new(EmptyDeriveExpander) as Arc<dyn TokenExpander>)
+            })
+            .collect(),
+    )
 }
 
 enum ParsedMeta {
@@ -198,9 +301,12 @@ struct FileMeta {
     cfg: CfgOptions,
     edition: Edition,
     env: Env,
+    proc_macros: Vec<String>,
+    out_dir: Option<String>,
+    step: Option<usize>,
 }
 
-//- /lib.rs crate:foo deps:bar,baz cfg:foo=a,bar=b env:OUTDIR=path/to,OTHER=foo)
+//- /lib.rs crate:foo deps:bar,baz cfg:foo=a,bar=b env:OUTDIR=path/to,OTHER=foo proc_macros:A,B out_dir:/out step:1
 fn parse_meta(meta: &str) -> ParsedMeta {
     let components = meta.split_ascii_whitespace().collect::<Vec<_>>();
 
@@ -218,6 +324,9 @@ fn parse_meta(meta: &str) -> ParsedMeta {
     let mut edition = Edition::Edition2018;
     let mut cfg = CfgOptions::default();
     let mut env = Env::default();
+    let mut proc_macros = Vec::new();
+    let mut out_dir = None;
+    let mut step = None;
     for component in components[1..].iter() {
         let (key, value) = split1(component, ':').unwrap();
         match key {
@@ -239,11 +348,14 @@ fn parse_meta(meta: &str) -> ParsedMeta {
                     }
                 }
             }
+            "proc_macros" => proc_macros = value.split(',').map(|it| it.to_string()).collect(),
+            "out_dir" => out_dir = Some(value.to_string()),
+            "step" => step = Some(value.parse().unwrap()),
             _ => panic!("bad component: {:?}", component),
         }
     }
 
-    ParsedMeta::File(FileMeta { path, krate, deps, edition, cfg, env })
+    ParsedMeta::File(FileMeta { path, krate, deps, edition, cfg, env, proc_macros, out_dir, step })
 }
 
 fn split1(haystack: &str, delim: char) -> Option<(&str, &str)> {
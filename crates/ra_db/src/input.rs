@@ -9,11 +9,11 @@
 use std::{
     fmt, ops,
     path::{Path, PathBuf},
-    str::FromStr,
     sync::Arc,
 };
 
 use ra_cfg::CfgOptions;
+pub use ra_syntax::Edition;
 use ra_syntax::SmolStr;
 use rustc_hash::FxHashMap;
 use rustc_hash::FxHashSet;
@@ -148,12 +148,6 @@ pub struct CrateData {
     pub proc_macro: Vec<ProcMacro>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Edition {
-    Edition2018,
-    Edition2015,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ExternSourceId(pub u32);
 
@@ -289,28 +283,6 @@ impl CrateData {
     }
 }
 
-impl FromStr for Edition {
-    type Err = ParseEditionError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let res = match s {
-            "2015" => Edition::Edition2015,
-            "2018" => Edition::Edition2018,
-            _ => return Err(ParseEditionError { invalid_input: s.to_string() }),
-        };
-        Ok(res)
-    }
-}
-
-impl fmt::Display for Edition {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            Edition::Edition2015 => "2015",
-            Edition::Edition2018 => "2018",
-        })
-    }
-}
-
 impl Env {
     pub fn set(&mut self, env: &str, value: String) {
         self.entries.insert(env.to_owned(), value);
@@ -339,19 +311,6 @@ impl ExternSource {
     }
 }
 
-#[derive(Debug)]
-pub struct ParseEditionError {
-    invalid_input: String,
-}
-
-impl fmt::Display for ParseEditionError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "invalid edition: {:?}", self.invalid_input)
-    }
-}
-
-impl std::error::Error for ParseEditionError {}
-
 #[derive(Debug)]
 pub struct CyclicDependenciesError;
 
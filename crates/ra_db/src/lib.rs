@@ -6,7 +6,7 @@ pub mod fixture;
 use std::{panic, sync::Arc};
 
 use ra_prof::profile;
-use ra_syntax::{ast, Parse, SourceFile, TextRange, TextUnit};
+use ra_syntax::{ast, Parse, SourceFile, SyntaxError, TextRange, TextUnit};
 
 pub use crate::{
     cancellation::Canceled,
@@ -108,6 +108,10 @@ pub trait SourceDatabase: CheckCanceled + FileLoader + std::fmt::Debug {
     #[salsa::invoke(parse_query)]
     fn parse(&self, file_id: FileId) -> Parse<ast::SourceFile>;
 
+    /// Syntax errors of the file which depend on the edition of `krate`.
+    #[salsa::invoke(edition_errors_query)]
+    fn edition_errors(&self, file_id: FileId, krate: CrateId) -> Arc<Vec<SyntaxError>>;
+
     /// The crate graph.
     #[salsa::input]
     fn crate_graph(&self) -> Arc<CrateGraph>;
@@ -119,6 +123,15 @@ fn parse_query(db: &impl SourceDatabase, file_id: FileId) -> Parse<ast::SourceFi
     SourceFile::parse(&*text)
 }
 
+fn edition_errors_query(
+    db: &impl SourceDatabase,
+    file_id: FileId,
+    krate: CrateId,
+) -> Arc<Vec<SyntaxError>> {
+    let edition = db.crate_graph()[krate].edition;
+    Arc::new(db.parse(file_id).tree().edition_errors(edition))
+}
+
 /// We don't want to give HIR knowledge of source roots, hence we extract these
 /// methods into a separate DB.
 #[salsa::query_group(SourceDatabaseExtStorage)]
@@ -12,9 +12,9 @@ use hir_def::{
     per_ns::PerNs,
     resolver::{HasResolver, Resolver},
     type_ref::{Mutability, TypeRef},
-    AdtId, AssocContainerId, ConstId, DefWithBodyId, EnumId, FunctionId, GenericDefId, HasModule,
-    ImplId, LocalEnumVariantId, LocalModuleId, LocalStructFieldId, Lookup, ModuleId, StaticId,
-    StructId, TraitId, TypeAliasId, TypeParamId, UnionId,
+    AdtId, AssocContainerId, ConstId, DefWithBodyId, EnumId, EnumVariantId, FunctionId,
+    GenericDefId, HasModule, ImplId, LocalEnumVariantId, LocalModuleId, LocalStructFieldId, Lookup,
+    ModuleId, StaticId, StructId, TraitId, TypeAliasId, TypeParamId, UnionId,
 };
 use hir_expand::{
     diagnostics::DiagnosticSink,
@@ -23,7 +23,7 @@ use hir_expand::{
 };
 use hir_ty::{
     autoderef, display::HirFormatter, expr::ExprValidator, method_resolution, ApplicationTy,
-    Canonical, InEnvironment, Substs, TraitEnvironment, Ty, TyDefId, TypeCtor,
+    BindingMode, Canonical, InEnvironment, Substs, TraitEnvironment, Ty, TyDefId, TypeCtor,
 };
 use ra_db::{CrateId, Edition, FileId};
 use ra_prof::profile;
@@ -141,6 +141,61 @@ impl ModuleDef {
             ModuleDef::BuiltinType(_) => None,
         }
     }
+
+    /// The modules from which this def can be named, either where it is
+    /// defined or through a re-export.
+    pub fn visible_from(self, db: &dyn HirDatabase) -> VisibleFrom {
+        let module = match self.module(db) {
+            Some(it) => it,
+            None => return VisibleFrom::Everywhere,
+        };
+        let assoc = match self {
+            ModuleDef::Function(it) => it.as_assoc_item(db),
+            ModuleDef::Const(it) => it.as_assoc_item(db),
+            ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+            _ => None,
+        };
+        let res = match assoc.map(|it| (it, it.container(db))) {
+            Some((_, AssocItemContainer::Trait(trait_))) => {
+                return ModuleDef::Trait(trait_).visible_from(db)
+            }
+            Some((assoc, AssocItemContainer::ImplDef(_))) => {
+                hir_def::visibility::visible_from_module(
+                    db.upcast(),
+                    assoc.visibility(db),
+                    module.id,
+                )
+            }
+            None => hir_def::visibility::visible_from(db.upcast(), self.into(), module.id.krate),
+        };
+        res.into()
+    }
+}
+
+/// The modules from which a def can be named.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VisibleFrom {
+    /// Other crates can name the def as well.
+    Everywhere,
+    /// Only the given modules and their descendants can name the def.
+    Modules(Vec<Module>),
+}
+
+impl From<hir_def::visibility::VisibleFrom> for VisibleFrom {
+    fn from(it: hir_def::visibility::VisibleFrom) -> VisibleFrom {
+        match it {
+            hir_def::visibility::VisibleFrom::Everywhere => VisibleFrom::Everywhere,
+            hir_def::visibility::VisibleFrom::Modules(modules) => {
+                VisibleFrom::Modules(modules.into_iter().map(|id| Module { id }).collect())
+            }
+        }
+    }
+}
+
+impl From<Visibility> for VisibleFrom {
+    fn from(vis: Visibility) -> VisibleFrom {
+        hir_def::visibility::VisibleFrom::from(vis).into()
+    }
 }
 
 pub use hir_def::{
@@ -325,14 +380,18 @@ impl StructField {
     pub fn parent_def(&self, _db: &dyn HirDatabase) -> VariantDef {
         self.parent
     }
+
+    /// Whether reading the field requires an `unsafe` block, which is the
+    /// case for union fields.
+    pub fn is_unsafe_to_read(&self) -> bool {
+        hir_def::StructFieldId::from(*self).is_unsafe_to_read()
+    }
 }
 
 impl HasVisibility for StructField {
     fn visibility(&self, db: &dyn HirDatabase) -> Visibility {
-        let variant_data = self.parent.variant_data(db);
-        let visibility = &variant_data.fields()[self.id].visibility;
         let parent_id: hir_def::VariantId = self.parent.into();
-        visibility.resolve(db.upcast(), &parent_id.resolver(db.upcast()))
+        db.field_visibilities(parent_id)[self.id]
     }
 }
 
@@ -462,11 +521,22 @@ impl EnumVariant {
     }
 
     pub fn kind(self, db: &dyn HirDatabase) -> StructKind {
-        self.variant_data(db).kind()
+        db.enum_data(self.parent.id).variants[self.id].kind
+    }
+
+    /// The value of the discriminant of the variant, if it can be evaluated.
+    /// Only the variants of enums without fields, which can be cast to an
+    /// integer, have a meaningful one.
+    pub fn discriminant(self, db: &dyn HirDatabase) -> Option<i128> {
+        let enum_data = db.enum_data(self.parent.id);
+        if enum_data.variants.iter().any(|(_, it)| it.kind != StructKind::Unit) {
+            return None;
+        }
+        db.enum_discriminants(self.parent.id).get(self.id).copied()
     }
 
     pub(crate) fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
-        db.enum_data(self.parent.id).variants[self.id].variant_data.clone()
+        db.enum_variant_data(EnumVariantId { parent: self.parent.id, local_id: self.id })
     }
 }
 
@@ -505,6 +575,12 @@ impl Adt {
         Some(self.module(db).krate())
     }
 
+    /// Whether this ADT derives or explicitly implements `Default`. This is a
+    /// purely syntactic check and doesn't involve trait solving.
+    pub fn has_default_impl(self, db: &dyn HirDatabase) -> bool {
+        db.adt_has_default_impl(self.into())
+    }
+
     pub fn name(&self, db: &dyn HirDatabase) -> Name {
         match self {
             Adt::Struct(s) => s.name(db),
@@ -616,6 +692,7 @@ impl Function {
         infer.add_diagnostics(db, self.id, sink);
         let mut validator = ExprValidator::new(self.id, infer, sink);
         validator.validate_body(db);
+        validator.validate_unused_bindings(db);
     }
 }
 
@@ -691,6 +768,17 @@ impl Trait {
         db.trait_data(self.id).items.iter().map(|(_name, it)| (*it).into()).collect()
     }
 
+    /// The items of this trait and of all its super traits, where items of
+    /// super traits are shadowed by same-named items of their sub traits.
+    pub fn items_with_super_traits(self, db: &dyn HirDatabase) -> Vec<AssocItem> {
+        db.trait_items_with_super_traits(self.id).iter().map(|(_name, it)| (*it).into()).collect()
+    }
+
+    /// The direct super traits of this trait.
+    pub fn super_traits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        db.super_traits(self.id).iter().map(|&id| Trait { id }).collect()
+    }
+
     pub fn is_auto(self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).auto
     }
@@ -898,6 +986,13 @@ impl Local {
         }
     }
 
+    /// The binding mode after match ergonomics, which is `ref` for `x` in
+    /// `let Some(x) = &opt`.
+    pub fn binding_mode(self, db: &dyn HirDatabase) -> BindingMode {
+        let infer = db.infer(self.parent.into());
+        infer.binding_mode(self.pat_id).unwrap_or_default()
+    }
+
     pub fn parent(self, _db: &dyn HirDatabase) -> DefWithBody {
         self.parent.into()
     }
@@ -952,8 +1047,16 @@ impl ImplDef {
         impls.all_impls().map(Self::from).collect()
     }
     pub fn for_trait(db: &dyn HirDatabase, krate: Crate, trait_: Trait) -> Vec<ImplDef> {
-        let impls = db.impls_in_crate(krate.id);
-        impls.lookup_impl_defs_for_trait(trait_.id).map(Self::from).collect()
+        db.trait_impls_in_crate(krate.id, trait_.id).iter().copied().map(Self::from).collect()
+    }
+
+    /// Impls of the trait in any crate of the crate graph.
+    pub fn for_trait_in_all_crates(db: &dyn HirDatabase, trait_: Trait) -> Vec<ImplDef> {
+        let mut res = Vec::new();
+        for krate in db.crate_graph().iter() {
+            res.extend(db.trait_impls_in_crate(krate, trait_.id).iter().copied().map(Self::from));
+        }
+        res
     }
 
     pub fn target_trait(&self, db: &dyn HirDatabase) -> Option<TypeRef> {
@@ -1059,6 +1162,14 @@ impl Type {
         matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Bool, .. }))
     }
 
+    pub fn is_integral(&self) -> bool {
+        matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Int(_), .. }))
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Float(_), .. }))
+    }
+
     pub fn is_mutable_reference(&self) -> bool {
         matches!(
             self.ty.value,
@@ -1112,6 +1223,27 @@ impl Type {
         db.trait_solve(self.krate, goal).is_some()
     }
 
+    /// Checks whether the types could be equal, treating unknown types as
+    /// equal to anything.
+    pub fn could_unify_with(&self, other: &Type) -> bool {
+        let tys = Canonical { value: (self.ty.value.clone(), other.ty.value.clone()), num_vars: 0 };
+        hir_ty::could_unify(&tys)
+    }
+
+    /// Checks whether a value of this type can be used where a value of the
+    /// `target` type is expected, allowing implicit coercions like `&mut T` to
+    /// `&T`, deref coercions and unsizing.
+    pub fn is_coercible_to(&self, db: &dyn HirDatabase, target: &Type) -> bool {
+        let tys = Canonical {
+            value: InEnvironment::new(
+                self.ty.environment.clone(),
+                (self.ty.value.clone(), target.ty.value.clone()),
+            ),
+            num_vars: 0,
+        };
+        db.could_coerce(self.krate, tys)
+    }
+
     // FIXME: this method is broken, as it doesn't take closures into account.
     pub fn as_callable(&self) -> Option<CallableDef> {
         Some(self.ty.value.as_callable()?.0)
@@ -1131,8 +1263,12 @@ impl Type {
 
     pub fn fields(&self, db: &dyn HirDatabase) -> Vec<(StructField, Type)> {
         if let Ty::Apply(a_ty) = &self.ty.value {
-            if let TypeCtor::Adt(AdtId::StructId(s)) = a_ty.ctor {
-                let var_def = s.into();
+            let var_def = match a_ty.ctor {
+                TypeCtor::Adt(AdtId::StructId(s)) => Some(hir_def::VariantId::from(s)),
+                TypeCtor::Adt(AdtId::UnionId(u)) => Some(hir_def::VariantId::from(u)),
+                _ => None,
+            };
+            if let Some(var_def) = var_def {
                 return db
                     .field_types(var_def)
                     .iter()
@@ -1287,6 +1423,7 @@ impl HirDisplay for Type {
 }
 
 /// For IDE only
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScopeDef {
     ModuleDef(ModuleDef),
     MacroDef(MacroDef),
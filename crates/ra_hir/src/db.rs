@@ -1,24 +1,27 @@
 //! FIXME: write short doc here
 
 pub use hir_def::db::{
-    AttrsQuery, BodyQuery, BodyWithSourceMapQuery, ConstDataQuery, CrateDefMapQueryQuery,
-    CrateLangItemsQuery, DefDatabase, DefDatabaseStorage, DocumentationQuery, EnumDataQuery,
-    ExprScopesQuery, FunctionDataQuery, GenericParamsQuery, ImplDataQuery, InternConstQuery,
+    AdtHasDefaultImplQuery, AllSuperTraitsQuery, AttrsQuery, BodyQuery, BodyWithSourceMapQuery,
+    ConstDataQuery, CrateDefMapQueryQuery, CrateLangItemsQuery, DefDatabase, DefDatabaseStorage,
+    DocumentationQuery, EnumDataQuery, EnumVariantDataQuery, ExprScopesQuery,
+    FieldVisibilitiesQuery, FunctionDataQuery, GenericParamsQuery, ImplDataQuery, InternConstQuery,
     InternDatabase, InternDatabaseStorage, InternEnumQuery, InternFunctionQuery, InternImplQuery,
     InternStaticQuery, InternStructQuery, InternTraitQuery, InternTypeAliasQuery, InternUnionQuery,
     LangItemQuery, ModuleLangItemsQuery, RawItemsQuery, StaticDataQuery, StructDataQuery,
-    TraitDataQuery, TypeAliasDataQuery, UnionDataQuery,
+    SuperTraitsQuery, TraitDataQuery, TraitItemsWithSuperTraitsQuery, TypeAliasDataQuery,
+    UnionDataQuery, VisibleFieldsQuery,
 };
 pub use hir_expand::db::{
     AstDatabase, AstDatabaseStorage, AstIdMapQuery, InternEagerExpansionQuery, InternMacroQuery,
     MacroArgQuery, MacroDefQuery, MacroExpandQuery, ParseMacroQuery,
 };
 pub use hir_ty::db::{
-    AssociatedTyDataQuery, AssociatedTyValueQuery, CallableItemSignatureQuery, FieldTypesQuery,
-    GenericDefaultsQuery, GenericPredicatesForParamQuery, GenericPredicatesQuery, HirDatabase,
-    HirDatabaseStorage, ImplDatumQuery, ImplSelfTyQuery, ImplTraitQuery, ImplsForTraitQuery,
-    ImplsInCrateQuery, InferQueryQuery, InternAssocTyValueQuery, InternChalkImplQuery,
-    InternTypeCtorQuery, InternTypeParamIdQuery, StructDatumQuery, TraitDatumQuery,
+    AssociatedTyDataQuery, AssociatedTyValueQuery, CallableItemSignatureQuery, ConstEvalQuery,
+    CouldCoerceQuery, EnumDiscriminantsQuery, FieldTypesQuery, GenericDefaultsQuery,
+    GenericPredicatesForParamQuery, GenericPredicatesQuery, HirDatabase, HirDatabaseStorage,
+    ImplDatumQuery, ImplSelfTyQuery, ImplTraitQuery, ImplsForTraitQuery, ImplsInCrateQuery,
+    InferQueryQuery, InternAssocTyValueQuery, InternChalkImplQuery, InternTypeCtorQuery,
+    InternTypeParamIdQuery, StructDatumQuery, TraitDatumQuery, TraitImplsInCrateQuery,
     TraitSolveQuery, TyQuery, ValueTyQuery,
 };
 
@@ -1,4 +1,9 @@
 //! FIXME: write short doc here
-pub use hir_def::diagnostics::UnresolvedModule;
+pub use hir_def::diagnostics::{MacroRulesWarning, MalformedCfg, UnresolvedModule};
 pub use hir_expand::diagnostics::{AstDiagnostic, Diagnostic, DiagnosticSink};
-pub use hir_ty::diagnostics::{MissingFields, MissingMatchArms, MissingOkInTailExpr, NoSuchField};
+pub use hir_ty::diagnostics::{
+    IndexOutOfBounds, MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkInTailExpr,
+    NoSuchField, NotAnIterator, PatternTypeMismatch, PrivateItem, PrivateItemAccess,
+    TryInNonTryFunction, UnresolvedMethodCall, UnresolvedPath, UnsatisfiedTraitBound,
+    UnusedMustUse, UnusedVariable, UseOfMovedValue,
+};
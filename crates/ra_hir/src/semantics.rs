@@ -13,8 +13,8 @@ use itertools::Itertools;
 use ra_db::{FileId, FileRange};
 use ra_prof::profile;
 use ra_syntax::{
-    algo::{find_node_at_offset, skip_trivia_token},
-    ast, AstNode, Direction, SyntaxNode, SyntaxToken, TextRange, TextUnit,
+    algo::{find_covering_element, find_node_at_offset, skip_trivia_token},
+    ast, AstNode, Direction, NodeOrToken, SyntaxNode, SyntaxToken, TextRange, TextUnit,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 
@@ -22,8 +22,9 @@ use crate::{
     db::HirDatabase,
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
-    AssocItem, Function, HirFileId, ImplDef, InFile, Local, MacroDef, Module, ModuleDef, Name,
-    Origin, Path, ScopeDef, StructField, Trait, Type, TypeParam,
+    Adjustment, AssocItem, Function, HirFileId, ImplDef, InFile, Local, MacroDef, Module,
+    ModuleDef, Name, Origin, Path, ReceiverAdjustments, ScopeDef, StructField, Trait, Type,
+    TypeParam,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -176,6 +177,20 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.analyze(call.syntax()).resolve_method_call(self.db, call)
     }
 
+    /// The autoderef and autoref steps applied to the receiver of a method call.
+    pub fn method_receiver_adjustments(
+        &self,
+        call: &ast::MethodCallExpr,
+    ) -> Option<ReceiverAdjustments> {
+        self.analyze(call.syntax()).method_receiver_adjustments(self.db, call)
+    }
+
+    /// The implicit conversions applied to an expression, like autoderefs,
+    /// autorefs and coercions, in the order they are applied.
+    pub fn expr_adjustments(&self, expr: &ast::Expr) -> Vec<Adjustment> {
+        self.analyze(expr.syntax()).expr_adjustments(self.db, expr).unwrap_or_default()
+    }
+
     pub fn resolve_field(&self, field: &ast::FieldExpr) -> Option<StructField> {
         self.analyze(field.syntax()).resolve_field(self.db, field)
     }
@@ -407,11 +422,19 @@ impl<'a, DB: HirDatabase> SemanticsScope<'a, DB> {
 
 // FIXME: Change `HasSource` trait to work with `Semantics` and remove this?
 pub fn original_range(db: &dyn HirDatabase, node: InFile<&SyntaxNode>) -> FileRange {
+    if let Some(range) = node.included_file_range(db.upcast()) {
+        return range;
+    }
     if let Some(range) = original_range_opt(db, node) {
         let original_file = range.file_id.original_file(db.upcast());
         if range.file_id == original_file.into() {
             return FileRange { file_id: original_file, range: range.value };
         }
+        // Tokens of a macro call in an included file map up to the expansion
+        // of the `include!`.
+        if let Some(range) = included_file_range(db, range) {
+            return range;
+        }
 
         log::error!("Fail to mapping up more for {:?}", range);
         return FileRange { file_id: range.file_id.original_file(db.upcast()), range: range.value };
@@ -430,6 +453,15 @@ pub fn original_range(db: &dyn HirDatabase, node: InFile<&SyntaxNode>) -> FileRa
     FileRange { file_id: node.file_id.original_file(db.upcast()), range: node.value.text_range() }
 }
 
+fn included_file_range(db: &dyn HirDatabase, range: InFile<TextRange>) -> Option<FileRange> {
+    let root = db.parse_or_expand(range.file_id)?;
+    let node = match find_covering_element(&root, range.value) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    range.with_value(&node).included_file_range(db.upcast())
+}
+
 fn original_range_opt(
     db: &dyn HirDatabase,
     node: InFile<&SyntaxNode>,
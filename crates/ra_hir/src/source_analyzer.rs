@@ -19,7 +19,8 @@ use hir_def::{
 use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile};
 use hir_ty::{
     expr::{record_literal_missing_fields, record_pattern_missing_fields},
-    InferenceResult, Substs, Ty,
+    method_resolution::ReceiverAdjustments,
+    Adjustment, InferenceResult, Substs, Ty,
 };
 use ra_syntax::{
     ast::{self, AstNode},
@@ -135,6 +136,24 @@ impl SourceAnalyzer {
         self.infer.as_ref()?.method_resolution(expr_id).map(Function::from)
     }
 
+    pub(crate) fn method_receiver_adjustments(
+        &self,
+        db: &dyn HirDatabase,
+        call: &ast::MethodCallExpr,
+    ) -> Option<ReceiverAdjustments> {
+        let expr_id = self.expr_id(db, &call.clone().into())?;
+        self.infer.as_ref()?.method_receiver_adjustments(expr_id)
+    }
+
+    pub(crate) fn expr_adjustments(
+        &self,
+        db: &dyn HirDatabase,
+        expr: &ast::Expr,
+    ) -> Option<Vec<Adjustment>> {
+        let expr_id = self.expr_id(db, expr)?;
+        Some(self.infer.as_ref()?.expr_adjustments(expr_id).to_vec())
+    }
+
     pub(crate) fn resolve_field(
         &self,
         db: &dyn HirDatabase,
@@ -225,6 +244,7 @@ impl SourceAnalyzer {
         literal: &ast::RecordLit,
     ) -> Option<Vec<(StructField, Type)>> {
         let krate = self.resolver.krate()?;
+        let module = self.resolver.module()?;
         let body = self.body.as_ref()?;
         let infer = self.infer.as_ref()?;
 
@@ -235,7 +255,7 @@ impl SourceAnalyzer {
         };
 
         let (variant, missing_fields, _exhaustive) =
-            record_literal_missing_fields(db, infer, expr_id, &body[expr_id])?;
+            record_literal_missing_fields(db, infer, expr_id, &body[expr_id], module)?;
         let res = self.missing_fields(db, krate, substs, variant, missing_fields);
         Some(res)
     }
@@ -246,6 +266,7 @@ impl SourceAnalyzer {
         pattern: &ast::RecordPat,
     ) -> Option<Vec<(StructField, Type)>> {
         let krate = self.resolver.krate()?;
+        let module = self.resolver.module()?;
         let body = self.body.as_ref()?;
         let infer = self.infer.as_ref()?;
 
@@ -256,7 +277,7 @@ impl SourceAnalyzer {
         };
 
         let (variant, missing_fields) =
-            record_pattern_missing_fields(db, infer, pat_id, &body[pat_id])?;
+            record_pattern_missing_fields(db, infer, pat_id, &body[pat_id], module)?;
         let res = self.missing_fields(db, krate, substs, variant, missing_fields);
         Some(res)
     }
@@ -365,6 +386,7 @@ pub(crate) fn resolve_hir_path(
                 ValueNs::StaticId(it) => PathResolution::Def(Static::from(it).into()),
                 ValueNs::StructId(it) => PathResolution::Def(Struct::from(it).into()),
                 ValueNs::EnumVariantId(it) => PathResolution::Def(EnumVariant::from(it).into()),
+                ValueNs::ConstParam(id) => PathResolution::TypeParam(TypeParam { id }),
             };
             Some(res)
         });
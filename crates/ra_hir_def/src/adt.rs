@@ -4,17 +4,26 @@ use std::sync::Arc;
 
 use either::Either;
 use hir_expand::{
-    name::{AsName, Name},
+    hygiene::Hygiene,
+    name::{name, AsName, Name},
     InFile,
 };
 use ra_arena::{map::ArenaMap, Arena};
+use ra_cfg::CfgOptions;
 use ra_prof::profile;
 use ra_syntax::ast::{self, NameOwner, TypeAscriptionOwner, VisibilityOwner};
 
 use crate::{
-    db::DefDatabase, src::HasChildSource, src::HasSource, trace::Trace, type_ref::TypeRef,
-    visibility::RawVisibility, EnumId, LocalEnumVariantId, LocalStructFieldId, Lookup, StructId,
-    UnionId, VariantId,
+    attr::Attrs,
+    db::DefDatabase,
+    resolver::{HasResolver, TypeNs},
+    src::HasChildSource,
+    src::HasSource,
+    trace::Trace,
+    type_ref::{ConstRef, TypeRef},
+    visibility::RawVisibility,
+    AdtId, AttrDefId, EnumId, EnumVariantId, HasModule, LocalEnumVariantId, LocalStructFieldId,
+    Lookup, StructId, UnionId, VariantId,
 };
 
 /// Note that we use `StructData` for unions as well!
@@ -22,18 +31,26 @@ use crate::{
 pub struct StructData {
     pub name: Name,
     pub variant_data: Arc<VariantData>,
+    pub attrs: Attrs,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnumData {
     pub name: Name,
     pub variants: Arena<EnumVariantData>,
+    pub attrs: Attrs,
 }
 
+/// The fields of a variant are lowered separately, by
+/// `DefDatabase::enum_variant_data`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnumVariantData {
     pub name: Name,
-    pub variant_data: Arc<VariantData>,
+    pub kind: StructKind,
+    /// The explicit discriminant, like the `3` in `A = 3`. It is evaluated
+    /// by `hir_ty`.
+    pub discriminant: Option<ConstRef>,
+    pub attrs: Attrs,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -49,20 +66,32 @@ pub struct StructFieldData {
     pub name: Name,
     pub type_ref: TypeRef,
     pub visibility: RawVisibility,
+    pub attrs: Attrs,
 }
 
 impl StructData {
     pub(crate) fn struct_data_query(db: &dyn DefDatabase, id: StructId) -> Arc<StructData> {
-        let src = id.lookup(db).source(db);
+        let loc = id.lookup(db);
+        let src = loc.source(db);
+        let crate_graph = db.crate_graph();
+        let cfg_options = &crate_graph[loc.container.module(db).krate].cfg_options;
 
         let name = src.value.name().map_or_else(Name::missing, |n| n.as_name());
-        let variant_data = VariantData::new(db, src.map(|s| s.kind()));
+        let attrs = Attrs::new(&src.value, &Hygiene::new(db.upcast(), src.file_id))
+            .expand_cfg_attr(cfg_options);
+        let variant_data = VariantData::new(db, src.map(|s| s.kind()), cfg_options);
         let variant_data = Arc::new(variant_data);
-        Arc::new(StructData { name, variant_data })
+        Arc::new(StructData { name, variant_data, attrs })
     }
     pub(crate) fn union_data_query(db: &dyn DefDatabase, id: UnionId) -> Arc<StructData> {
-        let src = id.lookup(db).source(db);
+        let loc = id.lookup(db);
+        let src = loc.source(db);
+        let crate_graph = db.crate_graph();
+        let cfg_options = &crate_graph[loc.container.module(db).krate].cfg_options;
+
         let name = src.value.name().map_or_else(Name::missing, |n| n.as_name());
+        let attrs = Attrs::new(&src.value, &Hygiene::new(db.upcast(), src.file_id))
+            .expand_cfg_attr(cfg_options);
         let variant_data = VariantData::new(
             db,
             src.map(|s| {
@@ -70,20 +99,26 @@ impl StructData {
                     .map(ast::StructKind::Record)
                     .unwrap_or(ast::StructKind::Unit)
             }),
+            cfg_options,
         );
         let variant_data = Arc::new(variant_data);
-        Arc::new(StructData { name, variant_data })
+        Arc::new(StructData { name, variant_data, attrs })
     }
 }
 
 impl EnumData {
     pub(crate) fn enum_data_query(db: &dyn DefDatabase, e: EnumId) -> Arc<EnumData> {
         let _p = profile("enum_data_query");
-        let src = e.lookup(db).source(db);
+        let loc = e.lookup(db);
+        let src = loc.source(db);
+        let crate_graph = db.crate_graph();
+        let cfg_options = &crate_graph[loc.container.module(db).krate].cfg_options;
         let name = src.value.name().map_or_else(Name::missing, |n| n.as_name());
+        let attrs = Attrs::new(&src.value, &Hygiene::new(db.upcast(), src.file_id))
+            .expand_cfg_attr(cfg_options);
         let mut trace = Trace::new_for_arena();
-        lower_enum(db, &mut trace, &src);
-        Arc::new(EnumData { name, variants: trace.into_arena() })
+        lower_enum(db, &mut trace, &src, cfg_options);
+        Arc::new(EnumData { name, variants: trace.into_arena(), attrs })
     }
 
     pub fn variant(&self, name: &Name) -> Option<LocalEnumVariantId> {
@@ -92,13 +127,46 @@ impl EnumData {
     }
 }
 
+/// Whether the ADT derives `Default` or has an explicit `Default` impl. Only
+/// the crate of the ADT is searched, as the orphan rules don't allow
+/// implementing the foreign `Default` trait anywhere else.
+pub(crate) fn adt_has_default_impl_query(db: &dyn DefDatabase, adt: AdtId) -> bool {
+    let _p = profile("adt_has_default_impl_query");
+    let attrs = db.attrs(AttrDefId::AdtId(adt));
+    if attrs.derives().any(|path| path.segments.last() == Some(&name![Default])) {
+        return true;
+    }
+
+    let def_map = db.crate_def_map(adt.module(db).krate);
+    def_map.modules.iter().flat_map(|(_, module)| module.scope.impls()).any(|impl_id| {
+        let data = db.impl_data(impl_id);
+        let (target_trait, target_type) = match (&data.target_trait, &data.target_type) {
+            (Some(TypeRef::Path(target_trait)), TypeRef::Path(target_type)) => {
+                (target_trait, target_type)
+            }
+            _ => return false,
+        };
+        if data.is_negative || target_trait.mod_path().segments.last() != Some(&name![Default]) {
+            return false;
+        }
+        let resolver = impl_id.resolver(db);
+        match resolver.resolve_path_in_type_ns_fully(db, target_type.mod_path()) {
+            Some(TypeNs::AdtId(it)) => it == adt,
+            _ => false,
+        }
+    })
+}
+
 impl HasChildSource for EnumId {
     type ChildId = LocalEnumVariantId;
     type Value = ast::EnumVariant;
     fn child_source(&self, db: &dyn DefDatabase) -> InFile<ArenaMap<Self::ChildId, Self::Value>> {
-        let src = self.lookup(db).source(db);
+        let loc = self.lookup(db);
+        let src = loc.source(db);
+        let crate_graph = db.crate_graph();
+        let cfg_options = &crate_graph[loc.container.module(db).krate].cfg_options;
         let mut trace = Trace::new_for_map();
-        lower_enum(db, &mut trace, &src);
+        lower_enum(db, &mut trace, &src, cfg_options);
         src.with_value(trace.into_map())
     }
 }
@@ -107,22 +175,45 @@ fn lower_enum(
     db: &dyn DefDatabase,
     trace: &mut Trace<EnumVariantData, ast::EnumVariant>,
     ast: &InFile<ast::EnumDef>,
+    cfg_options: &CfgOptions,
 ) {
+    let hygiene = Hygiene::new(db.upcast(), ast.file_id);
     for var in ast.value.variant_list().into_iter().flat_map(|it| it.variants()) {
+        let attrs = Attrs::new(&var, &hygiene).expand_cfg_attr(cfg_options);
+        if !attrs.is_cfg_enabled(cfg_options) {
+            continue;
+        }
         trace.alloc(
             || var.clone(),
             || EnumVariantData {
                 name: var.name().map_or_else(Name::missing, |it| it.as_name()),
-                variant_data: Arc::new(VariantData::new(db, ast.with_value(var.kind()))),
+                kind: StructKind::from_ast(&var.kind()),
+                discriminant: var.expr().map(ConstRef::from_expr),
+                attrs,
             },
         );
     }
 }
 
 impl VariantData {
-    fn new(db: &dyn DefDatabase, flavor: InFile<ast::StructKind>) -> Self {
+    pub(crate) fn enum_variant_data_query(
+        db: &dyn DefDatabase,
+        id: EnumVariantId,
+    ) -> Arc<VariantData> {
+        let _p = profile("enum_variant_data_query");
+        let crate_graph = db.crate_graph();
+        let cfg_options = &crate_graph[id.parent.lookup(db).container.module(db).krate].cfg_options;
+        let src = id.parent.child_source(db).map(|map| map[id.local_id].kind());
+        Arc::new(VariantData::new(db, src, cfg_options))
+    }
+
+    fn new(
+        db: &dyn DefDatabase,
+        flavor: InFile<ast::StructKind>,
+        cfg_options: &CfgOptions,
+    ) -> Self {
         let mut trace = Trace::new_for_arena();
-        match lower_struct(db, &mut trace, &flavor) {
+        match lower_struct(db, &mut trace, &flavor, cfg_options) {
             StructKind::Tuple => VariantData::Tuple(trace.into_arena()),
             StructKind::Record => VariantData::Record(trace.into_arena()),
             StructKind::Unit => VariantData::Unit,
@@ -169,8 +260,10 @@ impl HasChildSource for VariantId {
                     .unwrap_or(ast::StructKind::Unit)
             }),
         };
+        let crate_graph = db.crate_graph();
+        let cfg_options = &crate_graph[self.module(db).krate].cfg_options;
         let mut trace = Trace::new_for_map();
-        lower_struct(db, &mut trace, &src);
+        lower_struct(db, &mut trace, &src, cfg_options);
         src.with_value(trace.into_map())
     }
 }
@@ -182,20 +275,37 @@ pub enum StructKind {
     Unit,
 }
 
+impl StructKind {
+    fn from_ast(kind: &ast::StructKind) -> StructKind {
+        match kind {
+            ast::StructKind::Tuple(_) => StructKind::Tuple,
+            ast::StructKind::Record(_) => StructKind::Record,
+            ast::StructKind::Unit => StructKind::Unit,
+        }
+    }
+}
+
 fn lower_struct(
     db: &dyn DefDatabase,
     trace: &mut Trace<StructFieldData, Either<ast::TupleFieldDef, ast::RecordFieldDef>>,
     ast: &InFile<ast::StructKind>,
+    cfg_options: &CfgOptions,
 ) -> StructKind {
+    let hygiene = Hygiene::new(db.upcast(), ast.file_id);
     match &ast.value {
         ast::StructKind::Tuple(fl) => {
-            for (i, fd) in fl.fields().enumerate() {
+            let fields = fl
+                .fields()
+                .map(|fd| (Attrs::new(&fd, &hygiene).expand_cfg_attr(cfg_options), fd))
+                .filter(|(attrs, _)| attrs.is_cfg_enabled(cfg_options));
+            for (i, (attrs, fd)) in fields.enumerate() {
                 trace.alloc(
                     || Either::Left(fd.clone()),
                     || StructFieldData {
                         name: Name::new_tuple_field(i),
                         type_ref: TypeRef::from_ast_opt(fd.type_ref()),
                         visibility: RawVisibility::from_ast(db, ast.with_value(fd.visibility())),
+                        attrs,
                     },
                 );
             }
@@ -203,12 +313,17 @@ fn lower_struct(
         }
         ast::StructKind::Record(fl) => {
             for fd in fl.fields() {
+                let attrs = Attrs::new(&fd, &hygiene).expand_cfg_attr(cfg_options);
+                if !attrs.is_cfg_enabled(cfg_options) {
+                    continue;
+                }
                 trace.alloc(
                     || Either::Right(fd.clone()),
                     || StructFieldData {
                         name: fd.name().map(|n| n.as_name()).unwrap_or_else(Name::missing),
                         type_ref: TypeRef::from_ast_opt(fd.ascribed_type()),
                         visibility: RawVisibility::from_ast(db, ast.with_value(fd.visibility())),
+                        attrs,
                     },
                 );
             }
@@ -217,3 +332,234 @@ fn lower_struct(
         ast::StructKind::Unit => StructKind::Unit,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ra_db::fixture::WithFixture;
+
+    use super::*;
+    use crate::{expr::ArithOp, test_db::TestDB, AdtId, ModuleDefId};
+
+    fn adt(db: &TestDB, name: &str) -> AdtId {
+        let krate = db.test_crate();
+        let def_map = db.crate_def_map(krate);
+        let def = def_map[def_map.root]
+            .scope
+            .entries()
+            .find(|(it, _)| it.to_string() == name)
+            .and_then(|(_, def)| def.take_types());
+        match def {
+            Some(ModuleDefId::AdtId(it)) => it,
+            _ => panic!("no ADT named `{}`", name),
+        }
+    }
+
+    fn derives(attrs: &Attrs) -> Vec<String> {
+        attrs.derives().map(|it| it.to_string()).collect()
+    }
+
+    #[test]
+    fn lowers_adt_attributes() {
+        let db = TestDB::with_files(
+            r#"
+            //- /lib.rs
+            #[derive(Clone, Debug)]
+            #[repr(C)]
+            #[non_exhaustive]
+            struct S(#[allow(unused)] u8, u16);
+
+            #[derive(PartialEq)]
+            enum E {
+                #[non_exhaustive]
+                A { #[doc = "field"] x: u8 },
+                B,
+            }
+            "#,
+        );
+
+        let s = match adt(&db, "S") {
+            AdtId::StructId(it) => db.struct_data(it),
+            _ => panic!(),
+        };
+        assert_eq!(derives(&s.attrs), vec!["Clone", "Debug"]);
+        assert!(s.attrs.by_key("repr").exists());
+        assert!(s.attrs.by_key("non_exhaustive").exists());
+        let fields: Vec<_> = s.variant_data.fields().iter().map(|(_, it)| it).collect();
+        assert!(fields[0].attrs.by_key("allow").exists());
+        assert!(fields[1].attrs.is_empty());
+
+        let enum_id = match adt(&db, "E") {
+            AdtId::EnumId(it) => it,
+            _ => panic!(),
+        };
+        let e = db.enum_data(enum_id);
+        assert_eq!(derives(&e.attrs), vec!["PartialEq"]);
+        let variant =
+            |name: &str| e.variants.iter().find(|(_, it)| it.name.to_string() == name).unwrap();
+        let (local_id, a) = variant("A");
+        assert!(a.attrs.by_key("non_exhaustive").exists());
+        let a_data = db.enum_variant_data(EnumVariantId { parent: enum_id, local_id });
+        let (_, x) = a_data.fields().iter().next().unwrap();
+        assert_eq!(x.attrs.by_key("doc").string_value().map(|it| it.as_str()), Some("field"));
+        let (_, b) = variant("B");
+        assert!(b.attrs.is_empty());
+    }
+
+    #[test]
+    fn skips_cfg_disabled_fields_and_variants() {
+        let db = TestDB::with_files(
+            r#"
+            //- /lib.rs crate:foo cfg:feature=std
+            struct S(#[cfg(feature = "alloc")] u8, u16);
+
+            struct R {
+                #[cfg(feature = "std")]
+                a: u8,
+                #[cfg(not(feature = "std"))]
+                b: u8,
+            }
+
+            enum E {
+                #[cfg(feature = "alloc")]
+                A,
+                B,
+            }
+            "#,
+        );
+
+        let field_names = |id: StructId| -> Vec<String> {
+            let data = db.struct_data(id);
+            data.variant_data.fields().iter().map(|(_, it)| it.name.to_string()).collect()
+        };
+        match adt(&db, "S") {
+            AdtId::StructId(it) => assert_eq!(field_names(it), vec!["0"]),
+            _ => panic!(),
+        }
+        match adt(&db, "R") {
+            AdtId::StructId(it) => assert_eq!(field_names(it), vec!["a"]),
+            _ => panic!(),
+        }
+        match adt(&db, "E") {
+            AdtId::EnumId(it) => {
+                let variants: Vec<_> =
+                    db.enum_data(it).variants.iter().map(|(_, it)| it.name.to_string()).collect();
+                assert_eq!(variants, vec!["B"]);
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn expands_cfg_attr() {
+        let db = TestDB::with_files(
+            r#"
+            //- /lib.rs crate:foo cfg:feature=std
+            #[cfg_attr(feature = "std", derive(Clone), repr(C))]
+            #[cfg_attr(feature = "alloc", derive(Debug))]
+            #[cfg_attr(feature = "std", cfg_attr(feature = "std", derive(PartialEq)))]
+            struct S(#[cfg_attr(feature = "std", cfg(feature = "alloc"))] u8, u16);
+            "#,
+        );
+        let s = match adt(&db, "S") {
+            AdtId::StructId(it) => db.struct_data(it),
+            _ => panic!(),
+        };
+        assert_eq!(derives(&s.attrs), vec!["Clone", "PartialEq"]);
+        assert!(s.attrs.by_key("repr").exists());
+        assert!(!s.attrs.by_key("cfg_attr").exists());
+        assert_eq!(s.variant_data.fields().len(), 1);
+    }
+
+    #[test]
+    fn lowers_variant_fields_on_demand() {
+        let db = TestDB::with_files(
+            r#"
+            //- /lib.rs
+            enum E {
+                A(u8, u16),
+                B { x: u32 },
+                C,
+            }
+            "#,
+        );
+        let enum_id = match adt(&db, "E") {
+            AdtId::EnumId(it) => it,
+            _ => panic!(),
+        };
+        let e = db.enum_data(enum_id);
+        let kinds: Vec<_> = e.variants.iter().map(|(_, it)| it.kind).collect();
+        assert_eq!(kinds, vec![StructKind::Tuple, StructKind::Record, StructKind::Unit]);
+
+        let (local_id, _) = e.variants.iter().next().unwrap();
+        let events = db.log_executed(|| {
+            let data = db.enum_variant_data(EnumVariantId { parent: enum_id, local_id });
+            assert_eq!(data.fields().len(), 2);
+        });
+        let executed = events.iter().filter(|it| it.contains("enum_variant_data")).count();
+        assert_eq!(executed, 1, "{:#?}", events);
+    }
+
+    #[test]
+    fn lowers_discriminants() {
+        let db = TestDB::with_files(
+            r#"
+            //- /lib.rs
+            enum E {
+                A = 3,
+                B,
+                C = -(1 + 1),
+            }
+            "#,
+        );
+        let enum_id = match adt(&db, "E") {
+            AdtId::EnumId(it) => it,
+            _ => panic!(),
+        };
+        let discriminants: Vec<_> =
+            db.enum_data(enum_id).variants.iter().map(|(_, it)| it.discriminant.clone()).collect();
+        assert_eq!(
+            discriminants,
+            vec![
+                Some(ConstRef::Int(3)),
+                None,
+                Some(ConstRef::Neg(Box::new(ConstRef::BinaryOp {
+                    op: ArithOp::Add,
+                    lhs: Box::new(ConstRef::Int(1)),
+                    rhs: Box::new(ConstRef::Int(1)),
+                }))),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_default_impls() {
+        let db = TestDB::with_files(
+            r#"
+            //- /lib.rs
+            #[derive(Debug, Default)]
+            struct Derived;
+            struct Explicit;
+            struct Negative;
+            struct Other;
+            enum E { A }
+
+            mod imp {
+                use super::Explicit;
+                impl Default for Explicit {
+                    fn default() -> Self { Explicit }
+                }
+            }
+            impl !Default for Negative {}
+            impl Clone for Other {
+                fn clone(&self) -> Self { Other }
+            }
+            "#,
+        );
+        let has_default = |name: &str| db.adt_has_default_impl(adt(&db, name));
+        assert!(has_default("Derived"));
+        assert!(has_default("Explicit"));
+        assert!(!has_default("Negative"));
+        assert!(!has_default("Other"));
+        assert!(!has_default("E"));
+    }
+}
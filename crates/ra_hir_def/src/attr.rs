@@ -2,17 +2,21 @@
 
 use std::{ops, sync::Arc};
 
-use either::Either;
-use hir_expand::{hygiene::Hygiene, AstId, InFile};
+use hir_expand::{hygiene::Hygiene, name::AsName, AstId, InFile};
 use mbe::ast_to_token_tree;
+use ra_cfg::CfgOptions;
+use ra_db::CrateId;
 use ra_syntax::{
-    ast::{self, AstNode, AttrsOwner},
-    SmolStr,
+    ast::{self, make, AstNode, AstToken, AttrsOwner, HasStringValue},
+    SmolStr, SyntaxToken,
 };
 use tt::Subtree;
 
 use crate::{
-    db::DefDatabase, path::ModPath, src::HasChildSource, src::HasSource, AdtId, AttrDefId, Lookup,
+    db::DefDatabase,
+    path::{ModPath, PathKind},
+    src::HasSource,
+    AdtId, AttrDefId, HasModule, Lookup, VariantId,
 };
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -41,28 +45,29 @@ impl Attrs {
                     None => return Attrs::default(),
                 };
                 Attrs::from_attrs_owner(db, src.as_ref().map(|it| it as &dyn AttrsOwner))
+                    .expand_cfg_attr(&db.crate_graph()[module.krate].cfg_options)
             }
             AttrDefId::StructFieldId(it) => {
-                let src = it.parent.child_source(db);
-                match &src.value[it.local_id] {
-                    Either::Left(_tuple) => Attrs::default(),
-                    Either::Right(record) => Attrs::from_attrs_owner(db, src.with_value(record)),
-                }
+                let variant_data = match it.parent {
+                    VariantId::StructId(it) => db.struct_data(it).variant_data.clone(),
+                    VariantId::UnionId(it) => db.union_data(it).variant_data.clone(),
+                    VariantId::EnumVariantId(it) => db.enum_variant_data(it),
+                };
+                variant_data.fields()[it.local_id].attrs.clone()
             }
             AttrDefId::EnumVariantId(var_id) => {
-                let src = var_id.parent.child_source(db);
-                let src = src.as_ref().map(|it| &it[var_id.local_id]);
-                Attrs::from_attrs_owner(db, src.map(|it| it as &dyn AttrsOwner))
+                db.enum_data(var_id.parent).variants[var_id.local_id].attrs.clone()
             }
             AttrDefId::AdtId(it) => match it {
-                AdtId::StructId(it) => attrs_from_loc(it.lookup(db), db),
-                AdtId::EnumId(it) => attrs_from_loc(it.lookup(db), db),
-                AdtId::UnionId(it) => attrs_from_loc(it.lookup(db), db),
+                AdtId::StructId(it) => db.struct_data(it).attrs.clone(),
+                AdtId::EnumId(it) => db.enum_data(it).attrs.clone(),
+                AdtId::UnionId(it) => db.union_data(it).attrs.clone(),
             },
             AttrDefId::TraitId(it) => attrs_from_loc(it.lookup(db), db),
-            AttrDefId::MacroDefId(it) => {
-                it.ast_id.map_or_else(Default::default, |ast_id| attrs_from_ast(ast_id, db))
-            }
+            AttrDefId::MacroDefId(it) => match (it.ast_id, it.krate) {
+                (Some(ast_id), Some(krate)) => attrs_from_ast(ast_id, krate, db),
+                _ => Attrs::default(),
+            },
             AttrDefId::ImplId(it) => attrs_from_loc(it.lookup(db), db),
             AttrDefId::ConstId(it) => attrs_from_loc(it.lookup(db), db),
             AttrDefId::StaticId(it) => attrs_from_loc(it.lookup(db), db),
@@ -87,9 +92,37 @@ impl Attrs {
         Attrs { entries }
     }
 
+    /// Replaces each `#[cfg_attr(pred, attr1, attr2)]` by the attributes it
+    /// guards if `pred` holds, and removes it otherwise.
+    pub(crate) fn expand_cfg_attr(self, cfg_options: &CfgOptions) -> Attrs {
+        if !self.by_key("cfg_attr").exists() {
+            return self;
+        }
+        let entries = self.iter().flat_map(|attr| attr.expand_cfg_attr(cfg_options)).collect();
+        Attrs { entries: Some(entries) }
+    }
+
     pub fn by_key(&self, key: &'static str) -> AttrQuery<'_> {
         AttrQuery { attrs: self, key }
     }
+
+    /// Whether all of the `#[cfg(...)]` attributes hold, the malformed ones
+    /// are ignored.
+    pub(crate) fn is_cfg_enabled(&self, cfg_options: &CfgOptions) -> bool {
+        self.by_key("cfg").tt_values().all(|tt| cfg_options.is_cfg_enabled(tt) != Some(false))
+    }
+
+    /// The paths of the traits in all `#[derive(...)]` attributes.
+    pub fn derives(&self) -> impl Iterator<Item = ModPath> + '_ {
+        // for #[derive(Copy, Clone)], the subtree is `(Copy, Clone)`
+        self.by_key("derive").tt_values().flat_map(|subtree| {
+            subtree.token_trees.iter().filter_map(|tt| match tt {
+                tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => Some(ModPath::from_tt_ident(ident)),
+                // `,` is ok, anything else would be an error (which we currently ignore)
+                _ => None,
+            })
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -110,9 +143,7 @@ impl Attr {
         let input = match ast.input() {
             None => None,
             Some(ast::AttrInput::Literal(lit)) => {
-                // FIXME: escape? raw string?
-                let value = lit.syntax().first_token()?.text().trim_matches('"').into();
-                Some(AttrInput::Literal(value))
+                Some(AttrInput::Literal(literal_value(lit.token())))
             }
             Some(ast::AttrInput::TokenTree(tt)) => {
                 Some(AttrInput::TokenTree(ast_to_token_tree(&tt)?.0))
@@ -121,8 +152,76 @@ impl Attr {
 
         Some(Attr { path, input })
     }
+
+    /// Parses an attribute inside of `cfg_attr`, like `path = "foo.rs"` or
+    /// `derive(Clone)`.
+    fn from_tt(tokens: &[tt::TokenTree]) -> Option<Attr> {
+        let mut segments = Vec::new();
+        let mut rest = tokens;
+        loop {
+            match rest {
+                [tt::TokenTree::Leaf(tt::Leaf::Ident(ident)), tail @ ..] => {
+                    segments.push(ident.as_name());
+                    rest = tail;
+                }
+                _ => return None,
+            }
+            match rest {
+                [first, second, tail @ ..] if is_punct(first, ':') && is_punct(second, ':') => {
+                    rest = tail
+                }
+                _ => break,
+            }
+        }
+        let input = match rest {
+            [] => None,
+            [eq, tt::TokenTree::Leaf(tt::Leaf::Literal(lit))] if is_punct(eq, '=') => {
+                Some(AttrInput::Literal(literal_value(make::tokens::literal(&lit.text))))
+            }
+            [tt::TokenTree::Subtree(subtree)] => Some(AttrInput::TokenTree(subtree.clone())),
+            _ => return None,
+        };
+        Some(Attr { path: ModPath::from_segments(PathKind::Plain, segments), input })
+    }
+
+    fn expand_cfg_attr(&self, cfg_options: &CfgOptions) -> Vec<Attr> {
+        let subtree = match &self.input {
+            Some(AttrInput::TokenTree(it))
+                if self.path.as_ident().map_or(false, |it| it.to_string() == "cfg_attr") =>
+            {
+                it
+            }
+            _ => return vec![self.clone()],
+        };
+        let mut parts = subtree.token_trees.split(|tt| is_punct(tt, ','));
+        let pred = Subtree { delimiter: None, token_trees: parts.next().unwrap_or(&[]).to_vec() };
+        if cfg_options.is_cfg_enabled(&pred) == Some(false) {
+            return Vec::new();
+        }
+        // `cfg_attr`s can be nested, and a trailing comma is allowed
+        parts
+            .filter(|it| !it.is_empty())
+            .filter_map(Attr::from_tt)
+            .flat_map(|attr| attr.expand_cfg_attr(cfg_options))
+            .collect()
+    }
+}
+
+fn is_punct(tt: &tt::TokenTree, c: char) -> bool {
+    matches!(tt, tt::TokenTree::Leaf(tt::Leaf::Punct(p)) if p.char == c)
+}
+
+/// The value of a literal attribute input. Strings with invalid escapes, like
+/// the Windows-style `#[path = "a\b.rs"]`, are passed through verbatim.
+pub(crate) fn literal_value(token: SyntaxToken) -> SmolStr {
+    ast::String::cast(token.clone())
+        .and_then(|it| it.value())
+        .or_else(|| ast::RawString::cast(token.clone()).and_then(|it| it.value()))
+        .map(SmolStr::from)
+        .unwrap_or_else(|| token.text().trim_matches('"').into())
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct AttrQuery<'a> {
     attrs: &'a Attrs,
     key: &'static str,
@@ -155,19 +254,22 @@ impl<'a> AttrQuery<'a> {
     }
 }
 
-fn attrs_from_ast<N>(src: AstId<N>, db: &dyn DefDatabase) -> Attrs
+fn attrs_from_ast<N>(src: AstId<N>, krate: CrateId, db: &dyn DefDatabase) -> Attrs
 where
     N: ast::AttrsOwner,
 {
     let src = InFile::new(src.file_id, src.to_node(db.upcast()));
     Attrs::from_attrs_owner(db, src.as_ref().map(|it| it as &dyn AttrsOwner))
+        .expand_cfg_attr(&db.crate_graph()[krate].cfg_options)
 }
 
 fn attrs_from_loc<T>(node: T, db: &dyn DefDatabase) -> Attrs
 where
-    T: HasSource,
+    T: HasSource + HasModule,
     T::Value: ast::AttrsOwner,
 {
     let src = node.source(db);
+    let krate = node.module(db).krate;
     Attrs::from_attrs_owner(db, src.as_ref().map(|it| it as &dyn AttrsOwner))
+        .expand_cfg_attr(&db.crate_graph()[krate].cfg_options)
 }
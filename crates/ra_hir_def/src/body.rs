@@ -140,6 +140,9 @@ pub struct Body {
     /// The `ExprId` of the actual body expression.
     pub body_expr: ExprId,
     pub item_scope: ItemScope,
+    /// Whether the body contains imports or macro calls which were not
+    /// collected or expanded, so that its scopes may be missing names.
+    pub has_unresolved_names: bool,
 }
 
 pub type ExprPtr = Either<AstPtr<ast::Expr>, AstPtr<ast::RecordField>>;
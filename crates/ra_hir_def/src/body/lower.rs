@@ -27,12 +27,12 @@ use crate::{
         dummy_expr_id, ArithOp, Array, BinaryOp, BindingAnnotation, CmpOp, Expr, ExprId, Literal,
         LogicOp, MatchArm, Ordering, Pat, PatId, RecordFieldPat, RecordLitField, Statement,
     },
-    item_scope::BuiltinShadowMode,
+    item_scope::{BuiltinShadowMode, ItemScope},
     path::GenericArgs,
     path::Path,
-    type_ref::{Mutability, TypeRef},
-    AdtId, ConstLoc, ContainerId, DefWithBodyId, EnumLoc, FunctionLoc, Intern, ModuleDefId,
-    StaticLoc, StructLoc, TraitLoc, TypeAliasLoc, UnionLoc,
+    type_ref::{parse_int, Mutability, TypeRef},
+    AdtId, AssocContainerId, ConstLoc, ContainerId, DefWithBodyId, EnumLoc, FunctionLoc, Intern,
+    Lookup, ModuleDefId, StaticLoc, StructLoc, TraitLoc, TypeAliasLoc, UnionLoc,
 };
 
 pub(super) fn lower(
@@ -42,6 +42,14 @@ pub(super) fn lower(
     params: Option<ast::ParamList>,
     body: Option<ast::Expr>,
 ) -> (Body, BodySourceMap) {
+    let mut item_scope = ItemScope::default();
+    // `macro_rules!` macros declared in a block are also visible in the
+    // bodies of the items declared in it.
+    if let Some(parent) = enclosing_body(db, def) {
+        for (name, mac) in db.body(parent).item_scope.legacy_macros() {
+            item_scope.define_legacy_macro(name.clone(), mac);
+        }
+    }
     ExprCollector {
         db,
         def,
@@ -52,12 +60,26 @@ pub(super) fn lower(
             pats: Arena::default(),
             params: Vec::new(),
             body_expr: dummy_expr_id(),
-            item_scope: Default::default(),
+            item_scope,
+            has_unresolved_names: false,
         },
     }
     .collect(params, body)
 }
 
+/// The body `def` is declared in, if it's an item inside of a block.
+fn enclosing_body(db: &dyn DefDatabase, def: DefWithBodyId) -> Option<DefWithBodyId> {
+    let container = match def {
+        DefWithBodyId::FunctionId(it) => it.lookup(db).container,
+        DefWithBodyId::ConstId(it) => it.lookup(db).container,
+        DefWithBodyId::StaticId(it) => it.lookup(db).container.into(),
+    };
+    match container {
+        AssocContainerId::ContainerId(ContainerId::DefWithBodyId(it)) => Some(it),
+        _ => None,
+    }
+}
+
 struct ExprCollector<'a> {
     db: &'a dyn DefDatabase,
     def: DefWithBodyId,
@@ -393,6 +415,11 @@ impl ExprCollector<'_> {
                 let exprs = e.exprs().map(|expr| self.collect_expr(expr)).collect();
                 self.alloc_expr(Expr::Tuple { exprs }, syntax_ptr)
             }
+            ast::Expr::LetExpr(e) => {
+                let pat = self.collect_pat_opt(e.pat());
+                let expr = self.collect_expr_opt(e.expr());
+                self.alloc_expr(Expr::Let { pat, expr }, syntax_ptr)
+            }
             ast::Expr::BoxExpr(e) => {
                 let expr = self.collect_expr_opt(e.expr());
                 self.alloc_expr(Expr::Box { expr }, syntax_ptr)
@@ -417,7 +444,7 @@ impl ExprCollector<'_> {
                 }
             }
 
-            ast::Expr::Literal(e) => self.alloc_expr(Expr::Literal(e.kind().into()), syntax_ptr),
+            ast::Expr::Literal(e) => self.alloc_expr(Expr::Literal(e.into()), syntax_ptr),
             ast::Expr::IndexExpr(e) => {
                 let base = self.collect_expr_opt(e.base());
                 let index = self.collect_expr_opt(e.index());
@@ -455,7 +482,10 @@ impl ExprCollector<'_> {
                             self.expander.exit(self.db, mark);
                             id
                         }
-                        None => self.alloc_expr(Expr::Missing, syntax_ptr),
+                        None => {
+                            self.body.has_unresolved_names = true;
+                            self.alloc_expr(Expr::Missing, syntax_ptr)
+                        }
                     }
                 }
             }
@@ -487,7 +517,9 @@ impl ExprCollector<'_> {
                     let pat = self.collect_pat_opt(stmt.pat());
                     let type_ref = stmt.ascribed_type().map(TypeRef::from_ast);
                     let initializer = stmt.initializer().map(|e| self.collect_expr(e));
-                    Some(Statement::Let { pat, type_ref, initializer })
+                    let else_branch =
+                        stmt.let_else().map(|it| self.collect_block_opt(it.block_expr()));
+                    Some(Statement::Let { pat, type_ref, initializer, else_branch })
                 }
                 ast::Stmt::ExprStmt(stmt) => {
                     Some(Statement::Expr(self.collect_expr_opt(stmt.expr())))
@@ -495,6 +527,12 @@ impl ExprCollector<'_> {
             })
             .collect();
         let tail = block.expr().map(|e| self.collect_expr(e));
+        if expr.async_kw_token().is_some() {
+            // The block and the `async` share the syntax node, which maps to
+            // the latter.
+            let body = self.alloc_expr_desugared(Expr::Block { statements, tail });
+            return self.alloc_expr(Expr::Async { body }, syntax_node_ptr);
+        }
         self.alloc_expr(Expr::Block { statements, tail }, syntax_node_ptr)
     }
 
@@ -543,12 +581,16 @@ impl ExprCollector<'_> {
                     let ast_id = self.expander.ast_id(&def);
                     (TraitLoc { container, ast_id }.intern(self.db).into(), def.name())
                 }
-                ast::ModuleItem::ExternBlock(_) => continue, // FIXME: collect from extern blocks
-                ast::ModuleItem::ImplDef(_)
+                ast::ModuleItem::ExternBlock(_)
                 | ast::ModuleItem::UseItem(_)
                 | ast::ModuleItem::ExternCrateItem(_)
-                | ast::ModuleItem::Module(_)
-                | ast::ModuleItem::MacroCall(_) => continue,
+                | ast::ModuleItem::MacroCall(_) => {
+                    // FIXME: resolve imports, expand macros and collect from
+                    // extern blocks in blocks
+                    self.body.has_unresolved_names = true;
+                    continue;
+                }
+                ast::ModuleItem::ImplDef(_) | ast::ModuleItem::Module(_) => continue,
             };
             self.body.item_scope.define_def(def);
             if let Some(name) = name {
@@ -666,24 +708,51 @@ impl ExprCollector<'_> {
                     suffix: suffix.into_iter().map(|p| self.collect_pat(p)).collect(),
                 }
             }
-            ast::Pat::LiteralPat(lit) => {
-                if let Some(ast_lit) = lit.literal() {
-                    let expr = Expr::Literal(ast_lit.kind().into());
-                    let expr_ptr = AstPtr::new(&ast::Expr::Literal(ast_lit));
-                    let expr_id = self.alloc_expr(expr, expr_ptr);
-                    Pat::Lit(expr_id)
-                } else {
-                    Pat::Missing
+            ast::Pat::LiteralPat(lit) => match self.collect_literal_pat(lit) {
+                Some(expr_id) => Pat::Lit(expr_id),
+                None => Pat::Missing,
+            },
+            ast::Pat::RangePat(p) => {
+                let range_type = match p.range_separator_token() {
+                    Some(ast::RangeSeparator::Dotdot(_)) => ast::RangeOp::Exclusive,
+                    _ => ast::RangeOp::Inclusive,
+                };
+                Pat::Range {
+                    start: self.collect_range_pat_bound(p.start()),
+                    end: self.collect_range_pat_bound(p.end()),
+                    range_type,
                 }
             }
+            ast::Pat::ConstBlockPat(p) => Pat::ConstBlock(self.collect_block_opt(p.block_expr())),
 
             // FIXME: implement
-            ast::Pat::BoxPat(_) | ast::Pat::RangePat(_) | ast::Pat::MacroPat(_) => Pat::Missing,
+            ast::Pat::BoxPat(_) | ast::Pat::MacroPat(_) => Pat::Missing,
         };
         let ptr = AstPtr::new(&pat);
         self.alloc_pat(pattern, Either::Left(ptr))
     }
 
+    fn collect_literal_pat(&mut self, lit: ast::LiteralPat) -> Option<ExprId> {
+        let ast_lit = lit.literal()?;
+        let expr = Expr::Literal(ast_lit.clone().into());
+        let expr_ptr = AstPtr::new(&ast::Expr::Literal(ast_lit));
+        Some(self.alloc_expr(expr, expr_ptr))
+    }
+
+    /// Lowers an end of a range pattern, which is a literal, a path to a
+    /// constant or a `const` block, to an expression.
+    fn collect_range_pat_bound(&mut self, pat: Option<ast::Pat>) -> Option<ExprId> {
+        match pat? {
+            ast::Pat::LiteralPat(lit) => self.collect_literal_pat(lit),
+            ast::Pat::PathPat(p) => {
+                let path = self.expander.parse_path(p.path()?)?;
+                Some(self.alloc_expr_desugared(Expr::Path(path)))
+            }
+            ast::Pat::ConstBlockPat(p) => Some(self.collect_block_opt(p.block_expr())),
+            _ => None,
+        }
+    }
+
     fn collect_pat_opt(&mut self, pat: Option<ast::Pat>) -> PatId {
         if let Some(pat) = pat {
             self.collect_pat(pat)
@@ -737,13 +806,13 @@ impl From<ast::BinOp> for BinaryOp {
     }
 }
 
-impl From<ast::LiteralKind> for Literal {
-    fn from(ast_lit_kind: ast::LiteralKind) -> Self {
-        match ast_lit_kind {
+impl From<ast::Literal> for Literal {
+    fn from(ast_lit: ast::Literal) -> Self {
+        match ast_lit.kind() {
             LiteralKind::IntNumber { suffix } => {
                 let known_name = suffix.and_then(|it| BuiltinInt::from_suffix(&it));
 
-                Literal::Int(Default::default(), known_name)
+                Literal::Int(parse_int(ast_lit.token().text()).unwrap_or_default(), known_name)
             }
             LiteralKind::FloatNumber { suffix } => {
                 let known_name = suffix.and_then(|it| BuiltinFloat::from_suffix(&it));
@@ -752,9 +821,35 @@ impl From<ast::LiteralKind> for Literal {
             }
             LiteralKind::ByteString => Literal::ByteString(Default::default()),
             LiteralKind::String => Literal::String(Default::default()),
-            LiteralKind::Byte => Literal::Int(Default::default(), Some(BuiltinInt::U8)),
+            LiteralKind::Byte => {
+                let value = parse_byte(ast_lit.token().text()).unwrap_or_default();
+                Literal::Int(value.into(), Some(BuiltinInt::U8))
+            }
             LiteralKind::Bool(val) => Literal::Bool(val),
             LiteralKind::Char => Literal::Char(Default::default()),
         }
     }
 }
+
+/// Parses a byte literal like `b'a'` or `b'\n'`.
+fn parse_byte(text: &str) -> Option<u8> {
+    if !text.starts_with("b'") || !text.ends_with('\'') || text.len() < 4 {
+        return None;
+    }
+    let inner = &text[2..text.len() - 1];
+    if inner.starts_with("\\x") {
+        return u8::from_str_radix(&inner[2..], 16).ok();
+    }
+    let value = match inner {
+        "\\n" => b'\n',
+        "\\r" => b'\r',
+        "\\t" => b'\t',
+        "\\0" => 0,
+        "\\\\" => b'\\',
+        "\\'" => b'\'',
+        "\\\"" => b'"',
+        _ if inner.len() == 1 => inner.as_bytes()[0],
+        _ => return None,
+    };
+    Some(value)
+}
@@ -8,7 +8,7 @@ use rustc_hash::FxHashMap;
 use crate::{
     body::Body,
     db::DefDatabase,
-    expr::{Expr, ExprId, Pat, PatId, Statement},
+    expr::{BinaryOp, Expr, ExprId, LogicOp, Pat, PatId, Statement},
     DefWithBodyId,
 };
 
@@ -116,11 +116,14 @@ fn compute_block_scopes(
 ) {
     for stmt in statements {
         match stmt {
-            Statement::Let { pat, initializer, .. } => {
+            Statement::Let { pat, initializer, else_branch, .. } => {
                 if let Some(expr) = initializer {
                     scopes.set_scope(*expr, scope);
                     compute_expr_scopes(*expr, body, scopes, scope);
                 }
+                if let Some(expr) = else_branch {
+                    compute_expr_scopes(*expr, body, scopes, scope);
+                }
                 scope = scopes.new_scope(scope);
                 scopes.add_bindings(body, scope, *pat);
             }
@@ -135,12 +138,50 @@ fn compute_block_scopes(
     }
 }
 
+/// Computes the scopes of an `if` or `while` condition, returning the scope
+/// containing the bindings of its `let`s.
+fn compute_condition_scopes(
+    expr: ExprId,
+    body: &Body,
+    scopes: &mut ExprScopes,
+    scope: ScopeId,
+) -> ScopeId {
+    scopes.set_scope(expr, scope);
+    match &body[expr] {
+        Expr::Let { pat, expr: scrutinee } => {
+            compute_expr_scopes(*scrutinee, body, scopes, scope);
+            let scope = scopes.new_scope(scope);
+            scopes.add_bindings(body, scope, *pat);
+            scope
+        }
+        Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::LogicOp(LogicOp::And)) } => {
+            let scope = compute_condition_scopes(*lhs, body, scopes, scope);
+            compute_condition_scopes(*rhs, body, scopes, scope)
+        }
+        _ => {
+            compute_expr_scopes(expr, body, scopes, scope);
+            scope
+        }
+    }
+}
+
 fn compute_expr_scopes(expr: ExprId, body: &Body, scopes: &mut ExprScopes, scope: ScopeId) {
     scopes.set_scope(expr, scope);
     match &body[expr] {
         Expr::Block { statements, tail } => {
             compute_block_scopes(&statements, *tail, body, scopes, scope);
         }
+        Expr::If { condition, then_branch, else_branch } => {
+            let then_scope = compute_condition_scopes(*condition, body, scopes, scope);
+            compute_expr_scopes(*then_branch, body, scopes, then_scope);
+            if let Some(else_branch) = else_branch {
+                compute_expr_scopes(*else_branch, body, scopes, scope);
+            }
+        }
+        Expr::While { condition, body: body_expr } => {
+            let body_scope = compute_condition_scopes(*condition, body, scopes, scope);
+            compute_expr_scopes(*body_expr, body, scopes, body_scope);
+        }
         Expr::For { iterable, pat, body: body_expr } => {
             compute_expr_scopes(*iterable, body, scopes, scope);
             let scope = scopes.new_scope(scope);
@@ -285,6 +326,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_let_chain() {
+        do_check(
+            r"
+            fn quux() {
+                if let Some(x) = a && let Some(y) = x && <|> {}
+            }",
+            &["y", "x"],
+        );
+        do_check(
+            r"
+            fn quux() {
+                while let Some(x) = a && let Some(y) = x {
+                    <|>
+                }
+            }",
+            &["y", "x"],
+        );
+        do_check(
+            r"
+            fn quux() {
+                if let Some(x) = a && b {} else { <|> }
+            }",
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_let_else() {
+        do_check(
+            r"
+            fn quux() {
+                let Some(x) = a else { <|> };
+            }",
+            &[],
+        );
+        do_check(
+            r"
+            fn quux() {
+                let Some(x) = a else { return };
+                <|>
+            }",
+            &["x"],
+        );
+    }
+
     #[test]
     fn test_shadow_variable() {
         do_check(
@@ -7,7 +7,6 @@ use hir_expand::{
     name::{name, AsName, Name},
     AstId, InFile,
 };
-use ra_cfg::CfgOptions;
 use ra_prof::profile;
 use ra_syntax::ast::{
     self, AstNode, ImplItem, ModuleItemOwner, NameOwner, TypeAscriptionOwner, VisibilityOwner,
@@ -16,7 +15,9 @@ use ra_syntax::ast::{
 use crate::{
     attr::Attrs,
     db::DefDatabase,
-    path::{path, GenericArgs, Path},
+    generics::WherePredicateTarget,
+    path::{path, AssocTypeBinding, GenericArgs, Path},
+    resolver::{HasResolver, TypeNs},
     src::HasSource,
     type_ref::{Mutability, TypeBound, TypeRef},
     visibility::RawVisibility,
@@ -28,11 +29,18 @@ use crate::{
 pub struct FunctionData {
     pub name: Name,
     pub params: Vec<TypeRef>,
+    /// For `async fn`s, this is `impl Future<Output = T>`, as seen by callers.
     pub ret_type: TypeRef,
+    /// The declared return type `T` of an `async fn`, which is what its body
+    /// evaluates to.
+    pub async_ret_type: Option<TypeRef>,
     pub attrs: Attrs,
     /// True if the first param is `self`. This is relevant to decide whether this
     /// can be called as a method.
     pub has_self_param: bool,
+    /// True if the function is a C-variadic one, like `printf(format: *const i8, ...)`.
+    /// The `...` is not included in `params`.
+    pub is_varargs: bool,
     pub visibility: RawVisibility,
 }
 
@@ -43,6 +51,7 @@ impl FunctionData {
         let name = src.value.name().map(|n| n.as_name()).unwrap_or_else(Name::missing);
         let mut params = Vec::new();
         let mut has_self_param = false;
+        let mut is_varargs = false;
         if let Some(param_list) = src.value.param_list() {
             if let Some(self_param) = param_list.self_param() {
                 let self_type = if let Some(type_ref) = self_param.ascribed_type() {
@@ -63,11 +72,16 @@ impl FunctionData {
                 has_self_param = true;
             }
             for param in param_list.params() {
+                if param.dotdotdot_token().is_some() {
+                    is_varargs = true;
+                    continue;
+                }
                 let type_ref = TypeRef::from_ast_opt(param.ascribed_type());
                 params.push(type_ref);
             }
         }
-        let attrs = Attrs::new(&src.value, &Hygiene::new(db.upcast(), src.file_id));
+        let attrs = Attrs::new(&src.value, &Hygiene::new(db.upcast(), src.file_id))
+            .expand_cfg_attr(&db.crate_graph()[loc.module(db).krate].cfg_options);
 
         let ret_type = if let Some(type_ref) = src.value.ret_type().and_then(|rt| rt.type_ref()) {
             TypeRef::from_ast(type_ref)
@@ -75,19 +89,28 @@ impl FunctionData {
             TypeRef::unit()
         };
 
-        let ret_type = if src.value.async_kw_token().is_some() {
-            let future_impl = desugar_future_path(ret_type);
+        let (ret_type, async_ret_type) = if src.value.async_kw_token().is_some() {
+            let future_impl = desugar_future_path(ret_type.clone());
             let ty_bound = TypeBound::Path(future_impl);
-            TypeRef::ImplTrait(vec![ty_bound])
+            (TypeRef::ImplTrait(vec![ty_bound]), Some(ret_type))
         } else {
-            ret_type
+            (ret_type, None)
         };
 
         let vis_default = RawVisibility::default_for_container(loc.container);
         let visibility =
             RawVisibility::from_ast_with_default(db, vis_default, src.map(|s| s.visibility()));
 
-        let sig = FunctionData { name, params, ret_type, has_self_param, visibility, attrs };
+        let sig = FunctionData {
+            name,
+            params,
+            ret_type,
+            async_ret_type,
+            has_self_param,
+            is_varargs,
+            visibility,
+            attrs,
+        };
         Arc::new(sig)
     }
 }
@@ -96,7 +119,11 @@ fn desugar_future_path(orig: TypeRef) -> Path {
     let path = path![std::future::Future];
     let mut generic_args: Vec<_> = std::iter::repeat(None).take(path.segments.len() - 1).collect();
     let mut last = GenericArgs::empty();
-    last.bindings.push((name![Output], orig));
+    last.bindings.push(AssocTypeBinding {
+        name: name![Output],
+        type_ref: Some(orig),
+        bounds: Vec::new(),
+    });
     generic_args.push(Some(Arc::new(last)));
 
     Path::from_known_path(path, generic_args)
@@ -195,6 +222,73 @@ impl TraitData {
             _ => None,
         })
     }
+
+    /// The traits named in the bounds on `Self` of the trait, i.e. in
+    /// `trait Foo: Bar where Self: Baz`, these are `Bar` and `Baz`.
+    pub(crate) fn super_traits_query(db: &dyn DefDatabase, tr: TraitId) -> Arc<[TraitId]> {
+        let resolver = tr.resolver(db);
+        let generic_params = db.generic_params(tr.into());
+        let trait_self = generic_params.find_trait_self_param();
+        generic_params
+            .where_predicates
+            .iter()
+            .filter_map(|pred| match &pred.target {
+                WherePredicateTarget::TypeRef(TypeRef::Path(p))
+                    if p == &Path::from(name![Self]) =>
+                {
+                    pred.bound.as_path()
+                }
+                WherePredicateTarget::TypeParam(local_id) if Some(*local_id) == trait_self => {
+                    pred.bound.as_path()
+                }
+                _ => None,
+            })
+            .filter_map(|path| match resolver.resolve_path_in_type_ns_fully(db, path.mod_path()) {
+                Some(TypeNs::TraitId(t)) => Some(t),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The whole super trait hierarchy of the trait, starting with the trait
+    /// itself. Each trait is listed once, even with cycles like
+    /// `trait A: B {} trait B: A {}`.
+    pub(crate) fn all_super_traits_query(db: &dyn DefDatabase, tr: TraitId) -> Arc<[TraitId]> {
+        let mut result = vec![tr];
+        let mut i = 0;
+        while i < result.len() {
+            // yeah this is quadratic, but trait hierarchies should be flat
+            // enough that this doesn't matter
+            for &super_trait in db.super_traits(result[i]).iter() {
+                if !result.contains(&super_trait) {
+                    result.push(super_trait);
+                }
+            }
+            i += 1;
+        }
+        result.into()
+    }
+
+    /// The items of the trait together with the ones inherited from its super
+    /// traits. An item of a super trait is skipped if a trait closer in the
+    /// hierarchy has an item with the same name in the same namespace.
+    pub(crate) fn items_with_super_traits_query(
+        db: &dyn DefDatabase,
+        tr: TraitId,
+    ) -> Arc<[(Name, AssocItemId)]> {
+        let _p = profile("items_with_super_traits_query");
+        let is_type = |item: &AssocItemId| matches!(item, AssocItemId::TypeAliasId(_));
+        let mut result: Vec<(Name, AssocItemId)> = Vec::new();
+        for &t in db.all_super_traits(tr).iter() {
+            for (name, item) in db.trait_data(t).items.iter() {
+                if result.iter().any(|(n, it)| n == name && is_type(it) == is_type(item)) {
+                    continue;
+                }
+                result.push((name.clone(), *item));
+            }
+        }
+        result.into()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -318,10 +412,6 @@ fn collect_impl_items_in_macro(
     }
 }
 
-fn is_cfg_enabled(cfg_options: &CfgOptions, attrs: &Attrs) -> bool {
-    attrs.by_key("cfg").tt_values().all(|tt| cfg_options.is_cfg_enabled(tt) != Some(false))
-}
-
 fn collect_impl_items(
     db: &dyn DefDatabase,
     impl_items: impl Iterator<Item = ImplItem>,
@@ -341,10 +431,11 @@ fn collect_impl_items(
                 }
                 .intern(db);
 
-                if !is_cfg_enabled(
-                    &crate_graph[module_id.krate].cfg_options,
-                    &db.function_data(def).attrs,
-                ) {
+                if !db
+                    .function_data(def)
+                    .attrs
+                    .is_cfg_enabled(&crate_graph[module_id.krate].cfg_options)
+                {
                     None
                 } else {
                     Some(def.into())
@@ -369,3 +460,49 @@ fn collect_impl_items(
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use ra_db::fixture::WithFixture;
+
+    use super::*;
+    use crate::{test_db::TestDB, ModuleDefId};
+
+    fn trait_(db: &TestDB, name: &str) -> TraitId {
+        let krate = db.test_crate();
+        let def_map = db.crate_def_map(krate);
+        let def = def_map[def_map.root]
+            .scope
+            .entries()
+            .find(|(it, _)| it.to_string() == name)
+            .and_then(|(_, def)| def.take_types());
+        match def {
+            Some(ModuleDefId::TraitId(it)) => it,
+            _ => panic!("no trait named `{}`", name),
+        }
+    }
+
+    #[test]
+    fn collects_items_of_super_traits() {
+        let db = TestDB::with_files(
+            r#"
+            //- /lib.rs
+            trait A: C { fn a(&self); fn shadowed(&self); type Item; }
+            trait B where Self: A { fn b(&self); fn shadowed(&self); }
+            trait C: B { fn c(&self); type shadowed; }
+            trait D: C {}
+            "#,
+        );
+        let (a, b, c, d) = (trait_(&db, "A"), trait_(&db, "B"), trait_(&db, "C"), trait_(&db, "D"));
+
+        assert_eq!(&*db.super_traits(d), &[c]);
+        assert_eq!(&*db.super_traits(b), &[a]);
+        assert_eq!(&*db.all_super_traits(d), &[d, c, b, a]);
+
+        let items = db.trait_items_with_super_traits(d);
+        let names: Vec<_> = items.iter().map(|(name, _)| name.to_string()).collect();
+        assert_eq!(names, vec!["c", "shadowed", "b", "shadowed", "a", "Item"]);
+        let (_, b_shadowed) = &items[3];
+        assert_eq!(b_shadowed, &db.trait_data(b).items[1].1);
+    }
+}
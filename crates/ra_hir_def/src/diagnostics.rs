@@ -4,7 +4,7 @@ use std::any::Any;
 
 use hir_expand::diagnostics::Diagnostic;
 use ra_db::RelativePathBuf;
-use ra_syntax::{ast, AstPtr, SyntaxNodePtr};
+use ra_syntax::{ast, AstPtr, SyntaxNodePtr, TextRange};
 
 use hir_expand::{HirFileId, InFile};
 
@@ -26,3 +26,47 @@ impl Diagnostic for UnresolvedModule {
         self
     }
 }
+
+/// A `macro_rules` definition which is accepted, but likely wrong, like one
+/// with an unused metavariable or a rule which is never matched.
+#[derive(Debug)]
+pub struct MacroRulesWarning {
+    pub file: HirFileId,
+    pub decl: AstPtr<ast::MacroCall>,
+    pub range: TextRange,
+    pub message: String,
+}
+
+impl Diagnostic for MacroRulesWarning {
+    fn message(&self) -> String {
+        self.message.clone()
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.decl.into() }
+    }
+    fn highlight_range(&self) -> TextRange {
+        self.range
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MalformedCfg {
+    pub file: HirFileId,
+    pub attr: AstPtr<ast::Attr>,
+    pub message: String,
+}
+
+impl Diagnostic for MalformedCfg {
+    fn message(&self) -> String {
+        self.message.clone()
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.attr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
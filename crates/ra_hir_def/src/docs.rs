@@ -6,9 +6,13 @@
 use std::sync::Arc;
 
 use either::Either;
-use ra_syntax::ast;
+use ra_syntax::{
+    ast::{self, AstNode, AstToken},
+    NodeOrToken,
+};
 
 use crate::{
+    attr::literal_value,
     db::DefDatabase,
     src::{HasChildSource, HasSource},
     AdtId, AttrDefId, Lookup,
@@ -70,6 +74,69 @@ impl Documentation {
     }
 }
 
-pub(crate) fn docs_from_ast(node: &impl ast::DocCommentsOwner) -> Option<Documentation> {
-    node.doc_comment_text().map(|it| Documentation::new(&it))
+/// Merges the doc comments and `#[doc = "..."]` attributes of `node`, in
+/// source order, and strips their common indentation. Other `doc` attributes,
+/// like `#[doc(hidden)]`, don't contribute any text.
+pub(crate) fn docs_from_ast<N>(node: &N) -> Option<Documentation>
+where
+    N: ast::DocCommentsOwner + ast::AttrsOwner,
+{
+    let mut has_docs = false;
+    let mut lines = Vec::new();
+    for child in node.syntax().children_with_tokens() {
+        match child {
+            NodeOrToken::Token(token) => {
+                let comment = match ast::Comment::cast(token) {
+                    Some(it) if it.kind().doc.is_some() => it,
+                    _ => continue,
+                };
+                has_docs = true;
+                lines.extend(doc_comment_lines(&comment));
+            }
+            NodeOrToken::Node(node) => {
+                let attr = match ast::Attr::cast(node) {
+                    Some(it) if it.simple_name().as_deref() == Some("doc") => it,
+                    _ => continue,
+                };
+                if let Some(ast::AttrInput::Literal(lit)) = attr.input() {
+                    has_docs = true;
+                    lines.extend(literal_value(lit.token()).split('\n').map(String::from));
+                }
+            }
+        }
+    }
+    if !has_docs {
+        return None;
+    }
+    Some(Documentation::new(&unindent(&lines)))
+}
+
+/// The lines of a doc comment, without the `///` or `/**` prefix and the
+/// trailing `*/`.
+fn doc_comment_lines(comment: &ast::Comment) -> Vec<String> {
+    let text = comment.text().as_str();
+    let start = comment.prefix().len();
+    let end = if comment.kind().shape.is_block() && text.ends_with("*/") {
+        text.len() - 2
+    } else {
+        text.len()
+    };
+    text.get(start..end).unwrap_or_default().split('\n').map(String::from).collect()
+}
+
+/// Removes the leading whitespace common to all non-blank lines. Note that we
+/// do not trim the end of the lines, since whitespace can have special meaning
+/// at the end of a line in markdown.
+fn unindent(lines: &[String]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.chars().skip(indent).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
 }
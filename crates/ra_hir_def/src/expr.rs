@@ -35,7 +35,7 @@ pub enum Literal {
     ByteString(Vec<u8>),
     Char(char),
     Bool(bool),
-    Int(u64, Option<BuiltinInt>),
+    Int(u128, Option<BuiltinInt>),
     Float(u64, Option<BuiltinFloat>), // FIXME: f64 is not Eq
 }
 
@@ -49,6 +49,12 @@ pub enum Expr {
         then_branch: ExprId,
         else_branch: Option<ExprId>,
     },
+    /// A `let` in the condition of an `if` or `while` let chain. Plain `if let`
+    /// and `while let` are desugared to a `match` instead.
+    Let {
+        pat: PatId,
+        expr: ExprId,
+    },
     Block {
         statements: Vec<Statement>,
         tail: Option<ExprId>,
@@ -104,6 +110,9 @@ pub enum Expr {
     TryBlock {
         body: ExprId,
     },
+    Async {
+        body: ExprId,
+    },
     Cast {
         expr: ExprId,
         type_ref: TypeRef,
@@ -208,7 +217,13 @@ pub struct RecordLitField {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Statement {
-    Let { pat: PatId, type_ref: Option<TypeRef>, initializer: Option<ExprId> },
+    Let {
+        pat: PatId,
+        type_ref: Option<TypeRef>,
+        initializer: Option<ExprId>,
+        /// The diverging block of a `let ... else { ... }`.
+        else_branch: Option<ExprId>,
+    },
     Expr(ExprId),
 }
 
@@ -224,13 +239,17 @@ impl Expr {
                     f(*else_branch);
                 }
             }
+            Expr::Let { expr, .. } => f(*expr),
             Expr::Block { statements, tail } => {
                 for stmt in statements {
                     match stmt {
-                        Statement::Let { initializer, .. } => {
+                        Statement::Let { initializer, else_branch, .. } => {
                             if let Some(expr) = initializer {
                                 f(*expr);
                             }
+                            if let Some(expr) = else_branch {
+                                f(*expr);
+                            }
                         }
                         Statement::Expr(e) => f(*e),
                     }
@@ -239,7 +258,7 @@ impl Expr {
                     f(*expr);
                 }
             }
-            Expr::TryBlock { body } => f(*body),
+            Expr::TryBlock { body } | Expr::Async { body } => f(*body),
             Expr::Loop { body } => f(*body),
             Expr::While { condition, body } => {
                 f(*condition);
@@ -381,10 +400,14 @@ pub enum Pat {
         args: Vec<RecordFieldPat>,
         // FIXME: 'ellipsis' option
     },
+    /// The ends are missing in half-open ranges like `a..`.
     Range {
-        start: ExprId,
-        end: ExprId,
+        start: Option<ExprId>,
+        end: Option<ExprId>,
+        range_type: RangeOp,
     },
+    /// An inline `const { ... }` block.
+    ConstBlock(ExprId),
     Slice {
         prefix: Vec<PatId>,
         slice: Option<PatId>,
@@ -410,7 +433,12 @@ pub enum Pat {
 impl Pat {
     pub fn walk_child_pats(&self, mut f: impl FnMut(PatId)) {
         match self {
-            Pat::Range { .. } | Pat::Lit(..) | Pat::Path(..) | Pat::Wild | Pat::Missing => {}
+            Pat::Range { .. }
+            | Pat::ConstBlock(..)
+            | Pat::Lit(..)
+            | Pat::Path(..)
+            | Pat::Wild
+            | Pat::Missing => {}
             Pat::Bind { subpat, .. } => {
                 subpat.iter().copied().for_each(f);
             }
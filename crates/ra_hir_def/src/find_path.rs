@@ -8,6 +8,7 @@ use crate::{
     CrateId, ModuleDefId, ModuleId,
 };
 use hir_expand::name::{known, Name};
+use ra_db::Edition;
 use test_utils::tested_by;
 
 const MAX_PATH_LEN: usize = 15;
@@ -45,7 +46,23 @@ impl ModPath {
 /// Find a path that can be used to refer to a certain item. This can depend on
 /// *from where* you're referring to the item, hence the `from` parameter.
 pub fn find_path(db: &dyn DefDatabase, item: ItemInNs, from: ModuleId) -> Option<ModPath> {
-    find_path_inner(db, item, from, MAX_PATH_LEN)
+    let mut path = find_path_inner(db, item, from, MAX_PATH_LEN)?;
+    // - in the 2015 edition, paths in `use` items are relative to the crate
+    //   root, so paths starting with a name from the current module need to
+    //   start with `self` instead
+    if path.kind == PathKind::Plain
+        && path.segments.len() > 1
+        && db.crate_graph()[from.krate].edition == Edition::Edition2015
+    {
+        let def_map = db.crate_def_map(from.krate);
+        if from.local_id != def_map.root
+            && !def_map.modules[from.local_id].scope.get(&path.segments[0]).is_none()
+        {
+            tested_by!(find_path_2015_self_prefix);
+            path.kind = PathKind::Super(0);
+        }
+    }
+    Some(path)
 }
 
 fn find_path_inner(
@@ -94,11 +111,18 @@ fn find_path_inner(
         }
     }
 
-    // - if the item is the crate root of a dependency crate, return the name from the extern prelude
-    for (name, def_id) in &def_map.extern_prelude {
-        if item == ItemInNs::Types(*def_id) {
-            return Some(ModPath::from_segments(PathKind::Plain, vec![name.clone()]));
-        }
+    // - if the item is the crate root of a dependency crate, return the name from the extern prelude,
+    //   preferring names from `extern crate` items in the crate root, which might be aliases
+    let root_scope = &def_map.modules[def_map.root].scope;
+    let extern_crate_name = def_map
+        .extern_prelude
+        .iter()
+        .filter(|(_, def_id)| item == ItemInNs::Types(**def_id))
+        .min_by_key(|(name, def_id)| {
+            (root_scope.get(name).take_types() != Some(**def_id), name.to_string())
+        });
+    if let Some((name, _)) = extern_crate_name {
+        return Some(ModPath::from_segments(PathKind::Plain, vec![name.clone()]));
     }
 
     // - if the item is in the prelude, return the name from there
@@ -370,6 +394,35 @@ mod tests {
         check_found_path(code, "std_renamed::S");
     }
 
+    #[test]
+    fn different_crate_renamed_in_submodule() {
+        let code = r#"
+            //- /main.rs crate:main deps:std
+            extern crate std as std_renamed;
+            mod foo;
+            //- /foo.rs
+            <|>
+            //- /std.rs crate:std
+            pub struct S;
+        "#;
+        check_found_path(code, "std_renamed::S");
+    }
+
+    #[test]
+    fn edition_2015_paths_from_current_module_start_with_self() {
+        covers!(find_path_2015_self_prefix);
+        let code = r#"
+            //- /main.rs crate:main edition:2015
+            mod foo;
+            //- /foo.rs
+            mod bar {
+                pub struct S;
+            }
+            <|>
+        "#;
+        check_found_path(code, "self::bar::S");
+    }
+
     #[test]
     fn same_crate_reexport() {
         let code = r#"
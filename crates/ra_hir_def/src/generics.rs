@@ -12,7 +12,7 @@ use hir_expand::{
 use ra_arena::{map::ArenaMap, Arena};
 use ra_db::FileId;
 use ra_prof::profile;
-use ra_syntax::ast::{self, NameOwner, TypeBoundsOwner, TypeParamsOwner};
+use ra_syntax::ast::{self, NameOwner, TypeAscriptionOwner, TypeBoundsOwner, TypeParamsOwner};
 
 use crate::{
     child_by_source::ChildBySource,
@@ -31,6 +31,8 @@ pub struct TypeParamData {
     pub name: Option<Name>,
     pub default: Option<TypeRef>,
     pub provenance: TypeParamProvenance,
+    /// The declared type of a const parameter.
+    pub const_ty: Option<TypeRef>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -38,6 +40,9 @@ pub enum TypeParamProvenance {
     TypeParamList,
     TraitSelf,
     ArgumentImplTrait,
+    /// `const N: usize`. Const parameters take part in substitutions like type
+    /// parameters do, and come after them in the parameter list.
+    ConstParam,
 }
 
 /// Data about the generic parameters of a function, struct, impl, etc.
@@ -65,7 +70,7 @@ pub enum WherePredicateTarget {
     TypeParam(LocalTypeParamId),
 }
 
-type SourceMap = ArenaMap<LocalTypeParamId, Either<ast::TraitDef, ast::TypeParam>>;
+type SourceMap = ArenaMap<LocalTypeParamId, Either<ast::TraitDef, ast::GenericParam>>;
 
 impl GenericParams {
     pub(crate) fn generic_params_query(
@@ -115,6 +120,7 @@ impl GenericParams {
                     name: Some(name![Self]),
                     default: None,
                     provenance: TypeParamProvenance::TraitSelf,
+                    const_ty: None,
                 });
                 sm.insert(self_param_id, Either::Left(src.value.clone()));
                 // add super traits as bounds on Self
@@ -171,13 +177,25 @@ impl GenericParams {
                 name: Some(name.clone()),
                 default,
                 provenance: TypeParamProvenance::TypeParamList,
+                const_ty: None,
             };
             let param_id = self.types.alloc(param);
-            sm.insert(param_id, Either::Right(type_param.clone()));
+            sm.insert(param_id, Either::Right(type_param.clone().into()));
 
             let type_ref = TypeRef::Path(name.into());
             self.fill_bounds(&type_param, type_ref);
         }
+        for const_param in params.const_params() {
+            let name = const_param.name().map_or_else(Name::missing, |it| it.as_name());
+            let param = TypeParamData {
+                name: Some(name),
+                default: None,
+                provenance: TypeParamProvenance::ConstParam,
+                const_ty: Some(TypeRef::from_ast_opt(const_param.ascribed_type())),
+            };
+            let param_id = self.types.alloc(param);
+            sm.insert(param_id, Either::Right(const_param.into()));
+        }
     }
 
     fn fill_where_predicates(&mut self, where_clause: ast::WhereClause) {
@@ -210,6 +228,7 @@ impl GenericParams {
                     name: None,
                     default: None,
                     provenance: TypeParamProvenance::ArgumentImplTrait,
+                    const_ty: None,
                 };
                 let param_id = self.types.alloc(param);
                 for bound in bounds {
@@ -241,7 +260,7 @@ impl GenericParams {
 
 impl HasChildSource for GenericDefId {
     type ChildId = LocalTypeParamId;
-    type Value = Either<ast::TraitDef, ast::TypeParam>;
+    type Value = Either<ast::TraitDef, ast::GenericParam>;
     fn child_source(&self, db: &dyn DefDatabase) -> InFile<SourceMap> {
         let (_, sm) = GenericParams::new(db, *self);
         sm
@@ -255,7 +274,7 @@ impl ChildBySource for GenericDefId {
         let arena_map = arena_map.as_ref();
         for (local_id, src) in arena_map.value.iter() {
             let id = TypeParamId { parent: *self, local_id };
-            if let Either::Right(type_param) = src {
+            if let Either::Right(ast::GenericParam::TypeParam(type_param)) = src {
                 res[keys::TYPE_PARAM].insert(arena_map.with_value(type_param.clone()), id)
             }
         }
@@ -140,6 +140,14 @@ pub struct StructFieldId {
 
 pub type LocalStructFieldId = Idx<adt::StructFieldData>;
 
+impl StructFieldId {
+    /// Reading a field of a union is `unsafe`, as the union may hold one of
+    /// its other fields instead.
+    pub fn is_unsafe_to_read(self) -> bool {
+        matches!(self.parent, VariantId::UnionId(_))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ConstId(salsa::InternId);
 type ConstLoc = AssocItemLoc<ast::ConstDef>;
@@ -383,6 +391,17 @@ impl HasModule for AdtId {
     }
 }
 
+impl HasModule for VariantId {
+    fn module(&self, db: &dyn db::DefDatabase) -> ModuleId {
+        match self {
+            VariantId::EnumVariantId(it) => it.parent.lookup(db).container,
+            VariantId::StructId(it) => it.lookup(db).container,
+            VariantId::UnionId(it) => it.lookup(db).container,
+        }
+        .module(db)
+    }
+}
+
 impl HasModule for DefWithBodyId {
     fn module(&self, db: &dyn db::DefDatabase) -> ModuleId {
         match self {
@@ -407,7 +426,7 @@ impl HasModule for GenericDefId {
     }
 }
 
-impl HasModule for StaticLoc {
+impl<N: AstNode> HasModule for ItemLoc<N> {
     fn module(&self, db: &dyn db::DefDatabase) -> ModuleId {
         self.container.module(db)
     }
@@ -14,4 +14,5 @@ test_utils::marks!(
     macro_dollar_crate_other
     infer_resolve_while_let
     prefer_std_paths
+    find_path_2015_self_prefix
 );
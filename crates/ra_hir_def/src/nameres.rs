@@ -175,6 +175,9 @@ pub struct ModuleData {
 
     /// Where does this module come from?
     pub origin: ModuleOrigin,
+    /// Whether some imports or macro calls of this module could not be
+    /// resolved, so that its scope may be missing names.
+    pub has_unresolved_names: bool,
 }
 
 impl CrateDefMap {
@@ -295,11 +298,20 @@ pub enum ModuleSource {
 }
 
 mod diagnostics {
-    use hir_expand::diagnostics::DiagnosticSink;
+    use hir_expand::{db::TokenExpander, diagnostics::DiagnosticSink, MacroDefId};
+    use ra_cfg::CfgExpr;
     use ra_db::RelativePathBuf;
-    use ra_syntax::{ast, AstPtr};
-
-    use crate::{db::DefDatabase, diagnostics::UnresolvedModule, nameres::LocalModuleId, AstId};
+    use ra_syntax::{
+        ast::{self, AttrsOwner},
+        AstNode, AstPtr,
+    };
+
+    use crate::{
+        db::DefDatabase,
+        diagnostics::{MacroRulesWarning, MalformedCfg, UnresolvedModule},
+        nameres::LocalModuleId,
+        AstId,
+    };
 
     #[derive(Debug, PartialEq, Eq)]
     pub(super) enum DefDiagnostic {
@@ -308,6 +320,12 @@ mod diagnostics {
             declaration: AstId<ast::Module>,
             candidate: RelativePathBuf,
         },
+        /// Diagnostics for `macro_rules` definitions are computed lazily, as
+        /// they require parsing the definition.
+        MacroRules { module: LocalModuleId, definition: MacroDefId },
+        /// An item with a `#[cfg]` attribute which doesn't parse. The error is
+        /// recomputed from the source when reporting the diagnostic.
+        MalformedCfg { module: LocalModuleId, item: AstId<ast::ModuleItem> },
     }
 
     impl DefDiagnostic {
@@ -329,6 +347,77 @@ mod diagnostics {
                         candidate: candidate.clone(),
                     })
                 }
+                DefDiagnostic::MacroRules { module, definition } => {
+                    if *module != target_module {
+                        return;
+                    }
+                    let ast_id = match definition.ast_id {
+                        Some(it) => it,
+                        None => return,
+                    };
+                    // Definitions produced by macros can't be highlighted.
+                    if ast_id.file_id.call_node(db.upcast()).is_some() {
+                        return;
+                    }
+                    let expander = match db.macro_def(*definition) {
+                        Some(it) => it,
+                        None => return,
+                    };
+                    let (rules, token_map) = match &*expander {
+                        (TokenExpander::MacroRules(rules), token_map) => (rules, token_map),
+                        _ => return,
+                    };
+                    let macro_call = ast_id.to_node(db.upcast());
+                    let token_tree = match macro_call.token_tree() {
+                        Some(it) => it,
+                        None => return,
+                    };
+                    let offset = token_tree.syntax().text_range().start();
+                    for d in rules.diagnostics() {
+                        let range = match token_map.range_by_token(d.token_id()) {
+                            Some(mbe::TokenTextRange::Token(it)) => it,
+                            Some(mbe::TokenTextRange::Delimiter(open, close)) => {
+                                open.extend_to(&close)
+                            }
+                            None => continue,
+                        };
+                        sink.push(MacroRulesWarning {
+                            file: ast_id.file_id,
+                            decl: AstPtr::new(&macro_call),
+                            range: range + offset,
+                            message: d.message(),
+                        })
+                    }
+                }
+                DefDiagnostic::MalformedCfg { module, item } => {
+                    if *module != target_module {
+                        return;
+                    }
+                    // Items produced by macros can't be highlighted.
+                    if item.file_id.call_node(db.upcast()).is_some() {
+                        return;
+                    }
+                    let node = item.to_node(db.upcast());
+                    let malformed = node
+                        .attrs()
+                        .filter(|attr| attr.simple_name().map_or(false, |name| name == "cfg"))
+                        .find_map(|attr| {
+                            let tt = match attr.input()? {
+                                ast::AttrInput::TokenTree(it) => it,
+                                ast::AttrInput::Literal(_) => return None,
+                            };
+                            let (tt, _) = mbe::ast_to_token_tree(&tt)?;
+                            let error = CfgExpr::parse(&tt).err()?;
+                            Some((attr, error))
+                        });
+                    if let Some((attr, error)) = malformed {
+                        sink.push(MalformedCfg {
+                            file: item.file_id,
+                            attr: AstPtr::new(&attr),
+                            message: error.to_string(),
+                        })
+                    }
+                }
             }
         }
     }
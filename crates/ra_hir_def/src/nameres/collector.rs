@@ -10,7 +10,7 @@ use hir_expand::{
     proc_macro::ProcMacroExpander,
     HirFileId, MacroCallId, MacroDefId, MacroDefKind,
 };
-use ra_cfg::CfgOptions;
+use ra_cfg::{CfgExpr, CfgOptions};
 use ra_db::{CrateId, FileId, ProcMacroId};
 use ra_syntax::ast;
 use rustc_hash::FxHashMap;
@@ -168,6 +168,9 @@ impl DefCollector<'_> {
                 break;
             }
         }
+        for directive in self.unexpanded_macros.iter() {
+            self.def_map.modules[directive.module_id].has_unresolved_names = true;
+        }
 
         // Resolve all indeterminate resolved imports again
         // As some of the macros will expand newly import shadowing partial resolved imports
@@ -188,6 +191,7 @@ impl DefCollector<'_> {
         let unresolved_imports = std::mem::replace(&mut self.unresolved_imports, Vec::new());
         // show unresolved imports in completion, etc
         for directive in unresolved_imports {
+            self.def_map.modules[directive.module_id].has_unresolved_names = true;
             self.record_resolved_import(&directive)
         }
 
@@ -358,12 +362,23 @@ impl DefCollector<'_> {
     ) -> PartialResolvedImport {
         log::debug!("resolving import: {:?} ({:?})", import, self.def_map.edition);
         if import.is_extern_crate {
-            let res = self.def_map.resolve_name_in_extern_prelude(
-                &import
-                    .path
-                    .as_ident()
-                    .expect("extern crate should have been desugared to one-element path"),
-            );
+            let name = import
+                .path
+                .as_ident()
+                .expect("extern crate should have been desugared to one-element path");
+            let res = if *name == name![self] {
+                // `extern crate self as foo;` refers to the current crate
+                // and is only valid with an alias
+                if import.alias.is_none() {
+                    return PartialResolvedImport::Unresolved;
+                }
+                PerNs::types(
+                    ModuleId { krate: self.def_map.krate, local_id: self.def_map.root }.into(),
+                    Visibility::Public,
+                )
+            } else {
+                self.def_map.resolve_name_in_extern_prelude(name)
+            };
             PartialResolvedImport::Resolved(res)
         } else {
             let res = self.def_map.resolve_path_fp_with_macro(
@@ -418,6 +433,9 @@ impl DefCollector<'_> {
                         // glob import from other crate => we can just import everything once
                         let item_map = self.db.crate_def_map(m.krate);
                         let scope = &item_map[m.local_id].scope;
+                        if item_map[m.local_id].has_unresolved_names {
+                            self.def_map.modules[module_id].has_unresolved_names = true;
+                        }
 
                         // Module scoped macros is included
                         let items = scope
@@ -434,6 +452,9 @@ impl DefCollector<'_> {
                         // glob import from same crate => we do an initial
                         // import, and then need to propagate any further
                         // additions
+                        if self.def_map[m.local_id].has_unresolved_names {
+                            self.def_map.modules[module_id].has_unresolved_names = true;
+                        }
                         let scope = &self.def_map[m.local_id].scope;
 
                         // Module scoped macros is included
@@ -683,7 +704,8 @@ impl ModCollector<'_, '_> {
         // `#[macro_use] extern crate` is hoisted to imports macros before collecting
         // any other items.
         for item in items {
-            if self.is_cfg_enabled(&item.attrs) {
+            let attrs = self.expand_cfg_attr(&item.attrs);
+            if self.is_cfg_enabled(&attrs) {
                 if let raw::RawItemKind::Import(import_id) = item.kind {
                     let import = self.raw_items[import_id].clone();
                     if import.is_extern_crate && import.is_macro_use {
@@ -694,11 +716,11 @@ impl ModCollector<'_, '_> {
         }
 
         for item in items {
-            if self.is_cfg_enabled(&item.attrs) {
+            self.check_cfg_syntax(item);
+            let attrs = self.expand_cfg_attr(&item.attrs);
+            if self.is_cfg_enabled(&attrs) {
                 match item.kind {
-                    raw::RawItemKind::Module(m) => {
-                        self.collect_module(&self.raw_items[m], &item.attrs)
-                    }
+                    raw::RawItemKind::Module(m) => self.collect_module(&self.raw_items[m], &attrs),
                     raw::RawItemKind::Import(import_id) => {
                         self.def_collector.unresolved_imports.push(ImportDirective {
                             module_id: self.module_id,
@@ -707,9 +729,7 @@ impl ModCollector<'_, '_> {
                             status: PartialResolvedImport::Unresolved,
                         })
                     }
-                    raw::RawItemKind::Def(def) => {
-                        self.define_def(&self.raw_items[def], &item.attrs)
-                    }
+                    raw::RawItemKind::Def(def) => self.define_def(&self.raw_items[def], &attrs),
                     raw::RawItemKind::Macro(mac) => self.collect_macro(&self.raw_items[mac]),
                     raw::RawItemKind::Impl(imp) => {
                         let module = ModuleId {
@@ -725,6 +745,10 @@ impl ModCollector<'_, '_> {
                             .scope
                             .define_impl(impl_id)
                     }
+                    raw::RawItemKind::ExternBlock(_) => {
+                        self.def_collector.def_map.modules[self.module_id].has_unresolved_names =
+                            true;
+                    }
                 }
             }
         }
@@ -889,21 +913,11 @@ impl ModCollector<'_, '_> {
     }
 
     fn collect_derives(&mut self, attrs: &Attrs, def: &raw::DefData) {
-        for derive_subtree in attrs.by_key("derive").tt_values() {
-            // for #[derive(Copy, Clone)], `derive_subtree` is the `(Copy, Clone)` subtree
-            for tt in &derive_subtree.token_trees {
-                let ident = match &tt {
-                    tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => ident,
-                    tt::TokenTree::Leaf(tt::Leaf::Punct(_)) => continue, // , is ok
-                    _ => continue, // anything else would be an error (which we currently ignore)
-                };
-                let path = ModPath::from_tt_ident(ident);
-
-                let ast_id = AstIdWithPath::new(self.file_id, def.kind.ast_id(), path);
-                self.def_collector
-                    .unexpanded_attribute_macros
-                    .push(DeriveDirective { module_id: self.module_id, ast_id });
-            }
+        for path in attrs.derives() {
+            let ast_id = AstIdWithPath::new(self.file_id, def.kind.ast_id(), path);
+            self.def_collector
+                .unexpanded_attribute_macros
+                .push(DeriveDirective { module_id: self.module_id, ast_id });
         }
     }
 
@@ -935,6 +949,10 @@ impl ModCollector<'_, '_> {
                     kind: MacroDefKind::Declarative,
                 };
                 self.def_collector.define_macro(self.module_id, name.clone(), macro_id, mac.export);
+                self.def_collector.def_map.diagnostics.push(DefDiagnostic::MacroRules {
+                    module: self.module_id,
+                    definition: macro_id,
+                });
             }
             return;
         }
@@ -976,8 +994,38 @@ impl ModCollector<'_, '_> {
         }
     }
 
+    /// Records a diagnostic for the item if one of its `#[cfg]` attributes is
+    /// malformed. The item is still treated as enabled then, unless the valid
+    /// parts of the predicate say otherwise.
+    fn check_cfg_syntax(&mut self, item: &raw::RawItem) {
+        if item.attrs.by_key("cfg").tt_values().all(|tt| CfgExpr::parse(tt).is_ok()) {
+            return;
+        }
+        let ast_id = match item.kind {
+            raw::RawItemKind::Module(m) => match &self.raw_items[m] {
+                raw::ModuleData::Declaration { ast_id, .. }
+                | raw::ModuleData::Definition { ast_id, .. } => ast_id.upcast(),
+            },
+            raw::RawItemKind::Def(def) => self.raw_items[def].kind.ast_id(),
+            raw::RawItemKind::Macro(mac) => self.raw_items[mac].ast_id.upcast(),
+            raw::RawItemKind::Impl(imp) => self.raw_items[imp].ast_id.upcast(),
+            raw::RawItemKind::ExternBlock(ast_id) => ast_id.upcast(),
+            // FIXME: raw items don't keep the source of imports
+            raw::RawItemKind::Import(_) => return,
+        };
+        self.def_collector.def_map.diagnostics.push(DefDiagnostic::MalformedCfg {
+            module: self.module_id,
+            item: AstId::new(self.file_id, ast_id),
+        });
+    }
+
+    /// Raw items are shared between crates, so their `cfg_attr`s are only
+    /// expanded once the crate's cfg options are known.
+    fn expand_cfg_attr(&self, attrs: &Attrs) -> Attrs {
+        attrs.clone().expand_cfg_attr(self.def_collector.cfg_options)
+    }
+
     fn is_cfg_enabled(&self, attrs: &Attrs) -> bool {
-        // FIXME: handle cfg_attr :-)
         attrs
             .by_key("cfg")
             .tt_values()
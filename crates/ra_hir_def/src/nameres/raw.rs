@@ -10,13 +10,13 @@ use std::{ops::Index, sync::Arc};
 use hir_expand::{
     ast_id_map::AstIdMap,
     hygiene::Hygiene,
-    name::{AsName, Name},
+    name::{name, AsName, Name},
 };
 use ra_arena::{Arena, Idx};
 use ra_prof::profile;
 use ra_syntax::{
     ast::{self, AttrsOwner, NameOwner, VisibilityOwner},
-    AstNode,
+    AstNode, T,
 };
 use test_utils::tested_by;
 
@@ -116,6 +116,9 @@ pub(super) enum RawItemKind {
     Def(Idx<DefData>),
     Macro(Idx<MacroData>),
     Impl(Idx<ImplData>),
+    /// The items of extern blocks are not collected yet, the block is only
+    /// kept so that the module is known to miss names.
+    ExternBlock(FileAstId<ast::ExternBlock>),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -266,8 +269,10 @@ impl RawItemsCollector {
                 self.add_macro(current_module, it);
                 return;
             }
-            ast::ModuleItem::ExternBlock(_) => {
-                // FIXME: add extern block
+            ast::ModuleItem::ExternBlock(it) => {
+                // FIXME: add the items of extern blocks
+                let ast_id = self.source_ast_id_map.ast_id(&it);
+                self.push_item(current_module, attrs, RawItemKind::ExternBlock(ast_id));
                 return;
             }
         };
@@ -341,27 +346,32 @@ impl RawItemsCollector {
         current_module: Option<Idx<ModuleData>>,
         extern_crate: ast::ExternCrateItem,
     ) {
-        if let Some(name_ref) = extern_crate.name_ref() {
-            let path = ModPath::from_name_ref(&name_ref);
-            let visibility =
-                RawVisibility::from_ast_with_hygiene(extern_crate.visibility(), &self.hygiene);
-            let alias = extern_crate.alias().map(|a| {
-                a.name().map(|it| it.as_name()).map_or(ImportAlias::Underscore, ImportAlias::Alias)
-            });
-            let attrs = self.parse_attrs(&extern_crate);
-            // FIXME: cfg_attr
-            let is_macro_use = extern_crate.has_atom_attr("macro_use");
-            let import_data = ImportData {
-                path,
-                alias,
-                is_glob: false,
-                is_prelude: false,
-                is_extern_crate: true,
-                is_macro_use,
-                visibility,
-            };
-            self.push_import(current_module, attrs, import_data);
-        }
+        let path = match extern_crate.name_ref() {
+            Some(name_ref) => ModPath::from_name_ref(&name_ref),
+            // `extern crate self as foo;`
+            None if extern_crate.syntax().children().any(|it| it.kind() == T![self]) => {
+                name![self].into()
+            }
+            None => return,
+        };
+        let visibility =
+            RawVisibility::from_ast_with_hygiene(extern_crate.visibility(), &self.hygiene);
+        let alias = extern_crate.alias().map(|a| {
+            a.name().map(|it| it.as_name()).map_or(ImportAlias::Underscore, ImportAlias::Alias)
+        });
+        let attrs = self.parse_attrs(&extern_crate);
+        // FIXME: cfg_attr
+        let is_macro_use = extern_crate.has_atom_attr("macro_use");
+        let import_data = ImportData {
+            path,
+            alias,
+            is_glob: false,
+            is_prelude: false,
+            is_extern_crate: true,
+            is_macro_use,
+            visibility,
+        };
+        self.push_import(current_module, attrs, import_data);
     }
 
     fn add_macro(&mut self, current_module: Option<Idx<ModuleData>>, m: ast::MacroCall) {
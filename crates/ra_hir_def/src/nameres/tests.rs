@@ -416,6 +416,27 @@ fn extern_crate_rename_2015_edition() {
     );
 }
 
+#[test]
+fn extern_crate_self_as() {
+    let map = def_map(
+        "
+        //- /lib.rs
+        extern crate self as bla;
+
+        pub struct Baz;
+
+        use bla::Baz as Bar;
+        ",
+    );
+
+    assert_snapshot!(map, @r###"
+   ⋮crate
+   ⋮Bar: t v
+   ⋮Baz: t v
+   ⋮bla: t
+    "###);
+}
+
 #[test]
 fn import_across_source_roots() {
     let map = def_map(
@@ -573,6 +594,32 @@ fn cfg_test() {
     "###);
 }
 
+#[test]
+fn cfg_attr_test() {
+    let map = def_map(
+        r#"
+        //- /main.rs crate:main deps:std
+        use {Foo, Bar};
+
+        //- /lib.rs crate:std cfg:test
+        #[prelude_import]
+        pub use self::prelude::*;
+        mod prelude {
+            #[cfg_attr(test, cfg(not(test)))]
+            pub struct Foo;
+            #[cfg_attr(not(test), cfg(not(test)))]
+            pub struct Bar;
+        }
+        "#,
+    );
+
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮Bar: t v
+        ⋮Foo: _
+    "###);
+}
+
 #[test]
 fn infer_multiple_namespace() {
     let map = def_map(
@@ -131,3 +131,84 @@ fn typing_inside_a_macro_should_not_invalidate_def_map() {
         assert!(!format!("{:?}", events).contains("crate_def_map"), "{:#?}", events)
     }
 }
+
+#[test]
+fn typing_inside_an_included_file_should_invalidate_def_map() {
+    let (mut db, pos) = TestDB::with_position(
+        r#"
+        //- /lib.rs
+        #[rustc_builtin_macro]
+        macro_rules! include {() => {}}
+
+        include!("generated.rs");
+
+        //- /generated.rs
+        <|>
+        struct X;
+        "#,
+    );
+    let krate = db.test_crate();
+    {
+        let events = db.log_executed(|| {
+            let crate_def_map = db.crate_def_map(krate);
+            let names = crate_def_map[crate_def_map.root]
+                .scope
+                .resolutions()
+                .map(|(name, _)| name.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec!["X"]);
+        });
+        assert!(format!("{:?}", events).contains("crate_def_map"), "{:#?}", events)
+    }
+    db.set_file_text(pos.file_id, Arc::new("struct Y;".to_string()));
+
+    {
+        let events = db.log_executed(|| {
+            let crate_def_map = db.crate_def_map(krate);
+            let names = crate_def_map[crate_def_map.root]
+                .scope
+                .resolutions()
+                .map(|(name, _)| name.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec!["Y"]);
+        });
+        assert!(format!("{:?}", events).contains("crate_def_map"), "{:#?}", events)
+    }
+}
+
+#[test]
+fn typing_inside_a_dependency_should_not_invalidate_def_maps() {
+    let (mut db, steps) = TestDB::with_files_and_steps(
+        r"
+        //- /main.rs crate:main deps:dep
+        use dep::foo;
+
+        //- /dep.rs crate:dep
+        pub fn foo() -> i32 {
+            1 + 1
+        }
+
+        //- /dep.rs step:1
+        pub fn foo() -> i32 { 92 }
+        ",
+    );
+    let crates = db.crate_graph().iter().collect::<Vec<_>>();
+    {
+        let events = db.log_executed(|| {
+            for &krate in crates.iter() {
+                db.crate_def_map(krate);
+            }
+        });
+        assert!(format!("{:?}", events).contains("crate_def_map"), "{:#?}", events)
+    }
+    steps.apply(&mut db, 1);
+
+    {
+        let events = db.log_executed(|| {
+            for &krate in crates.iter() {
+                db.crate_def_map(krate);
+            }
+        });
+        assert!(!format!("{:?}", events).contains("crate_def_map"), "{:#?}", events)
+    }
+}
@@ -624,3 +624,77 @@ fn expand_multiple_derive() {
     );
     assert_eq!(map.modules[map.root].scope.impls().len(), 2);
 }
+
+#[test]
+fn include_defines_items_in_including_module() {
+    let map = def_map(
+        r#"
+        //- /lib.rs
+        #[rustc_builtin_macro]
+        macro_rules! include {() => {}}
+
+        mod generated {
+            include!("generated.rs");
+        }
+        use generated::Bar;
+
+        //- /generated.rs
+        pub struct Bar;
+        pub fn baz() {}
+        "#,
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮Bar: t v
+        ⋮generated: t
+        ⋮
+        ⋮crate::generated
+        ⋮Bar: t v
+        ⋮baz: v
+    "###);
+}
+
+#[test]
+fn include_from_out_dir() {
+    let map = def_map(
+        r#"
+        //- /lib.rs out_dir:/out
+        #[rustc_builtin_macro]
+        macro_rules! include {() => {}}
+
+        #[rustc_builtin_macro]
+        macro_rules! concat {() => {}}
+
+        #[rustc_builtin_macro]
+        macro_rules! env {() => {}}
+
+        include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+
+        //- root /out/
+
+        //- /out/generated.rs
+        pub struct Bar;
+        "#,
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮Bar: t v
+    "###);
+}
+
+#[test]
+fn resolves_proc_macros_of_dependencies() {
+    let map = def_map(
+        "
+        //- /main.rs crate:main deps:serde_derive
+        use serde_derive::{Deserialize, Serialize};
+
+        //- /serde_derive.rs crate:serde_derive proc_macros:Serialize,Deserialize
+        ",
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮Deserialize: m
+        ⋮Serialize: m
+    "###);
+}
@@ -375,6 +375,82 @@ fn module_resolution_explicit_path_mod_rs_with_win_separator() {
     "###);
 }
 
+#[test]
+fn module_resolution_explicit_path_with_escaped_win_separator() {
+    let map = def_map(
+        r###"
+        //- /main.rs
+        #[path = "module\\bar\\mod.rs"]
+        mod foo;
+
+        //- /module/bar/mod.rs
+        pub struct Baz;
+        "###,
+    );
+
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮foo: t
+        ⋮
+        ⋮crate::foo
+        ⋮Baz: t v
+    "###);
+}
+
+#[test]
+fn module_resolution_explicit_path_raw_string() {
+    let map = def_map(
+        r###"
+        //- /main.rs
+        #[path = r"generated\out.rs"]
+        mod foo;
+
+        //- /generated/out.rs
+        mod bar;
+
+        //- /generated/bar.rs
+        pub struct Baz;
+        "###,
+    );
+
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮foo: t
+        ⋮
+        ⋮crate::foo
+        ⋮bar: t
+        ⋮
+        ⋮crate::foo::bar
+        ⋮Baz: t v
+    "###);
+}
+
+#[test]
+fn module_resolution_explicit_path_in_cfg_attr() {
+    let map = def_map(
+        r###"
+        //- /main.rs crate:main cfg:feature=generated
+        #[cfg_attr(feature = "generated", path = "generated/out.rs")]
+        #[cfg_attr(not(feature = "generated"), path = "fallback.rs")]
+        mod foo;
+
+        //- /generated/out.rs
+        pub struct Baz;
+
+        //- /fallback.rs
+        pub struct Fallback;
+        "###,
+    );
+
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮foo: t
+        ⋮
+        ⋮crate::foo
+        ⋮Baz: t v
+    "###);
+}
+
 #[test]
 fn module_resolution_decl_inside_inline_module_with_path_attribute() {
     let map = def_map(
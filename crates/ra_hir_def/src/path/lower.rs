@@ -9,11 +9,11 @@ use hir_expand::{
     hygiene::Hygiene,
     name::{name, AsName},
 };
-use ra_syntax::ast::{self, AstNode, TypeAscriptionOwner};
+use ra_syntax::ast::{self, AstNode, TypeAscriptionOwner, TypeBoundsOwner};
 
 use crate::{
-    path::{GenericArg, GenericArgs, ModPath, Path, PathKind},
-    type_ref::TypeRef,
+    path::{AssocTypeBinding, GenericArg, GenericArgs, ModPath, Path, PathKind},
+    type_ref::{type_bounds_from_ast, ConstRef, TypeRef},
 };
 
 pub(super) use lower_use::lower_use_tree;
@@ -133,13 +133,18 @@ pub(super) fn lower_generic_args(node: ast::TypeArgList) -> Option<GenericArgs>
         let type_ref = TypeRef::from_ast_opt(type_arg.type_ref());
         args.push(GenericArg::Type(type_ref));
     }
+    // const args come after type args
+    for const_arg in node.const_args() {
+        args.push(GenericArg::Const(ConstRef::from_ast(const_arg)));
+    }
     // lifetimes ignored for now
     let mut bindings = Vec::new();
     for assoc_type_arg in node.assoc_type_args() {
         if let Some(name_ref) = assoc_type_arg.name_ref() {
             let name = name_ref.as_name();
-            let type_ref = TypeRef::from_ast_opt(assoc_type_arg.type_ref());
-            bindings.push((name, type_ref));
+            let type_ref = assoc_type_arg.type_ref().map(TypeRef::from_ast);
+            let bounds = type_bounds_from_ast(assoc_type_arg.type_bound_list());
+            bindings.push(AssocTypeBinding { name, type_ref, bounds });
         }
     }
     if args.is_empty() && bindings.is_empty() {
@@ -168,7 +173,11 @@ fn lower_generic_args_from_fn_path(
     }
     if let Some(ret_type) = ret_type {
         let type_ref = TypeRef::from_ast_opt(ret_type.type_ref());
-        bindings.push((name![Output], type_ref))
+        bindings.push(AssocTypeBinding {
+            name: name![Output],
+            type_ref: Some(type_ref),
+            bounds: Vec::new(),
+        });
     }
     if args.is_empty() && bindings.is_empty() {
         None
@@ -14,7 +14,7 @@ use crate::{
     builtin_type::BuiltinType,
     db::DefDatabase,
     expr::{ExprId, PatId},
-    generics::GenericParams,
+    generics::{GenericParams, TypeParamProvenance},
     item_scope::{BuiltinShadowMode, BUILTIN_SCOPE},
     nameres::CrateDefMap,
     path::{ModPath, PathKind},
@@ -92,6 +92,7 @@ pub enum ValueNs {
     StaticId(StaticId),
     StructId(StructId),
     EnumVariantId(EnumVariantId),
+    ConstParam(TypeParamId),
 }
 
 impl Resolver {
@@ -289,7 +290,15 @@ impl Resolver {
                         return Some(ResolveValueResult::Partial(ty, 1));
                     }
                 }
-                Scope::GenericParams { .. } => continue,
+                Scope::GenericParams { params, def } => {
+                    if let Some(local_id) = params.find_by_name(first_name) {
+                        let data = &params.types[local_id];
+                        if data.provenance == TypeParamProvenance::ConstParam {
+                            let id = TypeParamId { local_id, parent: *def };
+                            return Some(ResolveValueResult::ValueNs(ValueNs::ConstParam(id)));
+                        }
+                    }
+                }
 
                 Scope::ImplDefScope(impl_) if n_segments > 1 => {
                     if first_name == &name![Self] {
@@ -437,6 +446,16 @@ impl Resolver {
         self.module_scope().map(|t| t.0.krate)
     }
 
+    /// Whether some of the scopes may be missing names, because of imports
+    /// or macro calls which could not be resolved.
+    pub fn has_unresolved_names(&self) -> bool {
+        self.scopes.iter().any(|scope| match scope {
+            Scope::ModuleScope(m) => m.crate_def_map[m.module_id].has_unresolved_names,
+            Scope::LocalItemsScope(body) => body.has_unresolved_names,
+            _ => false,
+        })
+    }
+
     pub fn where_predicates_in_scope<'a>(
         &'a self,
     ) -> impl Iterator<Item = &'a crate::generics::WherePredicate> + 'a {
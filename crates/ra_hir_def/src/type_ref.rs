@@ -3,7 +3,10 @@
 
 use ra_syntax::ast::{self, TypeAscriptionOwner, TypeBoundsOwner};
 
-use crate::path::Path;
+use crate::{
+    expr::{ArithOp, BinaryOp},
+    path::Path,
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Mutability {
@@ -44,7 +47,7 @@ pub enum TypeRef {
     Path(Path),
     RawPtr(Box<TypeRef>, Mutability),
     Reference(Box<TypeRef>, Mutability),
-    Array(Box<TypeRef> /*, Expr*/),
+    Array(Box<TypeRef>, ConstRef),
     Slice(Box<TypeRef>),
     /// A fn pointer. Last element of the vector is the return type.
     Fn(Vec<TypeRef>),
@@ -54,11 +57,25 @@ pub enum TypeRef {
     Error,
 }
 
+/// A constant in a type, like the `3` in `Foo<3>` or the length of an array.
+/// Only integer literals, paths and arithmetic on them are lowered; they are
+/// evaluated when the type is lowered.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ConstRef {
+    Int(u128),
+    Path(Path),
+    Neg(Box<ConstRef>),
+    BinaryOp { op: ArithOp, lhs: Box<ConstRef>, rhs: Box<ConstRef> },
+    Unknown,
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum TypeBound {
+    /// A trait bound. Higher-ranked bounds like `for<'a> Fn(&'a T)` are
+    /// lowered to their trait, as lifetimes are erased.
     Path(Path),
-    // also for<> bounds
-    // also Lifetimes
+    /// A lifetime bound like `'a`, which doesn't constrain the erased type.
+    Lifetime,
     Error,
 }
 
@@ -81,7 +98,8 @@ impl TypeRef {
                 TypeRef::RawPtr(Box::new(inner_ty), mutability)
             }
             ast::TypeRef::ArrayType(inner) => {
-                TypeRef::Array(Box::new(TypeRef::from_ast_opt(inner.type_ref())))
+                let len = inner.expr().map_or(ConstRef::Unknown, ConstRef::from_expr);
+                TypeRef::Array(Box::new(TypeRef::from_ast_opt(inner.type_ref())), len)
             }
             ast::TypeRef::SliceType(inner) => {
                 TypeRef::Slice(Box::new(TypeRef::from_ast_opt(inner.type_ref())))
@@ -138,13 +156,13 @@ impl TypeRef {
                 TypeRef::Fn(types) | TypeRef::Tuple(types) => types.iter().for_each(|t| go(t, f)),
                 TypeRef::RawPtr(type_ref, _)
                 | TypeRef::Reference(type_ref, _)
-                | TypeRef::Array(type_ref)
+                | TypeRef::Array(type_ref, _)
                 | TypeRef::Slice(type_ref) => go(&type_ref, f),
                 TypeRef::ImplTrait(bounds) | TypeRef::DynTrait(bounds) => {
                     for bound in bounds {
                         match bound {
                             TypeBound::Path(path) => go_path(path, f),
-                            TypeBound::Error => (),
+                            TypeBound::Lifetime | TypeBound::Error => (),
                         }
                     }
                 }
@@ -160,11 +178,20 @@ impl TypeRef {
             for segment in path.segments().iter() {
                 if let Some(args_and_bindings) = segment.args_and_bindings {
                     for arg in &args_and_bindings.args {
-                        let crate::path::GenericArg::Type(type_ref) = arg;
-                        go(type_ref, f);
+                        if let crate::path::GenericArg::Type(type_ref) = arg {
+                            go(type_ref, f);
+                        }
                     }
-                    for (_, type_ref) in &args_and_bindings.bindings {
-                        go(type_ref, f);
+                    for binding in &args_and_bindings.bindings {
+                        if let Some(type_ref) = &binding.type_ref {
+                            go(type_ref, f);
+                        }
+                        for bound in &binding.bounds {
+                            match bound {
+                                TypeBound::Path(path) => go_path(path, f),
+                                TypeBound::Lifetime | TypeBound::Error => (),
+                            }
+                        }
                     }
                 }
             }
@@ -172,6 +199,73 @@ impl TypeRef {
     }
 }
 
+impl ConstRef {
+    /// Converts an `ast::ConstArg`, which is either a literal or a block.
+    pub(crate) fn from_ast(node: ast::ConstArg) -> Self {
+        if let Some(literal) = node.literal() {
+            ConstRef::from_expr(ast::Expr::Literal(literal))
+        } else if let Some(block) = node.block_expr() {
+            ConstRef::from_expr(ast::Expr::BlockExpr(block))
+        } else {
+            ConstRef::Unknown
+        }
+    }
+
+    /// Lowers an array length or an enum discriminant.
+    pub fn from_expr(expr: ast::Expr) -> Self {
+        let from_expr_opt =
+            |expr: Option<ast::Expr>| expr.map_or(ConstRef::Unknown, ConstRef::from_expr);
+        match expr {
+            ast::Expr::Literal(literal) => match literal.kind() {
+                ast::LiteralKind::IntNumber { .. } => {
+                    parse_int(literal.token().text()).map_or(ConstRef::Unknown, ConstRef::Int)
+                }
+                _ => ConstRef::Unknown,
+            },
+            ast::Expr::PathExpr(e) => {
+                // FIXME: Use `Path::from_src`
+                e.path().and_then(Path::from_ast).map_or(ConstRef::Unknown, ConstRef::Path)
+            }
+            ast::Expr::ParenExpr(e) => from_expr_opt(e.expr()),
+            ast::Expr::BlockExpr(e) => match e.block() {
+                Some(block) if e.unsafe_kw_token().is_none() && block.statements().count() == 0 => {
+                    from_expr_opt(block.expr())
+                }
+                _ => ConstRef::Unknown,
+            },
+            ast::Expr::PrefixExpr(e) if e.op_kind() == Some(ast::PrefixOp::Neg) => {
+                ConstRef::Neg(Box::new(from_expr_opt(e.expr())))
+            }
+            ast::Expr::BinExpr(e) => match e.op_kind().map(BinaryOp::from) {
+                Some(BinaryOp::ArithOp(op)) => ConstRef::BinaryOp {
+                    op,
+                    lhs: Box::new(from_expr_opt(e.lhs())),
+                    rhs: Box::new(from_expr_opt(e.rhs())),
+                },
+                _ => ConstRef::Unknown,
+            },
+            _ => ConstRef::Unknown,
+        }
+    }
+}
+
+pub(crate) fn parse_int(text: &str) -> Option<u128> {
+    const SUFFIXES: &[&str] =
+        &["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"];
+    let text = text.replace('_', "");
+    let (text, radix) = match text.get(..2) {
+        Some("0x") => (&text[2..], 16),
+        Some("0o") => (&text[2..], 8),
+        Some("0b") => (&text[2..], 2),
+        _ => (&text[..], 10),
+    };
+    let text = match SUFFIXES.iter().find(|it| text.ends_with(*it)) {
+        Some(suffix) => &text[..text.len() - suffix.len()],
+        None => text,
+    };
+    u128::from_str_radix(text, radix).ok()
+}
+
 pub(crate) fn type_bounds_from_ast(type_bounds_opt: Option<ast::TypeBoundList>) -> Vec<TypeBound> {
     if let Some(type_bounds) = type_bounds_opt {
         type_bounds.bounds().map(TypeBound::from_ast).collect()
@@ -182,21 +276,24 @@ pub(crate) fn type_bounds_from_ast(type_bounds_opt: Option<ast::TypeBoundList>)
 
 impl TypeBound {
     pub(crate) fn from_ast(node: ast::TypeBound) -> Self {
-        match node.kind() {
-            ast::TypeBoundKind::PathType(path_type) => {
-                let path = match path_type.path() {
-                    Some(p) => p,
-                    None => return TypeBound::Error,
-                };
-                // FIXME: Use `Path::from_src`
-                let path = match Path::from_ast(path) {
-                    Some(p) => p,
-                    None => return TypeBound::Error,
-                };
-                TypeBound::Path(path)
-            }
-            ast::TypeBoundKind::ForType(_) | ast::TypeBoundKind::Lifetime(_) => TypeBound::Error,
-        }
+        let path_type = match node.kind() {
+            ast::TypeBoundKind::PathType(path_type) => path_type,
+            ast::TypeBoundKind::ForType(for_type) => match for_type.type_ref() {
+                Some(ast::TypeRef::PathType(path_type)) => path_type,
+                _ => return TypeBound::Error,
+            },
+            ast::TypeBoundKind::Lifetime(_) => return TypeBound::Lifetime,
+        };
+        let path = match path_type.path() {
+            Some(p) => p,
+            None => return TypeBound::Error,
+        };
+        // FIXME: Use `Path::from_src`
+        let path = match Path::from_ast(path) {
+            Some(p) => p,
+            None => return TypeBound::Error,
+        };
+        TypeBound::Path(path)
     }
 
     pub fn as_path(&self) -> Option<&Path> {
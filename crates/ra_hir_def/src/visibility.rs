@@ -1,12 +1,18 @@
 //! Defines hir-level representation of visibility (e.g. `pub` and `pub(crate)`).
 
+use std::sync::Arc;
+
 use hir_expand::{hygiene::Hygiene, InFile};
+use ra_arena::map::ArenaMap;
 use ra_syntax::ast;
 
 use crate::{
     db::DefDatabase,
+    item_scope::ItemInNs,
+    nameres::CrateDefMap,
     path::{ModPath, PathKind},
-    AssocContainerId, ModuleId,
+    resolver::HasResolver,
+    AssocContainerId, CrateId, LocalStructFieldId, ModuleDefId, ModuleId, VariantId,
 };
 
 /// Visibility of an item, not yet resolved.
@@ -149,3 +155,341 @@ impl Visibility {
         ancestors.any(|m| m == to_module.local_id)
     }
 }
+
+/// Resolves the visibilities of the fields of a variant. Fields of enum
+/// variants are always public.
+pub(crate) fn field_visibilities_query(
+    db: &dyn DefDatabase,
+    variant_id: VariantId,
+) -> Arc<ArenaMap<LocalStructFieldId, Visibility>> {
+    let variant_data = match variant_id {
+        VariantId::StructId(it) => db.struct_data(it).variant_data.clone(),
+        VariantId::UnionId(it) => db.union_data(it).variant_data.clone(),
+        VariantId::EnumVariantId(it) => db.enum_variant_data(it),
+    };
+    let resolver = variant_id.resolver(db);
+    let mut res = ArenaMap::default();
+    for (field_id, field_data) in variant_data.fields().iter() {
+        let vis = match variant_id {
+            VariantId::EnumVariantId(_) => Visibility::Public,
+            _ => field_data.visibility.resolve(db, &resolver),
+        };
+        res.insert(field_id, vis);
+    }
+    Arc::new(res)
+}
+
+/// The fields of a variant which can be accessed from `from`.
+pub(crate) fn visible_fields_query(
+    db: &dyn DefDatabase,
+    variant_id: VariantId,
+    from: ModuleId,
+) -> Arc<[LocalStructFieldId]> {
+    db.field_visibilities(variant_id)
+        .iter()
+        .filter(|(_, vis)| vis.is_visible_from(db, from))
+        .map(|(field_id, _)| field_id)
+        .collect()
+}
+
+/// The modules from which an item can be named, either where it is defined
+/// or through a re-export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VisibleFrom {
+    /// Other crates can name the item as well.
+    Everywhere,
+    /// Only the given modules and their descendants can name the item. This
+    /// is empty if the item can't be named at all.
+    Modules(Vec<ModuleId>),
+}
+
+impl From<Visibility> for VisibleFrom {
+    fn from(vis: Visibility) -> VisibleFrom {
+        match vis {
+            Visibility::Module(m) => VisibleFrom::Modules(vec![m]),
+            Visibility::Public => VisibleFrom::Everywhere,
+        }
+    }
+}
+
+/// Computes the modules from which `item`, defined in `krate`, can be named.
+/// The item is only visible where both its own visibility and the module
+/// containing it are, but each re-export adds another way to reach it.
+pub fn visible_from(db: &dyn DefDatabase, item: ItemInNs, krate: CrateId) -> VisibleFrom {
+    let def_map = db.crate_def_map(krate);
+    let reach = reach_of(&def_map, item, &mut Vec::new());
+    to_visible_from(reach)
+}
+
+/// Computes the modules from which an item with visibility `vis` that isn't
+/// part of any module scope, like an associated item of an impl, can be
+/// named when it is defined in `module`.
+pub fn visible_from_module(db: &dyn DefDatabase, vis: Visibility, module: ModuleId) -> VisibleFrom {
+    let def_map = db.crate_def_map(module.krate);
+    let reach = reach_of(&def_map, ItemInNs::Types(module.into()), &mut Vec::new())
+        .into_iter()
+        .filter_map(|it| intersect(&def_map, vis, it))
+        .collect();
+    to_visible_from(remove_redundant(&def_map, reach))
+}
+
+fn to_visible_from(reach: Vec<Visibility>) -> VisibleFrom {
+    if reach.contains(&Visibility::Public) {
+        return VisibleFrom::Everywhere;
+    }
+    let modules = reach
+        .into_iter()
+        .filter_map(|vis| match vis {
+            Visibility::Module(m) => Some(m),
+            Visibility::Public => None,
+        })
+        .collect();
+    VisibleFrom::Modules(modules)
+}
+
+fn reach_of(
+    def_map: &CrateDefMap,
+    item: ItemInNs,
+    visiting: &mut Vec<ItemInNs>,
+) -> Vec<Visibility> {
+    let mut reach = Vec::new();
+    if let ItemInNs::Types(ModuleDefId::ModuleId(module)) = item {
+        if module.krate == def_map.krate {
+            if module.local_id == def_map.root {
+                return vec![Visibility::Public];
+            }
+            // A module can always be named from inside, via `self` and `super`.
+            reach.push(Visibility::Module(module));
+        }
+    }
+    // Re-exports may form cycles.
+    if visiting.contains(&item) {
+        return reach;
+    }
+    visiting.push(item);
+
+    if let Some(ModuleDefId::EnumVariantId(variant)) = item.as_module_def_id() {
+        reach.extend(reach_of(def_map, ItemInNs::Types(variant.parent.into()), visiting));
+    }
+    for (local_id, data) in def_map.modules.iter() {
+        let vis = match data.scope.name_of(item) {
+            Some((_, vis)) => vis,
+            None => continue,
+        };
+        let module = ModuleId { krate: def_map.krate, local_id };
+        for module_reach in reach_of(def_map, ItemInNs::Types(module.into()), visiting) {
+            reach.extend(intersect(def_map, vis, module_reach));
+        }
+    }
+
+    visiting.pop();
+    remove_redundant(def_map, reach)
+}
+
+fn intersect(def_map: &CrateDefMap, vis1: Visibility, vis2: Visibility) -> Option<Visibility> {
+    match (vis1, vis2) {
+        (Visibility::Public, vis) | (vis, Visibility::Public) => Some(vis),
+        (Visibility::Module(m1), Visibility::Module(m2)) => {
+            if vis1.is_visible_from_def_map(def_map, m2.local_id) {
+                Some(vis2)
+            } else if vis2.is_visible_from_def_map(def_map, m1.local_id) {
+                Some(vis1)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Removes the visibilities which are contained in another one.
+fn remove_redundant(def_map: &CrateDefMap, reach: Vec<Visibility>) -> Vec<Visibility> {
+    let contains = |outer: Visibility, inner: Visibility| match inner {
+        Visibility::Module(m) => outer.is_visible_from_def_map(def_map, m.local_id),
+        Visibility::Public => outer == Visibility::Public,
+    };
+    let mut res: Vec<Visibility> = Vec::new();
+    for vis in reach {
+        if res.iter().any(|&it| contains(it, vis)) {
+            continue;
+        }
+        res.retain(|&it| !contains(vis, it));
+        res.push(vis);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use hir_expand::hygiene::Hygiene;
+    use ra_db::fixture::WithFixture;
+    use ra_syntax::ast::AstNode;
+
+    use super::*;
+    use crate::{item_scope::BuiltinShadowMode, per_ns::PerNs, test_db::TestDB, AdtId};
+
+    fn resolve(db: &TestDB, module: ModuleId, path: &str) -> PerNs {
+        let parsed_path_file = ra_syntax::SourceFile::parse(&format!("use {};", path));
+        let ast_path = parsed_path_file
+            .syntax_node()
+            .descendants()
+            .find_map(ra_syntax::ast::Path::cast)
+            .unwrap();
+        let mod_path = ModPath::from_src(ast_path, &Hygiene::new_unhygienic()).unwrap();
+        let def_map = db.crate_def_map(module.krate);
+        def_map.resolve_path(db, module.local_id, &mod_path, BuiltinShadowMode::Module).0
+    }
+
+    /// Checks from where the item `path` refers to in the module with the
+    /// cursor is visible, with modules written as paths from the crate root.
+    fn check_visible_from(code: &str, path: &str, expected: &[&str]) {
+        let (db, pos) = TestDB::with_position(code);
+        let module = db.module_for_file(pos.file_id);
+        let def_map = db.crate_def_map(module.krate);
+        let per_ns = resolve(&db, module, path);
+        let item = match per_ns.take_types() {
+            Some(it) => ItemInNs::Types(it),
+            None => ItemInNs::Values(per_ns.take_values().unwrap()),
+        };
+
+        let actual = match visible_from(&db, item, module.krate) {
+            VisibleFrom::Everywhere => vec!["everywhere".to_string()],
+            VisibleFrom::Modules(modules) => modules
+                .into_iter()
+                .map(|m| {
+                    let mut segments = vec!["crate".to_string()];
+                    let mut names = Vec::new();
+                    let mut local_id = m.local_id;
+                    while let Some(parent) = def_map[local_id].parent {
+                        let (name, _) = def_map[parent]
+                            .children
+                            .iter()
+                            .find(|(_, child)| **child == local_id)
+                            .unwrap();
+                        names.push(name.to_string());
+                        local_id = parent;
+                    }
+                    segments.extend(names.into_iter().rev());
+                    segments.join("::")
+                })
+                .collect(),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn visible_from_declared_visibility() {
+        let code = r#"
+            //- /main.rs
+            pub fn f() {}
+            fn g() {}
+            mod a {
+                pub(super) struct S;
+            }
+            <|>
+        "#;
+        check_visible_from(code, "f", &["everywhere"]);
+        check_visible_from(code, "g", &["crate"]);
+        check_visible_from(code, "a::S", &["crate"]);
+    }
+
+    #[test]
+    fn visible_from_limited_by_modules() {
+        let code = r#"
+            //- /main.rs
+            mod a {
+                pub mod b {
+                    pub fn f() {}
+                    pub(crate) enum E { V }
+                }
+                mod c {
+                    pub fn g() {}
+                }
+                <|>
+            }
+        "#;
+        check_visible_from(code, "b::f", &["crate"]);
+        check_visible_from(code, "b::E::V", &["crate"]);
+        check_visible_from(code, "c::g", &["crate::a"]);
+    }
+
+    #[test]
+    fn visible_from_through_reexports() {
+        let code = r#"
+            //- /main.rs
+            mod a {
+                mod b {
+                    pub fn f() {}
+                    pub fn g() {}
+                }
+                pub use self::b::f;
+                pub(crate) use self::b::g;
+            }
+            pub use a::f;
+            <|>
+        "#;
+        check_visible_from(code, "f", &["everywhere"]);
+        check_visible_from(code, "a::g", &["crate"]);
+    }
+
+    /// Checks which fields of the struct `path` refers to can be accessed
+    /// from the module with the cursor.
+    fn check_visible_fields(code: &str, path: &str, expected: &[&str]) {
+        let (db, pos) = TestDB::with_position(code);
+        let module = db.module_for_file(pos.file_id);
+        let strukt = match resolve(&db, module, path).take_types() {
+            Some(ModuleDefId::AdtId(AdtId::StructId(it))) => it,
+            _ => panic!("no struct at `{}`", path),
+        };
+        let variant_data = db.struct_data(strukt).variant_data.clone();
+        let actual: Vec<_> = db
+            .visible_fields(strukt.into(), module)
+            .iter()
+            .map(|&it| variant_data.fields()[it].name.to_string())
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn visible_fields_outside_of_restriction() {
+        check_visible_fields(
+            r#"
+            //- /main.rs
+            mod a;
+            mod c;
+            //- /a.rs
+            pub struct S {
+                pub x: u32,
+                pub(crate) y: u32,
+                pub(in crate::a) z: u32,
+                w: u32,
+            }
+            //- /c.rs
+            <|>
+            "#,
+            "crate::a::S",
+            &["x", "y"],
+        );
+    }
+
+    #[test]
+    fn visible_fields_inside_of_restriction() {
+        check_visible_fields(
+            r#"
+            //- /main.rs
+            mod a;
+            //- /a.rs
+            pub struct S {
+                pub x: u32,
+                pub(crate) y: u32,
+                pub(in crate::a) z: u32,
+                w: u32,
+            }
+            mod b;
+            //- /a/b.rs
+            <|>
+            "#,
+            "super::S",
+            &["x", "y", "z", "w"],
+        );
+    }
+}
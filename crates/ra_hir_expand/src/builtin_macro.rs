@@ -318,14 +318,21 @@ fn parse_string(tt: &tt::Subtree) -> Result<String, mbe::ExpandError> {
         .ok_or_else(|| mbe::ExpandError::ConversionError)
 }
 
+pub(crate) fn include_input_to_file_id(
+    db: &dyn AstDatabase,
+    arg_id: EagerMacroId,
+    tt: &tt::Subtree,
+) -> Result<FileId, mbe::ExpandError> {
+    let path = parse_string(tt)?;
+    relative_file(db, arg_id.into(), &path).ok_or_else(|| mbe::ExpandError::ConversionError)
+}
+
 fn include_expand(
     db: &dyn AstDatabase,
     arg_id: EagerMacroId,
     tt: &tt::Subtree,
 ) -> Result<(tt::Subtree, FragmentKind), mbe::ExpandError> {
-    let path = parse_string(tt)?;
-    let file_id =
-        relative_file(db, arg_id.into(), &path).ok_or_else(|| mbe::ExpandError::ConversionError)?;
+    let file_id = include_input_to_file_id(db, arg_id, tt)?;
 
     // FIXME:
     // Handle include as expression
@@ -436,6 +443,7 @@ mod tests {
                         fragment: FragmentKind::Expr,
                         subtree: Arc::new(parsed_args.clone()),
                         file_id: file_id.into(),
+                        included_file: None,
                     }
                 });
 
@@ -445,6 +453,7 @@ mod tests {
                     fragment,
                     subtree: Arc::new(subtree),
                     file_id: file_id.into(),
+                    included_file: None,
                 };
 
                 let id: MacroCallId = db.intern_eager_expansion(eager.into()).into();
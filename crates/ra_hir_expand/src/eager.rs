@@ -21,6 +21,7 @@
 
 use crate::{
     ast::{self, AstNode},
+    builtin_macro::{include_input_to_file_id, EagerExpander},
     db::AstDatabase,
     EagerCallLoc, EagerMacroId, InFile, MacroCallId, MacroCallKind, MacroDefId, MacroDefKind,
 };
@@ -48,6 +49,7 @@ pub fn expand_eager_macro(
             fragment: FragmentKind::Expr,
             subtree: Arc::new(parsed_args.clone()),
             file_id: macro_call.file_id,
+            included_file: None,
         }
     });
     let arg_file_id: MacroCallId = arg_id.into();
@@ -61,9 +63,19 @@ pub fn expand_eager_macro(
     let subtree = to_subtree(&result)?;
 
     if let MacroDefKind::BuiltInEager(eager) = def.kind {
+        // Items of an included file are mapped back to it for navigation.
+        let included_file = match eager {
+            EagerExpander::Include => include_input_to_file_id(db, arg_id, &subtree).ok(),
+            _ => None,
+        };
         let (subtree, fragment) = eager.expand(db, arg_id, &subtree).ok()?;
-        let eager =
-            EagerCallLoc { def, fragment, subtree: Arc::new(subtree), file_id: macro_call.file_id };
+        let eager = EagerCallLoc {
+            def,
+            fragment,
+            subtree: Arc::new(subtree),
+            file_id: macro_call.file_id,
+            included_file,
+        };
 
         Some(db.intern_eager_expansion(eager))
     } else {
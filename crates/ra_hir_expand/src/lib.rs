@@ -18,11 +18,11 @@ pub mod eager;
 use std::hash::Hash;
 use std::sync::Arc;
 
-use ra_db::{impl_intern_key, salsa, CrateId, FileId};
+use ra_db::{impl_intern_key, salsa, CrateId, FileId, FileRange};
 use ra_syntax::{
     algo,
     ast::{self, AstNode},
-    SyntaxNode, SyntaxToken, TextUnit,
+    Direction, SyntaxNode, SyntaxToken, TextUnit,
 };
 
 use crate::ast_id_map::FileAstId;
@@ -88,6 +88,16 @@ impl HirFileId {
         }
     }
 
+    /// For the expansion of an `include!`, returns the included file.
+    pub fn included_file(self, db: &dyn db::AstDatabase) -> Option<FileId> {
+        match self.0 {
+            HirFileIdRepr::MacroFile(MacroFile { macro_call_id: MacroCallId::EagerMacro(id) }) => {
+                db.lookup_intern_eager_expansion(id).included_file
+            }
+            _ => None,
+        }
+    }
+
     /// If this is a macro call, returns the syntax node of the call.
     pub fn call_node(self, db: &dyn db::AstDatabase) -> Option<InFile<SyntaxNode>> {
         match self.0 {
@@ -273,6 +283,8 @@ pub struct EagerCallLoc {
     pub(crate) fragment: FragmentKind,
     pub(crate) subtree: Arc<tt::Subtree>,
     pub(crate) file_id: HirFileId,
+    /// The file an `include!` expands to.
+    pub(crate) included_file: Option<FileId>,
 }
 
 /// ExpansionInfo mainly describes how to map text range between src and expanded macro
@@ -389,6 +401,29 @@ impl<T> InFile<Option<T>> {
     }
 }
 
+impl InFile<&SyntaxNode> {
+    /// Maps a node of the expansion of an `include!` to its range in the
+    /// included file.
+    pub fn included_file_range(self, db: &dyn db::AstDatabase) -> Option<FileRange> {
+        let file_id = self.file_id.included_file(db)?;
+        let macro_file = match self.file_id.0 {
+            HirFileIdRepr::MacroFile(it) => it,
+            HirFileIdRepr::FileId(_) => return None,
+        };
+        let (_, exp_map) = db.parse_macro(macro_file)?;
+        // The token ids of the expansion are the ones of the included text.
+        let (_, file_map) = mbe::parse_to_token_tree(&db.file_text(file_id))?;
+        let map_token = |token: SyntaxToken| {
+            let token_id = exp_map.token_by_range(token.text_range())?;
+            file_map.range_by_token(token_id)?.by_kind(token.kind())
+        };
+        let first = algo::skip_trivia_token(self.value.first_token()?, Direction::Next)?;
+        let last = algo::skip_trivia_token(self.value.last_token()?, Direction::Prev)?;
+        let range = map_token(first)?.extend_to(&map_token(last)?);
+        Some(FileRange { file_id, range })
+    }
+}
+
 impl InFile<SyntaxNode> {
     pub fn ancestors_with_macros(
         self,
@@ -194,7 +194,7 @@ use smallvec::{smallvec, SmallVec};
 use crate::{
     db::HirDatabase,
     expr::{Body, Expr, Literal, Pat, PatId},
-    InferenceResult,
+    ApplicationTy, InferenceResult, Ty, TypeCtor,
 };
 use hir_def::{adt::VariantData, EnumVariantId, VariantId};
 
@@ -214,18 +214,33 @@ enum PatIdOrWild {
 
 impl PatIdOrWild {
     fn as_pat(self, cx: &MatchCheckCtx) -> Pat {
-        match self {
+        match self.strip_bindings(cx) {
             PatIdOrWild::PatId(id) => cx.body.pats[id].clone(),
             PatIdOrWild::Wild => Pat::Wild,
         }
     }
 
-    fn as_id(self) -> Option<PatId> {
-        match self {
+    fn as_id(self, cx: &MatchCheckCtx) -> Option<PatId> {
+        match self.strip_bindings(cx) {
             PatIdOrWild::PatId(id) => Some(id),
             PatIdOrWild::Wild => None,
         }
     }
+
+    /// Bindings don't affect which values a pattern matches, so `x` is
+    /// treated like `_` and `x @ p` like `p`.
+    fn strip_bindings(self, cx: &MatchCheckCtx) -> PatIdOrWild {
+        match self {
+            PatIdOrWild::PatId(id) => match &cx.body.pats[id] {
+                Pat::Bind { subpat: None, .. } => PatIdOrWild::Wild,
+                Pat::Bind { subpat: Some(subpat), .. } => {
+                    PatIdOrWild::PatId(*subpat).strip_bindings(cx)
+                }
+                _ => self,
+            },
+            PatIdOrWild::Wild => self,
+        }
+    }
 }
 
 impl From<PatId> for PatIdOrWild {
@@ -289,10 +304,14 @@ impl PatStack {
         Self::from_slice(&self.0[1..])
     }
 
-    fn replace_head_with(&self, pat_ids: &[PatId]) -> PatStack {
+    fn replace_head_with<I, T>(&self, pats: I) -> PatStack
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<PatIdOrWild>,
+    {
         let mut patterns: PatStackInner = smallvec![];
-        for pat in pat_ids {
-            patterns.push((*pat).into());
+        for pat in pats {
+            patterns.push(pat.into());
         }
         for pat in &self.0[1..] {
             patterns.push(*pat);
@@ -327,7 +346,7 @@ impl PatStack {
                     "we type check before calling this code, so we should never hit this case",
                 );
 
-                Some(self.replace_head_with(pat_ids))
+                Some(self.replace_head_with(pat_ids.iter().copied()))
             }
             (Pat::Lit(lit_expr), Constructor::Bool(constructor_val)) => {
                 match cx.body.exprs[lit_expr] {
@@ -344,7 +363,7 @@ impl PatStack {
             (Pat::Wild, constructor) => Some(self.expand_wildcard(cx, constructor)?),
             (Pat::Path(_), Constructor::Enum(constructor)) => {
                 // unit enum variants become `Pat::Path`
-                let pat_id = self.head().as_id().expect("we know this isn't a wild");
+                let pat_id = self.head().as_id(cx).expect("we know this isn't a wild");
                 if !enum_variant_matches(cx, pat_id, *constructor) {
                     None
                 } else {
@@ -352,7 +371,7 @@ impl PatStack {
                 }
             }
             (Pat::TupleStruct { args: ref pat_ids, .. }, Constructor::Enum(enum_constructor)) => {
-                let pat_id = self.head().as_id().expect("we know this isn't a wild");
+                let pat_id = self.head().as_id(cx).expect("we know this isn't a wild");
                 if !enum_variant_matches(cx, pat_id, *enum_constructor) {
                     None
                 } else {
@@ -363,7 +382,46 @@ impl PatStack {
                         return Err(MatchCheckErr::MalformedMatchArm);
                     }
 
-                    Some(self.replace_head_with(pat_ids))
+                    Some(self.replace_head_with(pat_ids.iter().copied()))
+                }
+            }
+            (Pat::Record { args: ref arg_pats, .. }, Constructor::Enum(e)) => {
+                let pat_id = self.head().as_id(cx).expect("we know this isn't a wild");
+                if !enum_variant_matches(cx, pat_id, *e) {
+                    None
+                } else {
+                    let variant_data = cx.db.enum_variant_data(*e);
+                    let fields = variant_data.fields();
+                    if arg_pats.iter().any(|arg| fields.iter().all(|(_, f)| f.name != arg.name)) {
+                        return Err(MatchCheckErr::MalformedMatchArm);
+                    }
+
+                    // Fields which aren't mentioned in the pattern (because of
+                    // `..`) match any value.
+                    let pats = fields.iter().map(|(_, field)| {
+                        arg_pats
+                            .iter()
+                            .find(|arg| arg.name == field.name)
+                            .map_or(PatIdOrWild::Wild, |arg| arg.pat.into())
+                    });
+                    Some(self.replace_head_with(pats))
+                }
+            }
+            (
+                Pat::Slice { ref prefix, ref slice, ref suffix },
+                Constructor::Slice(SliceKind::FixedLen(len)),
+            ) => {
+                if !SliceKind::of_pat(prefix, slice, suffix).covers_length(*len) {
+                    None
+                } else {
+                    // The elements matched by `..` are wildcards.
+                    let wild_count = len - prefix.len() - suffix.len();
+                    let pats = prefix
+                        .iter()
+                        .map(|&pat_id| pat_id.into())
+                        .chain(std::iter::repeat(PatIdOrWild::Wild).take(wild_count))
+                        .chain(suffix.iter().map(|&pat_id| pat_id.into()));
+                    Some(self.replace_head_with(pats))
                 }
             }
             (Pat::Or(_), _) => return Err(MatchCheckErr::NotImplemented),
@@ -530,7 +588,40 @@ pub(crate) fn is_useful(
         };
     }
 
-    if let Some(constructor) = pat_constructor(cx, v.head())? {
+    if let Some(Constructor::Slice(kind)) = pat_constructor(cx, v.head())? {
+        // A slice pattern covers slices of different lengths, so we split
+        // it into one constructor per length that is relevant for the matrix.
+        let mut used_constructors = vec![Constructor::Slice(kind)];
+        for pat in matrix.heads() {
+            if let Some(constructor) = pat_constructor(cx, pat)? {
+                used_constructors.push(constructor);
+            }
+        }
+
+        let mut found_unimplemented = false;
+        for len in 0..=max_slice_length(&used_constructors) {
+            if !kind.covers_length(len) {
+                continue;
+            }
+            let constructor = Constructor::Slice(SliceKind::FixedLen(len));
+            let matrix = matrix.specialize_constructor(&cx, &constructor)?;
+            let v = v
+                .specialize_constructor(&cx, &constructor)?
+                .expect("we know this can't fail because the slice pattern covers this length");
+
+            match is_useful(&cx, &matrix, &v) {
+                Ok(Usefulness::Useful) => return Ok(Usefulness::Useful),
+                Ok(Usefulness::NotUseful) => continue,
+                _ => found_unimplemented = true,
+            };
+        }
+
+        if found_unimplemented {
+            Err(MatchCheckErr::NotImplemented)
+        } else {
+            Ok(Usefulness::NotUseful)
+        }
+    } else if let Some(constructor) = pat_constructor(cx, v.head())? {
         let matrix = matrix.specialize_constructor(&cx, &constructor)?;
         let v = v
             .specialize_constructor(&cx, &constructor)?
@@ -565,7 +656,7 @@ pub(crate) fn is_useful(
                 // Here we create a constructor for each variant and then check
                 // usefulness after specializing for that constructor.
                 let mut found_unimplemented = false;
-                for constructor in constructor.all_constructors(cx) {
+                for constructor in constructor.all_constructors(cx, &used_constructors) {
                     let matrix = matrix.specialize_constructor(&cx, &constructor)?;
                     let v = v.expand_wildcard(&cx, &constructor)?;
 
@@ -589,7 +680,15 @@ pub(crate) fn is_useful(
                 let matrix = matrix.specialize_wildcard(&cx);
                 let v = v.to_tail();
 
-                is_useful(&cx, &matrix, &v)
+                let usefulness = is_useful(&cx, &matrix, &v)?;
+                // Opaque constructors might cover the values the wildcard arms
+                // don't cover.
+                if usefulness == Usefulness::Useful
+                    && used_constructors.iter().any(|it| matches!(it, Constructor::Opaque))
+                {
+                    return Err(MatchCheckErr::NotImplemented);
+                }
+                Ok(usefulness)
             }
         }
     }
@@ -603,6 +702,68 @@ enum Constructor {
     Bool(bool),
     Tuple { arity: usize },
     Enum(EnumVariantId),
+    Slice(SliceKind),
+    // Patterns whose values we don't know, like ranges, constants and
+    // literals other than `bool`s. They don't cover any constructor.
+    Opaque,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SliceKind {
+    /// A pattern like `[a, b]`, which only matches slices of exactly this length.
+    FixedLen(usize),
+    /// A pattern like `[a, .., b]`, which matches slices with at least
+    /// `prefix + suffix` elements.
+    VarLen(usize, usize),
+}
+
+impl SliceKind {
+    fn of_pat(prefix: &[PatId], slice: &Option<PatId>, suffix: &[PatId]) -> SliceKind {
+        match slice {
+            Some(_) => SliceKind::VarLen(prefix.len(), suffix.len()),
+            None => SliceKind::FixedLen(prefix.len() + suffix.len()),
+        }
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            SliceKind::FixedLen(len) => len,
+            SliceKind::VarLen(prefix, suffix) => prefix + suffix,
+        }
+    }
+
+    fn covers_length(self, len: usize) -> bool {
+        match self {
+            SliceKind::FixedLen(fixed_len) => fixed_len == len,
+            SliceKind::VarLen(prefix, suffix) => prefix + suffix <= len,
+        }
+    }
+}
+
+/// Returns the length `L` such that slices of any length above `L` are matched
+/// by exactly the same patterns as slices of length `L`, so that only the
+/// lengths `0..=L` need to be considered.
+///
+/// Slices longer than every fixed length pattern and than every prefix and
+/// suffix combined only differ in the elements matched by `..`.
+fn max_slice_length(constructors: &[Constructor]) -> usize {
+    let mut max_fixed_len = None;
+    let mut max_prefix = 0;
+    let mut max_suffix = 0;
+    for constructor in constructors {
+        match constructor {
+            Constructor::Slice(SliceKind::FixedLen(len)) => {
+                max_fixed_len = Some(max_fixed_len.map_or(*len, |it: usize| it.max(*len)));
+            }
+            Constructor::Slice(SliceKind::VarLen(prefix, suffix)) => {
+                max_prefix = max_prefix.max(*prefix);
+                max_suffix = max_suffix.max(*suffix);
+            }
+            _ => {}
+        }
+    }
+    let above_fixed_len = max_fixed_len.map_or(0, |it| it + 1);
+    above_fixed_len.max(max_prefix + max_suffix)
 }
 
 impl Constructor {
@@ -610,19 +771,24 @@ impl Constructor {
         let arity = match self {
             Constructor::Bool(_) => 0,
             Constructor::Tuple { arity } => *arity,
-            Constructor::Enum(e) => {
-                match cx.db.enum_data(e.parent).variants[e.local_id].variant_data.as_ref() {
-                    VariantData::Tuple(struct_field_data) => struct_field_data.len(),
-                    VariantData::Unit => 0,
-                    _ => return Err(MatchCheckErr::NotImplemented),
+            Constructor::Enum(e) => match cx.db.enum_variant_data(*e).as_ref() {
+                VariantData::Tuple(struct_field_data) | VariantData::Record(struct_field_data) => {
+                    struct_field_data.len()
                 }
-            }
+                VariantData::Unit => 0,
+            },
+            Constructor::Slice(kind) => kind.arity(),
+            Constructor::Opaque => 0,
         };
 
         Ok(arity)
     }
 
-    fn all_constructors(&self, cx: &MatchCheckCtx) -> Vec<Constructor> {
+    fn all_constructors(
+        &self,
+        cx: &MatchCheckCtx,
+        used_constructors: &[Constructor],
+    ) -> Vec<Constructor> {
         match self {
             Constructor::Bool(_) => vec![Constructor::Bool(true), Constructor::Bool(false)],
             Constructor::Tuple { .. } => vec![*self],
@@ -635,6 +801,10 @@ impl Constructor {
                     Constructor::Enum(EnumVariantId { parent: e.parent, local_id })
                 })
                 .collect(),
+            Constructor::Slice(_) => (0..=max_slice_length(used_constructors))
+                .map(|len| Constructor::Slice(SliceKind::FixedLen(len)))
+                .collect(),
+            Constructor::Opaque => vec![],
         }
     }
 }
@@ -647,10 +817,11 @@ fn pat_constructor(cx: &MatchCheckCtx, pat: PatIdOrWild) -> MatchCheckResult<Opt
         Pat::Tuple(pats) => Some(Constructor::Tuple { arity: pats.len() }),
         Pat::Lit(lit_expr) => match cx.body.exprs[lit_expr] {
             Expr::Literal(Literal::Bool(val)) => Some(Constructor::Bool(val)),
-            _ => return Err(MatchCheckErr::NotImplemented),
+            _ => Some(Constructor::Opaque),
         },
-        Pat::TupleStruct { .. } | Pat::Path(_) => {
-            let pat_id = pat.as_id().expect("we already know this pattern is not a wild");
+        Pat::Range { .. } | Pat::ConstBlock(_) => Some(Constructor::Opaque),
+        Pat::TupleStruct { .. } | Pat::Path(_) | Pat::Record { .. } => {
+            let pat_id = pat.as_id(cx).expect("we already know this pattern is not a wild");
             let variant_id =
                 cx.infer.variant_resolution_for_pat(pat_id).ok_or(MatchCheckErr::NotImplemented)?;
             match variant_id {
@@ -660,6 +831,19 @@ fn pat_constructor(cx: &MatchCheckCtx, pat: PatIdOrWild) -> MatchCheckResult<Opt
                 _ => return Err(MatchCheckErr::NotImplemented),
             }
         }
+        Pat::Slice { prefix, slice, suffix } => {
+            let pat_id = pat.as_id(cx).expect("we already know this pattern is not a wild");
+            // The length of array types is known, but the constructors of a
+            // slice pattern are all the lengths up to the longest pattern, as
+            // they don't depend on the scrutinee type. An array pattern would
+            // then leave the lengths other than that of the array uncovered,
+            // so only slices are checked.
+            match cx.infer.type_of_pat.get(pat_id) {
+                Some(Ty::Apply(ApplicationTy { ctor: TypeCtor::Slice, .. })) => {}
+                _ => return Err(MatchCheckErr::NotImplemented),
+            }
+            Some(Constructor::Slice(SliceKind::of_pat(&prefix, &slice, &suffix)))
+        }
         _ => return Err(MatchCheckErr::NotImplemented),
     };
 
@@ -701,6 +885,13 @@ fn all_constructors_covered(
 
             false
         }),
+        Constructor::Slice(_) => (0..=max_slice_length(used_constructors)).all(|len| {
+            used_constructors.iter().any(|constructor| match constructor {
+                Constructor::Slice(kind) => kind.covers_length(len),
+                _ => false,
+            })
+        }),
+        Constructor::Opaque => false,
     }
 }
 
@@ -1296,6 +1487,156 @@ mod tests {
         check_no_diagnostic(content);
     }
 
+    #[test]
+    fn binding_no_diagnostic() {
+        let content = r"
+            enum Either {
+                A(bool),
+                B,
+            }
+            fn test_fn() {
+                match Either::B {
+                    Either::A(x) => (),
+                    b @ Either::B => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+    }
+
+    #[test]
+    fn enum_record_missing_arms() {
+        let content = r"
+            enum Either {
+                A { foo: bool },
+                B,
+            }
+            fn test_fn() {
+                match Either::B {
+                    Either::A { foo: true } => (),
+                    Either::B => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn enum_record_no_diagnostic() {
+        let content = r"
+            enum Either {
+                A { foo: bool, bar: bool },
+                B,
+            }
+            fn test_fn() {
+                match Either::B {
+                    Either::A { bar: true, foo } => (),
+                    Either::A { bar: false, .. } => (),
+                    Either::B => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+    }
+
+    #[test]
+    fn slice_missing_arms() {
+        let content = r"
+            fn test_fn(s: &[bool]) {
+                match s {
+                    [] => (),
+                    [_] => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn slice_no_diagnostic() {
+        let content = r"
+            fn test_fn(s: &[bool]) {
+                match s {
+                    [] => (),
+                    [_, ..] => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+    }
+
+    #[test]
+    fn slice_of_bools_missing_arms() {
+        let content = r"
+            fn test_fn(s: &[bool]) {
+                match s {
+                    [.., true] => (),
+                    [.., false] => (),
+                }
+            }
+        ";
+
+        // The empty slice is not covered.
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn slice_of_bools_no_diagnostic() {
+        let content = r"
+            fn test_fn(s: &[bool]) {
+                match s {
+                    [] => (),
+                    [true, ..] => (),
+                    [_, rest @ .., false] => (),
+                    [false, .., true] => (),
+                    [false] => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+    }
+
+    #[test]
+    fn ranges_and_consts_with_wild_no_diagnostic() {
+        let content = r"
+            fn test_fn() {
+                match 5 {
+                    0..10 => (),
+                    10..=20 | const { 42 } => (),
+                    _ => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+    }
+
+    #[test]
+    fn enum_containing_range_missing_arms() {
+        let content = r"
+            enum Either {
+                A(u32),
+                B,
+                C,
+            }
+            fn test_fn() {
+                match Either::B {
+                    Either::A(0..10) => (),
+                    Either::A(_) => (),
+                    Either::B => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
     #[test]
     fn mismatched_types() {
         let content = r"
@@ -1315,8 +1656,15 @@ mod tests {
             }
         ";
 
-        // Match arms with the incorrect type are filtered out.
-        check_diagnostic(content);
+        // Match arms with the incorrect type are reported and filtered out.
+        assert_snapshot!(
+            check_diagnostic_message(content),
+            @r###"
+        "Either2::C": mismatched types: expected `Either`, found `Either2`
+        "Either2::D": mismatched types: expected `Either`, found `Either2`
+        "Either::A": Missing match arm
+        "###
+        );
     }
 
     #[test]
@@ -1330,8 +1678,47 @@ mod tests {
             }
         ";
 
-        // Match arms with the incorrect type are filtered out.
-        check_diagnostic(content);
+        // Match arms with the incorrect type are reported and filtered out.
+        assert_snapshot!(
+            check_diagnostic_message(content),
+            @r###"
+        "(true, false, true)": mismatched types: expected `(bool, bool)`, found `(bool, bool, bool)`
+        "true": mismatched types: expected `(bool, bool)`, found `bool`
+        "(true, false)": Missing match arm
+        "###
+        );
+    }
+
+    #[test]
+    fn mismatched_variant_of_other_enum() {
+        let content = r"
+            enum Option<T> {
+                Some(T),
+                None,
+            }
+            enum Result<T, E> {
+                Ok(T),
+                Err(E),
+            }
+            fn test_fn(x: Option<u32>, y: &Option<u32>) {
+                match x {
+                    Option::Some(_) => (),
+                    Result::Ok(_) => (),
+                    Option::None => (),
+                }
+                match y {
+                    Option::Some(_) => (),
+                    Option::None => (),
+                }
+            }
+        ";
+
+        assert_snapshot!(
+            check_diagnostic_message(content),
+            @r###"
+        "Result::Ok(_)": mismatched types: expected `Option<u32>`, found `Result<_, _>`
+        "###
+        );
     }
 
     #[test]
@@ -1344,8 +1731,14 @@ mod tests {
             }
         ";
 
-        // Match arms with the incorrect type are filtered out.
-        check_diagnostic(content);
+        // Match arms with the incorrect type are reported and filtered out.
+        assert_snapshot!(
+            check_diagnostic_message(content),
+            @r###"
+        "()": mismatched types: expected `i32`, found `()`
+        "(0)": Missing match arm
+        "###
+        );
     }
 
     #[test]
@@ -1416,21 +1809,17 @@ mod false_negatives {
     }
 
     #[test]
-    fn enum_record() {
+    fn array() {
         let content = r"
-            enum Either {
-                A { foo: u32 },
-                B,
-            }
             fn test_fn() {
-                match Either::B {
-                    Either::A { foo: 5 } => (),
+                match [false, true] {
+                    [true, _] => (),
                 }
             }
         ";
 
         // This is a false negative.
-        // We don't currently handle enum record types.
+        // We don't know the length of array types, so we only check slices.
         check_no_diagnostic(content);
     }
 
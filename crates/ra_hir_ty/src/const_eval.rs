@@ -0,0 +1,126 @@
+//! Evaluation of constant expressions, like array lengths and enum
+//! discriminants.
+//!
+//! Only integer literals, arithmetic on them and paths to other constants are
+//! understood, everything else evaluates to `None`.
+
+use std::{convert::TryFrom, sync::Arc};
+
+use hir_def::{
+    body::Body,
+    expr::{ArithOp, BinaryOp, Expr, Literal, UnaryOp},
+    path::ModPath,
+    resolver::{resolver_for_expr, HasResolver, Resolver, ValueNs},
+    type_ref::ConstRef,
+    ConstId, DefWithBodyId, EnumId, ExprId, LocalEnumVariantId,
+};
+use ra_arena::map::ArenaMap;
+
+use crate::db::HirDatabase;
+
+/// Evaluates a constant in a type, like the length of an array.
+pub(crate) fn eval_const_ref(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    const_ref: &ConstRef,
+) -> Option<i128> {
+    match const_ref {
+        ConstRef::Int(value) => i128::try_from(*value).ok(),
+        ConstRef::Path(path) => eval_path(db, resolver, path.mod_path()),
+        ConstRef::Neg(inner) => eval_const_ref(db, resolver, inner)?.checked_neg(),
+        ConstRef::BinaryOp { op, lhs, rhs } => {
+            let lhs = eval_const_ref(db, resolver, lhs)?;
+            let rhs = eval_const_ref(db, resolver, rhs)?;
+            eval_arith_op(*op, lhs, rhs)
+        }
+        ConstRef::Unknown => None,
+    }
+}
+
+/// Evaluates an expression of a body, like the count of `[0; N]`.
+pub(crate) fn eval_expr(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    body: &Body,
+    expr: ExprId,
+) -> Option<i128> {
+    match &body[expr] {
+        Expr::Literal(Literal::Int(value, _)) => i128::try_from(*value).ok(),
+        Expr::Path(path) => {
+            let resolver = resolver_for_expr(db.upcast(), owner, expr);
+            eval_path(db, &resolver, path.mod_path())
+        }
+        Expr::UnaryOp { expr, op: UnaryOp::Neg } => {
+            eval_expr(db, owner, body, *expr)?.checked_neg()
+        }
+        Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::ArithOp(op)) } => {
+            let lhs = eval_expr(db, owner, body, *lhs)?;
+            let rhs = eval_expr(db, owner, body, *rhs)?;
+            eval_arith_op(*op, lhs, rhs)
+        }
+        Expr::Block { statements, tail: Some(tail) } if statements.is_empty() => {
+            eval_expr(db, owner, body, *tail)
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn const_eval_query(db: &dyn HirDatabase, def: ConstId) -> Option<i128> {
+    let body = db.body(def.into());
+    eval_expr(db, def.into(), &body, body.body_expr)
+}
+
+pub(crate) fn const_eval_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &ConstId,
+) -> Option<i128> {
+    None
+}
+
+/// The values of the variants of an enum which are known. A variant without
+/// an explicit discriminant is one more than the previous one.
+pub(crate) fn enum_discriminants_query(
+    db: &dyn HirDatabase,
+    def: EnumId,
+) -> Arc<ArenaMap<LocalEnumVariantId, i128>> {
+    let enum_data = db.enum_data(def);
+    let resolver = def.resolver(db.upcast());
+
+    let mut res = ArenaMap::default();
+    let mut next = Some(0);
+    for (local_id, variant) in enum_data.variants.iter() {
+        let value = match &variant.discriminant {
+            Some(discriminant) => eval_const_ref(db, &resolver, discriminant),
+            None => next,
+        };
+        if let Some(value) = value {
+            res.insert(local_id, value);
+        }
+        next = value.and_then(|it| it.checked_add(1));
+    }
+    Arc::new(res)
+}
+
+fn eval_path(db: &dyn HirDatabase, resolver: &Resolver, path: &ModPath) -> Option<i128> {
+    match resolver.resolve_path_in_value_ns_fully(db.upcast(), path)? {
+        ValueNs::ConstId(it) => db.const_eval(it),
+        _ => None,
+    }
+}
+
+fn eval_arith_op(op: ArithOp, lhs: i128, rhs: i128) -> Option<i128> {
+    let shift_amount = |rhs: i128| u32::try_from(rhs).ok();
+    match op {
+        ArithOp::Add => lhs.checked_add(rhs),
+        ArithOp::Sub => lhs.checked_sub(rhs),
+        ArithOp::Mul => lhs.checked_mul(rhs),
+        ArithOp::Div => lhs.checked_div(rhs),
+        ArithOp::Rem => lhs.checked_rem(rhs),
+        ArithOp::Shl => lhs.checked_shl(shift_amount(rhs)?),
+        ArithOp::Shr => lhs.checked_shr(shift_amount(rhs)?),
+        ArithOp::BitXor => Some(lhs ^ rhs),
+        ArithOp::BitOr => Some(lhs | rhs),
+        ArithOp::BitAnd => Some(lhs & rhs),
+    }
+}
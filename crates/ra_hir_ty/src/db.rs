@@ -3,8 +3,8 @@
 use std::sync::Arc;
 
 use hir_def::{
-    db::DefDatabase, DefWithBodyId, GenericDefId, ImplId, LocalStructFieldId, TraitId, TypeParamId,
-    VariantId,
+    db::DefDatabase, ConstId, DefWithBodyId, EnumId, GenericDefId, ImplId, LocalEnumVariantId,
+    LocalStructFieldId, TraitId, TypeParamId, VariantId,
 };
 use ra_arena::map::ArenaMap;
 use ra_db::{impl_intern_key, salsa, CrateId, Upcast};
@@ -45,6 +45,13 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::lower::field_types_query)]
     fn field_types(&self, var: VariantId) -> Arc<ArenaMap<LocalStructFieldId, Binders<Ty>>>;
 
+    #[salsa::invoke(crate::const_eval::const_eval_query)]
+    #[salsa::cycle(crate::const_eval::const_eval_recover)]
+    fn const_eval(&self, def: ConstId) -> Option<i128>;
+
+    #[salsa::invoke(crate::const_eval::enum_discriminants_query)]
+    fn enum_discriminants(&self, def: EnumId) -> Arc<ArenaMap<LocalEnumVariantId, i128>>;
+
     #[salsa::invoke(crate::callable_item_sig)]
     fn callable_item_signature(&self, def: CallableDef) -> PolyFnSig;
 
@@ -64,6 +71,9 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::method_resolution::CrateImplDefs::impls_in_crate_query)]
     fn impls_in_crate(&self, krate: CrateId) -> Arc<CrateImplDefs>;
 
+    #[salsa::invoke(crate::traits::trait_impls_in_crate_query)]
+    fn trait_impls_in_crate(&self, krate: CrateId, trait_: TraitId) -> Arc<[ImplId]>;
+
     #[salsa::invoke(crate::traits::impls_for_trait_query)]
     fn impls_for_trait(&self, krate: CrateId, trait_: TraitId) -> Arc<[ImplId]>;
 
@@ -102,6 +112,13 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
         krate: CrateId,
         goal: crate::Canonical<crate::InEnvironment<crate::Obligation>>,
     ) -> Option<crate::traits::Solution>;
+
+    #[salsa::invoke(crate::infer::could_coerce_query)]
+    fn could_coerce(
+        &self,
+        krate: CrateId,
+        tys: crate::Canonical<crate::InEnvironment<(Ty, Ty)>>,
+    ) -> bool;
 }
 
 fn infer_wait(db: &impl HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
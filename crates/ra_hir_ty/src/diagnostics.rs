@@ -2,6 +2,7 @@
 
 use std::any::Any;
 
+use hir_def::{AdtId, FunctionId, StructFieldId, TraitId};
 use hir_expand::{db::AstDatabase, name::Name, HirFileId, InFile};
 use ra_syntax::{ast, AstNode, AstPtr, SyntaxNodePtr};
 use stdx::format_to;
@@ -29,6 +30,69 @@ impl Diagnostic for NoSuchField {
     }
 }
 
+#[derive(Debug)]
+pub struct UnresolvedPath {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub name: Name,
+}
+
+impl Diagnostic for UnresolvedPath {
+    fn message(&self) -> String {
+        format!("cannot find value `{}` in this scope", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for UnresolvedPath {
+    type AST = ast::PathExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::PathExpr::cast(node).unwrap()
+    }
+}
+
+/// A method call which could not be resolved.
+#[derive(Debug)]
+pub struct UnresolvedMethodCall {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub name: Name,
+}
+
+impl Diagnostic for UnresolvedMethodCall {
+    fn message(&self) -> String {
+        format!("no method named `{}` found", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for UnresolvedMethodCall {
+    type AST = ast::MethodCallExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::MethodCallExpr::cast(node).unwrap()
+    }
+}
+
 #[derive(Debug)]
 pub struct MissingFields {
     pub file: HirFileId,
@@ -81,6 +145,29 @@ impl Diagnostic for MissingMatchArms {
     }
 }
 
+/// A match arm whose pattern can't match the type of the scrutinee. The arm
+/// isn't taken into account when checking the match for missing arms.
+#[derive(Debug)]
+pub struct PatternTypeMismatch {
+    pub file: HirFileId,
+    pub pat: AstPtr<ast::Pat>,
+    /// The types as displayed in the message.
+    pub expected: String,
+    pub found: String,
+}
+
+impl Diagnostic for PatternTypeMismatch {
+    fn message(&self) -> String {
+        format!("mismatched types: expected `{}`, found `{}`", self.expected, self.found)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.pat.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct MissingOkInTailExpr {
     pub file: HirFileId,
@@ -108,3 +195,220 @@ impl AstDiagnostic for MissingOkInTailExpr {
         ast::Expr::cast(node).unwrap()
     }
 }
+
+#[derive(Debug)]
+pub struct MismatchedArgCount {
+    pub file: HirFileId,
+    pub call_expr: AstPtr<ast::Expr>,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Diagnostic for MismatchedArgCount {
+    fn message(&self) -> String {
+        let s = if self.expected == 1 { "" } else { "s" };
+        format!("Expected {} argument{}, found {}", self.expected, s, self.found)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.call_expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnusedMustUse {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    /// The name of the function or of the type marked `#[must_use]`.
+    pub name: Name,
+    pub is_fn: bool,
+    pub reason: Option<String>,
+}
+
+impl Diagnostic for UnusedMustUse {
+    fn message(&self) -> String {
+        let mut buf = if self.is_fn {
+            format!("unused return value of `{}` that must be used", self.name)
+        } else {
+            format!("unused `{}` that must be used", self.name)
+        };
+        if let Some(reason) = &self.reason {
+            format_to!(buf, ": {}", reason);
+        }
+        buf
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UseOfMovedValue {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub name: Name,
+}
+
+impl Diagnostic for UseOfMovedValue {
+    fn message(&self) -> String {
+        format!("use of moved value: `{}`", self.name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnsatisfiedTraitBound {
+    pub file: HirFileId,
+    pub call_expr: AstPtr<ast::Expr>,
+    /// The type not implementing the trait, as displayed in the message.
+    pub ty: String,
+    pub adt: AdtId,
+    pub trait_: TraitId,
+    pub trait_name: Name,
+}
+
+impl Diagnostic for UnsatisfiedTraitBound {
+    fn message(&self) -> String {
+        format!("the trait bound `{}: {}` is not satisfied", self.ty, self.trait_name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.call_expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A `for` loop over a value whose type doesn't implement `IntoIterator`.
+#[derive(Debug)]
+pub struct NotAnIterator {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    /// The type of the iterated value, as displayed in the message.
+    pub ty: String,
+}
+
+impl Diagnostic for NotAnIterator {
+    fn message(&self) -> String {
+        format!("`{}` is not an iterator", self.ty)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// Indexing a fixed-size array with a constant which is out of its bounds.
+#[derive(Debug)]
+pub struct IndexOutOfBounds {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub index: u128,
+    pub len: u128,
+}
+
+impl Diagnostic for IndexOutOfBounds {
+    fn message(&self) -> String {
+        format!("index out of bounds: the length is {} but the index is {}", self.len, self.index)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct TryInNonTryFunction {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    /// The return type of the function, as displayed in the message.
+    pub ret_ty: String,
+}
+
+impl Diagnostic for TryInNonTryFunction {
+    fn message(&self) -> String {
+        format!(
+            "the `?` operator can only be used in a function that returns `Result` or `Option`, not `{}`",
+            self.ret_ty
+        )
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnusedVariable {
+    pub file: HirFileId,
+    pub pat: AstPtr<ast::BindPat>,
+    pub name: Name,
+}
+
+impl Diagnostic for UnusedVariable {
+    fn message(&self) -> String {
+        format!("unused variable: `{}`", self.name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.pat.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for UnusedVariable {
+    type AST = ast::BindPat;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::BindPat::cast(node).unwrap()
+    }
+}
+
+/// An item whose visibility is checked where it is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivateItem {
+    Function(FunctionId),
+    Field(StructFieldId),
+}
+
+#[derive(Debug)]
+pub struct PrivateItemAccess {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub name: Name,
+    pub item: PrivateItem,
+}
+
+impl Diagnostic for PrivateItemAccess {
+    fn message(&self) -> String {
+        match self.item {
+            PrivateItem::Function(_) => format!("function `{}` is private", self.name),
+            PrivateItem::Field(_) => format!("field `{}` is private", self.name),
+        }
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
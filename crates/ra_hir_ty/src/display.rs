@@ -6,7 +6,10 @@ use crate::{
     db::HirDatabase, utils::generics, ApplicationTy, CallableDef, FnSig, GenericPredicate,
     Obligation, ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
 };
-use hir_def::{generics::TypeParamProvenance, AdtId, AssocContainerId, Lookup};
+use hir_def::{
+    find_path, generics::TypeParamProvenance, item_scope::ItemInNs, AdtId, AssocContainerId,
+    Lookup, ModuleDefId, ModuleId,
+};
 use hir_expand::name::Name;
 
 pub struct HirFormatter<'a, 'b> {
@@ -16,6 +19,7 @@ pub struct HirFormatter<'a, 'b> {
     curr_size: usize,
     pub(crate) max_size: Option<usize>,
     omit_verbose_types: bool,
+    qualify_from: Option<ModuleId>,
 }
 
 pub trait HirDisplay {
@@ -25,7 +29,7 @@ pub trait HirDisplay {
     where
         Self: Sized,
     {
-        HirDisplayWrapper(db, self, None, false)
+        HirDisplayWrapper(db, self, None, false, None)
     }
 
     fn display_truncated<'a>(
@@ -36,7 +40,20 @@ pub trait HirDisplay {
     where
         Self: Sized,
     {
-        HirDisplayWrapper(db, self, max_size, true)
+        HirDisplayWrapper(db, self, max_size, true, None)
+    }
+
+    /// Like `display`, but qualifies the names of ADTs with the shortest path
+    /// by which they are visible from `module`, like `std::fmt::Error`.
+    fn display_qualified<'a>(
+        &'a self,
+        db: &'a dyn HirDatabase,
+        module: ModuleId,
+    ) -> HirDisplayWrapper<'a, Self>
+    where
+        Self: Sized,
+    {
+        HirDisplayWrapper(db, self, None, false, Some(module))
     }
 }
 
@@ -79,9 +96,23 @@ impl<'a, 'b> HirFormatter<'a, 'b> {
     pub fn omit_verbose_types(&self) -> bool {
         self.omit_verbose_types
     }
+
+    /// Displays a type nested in the current one, qualifying paths the same way.
+    pub fn nested<'c, T: HirDisplay>(&self, t: &'c T) -> HirDisplayWrapper<'c, T>
+    where
+        'a: 'c,
+    {
+        HirDisplayWrapper(self.db, t, None, false, self.qualify_from)
+    }
 }
 
-pub struct HirDisplayWrapper<'a, T>(&'a dyn HirDatabase, &'a T, Option<usize>, bool);
+pub struct HirDisplayWrapper<'a, T>(
+    &'a dyn HirDatabase,
+    &'a T,
+    Option<usize>,
+    bool,
+    Option<ModuleId>,
+);
 
 impl<'a, T> fmt::Display for HirDisplayWrapper<'a, T>
 where
@@ -95,6 +126,7 @@ where
             curr_size: 0,
             max_size: self.2,
             omit_verbose_types: self.3,
+            qualify_from: self.4,
         })
     }
 }
@@ -121,30 +153,34 @@ impl HirDisplay for ApplicationTy {
             TypeCtor::Str => write!(f, "str")?,
             TypeCtor::Slice => {
                 let t = self.parameters.as_single();
-                write!(f, "[{}]", t.display(f.db))?;
+                write!(f, "[{}]", f.nested(t))?;
             }
             TypeCtor::Array => {
-                let t = self.parameters.as_single();
-                write!(f, "[{}; _]", t.display(f.db))?;
+                let (t, len) = (&self.parameters[0], &self.parameters[1]);
+                match len {
+                    Ty::Unknown | Ty::Infer(_) => write!(f, "[{}; _]", f.nested(t))?,
+                    _ => write!(f, "[{}; {}]", f.nested(t), f.nested(len))?,
+                }
             }
             TypeCtor::RawPtr(m) => {
                 let t = self.parameters.as_single();
-                write!(f, "*{}{}", m.as_keyword_for_ptr(), t.display(f.db))?;
+                write!(f, "*{}{}", m.as_keyword_for_ptr(), f.nested(t))?;
             }
             TypeCtor::Ref(m) => {
                 let t = self.parameters.as_single();
                 let ty_display = if f.omit_verbose_types() {
                     t.display_truncated(f.db, f.max_size)
                 } else {
-                    t.display(f.db)
+                    f.nested(t)
                 };
                 write!(f, "&{}{}", m.as_keyword_for_ref(), ty_display)?;
             }
             TypeCtor::Never => write!(f, "!")?,
+            TypeCtor::Const(value) => write!(f, "{}", value)?,
             TypeCtor::Tuple { .. } => {
                 let ts = &self.parameters;
                 if ts.len() == 1 {
-                    write!(f, "({},)", ts[0].display(f.db))?;
+                    write!(f, "({},)", f.nested(&ts[0]))?;
                 } else {
                     write!(f, "(")?;
                     f.write_joined(&*ts.0, ", ")?;
@@ -158,7 +194,7 @@ impl HirDisplay for ApplicationTy {
                 write!(f, ")")?;
                 let ret = sig.ret();
                 if *ret != Ty::unit() {
-                    write!(f, " -> {}", ret.display(f.db))?;
+                    write!(f, " -> {}", f.nested(ret))?;
                 }
             }
             TypeCtor::FnDef(def) => {
@@ -187,16 +223,22 @@ impl HirDisplay for ApplicationTy {
                 write!(f, ")")?;
                 let ret = sig.ret();
                 if *ret != Ty::unit() {
-                    write!(f, " -> {}", ret.display(f.db))?;
+                    write!(f, " -> {}", f.nested(ret))?;
                 }
             }
             TypeCtor::Adt(def_id) => {
-                let name = match def_id {
-                    AdtId::StructId(it) => f.db.struct_data(it).name.clone(),
-                    AdtId::UnionId(it) => f.db.union_data(it).name.clone(),
-                    AdtId::EnumId(it) => f.db.enum_data(it).name.clone(),
-                };
-                write!(f, "{}", name)?;
+                let item = ItemInNs::Types(ModuleDefId::AdtId(def_id));
+                match f.qualify_from.and_then(|it| find_path::find_path(f.db.upcast(), item, it)) {
+                    Some(path) => write!(f, "{}", path)?,
+                    None => {
+                        let name = match def_id {
+                            AdtId::StructId(it) => f.db.struct_data(it).name.clone(),
+                            AdtId::UnionId(it) => f.db.union_data(it).name.clone(),
+                            AdtId::EnumId(it) => f.db.enum_data(it).name.clone(),
+                        };
+                        write!(f, "{}", name)?;
+                    }
+                }
                 if self.parameters.len() > 0 {
                     let mut non_default_parameters = Vec::with_capacity(self.parameters.len());
                     let parameters_to_write = if f.omit_verbose_types() {
@@ -259,7 +301,7 @@ impl HirDisplay for ApplicationTy {
                     f.write_joined(sig.params(), ", ")?;
                     write!(f, "|")?;
                 };
-                write!(f, " -> {}", sig.ret().display(f.db))?;
+                write!(f, " -> {}", f.nested(sig.ret()))?;
             }
         }
         Ok(())
@@ -273,7 +315,7 @@ impl HirDisplay for ProjectionTy {
         }
 
         let trait_ = f.db.trait_data(self.trait_(f.db));
-        write!(f, "<{} as {}", self.parameters[0].display(f.db), trait_.name)?;
+        write!(f, "<{} as {}", f.nested(&self.parameters[0]), trait_.name)?;
         if self.parameters.len() > 1 {
             write!(f, "<")?;
             f.write_joined(&self.parameters[1..], ", ")?;
@@ -297,7 +339,9 @@ impl HirDisplay for Ty {
                 let generics = generics(f.db.upcast(), id.parent);
                 let param_data = &generics.params.types[id.local_id];
                 match param_data.provenance {
-                    TypeParamProvenance::TypeParamList | TypeParamProvenance::TraitSelf => {
+                    TypeParamProvenance::TypeParamList
+                    | TypeParamProvenance::TraitSelf
+                    | TypeParamProvenance::ConstParam => {
                         write!(f, "{}", param_data.name.clone().unwrap_or_else(Name::missing))?
                     }
                     TypeParamProvenance::ArgumentImplTrait => {
@@ -337,13 +381,47 @@ fn write_bounds_like_dyn_trait(
     // aren't as expected (i.e. self types = $0, projection
     // predicates for a certain trait come after the Implemented
     // predicate for that trait).
+    if predicates.is_empty() {
+        // Only lifetime bounds, which are erased.
+        return write!(f, "'_");
+    }
     let mut first = true;
     let mut angle_open = false;
+    // the associated type whose bounds we're currently writing, as in
+    // `impl Iterator<Item: Foo + Bar>`
+    let mut last_assoc_bound = None;
     for p in predicates.iter() {
         match p {
             GenericPredicate::Implemented(trait_ref) => {
+                if let Ty::Projection(projection_ty) = trait_ref.self_ty() {
+                    // an associated type bound; like projection predicates,
+                    // these come after the Implemented predicate of their trait
+                    if last_assoc_bound == Some(projection_ty.associated_ty) {
+                        write!(f, " + ")?;
+                    } else {
+                        if angle_open {
+                            write!(f, ", ")?;
+                        } else {
+                            write!(f, "<")?;
+                            angle_open = true;
+                        }
+                        let type_alias = f.db.type_alias_data(projection_ty.associated_ty);
+                        write!(f, "{}: ", type_alias.name)?;
+                    }
+                    write!(f, "{}", f.db.trait_data(trait_ref.trait_).name)?;
+                    if trait_ref.substs.len() > 1 {
+                        write!(f, "<")?;
+                        f.write_joined(&trait_ref.substs[1..], ", ")?;
+                        write!(f, ">")?;
+                    }
+                    last_assoc_bound = Some(projection_ty.associated_ty);
+                    first = false;
+                    continue;
+                }
+                last_assoc_bound = None;
                 if angle_open {
                     write!(f, ">")?;
+                    angle_open = false;
                 }
                 if !first {
                     write!(f, " + ")?;
@@ -360,6 +438,7 @@ fn write_bounds_like_dyn_trait(
                 }
             }
             GenericPredicate::Projection(projection_pred) => {
+                last_assoc_bound = None;
                 // in types in actual Rust, these will always come
                 // after the corresponding Implemented predicate
                 if angle_open {
@@ -373,6 +452,7 @@ fn write_bounds_like_dyn_trait(
                 projection_pred.ty.hir_fmt(f)?;
             }
             GenericPredicate::Error => {
+                last_assoc_bound = None;
                 if angle_open {
                     // impl Trait<X, {error}>
                     write!(f, ", ")?;
@@ -440,7 +520,7 @@ impl HirDisplay for GenericPredicate {
                     f,
                     ">::{} = {}",
                     f.db.type_alias_data(projection_pred.projection_ty.associated_ty).name,
-                    projection_pred.ty.display(f.db)
+                    f.nested(&projection_pred.ty)
                 )?;
             }
             GenericPredicate::Error => write!(f, "{{error}}")?,
@@ -452,13 +532,10 @@ impl HirDisplay for GenericPredicate {
 impl HirDisplay for Obligation {
     fn hir_fmt(&self, f: &mut HirFormatter) -> fmt::Result {
         match self {
-            Obligation::Trait(tr) => write!(f, "Implements({})", tr.display(f.db)),
-            Obligation::Projection(proj) => write!(
-                f,
-                "Normalize({} => {})",
-                proj.projection_ty.display(f.db),
-                proj.ty.display(f.db)
-            ),
+            Obligation::Trait(tr) => write!(f, "Implements({})", f.nested(tr)),
+            Obligation::Projection(proj) => {
+                write!(f, "Normalize({} => {})", f.nested(&proj.projection_ty), f.nested(&proj.ty))
+            }
         }
     }
 }
@@ -1,18 +1,31 @@
 //! FIXME: write short doc here
 
-use std::sync::Arc;
+use std::{convert::TryFrom, sync::Arc};
 
-use hir_def::{path::path, resolver::HasResolver, AdtId, FunctionId};
-use hir_expand::diagnostics::DiagnosticSink;
+use hir_def::{
+    attr::Attrs, lang_item::LangItemTarget, path::path, resolver::HasResolver, AdtId, FunctionId,
+    HasModule, Lookup, ModuleId,
+};
+use hir_expand::{diagnostics::DiagnosticSink, name::Name};
 use ra_syntax::{ast, AstPtr};
 use rustc_hash::FxHashSet;
 
 use crate::{
-    db::HirDatabase,
-    diagnostics::{MissingFields, MissingMatchArms, MissingOkInTailExpr},
-    utils::variant_data,
-    ApplicationTy, InferenceResult, Ty, TypeCtor,
     _match::{is_useful, MatchCheckCtx, Matrix, PatStack, Usefulness},
+    const_eval::eval_expr,
+    db::HirDatabase,
+    diagnostics::{
+        IndexOutOfBounds, MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkInTailExpr,
+        NotAnIterator, PatternTypeMismatch, TryInNonTryFunction, UnsatisfiedTraitBound,
+        UnusedMustUse, UnusedVariable, UseOfMovedValue,
+    },
+    display::HirDisplay,
+    lower::TyLoweringContext,
+    method_resolution::implements_trait,
+    traits::trait_goal_fails,
+    utils::{into_iterator_trait, variant_data},
+    ApplicationTy, CallableDef, Canonical, GenericPredicate, InEnvironment, InferenceResult,
+    Obligation, Substs, TraitEnvironment, TraitRef, Ty, TypeCtor, TypeWalk,
 };
 
 pub use hir_def::{
@@ -44,10 +57,11 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
 
     pub fn validate_body(&mut self, db: &dyn HirDatabase) {
         let body = db.body(self.func.into());
+        let module = self.func.lookup(db.upcast()).module(db.upcast());
 
         for (id, expr) in body.exprs.iter() {
             if let Some((variant_def, missed_fields, true)) =
-                record_literal_missing_fields(db, &self.infer, id, expr)
+                record_literal_missing_fields(db, &self.infer, id, expr, module)
             {
                 // XXX: only look at source_map if we do have missing fields
                 let (_, source_map) = db.body_with_source_map(self.func.into());
@@ -75,11 +89,450 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             if let Expr::Match { expr, arms } = expr {
                 self.validate_match(id, *expr, arms, db, self.infer.clone());
             }
+            if let Expr::Call { .. } | Expr::MethodCall { .. } = expr {
+                self.validate_call(db, id, expr);
+            }
+            if let Expr::Call { callee, .. } = expr {
+                self.validate_trait_bounds(db, id, *callee);
+            }
+            if let Expr::For { iterable, .. } = expr {
+                self.validate_for_loop(db, *iterable);
+            }
+            if let Expr::Index { base, index } = expr {
+                self.validate_index(db, &body, id, *base, *index);
+            }
+            if let Expr::Block { statements, .. } = expr {
+                for statement in statements {
+                    if let Statement::Expr(expr) = statement {
+                        self.validate_must_use(db, *expr, &body[*expr]);
+                    }
+                }
+            }
         }
         let body_expr = &body[body.body_expr];
         if let Expr::Block { tail: Some(t), .. } = body_expr {
             self.validate_results_in_tail_expr(body.body_expr, *t, db);
         }
+        self.validate_try_operators(db, &body);
+        self.validate_moves(db);
+    }
+
+    /// Reports bindings which are never read. This is a lint rather than an
+    /// error, so it isn't part of `validate_body`.
+    pub fn validate_unused_bindings(&mut self, db: &dyn HirDatabase) {
+        let body = db.body(self.func.into());
+        // A missing expression means the body has syntax errors or macro calls
+        // we couldn't expand, which might contain the uses we are looking for.
+        if body.exprs.iter().any(|(_, expr)| matches!(expr, Expr::Missing)) {
+            return;
+        }
+        // FIXME: also respect `allow` attributes on enclosing items and statements.
+        if is_lint_allowed(&db.function_data(self.func).attrs, "unused_variables") {
+            return;
+        }
+
+        // The left hand side of a plain assignment is written, not read.
+        let assignees: FxHashSet<ExprId> = body
+            .exprs
+            .iter()
+            .filter_map(|(_, expr)| match expr {
+                Expr::BinaryOp { lhs, op: Some(BinaryOp::Assignment { op: None }), .. } => {
+                    Some(*lhs)
+                }
+                _ => None,
+            })
+            .collect();
+
+        let scopes = db.expr_scopes(self.func.into());
+        let mut used = FxHashSet::default();
+        for (id, expr) in body.exprs.iter() {
+            if assignees.contains(&id) {
+                continue;
+            }
+            used.extend(resolve_local(&scopes, id, expr));
+        }
+        // The alternatives of an or-pattern bind the same names, but uses only
+        // resolve to one of the bindings.
+        for (_, pat) in body.pats.iter() {
+            if let Pat::Or(args) = pat {
+                let mut bindings = Vec::new();
+                args.iter().for_each(|&it| collect_bindings(&body, it, &mut bindings));
+                let used_names: Vec<Name> = bindings
+                    .iter()
+                    .filter(|(id, _)| used.contains(id))
+                    .map(|(_, name)| name.clone())
+                    .collect();
+                used.extend(
+                    bindings
+                        .into_iter()
+                        .filter(|(_, name)| used_names.contains(name))
+                        .map(|(id, _)| id),
+                );
+            }
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        for (id, pat) in body.pats.iter() {
+            let name = match pat {
+                Pat::Bind { name, .. } => name,
+                _ => continue,
+            };
+            let text = name.to_string();
+            if used.contains(&id) || text.starts_with('_') || text == "self" {
+                continue;
+            }
+            if let Ok(source_ptr) = source_map.pat_syntax(id) {
+                if let Some(pat) = source_ptr.value.left() {
+                    let root = source_ptr.file_syntax(db.upcast());
+                    if let ast::Pat::BindPat(bind_pat) = pat.to_node(&root) {
+                        self.sink.push(UnusedVariable {
+                            file: source_ptr.file_id,
+                            pat: AstPtr::new(&bind_pat),
+                            name: name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reports uses of locals after they were moved, if their type is known
+    /// not to be `Copy`.
+    ///
+    /// To avoid false positives, only moves which happen unconditionally in a
+    /// statement of a block are tracked, and only until the local is assigned
+    /// again. Later uses within the same block are errors then, whether they
+    /// are conditional or not.
+    fn validate_moves(&mut self, db: &dyn HirDatabase) {
+        let body = db.body(self.func.into());
+        // Uses might be hidden in macro calls we couldn't expand.
+        if body.exprs.iter().any(|(_, expr)| matches!(expr, Expr::Missing)) {
+            return;
+        }
+        let resolver = self.func.resolver(db.upcast());
+        let krate = match resolver.krate() {
+            Some(it) => it,
+            None => return,
+        };
+        let copy_trait = match db.lang_item(krate, "copy".into()) {
+            Some(LangItemTarget::TraitId(it)) => it,
+            _ => return,
+        };
+        let env = TraitEnvironment::lower(db, &resolver);
+        let infer = self.infer.clone();
+        let is_move_only = |pat: PatId| {
+            // Type parameters might be `Copy` as well, so only ADTs are checked.
+            let ty = match infer.type_of_pat.get(pat) {
+                Some(ty @ Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(_), .. })) => ty,
+                _ => return false,
+            };
+            let ty = Canonical { value: ty.clone(), num_vars: 0 };
+            is_trait_solvable(&ty.value)
+                && !implements_trait(&ty, db, env.clone(), krate, copy_trait)
+        };
+
+        let scopes = db.expr_scopes(self.func.into());
+        let mut uses_after_move = Vec::new();
+        for (_, expr) in body.exprs.iter() {
+            let (statements, tail) = match expr {
+                Expr::Block { statements, tail, .. } => (statements, tail),
+                _ => continue,
+            };
+            let mut steps = Vec::new();
+            for statement in statements {
+                let mut moves = Vec::new();
+                let root = match statement {
+                    Statement::Let { pat, initializer: Some(init), .. } => {
+                        // Patterns other than plain bindings might not move.
+                        match &body[*pat] {
+                            Pat::Bind { mode, subpat: None, .. }
+                                if *mode == BindingAnnotation::Unannotated
+                                    || *mode == BindingAnnotation::Mutable =>
+                            {
+                                collect_moved_operand(&body, &scopes, *init, &mut moves)
+                            }
+                            _ => collect_moves(&body, &scopes, *init, &mut moves),
+                        }
+                        *init
+                    }
+                    Statement::Let { initializer: None, .. } => continue,
+                    Statement::Expr(expr) => {
+                        collect_moves(&body, &scopes, *expr, &mut moves);
+                        *expr
+                    }
+                };
+                steps.push((root, moves));
+            }
+            if let Some(tail) = tail {
+                steps.push((*tail, Vec::new()));
+            }
+
+            for (idx, (root, moves)) in steps.iter().enumerate() {
+                for &(local, _) in moves.iter() {
+                    if !is_move_only(local) || assigns_local(&body, &scopes, *root, local) {
+                        continue;
+                    }
+                    for (later_root, _) in steps[idx + 1..].iter() {
+                        if assigns_local(&body, &scopes, *later_root, local) {
+                            break;
+                        }
+                        if let Some(use_id) = first_use(&body, &scopes, *later_root, local) {
+                            if !uses_after_move.contains(&(use_id, local)) {
+                                uses_after_move.push((use_id, local));
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        for (use_id, local) in uses_after_move {
+            let name = match &body[local] {
+                Pat::Bind { name, .. } => name.clone(),
+                _ => continue,
+            };
+            if let Ok(source_ptr) = source_map.expr_syntax(use_id) {
+                if let Some(expr) = source_ptr.value.left() {
+                    self.sink.push(UseOfMovedValue { file: source_ptr.file_id, expr, name });
+                }
+            }
+        }
+    }
+
+    fn validate_call(&mut self, db: &dyn HirDatabase, call_id: ExprId, expr: &Expr) -> Option<()> {
+        // Check that the number of arguments matches the number of parameters.
+        let (callee, mut found) = match expr {
+            Expr::Call { callee, args } => {
+                let (callable, _) = self.infer.type_of_expr.get(*callee)?.as_callable()?;
+                (callable, args.len())
+            }
+            // The receiver is passed as the `self` parameter.
+            Expr::MethodCall { args, .. } => {
+                (self.infer.method_resolution(call_id)?.into(), args.len() + 1)
+            }
+            _ => return None,
+        };
+
+        let (mut expected, is_varargs) = match callee {
+            CallableDef::FunctionId(it) => {
+                let data = db.function_data(it);
+                (data.params.len(), data.is_varargs)
+            }
+            CallableDef::StructId(it) => (db.struct_data(it).variant_data.fields().len(), false),
+            CallableDef::EnumVariantId(it) => (db.enum_variant_data(it).fields().len(), false),
+        };
+        if found == expected || (is_varargs && found > expected) {
+            return None;
+        }
+        if let Expr::MethodCall { .. } = expr {
+            // Don't count the receiver in the reported numbers.
+            expected -= 1;
+            found -= 1;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        if let Ok(source_ptr) = source_map.expr_syntax(call_id) {
+            if let Some(call_expr) = source_ptr.value.left() {
+                self.sink.push(MismatchedArgCount {
+                    file: source_ptr.file_id,
+                    call_expr,
+                    expected,
+                    found,
+                });
+            }
+        }
+        None
+    }
+
+    /// Checks the trait bounds of a called function for the types it is
+    /// called with. Only ADTs are checked, as the trait solver doesn't know
+    /// about all the builtin impls for other types.
+    fn validate_trait_bounds(
+        &mut self,
+        db: &dyn HirDatabase,
+        call_id: ExprId,
+        callee: ExprId,
+    ) -> Option<()> {
+        let (func, substs) = match self.infer.type_of_expr.get(callee)?.as_callable()? {
+            (CallableDef::FunctionId(it), substs) => (it, substs),
+            _ => return None,
+        };
+        let resolver = self.func.resolver(db.upcast());
+        let krate = resolver.krate()?;
+        let env = TraitEnvironment::lower(db, &resolver);
+        for pred in db.generic_predicates(func.into()).iter() {
+            let trait_ref = match pred.clone().subst(substs) {
+                GenericPredicate::Implemented(it) => it,
+                _ => continue,
+            };
+            let adt = match trait_ref.self_ty() {
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(it), .. }) => *it,
+                _ => continue,
+            };
+            // Auto traits and lang traits like `Sized` have builtin impls,
+            // which the impls of other traits might depend on as well.
+            let trait_data = db.trait_data(trait_ref.trait_);
+            let is_lang_item = db.attrs(trait_ref.trait_.into()).by_key("lang").exists();
+            if !trait_ref.substs.iter().all(is_trait_solvable) || trait_data.auto || is_lang_item {
+                continue;
+            }
+            let obligation = Obligation::Trait(trait_ref.clone());
+            let goal =
+                Canonical { value: InEnvironment::new(env.clone(), obligation), num_vars: 0 };
+            if !trait_goal_fails(db, krate, goal) {
+                continue;
+            }
+
+            let (_, source_map) = db.body_with_source_map(self.func.into());
+            if let Ok(source_ptr) = source_map.expr_syntax(call_id) {
+                if let Some(call_expr) = source_ptr.value.left() {
+                    self.sink.push(UnsatisfiedTraitBound {
+                        file: source_ptr.file_id,
+                        call_expr,
+                        ty: match resolver.module() {
+                            Some(module) => trait_ref.self_ty().display_qualified(db, module),
+                            None => trait_ref.self_ty().display(db),
+                        }
+                        .to_string(),
+                        adt,
+                        trait_: trait_ref.trait_,
+                        trait_name: trait_data.name.clone(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Checks that the value iterated by a `for` loop implements
+    /// `IntoIterator`.
+    fn validate_for_loop(&mut self, db: &dyn HirDatabase, iterable: ExprId) -> Option<()> {
+        let ty = self.infer.type_of_expr.get(iterable)?;
+        let mut is_known = true;
+        ty.walk(&mut |it| {
+            if let Ty::Unknown | Ty::Infer(_) = it {
+                is_known = false;
+            }
+        });
+        match ty {
+            Ty::Apply(a_ty) if is_known && a_ty.ctor != TypeCtor::Never => {}
+            _ => return None,
+        }
+        let resolver = self.func.resolver(db.upcast());
+        let krate = resolver.krate()?;
+        let into_iterator = into_iterator_trait(db.upcast(), &resolver)?;
+        let env = TraitEnvironment::lower(db, &resolver);
+        let trait_ref = TraitRef { trait_: into_iterator, substs: Substs::single(ty.clone()) };
+        let goal =
+            Canonical { value: InEnvironment::new(env, Obligation::Trait(trait_ref)), num_vars: 0 };
+        if !trait_goal_fails(db, krate, goal) {
+            return None;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        let source_ptr = source_map.expr_syntax(iterable).ok()?;
+        let expr = source_ptr.value.left()?;
+        self.sink.push(NotAnIterator {
+            file: source_ptr.file_id,
+            expr,
+            ty: ty.display(db).to_string(),
+        });
+        None
+    }
+
+    /// Checks constant indices into arrays of a known length.
+    fn validate_index(
+        &mut self,
+        db: &dyn HirDatabase,
+        body: &Body,
+        id: ExprId,
+        base: ExprId,
+        index: ExprId,
+    ) -> Option<()> {
+        let mut base_ty = self.infer.type_of_expr.get(base)?;
+        while let Some((inner, _)) = base_ty.as_reference() {
+            base_ty = inner;
+        }
+        let len = match base_ty {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Array, parameters }) => {
+                match &parameters[1] {
+                    Ty::Apply(ApplicationTy { ctor: TypeCtor::Const(len), .. }) => *len,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        // Indexing with a range gives a subslice, which isn't checked.
+        match self.infer.type_of_expr.get(index)? {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Int(_), .. }) => {}
+            _ => return None,
+        }
+        let index_value = u128::try_from(eval_expr(db, self.func.into(), body, index)?).ok()?;
+        if index_value < len {
+            return None;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        let source_ptr = source_map.expr_syntax(id).ok()?;
+        let expr = source_ptr.value.left()?;
+        self.sink.push(IndexOutOfBounds {
+            file: source_ptr.file_id,
+            expr,
+            index: index_value,
+            len,
+        });
+        None
+    }
+
+    /// Checks that the result of a call used as a statement isn't
+    /// `#[must_use]`, either because of the callee or because of its type.
+    fn validate_must_use(&mut self, db: &dyn HirDatabase, id: ExprId, expr: &Expr) -> Option<()> {
+        let callee = match expr {
+            Expr::Call { callee, .. } => {
+                match self.infer.type_of_expr.get(*callee)?.as_callable() {
+                    Some((CallableDef::FunctionId(it), _)) => Some(it),
+                    _ => None,
+                }
+            }
+            Expr::MethodCall { .. } => self.infer.method_resolution(id),
+            _ => return None,
+        };
+
+        let fn_must_use = callee.and_then(|it| {
+            let data = db.function_data(it);
+            let attr = data.attrs.by_key("must_use");
+            if attr.exists() {
+                Some((data.name.clone(), true, attr.string_value().map(|it| it.to_string())))
+            } else {
+                None
+            }
+        });
+        let must_use = fn_must_use.or_else(|| {
+            let adt = match self.infer.type_of_expr.get(id)? {
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(adt), .. }) => *adt,
+                _ => return None,
+            };
+            let attrs = db.attrs(adt.into());
+            let attr = attrs.by_key("must_use");
+            if !attr.exists() {
+                return None;
+            }
+            let name = match adt {
+                AdtId::StructId(it) => db.struct_data(it).name.clone(),
+                AdtId::UnionId(it) => db.union_data(it).name.clone(),
+                AdtId::EnumId(it) => db.enum_data(it).name.clone(),
+            };
+            Some((name, false, attr.string_value().map(|it| it.to_string())))
+        });
+        let (name, is_fn, reason) = must_use?;
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        let source_ptr = source_map.expr_syntax(id).ok()?;
+        let expr = source_ptr.value.left()?;
+        self.sink.push(UnusedMustUse { file: source_ptr.file_id, expr, name, is_fn, reason });
+        None
     }
 
     fn validate_match(
@@ -108,33 +561,27 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             // We skip any patterns whose type we cannot resolve.
             //
             // This could lead to false positives in this diagnostic, so
-            // it might be better to skip the entire diagnostic if we either
-            // cannot resolve a match arm or determine that the match arm has
-            // the wrong type.
-            if let Some(pat_ty) = infer.type_of_pat.get(pat) {
-                // We only include patterns whose type matches the type
-                // of the match expression. If we had a InvalidMatchArmPattern
-                // diagnostic or similar we could raise that in an else
-                // block here.
-                //
-                // When comparing the types, we also have to consider that rustc
-                // will automatically de-reference the match expression type if
-                // necessary.
-                //
-                // FIXME we should use the type checker for this.
-                if pat_ty == match_expr_ty
-                    || match_expr_ty
-                        .as_reference()
-                        .map(|(match_expr_ty, _)| match_expr_ty == pat_ty)
-                        .unwrap_or(false)
-                {
-                    // If we had a NotUsefulMatchArm diagnostic, we could
-                    // check the usefulness of each pattern as we added it
-                    // to the matrix here.
-                    let v = PatStack::from_pattern(pat);
-                    seen.push(&cx, v);
+            // it might be better to skip the entire diagnostic if we
+            // cannot resolve a match arm.
+            let pat_ty = match infer.type_of_pat.get(pat) {
+                Some(it) => it,
+                None => continue,
+            };
+            // Patterns of another type are reported on their own and left
+            // out of the exhaustiveness check.
+            match pattern_type_matches(pat_ty, match_expr_ty) {
+                Some(true) => {}
+                Some(false) => {
+                    self.report_pattern_type_mismatch(db, &source_map, pat, match_expr_ty, pat_ty);
+                    continue;
                 }
+                None => continue,
             }
+            // If we had a NotUsefulMatchArm diagnostic, we could
+            // check the usefulness of each pattern as we added it
+            // to the matrix here.
+            let v = PatStack::from_pattern(pat);
+            seen.push(&cx, v);
         }
 
         match is_useful(&cx, &seen, &PatStack::from_wild()) {
@@ -164,6 +611,78 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         }
     }
 
+    fn report_pattern_type_mismatch(
+        &mut self,
+        db: &dyn HirDatabase,
+        source_map: &BodySourceMap,
+        pat: PatId,
+        expected: &Ty,
+        found: &Ty,
+    ) {
+        let source_ptr = match source_map.pat_syntax(pat) {
+            Ok(it) => it,
+            Err(_) => return,
+        };
+        if let Some(pat_ptr) = source_ptr.value.left() {
+            // Parameters which can't be inferred from a pattern of the wrong
+            // type are unknown, they are shown like holes.
+            let found = found.display(db).to_string().replace("{unknown}", "_");
+            self.sink.push(PatternTypeMismatch {
+                file: source_ptr.file_id,
+                pat: pat_ptr,
+                expected: expected.display(db).to_string(),
+                found,
+            })
+        }
+    }
+
+    /// Checks that `?` is only used in functions whose return type implements
+    /// `Try`. The `?` in closures and `try` blocks return from those instead.
+    fn validate_try_operators(&mut self, db: &dyn HirDatabase, body: &Body) -> Option<()> {
+        let mut try_exprs = Vec::new();
+        collect_try_exprs(body, body.body_expr, &mut try_exprs);
+        if try_exprs.is_empty() {
+            return None;
+        }
+        let resolver = self.func.resolver(db.upcast());
+        let krate = resolver.krate()?;
+        let try_trait = resolver.resolve_known_trait(db.upcast(), &path![std::ops::Try])?;
+
+        let data = db.function_data(self.func);
+        let ret_type = data.async_ret_type.as_ref().unwrap_or(&data.ret_type);
+        let ret_ty = Ty::from_hir(&TyLoweringContext::new(db, &resolver), ret_type);
+        let mut is_known = true;
+        ret_ty.walk(&mut |it| {
+            if let Ty::Unknown = it {
+                is_known = false;
+            }
+        });
+        if !is_known || !matches!(ret_ty, Ty::Apply(_)) {
+            return None;
+        }
+        let env = TraitEnvironment::lower(db, &resolver);
+        let trait_ref = TraitRef { trait_: try_trait, substs: Substs::single(ret_ty.clone()) };
+        let goal =
+            Canonical { value: InEnvironment::new(env, Obligation::Trait(trait_ref)), num_vars: 0 };
+        if !trait_goal_fails(db, krate, goal) {
+            return None;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        for id in try_exprs {
+            if let Ok(source_ptr) = source_map.expr_syntax(id) {
+                if let Some(expr) = source_ptr.value.left() {
+                    self.sink.push(TryInNonTryFunction {
+                        file: source_ptr.file_id,
+                        expr,
+                        ret_ty: ret_ty.display(db).to_string(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
     fn validate_results_in_tail_expr(&mut self, body_id: ExprId, id: ExprId, db: &dyn HirDatabase) {
         // the mismatch will be on the whole block currently
         let mismatch = match self.infer.type_mismatch_for_expr(body_id) {
@@ -197,11 +716,186 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
     }
 }
 
+/// The binding a path expression refers to, if it is a local.
+fn resolve_local(scopes: &ExprScopes, id: ExprId, expr: &Expr) -> Option<PatId> {
+    let name = match expr {
+        Expr::Path(path) => path.mod_path().as_ident()?,
+        _ => return None,
+    };
+    let scope = scopes.scope_for(id)?;
+    Some(scopes.resolve_name_in_scope(scope, name)?.pat())
+}
+
+/// Collects the locals which are moved whenever `expr` is evaluated, together
+/// with the path expressions moving them.
+fn collect_moves(body: &Body, scopes: &ExprScopes, expr: ExprId, acc: &mut Vec<(PatId, ExprId)>) {
+    match &body[expr] {
+        Expr::Call { callee, args } => {
+            collect_moves(body, scopes, *callee, acc);
+            for arg in args {
+                collect_moved_operand(body, scopes, *arg, acc);
+            }
+        }
+        // The receiver might be borrowed by autoref.
+        Expr::MethodCall { receiver, args, .. } => {
+            collect_moves(body, scopes, *receiver, acc);
+            for arg in args {
+                collect_moved_operand(body, scopes, *arg, acc);
+            }
+        }
+        Expr::BinaryOp { rhs, op: Some(BinaryOp::Assignment { op: None }), .. } => {
+            collect_moved_operand(body, scopes, *rhs, acc);
+        }
+        Expr::BinaryOp { lhs, op: Some(BinaryOp::LogicOp(_)), .. } => {
+            collect_moves(body, scopes, *lhs, acc);
+        }
+        Expr::If { condition, .. } => collect_moves(body, scopes, *condition, acc),
+        // Only parts of these are evaluated, maybe several times, or not at
+        // all.
+        Expr::Match { .. }
+        | Expr::Loop { .. }
+        | Expr::While { .. }
+        | Expr::For { .. }
+        | Expr::Lambda { .. }
+        | Expr::Block { .. }
+        | Expr::TryBlock { .. }
+        | Expr::Async { .. } => {}
+        // Code after these is unreachable.
+        Expr::Return { .. } | Expr::Break { .. } | Expr::Continue => {}
+        expr => expr.walk_child_exprs(|it| collect_moves(body, scopes, it, acc)),
+    }
+}
+
+/// Like `collect_moves`, for an expression whose value is moved.
+fn collect_moved_operand(
+    body: &Body,
+    scopes: &ExprScopes,
+    expr: ExprId,
+    acc: &mut Vec<(PatId, ExprId)>,
+) {
+    match resolve_local(scopes, expr, &body[expr]) {
+        Some(local) => acc.push((local, expr)),
+        None => collect_moves(body, scopes, expr, acc),
+    }
+}
+
+/// Whether `local` is assigned anywhere in `expr`.
+fn assigns_local(body: &Body, scopes: &ExprScopes, expr: ExprId, local: PatId) -> bool {
+    let mut res = false;
+    walk_exprs(body, expr, &mut |id| {
+        if let Expr::BinaryOp { lhs, op: Some(BinaryOp::Assignment { .. }), .. } = &body[id] {
+            if resolve_local(scopes, *lhs, &body[*lhs]) == Some(local) {
+                res = true;
+            }
+        }
+    });
+    res
+}
+
+/// The first path expression in `expr` referring to `local`.
+fn first_use(body: &Body, scopes: &ExprScopes, expr: ExprId, local: PatId) -> Option<ExprId> {
+    let mut res = None;
+    walk_exprs(body, expr, &mut |id| {
+        if res.is_none() && resolve_local(scopes, id, &body[id]) == Some(local) {
+            res = Some(id);
+        }
+    });
+    res
+}
+
+/// Whether the trait solver can tell if `ty` implements a trait. It can't if
+/// the type is not fully known, or if it contains types like tuples or
+/// references, as the solver doesn't know their builtin impls of `Copy` and
+/// `Clone`.
+fn is_trait_solvable(ty: &Ty) -> bool {
+    let mut res = true;
+    ty.walk(&mut |it| match it {
+        Ty::Unknown | Ty::Infer(_) => res = false,
+        Ty::Apply(ApplicationTy { ctor, .. }) => match ctor {
+            TypeCtor::Tuple { .. }
+            | TypeCtor::Array
+            | TypeCtor::Ref(_)
+            | TypeCtor::RawPtr(_)
+            | TypeCtor::FnPtr { .. }
+            | TypeCtor::FnDef(_)
+            | TypeCtor::Closure { .. }
+            | TypeCtor::Never => res = false,
+            _ => {}
+        },
+        _ => {}
+    });
+    res
+}
+
+/// Whether a pattern of type `pat_ty` can match a value of `match_ty`, `None`
+/// if this is unclear because of unknown types or type parameters.
+fn pattern_type_matches(pat_ty: &Ty, match_ty: &Ty) -> Option<bool> {
+    // rustc will automatically dereference the match expression type if
+    // necessary.
+    let mut match_tys = vec![match_ty];
+    let mut ty = match_ty;
+    while let Some((inner, _)) = ty.as_reference() {
+        match_tys.push(inner);
+        ty = inner;
+    }
+    if match_tys.iter().any(|&it| it == pat_ty) {
+        return Some(true);
+    }
+
+    // FIXME we should use the type checker for this.
+    let ctor = |ty: &Ty| match ty {
+        Ty::Apply(a_ty) => Some(a_ty.ctor),
+        _ => None,
+    };
+    let pat_ctor = ctor(pat_ty)?;
+    if ctor(ty)? == TypeCtor::Never {
+        return None;
+    }
+    if match_tys.iter().any(|&it| ctor(it) == Some(pat_ctor)) {
+        return None;
+    }
+    Some(false)
+}
+
+/// The `?` expressions returning from the function, which excludes those in
+/// closures, `try` and `async` blocks.
+fn collect_try_exprs(body: &Body, expr: ExprId, acc: &mut Vec<ExprId>) {
+    match &body[expr] {
+        Expr::Lambda { .. } | Expr::TryBlock { .. } | Expr::Async { .. } => return,
+        Expr::Try { .. } => acc.push(expr),
+        _ => (),
+    }
+    body[expr].walk_child_exprs(|it| collect_try_exprs(body, it, acc));
+}
+
+fn walk_exprs(body: &Body, expr: ExprId, f: &mut impl FnMut(ExprId)) {
+    f(expr);
+    body[expr].walk_child_exprs(|it| walk_exprs(body, it, &mut *f));
+}
+
+fn collect_bindings(body: &Body, pat: PatId, acc: &mut Vec<(PatId, Name)>) {
+    if let Pat::Bind { name, .. } = &body[pat] {
+        acc.push((pat, name.clone()));
+    }
+    body[pat].walk_child_pats(|it| collect_bindings(body, it, acc));
+}
+
+/// Checks for `#[allow(lint)]`, or `#[allow(unused)]` for the lints in the
+/// `unused` group.
+fn is_lint_allowed(attrs: &Attrs, lint: &str) -> bool {
+    attrs.by_key("allow").tt_values().any(|tt| {
+        tt.to_string()
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .any(|it| it == lint || (it == "unused" && lint.starts_with("unused_")))
+    })
+}
+
 pub fn record_literal_missing_fields(
     db: &dyn HirDatabase,
     infer: &InferenceResult,
     id: ExprId,
     expr: &Expr,
+    from: ModuleId,
 ) -> Option<(VariantId, Vec<LocalStructFieldId>, /*exhaustive*/ bool)> {
     let (fields, exhausitve) = match expr {
         Expr::RecordLit { path: _, fields, spread } => (fields, spread.is_none()),
@@ -214,11 +908,14 @@ pub fn record_literal_missing_fields(
     }
 
     let variant_data = variant_data(db.upcast(), variant_def);
+    // Private fields can't be specified outside of their module anyway
+    let visible_fields = db.visible_fields(variant_def, from);
 
     let specified_fields: FxHashSet<_> = fields.iter().map(|f| &f.name).collect();
     let missed_fields: Vec<LocalStructFieldId> = variant_data
         .fields()
         .iter()
+        .filter(|(f, _)| visible_fields.contains(f))
         .filter_map(|(f, d)| if specified_fields.contains(&d.name) { None } else { Some(f) })
         .collect();
     if missed_fields.is_empty() {
@@ -232,6 +929,7 @@ pub fn record_pattern_missing_fields(
     infer: &InferenceResult,
     id: PatId,
     pat: &Pat,
+    from: ModuleId,
 ) -> Option<(VariantId, Vec<LocalStructFieldId>)> {
     let fields = match pat {
         Pat::Record { path: _, args } => args,
@@ -244,11 +942,14 @@ pub fn record_pattern_missing_fields(
     }
 
     let variant_data = variant_data(db.upcast(), variant_def);
+    // Private fields can't be specified outside of their module anyway
+    let visible_fields = db.visible_fields(variant_def, from);
 
     let specified_fields: FxHashSet<_> = fields.iter().map(|f| &f.name).collect();
     let missed_fields: Vec<LocalStructFieldId> = variant_data
         .fields()
         .iter()
+        .filter(|(f, _)| visible_fields.contains(f))
         .filter_map(|(f, d)| if specified_fields.contains(&d.name) { None } else { Some(f) })
         .collect();
     if missed_fields.is_empty() {
@@ -28,7 +28,8 @@ use hir_def::{
     path::{path, Path},
     resolver::{HasResolver, Resolver, TypeNs},
     type_ref::{Mutability, TypeRef},
-    AdtId, AssocItemId, DefWithBodyId, FunctionId, StructFieldId, TraitId, TypeAliasId, VariantId,
+    AdtId, AssocContainerId, AssocItemId, DefWithBodyId, FunctionId, Lookup, StructFieldId,
+    TraitId, TypeAliasId, VariantId,
 };
 use hir_expand::{diagnostics::DiagnosticSink, name::name};
 use ra_arena::map::ArenaMap;
@@ -37,14 +38,18 @@ use ra_syntax::SmolStr;
 
 use super::{
     primitive::{FloatTy, IntTy},
-    traits::{Guidance, Obligation, ProjectionPredicate, Solution},
+    traits::{FnTrait, Guidance, Obligation, ProjectionPredicate, Solution},
     ApplicationTy, GenericPredicate, InEnvironment, ProjectionTy, Substs, TraitEnvironment,
     TraitRef, Ty, TypeCtor, TypeWalk, Uncertain,
 };
 use crate::{
-    db::HirDatabase, infer::diagnostics::InferenceDiagnostic, lower::ImplTraitLoweringMode,
+    db::HirDatabase, diagnostics::PrivateItem, infer::diagnostics::InferenceDiagnostic,
+    lower::ImplTraitLoweringMode, method_resolution::ReceiverAdjustments,
+    utils::into_iterator_trait,
 };
 
+pub(crate) use coerce::could_coerce_query;
+pub use unify::could_unify;
 pub(crate) use unify::unify;
 
 macro_rules! ty_app {
@@ -90,7 +95,7 @@ impl_froms!(ExprOrPatId: ExprId, PatId);
 /// Binding modes inferred for patterns.
 /// https://doc.rust-lang.org/reference/patterns.html#binding-modes
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum BindingMode {
+pub enum BindingMode {
     Move,
     Ref(Mutability),
 }
@@ -118,11 +123,35 @@ pub struct TypeMismatch {
     pub actual: Ty,
 }
 
+/// An implicit conversion applied to an expression, like an autoref of a
+/// method receiver or a coercion.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Adjustment {
+    /// Going from `!` to any other type.
+    NeverToAny,
+    /// Dereferencing, either builtin or through `Deref`.
+    Deref,
+    /// Taking a reference, `&x` or `&mut x`.
+    Borrow(Mutability),
+    /// Taking a raw pointer, as in `&mut T` -> `*mut T`.
+    RawBorrow(Mutability),
+    /// Going from `*mut T` to `*const T`.
+    MutToConstPointer,
+    /// Going from a function item or a non-capturing closure to a function pointer.
+    FnPointer,
+    /// Unsizing, as in `&[T; N]` -> `&[T]` or `Box<T>` -> `Box<dyn Trait>`.
+    Unsize,
+}
+
 /// The result of type inference: A mapping from expressions and patterns to types.
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct InferenceResult {
     /// For each method call expr, records the function it resolves to.
     method_resolutions: FxHashMap<ExprId, FunctionId>,
+    /// For each method call expr, records the adjustments applied to its receiver.
+    method_receiver_adjustments: FxHashMap<ExprId, ReceiverAdjustments>,
+    /// For each expression, records the adjustments applied to it, in order.
+    expr_adjustments: FxHashMap<ExprId, Vec<Adjustment>>,
     /// For each field access expr, records the field it resolves to.
     field_resolutions: FxHashMap<ExprId, StructFieldId>,
     /// For each field in record literal, records the field it resolves to.
@@ -134,6 +163,9 @@ pub struct InferenceResult {
     diagnostics: Vec<InferenceDiagnostic>,
     pub type_of_expr: ArenaMap<ExprId, Ty>,
     pub type_of_pat: ArenaMap<PatId, Ty>,
+    /// For each binding pattern, records its binding mode after match
+    /// ergonomics.
+    pat_binding_modes: FxHashMap<PatId, BindingMode>,
     pub(super) type_mismatches: ArenaMap<ExprId, TypeMismatch>,
 }
 
@@ -141,6 +173,12 @@ impl InferenceResult {
     pub fn method_resolution(&self, expr: ExprId) -> Option<FunctionId> {
         self.method_resolutions.get(&expr).copied()
     }
+    pub fn method_receiver_adjustments(&self, expr: ExprId) -> Option<ReceiverAdjustments> {
+        self.method_receiver_adjustments.get(&expr).copied()
+    }
+    pub fn expr_adjustments(&self, expr: ExprId) -> &[Adjustment] {
+        self.expr_adjustments.get(&expr).map_or(&[], |it| it.as_slice())
+    }
     pub fn field_resolution(&self, expr: ExprId) -> Option<StructFieldId> {
         self.field_resolutions.get(&expr).copied()
     }
@@ -159,6 +197,9 @@ impl InferenceResult {
     pub fn assoc_resolutions_for_pat(&self, id: PatId) -> Option<AssocItemId> {
         self.assoc_resolutions.get(&id.into()).copied()
     }
+    pub fn binding_mode(&self, pat: PatId) -> Option<BindingMode> {
+        self.pat_binding_modes.get(&pat).copied()
+    }
     pub fn type_mismatch_for_expr(&self, expr: ExprId) -> Option<&TypeMismatch> {
         self.type_mismatches.get(expr)
     }
@@ -245,6 +286,16 @@ impl<'a> InferenceContext<'a> {
         self.result.method_resolutions.insert(expr, func);
     }
 
+    fn write_method_receiver_adjustments(&mut self, expr: ExprId, adj: ReceiverAdjustments) {
+        self.result.method_receiver_adjustments.insert(expr, adj);
+    }
+
+    fn write_expr_adjustments(&mut self, expr: ExprId, adjustments: Vec<Adjustment>) {
+        if !adjustments.is_empty() {
+            self.result.expr_adjustments.insert(expr, adjustments);
+        }
+    }
+
     fn write_field_resolution(&mut self, expr: ExprId, field: StructFieldId) {
         self.result.field_resolutions.insert(expr, field);
     }
@@ -265,6 +316,31 @@ impl<'a> InferenceContext<'a> {
         self.result.diagnostics.push(diagnostic);
     }
 
+    /// Reports the use of `item` by `expr` if its visibility doesn't allow
+    /// using it in the current body.
+    fn check_visibility(&mut self, expr: ExprId, item: PrivateItem) {
+        let module = match self.resolver.module() {
+            Some(it) => it,
+            None => return,
+        };
+        let db = self.db.upcast();
+        let visibility = match item {
+            PrivateItem::Function(it) => {
+                // Items of trait impls are as visible as the trait.
+                if let AssocContainerId::ImplId(impl_id) = it.lookup(db).container {
+                    if self.db.impl_data(impl_id).target_trait.is_some() {
+                        return;
+                    }
+                }
+                self.db.function_data(it).visibility.resolve(db, &it.resolver(db))
+            }
+            PrivateItem::Field(it) => self.db.field_visibilities(it.parent)[it.local_id],
+        };
+        if !visibility.is_visible_from(db, module) {
+            self.push_diagnostic(InferenceDiagnostic::PrivateItemAccess { expr, item });
+        }
+    }
+
     fn make_ty_with_mode(
         &mut self,
         type_ref: &TypeRef,
@@ -484,7 +560,13 @@ impl<'a> InferenceContext<'a> {
 
             self.infer_pat(*pat, &ty, BindingMode::default());
         }
-        let return_ty = self.make_ty_with_mode(&data.ret_type, ImplTraitLoweringMode::Disallowed); // FIXME implement RPIT
+        // The body of an `async fn` evaluates to the declared return type, not
+        // to the future returned to callers.
+        let ret_type = data.async_ret_type.as_ref().unwrap_or(&data.ret_type);
+        // The hidden type of a return position `impl Trait` is inferred from
+        // the body, so it is lowered to a type variable here. Callers see the
+        // opaque type from the signature instead.
+        let return_ty = self.make_ty_with_mode(ret_type, ImplTraitLoweringMode::Disallowed);
         self.return_ty = return_ty;
     }
 
@@ -499,8 +581,7 @@ impl<'a> InferenceContext<'a> {
     }
 
     fn resolve_into_iter_item(&self) -> Option<TypeAliasId> {
-        let path = path![std::iter::IntoIterator];
-        let trait_ = self.resolver.resolve_known_trait(self.db.upcast(), &path)?;
+        let trait_ = into_iterator_trait(self.db.upcast(), &self.resolver)?;
         self.db.trait_data(trait_).associated_type_by_name(&name![Item])
     }
 
@@ -525,6 +606,18 @@ impl<'a> InferenceContext<'a> {
         self.db.trait_data(trait_).associated_type_by_name(&name![Output])
     }
 
+    fn resolve_fn_once_output(&self) -> Option<TypeAliasId> {
+        let trait_ = self.resolve_lang_item(FnTrait::FnOnce.lang_item_name())?.as_trait()?;
+        self.db.trait_data(trait_).associated_type_by_name(&name![Output])
+    }
+
+    fn is_fn_trait(&self, trait_: TraitId) -> bool {
+        [FnTrait::FnOnce, FnTrait::FnMut, FnTrait::Fn].iter().any(|fn_trait| {
+            self.resolve_lang_item(fn_trait.lang_item_name()).and_then(|it| it.as_trait())
+                == Some(trait_)
+        })
+    }
+
     fn resolve_boxed_box(&self) -> Option<AdtId> {
         let struct_ = self.resolve_lang_item("owned_box")?.as_struct()?;
         Some(struct_.into())
@@ -663,14 +756,27 @@ impl Expectation {
 }
 
 mod diagnostics {
-    use hir_def::{expr::ExprId, src::HasSource, FunctionId, Lookup};
+    use hir_def::{
+        expr::{Expr, ExprId},
+        src::HasSource,
+        FunctionId, Lookup,
+    };
     use hir_expand::diagnostics::DiagnosticSink;
 
-    use crate::{db::HirDatabase, diagnostics::NoSuchField};
+    use crate::{
+        db::HirDatabase,
+        diagnostics::{
+            NoSuchField, PrivateItem, PrivateItemAccess, UnresolvedMethodCall, UnresolvedPath,
+        },
+        utils::variant_data,
+    };
 
     #[derive(Debug, PartialEq, Eq, Clone)]
     pub(super) enum InferenceDiagnostic {
         NoSuchField { expr: ExprId, field: usize },
+        UnresolvedPath { expr: ExprId },
+        PrivateItemAccess { expr: ExprId, item: PrivateItem },
+        UnresolvedMethodCall { expr: ExprId },
     }
 
     impl InferenceDiagnostic {
@@ -687,6 +793,60 @@ mod diagnostics {
                     let field = source_map.field_syntax(*expr, *field);
                     sink.push(NoSuchField { file, field })
                 }
+                InferenceDiagnostic::UnresolvedPath { expr } => {
+                    let file = owner.lookup(db.upcast()).source(db.upcast()).file_id;
+                    let (body, source_map) = db.body_with_source_map(owner.into());
+                    let name = match &body[*expr] {
+                        Expr::Path(path) => match path.segments().first() {
+                            Some(segment) => segment.name.clone(),
+                            None => return,
+                        },
+                        _ => return,
+                    };
+                    // Paths from macro expansions can't be pointed at in the file.
+                    let expr = match source_map.expr_syntax(*expr) {
+                        Ok(source) if source.file_id == file => match source.value.left() {
+                            Some(it) => it,
+                            None => return,
+                        },
+                        _ => return,
+                    };
+                    sink.push(UnresolvedPath { file, expr, name })
+                }
+                InferenceDiagnostic::PrivateItemAccess { expr, item } => {
+                    let file = owner.lookup(db.upcast()).source(db.upcast()).file_id;
+                    let (_, source_map) = db.body_with_source_map(owner.into());
+                    let name = match item {
+                        PrivateItem::Function(it) => db.function_data(*it).name.clone(),
+                        PrivateItem::Field(it) => {
+                            variant_data(db.upcast(), it.parent).fields()[it.local_id].name.clone()
+                        }
+                    };
+                    let expr = match source_map.expr_syntax(*expr) {
+                        Ok(source) if source.file_id == file => match source.value.left() {
+                            Some(it) => it,
+                            None => return,
+                        },
+                        _ => return,
+                    };
+                    sink.push(PrivateItemAccess { file, expr, name, item: *item })
+                }
+                InferenceDiagnostic::UnresolvedMethodCall { expr } => {
+                    let file = owner.lookup(db.upcast()).source(db.upcast()).file_id;
+                    let (body, source_map) = db.body_with_source_map(owner.into());
+                    let name = match &body[*expr] {
+                        Expr::MethodCall { method_name, .. } => method_name.clone(),
+                        _ => return,
+                    };
+                    let expr = match source_map.expr_syntax(*expr) {
+                        Ok(source) if source.file_id == file => match source.value.left() {
+                            Some(it) => it,
+                            None => return,
+                        },
+                        _ => return,
+                    };
+                    sink.push(UnresolvedMethodCall { file, expr, name })
+                }
             }
         }
     }
@@ -5,16 +5,29 @@
 //! See: https://doc.rust-lang.org/nomicon/coercions.html
 
 use hir_def::{lang_item::LangItemTarget, type_ref::Mutability};
+use ra_db::CrateId;
 use test_utils::tested_by;
 
-use crate::{autoderef, traits::Solution, Obligation, Substs, TraitRef, Ty, TypeCtor};
+use crate::{
+    autoderef, db::HirDatabase, traits::Solution, Canonical, Obligation, Substs, TraitRef, Ty,
+    TypeCtor, TypeWalk,
+};
 
-use super::{unify::TypeVarValue, InEnvironment, InferTy, InferenceContext};
+use super::{
+    unify::{could_unify, TypeVarValue},
+    Adjustment, InEnvironment, InferTy, InferenceContext,
+};
 
 impl<'a> InferenceContext<'a> {
     /// Unify two types, but may coerce the first one to the second one
     /// using "implicit coercion rules" if needed.
     pub(super) fn coerce(&mut self, from_ty: &Ty, to_ty: &Ty) -> bool {
+        self.coerce_adjusted(from_ty, to_ty).is_some()
+    }
+
+    /// Like `coerce`, but returns the adjustments applied to the coerced
+    /// expression, or `None` if the types can't be unified.
+    pub(super) fn coerce_adjusted(&mut self, from_ty: &Ty, to_ty: &Ty) -> Option<Vec<Adjustment>> {
         let from_ty = self.resolve_ty_shallow(from_ty).into_owned();
         let to_ty = self.resolve_ty_shallow(to_ty);
         self.coerce_inner(from_ty, &to_ty)
@@ -24,12 +37,15 @@ impl<'a> InferenceContext<'a> {
     ///
     /// Note that it is only possible that one type are coerced to another.
     /// Coercing both types to another least upper bound type is not possible in rustc,
-    /// which will simply result in "incompatible types" error.
+    /// which will simply result in "incompatible types" error. The exception is
+    /// distinct function items and closures, which are coerced to a common function pointer.
     pub(super) fn coerce_merge_branch(&mut self, ty1: &Ty, ty2: &Ty) -> Ty {
         if self.coerce(ty1, ty2) {
             ty2.clone()
         } else if self.coerce(ty2, ty1) {
             ty1.clone()
+        } else if let Some(ptr) = self.coerce_to_common_fn_ptr(ty1, ty2) {
+            ptr
         } else {
             tested_by!(coerce_merge_fail_fallback);
             // For incompatible types, we use the latter one as result
@@ -38,25 +54,27 @@ impl<'a> InferenceContext<'a> {
         }
     }
 
-    fn coerce_inner(&mut self, mut from_ty: Ty, to_ty: &Ty) -> bool {
+    fn coerce_inner(&mut self, mut from_ty: Ty, to_ty: &Ty) -> Option<Vec<Adjustment>> {
         match (&from_ty, to_ty) {
             // Never type will make type variable to fallback to Never Type instead of Unknown.
             (ty_app!(TypeCtor::Never), Ty::Infer(InferTy::TypeVar(tv))) => {
                 let var = self.table.new_maybe_never_type_var();
                 self.table.var_unification_table.union_value(*tv, TypeVarValue::Known(var));
-                return true;
+                return Some(vec![Adjustment::NeverToAny]);
             }
-            (ty_app!(TypeCtor::Never), _) => return true,
+            (ty_app!(TypeCtor::Never), _) => return Some(vec![Adjustment::NeverToAny]),
 
             // Trivial cases, this should go after `never` check to
             // avoid infer result type to be never
             _ => {
                 if self.table.unify_inner_trivial(&from_ty, &to_ty) {
-                    return true;
+                    return Some(Vec::new());
                 }
             }
         }
 
+        let mut adjustments = Vec::new();
+
         // Pointer weakening and function to pointer
         match (&mut from_ty, to_ty) {
             // `*mut T`, `&mut T, `&T`` -> `*const T`
@@ -66,6 +84,18 @@ impl<'a> InferenceContext<'a> {
             | (ty_app!(c1@TypeCtor::Ref(_)), ty_app!(c2@TypeCtor::RawPtr(Mutability::Shared)))
             | (ty_app!(c1@TypeCtor::Ref(_)), ty_app!(c2@TypeCtor::Ref(Mutability::Shared)))
             | (ty_app!(c1@TypeCtor::Ref(Mutability::Mut)), ty_app!(c2@TypeCtor::RawPtr(_))) => {
+                match (*c1, *c2) {
+                    (TypeCtor::Ref(_), TypeCtor::RawPtr(m)) => {
+                        adjustments.extend(vec![Adjustment::Deref, Adjustment::RawBorrow(m)]);
+                    }
+                    (TypeCtor::RawPtr(m1), TypeCtor::RawPtr(m2)) if m1 != m2 => {
+                        adjustments.push(Adjustment::MutToConstPointer);
+                    }
+                    (TypeCtor::Ref(m1), TypeCtor::Ref(m2)) if m1 != m2 => {
+                        adjustments.extend(vec![Adjustment::Deref, Adjustment::Borrow(m2)]);
+                    }
+                    _ => {}
+                }
                 *c1 = *c2;
             }
 
@@ -77,40 +107,52 @@ impl<'a> InferenceContext<'a> {
             | (
                 ty_app!(TypeCtor::Ref(Mutability::Shared)),
                 ty_app!(TypeCtor::Ref(Mutability::Mut)),
-            ) => return false,
+            ) => return None,
 
             // `{function_type}` -> `fn()`
-            (ty_app!(TypeCtor::FnDef(_)), ty_app!(TypeCtor::FnPtr { .. })) => {
-                match from_ty.callable_sig(self.db) {
-                    None => return false,
-                    Some(sig) => {
-                        let num_args = sig.params_and_return.len() as u16 - 1;
-                        from_ty =
-                            Ty::apply(TypeCtor::FnPtr { num_args }, Substs(sig.params_and_return));
-                    }
-                }
-            }
-
-            (ty_app!(TypeCtor::Closure { .. }, params), ty_app!(TypeCtor::FnPtr { .. })) => {
-                from_ty = params[0].clone();
+            // `{closure_type}` -> `fn()`
+            (ty_app!(TypeCtor::FnDef(_)), ty_app!(TypeCtor::FnPtr { .. }))
+            | (ty_app!(TypeCtor::Closure { .. }), ty_app!(TypeCtor::FnPtr { .. })) => {
+                from_ty = self.fn_ptr_of(&from_ty)?;
+                adjustments.push(Adjustment::FnPointer);
             }
 
             _ => {}
         }
 
-        if let Some(ret) = self.try_coerce_unsized(&from_ty, &to_ty) {
-            return ret;
+        let unsize = self
+            .try_coerce_unsized(&from_ty, &to_ty)
+            .or_else(|| self.try_coerce_unsized_builtin(&from_ty, &to_ty));
+        if let Some(ret) = unsize {
+            if !ret {
+                return None;
+            }
+            adjustments.push(Adjustment::Unsize);
+            return Some(adjustments);
         }
 
         // Auto Deref if cannot coerce
         match (&from_ty, to_ty) {
             // FIXME: DerefMut
-            (ty_app!(TypeCtor::Ref(_), st1), ty_app!(TypeCtor::Ref(_), st2)) => {
-                self.unify_autoderef_behind_ref(&st1[0], &st2[0])
+            (ty_app!(TypeCtor::Ref(_), st1), ty_app!(TypeCtor::Ref(m), st2)) => {
+                let steps = self.unify_autoderef_behind_ref(&st1[0], &st2[0])?;
+                if steps > 0 {
+                    // Reborrow after dereferencing the reference itself and
+                    // each of the autoderef steps.
+                    adjustments = vec![Adjustment::Deref; steps + 1];
+                    adjustments.push(Adjustment::Borrow(*m));
+                }
+                Some(adjustments)
             }
 
             // Otherwise, normal unify
-            _ => self.unify(&from_ty, to_ty),
+            _ => {
+                if self.unify(&from_ty, to_ty) {
+                    Some(adjustments)
+                } else {
+                    None
+                }
+            }
         }
     }
 
@@ -152,36 +194,215 @@ impl<'a> InferenceContext<'a> {
         Some(true)
     }
 
+    /// Unsizing coercions of references and raw pointers for crates without a
+    /// `coerce_unsized` lang item, like `&[T; N]` -> `&[T]` or `&T` -> `&dyn Trait`.
+    fn try_coerce_unsized_builtin(&mut self, from_ty: &Ty, to_ty: &Ty) -> Option<bool> {
+        let krate = self.resolver.krate()?;
+        if self.db.lang_item(krate, "coerce_unsized".into()).is_some() {
+            return None;
+        }
+        let (from_pointee, to_pointee) = match (from_ty, to_ty) {
+            (ty_app!(TypeCtor::Ref(m1), st1), ty_app!(TypeCtor::Ref(m2), st2))
+            | (ty_app!(TypeCtor::RawPtr(m1), st1), ty_app!(TypeCtor::RawPtr(m2), st2))
+                if m1 == m2 =>
+            {
+                (&st1[0], &st2[0])
+            }
+            _ => return None,
+        };
+        let from_pointee = self.resolve_ty_shallow(from_pointee).into_owned();
+        let to_pointee = self.resolve_ty_shallow(to_pointee).into_owned();
+        match (&from_pointee, &to_pointee) {
+            // `[T; N]` -> `[T]`
+            (ty_app!(TypeCtor::Array, st1), ty_app!(TypeCtor::Slice, st2)) => {
+                Some(self.unify(&st1[0], &st2[0]))
+            }
+            // Upcasting and unknown pointees are left to unification
+            (Ty::Dyn(_), _) | (Ty::Infer(_), _) => None,
+            // `T` -> `dyn Trait`, the bounds of the trait object are checked later
+            (_, Ty::Dyn(predicates)) => {
+                let self_ty = Substs::single(from_pointee.clone());
+                for predicate in predicates.iter() {
+                    let predicate = predicate.clone().subst_bound_vars(&self_ty);
+                    if let Some(obligation) = Obligation::from_predicate(predicate) {
+                        self.obligations.push(obligation);
+                    }
+                }
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    /// The function pointer type a function item or a closure coerces to.
+    fn fn_ptr_of(&mut self, ty: &Ty) -> Option<Ty> {
+        match &*self.resolve_ty_shallow(ty) {
+            ty @ ty_app!(TypeCtor::FnDef(_)) => {
+                let sig = ty.callable_sig(self.db)?;
+                let num_args = sig.params_and_return.len() as u16 - 1;
+                Some(Ty::apply(TypeCtor::FnPtr { num_args }, Substs(sig.params_and_return)))
+            }
+            ty_app!(TypeCtor::Closure { .. }, params) => Some(params[0].clone()),
+            _ => None,
+        }
+    }
+
+    /// Coerces two function items or closures of different types to a common
+    /// function pointer type, as in `if c { foo } else { bar }`.
+    fn coerce_to_common_fn_ptr(&mut self, ty1: &Ty, ty2: &Ty) -> Option<Ty> {
+        let ptr1 = self.fn_ptr_of(ty1)?;
+        let ptr2 = self.fn_ptr_of(ty2)?;
+        if self.unify(&ptr1, &ptr2) {
+            Some(ptr1)
+        } else {
+            None
+        }
+    }
+
     /// Unify `from_ty` to `to_ty` with optional auto Deref
     ///
     /// Note that the parameters are already stripped the outer reference.
-    fn unify_autoderef_behind_ref(&mut self, from_ty: &Ty, to_ty: &Ty) -> bool {
+    /// Returns the number of autoderef steps needed.
+    fn unify_autoderef_behind_ref(&mut self, from_ty: &Ty, to_ty: &Ty) -> Option<usize> {
         let canonicalized = self.canonicalizer().canonicalize_ty(from_ty.clone());
         let to_ty = self.resolve_ty_shallow(&to_ty);
         // FIXME: Auto DerefMut
-        for derefed_ty in autoderef::autoderef(
+        for (steps, derefed_ty) in autoderef::autoderef(
             self.db,
             self.resolver.krate(),
             InEnvironment {
                 value: canonicalized.value.clone(),
                 environment: self.trait_env.clone(),
             },
-        ) {
+        )
+        .enumerate()
+        {
             let derefed_ty = canonicalized.decanonicalize_ty(derefed_ty.value);
             match (&*self.resolve_ty_shallow(&derefed_ty), &*to_ty) {
                 // Stop when constructor matches.
                 (ty_app!(from_ctor, st1), ty_app!(to_ctor, st2)) if from_ctor == to_ctor => {
                     // It will not recurse to `coerce`.
-                    return self.table.unify_substs(st1, st2, 0);
+                    return if self.table.unify_substs(st1, st2, 0) { Some(steps) } else { None };
                 }
                 _ => {
                     if self.table.unify_inner_trivial(&derefed_ty, &to_ty) {
-                        return true;
+                        return Some(steps);
                     }
                 }
             }
         }
 
-        false
+        None
+    }
+}
+
+/// Checks whether a value of the first type could be coerced to the second
+/// type. Unlike `InferenceContext::coerce`, this works outside of bodies, so
+/// it can be used to compare the types of items with an expected type. The
+/// canonical variables of the types can be unified with anything.
+pub(crate) fn could_coerce_query(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    tys: Canonical<InEnvironment<(Ty, Ty)>>,
+) -> bool {
+    let Canonical { value: InEnvironment { value: (from_ty, to_ty), environment }, num_vars } = tys;
+    let unifies = |ty1: &Ty, ty2: &Ty| {
+        could_unify(&Canonical { value: (ty1.clone(), ty2.clone()), num_vars })
+    };
+    if unifies(&from_ty, &to_ty) {
+        return true;
+    }
+
+    let (from_pointee, to_pointee) = match (&from_ty, &to_ty) {
+        (ty_app!(TypeCtor::Never), _) => return true,
+
+        // `{function_type}` -> `fn()`
+        // `{closure_type}` -> `fn()`
+        (ty_app!(TypeCtor::FnDef(_)), ty_app!(TypeCtor::FnPtr { .. })) => {
+            return match from_ty.callable_sig(db) {
+                Some(sig) => {
+                    let num_args = sig.params_and_return.len() as u16 - 1;
+                    let ptr =
+                        Ty::apply(TypeCtor::FnPtr { num_args }, Substs(sig.params_and_return));
+                    unifies(&ptr, &to_ty)
+                }
+                None => false,
+            };
+        }
+        (ty_app!(TypeCtor::Closure { .. }, params), ty_app!(TypeCtor::FnPtr { .. })) => {
+            return unifies(&params[0], &to_ty);
+        }
+
+        // `&mut T` -> `&T`, `*mut T` -> `*const T`, `&T` -> `*const T`, `&mut T` -> `*mut T`
+        (ty_app!(TypeCtor::Ref(m1), st1), ty_app!(TypeCtor::Ref(m2), st2))
+        | (ty_app!(TypeCtor::RawPtr(m1), st1), ty_app!(TypeCtor::RawPtr(m2), st2))
+        | (ty_app!(TypeCtor::Ref(m1), st1), ty_app!(TypeCtor::RawPtr(m2), st2))
+            if *m1 == Mutability::Mut || *m2 == Mutability::Shared =>
+        {
+            (&st1[0], &st2[0])
+        }
+        _ => return false,
+    };
+    if unifies(from_pointee, to_pointee) {
+        return true;
+    }
+
+    // Unsizing, either through `CoerceUnsized` or through the builtin rules
+    // for crates without the lang item.
+    match db.lang_item(krate, "coerce_unsized".into()) {
+        Some(LangItemTarget::TraitId(trait_)) => {
+            let generic_params = crate::utils::generics(db.upcast(), trait_.into());
+            if generic_params.len() == 2 {
+                let substs = Substs::build_for_generics(&generic_params)
+                    .push(from_ty.clone())
+                    .push(to_ty.clone())
+                    .build();
+                let goal = InEnvironment::new(
+                    environment.clone(),
+                    Obligation::Trait(TraitRef { trait_, substs }),
+                );
+                if db.trait_solve(krate, Canonical { value: goal, num_vars }).is_some() {
+                    return true;
+                }
+            }
+        }
+        _ => match (from_pointee, to_pointee) {
+            // `[T; N]` -> `[T]`
+            (ty_app!(TypeCtor::Array, st1), ty_app!(TypeCtor::Slice, st2)) => {
+                return unifies(&st1[0], &st2[0])
+            }
+            // `T` -> `dyn Trait`, only for types without variables, as the
+            // predicates of the trait object are under another binder
+            (ty, Ty::Dyn(predicates)) if num_vars == 0 && !matches!(ty, Ty::Dyn(_)) => {
+                let self_ty = Substs::single(ty.clone());
+                return predicates.iter().all(|predicate| {
+                    let predicate = predicate.clone().subst_bound_vars(&self_ty);
+                    match Obligation::from_predicate(predicate) {
+                        Some(obligation) => {
+                            let goal = InEnvironment::new(environment.clone(), obligation);
+                            db.trait_solve(krate, Canonical { value: goal, num_vars }).is_some()
+                        }
+                        None => true,
+                    }
+                });
+            }
+            _ => {}
+        },
+    }
+
+    // Auto deref behind references
+    if !matches!(from_ty, ty_app!(TypeCtor::Ref(_))) || !matches!(to_ty, ty_app!(TypeCtor::Ref(_)))
+    {
+        return false;
     }
+    autoderef::autoderef(
+        db,
+        Some(krate),
+        InEnvironment {
+            value: Canonical { value: from_pointee.clone(), num_vars },
+            environment: environment.clone(),
+        },
+    )
+    .skip(1)
+    .any(|derefed_ty| unifies(&derefed_ty.value, to_pointee))
 }
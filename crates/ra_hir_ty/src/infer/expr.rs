@@ -1,24 +1,28 @@
 //! Type inference for expressions.
 
+use std::convert::TryFrom;
 use std::iter::{repeat, repeat_with};
 use std::sync::Arc;
 
 use hir_def::{
     builtin_type::Signedness,
     expr::{Array, BinaryOp, Expr, ExprId, Literal, Statement, UnaryOp},
-    path::{GenericArg, GenericArgs},
+    path::{GenericArg, GenericArgs, PathKind},
     resolver::resolver_for_expr,
-    AdtId, AssocContainerId, Lookup, StructFieldId,
+    AdtId, AssocContainerId, Lookup, StructFieldId, VariantId,
 };
 use hir_expand::name::Name;
 use ra_syntax::ast::RangeOp;
 
 use crate::{
-    autoderef, method_resolution, op,
+    autoderef,
+    const_eval::eval_expr,
+    diagnostics::PrivateItem,
+    method_resolution, op,
     traits::InEnvironment,
     utils::{generics, variant_data, Generics},
-    ApplicationTy, Binders, CallableDef, InferTy, IntTy, Mutability, Obligation, Substs, TraitRef,
-    Ty, TypeCtor, Uncertain,
+    ApplicationTy, Binders, BoundVar, CallableDef, Canonical, DebruijnIndex, GenericPredicate,
+    InferTy, IntTy, Mutability, Obligation, Substs, TraitRef, Ty, TypeCtor, TypeWalk, Uncertain,
 };
 
 use super::{BindingMode, Expectation, InferenceContext, InferenceDiagnostic, TypeMismatch};
@@ -40,18 +44,20 @@ impl<'a> InferenceContext<'a> {
     /// Return the type after possible coercion.
     pub(super) fn infer_expr_coerce(&mut self, expr: ExprId, expected: &Expectation) -> Ty {
         let ty = self.infer_expr_inner(expr, &expected);
-        let ty = if !self.coerce(&ty, &expected.coercion_target()) {
-            self.result
-                .type_mismatches
-                .insert(expr, TypeMismatch { expected: expected.ty.clone(), actual: ty.clone() });
-            // Return actual type when type mismatch.
-            // This is needed for diagnostic when return type mismatch.
-            ty
-        } else if expected.coercion_target() == &Ty::Unknown {
-            ty
-        } else {
-            expected.ty.clone()
+        let adjustments = match self.coerce_adjusted(&ty, &expected.coercion_target()) {
+            Some(it) => it,
+            None => {
+                self.result.type_mismatches.insert(
+                    expr,
+                    TypeMismatch { expected: expected.ty.clone(), actual: ty.clone() },
+                );
+                // Return actual type when type mismatch.
+                // This is needed for diagnostic when return type mismatch.
+                return self.resolve_ty_as_possible(ty);
+            }
         };
+        self.write_expr_adjustments(expr, adjustments);
+        let ty = if expected.coercion_target() == &Ty::Unknown { ty } else { expected.ty.clone() };
 
         self.resolve_ty_as_possible(ty)
     }
@@ -64,9 +70,10 @@ impl<'a> InferenceContext<'a> {
                 // if let is desugared to match, so this is always simple if
                 self.infer_expr(*condition, &Expectation::has_type(Ty::simple(TypeCtor::Bool)));
 
-                let then_ty = self.infer_expr_inner(*then_branch, &expected);
+                let branch_expected = self.branch_expectation(expected);
+                let then_ty = self.infer_expr_inner(*then_branch, &branch_expected);
                 let else_ty = match else_branch {
-                    Some(else_branch) => self.infer_expr_inner(*else_branch, &expected),
+                    Some(else_branch) => self.infer_expr_inner(*else_branch, &branch_expected),
                     None => Ty::unit(),
                 };
 
@@ -78,13 +85,24 @@ impl<'a> InferenceContext<'a> {
                 // FIXME should be std::result::Result<{inner}, _>
                 Ty::Unknown
             }
+            Expr::Async { body } => {
+                let _inner = self.infer_expr(*body, &Expectation::none());
+                // FIXME should be impl std::future::Future<Output = {inner}>
+                Ty::Unknown
+            }
+            Expr::Let { pat, expr } => {
+                let input_ty = self.infer_expr(*expr, &Expectation::none());
+                self.infer_pat(*pat, &input_ty, BindingMode::default());
+                Ty::simple(TypeCtor::Bool)
+            }
             Expr::Loop { body } => {
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
                 // FIXME handle break with value
                 Ty::simple(TypeCtor::Never)
             }
             Expr::While { condition, body } => {
-                // while let is desugared to a match loop, so this is always simple while
+                // while let is desugared to a match loop, so the condition is either a
+                // boolean or a let chain
                 self.infer_expr(*condition, &Expectation::has_type(Ty::simple(TypeCtor::Bool)));
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
                 Ty::unit()
@@ -103,10 +121,17 @@ impl<'a> InferenceContext<'a> {
                 assert_eq!(args.len(), arg_types.len());
 
                 let mut sig_tys = Vec::new();
+                let (expected_params, expected_ret_ty) =
+                    match self.deduce_closure_sig(&expected.ty, args.len()) {
+                        Some((params, ret_ty)) => (Some(params), ret_ty),
+                        None => (None, None),
+                    };
 
-                for (arg_pat, arg_type) in args.iter().zip(arg_types.iter()) {
+                for (idx, (arg_pat, arg_type)) in args.iter().zip(arg_types.iter()).enumerate() {
                     let expected = if let Some(type_ref) = arg_type {
                         self.make_ty(type_ref)
+                    } else if let Some(params) = &expected_params {
+                        params[idx].clone()
                     } else {
                         Ty::Unknown
                     };
@@ -115,9 +140,10 @@ impl<'a> InferenceContext<'a> {
                 }
 
                 // add return type
-                let ret_ty = match ret_type {
-                    Some(type_ref) => self.make_ty(type_ref),
-                    None => self.table.new_type_var(),
+                let ret_ty = match (ret_type, expected_ret_ty) {
+                    (Some(type_ref), _) => self.make_ty(type_ref),
+                    (None, Some(ty)) => ty,
+                    (None, None) => self.table.new_type_var(),
                 };
                 sig_tys.push(ret_ty.clone());
                 let sig_ty = Ty::apply(
@@ -165,6 +191,7 @@ impl<'a> InferenceContext<'a> {
                     self.table.new_type_var()
                 };
 
+                let branch_expected = self.branch_expectation(expected);
                 for arm in arms {
                     let _pat_ty = self.infer_pat(arm.pat, &input_ty, BindingMode::default());
                     if let Some(guard_expr) = arm.guard {
@@ -174,7 +201,7 @@ impl<'a> InferenceContext<'a> {
                         );
                     }
 
-                    let arm_ty = self.infer_expr_inner(arm.expr, &expected);
+                    let arm_ty = self.infer_expr_inner(arm.expr, &branch_expected);
                     result_ty = self.coerce_merge_branch(&result_ty, &arm_ty);
                 }
 
@@ -183,7 +210,27 @@ impl<'a> InferenceContext<'a> {
             Expr::Path(p) => {
                 // FIXME this could be more efficient...
                 let resolver = resolver_for_expr(self.db.upcast(), self.owner, tgt_expr);
-                self.infer_path(&resolver, p, tgt_expr.into()).unwrap_or(Ty::Unknown)
+                match self.infer_path(&resolver, p, tgt_expr.into()) {
+                    Some(ty) => ty,
+                    None => {
+                        // Only single names are reported, as qualified paths
+                        // may not resolve because of gaps in name resolution.
+                        // Names might also come from unresolved imports or
+                        // macro calls, so such scopes are skipped.
+                        let path = p.mod_path();
+                        if p.type_anchor().is_none()
+                            && path.kind == PathKind::Plain
+                            && path.segments.len() == 1
+                            && !resolver.has_unresolved_names()
+                            && resolver.resolve_path_in_value_ns(self.db.upcast(), path).is_none()
+                        {
+                            self.push_diagnostic(InferenceDiagnostic::UnresolvedPath {
+                                expr: tgt_expr,
+                            });
+                        }
+                        Ty::Unknown
+                    }
+                }
             }
             Expr::Continue => Ty::simple(TypeCtor::Never),
             Expr::Break { expr } => {
@@ -229,6 +276,7 @@ impl<'a> InferenceContext<'a> {
                         });
                     if let Some(field_def) = field_def {
                         self.result.record_field_resolutions.insert(field.expr, field_def);
+                        self.check_visibility(tgt_expr, PrivateItem::Field(field_def));
                     }
                     let field_ty = field_def
                         .map_or(Ty::Unknown, |it| field_types[it.local_id].clone().subst(&substs));
@@ -256,16 +304,11 @@ impl<'a> InferenceContext<'a> {
                             .as_tuple_index()
                             .and_then(|idx| a_ty.parameters.0.get(idx).cloned()),
                         TypeCtor::Adt(AdtId::StructId(s)) => {
-                            self.db.struct_data(s).variant_data.field(name).map(|local_id| {
-                                let field = StructFieldId { parent: s.into(), local_id };
-                                self.write_field_resolution(tgt_expr, field);
-                                self.db.field_types(s.into())[field.local_id]
-                                    .clone()
-                                    .subst(&a_ty.parameters)
-                            })
+                            self.infer_field_access(tgt_expr, s.into(), name, &a_ty.parameters)
+                        }
+                        TypeCtor::Adt(AdtId::UnionId(u)) => {
+                            self.infer_field_access(tgt_expr, u.into(), name, &a_ty.parameters)
                         }
-                        // FIXME:
-                        TypeCtor::Adt(AdtId::UnionId(_)) => None,
                         _ => None,
                     },
                     _ => None,
@@ -468,17 +511,16 @@ impl<'a> InferenceContext<'a> {
             }
             Expr::Array(array) => {
                 let elem_ty = match &expected.ty {
-                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => {
-                        st.as_single().clone()
-                    }
+                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => st[0].clone(),
                     _ => self.table.new_type_var(),
                 };
 
-                match array {
+                let len = match array {
                     Array::ElementList(items) => {
                         for expr in items.iter() {
                             self.infer_expr_coerce(*expr, &Expectation::has_type(elem_ty.clone()));
                         }
+                        Some(items.len() as i128)
                     }
                     Array::Repeat { initializer, repeat } => {
                         self.infer_expr_coerce(
@@ -491,10 +533,15 @@ impl<'a> InferenceContext<'a> {
                                 IntTy::usize(),
                             )))),
                         );
+                        eval_expr(self.db, self.owner, &self.body, *repeat)
                     }
-                }
+                };
+                let len_ty = match len.map(u128::try_from) {
+                    Some(Ok(len)) => Ty::simple(TypeCtor::Const(len)),
+                    _ => self.table.new_type_var(),
+                };
 
-                Ty::apply_one(TypeCtor::Array, elem_ty)
+                Ty::apply(TypeCtor::Array, Substs(vec![elem_ty, len_ty].into()))
             }
             Expr::Literal(lit) => match lit {
                 Literal::Bool(..) => Ty::simple(TypeCtor::Bool),
@@ -518,6 +565,21 @@ impl<'a> InferenceContext<'a> {
         ty
     }
 
+    /// Resolves the field `name` of a struct or union and returns its type.
+    fn infer_field_access(
+        &mut self,
+        tgt_expr: ExprId,
+        variant: VariantId,
+        name: &Name,
+        parameters: &Substs,
+    ) -> Option<Ty> {
+        let local_id = variant_data(self.db.upcast(), variant).field(name)?;
+        let field = StructFieldId { parent: variant, local_id };
+        self.write_field_resolution(tgt_expr, field);
+        self.check_visibility(tgt_expr, PrivateItem::Field(field));
+        Some(self.db.field_types(variant)[local_id].clone().subst(parameters))
+    }
+
     fn infer_block(
         &mut self,
         statements: &[Statement],
@@ -527,7 +589,7 @@ impl<'a> InferenceContext<'a> {
         let mut diverges = false;
         for stmt in statements {
             match stmt {
-                Statement::Let { pat, type_ref, initializer } => {
+                Statement::Let { pat, type_ref, initializer, else_branch } => {
                     let decl_ty =
                         type_ref.as_ref().map(|tr| self.make_ty(tr)).unwrap_or(Ty::Unknown);
 
@@ -542,6 +604,13 @@ impl<'a> InferenceContext<'a> {
                         }
                     }
 
+                    if let Some(expr) = else_branch {
+                        self.infer_expr_coerce(
+                            *expr,
+                            &Expectation::has_type(Ty::simple(TypeCtor::Never)),
+                        );
+                    }
+
                     let ty = self.resolve_ty_as_possible(ty);
                     self.infer_pat(*pat, &ty, BindingMode::default());
                 }
@@ -556,7 +625,10 @@ impl<'a> InferenceContext<'a> {
         let ty = if let Some(expr) = tail {
             self.infer_expr_coerce(expr, expected)
         } else {
-            self.coerce(&Ty::unit(), expected.coercion_target());
+            // A diverging block has type `!`, which must not constrain the
+            // expected type to `()`.
+            let ty = if diverges { Ty::simple(TypeCtor::Never) } else { Ty::unit() };
+            self.coerce(&ty, expected.coercion_target());
             Ty::unit()
         };
         if diverges {
@@ -589,13 +661,27 @@ impl<'a> InferenceContext<'a> {
                 method_name,
             )
         });
-        let (derefed_receiver_ty, method_ty, def_generics) = match resolved {
-            Some((ty, func)) => {
-                let ty = canonicalized_receiver.decanonicalize_ty(ty);
+        let (derefed_receiver_ty, actual_receiver_ty, method_ty, def_generics) = match resolved {
+            Some(candidate) => {
+                let self_ty = canonicalized_receiver.decanonicalize_ty(candidate.self_ty);
+                let adjusted_receiver_ty =
+                    canonicalized_receiver.decanonicalize_ty(candidate.receiver_ty);
+                let func = candidate.func;
                 self.write_method_resolution(tgt_expr, func);
-                (ty, self.db.value_ty(func.into()), Some(generics(self.db.upcast(), func.into())))
+                self.check_visibility(tgt_expr, PrivateItem::Function(func));
+                self.write_method_receiver_adjustments(tgt_expr, candidate.adjustments);
+                self.write_expr_adjustments(receiver, candidate.adjustments.to_adjustments());
+                (
+                    self_ty,
+                    adjusted_receiver_ty,
+                    self.db.value_ty(func.into()),
+                    Some(generics(self.db.upcast(), func.into())),
+                )
+            }
+            None => {
+                self.report_unresolved_method(tgt_expr, &canonicalized_receiver.value);
+                (receiver_ty.clone(), receiver_ty, Binders::new(0, Ty::Unknown), None)
             }
-            None => (receiver_ty, Binders::new(0, Ty::Unknown), None),
         };
         let substs = self.substs_for_method_call(def_generics, generic_args, &derefed_receiver_ty);
         let method_ty = method_ty.subst(&substs);
@@ -611,18 +697,29 @@ impl<'a> InferenceContext<'a> {
             }
             None => (Ty::Unknown, Vec::new(), Ty::Unknown),
         };
-        // Apply autoref so the below unification works correctly
-        // FIXME: return correct autorefs from lookup_method
-        let actual_receiver_ty = match expected_receiver_ty.as_reference() {
-            Some((_, mutability)) => Ty::apply_one(TypeCtor::Ref(mutability), derefed_receiver_ty),
-            _ => derefed_receiver_ty,
-        };
         self.unify(&expected_receiver_ty, &actual_receiver_ty);
 
         self.check_call_arguments(args, &param_tys);
         self.normalize_associated_types_in(ret_ty)
     }
 
+    /// Records that the method call could not be resolved. Similarly named
+    /// methods and traits providing the method are looked up by the IDE.
+    ///
+    /// As method resolution is not complete yet, nothing is recorded if the
+    /// receiver type isn't fully known.
+    fn report_unresolved_method(&mut self, tgt_expr: ExprId, receiver_ty: &Canonical<Ty>) {
+        let mut is_known = true;
+        receiver_ty.value.walk(&mut |ty| {
+            if let Ty::Unknown | Ty::Bound(_) = ty {
+                is_known = false;
+            }
+        });
+        if is_known {
+            self.push_diagnostic(InferenceDiagnostic::UnresolvedMethodCall { expr: tgt_expr });
+        }
+    }
+
     fn check_call_arguments(&mut self, args: &[ExprId], param_tys: &[Ty]) {
         // Quoting https://github.com/rust-lang/rust/blob/6ef275e6c3cb1384ec78128eceeb4963ff788dca/src/librustc_typeck/check/mod.rs#L3325 --
         // We do this in a pretty awful way: first we type-check any arguments
@@ -677,6 +774,10 @@ impl<'a> InferenceContext<'a> {
                         let ty = self.make_ty(type_ref);
                         substs.push(ty);
                     }
+                    GenericArg::Const(const_ref) => {
+                        let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
+                        substs.push(Ty::from_hir_const(&ctx, const_ref));
+                    }
                 }
             }
         };
@@ -688,6 +789,102 @@ impl<'a> InferenceContext<'a> {
         Substs(substs.into())
     }
 
+    /// Deduces the parameter and return types of a closure from the type it
+    /// is expected to have, so that they are known before inferring its body.
+    /// Besides function pointers, this looks at the `Fn*` bounds of trait
+    /// objects and at the pending obligations of type variables, like the one
+    /// for an argument of type `impl Fn(A) -> B`.
+    /// The expectation for the branches of an `if` or a `match`. A type
+    /// variable without any bounds carries no information, and unifying it
+    /// with the first branch would keep the branches from being coerced to a
+    /// common type, like a function pointer for distinct function items.
+    fn branch_expectation(&mut self, expected: &Expectation) -> Expectation {
+        let ty = self.resolve_ty_as_possible(expected.ty.clone());
+        if let Ty::Infer(InferTy::TypeVar(_)) = ty {
+            let table = &mut self.table;
+            let is_bounded = self.obligations.iter().any(|obligation| match obligation {
+                Obligation::Trait(trait_ref) => table.is_same_var(trait_ref.self_ty(), &ty),
+                Obligation::Projection(_) => false,
+            });
+            if !is_bounded {
+                return Expectation::none();
+            }
+        }
+        expected.clone()
+    }
+
+    fn deduce_closure_sig(
+        &mut self,
+        expected: &Ty,
+        num_args: usize,
+    ) -> Option<(Vec<Ty>, Option<Ty>)> {
+        let expected = self.resolve_ty_as_possible(expected.clone());
+        let (params, ret_ty) = match &expected {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::FnPtr { .. }, parameters }) => {
+                let (ret_ty, params) = parameters.split_last()?;
+                (params.to_vec(), Some(ret_ty.clone()))
+            }
+            Ty::Dyn(predicates) | Ty::Opaque(predicates) => {
+                let self_ty = Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0));
+                self.fn_sig_from_predicates(predicates.iter().cloned(), |_, ty| *ty == self_ty)?
+            }
+            Ty::Infer(InferTy::TypeVar(_)) => {
+                let predicates: Vec<_> = self
+                    .obligations
+                    .iter()
+                    .map(|obligation| match obligation {
+                        Obligation::Trait(trait_ref) => {
+                            GenericPredicate::Implemented(trait_ref.clone())
+                        }
+                        Obligation::Projection(projection) => {
+                            GenericPredicate::Projection(projection.clone())
+                        }
+                    })
+                    .collect();
+                self.fn_sig_from_predicates(predicates.into_iter(), |ctx, ty| {
+                    ctx.table.is_same_var(ty, &expected)
+                })?
+            }
+            _ => return None,
+        };
+        if params.len() != num_args {
+            return None;
+        }
+        Some((params, ret_ty))
+    }
+
+    fn fn_sig_from_predicates(
+        &mut self,
+        predicates: impl Iterator<Item = GenericPredicate>,
+        is_self: impl Fn(&mut Self, &Ty) -> bool,
+    ) -> Option<(Vec<Ty>, Option<Ty>)> {
+        let fn_once_output = self.resolve_fn_once_output();
+        let mut params = None;
+        let mut ret_ty = None;
+        for predicate in predicates {
+            match predicate {
+                GenericPredicate::Implemented(trait_ref) if self.is_fn_trait(trait_ref.trait_) => {
+                    if params.is_none() && is_self(self, trait_ref.self_ty()) {
+                        params = trait_ref
+                            .substs
+                            .get(1)
+                            .and_then(|it| it.as_tuple())
+                            .map(|it| it.to_vec());
+                    }
+                }
+                GenericPredicate::Projection(projection)
+                    if Some(projection.projection_ty.associated_ty) == fn_once_output =>
+                {
+                    if is_self(self, &projection.projection_ty.parameters[0]) {
+                        ret_ty = Some(projection.ty);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some((params?, ret_ty))
+    }
+
     fn register_obligations_for_call(&mut self, callable_ty: &Ty) {
         if let Ty::Apply(a_ty) = callable_ty {
             if let TypeCtor::FnDef(def) = a_ty.ctor {
@@ -92,7 +92,7 @@ impl<'a> InferenceContext<'a> {
             | Pat::Range { .. }
             | Pat::Slice { .. } => true,
             // FIXME: Path/Lit might actually evaluate to ref, but inference is unimplemented.
-            Pat::Path(..) | Pat::Lit(..) => true,
+            Pat::Path(..) | Pat::Lit(..) | Pat::ConstBlock(..) => true,
             Pat::Wild | Pat::Bind { .. } | Pat::Ref { .. } | Pat::Missing => false,
         };
         if is_non_ref_pat {
@@ -172,6 +172,7 @@ impl<'a> InferenceContext<'a> {
                 } else {
                     BindingMode::convert(*mode)
                 };
+                self.result.pat_binding_modes.insert(pat, mode);
                 let inner_ty = if let Some(subpat) = subpat {
                     self.infer_pat(*subpat, expected, default_bm)
                 } else {
@@ -190,23 +191,32 @@ impl<'a> InferenceContext<'a> {
                 return inner_ty;
             }
             Pat::Slice { prefix, slice: _slice, suffix } => {
-                let (container_ty, elem_ty) = match &expected {
-                    ty_app!(TypeCtor::Array, st) => (TypeCtor::Array, st.as_single().clone()),
-                    ty_app!(TypeCtor::Slice, st) => (TypeCtor::Slice, st.as_single().clone()),
-                    _ => (TypeCtor::Slice, Ty::Unknown),
+                let elem_ty = match &expected {
+                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => st[0].clone(),
+                    _ => Ty::Unknown,
                 };
 
                 for pat_id in prefix.iter().chain(suffix) {
                     self.infer_pat(*pat_id, &elem_ty, default_bm);
                 }
 
-                Ty::apply_one(container_ty, elem_ty)
+                match &expected {
+                    ty_app!(TypeCtor::Array, st) => {
+                        Ty::apply(TypeCtor::Array, Substs(vec![elem_ty, st[1].clone()].into()))
+                    }
+                    _ => Ty::apply_one(TypeCtor::Slice, elem_ty),
+                }
             }
             Pat::Wild => expected.clone(),
-            Pat::Range { start, end } => {
-                let start_ty = self.infer_expr(*start, &Expectation::has_type(expected.clone()));
-                let end_ty = self.infer_expr(*end, &Expectation::has_type(start_ty));
-                end_ty
+            Pat::Range { start, end, .. } => {
+                let mut ty = expected.clone();
+                for bound in start.iter().chain(end.iter()) {
+                    ty = self.infer_expr(*bound, &Expectation::has_type(ty));
+                }
+                ty
+            }
+            Pat::ConstBlock(expr) => {
+                self.infer_expr(*expr, &Expectation::has_type(expected.clone()))
             }
             Pat::Lit(expr) => self.infer_expr(*expr, &Expectation::has_type(expected.clone())),
             Pat::Missing => Ty::Unknown,
@@ -9,7 +9,7 @@ use hir_def::{
 };
 use hir_expand::name::Name;
 
-use crate::{method_resolution, Substs, Ty, ValueTyDefId};
+use crate::{diagnostics::PrivateItem, method_resolution, Substs, Ty, ValueTyDefId};
 
 use super::{ExprOrPatId, InferenceContext, TraitRef};
 
@@ -64,7 +64,12 @@ impl<'a> InferenceContext<'a> {
                 let ty = self.resolve_ty_as_possible(ty);
                 return Some(ty);
             }
-            ValueNs::FunctionId(it) => it.into(),
+            ValueNs::FunctionId(it) => {
+                if let ExprOrPatId::ExprId(expr) = id {
+                    self.check_visibility(expr, PrivateItem::Function(it));
+                }
+                it.into()
+            }
             ValueNs::ConstId(it) => it.into(),
             ValueNs::StaticId(it) => it.into(),
             ValueNs::StructId(it) => {
@@ -77,6 +82,14 @@ impl<'a> InferenceContext<'a> {
 
                 it.into()
             }
+            ValueNs::ConstParam(it) => {
+                let params = self.db.generic_params(it.parent);
+                let ty = match &params.types[it.local_id].const_ty {
+                    Some(type_ref) => self.make_ty(type_ref),
+                    None => Ty::Unknown,
+                };
+                return Some(ty);
+            }
         };
 
         let ty = self.db.value_ty(typable);
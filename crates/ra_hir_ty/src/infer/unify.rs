@@ -8,7 +8,8 @@ use test_utils::tested_by;
 
 use super::{InferenceContext, Obligation};
 use crate::{
-    BoundVar, Canonical, DebruijnIndex, InEnvironment, InferTy, Substs, Ty, TypeCtor, TypeWalk,
+    BoundVar, Canonical, DebruijnIndex, GenericPredicate, InEnvironment, InferTy, Substs, Ty,
+    TypeCtor, TypeWalk,
 };
 
 impl<'a> InferenceContext<'a> {
@@ -166,6 +167,15 @@ pub fn unify(ty1: &Canonical<Ty>, ty2: &Canonical<Ty>) -> Option<Substs> {
     )
 }
 
+/// Checks whether the two types can be made equal. The canonical variables are
+/// shared between both types.
+pub fn could_unify(tys: &Canonical<(Ty, Ty)>) -> bool {
+    let (ty1, ty2) = &tys.value;
+    let num_vars = tys.num_vars;
+    unify(&Canonical { value: ty1.clone(), num_vars }, &Canonical { value: ty2.clone(), num_vars })
+        .is_some()
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct InferenceTable {
     pub(super) var_unification_table: InPlaceUnificationTable<TypeVarId>,
@@ -210,6 +220,17 @@ impl InferenceTable {
         substs1.0.iter().zip(substs2.0.iter()).all(|(t1, t2)| self.unify_inner(t1, t2, depth))
     }
 
+    /// Whether `ty1` and `ty2` are type variables which have been unified with
+    /// each other.
+    pub fn is_same_var(&mut self, ty1: &Ty, ty2: &Ty) -> bool {
+        match (ty1, ty2) {
+            (Ty::Infer(tv1), Ty::Infer(tv2)) => {
+                self.var_unification_table.unioned(tv1.to_inner(), tv2.to_inner())
+            }
+            _ => false,
+        }
+    }
+
     fn unify_inner(&mut self, ty1: &Ty, ty2: &Ty, depth: usize) -> bool {
         if depth > 1000 {
             // prevent stackoverflows
@@ -225,10 +246,43 @@ impl InferenceTable {
             (Ty::Apply(a_ty1), Ty::Apply(a_ty2)) if a_ty1.ctor == a_ty2.ctor => {
                 self.unify_substs(&a_ty1.parameters, &a_ty2.parameters, depth + 1)
             }
+            (Ty::Dyn(preds1), Ty::Dyn(preds2)) | (Ty::Opaque(preds1), Ty::Opaque(preds2))
+                if preds1.len() == preds2.len() =>
+            {
+                preds1
+                    .iter()
+                    .zip(preds2.iter())
+                    .all(|(pred1, pred2)| self.unify_preds(pred1, pred2, depth + 1))
+            }
             _ => self.unify_inner_trivial(&ty1, &ty2),
         }
     }
 
+    fn unify_preds(
+        &mut self,
+        pred1: &GenericPredicate,
+        pred2: &GenericPredicate,
+        depth: usize,
+    ) -> bool {
+        match (pred1, pred2) {
+            (GenericPredicate::Implemented(tr1), GenericPredicate::Implemented(tr2))
+                if tr1.trait_ == tr2.trait_ =>
+            {
+                self.unify_substs(&tr1.substs, &tr2.substs, depth + 1)
+            }
+            (GenericPredicate::Projection(proj1), GenericPredicate::Projection(proj2))
+                if proj1.projection_ty.associated_ty == proj2.projection_ty.associated_ty =>
+            {
+                self.unify_substs(
+                    &proj1.projection_ty.parameters,
+                    &proj2.projection_ty.parameters,
+                    depth + 1,
+                ) && self.unify_inner(&proj1.ty, &proj2.ty, depth + 1)
+            }
+            _ => false,
+        }
+    }
+
     pub(super) fn unify_inner_trivial(&mut self, ty1: &Ty, ty2: &Ty) -> bool {
         match (ty1, ty2) {
             (Ty::Unknown, _) | (_, Ty::Unknown) => true,
@@ -31,6 +31,7 @@ pub mod traits;
 pub mod method_resolution;
 mod op;
 mod lower;
+mod const_eval;
 pub(crate) mod infer;
 pub mod display;
 pub(crate) mod utils;
@@ -63,7 +64,7 @@ use crate::{
 use display::HirDisplay;
 
 pub use autoderef::autoderef;
-pub use infer::{InferTy, InferenceResult};
+pub use infer::{could_unify, Adjustment, BindingMode, InferTy, InferenceResult};
 pub use lower::CallableDef;
 pub use lower::{
     callable_item_sig, ImplTraitLoweringMode, TyDefId, TyLoweringContext, ValueTyDefId,
@@ -100,6 +101,9 @@ pub enum TypeCtor {
     Slice,
 
     /// An array with the given length. Written as `[T; n]`.
+    ///
+    /// The length is stored in the second type parameter, as a `Const` or
+    /// `Unknown` if it couldn't be evaluated.
     Array,
 
     /// A raw pointer. Written as `*mut T` or `*const T`
@@ -150,6 +154,11 @@ pub enum TypeCtor {
     /// The closure signature is stored in a `FnPtr` type in the first type
     /// parameter.
     Closure { def: DefWithBodyId, expr: ExprId },
+
+    /// A known value of a const parameter, like the `3` in `Foo<3>`. Const
+    /// arguments are stored in `Substs` like types, so that they are unified
+    /// (by value) like any other type constructor.
+    Const(u128),
 }
 
 /// This exists just for Chalk, because Chalk just has a single `StructId` where
@@ -167,13 +176,14 @@ impl TypeCtor {
             | TypeCtor::Int(_)
             | TypeCtor::Float(_)
             | TypeCtor::Str
-            | TypeCtor::Never => 0,
+            | TypeCtor::Never
+            | TypeCtor::Const(_) => 0,
             TypeCtor::Slice
-            | TypeCtor::Array
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::Closure { .. } // 1 param representing the signature of the closure
             => 1,
+            TypeCtor::Array => 2,
             TypeCtor::Adt(adt) => {
                 let generic_params = generics(db.upcast(), adt.into());
                 generic_params.len()
@@ -204,7 +214,8 @@ impl TypeCtor {
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
-            | TypeCtor::Tuple { .. } => None,
+            | TypeCtor::Tuple { .. }
+            | TypeCtor::Const(_) => None,
             // Closure's krate is irrelevant for coherence I would think?
             TypeCtor::Closure { .. } => None,
             TypeCtor::Adt(adt) => Some(adt.module(db.upcast()).krate),
@@ -229,7 +240,8 @@ impl TypeCtor {
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
             | TypeCtor::Tuple { .. }
-            | TypeCtor::Closure { .. } => None,
+            | TypeCtor::Closure { .. }
+            | TypeCtor::Const(_) => None,
             TypeCtor::Adt(adt) => Some(adt.into()),
             TypeCtor::FnDef(callable) => Some(callable.into()),
             TypeCtor::AssociatedType(type_alias) => Some(type_alias.into()),
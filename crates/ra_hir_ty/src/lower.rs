@@ -5,6 +5,7 @@
 //!  - Building the type for an item: This happens through the `type_for_def` query.
 //!
 //! This usually involves resolving names, collecting generic arguments etc.
+use std::convert::TryFrom;
 use std::iter;
 use std::sync::Arc;
 
@@ -13,21 +14,22 @@ use hir_def::{
     builtin_type::BuiltinType,
     generics::{TypeParamProvenance, WherePredicate, WherePredicateTarget},
     path::{GenericArg, Path, PathSegment, PathSegments},
-    resolver::{HasResolver, Resolver, TypeNs},
-    type_ref::{TypeBound, TypeRef},
+    resolver::{HasResolver, Resolver, TypeNs, ValueNs},
+    type_ref::{ConstRef, TypeBound, TypeRef},
     AdtId, AssocContainerId, ConstId, EnumId, EnumVariantId, FunctionId, GenericDefId, HasModule,
     ImplId, LocalStructFieldId, Lookup, StaticId, StructId, TraitId, TypeAliasId, TypeParamId,
     UnionId, VariantId,
 };
 use ra_arena::map::ArenaMap;
 use ra_db::CrateId;
+use smallvec::{smallvec, SmallVec};
 
 use crate::{
+    const_eval::eval_const_ref,
     db::HirDatabase,
     primitive::{FloatTy, IntTy},
     utils::{
-        all_super_traits, associated_type_by_name_including_super_traits, generics, make_mut_slice,
-        variant_data,
+        associated_type_by_name_including_super_traits, generics, make_mut_slice, variant_data,
     },
     Binders, BoundVar, DebruijnIndex, FnSig, GenericPredicate, PolyFnSig, ProjectionPredicate,
     ProjectionTy, Substs, TraitEnvironment, TraitRef, Ty, TypeCtor,
@@ -93,6 +95,22 @@ impl Ty {
     pub fn from_hir(ctx: &TyLoweringContext<'_>, type_ref: &TypeRef) -> Self {
         Ty::from_hir_ext(ctx, type_ref).0
     }
+    /// Lowers a const generic argument or an array length. Values which can't
+    /// be evaluated become `Ty::Unknown`, so that they unify with anything.
+    pub fn from_hir_const(ctx: &TyLoweringContext<'_>, const_ref: &ConstRef) -> Self {
+        if let ConstRef::Path(path) = const_ref {
+            // Const parameters are lowered like type parameters.
+            let resolved =
+                ctx.resolver.resolve_path_in_value_ns_fully(ctx.db.upcast(), path.mod_path());
+            if let Some(ValueNs::ConstParam(_)) = resolved {
+                return Ty::from_hir_path(ctx, path).0;
+            }
+        }
+        match eval_const_ref(ctx.db, ctx.resolver, const_ref).map(u128::try_from) {
+            Some(Ok(value)) => Ty::simple(TypeCtor::Const(value)),
+            _ => Ty::Unknown,
+        }
+    }
     pub fn from_hir_ext(ctx: &TyLoweringContext<'_>, type_ref: &TypeRef) -> (Self, Option<TypeNs>) {
         let mut res = None;
         let ty = match type_ref {
@@ -113,9 +131,10 @@ impl Ty {
                 let inner_ty = Ty::from_hir(ctx, inner);
                 Ty::apply_one(TypeCtor::RawPtr(*mutability), inner_ty)
             }
-            TypeRef::Array(inner) => {
+            TypeRef::Array(inner, len) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
-                Ty::apply_one(TypeCtor::Array, inner_ty)
+                let len_ty = Ty::from_hir_const(ctx, len);
+                Ty::apply(TypeCtor::Array, Substs(vec![inner_ty, len_ty].into()))
             }
             TypeRef::Slice(inner) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
@@ -370,7 +389,7 @@ impl Ty {
             }
             _ => return Ty::Unknown,
         };
-        let traits = traits_from_env.into_iter().flat_map(|t| all_super_traits(ctx.db.upcast(), t));
+        let traits = traits_from_env.into_iter().flat_map(|t| ctx.db.all_super_traits(t).to_vec());
         for t in traits {
             if let Some(associated_ty) = ctx.db.trait_data(t).associated_type_by_name(&segment.name)
             {
@@ -458,6 +477,7 @@ pub(super) fn substs_from_path_segment(
                     let ty = Ty::from_hir(ctx, type_ref);
                     substs.push(ty);
                 }
+                GenericArg::Const(const_ref) => substs.push(Ty::from_hir_const(ctx, const_ref)),
             }
         }
     }
@@ -540,7 +560,7 @@ impl TraitRef {
     ) -> Option<TraitRef> {
         match bound {
             TypeBound::Path(path) => TraitRef::from_path(ctx, path, Some(self_ty)),
-            TypeBound::Error => None,
+            TypeBound::Lifetime | TypeBound::Error => None,
         }
     }
 }
@@ -574,12 +594,17 @@ impl GenericPredicate {
         self_ty: Ty,
     ) -> impl Iterator<Item = GenericPredicate> + 'a {
         let trait_ref = TraitRef::from_type_bound(ctx, bound, self_ty);
-        iter::once(trait_ref.clone().map_or(GenericPredicate::Error, GenericPredicate::Implemented))
-            .chain(
-                trait_ref
-                    .into_iter()
-                    .flat_map(move |tr| assoc_type_bindings_from_type_bound(ctx, bound, tr)),
-            )
+        // Lifetimes are erased, so their bounds always hold.
+        let implemented = match (bound, &trait_ref) {
+            (TypeBound::Lifetime, _) => None,
+            (_, Some(trait_ref)) => Some(GenericPredicate::Implemented(trait_ref.clone())),
+            (_, None) => Some(GenericPredicate::Error),
+        };
+        implemented.into_iter().chain(
+            trait_ref
+                .into_iter()
+                .flat_map(move |tr| assoc_type_bindings_from_type_bound(ctx, bound, tr)),
+        )
     }
 }
 
@@ -590,27 +615,41 @@ fn assoc_type_bindings_from_type_bound<'a>(
 ) -> impl Iterator<Item = GenericPredicate> + 'a {
     let last_segment = match bound {
         TypeBound::Path(path) => path.segments().last(),
-        TypeBound::Error => None,
+        TypeBound::Lifetime | TypeBound::Error => None,
     };
     last_segment
         .into_iter()
         .flat_map(|segment| segment.args_and_bindings.into_iter())
         .flat_map(|args_and_bindings| args_and_bindings.bindings.iter())
-        .map(move |(name, type_ref)| {
+        .flat_map(move |binding| {
             let associated_ty = associated_type_by_name_including_super_traits(
                 ctx.db.upcast(),
                 trait_ref.trait_,
-                &name,
+                &binding.name,
             );
             let associated_ty = match associated_ty {
-                None => return GenericPredicate::Error,
+                None => return smallvec![GenericPredicate::Error],
                 Some(t) => t,
             };
             let projection_ty =
                 ProjectionTy { associated_ty, parameters: trait_ref.substs.clone() };
-            let ty = Ty::from_hir(ctx, type_ref);
-            let projection_predicate = ProjectionPredicate { projection_ty, ty };
-            GenericPredicate::Projection(projection_predicate)
+            let mut preds = SmallVec::<[GenericPredicate; 1]>::with_capacity(
+                binding.type_ref.as_ref().map_or(0, |_| 1) + binding.bounds.len(),
+            );
+            if let Some(type_ref) = &binding.type_ref {
+                let ty = Ty::from_hir(ctx, type_ref);
+                let projection_predicate =
+                    ProjectionPredicate { projection_ty: projection_ty.clone(), ty };
+                preds.push(GenericPredicate::Projection(projection_predicate));
+            }
+            for bound in &binding.bounds {
+                preds.extend(GenericPredicate::from_type_bound(
+                    ctx,
+                    bound,
+                    Ty::Projection(projection_ty.clone()),
+                ));
+            }
+            preds
         })
 }
 
@@ -830,9 +869,8 @@ fn type_for_struct_constructor(db: &dyn HirDatabase, def: StructId) -> Binders<T
 }
 
 fn fn_sig_for_enum_variant_constructor(db: &dyn HirDatabase, def: EnumVariantId) -> PolyFnSig {
-    let enum_data = db.enum_data(def.parent);
-    let var_data = &enum_data.variants[def.local_id];
-    let fields = var_data.variant_data.fields();
+    let var_data = db.enum_variant_data(def);
+    let fields = var_data.fields();
     let resolver = def.parent.resolver(db.upcast());
     let ctx =
         TyLoweringContext::new(db, &resolver).with_type_param_mode(TypeParamLoweringMode::Variable);
@@ -845,8 +883,7 @@ fn fn_sig_for_enum_variant_constructor(db: &dyn HirDatabase, def: EnumVariantId)
 /// Build the type of a tuple enum variant constructor.
 fn type_for_enum_variant_constructor(db: &dyn HirDatabase, def: EnumVariantId) -> Binders<Ty> {
     let enum_data = db.enum_data(def.parent);
-    let var_data = &enum_data.variants[def.local_id].variant_data;
-    if let StructKind::Unit = var_data.kind() {
+    if let StructKind::Unit = enum_data.variants[def.local_id].kind {
         return type_for_adt(db, def.parent.into());
     }
     let generics = generics(db.upcast(), def.parent.into());
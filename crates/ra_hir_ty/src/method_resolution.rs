@@ -19,9 +19,8 @@ use crate::{
     autoderef,
     db::HirDatabase,
     primitive::{FloatBitness, Uncertain},
-    utils::all_super_traits,
-    ApplicationTy, Canonical, DebruijnIndex, InEnvironment, TraitEnvironment, TraitRef, Ty,
-    TypeCtor, TypeWalk,
+    Adjustment, ApplicationTy, Canonical, DebruijnIndex, InEnvironment, TraitEnvironment, TraitRef,
+    Ty, TypeCtor, TypeWalk,
 };
 
 /// This is used as a key for indexing impls.
@@ -137,8 +136,42 @@ impl Ty {
         Some(res)
     }
 }
+/// The adjustments rustc applies to the receiver of a method call: a number of
+/// autoderef steps, an optional array unsizing and an optional autoref.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReceiverAdjustments {
+    pub autoderefs: usize,
+    /// Whether an array was unsized to a slice after the autoderef steps.
+    pub unsize: bool,
+    pub autoref: Option<Mutability>,
+}
+
+impl ReceiverAdjustments {
+    /// The adjustments in the order they are applied to the receiver.
+    pub fn to_adjustments(&self) -> Vec<Adjustment> {
+        let mut res = vec![Adjustment::Deref; self.autoderefs];
+        if let Some(m) = self.autoref {
+            res.push(Adjustment::Borrow(m));
+        }
+        if self.unsize {
+            res.push(Adjustment::Unsize);
+        }
+        res
+    }
+}
+
+/// A method found by `lookup_method`.
+pub(crate) struct MethodCandidate {
+    /// The self type of the impl or trait the method comes from.
+    pub(crate) self_ty: Ty,
+    /// The type of the receiver after the adjustments.
+    pub(crate) receiver_ty: Ty,
+    pub(crate) func: FunctionId,
+    pub(crate) adjustments: ReceiverAdjustments,
+}
+
 /// Look up the method with the given name, returning the actual autoderefed
-/// receiver type (but without autoref applied yet).
+/// receiver type and the adjustments that lead to it.
 pub(crate) fn lookup_method(
     ty: &Canonical<Ty>,
     db: &dyn HirDatabase,
@@ -146,8 +179,8 @@ pub(crate) fn lookup_method(
     krate: CrateId,
     traits_in_scope: &FxHashSet<TraitId>,
     name: &Name,
-) -> Option<(Ty, FunctionId)> {
-    iterate_method_candidates(
+) -> Option<MethodCandidate> {
+    iterate_method_candidates_with_adjustments(
         ty,
         db,
         env,
@@ -155,8 +188,13 @@ pub(crate) fn lookup_method(
         &traits_in_scope,
         Some(name),
         LookupMode::MethodCall,
-        |ty, f| match f {
-            AssocItemId::FunctionId(f) => Some((ty.clone(), f)),
+        |self_ty, receiver_ty, item, adjustments| match item {
+            AssocItemId::FunctionId(func) => Some(MethodCandidate {
+                self_ty: self_ty.clone(),
+                receiver_ty: receiver_ty.clone(),
+                func,
+                adjustments,
+            }),
             _ => None,
         },
     )
@@ -186,13 +224,37 @@ pub fn iterate_method_candidates<T>(
     name: Option<&Name>,
     mode: LookupMode,
     mut callback: impl FnMut(&Ty, AssocItemId) -> Option<T>,
+) -> Option<T> {
+    iterate_method_candidates_with_adjustments(
+        ty,
+        db,
+        env,
+        krate,
+        traits_in_scope,
+        name,
+        mode,
+        |self_ty, _, item, _| callback(self_ty, item),
+    )
+}
+
+/// Like `iterate_method_candidates`, but also passes the receiver type and the
+/// adjustments to get there from `ty` to the callback. For path lookups, the
+/// receiver type is `ty` itself.
+fn iterate_method_candidates_with_adjustments<T>(
+    ty: &Canonical<Ty>,
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    krate: CrateId,
+    traits_in_scope: &FxHashSet<TraitId>,
+    name: Option<&Name>,
+    mode: LookupMode,
+    mut callback: impl FnMut(&Ty, &Ty, AssocItemId, ReceiverAdjustments) -> Option<T>,
 ) -> Option<T> {
     match mode {
         LookupMode::MethodCall => {
             // For method calls, rust first does any number of autoderef, and then one
-            // autoref (i.e. when the method takes &self or &mut self). We just ignore
-            // the autoref currently -- when we find a method matching the given name,
-            // we assume it fits.
+            // autoref (i.e. when the method takes &self or &mut self). At each
+            // step, the receiver is tried by value, then by `&` and `&mut`.
 
             // Also note that when we've got a receiver like &S, even if the method we
             // find in the end takes &self, we still do the autoderef step (just as
@@ -214,6 +276,8 @@ pub fn iterate_method_candidates<T>(
 
             let deref_chain = autoderef_method_receiver(db, krate, ty);
             for i in 0..deref_chain.len() {
+                let unsize = i > 0 && is_array_unsizing(&deref_chain[i - 1], &deref_chain[i]);
+                let autoderefs = if unsize { i - 1 } else { i };
                 if let Some(result) = iterate_method_candidates_with_autoref(
                     &deref_chain[i..],
                     db,
@@ -221,7 +285,10 @@ pub fn iterate_method_candidates<T>(
                     krate,
                     traits_in_scope,
                     name,
-                    &mut callback,
+                    |self_ty, receiver_ty, item, autoref| {
+                        let adjustments = ReceiverAdjustments { autoderefs, unsize, autoref };
+                        callback(self_ty, receiver_ty, item, adjustments)
+                    },
                 ) {
                     return Some(result);
                 }
@@ -237,7 +304,7 @@ pub fn iterate_method_candidates<T>(
                 krate,
                 traits_in_scope,
                 name,
-                &mut callback,
+                |self_ty, item| callback(self_ty, &ty.value, item, ReceiverAdjustments::default()),
             )
         }
     }
@@ -250,7 +317,7 @@ fn iterate_method_candidates_with_autoref<T>(
     krate: CrateId,
     traits_in_scope: &FxHashSet<TraitId>,
     name: Option<&Name>,
-    mut callback: impl FnMut(&Ty, AssocItemId) -> Option<T>,
+    mut callback: impl FnMut(&Ty, &Ty, AssocItemId, Option<Mutability>) -> Option<T>,
 ) -> Option<T> {
     if let Some(result) = iterate_method_candidates_by_receiver(
         &deref_chain[0],
@@ -260,41 +327,27 @@ fn iterate_method_candidates_with_autoref<T>(
         krate,
         &traits_in_scope,
         name,
-        &mut callback,
+        |self_ty, item| callback(self_ty, &deref_chain[0].value, item, None),
     ) {
         return Some(result);
     }
-    let refed = Canonical {
-        num_vars: deref_chain[0].num_vars,
-        value: Ty::apply_one(TypeCtor::Ref(Mutability::Shared), deref_chain[0].value.clone()),
-    };
-    if let Some(result) = iterate_method_candidates_by_receiver(
-        &refed,
-        deref_chain,
-        db,
-        env.clone(),
-        krate,
-        &traits_in_scope,
-        name,
-        &mut callback,
-    ) {
-        return Some(result);
-    }
-    let ref_muted = Canonical {
-        num_vars: deref_chain[0].num_vars,
-        value: Ty::apply_one(TypeCtor::Ref(Mutability::Mut), deref_chain[0].value.clone()),
-    };
-    if let Some(result) = iterate_method_candidates_by_receiver(
-        &ref_muted,
-        deref_chain,
-        db,
-        env,
-        krate,
-        &traits_in_scope,
-        name,
-        &mut callback,
-    ) {
-        return Some(result);
+    for &mutability in &[Mutability::Shared, Mutability::Mut] {
+        let refed = Canonical {
+            num_vars: deref_chain[0].num_vars,
+            value: Ty::apply_one(TypeCtor::Ref(mutability), deref_chain[0].value.clone()),
+        };
+        if let Some(result) = iterate_method_candidates_by_receiver(
+            &refed,
+            deref_chain,
+            db,
+            env.clone(),
+            krate,
+            &traits_in_scope,
+            name,
+            |self_ty, item| callback(self_ty, &refed.value, item, Some(mutability)),
+        ) {
+            return Some(result);
+        }
     }
     None
 }
@@ -373,26 +426,33 @@ fn iterate_trait_method_candidates<T>(
     receiver_ty: Option<&Canonical<Ty>>,
     mut callback: impl FnMut(&Ty, AssocItemId) -> Option<T>,
 ) -> Option<T> {
-    // if ty is `impl Trait` or `dyn Trait`, the trait doesn't need to be in scope
-    let inherent_trait = self_ty.value.inherent_trait().into_iter();
+    // if ty is `impl Trait` or `dyn Trait`, neither the trait nor its super
+    // traits need to be in scope, and they are trivially implemented
+    let inherent_traits = match self_ty.value.inherent_trait() {
+        Some(t) => db.all_super_traits(t).to_vec(),
+        None => Vec::new(),
+    };
     let env_traits = if let Ty::Placeholder(_) = self_ty.value {
         // if we have `T: Trait` in the param env, the trait doesn't need to be in scope
         env.trait_predicates_for_self_ty(&self_ty.value)
             .map(|tr| tr.trait_)
-            .flat_map(|t| all_super_traits(db.upcast(), t))
+            .flat_map(|t| db.all_super_traits(t).to_vec())
             .collect()
     } else {
         Vec::new()
     };
-    let traits =
-        inherent_trait.chain(env_traits.into_iter()).chain(traits_in_scope.iter().copied());
+    let traits = inherent_traits
+        .iter()
+        .copied()
+        .chain(env_traits.into_iter())
+        .chain(traits_in_scope.iter().copied());
     'traits: for t in traits {
         let data = db.trait_data(t);
 
         // we'll be lazy about checking whether the type implements the
         // trait, but if we find out it doesn't, we'll skip the rest of the
         // iteration
-        let mut known_implemented = false;
+        let mut known_implemented = inherent_traits.contains(&t);
         for (_name, item) in data.items.iter() {
             if !is_valid_candidate(db, name, receiver_ty, *item, self_ty) {
                 continue;
@@ -596,6 +656,16 @@ fn generic_implements_goal(
     Canonical { num_vars, value: InEnvironment::new(env, obligation) }
 }
 
+fn is_array_unsizing(from: &Canonical<Ty>, to: &Canonical<Ty>) -> bool {
+    match (&from.value, &to.value) {
+        (
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Array, parameters: from_params }),
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Slice, parameters: to_params }),
+        ) => from_params[0] == to_params[0],
+        _ => false,
+    }
+}
+
 fn autoderef_method_receiver(
     db: &dyn HirDatabase,
     krate: CrateId,
@@ -607,7 +677,7 @@ fn autoderef_method_receiver(
         deref_chain.last().map(|ty| &ty.value)
     {
         let num_vars = deref_chain.last().unwrap().num_vars;
-        let unsized_ty = Ty::apply(TypeCtor::Slice, parameters.clone());
+        let unsized_ty = Ty::apply_one(TypeCtor::Slice, parameters[0].clone());
         deref_chain.push(Canonical { value: unsized_ty, num_vars })
     }
     deref_chain
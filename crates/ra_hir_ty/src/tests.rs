@@ -6,6 +6,7 @@ mod patterns;
 mod traits;
 mod method_resolution;
 mod macros;
+mod lifetimes;
 
 use std::sync::Arc;
 
@@ -27,7 +28,10 @@ use ra_syntax::{
 };
 use stdx::format_to;
 
-use crate::{db::HirDatabase, display::HirDisplay, test_db::TestDB, InferenceResult};
+use crate::{
+    db::HirDatabase, display::HirDisplay, method_resolution::ReceiverAdjustments, test_db::TestDB,
+    Adjustment, InferenceResult,
+};
 
 // These tests compare the inference results for all expressions in a file
 // against snapshots of the expected results using insta. Use cargo-insta to
@@ -56,6 +60,36 @@ fn type_at(content: &str) -> String {
     type_at_pos(&db, file_pos)
 }
 
+fn receiver_adjustments_at(content: &str) -> Option<ReceiverAdjustments> {
+    let (db, pos) = TestDB::with_position(content);
+    let file = db.parse(pos.file_id).ok().unwrap();
+    let call = algo::find_node_at_offset::<ast::MethodCallExpr>(file.syntax(), pos.offset).unwrap();
+    let fn_def = call.syntax().ancestors().find_map(ast::FnDef::cast).unwrap();
+    let module = db.module_for_file(pos.file_id);
+    let func = *module.child_by_source(&db)[keys::FUNCTION]
+        .get(&InFile::new(pos.file_id.into(), fn_def))
+        .unwrap();
+
+    let (_body, source_map) = db.body_with_source_map(func.into());
+    let expr_id = source_map.node_expr(InFile::new(pos.file_id.into(), &ast::Expr::from(call)))?;
+    db.infer(func.into()).method_receiver_adjustments(expr_id)
+}
+
+fn adjustments_at(content: &str) -> Vec<Adjustment> {
+    let (db, pos) = TestDB::with_position(content);
+    let file = db.parse(pos.file_id).ok().unwrap();
+    let expr = algo::find_node_at_offset::<ast::Expr>(file.syntax(), pos.offset).unwrap();
+    let fn_def = expr.syntax().ancestors().find_map(ast::FnDef::cast).unwrap();
+    let module = db.module_for_file(pos.file_id);
+    let func = *module.child_by_source(&db)[keys::FUNCTION]
+        .get(&InFile::new(pos.file_id.into(), fn_def))
+        .unwrap();
+
+    let (_body, source_map) = db.body_with_source_map(func.into());
+    let expr_id = source_map.node_expr(InFile::new(pos.file_id.into(), &expr)).unwrap();
+    db.infer(func.into()).expr_adjustments(expr_id).to_vec()
+}
+
 fn infer(ra_fixture: &str) -> String {
     infer_with_mismatches(ra_fixture, false)
 }
@@ -320,6 +354,515 @@ fn no_such_field_diagnostics() {
     );
 }
 
+#[test]
+fn unresolved_path_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        mod m { pub fn f() {} }
+        fn g() {}
+        fn foo(x: i32) {
+            let y = x;
+            let z = w;
+            g();
+            h();
+            m::f();
+            m::i();
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "w": cannot find value `w` in this scope
+    "h": cannot find value `h` in this scope
+    "###
+    );
+}
+
+#[test]
+fn unresolved_path_diagnostics_with_unresolved_names_in_scope() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /lib.rs
+        mod a {
+            use unknown::*;
+            fn foo() { let x = w; }
+        }
+        mod b {
+            unknown_macro!();
+            fn foo() { let x = w; }
+        }
+        mod c {
+            fn foo() {
+                use unknown::w;
+                let x = w;
+            }
+            fn bar() {
+                fn baz() { let x = w; }
+                unknown_macro!();
+            }
+            fn qux() { let x = w; }
+        }
+        mod d {
+            extern "C" {
+                pub fn w();
+                static W: i32;
+            }
+            fn foo() {
+                w();
+                let x = W;
+            }
+            fn bar() {
+                extern "C" { fn v(); }
+                v();
+            }
+        }
+        mod e {
+            use super::d::*;
+            fn foo() { w(); }
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "w": cannot find value `w` in this scope
+    "###
+    );
+}
+
+#[test]
+fn unresolved_method_call_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        mod m {
+            pub trait Tr { fn by_trait(&self); }
+            pub struct S;
+            impl S {
+                pub fn length(&self) -> usize { 0 }
+                pub fn new() -> S { S }
+            }
+            impl Tr for S { fn by_trait(&self) {} }
+        }
+        fn foo(s: m::S, x: Unknown) {
+            s.length();
+            s.lenght();
+            s.nwe();
+            s.by_trait();
+            s.completely_unknown();
+            x.lenght();
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "s.lenght()": no method named `lenght` found
+    "s.nwe()": no method named `nwe` found
+    "s.by_trait()": no method named `by_trait` found
+    "s.completely_unknown()": no method named `completely_unknown` found
+    "###
+    );
+}
+
+#[test]
+fn private_item_access_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        mod m {
+            pub struct S { pub a: u32, b: u32 }
+            pub struct T(pub u32, u32);
+            pub trait Tr { fn by_trait(&self); }
+            impl S {
+                pub fn new() -> S { S { a: 0, b: 0 } }
+                fn private() {}
+                fn method(&self) {}
+                pub(crate) fn crate_method(&self) {}
+            }
+            impl Tr for S { fn by_trait(&self) {} }
+            fn helper() {}
+            fn inside(s: S) { s.method(); s.b; helper(); }
+        }
+        use m::Tr;
+        fn foo(s: m::S, t: m::T) {
+            m::S::new();
+            m::S::private();
+            s.method();
+            s.crate_method();
+            s.by_trait();
+            s.a;
+            s.b;
+            t.0;
+            t.1;
+            m::helper();
+            m::S { a: 1, b: 2 };
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "m::S::private": function `private` is private
+    "s.method()": function `method` is private
+    "s.b": field `b` is private
+    "t.1": field `1` is private
+    "m::helper": function `helper` is private
+    "m::S { a: 1, b: 2 }": field `b` is private
+    "###
+    );
+}
+
+#[test]
+fn mismatched_arg_count_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        struct S(u32, bool);
+        enum E { V(u32) }
+        impl S {
+            fn method(&self, x: u32) {}
+        }
+        fn f(x: u32) {}
+        extern fn printf(format: *const u8, ...) {}
+        fn foo(s: S, format: *const u8) {
+            f();
+            f(1, 2);
+            f(1);
+            S(1);
+            E::V(1, 2);
+            s.method();
+            s.method(1);
+            printf();
+            printf(format, 1, 2);
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "f()": Expected 1 argument, found 0
+    "f(1, 2)": Expected 1 argument, found 2
+    "S(1)": Expected 2 arguments, found 1
+    "E::V(1, 2)": Expected 1 argument, found 2
+    "s.method()": Expected 1 argument, found 0
+    "printf()": Expected 1 argument, found 0
+    "###
+    );
+}
+
+#[test]
+fn unused_must_use_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /lib.rs
+        #[must_use]
+        struct Token;
+        #[must_use = "the new value is returned"]
+        fn incremented(x: u32) -> u32 { x + 1 }
+        fn token() -> Token { Token }
+        struct S;
+        impl S {
+            #[must_use]
+            fn get(&self) -> u32 { 0 }
+            fn token(&self) -> Token { Token }
+        }
+        fn foo(s: S) -> Token {
+            incremented(1);
+            token();
+            s.get();
+            s.token();
+            let _ = incremented(1);
+            let _t = token();
+            s.token()
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "incremented(1)": unused return value of `incremented` that must be used: the new value is returned
+    "token()": unused `Token` that must be used
+    "s.get()": unused return value of `get` that must be used
+    "s.token()": unused `Token` that must be used
+    "###
+    );
+}
+
+#[test]
+fn use_of_moved_value_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /lib.rs
+        #[lang = "copy"]
+        trait Copy {}
+        struct NotCopy;
+        struct IsCopy;
+        impl Copy for IsCopy {}
+        struct Wrapper<T>(T);
+        impl<T: Copy> Copy for Wrapper<T> {}
+        fn consume<T>(t: T) {}
+
+        fn foo(cond: bool) {
+            let a = NotCopy;
+            consume(a);
+            consume(a);
+
+            let b = IsCopy;
+            consume(b);
+            consume(b);
+
+            let c = NotCopy;
+            let d = c;
+            if cond { consume(c) }
+
+            let mut e = NotCopy;
+            consume(e);
+            e = NotCopy;
+            consume(e);
+
+            let f = NotCopy;
+            if cond { consume(f) }
+            consume(f);
+
+            let g = NotCopy;
+            let ref h = g;
+            consume(g);
+
+            let i = Wrapper(IsCopy);
+            consume(i);
+            consume(i);
+
+            let j = Wrapper(&NotCopy);
+            consume(j);
+            consume(j);
+
+            let k = Wrapper((IsCopy, IsCopy));
+            consume(k);
+            consume(k);
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "a": use of moved value: `a`
+    "c": use of moved value: `c`
+    "###
+    );
+}
+
+#[test]
+fn unsatisfied_trait_bound_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /lib.rs
+        #[lang = "sized"]
+        trait Sized {}
+        trait Greet {}
+        struct Friend;
+        struct Stranger;
+        struct Wrapper<T>(T);
+        impl Greet for Friend {}
+        impl<T: Greet> Greet for Wrapper<T> {}
+        #[lang = "copy"]
+        trait Copy {}
+        struct Holder<T>(T);
+        impl<T: Copy> Greet for Holder<T> {}
+        fn greet<T: Greet>(t: T) {}
+
+        fn foo() {
+            greet(Friend);
+            greet(Stranger);
+            greet(Wrapper(Friend));
+            greet(Wrapper(Stranger));
+            greet(Holder(&Stranger));
+            greet(Holder((1, 2)));
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "greet(Stranger)": the trait bound `Stranger: Greet` is not satisfied
+    "greet(Wrapper(Stranger))": the trait bound `Wrapper<Stranger>: Greet` is not satisfied
+    "###
+    );
+}
+
+#[test]
+fn unsatisfied_trait_bound_diagnostics_qualify_types() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /lib.rs
+        #[lang = "sized"]
+        trait Sized {}
+        trait Greet {}
+        struct Wrapper<T>(T);
+        impl<T: Greet> Greet for Wrapper<T> {}
+        fn greet<T: Greet>(t: T) {}
+        mod people {
+            pub struct Stranger;
+        }
+
+        fn foo() {
+            greet(people::Stranger);
+            greet(Wrapper(people::Stranger));
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "greet(people::Stranger)": the trait bound `people::Stranger: Greet` is not satisfied
+    "greet(Wrapper(people::Stranger))": the trait bound `Wrapper<people::Stranger>: Greet` is not satisfied
+    "###
+    );
+}
+
+#[test]
+fn try_in_non_try_function_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /main.rs crate:main deps:std
+        struct Checked;
+        impl std::ops::Try for Checked {
+            type Ok = ();
+            type Error = ();
+        }
+
+        fn unit(r: Result<u32, ()>) {
+            r?;
+            let f = |r: Result<u32, ()>| -> Result<u32, ()> { Result::Ok(r?) };
+        }
+        fn spawned(r: Result<u32, ()>) {
+            let f = async move {
+                r?;
+                Result::Ok(())
+            };
+        }
+        fn result(r: Result<u32, ()>) -> Result<u32, ()> {
+            Result::Ok(r?)
+        }
+        fn checked(r: Result<u32, ()>) -> Checked {
+            r?;
+            Checked
+        }
+
+        //- /std.rs crate:std
+        #[prelude_import] use result::*;
+        pub mod ops {
+            pub trait Try {
+                type Ok;
+                type Error;
+            }
+        }
+        pub mod result {
+            pub enum Result<O, E> {
+                Ok(O),
+                Err(E)
+            }
+            impl<O, E> crate::ops::Try for Result<O, E> {
+                type Ok = O;
+                type Error = E;
+            }
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "r?": the `?` operator can only be used in a function that returns `Result` or `Option`, not `()`
+    "###
+    );
+}
+
+#[test]
+fn not_an_iterator_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /main.rs crate:main deps:std
+        struct Counter;
+        impl std::iter::Iterator for Counter {
+            type Item = u32;
+        }
+        struct Bag;
+        impl std::iter::IntoIterator for Bag {
+            type Item = u8;
+        }
+        struct Stone;
+
+        fn foo<I: std::iter::IntoIterator>(items: I) {
+            for x in Counter {}
+            for x in Bag {}
+            for x in items {}
+            for x in Stone {}
+            for x in 0u32 {}
+        }
+
+        //- /std.rs crate:std
+        pub mod iter {
+            pub trait Iterator {
+                type Item;
+            }
+            pub trait IntoIterator {
+                type Item;
+            }
+            impl<I: Iterator> IntoIterator for I {
+                type Item = I::Item;
+            }
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "Stone": `Stone` is not an iterator
+    "0u32": `u32` is not an iterator
+    "###
+    );
+}
+
+#[test]
+fn index_out_of_bounds_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /main.rs
+        const LEN: usize = 2 + 1;
+
+        fn foo(arr: [u8; LEN], slice: &[u8], r: &[u8; 2]) {
+            arr[2];
+            arr[LEN];
+            arr[1 + 2 * 2];
+            r[2];
+            slice[5];
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "arr[LEN]": index out of bounds: the length is 3 but the index is 3
+    "arr[1 + 2 * 2]": index out of bounds: the length is 3 but the index is 5
+    "r[2]": index out of bounds: the length is 2 but the index is 2
+    "###
+    );
+}
+
 #[test]
 fn no_such_field_with_feature_flag_diagnostics() {
     let diagnostics = TestDB::with_files(
@@ -349,3 +892,32 @@ fn no_such_field_with_feature_flag_diagnostics() {
 
     assert_snapshot!(diagnostics, @r###""###);
 }
+
+#[test]
+fn cfg_disabled_fields_are_not_missing_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r#"
+        //- /lib.rs crate:foo
+        struct MyStruct {
+            my_val: usize,
+            #[cfg(feature = "foo")]
+            bar: bool,
+        }
+
+        fn new(my_val: usize) -> MyStruct {
+            MyStruct { my_val }
+        }
+
+        fn new_with_bar(my_val: usize, bar: bool) -> MyStruct {
+            MyStruct { my_val, bar }
+        }
+        "#,
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "bar": no such field
+    "###
+    );
+}
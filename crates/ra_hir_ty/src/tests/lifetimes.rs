@@ -0,0 +1,136 @@
+use super::{infer_with_mismatches, type_at};
+
+/// Lifetimes are erased, so the types below only differ in lifetimes and
+/// must unify without mismatches.
+fn check_no_mismatches(ra_fixture: &str) {
+    let result = infer_with_mismatches(ra_fixture, true);
+    let mismatches = result.lines().filter(|it| it.contains(": expected ")).collect::<Vec<_>>();
+    assert!(mismatches.is_empty(), "unexpected mismatches:\n{}", mismatches.join("\n"));
+}
+
+#[test]
+fn references_with_and_without_lifetimes_unify() {
+    check_no_mismatches(
+        r#"
+struct S<'a, T>(&'a T);
+
+fn id<'a, T>(t: &'a T) -> &T { t }
+fn wrap<'a, T>(t: &'a T) -> S<'a, T> { S(t) }
+
+fn test<'a, 'b: 'a>(x: &'a u32, y: &'b u32, z: &'static mut u32) {
+    let a: &u32 = x;
+    let b: &'static u32 = y;
+    let c: &'a u32 = id(y);
+    let d: S<u32> = wrap(x);
+    let e: S<'b, u32> = S(y);
+    let f: &'a u32 = z;
+    let g = [x, y, &*z];
+}
+"#,
+    );
+}
+
+#[test]
+fn trait_objects_with_lifetime_bounds_unify() {
+    check_no_mismatches(
+        r#"
+trait Trait {}
+trait Send {}
+
+fn take(t: &dyn Trait) {}
+fn take_send(t: &(dyn Trait + Send)) {}
+
+fn test<'a>(x: &'a (dyn Trait + 'a), y: &'static (dyn Trait + Send + 'static)) {
+    take(x);
+    take_send(y);
+    let z: &dyn Trait = x;
+    let w: &'a (dyn Trait + Send + 'a) = y;
+}
+"#,
+    );
+}
+
+#[test]
+fn trait_objects_with_type_variables_unify() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait<T> {}
+
+fn make<T>() -> &'static dyn Trait<T> { loop {} }
+
+fn test() {
+    let y = make();
+    let z: &dyn Trait<u32> = y;
+    y<|>;
+}
+"#,
+    );
+    assert_eq!(t, "&dyn Trait<u32>");
+}
+
+#[test]
+fn method_through_reference_with_lifetime() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct S<'a, T>(&'a T);
+impl<'a, T> S<'a, T> {
+    fn get(&self) -> &'a T { self.0 }
+}
+
+fn test(x: S<'static, u32>) {
+    x.get()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "&u32");
+}
+
+#[test]
+fn lifetime_bounds_are_erased() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait {
+    fn foo(&self) -> u8;
+}
+
+fn test<'a, T: 'a + Trait>(t: &'a T) where T: 'static {
+    t.foo()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u8");
+
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait {}
+
+fn make<'a>() -> impl Trait + 'a { loop {} }
+
+fn test() {
+    make()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "impl Trait");
+}
+
+#[test]
+fn higher_ranked_bounds() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Visitor<'a> {
+    fn visit(&self, x: &'a u32) -> u64;
+}
+
+fn walk<V>(v: V) where V: for<'a> Visitor<'a> {
+    v.visit(&1)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
@@ -384,6 +384,29 @@ fn main() {
     );
 }
 
+#[test]
+fn infer_local_macro_in_nested_fn() {
+    assert_snapshot!(
+        infer(r#"
+fn main() {
+    macro_rules! foo {
+        () => { 1usize }
+    }
+    fn inner() {
+        let _a = foo!();
+    }
+}
+"#),
+        @r###"
+        [11; 116) '{     ...   } }': ()
+        [17; 66) 'macro_...     }': {unknown}
+        ![0; 6) '1usize': usize
+        [82; 114) '{     ...     }': ()
+        [96; 98) '_a': usize
+    "###
+    );
+}
+
 #[test]
 fn infer_builtin_macros_line() {
     assert_snapshot!(
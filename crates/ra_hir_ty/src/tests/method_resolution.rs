@@ -1,5 +1,6 @@
-use super::{infer, type_at, type_at_pos};
-use crate::test_db::TestDB;
+use super::{infer, receiver_adjustments_at, type_at, type_at_pos};
+use crate::{method_resolution::ReceiverAdjustments, test_db::TestDB};
+use hir_def::type_ref::Mutability;
 use insta::assert_snapshot;
 use ra_db::fixture::WithFixture;
 
@@ -1095,3 +1096,64 @@ fn test() { (S {}).method()<|>; }
     );
     assert_eq!(t, "()");
 }
+
+#[test]
+fn method_resolution_through_deref_chain() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref { type Target; fn deref(&self) -> &Self::Target; }
+struct Rc<T>(T);
+impl<T> Deref for Rc<T> { type Target = T; }
+struct Box<T>(T);
+impl<T> Deref for Box<T> { type Target = T; }
+struct S;
+impl S { fn get(&self) -> u32 { 0 } }
+fn test(x: Box<Rc<S>>) { x.get()<|>; }
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn method_receiver_adjustments() {
+    let fixture = |body: &str| {
+        format!(
+            r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref {{ type Target; fn deref(&self) -> &Self::Target; }}
+struct Rc<T>(T);
+impl<T> Deref for Rc<T> {{ type Target = T; }}
+#[lang = "slice"]
+impl<T> [T] {{ fn len(&self) -> usize {{ 0 }} }}
+trait Clone {{ fn clone(&self) -> Self; }}
+struct S;
+impl S {{ fn get(&self) -> u32 {{ 0 }} fn push(&mut self) {{}} }}
+impl Clone for S {{ fn clone(&self) -> S {{ S }} }}
+fn test(x: Rc<Rc<S>>, y: &S) {{ {} }}
+"#,
+            body
+        )
+    };
+    let adjustments =
+        |autoderefs, unsize, autoref| ReceiverAdjustments { autoderefs, unsize, autoref };
+
+    assert_eq!(
+        receiver_adjustments_at(&fixture("x.<|>get();")),
+        Some(adjustments(2, false, Some(Mutability::Shared)))
+    );
+    assert_eq!(
+        receiver_adjustments_at(&fixture("let mut s = S; s.<|>push();")),
+        Some(adjustments(0, false, Some(Mutability::Mut)))
+    );
+    assert_eq!(
+        receiver_adjustments_at(&fixture("y.<|>clone();")),
+        Some(adjustments(0, false, None))
+    );
+    assert_eq!(
+        receiver_adjustments_at(&fixture("[1, 2, 3].<|>len();")),
+        Some(adjustments(0, true, Some(Mutability::Shared)))
+    );
+}
@@ -261,3 +261,17 @@ fn test(a: i32) {
     );
     assert_eq!(t, "f64");
 }
+
+#[test]
+fn diverging_block_does_not_constrain_to_unit() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(c: bool) {
+    let x = if c { return; } else { 1 };
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "i32");
+}
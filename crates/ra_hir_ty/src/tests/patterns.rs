@@ -154,17 +154,51 @@ fn test(x: &i32) {
     [9; 10) 'x': &i32
     [18; 76) '{     ...2 {} }': ()
     [24; 46) 'if let...u32 {}': ()
+    [31; 32) '1': u32
     [31; 36) '1..76': u32
+    [34; 36) '76': u32
     [39; 43) '2u32': u32
     [44; 46) '{}': ()
     [51; 74) 'if let...u32 {}': ()
+    [58; 59) '1': u32
     [58; 64) '1..=76': u32
+    [62; 64) '76': u32
     [67; 71) '2u32': u32
     [72; 74) '{}': ()
     "###
     );
 }
 
+#[test]
+fn infer_half_open_range_and_const_block_patterns() {
+    assert_snapshot!(
+        infer_with_mismatches(r#"
+fn test(x: u8) {
+    match x {
+        10.. => (),
+        const { 5 } => (),
+        _ => (),
+    }
+}
+"#, true),
+        @r###"
+    [9; 10) 'x': u8
+    [16; 103) '{     ...   } }': ()
+    [22; 101) 'match ...     }': ()
+    [28; 29) 'x': u8
+    [40; 42) '10': u8
+    [40; 44) '10..': u8
+    [48; 50) '()': ()
+    [60; 71) 'const { 5 }': u8
+    [66; 71) '{ 5 }': u8
+    [68; 69) '5': u8
+    [75; 77) '()': ()
+    [87; 88) '_': u8
+    [92; 94) '()': ()
+    "###
+    );
+}
+
 #[test]
 fn infer_pattern_match_ergonomics() {
     assert_snapshot!(
@@ -243,8 +277,8 @@ fn test() {
     @r###"
     [11; 210) '{     ...   } }': ()
     [21; 26) 'slice': &[f64]
-    [37; 43) '&[0.0]': &[f64; _]
-    [38; 43) '[0.0]': [f64; _]
+    [37; 43) '&[0.0]': &[f64; 1]
+    [38; 43) '[0.0]': [f64; 1]
     [39; 42) '0.0': f64
     [49; 208) 'match ...     }': ()
     [55; 60) 'slice': &[f64]
@@ -288,19 +322,19 @@ fn test() {
 "#),
     @r###"
     [11; 180) '{     ...   } }': ()
-    [21; 24) 'arr': [f64; _]
-    [37; 47) '[0.0, 1.0]': [f64; _]
+    [21; 24) 'arr': [f64; 2]
+    [37; 47) '[0.0, 1.0]': [f64; 2]
     [38; 41) '0.0': f64
     [43; 46) '1.0': f64
     [53; 178) 'match ...     }': ()
-    [59; 62) 'arr': [f64; _]
-    [73; 81) '[1.0, a]': [f64; _]
+    [59; 62) 'arr': [f64; 2]
+    [73; 81) '[1.0, a]': [f64; 2]
     [74; 77) '1.0': f64
     [74; 77) '1.0': f64
     [79; 80) 'a': f64
     [85; 111) '{     ...     }': ()
     [99; 100) 'a': f64
-    [121; 127) '[b, c]': [f64; _]
+    [121; 127) '[b, c]': [f64; 2]
     [122; 123) 'b': f64
     [125; 126) 'c': f64
     [131; 172) '{     ...     }': ()
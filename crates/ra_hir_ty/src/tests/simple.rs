@@ -28,7 +28,7 @@ mod boxed {
 
 "#,
     );
-    assert_eq!("(Box<i32>, Box<Box<i32>>, Box<&i32>, Box<[i32; _]>)", type_at_pos(&db, pos));
+    assert_eq!("(Box<i32>, Box<Box<i32>>, Box<&i32>, Box<[i32; 1]>)", type_at_pos(&db, pos));
 }
 
 #[test]
@@ -160,6 +160,62 @@ fn test() {
     assert_eq!("f32", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_let_chain() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs
+enum Option<T> { Some(T), None }
+
+fn test() {
+    let foo: Option<Option<f32>> = None;
+    if let Option::Some(x) = foo && let Option::Some(y) = x && true {
+        <|>y
+    }
+}
+
+"#,
+    );
+    assert_eq!("f32", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_while_let_chain() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs
+enum Option<T> { Some(T), None }
+
+fn test() {
+    let foo: Option<(u8, i64)> = None;
+    while let Option::Some((a, b)) = foo && a > 0 {
+        <|>b
+    }
+}
+
+"#,
+    );
+    assert_eq!("i64", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_let_else() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs
+enum Option<T> { Some(T), None }
+
+fn test() {
+    let foo: Option<f32> = None;
+    let Option::Some(x) = foo else { return };
+    <|>x
+}
+
+"#,
+    );
+    assert_eq!("f32", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_basics() {
     assert_snapshot!(
@@ -317,6 +373,21 @@ fn test() {
     );
 }
 
+#[test]
+fn infer_union_field_access() {
+    let t = type_at(
+        r#"
+//- /main.rs
+union U { a: u32, b: f32 }
+
+fn test(u: U) {
+    unsafe { u.b<|>; }
+}
+"#,
+    );
+    assert_eq!(t, "f32");
+}
+
 #[test]
 fn infer_enum() {
     assert_snapshot!(
@@ -1104,59 +1175,88 @@ fn test(x: &str, y: isize) {
     [9; 10) 'x': &str
     [18; 19) 'y': isize
     [28; 293) '{     ... []; }': ()
-    [38; 39) 'a': [&str; _]
-    [42; 45) '[x]': [&str; _]
+    [38; 39) 'a': [&str; 1]
+    [42; 45) '[x]': [&str; 1]
     [43; 44) 'x': &str
-    [55; 56) 'b': [[&str; _]; _]
-    [59; 65) '[a, a]': [[&str; _]; _]
-    [60; 61) 'a': [&str; _]
-    [63; 64) 'a': [&str; _]
-    [75; 76) 'c': [[[&str; _]; _]; _]
-    [79; 85) '[b, b]': [[[&str; _]; _]; _]
-    [80; 81) 'b': [[&str; _]; _]
-    [83; 84) 'b': [[&str; _]; _]
-    [96; 97) 'd': [isize; _]
-    [100; 112) '[y, 1, 2, 3]': [isize; _]
+    [55; 56) 'b': [[&str; 1]; 2]
+    [59; 65) '[a, a]': [[&str; 1]; 2]
+    [60; 61) 'a': [&str; 1]
+    [63; 64) 'a': [&str; 1]
+    [75; 76) 'c': [[[&str; 1]; 2]; 2]
+    [79; 85) '[b, b]': [[[&str; 1]; 2]; 2]
+    [80; 81) 'b': [[&str; 1]; 2]
+    [83; 84) 'b': [[&str; 1]; 2]
+    [96; 97) 'd': [isize; 4]
+    [100; 112) '[y, 1, 2, 3]': [isize; 4]
     [101; 102) 'y': isize
     [104; 105) '1': isize
     [107; 108) '2': isize
     [110; 111) '3': isize
-    [122; 123) 'd': [isize; _]
-    [126; 138) '[1, y, 2, 3]': [isize; _]
+    [122; 123) 'd': [isize; 4]
+    [126; 138) '[1, y, 2, 3]': [isize; 4]
     [127; 128) '1': isize
     [130; 131) 'y': isize
     [133; 134) '2': isize
     [136; 137) '3': isize
-    [148; 149) 'e': [isize; _]
-    [152; 155) '[y]': [isize; _]
+    [148; 149) 'e': [isize; 1]
+    [152; 155) '[y]': [isize; 1]
     [153; 154) 'y': isize
-    [165; 166) 'f': [[isize; _]; _]
-    [169; 175) '[d, d]': [[isize; _]; _]
-    [170; 171) 'd': [isize; _]
-    [173; 174) 'd': [isize; _]
-    [185; 186) 'g': [[isize; _]; _]
-    [189; 195) '[e, e]': [[isize; _]; _]
-    [190; 191) 'e': [isize; _]
-    [193; 194) 'e': [isize; _]
-    [206; 207) 'h': [i32; _]
-    [210; 216) '[1, 2]': [i32; _]
+    [165; 166) 'f': [[isize; 4]; 2]
+    [169; 175) '[d, d]': [[isize; 4]; 2]
+    [170; 171) 'd': [isize; 4]
+    [173; 174) 'd': [isize; 4]
+    [185; 186) 'g': [[isize; 1]; 2]
+    [189; 195) '[e, e]': [[isize; 1]; 2]
+    [190; 191) 'e': [isize; 1]
+    [193; 194) 'e': [isize; 1]
+    [206; 207) 'h': [i32; 2]
+    [210; 216) '[1, 2]': [i32; 2]
     [211; 212) '1': i32
     [214; 215) '2': i32
-    [226; 227) 'i': [&str; _]
-    [230; 240) '["a", "b"]': [&str; _]
+    [226; 227) 'i': [&str; 2]
+    [230; 240) '["a", "b"]': [&str; 2]
     [231; 234) '"a"': &str
     [236; 239) '"b"': &str
-    [251; 252) 'b': [[&str; _]; _]
-    [255; 265) '[a, ["b"]]': [[&str; _]; _]
-    [256; 257) 'a': [&str; _]
-    [259; 264) '["b"]': [&str; _]
+    [251; 252) 'b': [[&str; 1]; 2]
+    [255; 265) '[a, ["b"]]': [[&str; 1]; 2]
+    [256; 257) 'a': [&str; 1]
+    [259; 264) '["b"]': [&str; 1]
     [260; 263) '"b"': &str
-    [275; 276) 'x': [u8; _]
-    [288; 290) '[]': [u8; _]
+    [275; 276) 'x': [u8; 0]
+    [288; 290) '[]': [u8; 0]
     "###
     );
 }
 
+#[test]
+fn infer_array_lengths() {
+    let t = type_at(
+        r#"
+//- /main.rs
+const N: usize = 2 * 3 + 1;
+const M: usize = N - 1;
+
+fn test(a: [u8; N], b: [u8; M + 2], c: [u8; { 4 }], d: [u8; unknown()]) {
+    (a, b, c, d, [0u8; N], [0; M << 1])<|>;
+}
+"#,
+    );
+    assert_eq!(t, "([u8; 7], [u8; 8], [u8; 4], [u8; _], [u8; 7], [i32; 12])");
+}
+
+#[test]
+fn infer_array_length_of_const_param() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test<const N: usize>(a: [u8; N]) {
+    a<|>;
+}
+"#,
+    );
+    assert_eq!(t, "[u8; N]");
+}
+
 #[test]
 fn infer_struct_generics() {
     assert_snapshot!(
@@ -1755,3 +1855,33 @@ fn main() {
     "###
     );
 }
+
+#[test]
+fn infer_const_param_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn foo<const N: usize>() {
+    let x = N;
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "usize");
+}
+
+#[test]
+fn infer_const_generic_args() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Arr<T, const N: usize>(T);
+fn take<T, const N: usize>(a: Arr<T, N>, b: Arr<T, N>) -> Arr<T, N> { a }
+
+fn test(x: Arr<u8, 3>, y: Arr<u8, { 0x3 }>) {
+    take(x, y)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Arr<u8, 3>");
+}
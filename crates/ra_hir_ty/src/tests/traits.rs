@@ -95,6 +95,64 @@ mod future {
     assert_eq!("impl Future<Output = u64>", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_async_fn_body_returns_declared_type() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+async fn foo() -> u64 {
+    let x = 128;
+    return x<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+
+"#,
+    );
+    assert_eq!("u64", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_await_async_method_in_async_fn() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+struct S;
+
+impl S {
+    async fn get(&self) -> (u8, u64) {
+        (1, 2)
+    }
+}
+
+async fn foo(s: S) -> u64 {
+    let v = s.get().await;
+    v<|>.1
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+
+"#,
+    );
+    assert_eq!("(u8, u64)", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_try() {
     let (db, pos) = TestDB::with_position(
@@ -135,6 +193,37 @@ mod result {
     assert_eq!("i32", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_try_with_user_type() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+struct Poll<T>(T);
+impl<T> std::ops::Try for Poll<T> {
+    type Ok = T;
+    type Error = ();
+}
+
+fn test() -> Poll<()> {
+    let v = Poll(1u8)?;
+    v<|>;
+    Poll(())
+}
+
+//- /std.rs crate:std
+
+pub mod ops {
+    pub trait Try {
+        type Ok;
+        type Error;
+    }
+}
+"#,
+    );
+    assert_eq!("u8", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_for_loop() {
     let (db, pos) = TestDB::with_position(
@@ -176,6 +265,99 @@ mod collections {
     assert_eq!("&str", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_for_loop_through_into_iterator() {
+    let fixture = |body: &str| {
+        format!(
+            r#"
+//- /main.rs crate:main deps:std
+use std::collections::{{HashMap, Vec}};
+
+struct Countdown(u32);
+impl std::iter::Iterator for Countdown {{
+    type Item = u32;
+}}
+
+fn test(v: Vec<u8>, m: HashMap<u16, char>) {{
+    {}
+}}
+
+//- /std.rs crate:std
+pub mod iter {{
+    pub trait Iterator {{
+        type Item;
+    }}
+    pub trait IntoIterator {{
+        type Item;
+    }}
+    impl<I: Iterator> IntoIterator for I {{
+        type Item = I::Item;
+    }}
+}}
+
+pub mod collections {{
+    pub struct Vec<T> {{}}
+    impl<T> crate::iter::IntoIterator for Vec<T> {{
+        type Item = T;
+    }}
+    impl<'a, T> crate::iter::IntoIterator for &'a Vec<T> {{
+        type Item = &'a T;
+    }}
+
+    pub struct HashMap<K, V> {{}}
+    impl<K, V> crate::iter::IntoIterator for HashMap<K, V> {{
+        type Item = (K, V);
+    }}
+    impl<'a, K, V> crate::iter::IntoIterator for &'a HashMap<K, V> {{
+        type Item = (&'a K, &'a V);
+    }}
+}}
+"#,
+            body
+        )
+    };
+    assert_eq!(type_at(&fixture("for x in Countdown(3) { x<|>; }")), "u32");
+    assert_eq!(type_at(&fixture("for x in v { x<|>; }")), "u8");
+    assert_eq!(type_at(&fixture("for x in &v { x<|>; }")), "&u8");
+    assert_eq!(type_at(&fixture("for x in m { x<|>; }")), "(u16, char)");
+    assert_eq!(type_at(&fixture("for (k, v) in &m { v<|>; }")), "&char");
+}
+
+#[test]
+fn infer_for_loop_in_no_std_crate() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:core
+#![no_std]
+
+struct Countdown(u32);
+impl core::iter::Iterator for Countdown {
+    type Item = u32;
+}
+
+fn test() {
+    for x in Countdown(3) {
+        x<|>;
+    }
+}
+
+//- /core.rs crate:core
+pub mod iter {
+    pub trait Iterator {
+        type Item;
+    }
+    pub trait IntoIterator {
+        type Item;
+    }
+    impl<I: Iterator> IntoIterator for I {
+        type Item = I::Item;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
 #[test]
 fn infer_ops_neg() {
     let (db, pos) = TestDB::with_position(
@@ -526,7 +708,7 @@ fn indexing_arrays() {
         @r###"
     [10; 26) '{ &mut...[2]; }': ()
     [12; 23) '&mut [9][2]': &mut {unknown}
-    [17; 20) '[9]': [i32; _]
+    [17; 20) '[9]': [i32; 1]
     [17; 23) '[9][2]': {unknown}
     [18; 19) '9': i32
     [21; 22) '2': i32
@@ -1115,7 +1297,6 @@ fn test() {
 }
 
 #[test]
-#[ignore]
 fn impl_trait() {
     assert_snapshot!(
         infer(r#"
@@ -1263,10 +1444,10 @@ fn test(a: impl Trait + 'lifetime, b: impl 'lifetime, c: impl (Trait), d: impl (
 }
 "#),
         @r###"
-    [24; 25) 'a': impl Trait + {error}
-    [51; 52) 'b': impl {error}
+    [24; 25) 'a': impl Trait
+    [51; 52) 'b': impl '_
     [70; 71) 'c': impl Trait
-    [87; 88) 'd': impl {error}
+    [87; 88) 'd': impl '_
     [108; 109) 'e': impl {error}
     [124; 125) 'f': impl Trait + {error}
     [148; 151) '{ }': ()
@@ -1357,6 +1538,47 @@ fn test<T: Trait<Type = u32>>(x: T, y: impl Trait<Type = i64>) {
     );
 }
 
+#[test]
+fn assoc_type_bounds() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait {
+    fn foo(&self) -> u32;
+}
+trait Iterator {
+    type Item;
+    fn next(&mut self) -> Self::Item;
+}
+
+fn test<I: Iterator<Item: Trait>>(mut it: I) {
+    let x = it.next();
+    x.foo()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn assoc_type_bounds_display() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait {}
+trait Other<T> {}
+trait Iterator {
+    type Item;
+}
+
+fn test(it: impl Iterator<Item: Trait + Other<u32>>) {
+    it<|>;
+}
+"#,
+    );
+    assert_eq!(t, "impl Iterator<Item: Trait + Other<u32>>");
+}
+
 #[test]
 fn impl_trait_assoc_binding_projection_bug() {
     let (db, pos) = TestDB::with_position(
@@ -1503,6 +1725,26 @@ fn test(x: &impl Trait1) {
     );
 }
 
+#[test]
+fn super_trait_dyn_trait_method_resolution() {
+    let t = type_at(
+        r#"
+//- /main.rs
+mod foo {
+    trait SuperTrait {
+        fn foo(&self) -> u32 {}
+    }
+}
+trait Trait1: foo::SuperTrait {}
+
+fn test(x: &dyn Trait1) {
+    x.foo()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
 #[test]
 fn super_trait_cycle() {
     // This just needs to not crash
@@ -1758,6 +2000,77 @@ fn test() {
     );
 }
 
+#[test]
+fn closure_param_types_from_impl_fn_argument() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+
+struct S;
+impl S {
+    fn method(&self) -> u64 {}
+}
+
+fn takes(f: impl FnOnce(S) -> u64) {}
+
+fn test() {
+    takes(|s| s.method()<|>);
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn closure_param_types_from_fn_ptr() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+
+struct S;
+impl S {
+    fn method(&self) -> u64 {}
+}
+
+fn test() {
+    let f: fn(S) -> u64 = |s| s.method()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn closure_param_types_from_dyn_fn() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+
+struct S;
+impl S {
+    fn method(&self) -> u64 {}
+}
+
+fn test() {
+    let f: &dyn FnOnce(S) -> u64 = &|s| s.method()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
 #[test]
 fn unselected_projection_in_trait_env_1() {
     let t = type_at(
@@ -2046,3 +2359,62 @@ fn test(x: Box<dyn Trait>) {
     );
     assert_eq!(t, "()");
 }
+
+#[test]
+fn impl_trait_through_chalk() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait<T> {
+    fn foo(&self) -> T;
+}
+struct Wrap<I>(I);
+impl<T, I: Trait<T>> Trait<T> for Wrap<I> {}
+
+fn bar() -> impl Trait<u64> { loop {} }
+
+fn test() {
+    Wrap(bar()).foo()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn auto_trait_from_constituent_types() {
+    let fixture = |body| {
+        format!(
+            r#"
+//- /main.rs
+auto trait Send {{}}
+struct Rc<T>(*const T);
+impl<T> !Send for Rc<T> {{}}
+impl<T> !Send for *const T {{}}
+
+trait IsSend {{
+    fn is_send(&self) -> u32;
+}}
+impl<T: Send> IsSend for T {{}}
+
+struct S(u8, (char, [i64; 2]));
+struct W<T>(T);
+enum E {{ A(u8), B(Rc<u8>) }}
+struct Explicit(Rc<u8>);
+unsafe impl Send for Explicit {{}}
+
+fn test(p: *const u8) {{
+    {}
+}}
+"#,
+            body
+        )
+    };
+    assert_eq!(type_at(&fixture("S(0, ('a', [1, 2])).is_send()<|>;")), "u32");
+    assert_eq!(type_at(&fixture("W(&S(0, ('a', [1, 2]))).is_send()<|>;")), "u32");
+    assert_eq!(type_at(&fixture("Explicit(Rc(p)).is_send()<|>;")), "u32");
+    assert_eq!(type_at(&fixture("p.is_send()<|>;")), "{unknown}");
+    assert_eq!(type_at(&fixture("Rc(p).is_send()<|>;")), "{unknown}");
+    assert_eq!(type_at(&fixture("W((1u8, Rc(p))).is_send()<|>;")), "{unknown}");
+    assert_eq!(type_at(&fixture("E::A(1).is_send()<|>;")), "{unknown}");
+}
@@ -35,6 +35,15 @@ fn create_chalk_solver() -> chalk_solve::Solver<Interner> {
     solver_choice.into_solver()
 }
 
+/// Collects impls for the given trait defined in `krate` itself.
+pub(crate) fn trait_impls_in_crate_query(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    trait_: TraitId,
+) -> Arc<[ImplId]> {
+    db.impls_in_crate(krate).lookup_impl_defs_for_trait(trait_).collect()
+}
+
 /// Collects impls for the given trait in the whole dependency tree of `krate`.
 pub(crate) fn impls_for_trait_query(
     db: &dyn HirDatabase,
@@ -50,8 +59,7 @@ pub(crate) fn impls_for_trait_query(
     for dep in &db.crate_graph()[krate].dependencies {
         impls.extend(db.impls_for_trait(dep.crate_id, trait_).iter());
     }
-    let crate_impl_defs = db.impls_in_crate(krate);
-    impls.extend(crate_impl_defs.lookup_impl_defs_for_trait(trait_));
+    impls.extend(db.trait_impls_in_crate(krate, trait_).iter());
     impls.into_iter().collect()
 }
 
@@ -162,21 +170,38 @@ pub(crate) fn trait_solve_query(
     // We currently don't deal with universes (I think / hope they're not yet
     // relevant for our use cases?)
     let u_canonical = chalk_ir::UCanonical { canonical, universes: 1 };
-    let solution = solve(db, krate, &u_canonical);
+    let solution = solve(db, krate, &u_canonical, &std::cell::Cell::new(CHALK_SOLVER_FUEL));
     solution.map(|solution| solution_from_chalk(db, solution))
 }
 
+/// Whether `goal` definitely doesn't hold. `trait_solve` also returns `None`
+/// when the solver runs out of fuel, which must not be reported as an error
+/// in the user's code, so in that case the goal is solved again while keeping
+/// track of the fuel.
+pub(crate) fn trait_goal_fails(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    goal: Canonical<InEnvironment<Obligation>>,
+) -> bool {
+    if db.trait_solve(krate, goal.clone()).is_some() {
+        return false;
+    }
+    let canonical = goal.to_chalk(db).cast(&Interner);
+    let u_canonical = chalk_ir::UCanonical { canonical, universes: 1 };
+    let fuel = std::cell::Cell::new(CHALK_SOLVER_FUEL);
+    solve(db, krate, &u_canonical, &fuel).is_none() && fuel.get() >= 0
+}
+
 fn solve(
     db: &dyn HirDatabase,
     krate: CrateId,
     goal: &chalk_ir::UCanonical<chalk_ir::InEnvironment<chalk_ir::Goal<Interner>>>,
+    fuel: &std::cell::Cell<i32>,
 ) -> Option<chalk_solve::Solution<Interner>> {
     let context = ChalkContext { db, krate };
     log::debug!("solve goal: {:?}", goal);
     let mut solver = create_chalk_solver();
 
-    let fuel = std::cell::Cell::new(CHALK_SOLVER_FUEL);
-
     let solution = solver.solve_limited(&context, goal, || {
         context.db.check_canceled();
         let remaining = fuel.get();
@@ -270,7 +295,7 @@ pub enum FnTrait {
 }
 
 impl FnTrait {
-    fn lang_item_name(self) -> &'static str {
+    pub(crate) fn lang_item_name(self) -> &'static str {
         match self {
             FnTrait::FnOnce => "fn_once",
             FnTrait::FnMut => "fn_mut",
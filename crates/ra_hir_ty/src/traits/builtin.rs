@@ -6,10 +6,8 @@ use ra_db::CrateId;
 
 use super::{AssocTyValue, Impl, UnsizeToSuperTraitObjectData};
 use crate::{
-    db::HirDatabase,
-    utils::{all_super_traits, generics},
-    ApplicationTy, Binders, BoundVar, DebruijnIndex, GenericPredicate, Substs, TraitRef, Ty,
-    TypeCtor, TypeWalk,
+    db::HirDatabase, utils::generics, ApplicationTy, Binders, BoundVar, DebruijnIndex,
+    GenericPredicate, Substs, TraitRef, Ty, TypeCtor, TypeWalk,
 };
 
 pub(super) struct BuiltinImplData {
@@ -80,7 +78,7 @@ fn get_builtin_unsize_impls(
         // FIXME what about more complicated dyn tys with marker traits?
         if let Some(trait_ref) = ty.dyn_trait_ref() {
             if trait_ref.trait_ != target_trait.trait_ {
-                let super_traits = all_super_traits(db.upcast(), trait_ref.trait_);
+                let super_traits = db.all_super_traits(trait_ref.trait_);
                 if super_traits.contains(&target_trait.trait_) {
                     callback(Impl::UnsizeToSuperTraitObject(UnsizeToSuperTraitObjectData {
                         trait_: trait_ref.trait_,
@@ -239,23 +237,23 @@ fn check_unsize_impl_prerequisites(db: &dyn HirDatabase, krate: CrateId) -> bool
 }
 
 fn array_unsize_impl_datum(db: &dyn HirDatabase, krate: CrateId) -> BuiltinImplData {
-    // impl<T> Unsize<[T]> for [T; _]
-    // (this can be a single impl because we don't distinguish array sizes currently)
+    // impl<T, const N: usize> Unsize<[T]> for [T; N]
 
     let trait_ = get_unsize_trait(db, krate) // get unsize trait
         // the existence of the Unsize trait has been checked before
         .expect("Unsize trait missing");
 
     let var = Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0));
+    let len = Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 1));
     let substs = Substs::builder(2)
-        .push(Ty::apply_one(TypeCtor::Array, var.clone()))
+        .push(Ty::apply(TypeCtor::Array, Substs(vec![var.clone(), len].into())))
         .push(Ty::apply_one(TypeCtor::Slice, var))
         .build();
 
     let trait_ref = TraitRef { trait_, substs };
 
     BuiltinImplData {
-        num_vars: 1,
+        num_vars: 2,
         trait_ref,
         where_clauses: Vec::new(),
         assoc_ty_values: Vec::new(),
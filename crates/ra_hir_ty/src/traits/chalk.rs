@@ -8,7 +8,10 @@ use chalk_ir::{
     UniverseIndex,
 };
 
-use hir_def::{AssocContainerId, AssocItemId, GenericDefId, HasModule, Lookup, TypeAliasId};
+use hir_def::{
+    AdtId, AssocContainerId, AssocItemId, EnumVariantId, GenericDefId, HasModule, Lookup,
+    TypeAliasId, VariantId,
+};
 use ra_db::{
     salsa::{InternId, InternKey},
     CrateId,
@@ -16,8 +19,8 @@ use ra_db::{
 
 use super::{builtin, AssocTyValue, Canonical, ChalkContext, Impl, Obligation};
 use crate::{
-    db::HirDatabase, display::HirDisplay, utils::generics, ApplicationTy, GenericPredicate,
-    ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
+    db::HirDatabase, display::HirDisplay, utils::generics, ApplicationTy, BoundVar, DebruijnIndex,
+    GenericPredicate, ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
 };
 
 #[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
@@ -240,7 +243,10 @@ impl ToChalk for Ty {
             }
             Ty::Bound(idx) => chalk_ir::TyData::BoundVar(idx).intern(&Interner),
             Ty::Infer(_infer_ty) => panic!("uncanonicalized infer ty"),
-            Ty::Dyn(predicates) => {
+            // Chalk doesn't know about opaque types yet, but like a trait
+            // object an `impl Trait` type implements exactly its bounds.
+            // FIXME: this loses the distinction when converting back
+            Ty::Dyn(predicates) | Ty::Opaque(predicates) => {
                 let where_clauses = predicates
                     .iter()
                     .filter(|p| !p.is_error())
@@ -250,7 +256,7 @@ impl ToChalk for Ty {
                 let bounded_ty = chalk_ir::DynTy { bounds: make_binders(where_clauses, 1) };
                 chalk_ir::TyData::Dyn(bounded_ty).intern(&Interner)
             }
-            Ty::Opaque(_) | Ty::Unknown => {
+            Ty::Unknown => {
                 let substitution = chalk_ir::Substitution::empty(&Interner);
                 let name = TypeName::Error;
                 chalk_ir::ApplicationTy { name, substitution }.cast(&Interner).intern(&Interner)
@@ -675,7 +681,16 @@ impl<'a> chalk_solve::RustIrDatabase<Interner> for ChalkContext<'a> {
     }
     fn impl_provided_for(&self, auto_trait_id: TraitId, struct_id: StructId) -> bool {
         debug!("impl_provided_for {:?}, {:?}", auto_trait_id, struct_id);
-        false // FIXME
+        let trait_: hir_def::TraitId = from_chalk(self.db, auto_trait_id);
+        let type_ctor: TypeCtor = from_chalk(self.db, TypeName::Struct(struct_id));
+        // Any explicit impl, positive or negative, replaces the rule derived
+        // from the constituent types.
+        self.db.impls_for_trait(self.krate, trait_).iter().any(|&impl_id| {
+            match self.db.impl_self_ty(impl_id).value {
+                Ty::Apply(ApplicationTy { ctor, .. }) => ctor == type_ctor,
+                _ => false,
+            }
+        })
     }
     fn associated_ty_value(&self, id: AssociatedTyValueId) -> Arc<AssociatedTyValue> {
         self.db.associated_ty_value(self.krate, id)
@@ -782,7 +797,10 @@ pub(crate) fn struct_datum_query(
         fundamental: false,
     };
     let struct_datum_bound = chalk_rust_ir::StructDatumBound {
-        fields: Vec::new(), // FIXME add fields (only relevant for auto traits)
+        fields: auto_trait_constituents(db, type_ctor, num_params)
+            .into_iter()
+            .map(|ty| ty.to_chalk(db))
+            .collect(),
         where_clauses,
     };
     let struct_datum =
@@ -790,6 +808,38 @@ pub(crate) fn struct_datum_query(
     Arc::new(struct_datum)
 }
 
+/// The types which need to implement an auto trait for a type to implement it
+/// without an explicit impl: the field types of ADTs, and the element types of
+/// builtin types. These are expressed in terms of the type's parameters.
+fn auto_trait_constituents(
+    db: &dyn HirDatabase,
+    type_ctor: TypeCtor,
+    num_params: usize,
+) -> Vec<Ty> {
+    let param = |idx| Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, idx));
+    let variants: Vec<VariantId> = match type_ctor {
+        TypeCtor::Adt(AdtId::StructId(it)) => vec![it.into()],
+        TypeCtor::Adt(AdtId::UnionId(it)) => vec![it.into()],
+        TypeCtor::Adt(AdtId::EnumId(it)) => db
+            .enum_data(it)
+            .variants
+            .iter()
+            .map(|(local_id, _)| EnumVariantId { parent: it, local_id }.into())
+            .collect(),
+        TypeCtor::Slice | TypeCtor::Array | TypeCtor::RawPtr(_) | TypeCtor::Ref(_) => {
+            return vec![param(0)]
+        }
+        TypeCtor::Tuple { .. } => return (0..num_params).map(param).collect(),
+        _ => return Vec::new(),
+    };
+    variants
+        .into_iter()
+        .flat_map(|variant| {
+            db.field_types(variant).iter().map(|(_, ty)| ty.value.clone()).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 pub(crate) fn impl_datum_query(
     db: &dyn HirDatabase,
     krate: CrateId,
@@ -2,65 +2,15 @@
 //! query, but can't be computed directly from `*Data` (ie, which need a `db`).
 use std::sync::Arc;
 
-use hir_def::generics::WherePredicateTarget;
 use hir_def::{
     adt::VariantData,
     db::DefDatabase,
     generics::{GenericParams, TypeParamData, TypeParamProvenance},
-    path::Path,
-    resolver::{HasResolver, TypeNs},
-    type_ref::TypeRef,
+    path::path,
+    resolver::Resolver,
     AssocContainerId, GenericDefId, Lookup, TraitId, TypeAliasId, TypeParamId, VariantId,
 };
-use hir_expand::name::{name, Name};
-
-fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId) -> Vec<TraitId> {
-    let resolver = trait_.resolver(db);
-    // returning the iterator directly doesn't easily work because of
-    // lifetime problems, but since there usually shouldn't be more than a
-    // few direct traits this should be fine (we could even use some kind of
-    // SmallVec if performance is a concern)
-    let generic_params = db.generic_params(trait_.into());
-    let trait_self = generic_params.find_trait_self_param();
-    generic_params
-        .where_predicates
-        .iter()
-        .filter_map(|pred| match &pred.target {
-            WherePredicateTarget::TypeRef(TypeRef::Path(p)) if p == &Path::from(name![Self]) => {
-                pred.bound.as_path()
-            }
-            WherePredicateTarget::TypeParam(local_id) if Some(*local_id) == trait_self => {
-                pred.bound.as_path()
-            }
-            _ => None,
-        })
-        .filter_map(|path| match resolver.resolve_path_in_type_ns_fully(db, path.mod_path()) {
-            Some(TypeNs::TraitId(t)) => Some(t),
-            _ => None,
-        })
-        .collect()
-}
-
-/// Returns an iterator over the whole super trait hierarchy (including the
-/// trait itself).
-pub(super) fn all_super_traits(db: &dyn DefDatabase, trait_: TraitId) -> Vec<TraitId> {
-    // we need to take care a bit here to avoid infinite loops in case of cycles
-    // (i.e. if we have `trait A: B; trait B: A;`)
-    let mut result = vec![trait_];
-    let mut i = 0;
-    while i < result.len() {
-        let t = result[i];
-        // yeah this is quadratic, but trait hierarchies should be flat
-        // enough that this doesn't matter
-        for tt in direct_super_traits(db, t) {
-            if !result.contains(&tt) {
-                result.push(tt);
-            }
-        }
-        i += 1;
-    }
-    result
-}
+use hir_expand::name::Name;
 
 /// Finds a path from a trait to one of its super traits. Returns an empty
 /// vector if there is no path.
@@ -79,7 +29,7 @@ pub(super) fn find_super_trait_path(
             return true;
         }
 
-        for tt in direct_super_traits(db, trait_) {
+        for &tt in db.super_traits(trait_).iter() {
             if path.contains(&tt) {
                 continue;
             }
@@ -99,21 +49,25 @@ pub(super) fn associated_type_by_name_including_super_traits(
     trait_: TraitId,
     name: &Name,
 ) -> Option<TypeAliasId> {
-    all_super_traits(db, trait_)
-        .into_iter()
-        .find_map(|t| db.trait_data(t).associated_type_by_name(name))
+    db.all_super_traits(trait_).iter().find_map(|&t| db.trait_data(t).associated_type_by_name(name))
 }
 
 pub(super) fn variant_data(db: &dyn DefDatabase, var: VariantId) -> Arc<VariantData> {
     match var {
         VariantId::StructId(it) => db.struct_data(it).variant_data.clone(),
         VariantId::UnionId(it) => db.union_data(it).variant_data.clone(),
-        VariantId::EnumVariantId(it) => {
-            db.enum_data(it.parent).variants[it.local_id].variant_data.clone()
-        }
+        VariantId::EnumVariantId(it) => db.enum_variant_data(it),
     }
 }
 
+/// The `IntoIterator` trait `for` loops go through, which comes from `core`
+/// in `#![no_std]` crates.
+pub(crate) fn into_iterator_trait(db: &dyn DefDatabase, resolver: &Resolver) -> Option<TraitId> {
+    resolver
+        .resolve_known_trait(db, &path![std::iter::IntoIterator])
+        .or_else(|| resolver.resolve_known_trait(db, &path![core::iter::IntoIterator]))
+}
+
 /// Helper for mutating `Arc<[T]>` (i.e. `Arc::make_mut` for Arc slices).
 /// The underlying values are cloned if there are other strong references.
 pub(crate) fn make_mut_slice<T: Clone>(a: &mut Arc<[T]>) -> &mut [T] {
@@ -177,7 +131,7 @@ impl Generics {
         (parent + child, parent, child)
     }
 
-    /// (parent total, self param, type param list, impl trait)
+    /// (parent total, self param, type and const param list, impl trait)
     pub(crate) fn provenance_split(&self) -> (usize, usize, usize, usize) {
         let parent = self.parent_generics.as_ref().map_or(0, |p| p.len());
         let self_params = self
@@ -190,7 +144,10 @@ impl Generics {
             .params
             .types
             .iter()
-            .filter(|(_, p)| p.provenance == TypeParamProvenance::TypeParamList)
+            .filter(|(_, p)| {
+                p.provenance == TypeParamProvenance::TypeParamList
+                    || p.provenance == TypeParamProvenance::ConstParam
+            })
             .count();
         let impl_trait_params = self
             .params
@@ -1,12 +1,12 @@
 //! FIXME: write short doc here
 
-use ra_assists::{resolved_assists, AssistAction, AssistLabel};
+use ra_assists::{resolved_assists, AssistAction, AssistLabel, ResolvedAssist};
 use ra_db::{FilePosition, FileRange};
 use ra_ide_db::RootDatabase;
 
-use crate::{FileId, SourceChange, SourceFileEdit};
+use crate::{FileId, FileSystemEdit, SourceChange, SourceFileEdit};
 
-pub use ra_assists::AssistId;
+pub use ra_assists::{AssistConfig, AssistId, BodyWrapper};
 
 #[derive(Debug)]
 pub struct Assist {
@@ -16,28 +16,72 @@ pub struct Assist {
     pub source_change: SourceChange,
 }
 
-pub(crate) fn assists(db: &RootDatabase, frange: FileRange) -> Vec<Assist> {
-    resolved_assists(db, frange)
+/// An assist without its edit, which can be computed with `resolve_assist`.
+#[derive(Debug)]
+pub struct UnresolvedAssist {
+    pub id: AssistId,
+    pub label: String,
+    pub group_label: Option<String>,
+}
+
+pub(crate) fn assists(db: &RootDatabase, config: &AssistConfig, frange: FileRange) -> Vec<Assist> {
+    resolved_assists(db, config, frange)
+        .into_iter()
+        .map(|assist| to_assist(assist, frange))
+        .collect()
+}
+
+pub(crate) fn unresolved_assists(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    frange: FileRange,
+) -> Vec<UnresolvedAssist> {
+    ra_assists::unresolved_assists(db, config, frange)
         .into_iter()
-        .map(|assist| {
-            let file_id = frange.file_id;
-            let assist_label = &assist.label;
-            Assist {
-                id: assist_label.id,
-                label: assist_label.label.clone(),
-                group_label: assist.group_label.map(|it| it.0),
-                source_change: action_to_edit(assist.action, file_id, assist_label),
-            }
+        .map(|assist| UnresolvedAssist {
+            id: assist.label.id,
+            label: assist.label.label,
+            group_label: assist.group_label.map(|it| it.0),
         })
         .collect()
 }
 
+pub(crate) fn resolve_assist(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    frange: FileRange,
+    id: &str,
+    label: &str,
+) -> Option<Assist> {
+    let assist = ra_assists::resolve_assist(db, config, frange, id, label)?;
+    Some(to_assist(assist, frange))
+}
+
+fn to_assist(assist: ResolvedAssist, frange: FileRange) -> Assist {
+    let assist_label = &assist.label;
+    Assist {
+        id: assist_label.id,
+        label: assist_label.label.clone(),
+        group_label: assist.group_label.map(|it| it.0),
+        source_change: action_to_edit(assist.action, frange.file_id, assist_label),
+    }
+}
+
 fn action_to_edit(
     action: AssistAction,
     file_id: FileId,
     assist_label: &AssistLabel,
 ) -> SourceChange {
     let file_edit = SourceFileEdit { file_id, edit: action.edit };
-    SourceChange::source_file_edit(assist_label.label.clone(), file_edit)
+    let file_system_edits = action
+        .new_files
+        .into_iter()
+        .map(|it| FileSystemEdit::CreateFile {
+            source_root: it.source_root,
+            path: it.path,
+            initial_contents: it.contents,
+        })
+        .collect();
+    SourceChange::from_edits(assist_label.label.clone(), vec![file_edit], file_system_edits)
         .with_cursor_opt(action.cursor_position.map(|offset| FilePosition { offset, file_id }))
 }
@@ -13,6 +13,7 @@ mod complete_snippet;
 mod complete_qualified_path;
 mod complete_unqualified_path;
 mod complete_postfix;
+mod complete_custom_snippet;
 mod complete_macro_in_item_position;
 mod complete_trait_impl;
 #[cfg(test)]
@@ -37,6 +38,9 @@ pub struct CompletionConfig {
     pub enable_postfix_completions: bool,
     pub add_call_parenthesis: bool,
     pub add_call_argument_snippets: bool,
+    /// Snippets configured by the user, see `complete_custom_snippet` for the
+    /// variables they can use.
+    pub snippets: Vec<Snippet>,
 }
 
 impl Default for CompletionConfig {
@@ -45,10 +49,30 @@ impl Default for CompletionConfig {
             enable_postfix_completions: true,
             add_call_parenthesis: true,
             add_call_argument_snippets: true,
+            snippets: Vec::new(),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snippet {
+    pub label: String,
+    pub body: String,
+    pub description: Option<String>,
+    pub scope: SnippetScope,
+}
+
+/// Where a snippet is offered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnippetScope {
+    /// In place of an expression, inside of a function.
+    Expr,
+    /// In place of an item.
+    Item,
+    /// After a `.`, replacing the receiver.
+    Postfix,
+}
+
 /// Main entry point for completion. We run completion as a two-phase process.
 ///
 /// First, we look at the position and collect a so-called `CompletionContext.
@@ -91,6 +115,7 @@ pub(crate) fn completions(
     complete_record::complete_record(&mut acc, &ctx);
     complete_pattern::complete_pattern(&mut acc, &ctx);
     complete_postfix::complete_postfix(&mut acc, &ctx);
+    complete_custom_snippet::complete_custom_snippet(&mut acc, &ctx);
     complete_macro_in_item_position::complete_macro_in_item_position(&mut acc, &ctx);
     complete_trait_impl::complete_trait_impl(&mut acc, &ctx);
 
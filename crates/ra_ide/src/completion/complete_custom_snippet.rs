@@ -0,0 +1,343 @@
+//! Completes the snippets configured by the user.
+//!
+//! The body of a snippet can refer to variables, which are expanded when the
+//! snippet is completed:
+//!
+//! * `$RECEIVER` is the text of the receiver of a postfix snippet,
+//! * `$RECEIVER_TYPE` is the type of the receiver of a postfix snippet,
+//! * `$CRATE_NAME` is the name of the current crate,
+//! * `$EXPECTED_TYPE` is the type expected at the cursor, known in the
+//!   initializer of a `let` with a type and in the fields of a struct literal.
+//!
+//! Both `$NAME` and `${NAME}` are accepted. A snippet using a variable which
+//! isn't available at the cursor is not offered. Other variables, like the
+//! tabstops, are left for the client.
+
+use hir::{HirDisplay, Type};
+use ra_syntax::{
+    algo::skip_trivia_token,
+    ast::{self, AstNode, TypeAscriptionOwner},
+    Direction, TextRange,
+};
+use ra_text_edit::TextEdit;
+
+use crate::completion::{
+    complete_postfix::{get_receiver_text, include_references},
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+    SnippetScope,
+};
+
+pub(super) fn complete_custom_snippet(acc: &mut Completions, ctx: &CompletionContext) {
+    if ctx.config.snippets.is_empty() {
+        return;
+    }
+    let scope = if ctx.dot_receiver.is_some() {
+        if !ctx.config.enable_postfix_completions {
+            return;
+        }
+        SnippetScope::Postfix
+    } else if ctx.is_trivial_path && ctx.function_syntax.is_some() {
+        SnippetScope::Expr
+    } else if ctx.is_new_item {
+        SnippetScope::Item
+    } else {
+        return;
+    };
+
+    let vars = SnippetVariables::new(ctx);
+    for snippet in ctx.config.snippets.iter().filter(|it| it.scope == scope) {
+        let body = match expand_variables(&snippet.body, &vars) {
+            Some(it) => it,
+            None => continue,
+        };
+        let item = match (&scope, &vars.receiver) {
+            (SnippetScope::Postfix, Some((receiver, _))) => {
+                let receiver_range = ctx.sema.original_range(receiver.syntax()).range;
+                let delete_range =
+                    TextRange::from_to(receiver_range.start(), ctx.source_range().end());
+                CompletionItem::new(CompletionKind::Postfix, ctx.source_range(), &snippet.label)
+                    .snippet_edit(TextEdit::replace(delete_range, body))
+            }
+            _ => CompletionItem::new(CompletionKind::Snippet, ctx.source_range(), &snippet.label)
+                .insert_snippet(body)
+                .kind(CompletionItemKind::Snippet),
+        };
+        item.set_detail(snippet.description.as_ref()).add_to(acc);
+    }
+}
+
+struct SnippetVariables {
+    /// The receiver of a postfix snippet, including the references, and its text.
+    receiver: Option<(ast::Expr, String)>,
+    receiver_type: Option<String>,
+    crate_name: Option<String>,
+    expected_type: Option<String>,
+}
+
+impl SnippetVariables {
+    fn new(ctx: &CompletionContext) -> SnippetVariables {
+        let receiver = ctx.dot_receiver.as_ref().map(|it| {
+            let receiver = include_references(it);
+            let text = get_receiver_text(&receiver, ctx.dot_receiver_is_ambiguous_float_literal);
+            (receiver, text)
+        });
+        let receiver_type = ctx
+            .dot_receiver
+            .as_ref()
+            .and_then(|it| ctx.sema.type_of_expr(it))
+            .and_then(|it| display_type(ctx, it));
+        let crate_name = ctx.krate.and_then(|krate| {
+            let name = ctx.db.crate_graph()[krate.into()].display_name.as_ref()?.to_string();
+            Some(name)
+        });
+        let expected_type = expected_type(ctx).and_then(|it| display_type(ctx, it));
+        SnippetVariables { receiver, receiver_type, crate_name, expected_type }
+    }
+
+    /// `None` if `name` is not one of our variables, `Some(None)` if it is but
+    /// the value isn't known at the cursor.
+    fn get(&self, name: &str) -> Option<Option<&str>> {
+        let value = match name {
+            "RECEIVER" => self.receiver.as_ref().map(|(_, text)| text),
+            "RECEIVER_TYPE" => self.receiver_type.as_ref(),
+            "CRATE_NAME" => self.crate_name.as_ref(),
+            "EXPECTED_TYPE" => self.expected_type.as_ref(),
+            _ => return None,
+        };
+        Some(value.map(String::as_str))
+    }
+}
+
+fn display_type(ctx: &CompletionContext, ty: Type) -> Option<String> {
+    if ty.is_unknown() {
+        return None;
+    }
+    Some(ty.display(ctx.db).to_string())
+}
+
+/// The type expected by the expression at the cursor, which is only known
+/// when it is written down next to it.
+fn expected_type(ctx: &CompletionContext) -> Option<Type> {
+    let offset = ctx.offset;
+    // Without an expression, the whitespace at the cursor is outside of the
+    // `let` or the field.
+    let parent = skip_trivia_token(ctx.original_token.clone(), Direction::Prev)?.parent();
+
+    if let Some(let_stmt) = parent.ancestors().find_map(ast::LetStmt::cast) {
+        let eq = let_stmt.eq_token()?;
+        if let_stmt.ascribed_type().is_none()
+            || offset < eq.syntax().text_range().end()
+            || let_stmt.semi_token().map_or(false, |it| offset > it.syntax().text_range().start())
+        {
+            return None;
+        }
+        return ctx.sema.type_of_pat(&let_stmt.pat()?);
+    }
+
+    let field = parent.ancestors().find_map(ast::RecordField::cast)?;
+    if offset < field.colon_token()?.syntax().text_range().end() {
+        return None;
+    }
+    let record_lit = field.syntax().ancestors().find_map(ast::RecordLit::cast)?;
+    let name = field.name_ref()?;
+    let ty = ctx.sema.type_of_expr(&ast::Expr::RecordLit(record_lit))?;
+    ty.fields(ctx.db)
+        .into_iter()
+        .find(|(field, _)| field.name(ctx.db).to_string() == name.text().as_str())
+        .map(|(_, ty)| ty)
+}
+
+/// Replaces the variables in the body of a snippet, or returns `None` if one
+/// of them isn't available.
+fn expand_variables(body: &str, vars: &SnippetVariables) -> Option<String> {
+    let mut res = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(idx) = rest.find(|c| c == '$' || c == '\\') {
+        res.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        if rest.starts_with('\\') {
+            // Keep escaped characters as is.
+            let len = rest[1..].chars().next().map_or(1, |c| 1 + c.len_utf8());
+            res.push_str(&rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+        let (name, len) = match variable_at(rest) {
+            Some(it) => it,
+            None => {
+                res.push('$');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        match vars.get(name) {
+            Some(value) => escape(value?, &mut res),
+            None => res.push_str(&rest[..len]),
+        }
+        rest = &rest[len..];
+    }
+    res.push_str(rest);
+    Some(res)
+}
+
+/// Parses `$NAME` or `${NAME}` at the start of `text`, returns the name and
+/// the length of the variable. Tabstops like `$1` are not variables.
+fn variable_at(text: &str) -> Option<(&str, usize)> {
+    let is_name_char = |c: char| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_';
+    let braced = text[1..].starts_with('{');
+    let start = if braced { 2 } else { 1 };
+    let len = text[start..].find(|c| !is_name_char(c)).unwrap_or(text.len() - start);
+    let name = &text[start..start + len];
+    if !name.starts_with(|c: char| c.is_ascii_uppercase() || c == '_') {
+        return None;
+    }
+    if !braced {
+        return Some((name, start + len));
+    }
+    if !text[start + len..].starts_with('}') {
+        return None;
+    }
+    Some((name, start + len + 1))
+}
+
+/// Escapes the characters which have a meaning in snippets.
+fn escape(text: &str, acc: &mut String) {
+    for c in text.chars() {
+        if c == '$' || c == '\\' || c == '}' {
+            acc.push('\\');
+        }
+        acc.push(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{
+        test_utils::do_completion_with_options, CompletionConfig, CompletionKind, Snippet,
+        SnippetScope,
+    };
+
+    fn snippet(label: &str, body: &str, scope: SnippetScope) -> Snippet {
+        Snippet { label: label.to_string(), body: body.to_string(), description: None, scope }
+    }
+
+    fn complete(code: &str, kind: CompletionKind, snippets: Vec<Snippet>) -> Vec<String> {
+        let labels = snippets.iter().map(|it| it.label.clone()).collect::<Vec<_>>();
+        let config = CompletionConfig { snippets, ..CompletionConfig::default() };
+        do_completion_with_options(code, kind, &config)
+            .into_iter()
+            .filter(|it| labels.iter().any(|label| label == it.label()))
+            .map(|it| {
+                let edit = it.text_edit().as_atoms();
+                assert_eq!(edit.len(), 1);
+                format!("{}: {}", it.label(), edit[0].insert)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn completes_custom_snippets_in_scope() {
+        let snippets = || {
+            vec![
+                snippet("log", "log::info!(\"$1\");", SnippetScope::Expr),
+                snippet("fixture", "fn ${1:fixture}() {}", SnippetScope::Item),
+            ]
+        };
+        assert_eq!(
+            complete("fn foo() { <|> }", CompletionKind::Snippet, snippets()),
+            vec!["log: log::info!(\"$1\");"]
+        );
+        assert_eq!(
+            complete("mod tests { <|> }", CompletionKind::Snippet, snippets()),
+            vec!["fixture: fn ${1:fixture}() {}"]
+        );
+        assert_eq!(
+            complete("fn foo() { ::<|> }", CompletionKind::Snippet, snippets()),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn expands_receiver_variables() {
+        assert_eq!(
+            complete(
+                r"
+                struct Meters(u32);
+                fn foo(m: Meters) { m.<|> }
+                ",
+                CompletionKind::Postfix,
+                vec![snippet(
+                    "conv",
+                    "<$RECEIVER_TYPE as Into<${1:T}>>::into(${RECEIVER})",
+                    SnippetScope::Postfix
+                )],
+            ),
+            vec!["conv: <Meters as Into<${1:T}>>::into(m)"]
+        );
+    }
+
+    #[test]
+    fn expands_expected_type() {
+        let snippets = || vec![snippet("new", "$EXPECTED_TYPE::new($0)", SnippetScope::Expr)];
+        assert_eq!(
+            complete(
+                r"
+                struct S<T>(T);
+                fn foo() { let x: S<u8> = <|> }
+                ",
+                CompletionKind::Snippet,
+                snippets(),
+            ),
+            vec!["new: S<u8>::new($0)"]
+        );
+        assert_eq!(
+            complete(
+                r"
+                struct Inner;
+                struct Outer { inner: Inner }
+                fn foo() { Outer { inner: <|> } }
+                ",
+                CompletionKind::Snippet,
+                snippets(),
+            ),
+            vec!["new: Inner::new($0)"]
+        );
+        // Nothing is expected without a type annotation.
+        assert_eq!(
+            complete("fn foo() { let x = <|> }", CompletionKind::Snippet, snippets()),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn expands_crate_name_and_escapes_values() {
+        assert_eq!(
+            complete(
+                r"
+                //- /main.rs
+                fn main() {}
+                //- /my_crate/lib.rs
+                fn foo() { <|> }
+                ",
+                CompletionKind::Snippet,
+                vec![snippet(
+                    "krate",
+                    "${CRATE_NAME}::$0 \\$CRATE_NAME $TM_FILENAME",
+                    SnippetScope::Expr
+                )],
+            ),
+            vec!["krate: my_crate::$0 \\$CRATE_NAME $TM_FILENAME"]
+        );
+        assert_eq!(
+            complete(
+                r"
+                struct S;
+                fn foo(s: &S) { s.<|> }
+                ",
+                CompletionKind::Postfix,
+                vec![snippet("ty", "${RECEIVER_TYPE}", SnippetScope::Postfix)],
+            ),
+            vec!["ty: &S"]
+        );
+    }
+}
@@ -103,6 +103,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_union_field_completion() {
+        assert_debug_snapshot!(
+        do_ref_completion(
+                r"
+                union U { the_field: u32 }
+                fn foo(u: U) {
+                u.<|>
+                }
+                ",
+        ),
+            @r###"
+        [
+            CompletionItem {
+                label: "the_field",
+                source_range: [93; 93),
+                delete: [93; 93),
+                insert: "the_field",
+                kind: Field,
+                detail: "unsafe u32",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn test_struct_field_completion_self() {
         assert_debug_snapshot!(
@@ -401,6 +427,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_dyn_trait_super_trait_method_completion() {
+        assert_debug_snapshot!(
+            do_ref_completion(
+                r"
+            trait Super { fn super_method(&self); }
+            trait Sub: Super { fn sub_method(&self); }
+            fn foo(a: &dyn Sub) {
+               a.<|>
+            }
+            ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "sub_method()",
+                source_range: [159; 159),
+                delete: [159; 159),
+                insert: "sub_method()$0",
+                kind: Method,
+                lookup: "sub_method",
+                detail: "fn sub_method(&self)",
+            },
+            CompletionItem {
+                label: "super_method()",
+                source_range: [159; 159),
+                delete: [159; 159),
+                insert: "super_method()$0",
+                kind: Method,
+                lookup: "super_method",
+                detail: "fn super_method(&self)",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn completes_trait_method_from_other_module() {
         assert_debug_snapshot!(
@@ -77,7 +77,7 @@ mod tests {
                 delete: [246; 246),
                 insert: "X",
                 kind: EnumVariant,
-                detail: "()",
+                detail: "= 0",
             },
             CompletionItem {
                 label: "Z",
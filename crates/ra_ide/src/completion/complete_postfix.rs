@@ -86,7 +86,10 @@ pub(super) fn complete_postfix(acc: &mut Completions, ctx: &CompletionContext) {
         .add_to(acc);
 }
 
-fn get_receiver_text(receiver: &ast::Expr, receiver_is_ambiguous_float_literal: bool) -> String {
+pub(super) fn get_receiver_text(
+    receiver: &ast::Expr,
+    receiver_is_ambiguous_float_literal: bool,
+) -> String {
     if receiver_is_ambiguous_float_literal {
         let text = receiver.syntax().text();
         let without_dot = ..text.len() - TextUnit::of_char('.');
@@ -96,7 +99,7 @@ fn get_receiver_text(receiver: &ast::Expr, receiver_is_ambiguous_float_literal:
     }
 }
 
-fn include_references(initial_element: &ast::Expr) -> ast::Expr {
+pub(super) fn include_references(initial_element: &ast::Expr) -> ast::Expr {
     let mut resulting_element = initial_element.clone();
     while let Some(parent_ref_element) =
         resulting_element.syntax().parent().and_then(ast::RefExpr::cast)
@@ -22,13 +22,17 @@ impl Completions {
         ty: &Type,
     ) {
         let is_deprecated = is_deprecated(field, ctx.db);
+        let mut detail = ty.display(ctx.db).to_string();
+        if field.is_unsafe_to_read() {
+            detail = format!("unsafe {}", detail);
+        }
         CompletionItem::new(
             CompletionKind::Reference,
             ctx.source_range(),
             field.name(ctx.db).to_string(),
         )
         .kind(CompletionItemKind::Field)
-        .detail(ty.display(ctx.db).to_string())
+        .detail(detail)
         .set_documentation(field.docs(ctx.db))
         .set_deprecated(is_deprecated)
         .add_to(self);
@@ -133,7 +137,7 @@ impl Completions {
             let (before, after) = (&docs[..idx], &docs[idx + s.len()..]);
             // Ensure to match the full word
             if after.starts_with('!')
-                && !before.ends_with(|c: char| c == '_' || c.is_ascii_alphanumeric())
+                && !before.ends_with(|c: char| c == '_' || c.is_alphanumeric())
             {
                 // It may have spaces before the braces like `foo! {}`
                 match after[1..].chars().find(|&c| !c.is_whitespace()) {
@@ -266,13 +270,14 @@ impl Completions {
             .into_iter()
             .map(|field| (field.name(ctx.db), field.signature_ty(ctx.db)));
         let variant_kind = variant.kind(ctx.db);
-        let detail = match variant_kind {
-            StructKind::Tuple | StructKind::Unit => detail_types
+        let detail = match (variant_kind, variant.discriminant(ctx.db)) {
+            (StructKind::Unit, Some(discriminant)) => format!("= {}", discriminant),
+            (StructKind::Tuple, _) | (StructKind::Unit, _) => detail_types
                 .map(|(_, t)| t.display(ctx.db).to_string())
                 .sep_by(", ")
                 .surround_with("(", ")")
                 .to_string(),
-            StructKind::Record => detail_types
+            (StructKind::Record, _) => detail_types
                 .map(|(n, t)| format!("{}: {}", n, t.display(ctx.db).to_string()))
                 .sep_by(", ")
                 .surround_with("{ ", " }")
@@ -430,7 +435,8 @@ mod tests {
         do_reference_completion(
             r#"
                 enum Foo {
-                    Foo
+                    Foo,
+                    Bar(i32),
                 }
 
                 fn main() { Foo::Fo<|> }
@@ -438,10 +444,20 @@ mod tests {
         ),
         @r###"
         [
+            CompletionItem {
+                label: "Bar(…)",
+                source_range: [135; 137),
+                delete: [135; 137),
+                insert: "Bar($0)",
+                kind: EnumVariant,
+                lookup: "Bar",
+                detail: "(i32)",
+                trigger_call_info: true,
+            },
             CompletionItem {
                 label: "Foo",
-                source_range: [104; 106),
-                delete: [104; 106),
+                source_range: [135; 137),
+                delete: [135; 137),
                 insert: "Foo",
                 kind: EnumVariant,
                 detail: "()",
@@ -450,6 +466,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn enum_detail_includes_discriminant_for_fieldless_enum() {
+        assert_debug_snapshot!(
+        do_reference_completion(
+            r#"
+                const BASE: u8 = 1 << 4;
+                enum Foo {
+                    Foo = BASE + 1,
+                    Bar,
+                }
+
+                fn main() { Foo::Fo<|> }
+                "#,
+        ),
+        @r###"
+        [
+            CompletionItem {
+                label: "Bar",
+                source_range: [182; 184),
+                delete: [182; 184),
+                insert: "Bar",
+                kind: EnumVariant,
+                detail: "= 18",
+            },
+            CompletionItem {
+                label: "Foo",
+                source_range: [182; 184),
+                delete: [182; 184),
+                insert: "Foo",
+                kind: EnumVariant,
+                detail: "= 17",
+            },
+        ]"###
+        );
+    }
+
     #[test]
     fn sets_deprecated_flag_in_completion_items() {
         assert_debug_snapshot!(
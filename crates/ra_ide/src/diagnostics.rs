@@ -1,25 +1,42 @@
 //! FIXME: write short doc here
 
+mod non_ascii_idents;
+mod spelling;
+
 use std::cell::RefCell;
 
+use either::Either;
 use hir::{
-    diagnostics::{AstDiagnostic, Diagnostic as _, DiagnosticSink},
+    diagnostics::{AstDiagnostic, Diagnostic as _, DiagnosticSink, PrivateItem},
+    Adt, AsAssocItem, AssocItemContainer, FieldSource, HasSource, InFile, ModuleDef, ScopeDef,
     Semantics,
 };
 use itertools::Itertools;
+use ra_assists::utils::insert_use_statement;
 use ra_db::{RelativePath, SourceDatabase, SourceDatabaseExt};
-use ra_ide_db::RootDatabase;
+use ra_ide_db::{imports_locator::ImportsLocator, RootDatabase};
 use ra_prof::profile;
 use ra_syntax::{
-    algo,
-    ast::{self, make, AstNode},
+    algo::{self, find_node_at_offset},
+    ast::{
+        self,
+        edit::{self, IndentLevel},
+        make, AstNode, NameOwner, TypeBoundsOwner, TypeParamsOwner, VisibilityOwner,
+    },
+    SourceFile,
+    SyntaxKind::{ATTR, COMMENT, WHITESPACE},
     SyntaxNode, TextRange, T,
 };
 use ra_text_edit::{TextEdit, TextEditBuilder};
+use rustc_hash::FxHashSet;
+
+use crate::{
+    Diagnostic, FileId, FilePosition, FileSystemEdit, RangeInfo, SourceChange, SourceFileEdit,
+};
 
-use crate::{Diagnostic, FileId, FileSystemEdit, SourceChange, SourceFileEdit};
+pub use self::spelling::{Dictionary, SpellCheckConfig};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Severity {
     Error,
     WeakWarning,
@@ -28,20 +45,97 @@ pub enum Severity {
 pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic> {
     let _p = profile("diagnostics");
     let sema = Semantics::new(db);
-    let parse = db.parse(file_id);
+    let file = sema.parse(file_id);
+    collect_diagnostics(&sema, file_id, file.syntax(), |sink| {
+        if let Some(m) = sema.to_module_def(file_id) {
+            m.diagnostics(db, sink);
+        }
+    })
+}
+
+/// Computes the hints for misspelled words in the given file. These are kept
+/// apart from the other diagnostics, as they are opt-in.
+pub(crate) fn spelling_diagnostics(
+    db: &RootDatabase,
+    file_id: FileId,
+    config: &SpellCheckConfig,
+) -> Vec<Diagnostic> {
+    let _p = profile("spelling_diagnostics");
+    let file = db.parse(file_id).tree();
     let mut res = Vec::new();
+    spelling::check_spelling(&mut res, file.syntax(), config);
+    res
+}
 
-    res.extend(parse.errors().iter().map(|err| Diagnostic {
-        range: err.range(),
-        message: format!("Syntax Error: {}", err),
-        severity: Severity::Error,
-        fix: None,
+/// Computes the diagnostics for the item at the given position only: the
+/// innermost function containing it, or else the innermost non-module item.
+/// This is much cheaper than `diagnostics` for a large file, and is used to
+/// refresh diagnostics while typing.
+pub(crate) fn item_diagnostics(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<Diagnostic>>> {
+    let _p = profile("item_diagnostics");
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let fn_def = find_node_at_offset::<ast::FnDef>(file.syntax(), position.offset);
+    let item = match &fn_def {
+        Some(it) => it.syntax().clone(),
+        None => file
+            .syntax()
+            .token_at_offset(position.offset)
+            .left_biased()?
+            .ancestors()
+            .filter_map(ast::ModuleItem::cast)
+            .find(|it| !matches!(it, ast::ModuleItem::Module(_)))?
+            .syntax()
+            .clone(),
+    };
+    let range = item.text_range();
+    let mut res = collect_diagnostics(&sema, position.file_id, &item, |sink| {
+        if let Some(function) = fn_def.and_then(|it| sema.to_def(&it)) {
+            function.diagnostics(db, sink);
+        }
+    });
+    res.retain(|it| it.range.is_subrange(&range));
+    Some(RangeInfo::new(range, res))
+}
+
+/// Collects the diagnostics for the given node of the file, the semantic
+/// diagnostics of the corresponding definitions are added by `add_hir_diagnostics`.
+fn collect_diagnostics(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    add_hir_diagnostics: impl FnOnce(&mut DiagnosticSink),
+) -> Vec<Diagnostic> {
+    let db = sema.db;
+    let range = node.text_range();
+    let mut res = Vec::new();
+
+    let parse = db.parse(file_id);
+    let mut syntax_errors = parse.errors().to_vec();
+    for &krate in db.relevant_crates(file_id).iter() {
+        for err in db.edition_errors(file_id, krate).iter() {
+            if !syntax_errors.contains(err) {
+                syntax_errors.push(err.clone());
+            }
+        }
+    }
+    res.extend(syntax_errors.iter().filter(|err| err.range().is_subrange(&range)).map(|err| {
+        Diagnostic {
+            range: err.range(),
+            message: format!("Syntax Error: {}", err),
+            severity: Severity::Error,
+            fix: None,
+        }
     }));
 
-    for node in parse.tree().syntax().descendants() {
+    for node in node.descendants() {
         check_unnecessary_braces_in_use_statement(&mut res, file_id, &node);
         check_struct_shorthand_initialization(&mut res, file_id, &node);
     }
+    non_ascii_idents::check_non_ascii_idents(&mut res, node);
     let res = RefCell::new(res);
     let mut sink = DiagnosticSink::new(|d| {
         res.borrow_mut().push(Diagnostic {
@@ -59,7 +153,8 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             .parent()
             .unwrap_or_else(|| RelativePath::new(""))
             .join(&d.candidate);
-        let create_file = FileSystemEdit::CreateFile { source_root, path };
+        let create_file =
+            FileSystemEdit::CreateFile { source_root, path, initial_contents: String::new() };
         let fix = SourceChange::file_system_edit("create module", create_file);
         res.borrow_mut().push(Diagnostic {
             range: d.highlight_range(),
@@ -77,10 +172,37 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
         let fix = if d.missed_fields.iter().any(|it| it.as_tuple_index().is_some()) {
             None
         } else {
+            // Fields whose type is known to implement `Default` are filled
+            // with `Default::default()` rather than a `todo!()`.
+            let defaultable_fields = if d.file == file_id.into() {
+                let file = sema.parse(file_id);
+                d.field_list
+                    .to_node(file.syntax())
+                    .syntax()
+                    .parent()
+                    .and_then(ast::RecordLit::cast)
+                    .map(|lit| {
+                        sema.record_literal_missing_fields(&lit)
+                            .into_iter()
+                            .filter(|(_, ty)| {
+                                ty.as_adt().map_or(false, |it| it.has_default_impl(db))
+                            })
+                            .map(|(field, _)| field.name(db))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+
             let mut field_list = d.ast(db);
             for f in d.missed_fields.iter() {
-                let field =
-                    make::record_field(make::name_ref(&f.to_string()), Some(make::expr_unit()));
+                let value = if defaultable_fields.contains(f) {
+                    make::expr_default()
+                } else {
+                    make::expr_todo()
+                };
+                let field = make::record_field(make::name_ref(&f.to_string()), Some(value));
                 field_list = field_list.append_field(&field);
             }
 
@@ -101,6 +223,87 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             fix,
         })
     })
+    .on::<hir::diagnostics::UnresolvedPath, _>(|d| {
+        let fix = if d.file == file_id.into() {
+            let file = sema.parse(file_id);
+            match d.expr.to_node(file.syntax()) {
+                ast::Expr::PathExpr(path_expr) => unresolved_path_fix(sema, file_id, &path_expr),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        // Name resolution is not complete yet, so names without a fix to
+        // offer are only hinted at.
+        let severity = if fix.is_some() { Severity::Error } else { Severity::WeakWarning };
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity,
+            fix,
+        })
+    })
+    .on::<hir::diagnostics::UnresolvedMethodCall, _>(|d| {
+        if let Some(diagnostic) = unresolved_method_call_diagnostic(sema, file_id, d) {
+            res.borrow_mut().push(diagnostic)
+        }
+    })
+    .on::<hir::diagnostics::UnsatisfiedTraitBound, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: trait_impl_fix(db, Adt::from(d.adt), hir::Trait::from(d.trait_)),
+        })
+    })
+    .on::<hir::diagnostics::PrivateItemAccess, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: private_item_fix(sema, file_id, d.item),
+        })
+    })
+    .on::<hir::diagnostics::TryInNonTryFunction, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::NotAnIterator, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::IndexOutOfBounds, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::MacroRulesWarning, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::MalformedCfg, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: None,
+        })
+    })
     .on::<hir::diagnostics::MissingMatchArms, _>(|d| {
         res.borrow_mut().push(Diagnostic {
             range: d.highlight_range(),
@@ -109,6 +312,14 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             fix: None,
         })
     })
+    .on::<hir::diagnostics::PatternTypeMismatch, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: None,
+        })
+    })
     .on::<hir::diagnostics::MissingOkInTailExpr, _>(|d| {
         let node = d.ast(db);
         let replacement = format!("Ok({})", node.syntax());
@@ -120,10 +331,34 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             severity: Severity::Error,
             fix: Some(fix),
         })
+    })
+    .on::<hir::diagnostics::UseOfMovedValue, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::UnusedMustUse, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::UnusedVariable, _>(|d| {
+        let fix =
+            if d.file == file_id.into() { unused_variable_fix(&d.ast(db), file_id) } else { None };
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
     });
-    if let Some(m) = sema.to_module_def(file_id) {
-        m.diagnostics(db, &mut sink);
-    };
+    add_hir_diagnostics(&mut sink);
     drop(sink);
     res.into_inner()
 }
@@ -205,6 +440,349 @@ fn check_struct_shorthand_initialization(
     Some(())
 }
 
+/// Prefixes the name of an unused binding with `_`, expanding field shorthands
+/// like `S { x }` to `S { x: _x }`.
+fn unused_variable_fix(bind_pat: &ast::BindPat, file_id: FileId) -> Option<SourceChange> {
+    let name = bind_pat.name()?;
+    let range = bind_pat.syntax().text_range();
+    let mut replacement = bind_pat.syntax().text().to_string();
+    replacement.insert((name.syntax().text_range().start() - range.start()).to_usize(), '_');
+    if bind_pat.syntax().parent().and_then(ast::RecordFieldPatList::cast).is_some() {
+        replacement = format!("{}: {}", name.text(), replacement);
+    }
+    Some(SourceChange::source_file_edit_from(
+        format!("prefix `{}` with an underscore", name.text()),
+        file_id,
+        TextEdit::replace(range, replacement),
+    ))
+}
+
+/// Suggests a similarly named value for an unresolved name, like rustc's "did
+/// you mean" hints.
+fn unresolved_path_fix(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    path_expr: &ast::PathExpr,
+) -> Option<SourceChange> {
+    let path = path_expr.path()?;
+    let name_ref = path.segment()?.name_ref()?;
+
+    let mut candidates = Vec::new();
+    sema.scope(path.syntax()).process_all_names(&mut |name, def| match def {
+        ScopeDef::Local(_)
+        | ScopeDef::ModuleDef(ModuleDef::Function(_))
+        | ScopeDef::ModuleDef(ModuleDef::Const(_))
+        | ScopeDef::ModuleDef(ModuleDef::Static(_))
+        | ScopeDef::ModuleDef(ModuleDef::EnumVariant(_))
+        | ScopeDef::ModuleDef(ModuleDef::Adt(Adt::Struct(_))) => candidates.push(name.to_string()),
+        _ => (),
+    });
+    let suggestion = best_match(name_ref.text(), candidates)?;
+
+    let edit = TextEdit::replace(name_ref.syntax().text_range(), suggestion.clone());
+    Some(SourceChange::source_file_edit_from(format!("change to `{}`", suggestion), file_id, edit))
+}
+
+/// Makes an inaccessible item `pub(crate)`, or `pub` if it is defined in
+/// another crate of the workspace.
+fn private_item_fix(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    item: PrivateItem,
+) -> Option<SourceChange> {
+    let db = sema.db;
+    let (src, visibility, krate) = match item {
+        PrivateItem::Function(it) => {
+            let function = hir::Function::from(it);
+            let src = function.source(db);
+            let visibility = src.value.visibility();
+            (src.map(|it| it.syntax().clone()), visibility, function.module(db).krate())
+        }
+        PrivateItem::Field(it) => {
+            let field = hir::StructField::from(it);
+            let krate = field.parent_def(db).module(db).krate();
+            match field.source(db) {
+                InFile { file_id, value: FieldSource::Named(it) } => {
+                    (InFile::new(file_id, it.syntax().clone()), it.visibility(), krate)
+                }
+                InFile { file_id, value: FieldSource::Pos(it) } => {
+                    (InFile::new(file_id, it.syntax().clone()), it.visibility(), krate)
+                }
+            }
+        }
+    };
+    let item_file_id = src.file_id.original_file(db);
+    // Items from macro expansions and libraries can't be edited.
+    if src.file_id != item_file_id.into()
+        || db.source_root(db.file_source_root(item_file_id)).is_library
+    {
+        return None;
+    }
+    let current_krate = sema.to_module_def(file_id)?.krate();
+    let new_visibility = if krate == current_krate { "pub(crate)" } else { "pub" };
+
+    let edit = match visibility {
+        Some(it) => TextEdit::replace(it.syntax().text_range(), new_visibility.to_string()),
+        None => {
+            // The visibility goes after the doc comments and attributes.
+            let offset = src
+                .value
+                .children_with_tokens()
+                .find(|it| match it.kind() {
+                    WHITESPACE | COMMENT | ATTR => false,
+                    _ => true,
+                })
+                .map(|it| it.text_range().start())?;
+            TextEdit::insert(offset, format!("{} ", new_visibility))
+        }
+    };
+    Some(SourceChange::source_file_edit_from(
+        format!("change visibility to {}", new_visibility),
+        item_file_id,
+        edit,
+    ))
+}
+
+/// Generates an `impl Trait for Type` block with stubs for the required
+/// members of the trait, right after the definition of the type. The type may
+/// be defined in another file than the one with the diagnostic.
+fn trait_impl_fix(db: &RootDatabase, adt: Adt, trait_: hir::Trait) -> Option<SourceChange> {
+    let src = match adt {
+        Adt::Struct(it) => it.source(db).map(ast::NominalDef::from),
+        Adt::Union(it) => it.source(db).map(ast::NominalDef::from),
+        Adt::Enum(it) => it.source(db).map(ast::NominalDef::from),
+    };
+    let file_id = src.file_id.original_file(db);
+    // Types from macro expansions and libraries can't be edited.
+    if src.file_id != file_id.into() || db.source_root(db.file_source_root(file_id)).is_library {
+        return None;
+    }
+    let adt_def = src.value;
+    let trait_path = adt.module(db).find_use_path(db, ModuleDef::Trait(trait_))?;
+
+    let generic_params: Vec<(String, String)> = adt_def
+        .type_param_list()
+        .into_iter()
+        .flat_map(|it| it.generic_params())
+        .filter_map(|param| match param {
+            ast::GenericParam::LifetimeParam(it) => {
+                Some((it.syntax().to_string(), it.lifetime_token()?.to_string()))
+            }
+            ast::GenericParam::TypeParam(it) => {
+                let name = it.name()?.text().to_string();
+                let param = match it.type_bound_list() {
+                    Some(bounds) => format!("{}: {}", name, bounds.syntax()),
+                    None => name.clone(),
+                };
+                Some((param, name))
+            }
+            ast::GenericParam::ConstParam(it) => {
+                Some((it.syntax().to_string(), it.name()?.text().to_string()))
+            }
+        })
+        .collect();
+    let (params, args) = if generic_params.is_empty() {
+        (String::new(), String::new())
+    } else {
+        (
+            format!("<{}>", generic_params.iter().map(|(param, _)| param).join(", ")),
+            format!("<{}>", generic_params.iter().map(|(_, arg)| arg).join(", ")),
+        )
+    };
+    let where_clause =
+        adt_def.where_clause().map_or(String::new(), |it| format!(" {}", it.syntax()));
+    let header = format!(
+        "impl{} {} for {}{}{}",
+        params,
+        trait_path,
+        adt_def.name()?.text(),
+        args,
+        where_clause
+    );
+
+    let items = trait_.items(db).into_iter().filter_map(|item| {
+        let item = match item {
+            hir::AssocItem::Function(it) => {
+                let def = it.source(db).value;
+                if def.body().is_some() {
+                    return None;
+                }
+                ast::ImplItem::FnDef(def.with_body(make::block_from_expr(make::expr_todo())))
+            }
+            hir::AssocItem::TypeAlias(it) => {
+                let def = it.source(db).value;
+                if def.type_ref().is_some() {
+                    return None;
+                }
+                ast::ImplItem::TypeAliasDef(def)
+            }
+            hir::AssocItem::Const(it) => {
+                let def = it.source(db).value;
+                if def.body().is_some() {
+                    return None;
+                }
+                ast::ImplItem::ConstDef(def)
+            }
+        };
+        Some(edit::remove_attrs_and_docs(&item))
+    });
+    let item_list = SourceFile::parse(&format!("{} {{}}", header))
+        .tree()
+        .syntax()
+        .descendants()
+        .find_map(ast::ItemList::cast)?
+        .append_items(items);
+
+    let indent = IndentLevel::from_node(adt_def.syntax());
+    let impl_text = format!(
+        "\n\n{:width$}{} {}",
+        "",
+        header,
+        indent.increase_indent(item_list).syntax(),
+        width = indent.0 as usize * 4
+    );
+    let edit = TextEdit::insert(adt_def.syntax().text_range().end(), impl_text);
+    Some(SourceChange::source_file_edit_from(
+        format!("implement `{}` for `{}`", trait_.name(db), adt.name(db)),
+        file_id,
+        edit,
+    ))
+}
+
+/// Offers to import a trait which provides the method, or else to change the
+/// call to the most similarly named method of the receiver.
+///
+/// As method resolution is not complete yet, unresolved methods are only
+/// reported if there is a fix to offer.
+fn unresolved_method_call_diagnostic(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::UnresolvedMethodCall,
+) -> Option<Diagnostic> {
+    if d.file != file_id.into() {
+        return None;
+    }
+    let db = sema.db;
+    let file = sema.parse(file_id);
+    let method_call = ast::MethodCallExpr::cast(d.expr.to_node(file.syntax()).syntax().clone())?;
+    let receiver_ty = sema.type_of_expr(&method_call.expr()?)?;
+    let name_ref = method_call.name_ref()?;
+    let name = name_ref.text();
+    let range = name_ref.syntax().text_range();
+    let scope = sema.scope(method_call.syntax());
+    let module = scope.module()?;
+    let krate = module.krate();
+
+    // The method exists, but its trait needs to be imported first.
+    let trait_to_import = ImportsLocator::new(db).find_imports(name).into_iter().find_map(|it| {
+        let trait_ = match it {
+            Either::Left(ModuleDef::Function(f)) => match f.as_assoc_item(db)?.container(db) {
+                AssocItemContainer::Trait(it) => it,
+                AssocItemContainer::ImplDef(_) => return None,
+            },
+            _ => return None,
+        };
+        let mut trait_candidates = FxHashSet::default();
+        trait_candidates.insert(trait_.into());
+        receiver_ty.iterate_method_candidates(db, krate, &trait_candidates, None, |_, f| {
+            if f.name(db).to_string() == name.as_str() {
+                Some(trait_)
+            } else {
+                None
+            }
+        })
+    });
+    if let Some(trait_) = trait_to_import {
+        let import = module.find_use_path(db, ModuleDef::Trait(trait_))?;
+        let mut builder = TextEditBuilder::default();
+        insert_use_statement(method_call.syntax(), &import, &mut builder);
+        return Some(Diagnostic {
+            range,
+            message: format!(
+                "no method named `{}` found, trait `{}` is not in scope",
+                d.name, import
+            ),
+            severity: Severity::Error,
+            fix: Some(SourceChange::source_file_edit_from(
+                format!("import `{}`", import),
+                file_id,
+                builder.finish(),
+            )),
+        });
+    }
+
+    let mut candidates = Vec::new();
+    receiver_ty.iterate_method_candidates(db, krate, &scope.traits_in_scope(), None, |_, f| {
+        if f.has_self_param(db) {
+            candidates.push(f.name(db).to_string());
+        }
+        None::<()>
+    });
+    let suggestion = best_match(name, candidates)?;
+
+    let edit = TextEdit::replace(range, suggestion.clone());
+    Some(Diagnostic {
+        range,
+        message: format!("no method named `{}` found, did you mean `{}`?", name, suggestion),
+        severity: Severity::Error,
+        fix: Some(SourceChange::source_file_edit_from(
+            format!("change to `{}`", suggestion),
+            file_id,
+            edit,
+        )),
+    })
+}
+
+/// Finds the leading segments of `use` paths which name a crate that is not
+/// in the crate graph. Returns the range of each segment and the crate name.
+pub(crate) fn unresolved_crates(db: &RootDatabase, file_id: FileId) -> Vec<(TextRange, String)> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(file_id);
+    source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::UseTree::cast)
+        .filter_map(|tree| unresolved_crate(&sema, tree))
+        .collect()
+}
+
+fn unresolved_crate(
+    sema: &Semantics<RootDatabase>,
+    tree: ast::UseTree,
+) -> Option<(TextRange, String)> {
+    // In `use foo::{bar, baz}`, only `foo` can name a crate.
+    let is_nested = tree
+        .syntax()
+        .ancestors()
+        .skip(1)
+        .filter_map(ast::UseTree::cast)
+        .any(|it| it.path().is_some());
+    if is_nested {
+        return None;
+    }
+    let mut path = tree.path()?;
+    while let Some(qualifier) = path.qualifier() {
+        path = qualifier;
+    }
+    let name_ref = match path.segment()?.kind()? {
+        ast::PathSegmentKind::Name(it) => it,
+        _ => return None,
+    };
+    let name = name_ref.text();
+    if ["std", "core", "alloc", "proc_macro", "test"].contains(&name.as_str()) {
+        return None;
+    }
+    sema.scope(path.syntax()).module()?;
+    if sema.resolve_path(&path).is_some() {
+        return None;
+    }
+    Some((name_ref.syntax().text_range(), name.to_string()))
+}
+
+fn best_match(name: &str, candidates: Vec<String>) -> Option<String> {
+    stdx::similar_names(name, candidates).into_iter().next()
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
@@ -212,7 +790,7 @@ mod tests {
     use stdx::SepBy;
     use test_utils::assert_eq_text;
 
-    use crate::mock_analysis::{analysis_and_position, single_file};
+    use crate::mock_analysis::{analysis_and_position, single_file, MockAnalysis};
 
     use super::*;
 
@@ -460,7 +1038,7 @@ mod tests {
             }
 
             fn test_fn() {
-                let s = TestStruct{};
+                let _s = TestStruct{};
             }
         ";
         let after = r"
@@ -470,7 +1048,36 @@ mod tests {
             }
 
             fn test_fn() {
-                let s = TestStruct{ one: (), two: ()};
+                let _s = TestStruct{ one: todo!(), two: todo!()};
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_fill_struct_fields_skips_private_fields() {
+        let before = r"
+            mod m {
+                pub struct TestStruct {
+                    pub one: i32,
+                    two: i64,
+                }
+            }
+
+            fn test_fn() {
+                let _s = m::TestStruct{};
+            }
+        ";
+        let after = r"
+            mod m {
+                pub struct TestStruct {
+                    pub one: i32,
+                    two: i64,
+                }
+            }
+
+            fn test_fn() {
+                let _s = m::TestStruct{ one: todo!()};
             }
         ";
         check_apply_diagnostic_fix(before, after);
@@ -485,7 +1092,7 @@ mod tests {
 
             impl TestStruct {
                 fn test_fn() {
-                    let s = Self {};
+                    let _s = Self {};
                 }
             }
         ";
@@ -496,7 +1103,7 @@ mod tests {
 
             impl TestStruct {
                 fn test_fn() {
-                    let s = Self { one: ()};
+                    let _s = Self { one: todo!()};
                 }
             }
         ";
@@ -511,7 +1118,7 @@ mod tests {
             }
 
             impl Expr {
-                fn new_bin(lhs: Box<Expr>, rhs: Box<Expr>) -> Expr {
+                fn new_bin(_lhs: Box<Expr>, _rhs: Box<Expr>) -> Expr {
                     Expr::Bin { <|> }
                 }
             }
@@ -523,8 +1130,8 @@ mod tests {
             }
 
             impl Expr {
-                fn new_bin(lhs: Box<Expr>, rhs: Box<Expr>) -> Expr {
-                    Expr::Bin { lhs: (), rhs: () <|> }
+                fn new_bin(_lhs: Box<Expr>, _rhs: Box<Expr>) -> Expr {
+                    Expr::Bin { lhs: todo!(), rhs: todo!() <|> }
                 }
             }
 
@@ -541,7 +1148,7 @@ mod tests {
             }
 
             fn test_fn() {
-                let s = TestStruct{ two: 2 };
+                let _s = TestStruct{ two: 2 };
             }
         ";
         let after = r"
@@ -551,7 +1158,50 @@ mod tests {
             }
 
             fn test_fn() {
-                let s = TestStruct{ two: 2, one: () };
+                let _s = TestStruct{ two: 2, one: todo!() };
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_fill_struct_fields_with_default_impls() {
+        let before = r"
+            #[derive(Default)]
+            struct Derived;
+            struct Explicit;
+            impl Default for Explicit {
+                fn default() -> Self { Explicit }
+            }
+            struct NoDefault;
+
+            struct TestStruct {
+                derived: Derived,
+                explicit: Explicit,
+                none: NoDefault,
+            }
+
+            fn test_fn() {
+                let _s = TestStruct{};
+            }
+        ";
+        let after = r"
+            #[derive(Default)]
+            struct Derived;
+            struct Explicit;
+            impl Default for Explicit {
+                fn default() -> Self { Explicit }
+            }
+            struct NoDefault;
+
+            struct TestStruct {
+                derived: Derived,
+                explicit: Explicit,
+                none: NoDefault,
+            }
+
+            fn test_fn() {
+                let _s = TestStruct{ derived: Default::default(), explicit: Default::default(), none: todo!()};
             }
         ";
         check_apply_diagnostic_fix(before, after);
@@ -567,7 +1217,7 @@ mod tests {
 
             fn test_fn() {
                 let one = 1;
-                let s = TestStruct{ one, two: 2 };
+                let _s = TestStruct{ one, two: 2 };
             }
         ";
 
@@ -582,9 +1232,9 @@ mod tests {
                 two: i64,
             }
 
-            fn test_fn() {
-                let one = 1;
-                let s = TestStruct{ ..a };
+            fn test_fn(a: TestStruct) {
+                let _one = 1;
+                let _s = TestStruct{ ..a };
             }
         ";
 
@@ -610,6 +1260,7 @@ mod tests {
                                     0,
                                 ),
                                 path: "foo.rs",
+                                initial_contents: "",
                             },
                         ],
                         cursor_position: None,
@@ -621,6 +1272,436 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_macro_rules_diagnostic() {
+        let (analysis, file_id) = single_file("macro_rules! m { ($a:expr, $b:expr) => { $a }; }");
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "unused macro metavariable `$b`",
+                range: [28; 29),
+                fix: None,
+                severity: WeakWarning,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_malformed_cfg_diagnostic() {
+        let (analysis, file_id) = single_file("#[cfg(feature = 1)]\nfn f() {}");
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "value of `cfg` predicate `feature` must be a string literal",
+                range: [0; 19),
+                fix: None,
+                severity: Error,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_mixed_script_identifier_diagnostic() {
+        // The `o` is a Cyrillic letter.
+        let (analysis, file_id) = single_file("fn f() { let p\u{43e}int = 1; p\u{43e}int; }");
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "identifier `pоint` contains mixed scripts",
+                range: [13; 19),
+                fix: None,
+                severity: WeakWarning,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_spelling_diagnostics() {
+        let (analysis, file_id) = single_file(
+            r#"/// Retruns the `lenght` of a [HashMap](https://doc.rust-lang.org/std) entry.
+///
+/// ```
+/// let wrnog = 1;
+/// ```
+fn entry_lenght() {}
+"#,
+        );
+        let config = SpellCheckConfig {
+            dictionary: Dictionary::from_word_list("# words\nreturns\nthe\nof\nentry/S\nlength\n"),
+        };
+        let diagnostics = analysis.spelling_diagnostics(file_id, &config).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "possibly misspelled word `Retruns`",
+                range: [4; 11),
+                fix: None,
+                severity: WeakWarning,
+            },
+            Diagnostic {
+                message: "possibly misspelled word `lenght`",
+                range: [126; 132),
+                fix: None,
+                severity: WeakWarning,
+            },
+        ]
+        "###);
+
+        let diagnostics =
+            analysis.spelling_diagnostics(file_id, &SpellCheckConfig::default()).unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_confusable_identifier_diagnostic() {
+        // The second `a` is a Cyrillic letter.
+        let (analysis, file_id) = single_file("fn f() { let a = 1; let \u{430} = a; \u{430}; }");
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "identifier `а` is confusable with `a`",
+                range: [24; 26),
+                fix: None,
+                severity: WeakWarning,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_no_diagnostic_for_single_script_identifiers() {
+        check_no_diagnostic("fn föö() { let λ = 1; let _x = λ; }");
+    }
+
+    #[test]
+    fn test_unresolved_value_suggestion() {
+        let before = r"
+            struct Length(usize);
+
+            fn test_fn(length: usize) -> usize {
+                length + lenght
+            }
+        ";
+        let after = r"
+            struct Length(usize);
+
+            fn test_fn(length: usize) -> usize {
+                length + length
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_unresolved_method_suggestion() {
+        let before = r"
+            struct S;
+            impl S {
+                fn length(&self) -> usize { 0 }
+                fn new() -> S { S }
+            }
+
+            fn test_fn(s: S) {
+                s.lenght();
+            }
+        ";
+        let after = r"
+            struct S;
+            impl S {
+                fn length(&self) -> usize { 0 }
+                fn new() -> S { S }
+            }
+
+            fn test_fn(s: S) {
+                s.length();
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_unresolved_method_trait_import() {
+        let before = r"
+            mod test_mod {
+                pub trait TestTrait {
+                    fn test_method(&self);
+                }
+                pub struct TestStruct {}
+                impl TestTrait for TestStruct {
+                    fn test_method(&self) {}
+                }
+            }
+
+            fn test_fn(test_struct: test_mod::TestStruct) {
+                test_struct.test_method()
+            }
+        ";
+        let after = r"
+            use test_mod::TestTrait;
+
+            mod test_mod {
+                pub trait TestTrait {
+                    fn test_method(&self);
+                }
+                pub struct TestStruct {}
+                impl TestTrait for TestStruct {
+                    fn test_method(&self) {}
+                }
+            }
+
+            fn test_fn(test_struct: test_mod::TestStruct) {
+                test_struct.test_method()
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_unresolved_names_without_suggestion() {
+        let (analysis, file_id) = single_file(
+            r"
+            struct S;
+
+            fn test_fn(s: S) {
+                let _x = completely_unknown;
+                s.completely_unknown();
+            }
+            ",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "cannot find value `completely_unknown` in this scope");
+        assert_eq!(diagnostics[0].severity, Severity::WeakWarning);
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_unsatisfied_trait_bound_fix() {
+        let (analysis, file_position) = analysis_and_position(
+            r#"
+            //- /main.rs
+            mod foo;
+            use foo::{Foo, Greet};
+            fn greet<T: Greet>(t: T) {}
+            fn main() {
+                greet(Foo<|>);
+            }
+            //- /foo.rs
+            pub trait Greet {
+                type Name;
+                /// Greets politely.
+                fn greet(&self) -> u32;
+                fn wave(&self) {}
+            }
+            pub struct Foo;
+        "#,
+        );
+        let diagnostics = analysis.diagnostics(file_position.file_id).unwrap();
+        let diagnostic = diagnostics
+            .into_iter()
+            .find(|it| it.message == "the trait bound `Foo: Greet` is not satisfied")
+            .unwrap();
+        let mut fix = diagnostic.fix.unwrap();
+        assert_eq!(fix.label, "implement `Greet` for `Foo`");
+        let edit = fix.source_file_edits.pop().unwrap();
+        assert_ne!(edit.file_id, file_position.file_id);
+        let actual = edit.edit.apply(&analysis.file_text(edit.file_id).unwrap());
+        assert_eq_text!(
+            r#"pub trait Greet {
+    type Name;
+    /// Greets politely.
+    fn greet(&self) -> u32;
+    fn wave(&self) {}
+}
+pub struct Foo;
+
+impl Greet for Foo {
+    type Name;
+    fn greet(&self) -> u32 { todo!() }
+}
+"#,
+            &actual
+        );
+    }
+
+    #[test]
+    fn test_private_item_access_fix() {
+        let before = r"
+            mod m {
+                pub struct S;
+                impl S {
+                    /// Creates an `S`.
+                    fn new() -> S { S }
+                }
+            }
+            fn test_fn() {
+                m::S::new();
+            }
+        ";
+        let after = r"
+            mod m {
+                pub struct S;
+                impl S {
+                    /// Creates an `S`.
+                    pub(crate) fn new() -> S { S }
+                }
+            }
+            fn test_fn() {
+                m::S::new();
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_private_field_access_fix_in_other_file() {
+        let (analysis, file_position) = analysis_and_position(
+            r"
+            //- /main.rs
+            mod foo;
+            fn test_fn(s: foo::S) -> u32 {
+                s.x<|>
+            }
+            //- /foo.rs
+            pub struct S { pub(self) x: u32 }
+        ",
+        );
+        let diagnostic = analysis.diagnostics(file_position.file_id).unwrap().pop().unwrap();
+        assert_eq!(diagnostic.message, "field `x` is private");
+        let mut fix = diagnostic.fix.unwrap();
+        assert_eq!(fix.label, "change visibility to pub(crate)");
+        let edit = fix.source_file_edits.pop().unwrap();
+        assert_ne!(edit.file_id, file_position.file_id);
+        let actual = edit.edit.apply(&analysis.file_text(edit.file_id).unwrap());
+        assert_eq_text!("pub struct S { pub(crate) x: u32 }\n", &actual);
+    }
+
+    #[test]
+    fn test_unused_variable_fix() {
+        let before = r"
+            fn test_fn() {
+                let mut count = 0;
+            }
+        ";
+        let after = r"
+            fn test_fn() {
+                let mut _count = 0;
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_unused_variable_fix_in_field_shorthand() {
+        let before = r"
+            struct S { a: i32, b: i32 }
+
+            fn test_fn(s: S) -> i32 {
+                let S { a, b } = s;
+                a
+            }
+        ";
+        let after = r"
+            struct S { a: i32, b: i32 }
+
+            fn test_fn(s: S) -> i32 {
+                let S { a, b: _b } = s;
+                a
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_unused_variable_only_assigned() {
+        let (analysis, file_id) = single_file(
+            r"
+            fn test_fn(flag: bool) {
+                let mut x = 0;
+                if flag {
+                    x = 1;
+                }
+            }
+            ",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let messages = diagnostics.iter().map(|it| it.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(messages, vec!["unused variable: `x`"]);
+    }
+
+    #[test]
+    fn test_no_unused_variable_diagnostic() {
+        check_no_diagnostic(
+            r"
+            struct S;
+
+            fn used(x: i32, _y: i32) -> i32 {
+                let z = |_: S| x;
+                let mut w = 0;
+                w += 1;
+                z(S) + w
+            }
+
+            #[allow(unused)]
+            fn allowed(x: i32) {}
+
+            #[allow(unused_variables)]
+            fn allowed_by_name(x: i32) {}
+
+            enum E { A(i32), B(i32) }
+
+            fn or_pattern(e: E) -> i32 {
+                match e {
+                    E::A(x) | E::B(x) => x,
+                }
+            }
+
+            trait T {
+                fn no_body(&self, x: i32);
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn test_unresolved_crates() {
+        let mock = MockAnalysis::with_files(
+            r"
+            //- /main.rs
+            use dep::Foo;
+            use local::Bar;
+            use missing::{Baz, baz::Quux};
+            use {other::X, crate::local::Y, self::local::Z};
+            use std::mem;
+
+            mod local {
+                pub struct Bar;
+                pub struct Y;
+                pub struct Z;
+            }
+
+            //- /dep/lib.rs
+            pub struct Foo;
+            ",
+        );
+        let file_id = mock.id_of("/main.rs");
+        let analysis = mock.analysis();
+        let names = analysis
+            .unresolved_crates(file_id)
+            .unwrap()
+            .into_iter()
+            .map(|(_range, name)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["missing", "other"]);
+    }
+
     #[test]
     fn test_check_unnecessary_braces_in_use_statement() {
         check_not_applicable(
@@ -719,4 +1800,33 @@ fn main() {
             check_struct_shorthand_initialization,
         );
     }
+
+    #[test]
+    fn test_item_diagnostics() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+            //- /lib.rs
+            struct S { foo: i32, bar: () }
+            fn outside() -> S { S { foo: 92 } }
+            fn inside() -> S {
+                S { <|>foo: 92 }
+            }
+            "#,
+        );
+        let item = analysis.item_diagnostics(position).unwrap().unwrap();
+        let messages = item.info.iter().map(|it| it.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(messages, vec!["Missing structure fields:\n- bar"]);
+        assert!(item.info[0].range.is_subrange(&item.range));
+        assert_eq!(analysis.diagnostics(position.file_id).unwrap().len(), 2);
+
+        let (analysis, position) = analysis_and_position(
+            r#"
+            //- /lib.rs
+            fn foo() {}
+            <|>
+            fn bar() {}
+            "#,
+        );
+        assert!(analysis.item_diagnostics(position).unwrap().is_none());
+    }
 }
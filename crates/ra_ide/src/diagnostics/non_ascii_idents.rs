@@ -0,0 +1,116 @@
+//! Checks for non-ASCII identifiers that are easy to misread, mirroring the
+//! groundwork of rustc's `non_ascii_idents` lint group.
+//!
+//! Two cases are reported: identifiers mixing characters of several scripts,
+//! and identifiers that differ from another identifier in the same item but
+//! look the same once confusable characters are normalized.
+
+use ra_syntax::{ast, AstNode, SmolStr, SyntaxNode};
+use rustc_hash::FxHashMap;
+
+use crate::{diagnostics::Severity, Diagnostic};
+
+pub(super) fn check_non_ascii_idents(acc: &mut Vec<Diagnostic>, node: &SyntaxNode) {
+    let mut skeletons: FxHashMap<String, SmolStr> = FxHashMap::default();
+    for name in node.descendants().filter_map(ast::Name::cast) {
+        let text = name.text().clone();
+        if text.is_ascii() {
+            skeletons.entry(skeleton(&text)).or_insert(text);
+            continue;
+        }
+        let range = name.syntax().text_range();
+        if is_mixed_script(&text) {
+            acc.push(Diagnostic {
+                range,
+                message: format!("identifier `{}` contains mixed scripts", text),
+                severity: Severity::WeakWarning,
+                fix: None,
+            });
+        }
+        let other = skeletons.entry(skeleton(&text)).or_insert_with(|| text.clone());
+        if *other != text {
+            acc.push(Diagnostic {
+                range,
+                message: format!("identifier `{}` is confusable with `{}`", text, other),
+                severity: Severity::WeakWarning,
+                fix: None,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Common,
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Han,
+    Other,
+}
+
+fn script(c: char) -> Script {
+    match c as u32 {
+        0x30..=0x39 | 0x5F | 0xB7 => Script::Common,
+        0x41..=0x5A | 0x61..=0x7A | 0xAA | 0xBA | 0xC0..=0x24F | 0x1E00..=0x1EFF => Script::Latin,
+        0x370..=0x3FF | 0x1F00..=0x1FFF => Script::Greek,
+        0x400..=0x52F | 0x1C80..=0x1C8F | 0x2DE0..=0x2DFF | 0xA640..=0xA69F => Script::Cyrillic,
+        0x530..=0x58F => Script::Armenian,
+        0x590..=0x5FF => Script::Hebrew,
+        0x600..=0x6FF | 0x750..=0x77F => Script::Arabic,
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0x20000..=0x2A6DF => Script::Han,
+        _ => Script::Other,
+    }
+}
+
+fn is_mixed_script(text: &str) -> bool {
+    let mut scripts = text.chars().map(script).filter(|&it| it != Script::Common);
+    let first = match scripts.next() {
+        Some(it) => it,
+        None => return false,
+    };
+    // We don't know the script of the characters outside of our table, so
+    // don't report them.
+    first != Script::Other && scripts.any(|it| it != first && it != Script::Other)
+}
+
+/// Maps characters that look like a Latin letter or digit onto it, a small
+/// subset of the Unicode confusables table.
+fn skeleton(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'а' | 'α' => 'a',
+            'Α' | 'А' => 'A',
+            'Β' | 'В' => 'B',
+            'с' | 'ϲ' => 'c',
+            'С' | 'Ϲ' => 'C',
+            'е' | 'ε' => 'e',
+            'Ε' | 'Е' => 'E',
+            'Η' | 'Н' => 'H',
+            'і' | 'ι' => 'i',
+            'Ι' | 'І' => 'I',
+            'ј' => 'j',
+            'Ј' => 'J',
+            'Κ' | 'К' => 'K',
+            'Μ' | 'М' => 'M',
+            'Ν' => 'N',
+            'о' | 'ο' => 'o',
+            'Ο' | 'О' => 'O',
+            'р' | 'ρ' => 'p',
+            'Ρ' | 'Р' => 'P',
+            'ѕ' => 's',
+            'Ѕ' => 'S',
+            'Τ' | 'Т' => 'T',
+            'υ' => 'u',
+            'х' | 'χ' => 'x',
+            'Χ' | 'Х' => 'X',
+            'у' => 'y',
+            'Υ' | 'Ү' => 'Y',
+            'Ζ' => 'Z',
+            _ => c,
+        })
+        .collect()
+}
@@ -0,0 +1,212 @@
+//! Checks the spelling of the words in doc comments and of the segments of
+//! identifiers against a dictionary supplied by the user.
+//!
+//! Code spans, fenced code blocks and links in doc comments are skipped, as
+//! are words containing digits, acronyms and words shorter than three letters.
+//! Identifiers are split at underscores and case changes, so `HttpClient` and
+//! `http_client` are both checked as `http` and `client`.
+
+use std::{fmt, iter, sync::Arc};
+
+use ra_syntax::{ast, AstNode, NodeOrToken, SyntaxNode, TextRange, TextUnit};
+use rustc_hash::FxHashSet;
+
+use crate::{diagnostics::Severity, Diagnostic};
+
+#[derive(Clone, Debug, Default)]
+pub struct SpellCheckConfig {
+    pub dictionary: Dictionary,
+}
+
+/// A set of correctly spelled words, compared case-insensitively.
+#[derive(Clone, Default)]
+pub struct Dictionary {
+    words: Arc<FxHashSet<String>>,
+}
+
+impl Dictionary {
+    /// Reads a word list with one word per line. Empty lines and lines
+    /// starting with `#` are ignored, as are hunspell-style affix flags after
+    /// a `/`.
+    pub fn from_word_list(text: &str) -> Dictionary {
+        let words = text
+            .lines()
+            .map(|line| line.split('/').next().unwrap_or_default().trim())
+            .filter(|word| !word.is_empty() && !word.starts_with('#'))
+            .map(|word| word.to_lowercase())
+            .collect();
+        Dictionary { words: Arc::new(words) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        self.words.contains(&word) || KNOWN_WORDS.contains(&word.as_str())
+    }
+}
+
+impl fmt::Debug for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dictionary({} words)", self.words.len())
+    }
+}
+
+/// Abbreviations which are common in Rust code, but rarely part of a
+/// dictionary.
+const KNOWN_WORDS: &[&str] = &[
+    "args", "async", "bool", "buf", "cfg", "ctx", "deref", "dyn", "enum", "eprintln", "fmt",
+    "impl", "impls", "init", "iter", "len", "lhs", "mut", "println", "ptr", "repr", "rhs", "rustc",
+    "rustdoc", "rustfmt", "src", "std", "stdin", "stdout", "stderr", "str", "struct", "tmp",
+    "usize", "isize", "utf", "vec",
+];
+
+const MIN_WORD_LEN: usize = 3;
+
+pub(super) fn check_spelling(
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+    config: &SpellCheckConfig,
+) {
+    if config.dictionary.is_empty() {
+        return;
+    }
+    let mut in_fence = false;
+    for element in node.descendants_with_tokens() {
+        match element {
+            NodeOrToken::Node(node) => {
+                if let Some(name) = ast::Name::cast(node) {
+                    let start = name.syntax().text_range().start();
+                    let text = name.text().as_str();
+                    let (start, text) = if text.starts_with("r#") {
+                        (start + TextUnit::of_str("r#"), &text["r#".len()..])
+                    } else {
+                        (start, text)
+                    };
+                    check_words(acc, &config.dictionary, start, text);
+                }
+            }
+            NodeOrToken::Token(token) => {
+                let comment = match ast::Comment::cast(token.clone()) {
+                    Some(it) if it.kind().doc.is_some() => it,
+                    // A fence can't continue past the end of a doc comment.
+                    _ => {
+                        if !token.kind().is_trivia() {
+                            in_fence = false;
+                        }
+                        continue;
+                    }
+                };
+                let text = comment.text().as_str();
+                let body_start = comment.prefix().len();
+                let body_end = if comment.kind().shape.is_block() && text.ends_with("*/") {
+                    text.len() - "*/".len()
+                } else {
+                    text.len()
+                };
+                let mut offset =
+                    comment.syntax().text_range().start() + TextUnit::from_usize(body_start);
+                for line in text[body_start..body_end.max(body_start)].split('\n') {
+                    if line.trim_start().trim_start_matches('*').trim_start().starts_with("```") {
+                        in_fence = !in_fence;
+                    } else if !in_fence {
+                        check_words(acc, &config.dictionary, offset, &mask_code_and_links(line));
+                    }
+                    offset += TextUnit::of_str(line) + TextUnit::of_char('\n');
+                }
+            }
+        }
+    }
+}
+
+fn check_words(acc: &mut Vec<Diagnostic>, dictionary: &Dictionary, start: TextUnit, text: &str) {
+    for (offset, word) in words(text) {
+        let is_acronym = word.chars().all(char::is_uppercase);
+        if word.chars().count() < MIN_WORD_LEN || is_acronym || dictionary.contains(word) {
+            continue;
+        }
+        acc.push(Diagnostic {
+            range: TextRange::offset_len(
+                start + TextUnit::from_usize(offset),
+                TextUnit::of_str(word),
+            ),
+            message: format!("possibly misspelled word `{}`", word),
+            severity: Severity::WeakWarning,
+            fix: None,
+        });
+    }
+}
+
+/// Replaces code spans and links with spaces, keeping the offsets of the
+/// remaining words intact.
+fn mask_code_and_links(line: &str) -> String {
+    let mut res = String::with_capacity(line.len());
+    let mut in_code = false;
+    for (i, chunk) in line.split(' ').enumerate() {
+        if i > 0 {
+            res.push(' ');
+        }
+        let is_link = chunk.contains("://");
+        for c in chunk.chars() {
+            if c == '`' {
+                in_code = !in_code;
+            }
+            if in_code || is_link || c == '`' {
+                res.extend(iter::repeat(' ').take(c.len_utf8()));
+            } else {
+                res.push(c);
+            }
+        }
+    }
+    res
+}
+
+/// Splits `text` into words with their offsets. Runs of letters are split at
+/// case changes, like in `HashMap` or `HTTPServer`, and runs containing digits
+/// are skipped. Apostrophes inside of a word are kept, as in `doesn't`.
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut res = Vec::new();
+    let mut run_start = None;
+    for (idx, c) in text.char_indices().chain(iter::once((text.len(), ' '))) {
+        let is_apostrophe = c == '\''
+            && run_start.is_some()
+            && text[idx + 1..].chars().next().map_or(false, char::is_alphabetic);
+        if c.is_alphanumeric() || is_apostrophe {
+            if run_start.is_none() {
+                run_start = Some(idx);
+            }
+            continue;
+        }
+        if let Some(start) = run_start.take() {
+            let run = &text[start..idx];
+            if !run.chars().any(char::is_numeric) {
+                res.extend(
+                    split_case(run).into_iter().map(|(offset, word)| (start + offset, word)),
+                );
+            }
+        }
+    }
+    res
+}
+
+fn split_case(run: &str) -> Vec<(usize, &str)> {
+    let chars: Vec<(usize, char)> = run.char_indices().collect();
+    let mut res = Vec::new();
+    let mut start = 0;
+    for (i, &(idx, c)) in chars.iter().enumerate().skip(1) {
+        let prev = chars[i - 1].1;
+        let next_is_lower = chars.get(i + 1).map_or(false, |&(_, it)| it.is_lowercase());
+        if c.is_uppercase() && (prev.is_lowercase() || (prev.is_uppercase() && next_is_lower)) {
+            res.push((start, &run[start..idx]));
+            start = idx;
+        }
+    }
+    res.push((start, &run[start..]));
+    res
+}
@@ -0,0 +1,245 @@
+//! Evaluating a single function with user-supplied arguments.
+//!
+//! We don't have an interpreter, so instead we generate a tiny binary which
+//! depends on the crate of the function, calls it and prints the result. The
+//! binary lives in a scratch package outside of the user's sources, so only
+//! functions which other crates can name are supported. When a function can't
+//! be evaluated, the error says why, so that the user isn't left guessing.
+
+use hir::{ModuleDef, Semantics, VisibleFrom};
+use itertools::Itertools;
+use ra_db::SourceDatabase;
+use ra_ide_db::RootDatabase;
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner, TypeParamsOwner},
+    SyntaxKind,
+};
+
+use crate::FilePosition;
+
+#[derive(Debug)]
+pub struct FunctionHarness {
+    /// The name of the evaluated function.
+    pub name: String,
+    /// The `main.rs` of the scratch package, which calls the function and
+    /// prints the result.
+    pub main: String,
+}
+
+pub(crate) fn function_harness(
+    db: &RootDatabase,
+    position: FilePosition,
+    args: &str,
+) -> Result<FunctionHarness, String> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let no_function = || "there is no function at the cursor".to_string();
+    let fn_def = find_node_at_offset::<ast::FnDef>(file.syntax(), position.offset)
+        .ok_or_else(no_function)?;
+    let name = fn_def.name().ok_or_else(no_function)?.text().to_string();
+    if !is_pure_free_fn(&fn_def) {
+        return Err(format!(
+            "`{}` can't be evaluated: only free functions which aren't generic, `async` or \
+             `unsafe` and don't take `self` or `&mut` parameters can be evaluated",
+            name
+        ));
+    }
+
+    // The scratch package is another crate, so it can only call functions
+    // which are `pub` all the way from the crate root.
+    let not_public = |item: &str| {
+        format!(
+            "`{}` can't be evaluated: it is called from a scratch crate, but {} is not public",
+            name, item
+        )
+    };
+    let function = sema.to_def(&fn_def).ok_or_else(no_function)?;
+    if ModuleDef::Function(function).visible_from(db) != VisibleFrom::Everywhere {
+        return Err(not_public("the function"));
+    }
+    let module = function.module(db);
+    let crate_name = db.crate_graph()[module.krate().into()]
+        .display_name
+        .as_ref()
+        .ok_or_else(|| format!("`{}` can't be evaluated: its crate has no name", name))?
+        .to_string();
+    let mut segments = vec![crate_name];
+    for module in module.path_to_root(db).into_iter().rev() {
+        // the crate root has no name
+        let module_name = match module.name(db) {
+            Some(it) => it,
+            None => continue,
+        };
+        if ModuleDef::Module(module).visible_from(db) != VisibleFrom::Everywhere {
+            return Err(not_public(&format!("module `{}`", module_name)));
+        }
+        segments.push(module_name.to_string());
+    }
+    segments.push(name.clone());
+
+    let main = format!(
+        "fn main() {{\n    let result = {path}({args});\n    println!(\"{{:#?}}\", result);\n}}\n",
+        path = segments.iter().join("::"),
+        args = args.trim(),
+    );
+    Ok(FunctionHarness { name, main })
+}
+
+/// We can't tell whether a function has side effects, but we can at least
+/// rule out the functions which can't be called without further context.
+fn is_pure_free_fn(fn_def: &ast::FnDef) -> bool {
+    let in_module = match fn_def.syntax().parent() {
+        Some(parent) => match parent.kind() {
+            SyntaxKind::SOURCE_FILE => true,
+            SyntaxKind::ITEM_LIST => {
+                parent.parent().map_or(false, |it| it.kind() == SyntaxKind::MODULE)
+            }
+            _ => false,
+        },
+        None => false,
+    };
+    let param_list = match fn_def.param_list() {
+        Some(it) => it,
+        None => return false,
+    };
+    in_module
+        && fn_def.body().is_some()
+        && fn_def.async_kw_token().is_none()
+        && fn_def.unsafe_kw_token().is_none()
+        && fn_def.type_param_list().is_none()
+        && param_list.self_param().is_none()
+        && !param_list
+            .params()
+            .filter_map(|param| param.ascribed_type())
+            .any(|ty| ty.syntax().descendants().any(|it| it.kind() == SyntaxKind::MUT_KW))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::analysis_and_position;
+
+    fn check_harness(fixture: &str, args: &str, call: &str) {
+        let (analysis, position) = analysis_and_position(fixture);
+        let harness = analysis.function_harness(position, args).unwrap().unwrap();
+        assert!(harness.main.contains(call), "{}", harness.main);
+    }
+
+    fn check_no_harness(fixture: &str, reason: &str) {
+        let (analysis, position) = analysis_and_position(fixture);
+        let err = analysis.function_harness(position, "").unwrap().unwrap_err();
+        assert!(err.contains(reason), "{}", err);
+    }
+
+    #[test]
+    fn harness_for_free_fn() {
+        check_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            pub fn add(a: i32, b: i32) -> i32 { a<|> + b }
+            "#,
+            " 1, 2 ",
+            "let result = foo::add(1, 2);",
+        );
+    }
+
+    #[test]
+    fn harness_for_fn_in_modules() {
+        check_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            pub mod bar {
+                pub mod baz {
+                    pub fn double(s: &str) -> String { <|>s.repeat(2) }
+                }
+            }
+            "#,
+            "\"ab\"",
+            "let result = foo::bar::baz::double(\"ab\");",
+        );
+    }
+
+    #[test]
+    fn no_harness_for_private_fns() {
+        check_no_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            fn add(a: i32, b: i32) -> i32 { a<|> + b }
+            "#,
+            "the function is not public",
+        );
+        check_no_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            mod bar {
+                pub fn add(a: i32, b: i32) -> i32 { a<|> + b }
+            }
+            "#,
+            "module `bar` is not public",
+        );
+    }
+
+    #[test]
+    fn no_harness_for_impure_fns() {
+        check_no_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            pub struct S;
+            impl S {
+                pub fn get(&self) -> i32 { <|>92 }
+            }
+            "#,
+            "only free functions",
+        );
+        check_no_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            pub fn push(v: &mut Vec<i32>) { <|>v.push(92) }
+            "#,
+            "only free functions",
+        );
+        check_no_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            pub fn id<T>(t: T) -> T { <|>t }
+            "#,
+            "only free functions",
+        );
+        check_no_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            pub async fn foo() -> i32 { <|>92 }
+            "#,
+            "only free functions",
+        );
+    }
+
+    #[test]
+    fn no_harness_outside_of_fns() {
+        check_no_harness(
+            r#"
+            //- /main.rs
+            fn main() {}
+            //- /foo/lib.rs
+            pub struct S<|>;
+            "#,
+            "there is no function at the cursor",
+        );
+    }
+}
@@ -209,6 +209,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn goto_def_in_included_file() {
+        check_goto(
+            "
+            //- /lib.rs
+            #[rustc_builtin_macro]
+            macro_rules! include {() => {}}
+
+            include!(\"foo.rs\");
+
+            fn main() {
+                bar<|>();
+            }
+
+            //- /foo.rs
+            fn bar() -> u32 { 0 }
+            ",
+            "bar FN_DEF FileId(2) [0; 21) [3; 6)",
+            "fn bar() -> u32 { 0 }|bar",
+        );
+    }
+
     #[test]
     fn goto_def_for_macros() {
         covers!(ra_ide_db::goto_def_for_macros);
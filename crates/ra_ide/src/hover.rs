@@ -2,20 +2,15 @@
 //! source code items (e.g. function call, struct field, variable symbol...)
 
 use hir::{
-    Adt, AsAssocItem, AssocItemContainer, FieldSource, HasSource, HirDisplay, ModuleDef,
-    ModuleSource, Semantics,
+    Adt, AsAssocItem, AssocItemContainer, BindingMode, Docs, FieldSource, HasSource, HasVisibility,
+    HirDisplay, Local, ModuleDef, ModuleSource, Mutability, Semantics, VisibleFrom,
 };
 use ra_db::SourceDatabase;
 use ra_ide_db::{
     defs::{classify_name, classify_name_ref, Definition},
     RootDatabase,
 };
-use ra_syntax::{
-    ast::{self, DocCommentsOwner},
-    match_ast, AstNode,
-    SyntaxKind::*,
-    SyntaxToken, TokenAtOffset,
-};
+use ra_syntax::{ast, match_ast, AstNode, SyntaxKind::*, SyntaxToken, TokenAtOffset};
 
 use crate::{
     display::{macro_label, rust_code_markup, rust_code_markup_with_doc, ShortLabel},
@@ -114,36 +109,49 @@ fn hover_text_from_name_kind(db: &RootDatabase, def: Definition) -> Option<Strin
     return match def {
         Definition::Macro(it) => {
             let src = it.source(db);
-            hover_text(src.value.doc_comment_text(), Some(macro_label(&src.value)), mod_path)
+            hover_text(it.docs(db).map(Into::into), Some(macro_label(&src.value)), mod_path)
         }
         Definition::StructField(it) => {
             let src = it.source(db);
             match src.value {
-                FieldSource::Named(it) => {
-                    hover_text(it.doc_comment_text(), it.short_label(), mod_path)
+                FieldSource::Named(field) => {
+                    hover_text(it.docs(db).map(Into::into), field.short_label(), mod_path)
                 }
                 _ => None,
             }
         }
-        Definition::ModuleDef(it) => match it {
-            ModuleDef::Module(it) => match it.definition_source(db).value {
-                ModuleSource::Module(it) => {
-                    hover_text(it.doc_comment_text(), it.short_label(), mod_path)
+        Definition::ModuleDef(def) => {
+            let text = match def {
+                ModuleDef::Module(it) => match it.definition_source(db).value {
+                    ModuleSource::Module(module) => {
+                        hover_text(it.docs(db).map(Into::into), module.short_label(), mod_path)
+                    }
+                    _ => None,
+                },
+                ModuleDef::Function(it) => from_def_source(db, it, mod_path),
+                ModuleDef::Adt(Adt::Struct(it)) => from_def_source(db, it, mod_path),
+                ModuleDef::Adt(Adt::Union(it)) => from_def_source(db, it, mod_path),
+                ModuleDef::Adt(Adt::Enum(it)) => from_def_source(db, it, mod_path),
+                ModuleDef::EnumVariant(it) => {
+                    let src = it.source(db);
+                    let label = src.value.short_label().map(|label| match it.discriminant(db) {
+                        Some(discriminant) => format!("{} = {}", label, discriminant),
+                        None => label,
+                    });
+                    hover_text(it.docs(db).map(Into::into), label, mod_path)
                 }
-                _ => None,
-            },
-            ModuleDef::Function(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Struct(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Union(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Enum(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::EnumVariant(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Const(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Static(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Trait(it) => from_def_source(db, it, mod_path),
-            ModuleDef::TypeAlias(it) => from_def_source(db, it, mod_path),
-            ModuleDef::BuiltinType(it) => Some(it.to_string()),
-        },
-        Definition::Local(it) => Some(rust_code_markup(&it.ty(db).display_truncated(db, None))),
+                ModuleDef::Const(it) => from_def_source(db, it, mod_path),
+                ModuleDef::Static(it) => from_def_source(db, it, mod_path),
+                ModuleDef::Trait(it) => from_def_source(db, it, mod_path),
+                ModuleDef::TypeAlias(it) => from_def_source(db, it, mod_path),
+                ModuleDef::BuiltinType(it) => Some(it.to_string()),
+            }?;
+            match visible_to(db, def) {
+                Some(modules) => Some(format!("{}\n\nvisible to: {}", text, modules)),
+                None => Some(text),
+            }
+        }
+        Definition::Local(it) => Some(rust_code_markup(&local_label(db, it))),
         Definition::TypeParam(_) | Definition::SelfType(_) => {
             // FIXME: Hover for generic param
             None
@@ -152,14 +160,55 @@ fn hover_text_from_name_kind(db: &RootDatabase, def: Definition) -> Option<Strin
 
     fn from_def_source<A, D>(db: &RootDatabase, def: D, mod_path: Option<String>) -> Option<String>
     where
-        D: HasSource<Ast = A>,
-        A: ast::DocCommentsOwner + ast::NameOwner + ShortLabel,
+        D: HasSource<Ast = A> + Docs,
+        A: ast::NameOwner + ShortLabel,
     {
         let src = def.source(db);
-        hover_text(src.value.doc_comment_text(), src.value.short_label(), mod_path)
+        hover_text(def.docs(db).map(Into::into), src.value.short_label(), mod_path)
     }
 }
 
+/// Lists the modules from which `def` can be named, if that's less than what
+/// its visibility says, like for a `pub` item in a private module.
+fn visible_to(db: &RootDatabase, def: ModuleDef) -> Option<String> {
+    let assoc = match def {
+        ModuleDef::Function(it) => it.as_assoc_item(db),
+        ModuleDef::Const(it) => it.as_assoc_item(db),
+        ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+        _ => None,
+    };
+    let declared: VisibleFrom = match assoc {
+        // Items of traits are as visible as the trait itself.
+        Some(assoc) => match assoc.container(db) {
+            AssocItemContainer::Trait(it) => return visible_to(db, ModuleDef::Trait(it)),
+            AssocItemContainer::ImplDef(_) => assoc.visibility(db),
+        },
+        None => def.module(db)?.visibility_of(db, &def)?,
+    }
+    .into();
+    let modules = match def.visible_from(db) {
+        VisibleFrom::Modules(modules) if declared != VisibleFrom::Modules(modules.clone()) => {
+            modules
+        }
+        _ => return None,
+    };
+    if modules.is_empty() {
+        return None;
+    }
+    let paths = modules.into_iter().map(|module| {
+        once("crate".to_string())
+            .chain(
+                module
+                    .path_to_root(db)
+                    .into_iter()
+                    .rev()
+                    .filter_map(|it| it.name(db).map(|name| name.to_string())),
+            )
+            .join("::")
+    });
+    Some(paths.join(", "))
+}
+
 pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeInfo<HoverResult>> {
     let sema = Semantics::new(db);
     let file = sema.parse(position.file_id).syntax().clone();
@@ -225,6 +274,23 @@ fn pick_best(tokens: TokenAtOffset<SyntaxToken>) -> Option<SyntaxToken> {
     }
 }
 
+/// Shows just the type of a plain binding. Bindings which are `mut` or bind by
+/// reference (either explicitly or through match ergonomics) also show their
+/// name and binding mode, e.g. `x: &mut String (by ref mut)`.
+fn local_label(db: &RootDatabase, local: Local) -> String {
+    let ty = local.ty(db).display_truncated(db, None).to_string();
+    let name = match local.name(db) {
+        Some(it) => it,
+        None => return ty,
+    };
+    match local.binding_mode(db) {
+        BindingMode::Move if local.is_mut(db) => format!("mut {}: {}", name, ty),
+        BindingMode::Move => ty,
+        BindingMode::Ref(Mutability::Shared) => format!("{}: {} (by ref)", name, ty),
+        BindingMode::Ref(Mutability::Mut) => format!("{}: {} (by ref mut)", name, ty),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ra_db::FileLoader;
@@ -462,7 +528,7 @@ fn main() {
         "#,
             &["
 Option
-None
+None = 0
 ```
 
 The None variant
@@ -492,6 +558,26 @@ The Some variant
         );
     }
 
+    #[test]
+    fn hover_enum_variant_discriminant() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            const FLAG: u32 = 1 << 3;
+            enum Flags {
+                A = 1,
+                B = FLAG | 1,
+                C,
+            }
+            fn main() {
+                let c = Flags::<|>C;
+            }
+        "#,
+            &["Flags
+C = 10"],
+        );
+    }
+
     #[test]
     fn hover_for_local_variable() {
         let (analysis, position) = single_file_with_position("fn func(foo: i32) { fo<|>o; }");
@@ -524,6 +610,42 @@ fn func(foo: i32) { if true { <|>foo; }; }
         assert_eq!(trim_markup_opt(hover.info.first()), Some("i32"));
     }
 
+    #[test]
+    fn hover_local_binding_modes() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct String;
+            fn main() {
+                let mut s = String;
+                if let Some(<|>x) = &mut Some(s) {}
+            }
+            enum Option<T> { Some(T), None }
+            use Option::Some;
+            "#,
+            &["x: &mut String (by ref mut)"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let ref <|>x = 92u32;
+            }
+            "#,
+            &["x: &u32 (by ref)"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let mut x = 92u32;
+                <|>x += 1;
+            }
+            "#,
+            &["mut x: u32"],
+        );
+    }
+
     #[test]
     fn test_hover_infer_associated_method_result() {
         let (analysis, position) = single_file_with_position(
@@ -545,6 +667,43 @@ fn func(foo: i32) { if true { <|>foo; }; }
         assert_eq!(trim_markup_opt(hover.info.first()), Some("Thing"));
     }
 
+    #[test]
+    fn hover_shows_where_item_is_visible() {
+        let (analysis, position) = single_file_with_position(
+            "
+            mod private {
+                pub fn foo() {}
+            }
+
+            fn main() {
+                private::foo<|>();
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(
+            trim_markup_opt(hover.info.first()),
+            Some("private\npub fn foo()\n```\n\nvisible to: crate")
+        );
+
+        let (analysis, position) = single_file_with_position(
+            "
+            mod outer {
+                pub mod inner {
+                    pub fn reachable() {}
+                }
+            }
+            pub use outer::inner;
+
+            fn main() {
+                inner::reachable<|>();
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(trim_markup_opt(hover.info.first()), Some("outer::inner\npub fn reachable()"));
+    }
+
     #[test]
     fn test_hover_infer_associated_method_exact() {
         let (analysis, position) = single_file_with_position(
@@ -844,4 +1003,40 @@ fn func(foo: i32) { if true { <|>foo; }; }
             &["fn foo()\n```\n\n<- `\u{3000}` here"],
         );
     }
+
+    #[test]
+    fn test_hover_doc_attrs_are_merged() {
+        check_hover_result(
+            r#"
+            //- /lib.rs
+            /// Doc comment
+            #[doc = " doc attribute"]
+            #[doc(hidden)]
+            ///
+            ///   indented
+            fn foo() {}
+
+            fn bar() {
+                fo<|>o();
+            }
+            "#,
+            &["fn foo()\n```\n\nDoc comment\ndoc attribute\n\n  indented"],
+        );
+    }
+
+    #[test]
+    fn test_hover_doc_hidden_only() {
+        check_hover_result(
+            r#"
+            //- /lib.rs
+            #[doc(hidden)]
+            fn foo() {}
+
+            fn bar() {
+                fo<|>o();
+            }
+            "#,
+            &["fn foo()"],
+        );
+    }
 }
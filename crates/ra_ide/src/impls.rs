@@ -24,7 +24,7 @@ pub(crate) fn goto_implementation(
     } else if let Some(trait_def) = find_node_at_offset::<ast::TraitDef>(&syntax, position.offset) {
         return Some(RangeInfo::new(
             trait_def.syntax().text_range(),
-            impls_for_trait(&sema, &trait_def, krate)?,
+            impls_for_trait(&sema, &trait_def)?,
         ));
     }
 
@@ -56,11 +56,10 @@ fn impls_for_def(
 fn impls_for_trait(
     sema: &Semantics<RootDatabase>,
     node: &ast::TraitDef,
-    krate: Crate,
 ) -> Option<Vec<NavigationTarget>> {
     let tr = sema.to_def(node)?;
 
-    let impls = ImplDef::for_trait(sema.db, krate, tr);
+    let impls = ImplDef::for_trait_in_all_crates(sema.db, tr);
 
     Some(impls.into_iter().map(|imp| imp.to_nav(sema.db)).collect())
 }
@@ -168,6 +167,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn goto_implementation_for_trait_in_dependent_crate() {
+        check_goto(
+            "
+            //- /main.rs
+            use foo::T;
+            struct Bar;
+            impl T for Bar {}
+            //- /foo/lib.rs
+            pub trait T<|> {}
+            pub struct Foo;
+            impl T for Foo {}
+            ",
+            &["impl IMPL_DEF FileId(1) [24; 41)", "impl IMPL_DEF FileId(2) [31; 48)"],
+        );
+    }
+
     #[test]
     fn goto_implementation_all_impls() {
         check_goto(
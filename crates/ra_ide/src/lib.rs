@@ -22,10 +22,12 @@ mod prime_caches;
 mod status;
 mod completion;
 mod runnables;
+mod function_harness;
 mod goto_definition;
 mod goto_type_definition;
 mod extend_selection;
 mod hover;
+mod moniker;
 mod call_hierarchy;
 mod call_info;
 mod syntax_highlighting;
@@ -65,16 +67,24 @@ use ra_syntax::{SourceFile, TextRange, TextUnit};
 use crate::display::ToNav;
 
 pub use crate::{
-    assists::{Assist, AssistId},
+    assists::{Assist, AssistConfig, AssistId, BodyWrapper, UnresolvedAssist},
     call_hierarchy::CallItem,
-    completion::{CompletionConfig, CompletionItem, CompletionItemKind, InsertTextFormat},
-    diagnostics::Severity,
+    completion::{
+        CompletionConfig, CompletionItem, CompletionItemKind, InsertTextFormat, Snippet,
+        SnippetScope,
+    },
+    diagnostics::{Dictionary, Severity, SpellCheckConfig},
     display::{file_structure, FunctionSignature, NavigationTarget, StructureNode},
     expand_macro::ExpandedMacro,
     folding_ranges::{Fold, FoldKind},
+    function_harness::FunctionHarness,
     hover::HoverResult,
     inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
-    references::{Declaration, Reference, ReferenceAccess, ReferenceKind, ReferenceSearchResult},
+    moniker::{MonikerIdentifier, MonikerKind, MonikerResult},
+    references::{
+        Declaration, Reference, ReferenceAccess, ReferenceKind, ReferenceSearchResult,
+        RenameConflict,
+    },
     runnables::{Runnable, RunnableKind, TestId},
     source_change::{FileSystemEdit, SourceChange, SourceFileEdit},
     ssr::SsrError,
@@ -92,7 +102,7 @@ pub use ra_ide_db::{
     line_index::{LineCol, LineIndex},
     line_index_utils::translate_offset_with_edit,
     search::SearchScope,
-    symbol_index::Query,
+    symbol_index::{Query, SymbolCache},
     RootDatabase,
 };
 
@@ -388,6 +398,14 @@ impl Analysis {
         self.with_db(|db| hover::hover(db, position))
     }
 
+    /// Computes stable, cross-crate identifiers for the symbol at position.
+    pub fn moniker(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<Vec<MonikerResult>>>> {
+        self.with_db(|db| moniker::moniker(db, position))
+    }
+
     /// Computes parameter information for the given call expression.
     pub fn call_info(&self, position: FilePosition) -> Cancelable<Option<CallInfo>> {
         self.with_db(|db| call_info::call_info(db, position))
@@ -436,6 +454,17 @@ impl Analysis {
         self.with_db(|db| runnables::runnables(db, file_id))
     }
 
+    /// Returns a binary which calls the function at `position` with `args`, if
+    /// the function can be called from another crate without any context, or
+    /// the reason why it can't be.
+    pub fn function_harness(
+        &self,
+        position: FilePosition,
+        args: &str,
+    ) -> Cancelable<Result<FunctionHarness, String>> {
+        self.with_db(|db| function_harness::function_harness(db, position, args))
+    }
+
     /// Computes syntax highlighting for the given file
     pub fn highlight(&self, file_id: FileId) -> Cancelable<Vec<HighlightedRange>> {
         self.with_db(|db| syntax_highlighting::highlight(db, file_id, None))
@@ -462,8 +491,29 @@ impl Analysis {
 
     /// Computes assists (aka code actions aka intentions) for the given
     /// position.
-    pub fn assists(&self, frange: FileRange) -> Cancelable<Vec<Assist>> {
-        self.with_db(|db| assists::assists(db, frange))
+    pub fn assists(&self, frange: FileRange, config: &AssistConfig) -> Cancelable<Vec<Assist>> {
+        self.with_db(|db| assists::assists(db, config, frange))
+    }
+
+    /// Computes assists for the given position without their edits, which
+    /// are computed by `resolve_assist` once an assist is picked.
+    pub fn unresolved_assists(
+        &self,
+        frange: FileRange,
+        config: &AssistConfig,
+    ) -> Cancelable<Vec<UnresolvedAssist>> {
+        self.with_db(|db| assists::unresolved_assists(db, config, frange))
+    }
+
+    /// Computes the edit of the assist with the given id and label.
+    pub fn resolve_assist(
+        &self,
+        frange: FileRange,
+        config: &AssistConfig,
+        id: &str,
+        label: &str,
+    ) -> Cancelable<Option<Assist>> {
+        self.with_db(|db| assists::resolve_assist(db, config, frange, id, label))
     }
 
     /// Computes the set of diagnostics for the given file.
@@ -471,6 +521,32 @@ impl Analysis {
         self.with_db(|db| diagnostics::diagnostics(db, file_id))
     }
 
+    /// Computes the hints for misspelled words in doc comments and
+    /// identifiers of the given file.
+    pub fn spelling_diagnostics(
+        &self,
+        file_id: FileId,
+        config: &SpellCheckConfig,
+    ) -> Cancelable<Vec<Diagnostic>> {
+        self.with_db(|db| diagnostics::spelling_diagnostics(db, file_id, config))
+    }
+
+    /// Computes the diagnostics for the item at the given position only,
+    /// together with the range of the item. Returns `None` if the position is
+    /// not inside of an item.
+    pub fn item_diagnostics(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<Vec<Diagnostic>>>> {
+        self.with_db(|db| diagnostics::item_diagnostics(db, position))
+    }
+
+    /// Returns the crates named by `use` items in the given file which are
+    /// not part of the crate graph, together with the ranges of their names.
+    pub fn unresolved_crates(&self, file_id: FileId) -> Cancelable<Vec<(TextRange, String)>> {
+        self.with_db(|db| diagnostics::unresolved_crates(db, file_id))
+    }
+
     /// Returns the edit required to rename reference at the position to the new
     /// name.
     pub fn rename(
@@ -481,6 +557,16 @@ impl Analysis {
         self.with_db(|db| references::rename(db, position, new_name))
     }
 
+    /// Returns the places where renaming the reference at the position to the
+    /// new name would change the meaning of the code or break it.
+    pub fn rename_conflicts(
+        &self,
+        position: FilePosition,
+        new_name: &str,
+    ) -> Cancelable<Vec<RenameConflict>> {
+        self.with_db(|db| references::rename_conflicts(db, position, new_name))
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
@@ -493,6 +579,12 @@ impl Analysis {
     }
 
     /// Performs an operation on that may be Canceled.
+    /// Collects the symbols of the local files, to be saved for the next
+    /// session.
+    pub fn symbol_cache(&self) -> Cancelable<SymbolCache> {
+        self.with_db(|db| symbol_index::local_symbol_cache(db))
+    }
+
     fn with_db<F: FnOnce(&RootDatabase) -> T + std::panic::UnwindSafe, T>(
         &self,
         f: F,
@@ -509,7 +601,11 @@ fn analysis_is_send() {
 
 #[cfg(test)]
 mod tests {
-    use crate::{display::NavigationTarget, mock_analysis::single_file, Query};
+    use crate::{
+        display::NavigationTarget,
+        mock_analysis::{single_file, MockAnalysis},
+        AnalysisChange, Query, SymbolCache,
+    };
     use ra_syntax::{
         SmolStr,
         SyntaxKind::{FN_DEF, STRUCT_DEF},
@@ -558,6 +654,27 @@ mod foo {
         assert_eq!(s.container_name(), Some(&SmolStr::new("foo")));
     }
 
+    #[test]
+    fn test_world_symbols_include_local_items() {
+        let code = r#"
+mod outer {
+    fn foo() {
+        let f = |x: u32| x;
+        let g = || {
+            struct FooInner;
+        };
+    }
+}
+    "#;
+
+        let mut symbols = get_symbols_matching(code, "FooInner");
+
+        let s = symbols.pop().unwrap();
+
+        assert_eq!(s.name(), "FooInner");
+        assert_eq!(s.container_name(), Some(&SmolStr::new("outer::foo::{closure#1}")));
+    }
+
     #[test]
     fn test_world_symbols_are_case_sensitive() {
         let code = r#"
@@ -575,6 +692,87 @@ struct Foo;
         assert_eq!(struct_match, Some(STRUCT_DEF));
     }
 
+    #[test]
+    fn test_symbol_cache_roundtrip() {
+        let (analysis, _) = single_file(
+            r#"
+mod foo {
+    fn bar() {}
+}
+struct Baz;
+"#,
+        );
+        let cache = analysis.symbol_cache().unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(SymbolCache::from_text(&cache.to_text()), Some(cache));
+        assert_eq!(SymbolCache::from_text("some other file"), None);
+    }
+
+    #[test]
+    fn test_symbol_cache_rejects_corrupt_text() {
+        let (analysis, _) = single_file("fn foo() {}");
+        let text = analysis.symbol_cache().unwrap().to_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let symbol: Vec<&str> = lines[2].split('\t').collect();
+        let with_symbol =
+            |symbol: &[&str]| format!("{}\n{}\n{}\n", lines[0], lines[1], symbol.join("\t"));
+
+        assert!(SymbolCache::from_text(&with_symbol(&symbol)).is_some());
+        // truncated
+        assert_eq!(SymbolCache::from_text(&format!("{}\n{}\n", lines[0], lines[1])), None);
+        // unknown syntax kind
+        let mut bad_kind = symbol.clone();
+        bad_kind[0] = "65535";
+        assert_eq!(SymbolCache::from_text(&with_symbol(&bad_kind)), None);
+        // inverted range
+        let mut bad_range = symbol.clone();
+        bad_range.swap(1, 2);
+        assert_eq!(SymbolCache::from_text(&with_symbol(&bad_range)), None);
+        // written by a version with other syntax kinds
+        let old = text.replacen(lines[0], "rust-analyzer symbol cache 1", 1);
+        assert_eq!(SymbolCache::from_text(&old), None);
+    }
+
+    #[test]
+    fn test_world_symbols_from_symbol_cache() {
+        let code = "fn foo() {}";
+        let (analysis, _) = single_file(code);
+        // Unchanged files take their symbols from the cache, so a renamed
+        // symbol in the cache shows that the file was not parsed again.
+        let text = analysis.symbol_cache().unwrap().to_text().replace("\tfoo\t", "\tbar\t");
+        let cache = SymbolCache::from_text(&text).unwrap();
+
+        let mut mock = MockAnalysis::new();
+        mock.add_file("/main.rs", code);
+        let mut host = mock.analysis_host();
+        let mut change = AnalysisChange::new();
+        change.set_symbol_cache(cache);
+        host.apply_change(change);
+
+        let symbols = host.analysis().symbol_search(Query::new("bar".into())).unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name(), "bar");
+    }
+
+    #[test]
+    fn test_symbol_cache_drops_stale_files() {
+        let (analysis, _) = single_file("fn foo() {}");
+        let stale = analysis.symbol_cache().unwrap();
+
+        let mut mock = MockAnalysis::new();
+        mock.add_file("/main.rs", "fn bar() {}");
+        let mut host = mock.analysis_host();
+        let mut change = AnalysisChange::new();
+        change.set_symbol_cache(stale);
+        host.apply_change(change);
+
+        let cache = host.analysis().symbol_cache().unwrap();
+        assert_eq!(cache.len(), 1);
+        assert!(cache.to_text().contains("\tbar\t"));
+        assert!(!cache.to_text().contains("\tfoo\t"));
+    }
+
     fn get_symbols_matching(text: &str, query: &str) -> Vec<NavigationTarget> {
         let (analysis, _) = single_file(text);
         analysis.symbol_search(Query::new(query.into())).unwrap()
@@ -0,0 +1,221 @@
+//! Computes monikers, identifiers for symbols which stay the same across
+//! crates and tools, so that external indexes can link definitions and uses.
+//!
+//! The identifier consists of the cargo package, its version and the path of
+//! the item, like `regex 1.3.9 regex::Regex::new`.
+
+use std::fmt;
+
+use hir::{AsAssocItem, AssocItemContainer, ModuleDef, Semantics};
+use ra_db::SourceDatabase;
+use ra_ide_db::{
+    defs::{classify_name, classify_name_ref, Definition},
+    RootDatabase,
+};
+use ra_syntax::{ast, match_ast, AstNode, SyntaxKind::*, SyntaxToken, TokenAtOffset};
+
+use crate::{FilePosition, RangeInfo};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonikerKind {
+    /// The symbol is defined in another crate.
+    Import,
+    /// The symbol is defined in the crate of the current file.
+    Export,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonikerResult {
+    pub identifier: MonikerIdentifier,
+    pub kind: MonikerKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonikerIdentifier {
+    /// The name of the cargo package, or of the crate if it isn't part of one.
+    pub package_name: String,
+    pub version: Option<String>,
+    /// The path of the symbol, starting with the crate name.
+    pub path: Vec<String>,
+}
+
+impl fmt::Display for MonikerIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.package_name)?;
+        if let Some(version) = &self.version {
+            write!(f, " {}", version)?;
+        }
+        write!(f, " {}", self.path.join("::"))
+    }
+}
+
+pub(crate) fn moniker(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<MonikerResult>>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id).syntax().clone();
+    let original_token = pick_best(file.token_at_offset(position.offset))?;
+    let token = sema.descend_into_macros(original_token.clone());
+
+    let def = match_ast! {
+        match (token.parent()) {
+            ast::NameRef(name_ref) => classify_name_ref(&sema, &name_ref)?.definition(),
+            ast::Name(name) => classify_name(&sema, &name)?.definition(),
+            _ => return None,
+        }
+    };
+    let current_crate = sema.to_module_def(position.file_id)?.krate();
+
+    let module = match &def {
+        Definition::ModuleDef(ModuleDef::Module(it)) => *it,
+        _ => def.module(db)?,
+    };
+    let krate = module.krate();
+    let crate_data = &db.crate_graph()[krate.into()];
+    let crate_name = crate_data.display_name.as_ref()?.to_string();
+
+    let mut path = vec![crate_name.clone()];
+    path.extend(
+        module
+            .path_to_root(db)
+            .into_iter()
+            .rev()
+            .filter_map(|it| it.name(db))
+            .map(|it| it.to_string()),
+    );
+    match def {
+        Definition::ModuleDef(ModuleDef::Module(_)) => {}
+        Definition::Local(_) | Definition::TypeParam(_) | Definition::SelfType(_) => return None,
+        _ => {
+            if let Some(owner) = owner_name(db, &def) {
+                path.push(owner);
+            }
+            path.push(def.name(db)?.to_string());
+        }
+    }
+
+    let identifier = MonikerIdentifier {
+        package_name: crate_data.env.get("CARGO_PKG_NAME").unwrap_or(crate_name),
+        version: crate_data.env.get("CARGO_PKG_VERSION"),
+        path,
+    };
+    let kind = if krate == current_crate { MonikerKind::Export } else { MonikerKind::Import };
+    Some(RangeInfo::new(original_token.text_range(), vec![MonikerResult { identifier, kind }]))
+}
+
+/// The name of the item containing `def` which isn't a module, like the enum of
+/// an enum variant.
+fn owner_name(db: &RootDatabase, def: &Definition) -> Option<String> {
+    let name = match def {
+        Definition::StructField(it) => it.parent_def(db).name(db),
+        Definition::ModuleDef(ModuleDef::EnumVariant(it)) => it.parent_enum(db).name(db),
+        Definition::ModuleDef(it) => {
+            let assoc_item = match it {
+                ModuleDef::Function(it) => it.as_assoc_item(db),
+                ModuleDef::Const(it) => it.as_assoc_item(db),
+                ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+                _ => None,
+            }?;
+            match assoc_item.container(db) {
+                AssocItemContainer::Trait(it) => it.name(db),
+                AssocItemContainer::ImplDef(it) => it.target_ty(db).as_adt()?.name(db),
+            }
+        }
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+fn pick_best(tokens: TokenAtOffset<SyntaxToken>) -> Option<SyntaxToken> {
+    return tokens.max_by_key(priority);
+    fn priority(n: &SyntaxToken) -> usize {
+        match n.kind() {
+            IDENT => 2,
+            kind if kind.is_trivia() => 0,
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::analysis_and_position;
+
+    fn check(ra_fixture: &str, expected: &str) {
+        let (analysis, position) = analysis_and_position(ra_fixture);
+        let monikers = analysis.moniker(position).unwrap().unwrap().info;
+        let actual = monikers
+            .iter()
+            .map(|it| format!("{:?} {}", it.kind, it.identifier))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn moniker_for_imported_function() {
+        check(
+            r#"
+            //- /main.rs
+            fn main() {
+                foo::bar::baz<|>();
+            }
+
+            //- /foo/lib.rs
+            pub mod bar {
+                pub fn baz() {}
+            }
+            "#,
+            "Import foo foo::bar::baz",
+        );
+    }
+
+    #[test]
+    fn moniker_for_exported_method() {
+        check(
+            r#"
+            //- /main.rs
+            fn main() {}
+
+            //- /foo/lib.rs
+            pub struct Foo;
+            impl Foo {
+                pub fn new<|>() -> Foo { Foo }
+            }
+            "#,
+            "Export foo foo::Foo::new",
+        );
+    }
+
+    #[test]
+    fn moniker_for_enum_variant() {
+        check(
+            r#"
+            //- /main.rs
+            fn main() {
+                let _ = foo::E::V<|>;
+            }
+
+            //- /foo/lib.rs
+            pub enum E { V }
+            "#,
+            "Import foo foo::E::V",
+        );
+    }
+
+    #[test]
+    fn no_moniker_for_locals() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+            //- /main.rs
+            fn main() {
+                let x<|> = 92;
+            }
+
+            //- /foo/lib.rs
+            "#,
+        );
+        assert!(analysis.moniker(position).unwrap().is_none());
+    }
+}
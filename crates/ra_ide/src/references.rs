@@ -26,7 +26,8 @@ use ra_syntax::{
 
 use crate::{display::TryToNav, FilePosition, FileRange, NavigationTarget, RangeInfo};
 
-pub(crate) use self::rename::rename;
+pub use self::rename::RenameConflict;
+pub(crate) use self::rename::{rename, rename_conflicts};
 
 pub use ra_ide_db::search::{Reference, ReferenceAccess, ReferenceKind};
 
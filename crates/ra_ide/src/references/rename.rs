@@ -1,19 +1,30 @@
 //! FIXME: write short doc here
 
-use hir::{ModuleSource, Semantics};
+use hir::{
+    AsAssocItem, AssocItem, AssocItemContainer, HirDisplay, Local, ModuleDef, ModuleSource,
+    ScopeDef, Semantics, SemanticsScope,
+};
 use ra_db::{RelativePath, RelativePathBuf, SourceDatabaseExt};
-use ra_ide_db::RootDatabase;
+use ra_ide_db::{defs::Definition, RootDatabase};
 use ra_syntax::{
     algo::find_node_at_offset, ast, lex_single_valid_syntax_kind, AstNode, SyntaxKind, SyntaxNode,
 };
 use ra_text_edit::TextEdit;
 
 use crate::{
-    FilePosition, FileSystemEdit, RangeInfo, Reference, ReferenceKind, SourceChange,
+    FilePosition, FileRange, FileSystemEdit, RangeInfo, Reference, ReferenceKind, SourceChange,
     SourceFileEdit, TextRange,
 };
 
-use super::find_all_refs;
+use super::{find_all_refs, find_name};
+
+/// A place where renaming to the new name would change the meaning of the
+/// code or make it fail to compile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameConflict {
+    pub file_range: FileRange,
+    pub message: String,
+}
 
 pub(crate) fn rename(
     db: &RootDatabase,
@@ -146,6 +157,257 @@ fn rename_reference(
     Some(RangeInfo::new(range, SourceChange::source_file_edits("rename", edit)))
 }
 
+/// Finds the conflicts a rename would introduce: other definitions with the
+/// new name in the scope of the declaration or of a reference, and fields or
+/// methods which already use the new name.
+pub(crate) fn rename_conflicts(
+    db: &RootDatabase,
+    position: FilePosition,
+    new_name: &str,
+) -> Vec<RenameConflict> {
+    let sema = Semantics::new(db);
+    let syntax = sema.parse(position.file_id).syntax().clone();
+    let opt_name = sema.find_node_at_offset_with_descend::<ast::Name>(&syntax, position.offset);
+    let def = match find_name(&sema, &syntax, position, opt_name) {
+        Some(it) => it.info,
+        None => return Vec::new(),
+    };
+    let refs = match find_all_refs(db, position, None) {
+        Some(it) => it.info,
+        None => return Vec::new(),
+    };
+    let decl_range = FileRange {
+        file_id: refs.declaration().nav.file_id(),
+        range: refs.declaration().nav.range(),
+    };
+    let new_name = new_name.trim_start_matches("r#");
+
+    let mut res = Vec::new();
+    let conflict = |message: String| RenameConflict { file_range: decl_range, message };
+    match def {
+        Definition::StructField(field) => {
+            let fields = field.parent_def(db).fields(db);
+            if fields.into_iter().any(|it| it != field && it.name(db).to_string() == new_name) {
+                res.push(conflict(format!("field `{}` already exists", new_name)));
+            }
+            return res;
+        }
+        Definition::ModuleDef(ModuleDef::Function(func)) => {
+            if let Some(item) = func.as_assoc_item(db) {
+                let scope = sema.scope(&syntax);
+                if let Some(message) = assoc_item_conflict(&sema, &scope, item, new_name) {
+                    res.push(conflict(message));
+                }
+                return res;
+            }
+        }
+        _ => (),
+    }
+
+    let namespace = match definition_namespace(&def) {
+        Some(it) => it,
+        None => return res,
+    };
+    let outer_def = def_in_scope(&sema, decl_range, namespace, new_name);
+    if let (Definition::ModuleDef(it), Some(ScopeDef::ModuleDef(existing))) = (&def, outer_def) {
+        if *it != existing && it.module(db) == existing.module(db) {
+            res.push(conflict(format!("`{}` is already defined in this module", new_name)));
+        }
+    }
+
+    // A reference is captured if another definition of the new name is
+    // declared between the renamed definition and the reference.
+    for reference in refs.references() {
+        let file_range = reference.file_range;
+        let existing = match def_in_scope(&sema, file_range, namespace, new_name) {
+            Some(it) => it,
+            None => continue,
+        };
+        if Some(existing) != outer_def && !is_same_def(&existing, &def) {
+            res.push(RenameConflict {
+                file_range,
+                message: format!("`{}` would refer to {} here", new_name, describe(existing)),
+            });
+        }
+    }
+
+    // Conversely, the renamed local captures later uses of an outer local.
+    if let (Definition::Local(local), Some(outer_def @ ScopeDef::Local(_))) = (def, outer_def) {
+        res.extend(captured_uses(&sema, decl_range, local, outer_def, new_name));
+    }
+    res
+}
+
+fn assoc_item_conflict(
+    sema: &Semantics<RootDatabase>,
+    scope: &SemanticsScope<RootDatabase>,
+    item: AssocItem,
+    new_name: &str,
+) -> Option<String> {
+    let db = sema.db;
+    let (siblings, container) = match item.container(db) {
+        AssocItemContainer::ImplDef(it) => (it.items(db), "impl"),
+        AssocItemContainer::Trait(it) => (it.items(db), "trait"),
+    };
+    let has_name = |it: AssocItem| match it {
+        AssocItem::Function(it) => it.name(db).to_string() == new_name,
+        AssocItem::Const(it) => it.name(db).map_or(false, |name| name.to_string() == new_name),
+        AssocItem::TypeAlias(it) => it.name(db).to_string() == new_name,
+    };
+    if siblings.into_iter().any(|it| it != item && has_name(it)) {
+        return Some(format!("`{}` is already defined in this {}", new_name, container));
+    }
+
+    // An inherent method shadows trait methods of the same name, and clashes
+    // with inherent methods of other impls.
+    let impl_def = match item.container(db) {
+        AssocItemContainer::ImplDef(it) if it.target_trait(db).is_none() => it,
+        _ => return None,
+    };
+    let krate = impl_def.krate(db);
+    let ty = impl_def.target_ty(db);
+    ty.iterate_method_candidates(db, krate, &scope.traits_in_scope(), None, |_, func| {
+        if AssocItem::Function(func) != item && has_name(AssocItem::Function(func)) {
+            Some(format!("`{}` already has a method `{}`", ty.display(db), new_name))
+        } else {
+            None
+        }
+    })
+}
+
+/// The uses of `outer` after the declaration of `local`, which would refer to
+/// `local` once it is renamed to the name of `outer`.
+fn captured_uses(
+    sema: &Semantics<RootDatabase>,
+    decl_range: FileRange,
+    local: Local,
+    outer: ScopeDef,
+    new_name: &str,
+) -> Vec<RenameConflict> {
+    let old_name = match local.name(sema.db) {
+        Some(it) => it.to_string(),
+        None => return Vec::new(),
+    };
+    let file = sema.parse(decl_range.file_id);
+    file.syntax()
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .filter(|it| it.text() == new_name)
+        .map(|it| FileRange { file_id: decl_range.file_id, range: it.syntax().text_range() })
+        .filter(|it| it.range.start() >= decl_range.range.end())
+        .filter(|&file_range| {
+            def_in_scope(sema, file_range, Namespace::Values, new_name) == Some(outer)
+                && def_in_scope(sema, file_range, Namespace::Values, &old_name)
+                    == Some(ScopeDef::Local(local))
+        })
+        .map(|file_range| RenameConflict {
+            file_range,
+            message: format!("`{}` would refer to the renamed variable here", new_name),
+        })
+        .collect()
+}
+
+fn describe(def: ScopeDef) -> &'static str {
+    match def {
+        ScopeDef::Local(_) => "another local variable",
+        ScopeDef::GenericParam(_) => "a type parameter",
+        ScopeDef::MacroDef(_) => "another macro",
+        _ => "another item",
+    }
+}
+
+/// The definition `name` resolves to in `namespace` at the unqualified name
+/// in `file_range`.
+fn def_in_scope(
+    sema: &Semantics<RootDatabase>,
+    file_range: FileRange,
+    namespace: Namespace,
+    name: &str,
+) -> Option<ScopeDef> {
+    let file = sema.parse(file_range.file_id);
+    let offset = file_range.range.start();
+    let node = match find_node_at_offset::<ast::NameRef>(file.syntax(), offset) {
+        Some(name_ref) if name_ref.syntax().text_range() == file_range.range => {
+            let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+            let path = segment.syntax().parent().and_then(ast::Path::cast)?;
+            if path.qualifier().is_some() {
+                return None;
+            }
+            name_ref.syntax().clone()
+        }
+        _ => {
+            let name = find_node_at_offset::<ast::Name>(file.syntax(), offset)?;
+            name.syntax().clone()
+        }
+    };
+
+    // Names are processed from the innermost scope outwards.
+    let mut res = None;
+    sema.scope_at_offset(&node, offset).process_all_names(&mut |it, scope_def| {
+        if res.is_none()
+            && it.to_string() == name
+            && scope_def_namespace(&scope_def) == Some(namespace)
+        {
+            res = Some(scope_def);
+        }
+    });
+    res
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Namespace {
+    Types,
+    Values,
+    Macros,
+}
+
+fn definition_namespace(def: &Definition) -> Option<Namespace> {
+    match def {
+        Definition::Macro(_) => Some(Namespace::Macros),
+        Definition::StructField(_) => None,
+        Definition::ModuleDef(it) => Some(module_def_namespace(it)),
+        Definition::SelfType(_) | Definition::TypeParam(_) => Some(Namespace::Types),
+        Definition::Local(_) => Some(Namespace::Values),
+    }
+}
+
+fn scope_def_namespace(def: &ScopeDef) -> Option<Namespace> {
+    match def {
+        ScopeDef::ModuleDef(it) => Some(module_def_namespace(it)),
+        ScopeDef::MacroDef(_) => Some(Namespace::Macros),
+        ScopeDef::GenericParam(_) | ScopeDef::ImplSelfType(_) | ScopeDef::AdtSelfType(_) => {
+            Some(Namespace::Types)
+        }
+        ScopeDef::Local(_) => Some(Namespace::Values),
+        ScopeDef::Unknown => None,
+    }
+}
+
+fn module_def_namespace(def: &ModuleDef) -> Namespace {
+    match def {
+        ModuleDef::Function(_)
+        | ModuleDef::EnumVariant(_)
+        | ModuleDef::Const(_)
+        | ModuleDef::Static(_) => Namespace::Values,
+        ModuleDef::Module(_)
+        | ModuleDef::Adt(_)
+        | ModuleDef::Trait(_)
+        | ModuleDef::TypeAlias(_)
+        | ModuleDef::BuiltinType(_) => Namespace::Types,
+    }
+}
+
+fn is_same_def(scope_def: &ScopeDef, def: &Definition) -> bool {
+    match (scope_def, def) {
+        (ScopeDef::ModuleDef(a), Definition::ModuleDef(b)) => a == b,
+        (ScopeDef::MacroDef(a), Definition::Macro(b)) => a == b,
+        (ScopeDef::GenericParam(a), Definition::TypeParam(b)) => a == b,
+        (ScopeDef::ImplSelfType(a), Definition::SelfType(b)) => a == b,
+        (ScopeDef::Local(a), Definition::Local(b)) => a == b,
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
@@ -712,6 +974,122 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_rename_conflicts_with_local_in_scope() {
+        let conflicts = rename_conflict_messages(
+            r#"
+    fn main() {
+        let y = 1;
+        let x<|> = 2;
+        y + x;
+    }"#,
+            "y",
+        );
+        assert_eq!(conflicts, vec!["`y` would refer to the renamed variable here"]);
+    }
+
+    #[test]
+    fn test_rename_conflicts_with_local_declared_before_use() {
+        let conflicts = rename_conflict_messages(
+            r#"
+    fn main() {
+        let x<|> = 1;
+        let y = 2;
+        x + y;
+    }"#,
+            "y",
+        );
+        assert_eq!(conflicts, vec!["`y` would refer to another local variable here"]);
+    }
+
+    #[test]
+    fn test_rename_conflicts_with_item_in_module() {
+        let conflicts = rename_conflict_messages(
+            r#"
+    fn foo<|>() {}
+    fn bar() {}"#,
+            "bar",
+        );
+        assert_eq!(conflicts, vec!["`bar` is already defined in this module"]);
+    }
+
+    #[test]
+    fn test_rename_conflicts_ignore_other_namespaces() {
+        let conflicts = rename_conflict_messages(
+            r#"
+    struct Foo;
+    fn main() {
+        let x<|> = 2;
+        x;
+    }"#,
+            "Foo",
+        );
+        assert!(conflicts.is_empty());
+        let conflicts = rename_conflict_messages(
+            r#"
+    fn main() {
+        let y = 1;
+        let x<|> = 2;
+        x;
+    }"#,
+            "z",
+        );
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_rename_conflicts_ignore_shadowed_locals() {
+        let conflicts = rename_conflict_messages(
+            r#"
+    fn main() {
+        let y = 1;
+        let x<|> = 2;
+        x;
+    }"#,
+            "y",
+        );
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_rename_conflicts_with_field() {
+        let conflicts = rename_conflict_messages(
+            r#"
+    struct Foo { a<|>: i32, b: i32 }
+    fn f(foo: Foo) { foo.a; }"#,
+            "b",
+        );
+        assert_eq!(conflicts, vec!["field `b` already exists"]);
+    }
+
+    #[test]
+    fn test_rename_conflicts_with_method() {
+        let conflicts = rename_conflict_messages(
+            r#"
+    struct S;
+    impl S {
+        fn foo<|>(&self) {}
+        fn bar(&self) {}
+    }"#,
+            "bar",
+        );
+        assert_eq!(conflicts, vec!["`bar` is already defined in this impl"]);
+        let conflicts = rename_conflict_messages(
+            r#"
+    struct S;
+    impl S { fn foo<|>(&self) {} }
+    impl S { fn bar(&self) {} }"#,
+            "bar",
+        );
+        assert_eq!(conflicts, vec!["`S` already has a method `bar`"]);
+    }
+
+    fn rename_conflict_messages(text: &str, new_name: &str) -> Vec<String> {
+        let (analysis, position) = single_file_with_position(text);
+        let conflicts = analysis.rename_conflicts(position, new_name).unwrap();
+        conflicts.into_iter().map(|it| it.message).collect()
+    }
+
     fn test_rename(text: &str, new_name: &str, expected: &str) {
         let (analysis, position) = single_file_with_position(text);
         let source_change = analysis.rename(position, new_name).unwrap();
@@ -97,8 +97,17 @@ pub struct SourceFileEdit {
 
 #[derive(Debug)]
 pub enum FileSystemEdit {
-    CreateFile { source_root: SourceRootId, path: RelativePathBuf },
-    MoveFile { src: FileId, dst_source_root: SourceRootId, dst_path: RelativePathBuf },
+    /// Creates a file with the given text, which may be empty.
+    CreateFile {
+        source_root: SourceRootId,
+        path: RelativePathBuf,
+        initial_contents: String,
+    },
+    MoveFile {
+        src: FileId,
+        dst_source_root: SourceRootId,
+        dst_path: RelativePathBuf,
+    },
 }
 
 pub(crate) struct SingleFileChange {
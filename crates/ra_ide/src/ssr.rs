@@ -4,7 +4,7 @@ use crate::source_change::SourceFileEdit;
 use ra_db::{SourceDatabase, SourceDatabaseExt};
 use ra_ide_db::symbol_index::SymbolsDatabase;
 use ra_ide_db::RootDatabase;
-use ra_syntax::ast::make::try_expr_from_text;
+use ra_syntax::ast::make::try_parse_expr;
 use ra_syntax::ast::{
     ArgList, AstToken, CallExpr, Comment, Expr, MethodCallExpr, RecordField, RecordLit,
 };
@@ -110,7 +110,7 @@ impl FromStr for SsrQuery {
             template = replace_in_template(template, var, new_var);
         }
 
-        let template = try_expr_from_text(&template)
+        let template = try_parse_expr(&template)
             .ok_or(SsrError("Template is not an expression".into()))?
             .syntax()
             .clone();
@@ -126,7 +126,7 @@ impl FromStr for SsrQuery {
         });
 
         let pattern = SsrPattern {
-            pattern: try_expr_from_text(&pattern)
+            pattern: try_parse_expr(&pattern)
                 .ok_or(SsrError("Pattern is not an expression".into()))?
                 .syntax()
                 .clone(),
@@ -158,7 +158,7 @@ fn split_by_var(s: &str) -> Result<(&str, &str, &str), SsrError> {
 }
 
 fn is_name(s: &str) -> Result<(), SsrError> {
-    if s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+    if s.chars().all(|c| c.is_alphanumeric() || c == '_') {
         Ok(())
     } else {
         Err(SsrError("Name can contain only alphanumerics and _".into()))
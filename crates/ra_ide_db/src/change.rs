@@ -15,7 +15,7 @@ use rayon::prelude::*;
 use rustc_hash::FxHashMap;
 
 use crate::{
-    symbol_index::{SymbolIndex, SymbolsDatabase},
+    symbol_index::{SymbolCache, SymbolIndex, SymbolsDatabase},
     DebugData, RootDatabase,
 };
 
@@ -26,6 +26,7 @@ pub struct AnalysisChange {
     files_changed: Vec<(FileId, Arc<String>)>,
     libraries_added: Vec<LibraryData>,
     crate_graph: Option<CrateGraph>,
+    symbol_cache: Option<SymbolCache>,
     debug_data: DebugData,
 }
 
@@ -47,6 +48,9 @@ impl fmt::Debug for AnalysisChange {
         if self.crate_graph.is_some() {
             d.field("crate_graph", &self.crate_graph);
         }
+        if let Some(symbol_cache) = &self.symbol_cache {
+            d.field("symbol_cache", &symbol_cache.len());
+        }
         d.finish()
     }
 }
@@ -88,6 +92,10 @@ impl AnalysisChange {
         self.crate_graph = Some(graph);
     }
 
+    pub fn set_symbol_cache(&mut self, cache: SymbolCache) {
+        self.symbol_cache = Some(cache);
+    }
+
     pub fn set_debug_root_path(&mut self, source_root_id: SourceRootId, path: String) {
         self.debug_data.root_paths.insert(source_root_id, path);
     }
@@ -218,6 +226,9 @@ impl RootDatabase {
         if let Some(crate_graph) = change.crate_graph {
             self.set_crate_graph_with_durability(Arc::new(crate_graph), Durability::HIGH)
         }
+        if let Some(symbol_cache) = change.symbol_cache {
+            self.set_symbol_cache_with_durability(Arc::new(symbol_cache), Durability::HIGH)
+        }
 
         Arc::make_mut(&mut self.debug_data).merge(change.debug_data)
     }
@@ -302,6 +313,7 @@ impl RootDatabase {
         sweep_each_query![
             // SourceDatabase
             ra_db::ParseQuery
+            ra_db::EditionErrorsQuery
             ra_db::SourceRootCratesQuery
 
             // AstDatabase
@@ -319,8 +331,15 @@ impl RootDatabase {
             hir::db::StructDataQuery
             hir::db::UnionDataQuery
             hir::db::EnumDataQuery
+            hir::db::EnumVariantDataQuery
+            hir::db::FieldVisibilitiesQuery
+            hir::db::VisibleFieldsQuery
+            hir::db::AdtHasDefaultImplQuery
             hir::db::ImplDataQuery
             hir::db::TraitDataQuery
+            hir::db::SuperTraitsQuery
+            hir::db::AllSuperTraitsQuery
+            hir::db::TraitItemsWithSuperTraitsQuery
             hir::db::TypeAliasDataQuery
             hir::db::FunctionDataQuery
             hir::db::ConstDataQuery
@@ -353,11 +372,14 @@ impl RootDatabase {
             hir::db::ImplSelfTyQuery
             hir::db::ImplTraitQuery
             hir::db::FieldTypesQuery
+            hir::db::ConstEvalQuery
+            hir::db::EnumDiscriminantsQuery
             hir::db::CallableItemSignatureQuery
             hir::db::GenericPredicatesForParamQuery
             hir::db::GenericPredicatesQuery
             hir::db::GenericDefaultsQuery
             hir::db::ImplsInCrateQuery
+            hir::db::TraitImplsInCrateQuery
             hir::db::ImplsForTraitQuery
             hir::db::InternTypeCtorQuery
             hir::db::InternTypeParamIdQuery
@@ -369,6 +391,7 @@ impl RootDatabase {
             hir::db::ImplDatumQuery
             hir::db::AssociatedTyValueQuery
             hir::db::TraitSolveQuery
+            hir::db::CouldCoerceQuery
 
             // SymbolsDatabase
             crate::symbol_index::FileSymbolsQuery
@@ -115,6 +115,7 @@ impl RootDatabase {
         db.set_crate_graph_with_durability(Default::default(), Durability::HIGH);
         db.set_local_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_library_roots_with_durability(Default::default(), Durability::HIGH);
+        db.set_symbol_cache_with_durability(Default::default(), Durability::HIGH);
         db.update_lru_capacity(lru_capacity);
         db
     }
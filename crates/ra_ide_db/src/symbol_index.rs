@@ -19,6 +19,14 @@
 //! for each library (which is assumed to never change) and an FST for each Rust
 //! file in the current workspace, and run a query against the union of all
 //! those FSTs.
+//!
+//! To make the index available right after startup, the symbols of the local
+//! files can be saved to disk as a `SymbolCache` once the workspace is loaded
+//! and at the end of a session. As the cache is keyed by the contents of the
+//! files, the symbols of unchanged files are then taken from the cache instead
+//! of parsing the files again. Only symbols are cached: completion doesn't
+//! score items by how often they are referenced, so there are no reference
+//! counts to persist.
 
 use std::{
     cmp::Ordering,
@@ -37,10 +45,11 @@ use ra_syntax::{
     ast::{self, NameOwner},
     match_ast, AstNode, Parse, SmolStr, SourceFile,
     SyntaxKind::{self, *},
-    SyntaxNode, SyntaxNodePtr, TextRange, WalkEvent,
+    SyntaxNode, SyntaxNodePtr, TextRange, TextUnit, WalkEvent,
 };
 #[cfg(not(feature = "wasm"))]
 use rayon::prelude::*;
+use rustc_hash::{FxHashMap, FxHasher};
 
 use crate::RootDatabase;
 
@@ -97,10 +106,19 @@ pub trait SymbolsDatabase: hir::db::HirDatabase {
     /// Files in libraries are assumed to never change.
     #[salsa::input]
     fn library_roots(&self) -> Arc<Vec<SourceRootId>>;
+    /// Symbols of the local files from a previous session.
+    #[salsa::input]
+    fn symbol_cache(&self) -> Arc<SymbolCache>;
 }
 
 fn file_symbols(db: &impl SymbolsDatabase, file_id: FileId) -> Arc<SymbolIndex> {
     db.check_canceled();
+    let cache = db.symbol_cache();
+    if !cache.is_empty() {
+        if let Some(symbols) = cache.get(&db.file_text(file_id), file_id) {
+            return Arc::new(SymbolIndex::new(symbols));
+        }
+    }
     let parse = db.parse(file_id);
 
     let symbols = source_file_to_file_symbols(&parse.tree(), file_id);
@@ -152,6 +170,20 @@ pub fn world_symbols(db: &RootDatabase, query: Query) -> Vec<FileSymbol> {
     query.search(&buf)
 }
 
+/// Collects the symbols of all local files, to be loaded into the next
+/// session with `AnalysisChange::set_symbol_cache`. The cache is built from
+/// the current files only, so the entries of the loaded cache which no file
+/// matches anymore are dropped.
+pub fn local_symbol_cache(db: &RootDatabase) -> SymbolCache {
+    let mut res = SymbolCache::default();
+    for &root in db.local_roots().iter() {
+        for file_id in db.source_root(root).walk() {
+            res.insert(&db.file_text(file_id), &db.file_symbols(file_id).symbols);
+        }
+    }
+    res
+}
+
 pub fn index_resolve(db: &RootDatabase, name_ref: &ast::NameRef) -> Vec<FileSymbol> {
     let name = name_ref.text();
     let mut query = Query::new(name.to_string());
@@ -299,6 +331,129 @@ impl Query {
     }
 }
 
+/// Symbols of files, keyed by a hash of the file contents.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SymbolCache {
+    files: FxHashMap<u64, Vec<FileSymbol>>,
+}
+
+impl SymbolCache {
+    /// The header of the serialized cache. The numbering of syntax kinds is
+    /// not stable across builds, so we include their count to at least reject
+    /// caches written by versions with a different set of kinds.
+    fn header() -> String {
+        format!("rust-analyzer symbol cache 2 {}", SyntaxKind::__LAST as u16)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn insert(&mut self, text: &str, symbols: &[FileSymbol]) {
+        let symbols = symbols
+            .iter()
+            .map(|symbol| FileSymbol { file_id: FileId(0), ..symbol.clone() })
+            .collect();
+        self.files.insert(content_hash(text), symbols);
+    }
+
+    fn get(&self, text: &str, file_id: FileId) -> Option<Vec<FileSymbol>> {
+        let symbols = self.files.get(&content_hash(text))?;
+        Some(symbols.iter().map(|symbol| FileSymbol { file_id, ..symbol.clone() }).collect())
+    }
+
+    /// Serializes the cache, with a line per file followed by a line per
+    /// symbol. Symbol names and container paths never contain tabs.
+    pub fn to_text(&self) -> String {
+        let mut res = format!("{}\n", SymbolCache::header());
+        for (hash, symbols) in self.files.iter() {
+            res.push_str(&format!("{}\t{}\n", hash, symbols.len()));
+            for symbol in symbols {
+                let range = symbol.ptr.range();
+                let name_range = match symbol.name_range {
+                    Some(it) => format!("{}\t{}", it.start(), it.end()),
+                    None => "-\t-".to_string(),
+                };
+                res.push_str(&format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\n",
+                    u16::from(symbol.ptr.kind()),
+                    range.start(),
+                    range.end(),
+                    name_range,
+                    symbol.name,
+                    symbol.container_name.as_ref().map_or("", |it| it.as_str()),
+                ));
+            }
+        }
+        res
+    }
+
+    /// Reads a cache written by `to_text`. Returns `None` if the text is not a
+    /// valid cache, for example because it was written by another version or
+    /// is truncated.
+    pub fn from_text(text: &str) -> Option<SymbolCache> {
+        fn offset(text: &str) -> Option<TextUnit> {
+            text.parse::<u32>().ok().map(TextUnit::from)
+        }
+        fn text_range(start: &str, end: &str) -> Option<TextRange> {
+            let (start, end) = (offset(start)?, offset(end)?);
+            if start > end {
+                return None;
+            }
+            Some(TextRange::from_to(start, end))
+        }
+
+        let mut lines = text.lines();
+        if lines.next()? != SymbolCache::header() {
+            return None;
+        }
+        let mut res = SymbolCache::default();
+        while let Some(line) = lines.next() {
+            let mut fields = line.split('\t');
+            let hash = fields.next()?.parse::<u64>().ok()?;
+            let len = fields.next()?.parse::<usize>().ok()?;
+            let mut symbols = Vec::with_capacity(len);
+            for _ in 0..len {
+                let fields: Vec<&str> = lines.next()?.split('\t').collect();
+                if fields.len() != 7 {
+                    return None;
+                }
+                let kind = fields[0].parse::<u16>().ok()?;
+                if kind >= SyntaxKind::__LAST as u16 {
+                    return None;
+                }
+                let kind = SyntaxKind::from(kind);
+                let range = text_range(fields[1], fields[2])?;
+                let name_range = match (fields[3], fields[4]) {
+                    ("-", "-") => None,
+                    (start, end) => Some(text_range(start, end)?),
+                };
+                let container_name =
+                    if fields[6].is_empty() { None } else { Some(SmolStr::new(fields[6])) };
+                symbols.push(FileSymbol {
+                    file_id: FileId(0),
+                    name: SmolStr::new(fields[5]),
+                    ptr: SyntaxNodePtr::from_raw(kind, range),
+                    name_range,
+                    container_name,
+                });
+            }
+            res.files.insert(hash, symbols);
+        }
+        Some(res)
+    }
+}
+
+fn content_hash(text: &str) -> u64 {
+    let mut hasher = FxHasher::default();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn is_type(kind: SyntaxKind) -> bool {
     match kind {
         STRUCT_DEF | ENUM_DEF | TRAIT_DEF | TYPE_ALIAS_DEF => true,
@@ -319,21 +474,32 @@ pub struct FileSymbol {
 
 fn source_file_to_file_symbols(source_file: &SourceFile, file_id: FileId) -> Vec<FileSymbol> {
     let mut symbols = Vec::new();
-    let mut stack = Vec::new();
+    // Path of the enclosing symbols and closures, together with the number of
+    // closures seen so far directly inside each of them.
+    let mut stack: Vec<(SmolStr, usize)> = Vec::new();
+    let mut top_level_closures = 0;
 
     for event in source_file.syntax().preorder() {
         match event {
             WalkEvent::Enter(node) => {
                 if let Some(mut symbol) = to_file_symbol(&node, file_id) {
-                    symbol.container_name = stack.last().cloned();
+                    symbol.container_name = container_path(&stack);
 
-                    stack.push(symbol.name.clone());
+                    stack.push((symbol.name.clone(), 0));
                     symbols.push(symbol);
+                } else if node.kind() == LAMBDA_EXPR {
+                    let closure_count = match stack.last_mut() {
+                        Some((_, count)) => count,
+                        None => &mut top_level_closures,
+                    };
+                    let segment = format!("{{closure#{}}}", closure_count);
+                    *closure_count += 1;
+                    stack.push((segment.into(), 0));
                 }
             }
 
             WalkEvent::Leave(node) => {
-                if to_symbol(&node).is_some() {
+                if to_symbol(&node).is_some() || node.kind() == LAMBDA_EXPR {
                     stack.pop();
                 }
             }
@@ -343,6 +509,14 @@ fn source_file_to_file_symbols(source_file: &SourceFile, file_id: FileId) -> Vec
     symbols
 }
 
+fn container_path(stack: &[(SmolStr, usize)]) -> Option<SmolStr> {
+    if stack.is_empty() {
+        return None;
+    }
+    let path = stack.iter().map(|(segment, _)| segment.as_str()).collect::<Vec<_>>().join("::");
+    Some(path.into())
+}
+
 fn to_symbol(node: &SyntaxNode) -> Option<(SmolStr, SyntaxNodePtr, TextRange)> {
     fn decl<N: NameOwner>(node: N) -> Option<(SmolStr, SyntaxNodePtr, TextRange)> {
         let name = node.name()?;
@@ -0,0 +1,180 @@
+//! Checks for `macro_rules` definitions which are valid, but most likely not
+//! what the author intended: metavariables which are bound but never used (or
+//! used but never bound), and rules which are shadowed by an earlier rule.
+
+use ra_syntax::SmolStr;
+use rustc_hash::FxHashSet;
+
+use crate::{
+    parser::{parse_pattern, parse_template, Op, RepeatKind},
+    MacroRules, Rule,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RuleDiagnostic {
+    /// A metavariable bound in the matcher is not used in the transcriber.
+    UnusedMetavar { name: SmolStr, id: tt::TokenId },
+    /// A metavariable used in the transcriber is not bound in the matcher.
+    UnboundMetavar { name: SmolStr, id: tt::TokenId },
+    /// The rule can't ever match, because an earlier rule matches all of its
+    /// inputs.
+    UnreachableRule { id: tt::TokenId },
+}
+
+impl RuleDiagnostic {
+    pub fn message(&self) -> String {
+        match self {
+            RuleDiagnostic::UnusedMetavar { name, .. } => {
+                format!("unused macro metavariable `${}`", name)
+            }
+            RuleDiagnostic::UnboundMetavar { name, .. } => {
+                format!("unknown macro metavariable `${}`", name)
+            }
+            RuleDiagnostic::UnreachableRule { .. } => {
+                "unreachable macro rule, an earlier rule matches all of its inputs".to_string()
+            }
+        }
+    }
+
+    /// The token the diagnostic refers to. For unreachable rules, this is the
+    /// delimiter of the matcher.
+    pub fn token_id(&self) -> tt::TokenId {
+        match self {
+            RuleDiagnostic::UnusedMetavar { id, .. }
+            | RuleDiagnostic::UnboundMetavar { id, .. }
+            | RuleDiagnostic::UnreachableRule { id } => *id,
+        }
+    }
+}
+
+impl MacroRules {
+    pub fn diagnostics(&self) -> Vec<RuleDiagnostic> {
+        let mut res = Vec::new();
+        for (idx, rule) in self.rules.iter().enumerate() {
+            check_metavars(rule, &mut res);
+            let lhs = ops(&rule.lhs);
+            if self.rules[..idx].iter().any(|earlier| covers(&ops(&earlier.lhs), &lhs)) {
+                res.push(RuleDiagnostic::UnreachableRule { id: rule.lhs_id });
+            }
+        }
+        res
+    }
+}
+
+fn check_metavars(rule: &Rule, acc: &mut Vec<RuleDiagnostic>) {
+    let mut bound = Vec::new();
+    collect_vars(&rule.lhs, ops, &mut bound);
+    let mut used = Vec::new();
+    collect_vars(&rule.rhs, template_ops, &mut used);
+
+    let bound_names = bound.iter().map(|(name, _)| name).collect::<FxHashSet<_>>();
+    let used_names = used.iter().map(|(name, _)| name).collect::<FxHashSet<_>>();
+
+    for (name, id) in bound.iter() {
+        if !name.starts_with('_') && !used_names.contains(name) {
+            acc.push(RuleDiagnostic::UnusedMetavar { name: name.clone(), id: *id });
+        }
+    }
+
+    // Macros defining macros use `$var`s of the inner macro in the transcriber.
+    if defines_macro(&rule.rhs) {
+        return;
+    }
+    for (name, id) in used.iter() {
+        if name != "crate" && !bound_names.contains(name) {
+            acc.push(RuleDiagnostic::UnboundMetavar { name: name.clone(), id: *id });
+        }
+    }
+}
+
+fn ops(pattern: &tt::Subtree) -> Vec<Op<'_>> {
+    parse_pattern(pattern).filter_map(Result::ok).collect()
+}
+
+fn template_ops(template: &tt::Subtree) -> Vec<Op<'_>> {
+    parse_template(template).filter_map(Result::ok).collect()
+}
+
+fn collect_vars(
+    subtree: &tt::Subtree,
+    parse: fn(&tt::Subtree) -> Vec<Op<'_>>,
+    acc: &mut Vec<(SmolStr, tt::TokenId)>,
+) {
+    for op in parse(subtree) {
+        match op {
+            Op::Var { name, id, .. } => acc.push((name.clone(), id)),
+            Op::Repeat { subtree, .. } | Op::TokenTree(tt::TokenTree::Subtree(subtree)) => {
+                collect_vars(subtree, parse, acc)
+            }
+            Op::TokenTree(tt::TokenTree::Leaf(_)) => (),
+        }
+    }
+}
+
+fn defines_macro(subtree: &tt::Subtree) -> bool {
+    subtree.token_trees.iter().any(|tt| match tt {
+        tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => ident.text == "macro_rules",
+        tt::TokenTree::Subtree(subtree) => defines_macro(subtree),
+        tt::TokenTree::Leaf(_) => false,
+    })
+}
+
+/// Checks whether every input matched by the pattern `rhs` is also matched by
+/// `lhs`. This is conservative: `false` means "don't know".
+fn covers(lhs: &[Op<'_>], rhs: &[Op<'_>]) -> bool {
+    match (lhs.first(), rhs.first()) {
+        (None, None) => true,
+        (Some(op), _) if lhs.len() == 1 && matches_anything(op) => true,
+        (Some(l), Some(r)) => covers_one(l, r) && covers(&lhs[1..], &rhs[1..]),
+        _ => false,
+    }
+}
+
+fn covers_one(lhs: &Op<'_>, rhs: &Op<'_>) -> bool {
+    match (lhs, rhs) {
+        (Op::Var { kind: Some(l), .. }, Op::Var { kind: Some(r), .. }) if l == r => true,
+        (Op::Var { kind: Some(kind), .. }, rhs) if kind.as_str() == "tt" => match rhs {
+            Op::Var { kind: Some(kind), .. } => kind.as_str() == "ident",
+            // `=>` is two puncts here, but a single token tree for rustc.
+            Op::TokenTree(tt::TokenTree::Leaf(tt::Leaf::Punct(punct))) => {
+                punct.spacing == tt::Spacing::Alone
+            }
+            Op::TokenTree(_) => true,
+            _ => false,
+        },
+        (
+            Op::Var { kind: Some(kind), .. },
+            Op::TokenTree(tt::TokenTree::Leaf(tt::Leaf::Ident(ident))),
+        ) if kind.as_str() == "ident" => ident.text != "_",
+        (Op::TokenTree(tt::TokenTree::Leaf(l)), Op::TokenTree(tt::TokenTree::Leaf(r))) => {
+            match (l, r) {
+                (tt::Leaf::Ident(l), tt::Leaf::Ident(r)) => l.text == r.text,
+                (tt::Leaf::Literal(l), tt::Leaf::Literal(r)) => l.text == r.text,
+                (tt::Leaf::Punct(l), tt::Leaf::Punct(r)) => l.char == r.char,
+                _ => false,
+            }
+        }
+        (Op::TokenTree(tt::TokenTree::Subtree(l)), Op::TokenTree(tt::TokenTree::Subtree(r))) => {
+            l.delimiter.map(|it| it.kind) == r.delimiter.map(|it| it.kind)
+                && covers(&ops(l), &ops(r))
+        }
+        (
+            Op::Repeat { subtree: l, kind: l_kind, separator: l_sep },
+            Op::Repeat { subtree: r, kind: r_kind, separator: r_sep },
+        ) => l_kind == r_kind && l_sep == r_sep && covers(&ops(l), &ops(r)),
+        _ => false,
+    }
+}
+
+/// `$($t:tt)*`
+fn matches_anything(op: &Op<'_>) -> bool {
+    match op {
+        Op::Repeat { subtree, kind: RepeatKind::ZeroOrMore, separator: None } => {
+            match ops(subtree).as_slice() {
+                [Op::Var { kind: Some(kind), .. }] => kind.as_str() == "tt",
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
@@ -8,9 +8,12 @@ mod mbe_expander;
 mod syntax_bridge;
 mod tt_iter;
 mod subtree_source;
+mod diagnostics;
 
 pub use tt::{Delimiter, Punct};
 
+pub use crate::diagnostics::RuleDiagnostic;
+
 use crate::{
     parser::{parse_pattern, Op},
     tt_iter::TtIter,
@@ -39,7 +42,7 @@ impl From<tt::ExpansionError> for ExpandError {
 
 pub use crate::syntax_bridge::{
     ast_to_token_tree, parse_to_token_tree, syntax_node_to_token_tree, token_tree_to_syntax_node,
-    TokenMap,
+    TokenMap, TokenTextRange,
 };
 
 /// This struct contains AST for a single `macro_rules` definition. What might
@@ -57,6 +60,8 @@ pub struct MacroRules {
 struct Rule {
     lhs: tt::Subtree,
     rhs: tt::Subtree,
+    /// Id of the delimiter of `lhs`, which is stripped.
+    lhs_id: tt::TokenId,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -182,6 +187,7 @@ impl Rule {
             .expect_subtree()
             .map_err(|()| ParseError::Expected("expected subtree".to_string()))?
             .clone();
+        let lhs_id = lhs.delimiter.map_or(tt::TokenId::unspecified(), |it| it.id);
         lhs.delimiter = None;
         src.expect_char('=').map_err(|()| ParseError::Expected("expected `=`".to_string()))?;
         src.expect_char('>').map_err(|()| ParseError::Expected("expected `>`".to_string()))?;
@@ -190,7 +196,7 @@ impl Rule {
             .map_err(|()| ParseError::Expected("expected subtree".to_string()))?
             .clone();
         rhs.delimiter = None;
-        Ok(crate::Rule { lhs, rhs })
+        Ok(crate::Rule { lhs, rhs, lhs_id })
     }
 }
 
@@ -91,7 +91,7 @@ fn expand_subtree(ctx: &mut ExpandCtx, template: &tt::Subtree) -> ExpandResult<t
                 err = err.or(e);
                 buf.push(tt.into());
             }
-            Op::Var { name, kind: _ } => {
+            Op::Var { name, .. } => {
                 let ExpandResult(fragment, e) = expand_var(ctx, name);
                 err = err.or(e);
                 push_fragment(&mut buf, fragment);
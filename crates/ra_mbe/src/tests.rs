@@ -55,6 +55,85 @@ mod rule_parsing {
     }
 }
 
+mod rule_diagnostics {
+    use ra_syntax::{ast, AstNode};
+
+    use super::*;
+    use crate::{ast_to_token_tree, TokenTextRange};
+
+    fn check(macro_body: &str, expected: &[(&str, &str)]) {
+        let macro_definition = format!("macro_rules! m {{ {} }}", macro_body);
+        let source_file = ast::SourceFile::parse(&macro_definition).ok().unwrap();
+        let token_tree = source_file
+            .syntax()
+            .descendants()
+            .find_map(ast::MacroCall::cast)
+            .and_then(|it| it.token_tree())
+            .unwrap();
+        let (definition_tt, token_map) = ast_to_token_tree(&token_tree).unwrap();
+        let rules = MacroRules::parse(&definition_tt).unwrap();
+
+        let offset = token_tree.syntax().text_range().start();
+        let actual = rules
+            .diagnostics()
+            .into_iter()
+            .map(|d| {
+                let range = match token_map.range_by_token(d.token_id()).unwrap() {
+                    TokenTextRange::Token(it) => it,
+                    TokenTextRange::Delimiter(open, close) => open.extend_to(&close),
+                };
+                let text = &macro_definition[range + offset];
+                (d.message(), text.to_string())
+            })
+            .collect::<Vec<_>>();
+        let expected = expected
+            .iter()
+            .map(|(message, text)| (message.to_string(), text.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_no_diagnostics() {
+        check("($a:expr, $($b:ident),*) => { $a + $($b)+* }", &[]);
+        check("($_unused:expr) => { $crate::foo() }", &[]);
+        check("($a:ident) => { macro_rules! inner { ($b:tt) => { $a $b } } }", &[]);
+        check("(a) => {}; (b) => {}; ($a:tt $b:tt) => { $a $b }", &[]);
+    }
+
+    #[test]
+    fn test_unused_and_unbound_metavars() {
+        check(
+            "($a:expr, $b:expr) => { $a + $c }",
+            &[("unused macro metavariable `$b`", "b"), ("unknown macro metavariable `$c`", "c")],
+        );
+        check(
+            "($($a:ident)*) => { $($a)* }; ($($b:ident)*) => { }",
+            &[
+                ("unused macro metavariable `$b`", "b"),
+                (
+                    "unreachable macro rule, an earlier rule matches all of its inputs",
+                    "($($b:ident)*)",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_unreachable_rules() {
+        let unreachable = "unreachable macro rule, an earlier rule matches all of its inputs";
+        check("($($t:tt)*) => { $($t)* }; (foo) => {}", &[(unreachable, "(foo)")]);
+        check("($i:ident) => { $i }; (foo) => {}; ([$t:tt]) => { $t }", &[(unreachable, "(foo)")]);
+        check(
+            "($t:tt, $e:expr) => { $t $e }; ([$x:ident], $y:expr) => { $x $y }",
+            &[(unreachable, "([$x:ident], $y:expr)")],
+        );
+        // `=>` is a single token tree.
+        check("($a:tt $b:tt) => { $a $b }; (=>) => {}", &[]);
+        check("($e:expr) => { $e }; (1) => {}", &[]);
+    }
+}
+
 // Good first issue (although a slightly challenging one):
 //
 // * Pick a random test from here
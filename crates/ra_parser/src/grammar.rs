@@ -38,6 +38,7 @@ mod types;
 
 use crate::{
     parser::{CompletedMarker, Marker, Parser},
+    syntax_kind::token_sets,
     SyntaxKind::{self, *},
     TokenSet,
 };
@@ -150,7 +151,7 @@ pub(crate) fn reparser(
         MATCH_ARM_LIST => items::match_arm_list,
         USE_TREE_LIST => items::use_tree_list,
         EXTERN_ITEM_LIST => items::extern_item_list,
-        TOKEN_TREE if first_child? == T!['{'] => items::token_tree,
+        TOKEN_TREE if matches!(first_child?, T!['{'] | T!['('] | T!['[']) => items::token_tree,
         ITEM_LIST => match parent? {
             IMPL_DEF => items::impl_item_list,
             TRAIT_DEF => items::trait_item_list,
@@ -10,7 +10,9 @@ pub(super) fn inner_attributes(p: &mut Parser) {
 
 pub(super) fn outer_attributes(p: &mut Parser) {
     while p.at(T![#]) {
-        attribute(p, false)
+        // Misplaced inner attributes are parsed as well, and reported during
+        // validation.
+        attribute(p, p.nth(1) == T![!])
     }
 }
 
@@ -3,7 +3,7 @@
 mod atom;
 
 pub(crate) use self::atom::match_arm_list;
-pub(super) use self::atom::{literal, LITERAL_FIRST};
+pub(super) use self::atom::{is_const_closure_start, literal};
 use super::*;
 
 pub(super) enum StmtWithSemi {
@@ -12,8 +12,6 @@ pub(super) enum StmtWithSemi {
     Optional,
 }
 
-const EXPR_FIRST: TokenSet = LHS_FIRST;
-
 pub(super) fn expr(p: &mut Parser) -> (Option<CompletedMarker>, BlockLike) {
     let r = Restrictions { forbid_structs: false, prefer_stmt: false };
     expr_bp(p, r, 1)
@@ -180,6 +178,18 @@ pub(super) fn stmt(p: &mut Parser, with_semi: StmtWithSemi) {
         }
         if p.eat(T![=]) {
             expressions::expr_with_attrs(p);
+
+            // test let_else
+            // fn foo() {
+            //     let Some(x) = opt else { return };
+            //     let Ok(y): Result = res else { return };
+            // }
+            if p.at(T![else]) {
+                let m = p.start();
+                p.bump(T![else]);
+                block(p);
+                m.complete(p, LET_ELSE);
+            }
         }
 
         match with_semi {
@@ -278,8 +288,8 @@ fn current_op(p: &Parser) -> (u8, SyntaxKind) {
 }
 
 // Parses expression with binding power of at least bp.
-fn expr_bp(p: &mut Parser, mut r: Restrictions, bp: u8) -> (Option<CompletedMarker>, BlockLike) {
-    let mut lhs = match lhs(p, r) {
+fn expr_bp(p: &mut Parser, r: Restrictions, bp: u8) -> (Option<CompletedMarker>, BlockLike) {
+    let lhs = match lhs(p, r) {
         Some((lhs, blocklike)) => {
             // test stmt_bin_expr_ambiguity
             // fn foo() {
@@ -293,7 +303,17 @@ fn expr_bp(p: &mut Parser, mut r: Restrictions, bp: u8) -> (Option<CompletedMark
         }
         None => return (None, BlockLike::NotBlock),
     };
+    (Some(expr_bp_rest(p, lhs, r, bp)), BlockLike::NotBlock)
+}
 
+// Parses the binary operators following an already parsed `lhs`, as long as
+// their binding power is at least bp.
+fn expr_bp_rest(
+    p: &mut Parser,
+    mut lhs: CompletedMarker,
+    mut r: Restrictions,
+    bp: u8,
+) -> CompletedMarker {
     loop {
         let is_range = p.at(T![..]) || p.at(T![..=]);
         let (op_bp, op) = current_op(p);
@@ -325,7 +345,7 @@ fn expr_bp(p: &mut Parser, mut r: Restrictions, bp: u8) -> (Option<CompletedMark
             //     match a.b()..S { _ => () };
             // }
             let has_trailing_expression =
-                p.at_ts(EXPR_FIRST) && !(r.forbid_structs && p.at(T!['{']));
+                p.at_ts(token_sets::EXPR_FIRST) && !(r.forbid_structs && p.at(T!['{']));
             if !has_trailing_expression {
                 // no RHS
                 lhs = m.complete(p, RANGE_EXPR);
@@ -336,11 +356,9 @@ fn expr_bp(p: &mut Parser, mut r: Restrictions, bp: u8) -> (Option<CompletedMark
         expr_bp(p, Restrictions { prefer_stmt: false, ..r }, op_bp + 1);
         lhs = m.complete(p, if is_range { RANGE_EXPR } else { BIN_EXPR });
     }
-    (Some(lhs), BlockLike::NotBlock)
+    lhs
 }
 
-const LHS_FIRST: TokenSet = atom::ATOM_EXPR_FIRST.union(token_set![AMP, STAR, EXCL, DOT, MINUS]);
-
 fn lhs(p: &mut Parser, r: Restrictions) -> Option<(CompletedMarker, BlockLike)> {
     let m;
     let kind = match p.current() {
@@ -373,7 +391,7 @@ fn lhs(p: &mut Parser, r: Restrictions) -> Option<(CompletedMarker, BlockLike)>
                 if p.at(op) {
                     m = p.start();
                     p.bump(op);
-                    if p.at_ts(EXPR_FIRST) && !(r.forbid_structs && p.at(T!['{'])) {
+                    if p.at_ts(token_sets::EXPR_FIRST) && !(r.forbid_structs && p.at(T!['{'])) {
                         expr_bp(p, r, 2);
                     }
                     return Some((m.complete(p, RANGE_EXPR), BlockLike::NotBlock));
@@ -15,21 +15,8 @@ use super::*;
 //     let _ = b"e";
 //     let _ = br"f";
 // }
-pub(crate) const LITERAL_FIRST: TokenSet = token_set![
-    TRUE_KW,
-    FALSE_KW,
-    INT_NUMBER,
-    FLOAT_NUMBER,
-    BYTE,
-    CHAR,
-    STRING,
-    RAW_STRING,
-    BYTE_STRING,
-    RAW_BYTE_STRING
-];
-
 pub(crate) fn literal(p: &mut Parser) -> Option<CompletedMarker> {
-    if !p.at_ts(LITERAL_FIRST) {
+    if !p.at_ts(token_sets::LITERAL_FIRST) {
         return None;
     }
     let m = p.start();
@@ -37,30 +24,6 @@ pub(crate) fn literal(p: &mut Parser) -> Option<CompletedMarker> {
     Some(m.complete(p, LITERAL))
 }
 
-// E.g. for after the break in `if break {}`, this should not match
-pub(super) const ATOM_EXPR_FIRST: TokenSet =
-    LITERAL_FIRST.union(paths::PATH_FIRST).union(token_set![
-        T!['('],
-        T!['{'],
-        T!['['],
-        L_DOLLAR,
-        T![|],
-        T![move],
-        T![box],
-        T![if],
-        T![while],
-        T![match],
-        T![unsafe],
-        T![return],
-        T![break],
-        T![continue],
-        T![async],
-        T![try],
-        T![loop],
-        T![for],
-        LIFETIME,
-    ]);
-
 const EXPR_RECOVERY_SET: TokenSet = token_set![LET_KW, R_DOLLAR];
 
 pub(super) fn atom_expr(p: &mut Parser, r: Restrictions) -> Option<(CompletedMarker, BlockLike)> {
@@ -78,6 +41,7 @@ pub(super) fn atom_expr(p: &mut Parser, r: Restrictions) -> Option<(CompletedMar
         T![|] => lambda_expr(p),
         T![move] if la == T![|] => lambda_expr(p),
         T![async] if la == T![|] || (la == T![move] && p.nth(2) == T![|]) => lambda_expr(p),
+        T![const] if is_const_closure_start(p) => lambda_expr(p),
         T![if] => if_expr(p),
 
         T![loop] => loop_expr(p, None),
@@ -157,7 +121,7 @@ fn tuple_expr(p: &mut Parser) -> CompletedMarker {
     let mut saw_expr = false;
     while !p.at(EOF) && !p.at(T![')']) {
         saw_expr = true;
-        if !p.at_ts(EXPR_FIRST) {
+        if !p.at_ts(token_sets::EXPR_FIRST) {
             p.error("expected expression");
             break;
         }
@@ -209,6 +173,22 @@ fn array_expr(p: &mut Parser) -> CompletedMarker {
     m.complete(p, ARRAY_EXPR)
 }
 
+/// `const` closures are not valid Rust, but they are parsed so that validation
+/// can report a better error than the parser would.
+pub(crate) fn is_const_closure_start(p: &Parser) -> bool {
+    if !p.at(T![const]) {
+        return false;
+    }
+    let mut n = 1;
+    if p.nth(n) == T![async] {
+        n += 1;
+    }
+    if p.nth(n) == T![move] {
+        n += 1;
+    }
+    p.nth(n) == T![|]
+}
+
 // test lambda_expr
 // fn foo() {
 //     || ();
@@ -225,8 +205,10 @@ fn lambda_expr(p: &mut Parser) -> CompletedMarker {
             || (p.at(T![move]) && p.nth(1) == T![|])
             || (p.at(T![async]) && p.nth(1) == T![|])
             || (p.at(T![async]) && p.nth(1) == T![move] && p.nth(2) == T![|])
+            || p.at(T![const])
     );
     let m = p.start();
+    p.eat(T![const]);
     p.eat(T![async]);
     p.eat(T![move]);
     params::param_list_closure(p);
@@ -239,7 +221,7 @@ fn lambda_expr(p: &mut Parser) -> CompletedMarker {
             p.error("expected `{`");
         }
     } else {
-        if p.at_ts(EXPR_FIRST) {
+        if p.at_ts(token_sets::EXPR_FIRST) {
             expr(p);
         } else {
             p.error("expected expression");
@@ -337,16 +319,59 @@ fn for_expr(p: &mut Parser, m: Option<Marker>) -> CompletedMarker {
 //     while let Some(_) | Some(_) = None {}
 //     while let | Some(_) = None {}
 // }
+//
+// test let_chain
+// fn foo() {
+//     if let Some(x) = a && let Some(y) = x && y > 0 {}
+//     if a && let Some(b) = c {}
+//     while let Some(x) = it.next() && x != 0 {}
+//     if let Some(x) = a || b {}
+// }
 fn cond(p: &mut Parser) {
     let m = p.start();
-    if p.eat(T![let]) {
-        patterns::pattern_top(p);
-        p.expect(T![=]);
+    let r = Restrictions { forbid_structs: true, prefer_stmt: false };
+    let lhs = if p.at(T![let]) {
+        let let_m = p.start();
+        let scrutinee = let_expr_tail(p, r);
+        if p.at(T![&&]) {
+            Some(let_m.complete(p, LET_EXPR))
+        } else {
+            // A plain `if let` keeps the pattern and the scrutinee as
+            // children of the condition itself.
+            let_m.abandon(p);
+            scrutinee
+        }
+    } else {
+        expr_bp(p, r, 5).0
+    };
+    if let Some(mut lhs) = lhs {
+        while p.at(T![&&]) {
+            let m = lhs.precede(p);
+            p.bump(T![&&]);
+            if p.at(T![let]) {
+                let let_m = p.start();
+                let_expr_tail(p, r);
+                let_m.complete(p, LET_EXPR);
+            } else {
+                expr_bp(p, r, 5);
+            }
+            lhs = m.complete(p, BIN_EXPR);
+        }
+        expr_bp_rest(p, lhs, r, 1);
     }
-    expr_no_struct(p);
     m.complete(p, CONDITION);
 }
 
+// Parses `let pat = expr` in a condition, stopping the scrutinee before any
+// `&&` so that it can be part of a let chain.
+fn let_expr_tail(p: &mut Parser, r: Restrictions) -> Option<CompletedMarker> {
+    assert!(p.at(T![let]));
+    p.bump(T![let]);
+    patterns::pattern_top(p);
+    p.expect(T![=]);
+    expr_bp(p, r, 5).0
+}
+
 // test match_expr
 // fn foo() {
 //     match () { };
@@ -480,7 +505,7 @@ fn return_expr(p: &mut Parser) -> CompletedMarker {
     assert!(p.at(T![return]));
     let m = p.start();
     p.bump(T![return]);
-    if p.at_ts(EXPR_FIRST) {
+    if p.at_ts(token_sets::EXPR_FIRST) {
         expr(p);
     }
     m.complete(p, RETURN_EXPR)
@@ -522,7 +547,7 @@ fn break_expr(p: &mut Parser, r: Restrictions) -> CompletedMarker {
     //     for i in break {}
     //     match break {}
     // }
-    if p.at_ts(EXPR_FIRST) && !(r.forbid_structs && p.at(T!['{'])) {
+    if p.at_ts(token_sets::EXPR_FIRST) && !(r.forbid_structs && p.at(T!['{'])) {
         expr(p);
     }
     m.complete(p, BREAK_EXPR)
@@ -550,7 +575,7 @@ fn box_expr(p: &mut Parser, m: Option<Marker>) -> CompletedMarker {
     assert!(p.at(T![box]));
     let m = m.unwrap_or_else(|| p.start());
     p.bump(T![box]);
-    if p.at_ts(EXPR_FIRST) {
+    if p.at_ts(token_sets::EXPR_FIRST) {
         expr(p);
     }
     m.complete(p, BOX_EXPR)
@@ -31,10 +31,7 @@ pub(super) enum ItemFlavor {
     Trait,
 }
 
-pub(super) const ITEM_RECOVERY_SET: TokenSet = token_set![
-    FN_KW, STRUCT_KW, ENUM_KW, IMPL_KW, TRAIT_KW, CONST_KW, STATIC_KW, LET_KW, MOD_KW, PUB_KW,
-    CRATE_KW, USE_KW, MACRO_KW
-];
+pub(super) const ITEM_RECOVERY_SET: TokenSet = token_sets::ITEM_FIRST.union(token_set![LET_KW]);
 
 pub(super) fn item_or_macro(p: &mut Parser, stop_on_r_curly: bool, flavor: ItemFlavor) {
     let m = p.start();
@@ -89,7 +86,11 @@ pub(super) fn maybe_item(p: &mut Parser, m: Marker, flavor: ItemFlavor) -> Resul
     let mut has_mods = false;
 
     // modifiers
-    has_mods |= p.eat(T![const]);
+    // `const` closures are parsed as expressions and rejected during validation
+    if p.at(T![const]) && !expressions::is_const_closure_start(p) {
+        p.bump(T![const]);
+        has_mods = true;
+    }
 
     // test_err async_without_semicolon
     // fn foo() { let _ = async {} }
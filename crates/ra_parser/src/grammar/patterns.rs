@@ -2,9 +2,11 @@
 
 use super::*;
 
-pub(super) const PATTERN_FIRST: TokenSet = expressions::LITERAL_FIRST
+pub(super) const PATTERN_FIRST: TokenSet = token_sets::LITERAL_FIRST
     .union(paths::PATH_FIRST)
-    .union(token_set![BOX_KW, REF_KW, MUT_KW, L_PAREN, L_BRACK, AMP, UNDERSCORE, MINUS, DOT]);
+    .union(token_set![
+        BOX_KW, REF_KW, MUT_KW, CONST_KW, L_PAREN, L_BRACK, AMP, UNDERSCORE, MINUS, DOT
+    ]);
 
 pub(crate) fn pattern(p: &mut Parser) {
     pattern_r(p, PAT_RECOVERY_SET);
@@ -61,11 +63,24 @@ fn pattern_single_r(p: &mut Parser, recovery_set: TokenSet) {
         //         200 .. 301=> (),
         //     }
         // }
+
+        // test half_open_range_pat
+        // fn main() {
+        //     match 92 {
+        //         0..10 => (),
+        //         10.. => (),
+        //         -5..=-1 | 20.. => (),
+        //     }
+        // }
         for &range_op in [T![...], T![..=], T![..]].iter() {
             if p.at(range_op) {
                 let m = lhs.precede(p);
                 p.bump(range_op);
-                atom_pat(p, recovery_set);
+                // A missing end of an inclusive range is reported by the
+                // validator, `a..` is a half-open range.
+                if p.at_ts(PATTERN_FIRST) && !p.at(T![..]) {
+                    atom_pat(p, recovery_set);
+                }
                 m.complete(p, RANGE_PAT);
                 return;
             }
@@ -92,6 +107,7 @@ fn atom_pat(p: &mut Parser, recovery_set: TokenSet) -> Option<CompletedMarker> {
         _ if is_literal_pat_start(p) => literal_pat(p),
 
         T![.] if p.at(T![..]) => dot_dot_pat(p),
+        T![const] if p.nth(1) == T!['{'] => const_block_pat(p),
         T![_] => placeholder_pat(p),
         T![&] => ref_pat(p),
         T!['('] => tuple_pat(p),
@@ -108,7 +124,7 @@ fn atom_pat(p: &mut Parser, recovery_set: TokenSet) -> Option<CompletedMarker> {
 
 fn is_literal_pat_start(p: &Parser) -> bool {
     p.at(T![-]) && (p.nth(1) == INT_NUMBER || p.nth(1) == FLOAT_NUMBER)
-        || p.at_ts(expressions::LITERAL_FIRST)
+        || p.at_ts(token_sets::LITERAL_FIRST)
 }
 
 // test literal_pattern
@@ -249,10 +265,6 @@ fn placeholder_pat(p: &mut Parser) -> CompletedMarker {
 //     let (a, ..,) = ();
 //     let Tuple(a, ..) = ();
 //     let Tuple(a, ..,) = ();
-//     let (.., ..) = ();
-//     let Tuple(.., ..) = ();
-//     let (.., a, ..) = ();
-//     let Tuple(.., a, ..) = ();
 //     //
 //     // Slices
 //     //
@@ -261,9 +273,6 @@ fn placeholder_pat(p: &mut Parser) -> CompletedMarker {
 //     let [head, tail @ ..] = ();
 //     let [head, .., cons] = ();
 //     let [head, mid @ .., cons] = ();
-//     let [head, .., .., cons] = ();
-//     let [head, .., mid, tail @ ..] = ();
-//     let [head, .., mid, .., cons] = ();
 // }
 fn dot_dot_pat(p: &mut Parser) -> CompletedMarker {
     assert!(p.at(T![..]));
@@ -357,8 +366,11 @@ fn pat_list(p: &mut Parser, ket: SyntaxKind) {
 // }
 fn bind_pat(p: &mut Parser, with_at: bool) -> CompletedMarker {
     let m = p.start();
-    p.eat(T![ref]);
-    p.eat(T![mut]);
+    let has_ref = p.eat(T![ref]);
+    if p.eat(T![mut]) && !has_ref {
+        // `mut ref` is reported by the validator
+        p.eat(T![ref]);
+    }
     name(p);
     if with_at && p.eat(T![@]) {
         pattern_single(p);
@@ -366,6 +378,22 @@ fn bind_pat(p: &mut Parser, with_at: bool) -> CompletedMarker {
     m.complete(p, BIND_PAT)
 }
 
+// test const_block_pat
+// fn main() {
+//     match 92 {
+//         const { N + 1 } => (),
+//         const { 0 }..=const { 10 } => (),
+//         _ => (),
+//     }
+// }
+fn const_block_pat(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T![const]));
+    let m = p.start();
+    p.bump(T![const]);
+    expressions::block(p);
+    m.complete(p, CONST_BLOCK_PAT)
+}
+
 // test box_pat
 // fn main() {
 //     let box i = ();
@@ -93,7 +93,9 @@ fn lifetime_bounds(p: &mut Parser) {
 }
 
 pub(super) fn bounds_without_colon_m(p: &mut Parser, marker: Marker) -> CompletedMarker {
-    while type_bound(p) {
+    // A `+` without a bound before it is reported during validation, so that
+    // the rest of the bounds are still parsed.
+    while type_bound(p) || p.at(T![+]) {
         if !p.eat(T![+]) {
             break;
         }
@@ -153,13 +155,36 @@ pub(super) fn opt_where_clause(p: &mut Parser) {
         }
 
         if !comma {
-            p.error("expected comma");
+            if is_where_predicate(p) {
+                p.error("expected comma");
+            } else if !where_clause_recovery(p) {
+                break;
+            }
         }
     }
 
     m.complete(p, WHERE_CLAUSE);
 }
 
+// test_err where_clause_recovery
+// fn foo<T>() where T: 92, T: Copy { let x = 1; }
+// fn bar<T>() where T: Copy ), T: Clone { let x = 1; }
+/// Skips tokens which can't continue the where clause up to the next comma,
+/// so that the rest of the item is still parsed. Returns whether the clause
+/// continues after the skipped tokens.
+fn where_clause_recovery(p: &mut Parser) -> bool {
+    if p.at(EOF) || p.at(T!['}']) {
+        return false;
+    }
+    let m = p.start();
+    p.error("expected a bound or a comma");
+    while !(p.at(EOF) || p.at(T![,]) || p.at(T!['}']) || is_where_clause_end(p)) {
+        p.bump_any();
+    }
+    m.complete(p, ERROR);
+    p.eat(T![,])
+}
+
 fn is_where_predicate(p: &mut Parser) -> bool {
     match p.current() {
         LIFETIME => true,
@@ -22,7 +22,7 @@ mod grammar;
 
 pub(crate) use token_set::TokenSet;
 
-pub use syntax_kind::SyntaxKind;
+pub use syntax_kind::{SyntaxKind, SyntaxKindSet};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ParseError(pub String);
@@ -126,7 +126,8 @@ pub fn parse_fragment(
 pub struct Reparser(fn(&mut parser::Parser));
 
 impl Reparser {
-    /// If the node is a braced block, return the corresponding `Reparser`.
+    /// If the node is a delimited block or token tree, return the corresponding
+    /// `Reparser`.
     pub fn for_node(
         node: SyntaxKind,
         first_child: Option<SyntaxKind>,
@@ -137,8 +138,8 @@ impl Reparser {
 
     /// Re-parse given tokens using this `Reparser`.
     ///
-    /// Tokens must start with `{`, end with `}` and form a valid brace
-    /// sequence.
+    /// Tokens must start and end with the delimiters of the node and form a
+    /// valid delimiter sequence.
     pub fn parse(self, token_source: &mut dyn TokenSource, tree_sink: &mut dyn TreeSink) {
         let Reparser(r) = self;
         let mut p = parser::Parser::new(token_source);
@@ -3,6 +3,9 @@
 #[macro_use]
 mod generated;
 
+use std::fmt;
+
+pub(crate) use self::generated::token_sets;
 pub use self::generated::SyntaxKind;
 
 impl From<u16> for SyntaxKind {
@@ -26,3 +29,56 @@ impl SyntaxKind {
         }
     }
 }
+
+/// A bit-set of `SyntaxKind`s. Unlike `TokenSet`, this can hold node kinds as
+/// well.
+///
+/// Sets for the enums of the grammar, like `SyntaxKind::EXPRESSIONS`, are
+/// generated.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SyntaxKindSet([u128; 2]);
+
+// Makes sure all kinds fit into the set.
+const _: [(); 0] = [(); (SyntaxKind::__LAST as usize >= 256) as usize];
+
+impl SyntaxKindSet {
+    pub const EMPTY: SyntaxKindSet = SyntaxKindSet([0; 2]);
+
+    pub const fn singleton(kind: SyntaxKind) -> SyntaxKindSet {
+        SyntaxKindSet([mask(kind, 0), mask(kind, 1)])
+    }
+
+    pub const fn union(self, other: SyntaxKindSet) -> SyntaxKindSet {
+        SyntaxKindSet([self.0[0] | other.0[0], self.0[1] | other.0[1]])
+    }
+
+    pub fn contains(&self, kind: SyntaxKind) -> bool {
+        self.0[0] & mask(kind, 0) != 0 || self.0[1] & mask(kind, 1) != 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = SyntaxKind> {
+        (0..SyntaxKind::__LAST as u16).map(SyntaxKind::from).filter(move |&it| self.contains(it))
+    }
+}
+
+impl fmt::Debug for SyntaxKindSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// The bit of `kind` in the `word`th word of the set, if it's there.
+const fn mask(kind: SyntaxKind, word: usize) -> u128 {
+    let idx = kind as usize;
+    ((idx / 128 == word) as u128) << (idx % 128)
+}
+
+#[test]
+fn syntax_kind_set_works_for_nodes() {
+    let set = SyntaxKindSet::singleton(SyntaxKind::EOF).union(SyntaxKind::PATTERNS);
+    assert!(set.contains(SyntaxKind::EOF));
+    assert!(set.contains(SyntaxKind::BIND_PAT));
+    assert!(set.contains(SyntaxKind::MACRO_PAT));
+    assert!(!set.contains(SyntaxKind::TUPLE_EXPR));
+    assert!(!set.contains(SyntaxKind::SHEBANG));
+}
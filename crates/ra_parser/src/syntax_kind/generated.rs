@@ -169,6 +169,7 @@ pub enum SyntaxKind {
     RANGE_PAT,
     LITERAL_PAT,
     MACRO_PAT,
+    CONST_BLOCK_PAT,
     TUPLE_EXPR,
     ARRAY_EXPR,
     PAREN_EXPR,
@@ -177,6 +178,7 @@ pub enum SyntaxKind {
     IF_EXPR,
     WHILE_EXPR,
     CONDITION,
+    LET_EXPR,
     LOOP_EXPR,
     FOR_EXPR,
     CONTINUE_EXPR,
@@ -229,6 +231,7 @@ pub enum SyntaxKind {
     NAME,
     NAME_REF,
     LET_STMT,
+    LET_ELSE,
     EXPR_STMT,
     TYPE_PARAM_LIST,
     LIFETIME_PARAM,
@@ -251,7 +254,105 @@ pub enum SyntaxKind {
     __LAST,
 }
 use self::SyntaxKind::*;
+use super::SyntaxKindSet;
 impl SyntaxKind {
+    #[doc = "The kinds of `ast::ModuleItem` nodes."]
+    pub const ITEMS: SyntaxKindSet = SyntaxKindSet::EMPTY
+        .union(SyntaxKindSet::singleton(STRUCT_DEF))
+        .union(SyntaxKindSet::singleton(UNION_DEF))
+        .union(SyntaxKindSet::singleton(ENUM_DEF))
+        .union(SyntaxKindSet::singleton(FN_DEF))
+        .union(SyntaxKindSet::singleton(TRAIT_DEF))
+        .union(SyntaxKindSet::singleton(TYPE_ALIAS_DEF))
+        .union(SyntaxKindSet::singleton(IMPL_DEF))
+        .union(SyntaxKindSet::singleton(USE_ITEM))
+        .union(SyntaxKindSet::singleton(EXTERN_CRATE_ITEM))
+        .union(SyntaxKindSet::singleton(CONST_DEF))
+        .union(SyntaxKindSet::singleton(STATIC_DEF))
+        .union(SyntaxKindSet::singleton(MODULE))
+        .union(SyntaxKindSet::singleton(MACRO_CALL))
+        .union(SyntaxKindSet::singleton(EXTERN_BLOCK));
+    #[doc = "The kinds of `ast::ImplItem` nodes."]
+    pub const IMPL_ITEMS: SyntaxKindSet = SyntaxKindSet::EMPTY
+        .union(SyntaxKindSet::singleton(FN_DEF))
+        .union(SyntaxKindSet::singleton(TYPE_ALIAS_DEF))
+        .union(SyntaxKindSet::singleton(CONST_DEF));
+    #[doc = "The kinds of `ast::Expr` nodes."]
+    pub const EXPRESSIONS: SyntaxKindSet = SyntaxKindSet::EMPTY
+        .union(SyntaxKindSet::singleton(TUPLE_EXPR))
+        .union(SyntaxKindSet::singleton(ARRAY_EXPR))
+        .union(SyntaxKindSet::singleton(PAREN_EXPR))
+        .union(SyntaxKindSet::singleton(PATH_EXPR))
+        .union(SyntaxKindSet::singleton(LAMBDA_EXPR))
+        .union(SyntaxKindSet::singleton(IF_EXPR))
+        .union(SyntaxKindSet::singleton(LOOP_EXPR))
+        .union(SyntaxKindSet::singleton(FOR_EXPR))
+        .union(SyntaxKindSet::singleton(WHILE_EXPR))
+        .union(SyntaxKindSet::singleton(CONTINUE_EXPR))
+        .union(SyntaxKindSet::singleton(BREAK_EXPR))
+        .union(SyntaxKindSet::singleton(LABEL))
+        .union(SyntaxKindSet::singleton(BLOCK_EXPR))
+        .union(SyntaxKindSet::singleton(RETURN_EXPR))
+        .union(SyntaxKindSet::singleton(MATCH_EXPR))
+        .union(SyntaxKindSet::singleton(RECORD_LIT))
+        .union(SyntaxKindSet::singleton(CALL_EXPR))
+        .union(SyntaxKindSet::singleton(INDEX_EXPR))
+        .union(SyntaxKindSet::singleton(METHOD_CALL_EXPR))
+        .union(SyntaxKindSet::singleton(FIELD_EXPR))
+        .union(SyntaxKindSet::singleton(AWAIT_EXPR))
+        .union(SyntaxKindSet::singleton(TRY_EXPR))
+        .union(SyntaxKindSet::singleton(TRY_BLOCK_EXPR))
+        .union(SyntaxKindSet::singleton(CAST_EXPR))
+        .union(SyntaxKindSet::singleton(REF_EXPR))
+        .union(SyntaxKindSet::singleton(PREFIX_EXPR))
+        .union(SyntaxKindSet::singleton(RANGE_EXPR))
+        .union(SyntaxKindSet::singleton(BIN_EXPR))
+        .union(SyntaxKindSet::singleton(LITERAL))
+        .union(SyntaxKindSet::singleton(MACRO_CALL))
+        .union(SyntaxKindSet::singleton(BOX_EXPR))
+        .union(SyntaxKindSet::singleton(LET_EXPR));
+    #[doc = "The kinds of `ast::Pat` nodes."]
+    pub const PATTERNS: SyntaxKindSet = SyntaxKindSet::EMPTY
+        .union(SyntaxKindSet::singleton(OR_PAT))
+        .union(SyntaxKindSet::singleton(PAREN_PAT))
+        .union(SyntaxKindSet::singleton(REF_PAT))
+        .union(SyntaxKindSet::singleton(BOX_PAT))
+        .union(SyntaxKindSet::singleton(BIND_PAT))
+        .union(SyntaxKindSet::singleton(PLACEHOLDER_PAT))
+        .union(SyntaxKindSet::singleton(DOT_DOT_PAT))
+        .union(SyntaxKindSet::singleton(PATH_PAT))
+        .union(SyntaxKindSet::singleton(RECORD_PAT))
+        .union(SyntaxKindSet::singleton(TUPLE_STRUCT_PAT))
+        .union(SyntaxKindSet::singleton(TUPLE_PAT))
+        .union(SyntaxKindSet::singleton(SLICE_PAT))
+        .union(SyntaxKindSet::singleton(RANGE_PAT))
+        .union(SyntaxKindSet::singleton(LITERAL_PAT))
+        .union(SyntaxKindSet::singleton(MACRO_PAT))
+        .union(SyntaxKindSet::singleton(CONST_BLOCK_PAT));
+    #[doc = "The kinds of `ast::TypeRef` nodes."]
+    pub const TYPES: SyntaxKindSet = SyntaxKindSet::EMPTY
+        .union(SyntaxKindSet::singleton(PAREN_TYPE))
+        .union(SyntaxKindSet::singleton(TUPLE_TYPE))
+        .union(SyntaxKindSet::singleton(NEVER_TYPE))
+        .union(SyntaxKindSet::singleton(PATH_TYPE))
+        .union(SyntaxKindSet::singleton(POINTER_TYPE))
+        .union(SyntaxKindSet::singleton(ARRAY_TYPE))
+        .union(SyntaxKindSet::singleton(SLICE_TYPE))
+        .union(SyntaxKindSet::singleton(REFERENCE_TYPE))
+        .union(SyntaxKindSet::singleton(PLACEHOLDER_TYPE))
+        .union(SyntaxKindSet::singleton(FN_POINTER_TYPE))
+        .union(SyntaxKindSet::singleton(FOR_TYPE))
+        .union(SyntaxKindSet::singleton(IMPL_TRAIT_TYPE))
+        .union(SyntaxKindSet::singleton(DYN_TRAIT_TYPE));
+    #[doc = "The kinds of `ast::Stmt` nodes."]
+    pub const STATEMENTS: SyntaxKindSet = SyntaxKindSet::EMPTY
+        .union(SyntaxKindSet::singleton(LET_STMT))
+        .union(SyntaxKindSet::singleton(EXPR_STMT));
+    #[doc = "The kinds of `ast::GenericParam` nodes."]
+    pub const GENERIC_PARAMS: SyntaxKindSet = SyntaxKindSet::EMPTY
+        .union(SyntaxKindSet::singleton(LIFETIME_PARAM))
+        .union(SyntaxKindSet::singleton(TYPE_PARAM))
+        .union(SyntaxKindSet::singleton(CONST_PARAM));
     pub fn is_keyword(self) -> bool {
         match self {
             AS_KW | ASYNC_KW | AWAIT_KW | BOX_KW | BREAK_KW | CONST_KW | CONTINUE_KW | CRATE_KW
@@ -656,3 +757,120 @@ macro_rules! T {
         $crate::SyntaxKind::RAW_KW
     };
 }
+#[doc = r" Sets of tokens for the parser, derived from the syntax kinds so that"]
+#[doc = r" they don't go stale when new tokens are added."]
+#[allow(dead_code)]
+pub(crate) mod token_sets {
+    use super::SyntaxKind::*;
+    use crate::TokenSet;
+    #[doc = r" All keywords, including the contextual ones."]
+    pub(crate) const KEYWORDS: TokenSet = TokenSet::EMPTY
+        .union(TokenSet::singleton(AS_KW))
+        .union(TokenSet::singleton(ASYNC_KW))
+        .union(TokenSet::singleton(AWAIT_KW))
+        .union(TokenSet::singleton(BOX_KW))
+        .union(TokenSet::singleton(BREAK_KW))
+        .union(TokenSet::singleton(CONST_KW))
+        .union(TokenSet::singleton(CONTINUE_KW))
+        .union(TokenSet::singleton(CRATE_KW))
+        .union(TokenSet::singleton(DYN_KW))
+        .union(TokenSet::singleton(ELSE_KW))
+        .union(TokenSet::singleton(ENUM_KW))
+        .union(TokenSet::singleton(EXTERN_KW))
+        .union(TokenSet::singleton(FALSE_KW))
+        .union(TokenSet::singleton(FN_KW))
+        .union(TokenSet::singleton(FOR_KW))
+        .union(TokenSet::singleton(IF_KW))
+        .union(TokenSet::singleton(IMPL_KW))
+        .union(TokenSet::singleton(IN_KW))
+        .union(TokenSet::singleton(LET_KW))
+        .union(TokenSet::singleton(LOOP_KW))
+        .union(TokenSet::singleton(MACRO_KW))
+        .union(TokenSet::singleton(MATCH_KW))
+        .union(TokenSet::singleton(MOD_KW))
+        .union(TokenSet::singleton(MOVE_KW))
+        .union(TokenSet::singleton(MUT_KW))
+        .union(TokenSet::singleton(PUB_KW))
+        .union(TokenSet::singleton(REF_KW))
+        .union(TokenSet::singleton(RETURN_KW))
+        .union(TokenSet::singleton(SELF_KW))
+        .union(TokenSet::singleton(STATIC_KW))
+        .union(TokenSet::singleton(STRUCT_KW))
+        .union(TokenSet::singleton(SUPER_KW))
+        .union(TokenSet::singleton(TRAIT_KW))
+        .union(TokenSet::singleton(TRUE_KW))
+        .union(TokenSet::singleton(TRY_KW))
+        .union(TokenSet::singleton(TYPE_KW))
+        .union(TokenSet::singleton(UNSAFE_KW))
+        .union(TokenSet::singleton(USE_KW))
+        .union(TokenSet::singleton(WHERE_KW))
+        .union(TokenSet::singleton(WHILE_KW))
+        .union(TokenSet::singleton(AUTO_KW))
+        .union(TokenSet::singleton(DEFAULT_KW))
+        .union(TokenSet::singleton(EXISTENTIAL_KW))
+        .union(TokenSet::singleton(UNION_KW))
+        .union(TokenSet::singleton(RAW_KW));
+    #[doc = r" Literals, including `true` and `false`."]
+    pub(crate) const LITERAL_FIRST: TokenSet = TokenSet::EMPTY
+        .union(TokenSet::singleton(TRUE_KW))
+        .union(TokenSet::singleton(FALSE_KW))
+        .union(TokenSet::singleton(INT_NUMBER))
+        .union(TokenSet::singleton(FLOAT_NUMBER))
+        .union(TokenSet::singleton(CHAR))
+        .union(TokenSet::singleton(BYTE))
+        .union(TokenSet::singleton(STRING))
+        .union(TokenSet::singleton(RAW_STRING))
+        .union(TokenSet::singleton(BYTE_STRING))
+        .union(TokenSet::singleton(RAW_BYTE_STRING));
+    #[doc = r" Tokens which can start an item, apart from attributes, macro"]
+    #[doc = r" calls and contextual keywords."]
+    pub(crate) const ITEM_FIRST: TokenSet = TokenSet::EMPTY
+        .union(TokenSet::singleton(ASYNC_KW))
+        .union(TokenSet::singleton(CONST_KW))
+        .union(TokenSet::singleton(CRATE_KW))
+        .union(TokenSet::singleton(ENUM_KW))
+        .union(TokenSet::singleton(EXTERN_KW))
+        .union(TokenSet::singleton(FN_KW))
+        .union(TokenSet::singleton(IMPL_KW))
+        .union(TokenSet::singleton(MACRO_KW))
+        .union(TokenSet::singleton(MOD_KW))
+        .union(TokenSet::singleton(PUB_KW))
+        .union(TokenSet::singleton(STATIC_KW))
+        .union(TokenSet::singleton(STRUCT_KW))
+        .union(TokenSet::singleton(TRAIT_KW))
+        .union(TokenSet::singleton(TYPE_KW))
+        .union(TokenSet::singleton(UNSAFE_KW))
+        .union(TokenSet::singleton(USE_KW));
+    #[doc = r" Tokens which can start an expression."]
+    pub(crate) const EXPR_FIRST: TokenSet = LITERAL_FIRST
+        .union(TokenSet::singleton(L_PAREN))
+        .union(TokenSet::singleton(L_CURLY))
+        .union(TokenSet::singleton(L_BRACK))
+        .union(TokenSet::singleton(PIPE))
+        .union(TokenSet::singleton(AMP))
+        .union(TokenSet::singleton(STAR))
+        .union(TokenSet::singleton(EXCL))
+        .union(TokenSet::singleton(DOT))
+        .union(TokenSet::singleton(MINUS))
+        .union(TokenSet::singleton(COLON))
+        .union(TokenSet::singleton(L_ANGLE))
+        .union(TokenSet::singleton(ASYNC_KW))
+        .union(TokenSet::singleton(BOX_KW))
+        .union(TokenSet::singleton(BREAK_KW))
+        .union(TokenSet::singleton(CONTINUE_KW))
+        .union(TokenSet::singleton(CRATE_KW))
+        .union(TokenSet::singleton(FOR_KW))
+        .union(TokenSet::singleton(IF_KW))
+        .union(TokenSet::singleton(LOOP_KW))
+        .union(TokenSet::singleton(MATCH_KW))
+        .union(TokenSet::singleton(MOVE_KW))
+        .union(TokenSet::singleton(RETURN_KW))
+        .union(TokenSet::singleton(SELF_KW))
+        .union(TokenSet::singleton(SUPER_KW))
+        .union(TokenSet::singleton(TRY_KW))
+        .union(TokenSet::singleton(UNSAFE_KW))
+        .union(TokenSet::singleton(WHILE_KW))
+        .union(TokenSet::singleton(IDENT))
+        .union(TokenSet::singleton(LIFETIME))
+        .union(TokenSet::singleton(L_DOLLAR));
+}
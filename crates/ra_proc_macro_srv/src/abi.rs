@@ -0,0 +1,192 @@
+//! Selects the `proc_macro` bridge ABI for a proc macro dylib.
+//!
+//! The bridge between a proc macro and its server is an implementation detail
+//! of rustc, which changes between releases. A dylib can only be driven by a
+//! copy of the bridge matching the toolchain it was compiled with, so we read
+//! the rustc version from the dylib and pick the matching copy. Every copy
+//! lives in its own module, next to `proc_macro`, and gets a variant in `Abi`,
+//! which `expand_task` and `list_macros` dispatch on.
+//!
+//! The version is taken from the `rustc version ...` identification string,
+//! which LLVM embeds in the `.comment` section of ELF binaries. For other
+//! formats we can't tell the version and fall back to the latest ABI.
+//!
+//! The ABI is cached per path until the dylib is rebuilt.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use goblin::Object;
+use memmap::Mmap;
+
+/// The rustc version a dylib was compiled with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RustcVersion {
+    pub(crate) major: u32,
+    pub(crate) minor: u32,
+    pub(crate) patch: u32,
+    /// `nightly`, `beta` or `dev`, `None` for stable releases.
+    pub(crate) channel: Option<String>,
+    /// The commit date, like `2020-03-01`.
+    pub(crate) date: Option<String>,
+}
+
+impl RustcVersion {
+    /// Parses the output of `rustc --version` or the identification string
+    /// in a binary, like `rustc version 1.43.0-nightly (6050e523b 2020-03-01)`.
+    pub(crate) fn parse(text: &str) -> Option<RustcVersion> {
+        let mut words = text.split_whitespace();
+        if words.next()? != "rustc" {
+            return None;
+        }
+        let mut release = words.next()?;
+        if release == "version" {
+            release = words.next()?;
+        }
+        let (numbers, channel) = match release.find('-') {
+            Some(idx) => (&release[..idx], Some(release[idx + 1..].to_string())),
+            None => (release, None),
+        };
+        let mut numbers = numbers.split('.').map(|it| it.parse::<u32>().ok());
+        let major = numbers.next()??;
+        let minor = numbers.next()??;
+        let patch = numbers.next()??;
+        if numbers.next().is_some() {
+            return None;
+        }
+        // The commit hash and date are missing in builds outside of git.
+        let date = words.nth(1).map(|it| it.trim_end_matches(')').to_string());
+        Some(RustcVersion { major, minor, patch, channel, date })
+    }
+
+    /// Reads the version from the identification string of a dylib. The dylib
+    /// is mapped into memory, so only its headers and the `.comment` section
+    /// are actually read.
+    pub(crate) fn read_dylib(path: &Path) -> io::Result<Option<RustcVersion>> {
+        let file = File::open(path)?;
+        let bytes = unsafe { Mmap::map(&file)? };
+        let elf = match Object::parse(&bytes) {
+            Ok(Object::Elf(elf)) => elf,
+            Ok(_) => return Ok(None),
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err.to_string())),
+        };
+        let comment = elf
+            .section_headers
+            .iter()
+            .find(|it| elf.shdr_strtab.get(it.sh_name).and_then(Result::ok) == Some(".comment"));
+        let comment = match comment {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        let start = comment.sh_offset as usize;
+        let end = start.saturating_add(comment.sh_size as usize).min(bytes.len());
+        // The section holds the NUL terminated identification strings of all
+        // the compilers which contributed to the binary.
+        let version = bytes[start.min(end)..end]
+            .split(|&b| b == 0)
+            .find_map(|it| RustcVersion::parse(&String::from_utf8_lossy(it)));
+        Ok(version)
+    }
+}
+
+impl fmt::Display for RustcVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(channel) = &self.channel {
+            write!(f, "-{}", channel)?;
+        }
+        Ok(())
+    }
+}
+
+/// A copy of the `proc_macro` bridge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Abi {
+    /// The bridge in `proc_macro`, copied from rustc 1.43.
+    Abi1_43,
+}
+
+/// The first rustc release using each ABI, ordered by release.
+const ABIS: &[((u32, u32), Abi)] = &[((1, 43), Abi::Abi1_43)];
+
+thread_local! {
+    /// The ABIs of the dylibs seen so far, with their modification times.
+    static DYLIB_ABIS: RefCell<HashMap<PathBuf, (SystemTime, Abi)>> = Default::default();
+}
+
+impl Abi {
+    pub(crate) fn latest() -> Abi {
+        ABIS[ABIS.len() - 1].1
+    }
+
+    /// The ABI of the bridge a dylib was compiled with.
+    pub(crate) fn for_dylib(path: &Path) -> Result<Abi, String> {
+        let read_error =
+            |err: io::Error| format!("failed to read proc macro dylib {}: {}", path.display(), err);
+        let modified = fs::metadata(path).and_then(|it| it.modified()).map_err(read_error)?;
+        let cached = DYLIB_ABIS.with(|abis| abis.borrow().get(path).copied());
+        if let Some((cached_modified, abi)) = cached {
+            if cached_modified == modified {
+                return Ok(abi);
+            }
+        }
+
+        let abi = match RustcVersion::read_dylib(path).map_err(read_error)? {
+            Some(version) => Abi::for_version(&version)?,
+            None => Abi::latest(),
+        };
+        DYLIB_ABIS.with(|abis| abis.borrow_mut().insert(path.to_path_buf(), (modified, abi)));
+        Ok(abi)
+    }
+
+    pub(crate) fn for_version(version: &RustcVersion) -> Result<Abi, String> {
+        ABIS.iter()
+            .rev()
+            .find(|((major, minor), _)| (version.major, version.minor) >= (*major, *minor))
+            .map(|&(_, abi)| abi)
+            .ok_or_else(|| format!("proc macros compiled with rustc {} are not supported", version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rustc_version() {
+        assert_eq!(
+            RustcVersion::parse("rustc version 1.43.0-nightly (6050e523b 2020-03-01)"),
+            Some(RustcVersion {
+                major: 1,
+                minor: 43,
+                patch: 0,
+                channel: Some("nightly".to_string()),
+                date: Some("2020-03-01".to_string()),
+            })
+        );
+        assert_eq!(
+            RustcVersion::parse("rustc 1.42.0"),
+            Some(RustcVersion { major: 1, minor: 42, patch: 0, channel: None, date: None })
+        );
+        assert_eq!(RustcVersion::parse("clang version 9.0.0"), None);
+        assert_eq!(RustcVersion::parse("rustc version 1.43"), None);
+    }
+
+    #[test]
+    fn select_abi_by_version() {
+        let abi = |text| Abi::for_version(&RustcVersion::parse(text).unwrap());
+        assert_eq!(abi("rustc 1.43.1"), Ok(Abi::Abi1_43));
+        assert_eq!(abi("rustc 1.44.0-nightly (abcdef012 2020-04-01)"), Ok(Abi::Abi1_43));
+        assert_eq!(
+            abi("rustc 1.40.0"),
+            Err("proc macros compiled with rustc 1.40.0 are not supported".to_string())
+        );
+    }
+}
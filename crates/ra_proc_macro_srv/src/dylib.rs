@@ -0,0 +1,129 @@
+//! Loads a proc macro dylib and runs its macros through the `proc_macro`
+//! bridge copied from rustc 1.43.
+
+use std::{fmt, fs::File, io, path::Path};
+
+use goblin::{mach::Mach, Object};
+use libloading::Library;
+use memmap::Mmap;
+use ra_proc_macro::ProcMacroKind;
+
+use crate::{
+    proc_macro::bridge::{self, server::SameThread},
+    rustc_server::{Rustc, TokenStream},
+};
+
+/// The static holding the macros of a proc macro crate is called
+/// `__rustc_proc_macro_decls_<hash>__`.
+const REGISTRAR_SYMBOL: &str = "_rustc_proc_macro_decls_";
+
+fn invalid_data(err: impl fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+fn exported_symbols(file: &Path) -> io::Result<Vec<String>> {
+    let file = File::open(file)?;
+    let buffer = unsafe { Mmap::map(&file)? };
+    let symbols = match Object::parse(&buffer).map_err(invalid_data)? {
+        Object::Elf(elf) => {
+            let symbols = elf.dynstrtab.to_vec().map_err(invalid_data)?;
+            symbols.iter().map(|it| it.to_string()).collect()
+        }
+        Object::PE(pe) => pe.exports.iter().flat_map(|it| it.name).map(str::to_string).collect(),
+        Object::Mach(Mach::Binary(binary)) => {
+            let exports = binary.exports().map_err(invalid_data)?;
+            // Mach-O symbols carry an extra leading underscore.
+            exports.into_iter().map(|it| it.name.trim_start_matches('_').to_string()).collect()
+        }
+        Object::Mach(Mach::Fat(_)) | Object::Archive(_) | Object::Unknown(_) => Vec::new(),
+    };
+    Ok(symbols)
+}
+
+#[cfg(windows)]
+fn load_library(file: &Path) -> Result<Library, libloading::Error> {
+    Library::new(file)
+}
+
+/// Loads the dylib with `RTLD_DEEPBIND`, so that it uses its own copy of
+/// `proc_macro` instead of one loaded by a dylib before it.
+#[cfg(unix)]
+fn load_library(file: &Path) -> Result<Library, libloading::Error> {
+    use libloading::os::unix::Library as UnixLibrary;
+    use std::os::raw::c_int;
+
+    const RTLD_NOW: c_int = 0x00002;
+    const RTLD_DEEPBIND: c_int = 0x00008;
+
+    UnixLibrary::open(Some(file), RTLD_NOW | RTLD_DEEPBIND).map(|lib| lib.into())
+}
+
+/// The macros of a dylib compiled with the 1.43 bridge.
+pub(crate) struct Expander {
+    // Keeps the code of the macros loaded.
+    _lib: Library,
+    macros: Vec<bridge::client::ProcMacro>,
+}
+
+impl Expander {
+    pub(crate) fn new(path: &Path) -> Result<Expander, String> {
+        let error = |err: &dyn fmt::Display| {
+            format!("failed to load proc macro dylib {}: {}", path.display(), err)
+        };
+        let symbol = exported_symbols(path)
+            .map_err(|err| error(&err))?
+            .into_iter()
+            .find(|it| it.contains(REGISTRAR_SYMBOL))
+            .ok_or_else(|| error(&"no proc macros are exported"))?;
+        let lib = load_library(path).map_err(|err| error(&err))?;
+        let macros = {
+            let macros: libloading::Symbol<&&[bridge::client::ProcMacro]> =
+                unsafe { lib.get(symbol.as_bytes()) }.map_err(|err| error(&err))?;
+            macros.to_vec()
+        };
+        Ok(Expander { _lib: lib, macros })
+    }
+
+    pub(crate) fn expand(
+        &self,
+        macro_name: &str,
+        macro_body: &ra_tt::Subtree,
+        attributes: Option<&ra_tt::Subtree>,
+    ) -> Result<ra_tt::Subtree, String> {
+        let proc_macro = self
+            .macros
+            .iter()
+            .find(|it| it.name() == macro_name)
+            .ok_or_else(|| format!("proc macro `{}` is not exported", macro_name))?;
+        let body = TokenStream { subtree: macro_body.clone() };
+        let res = match proc_macro {
+            bridge::client::ProcMacro::CustomDerive { client, .. }
+            | bridge::client::ProcMacro::Bang { client, .. } => {
+                client.run(&SameThread, Rustc::default(), body)
+            }
+            bridge::client::ProcMacro::Attr { client, .. } => {
+                let attributes = attributes
+                    .map_or_else(TokenStream::new, |it| TokenStream { subtree: it.clone() });
+                client.run(&SameThread, Rustc::default(), attributes, body)
+            }
+        };
+        res.map(|it| it.subtree).map_err(|msg| {
+            let msg = msg.as_str().unwrap_or("<unknown>");
+            format!("proc macro `{}` panicked: {}", macro_name, msg)
+        })
+    }
+
+    pub(crate) fn list_macros(&self) -> Vec<(String, ProcMacroKind)> {
+        self.macros
+            .iter()
+            .map(|it| {
+                let kind = match it {
+                    bridge::client::ProcMacro::CustomDerive { .. } => ProcMacroKind::CustomDerive,
+                    bridge::client::ProcMacro::Bang { .. } => ProcMacroKind::FuncLike,
+                    bridge::client::ProcMacro::Attr { .. } => ProcMacroKind::Attr,
+                };
+                (it.name().to_string(), kind)
+            })
+            .collect()
+    }
+}
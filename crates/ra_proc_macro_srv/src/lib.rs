@@ -17,13 +17,32 @@ mod proc_macro;
 #[doc(hidden)]
 mod rustc_server;
 
+mod abi;
+mod dylib;
+
+use abi::Abi;
 use proc_macro::bridge::client::TokenStream;
 use ra_proc_macro::{ExpansionResult, ExpansionTask, ListMacrosResult, ListMacrosTask};
 
-pub fn expand_task(_task: &ExpansionTask) -> Result<ExpansionResult, String> {
-    unimplemented!()
+pub fn expand_task(task: &ExpansionTask) -> Result<ExpansionResult, String> {
+    match Abi::for_dylib(&task.lib)? {
+        Abi::Abi1_43 => {
+            let expander = dylib::Expander::new(&task.lib)?;
+            let expansion =
+                expander.expand(&task.macro_name, &task.macro_body, task.attributes.as_ref())?;
+            Ok(ExpansionResult { expansion })
+        }
+    }
 }
 
-pub fn list_macros(_task: &ListMacrosTask) -> Result<ListMacrosResult, String> {
-    unimplemented!()
+pub fn list_macros(task: &ListMacrosTask) -> Result<ListMacrosResult, String> {
+    match Abi::for_dylib(&task.lib)? {
+        Abi::Abi1_43 => {
+            let expander = dylib::Expander::new(&task.lib)?;
+            Ok(ListMacrosResult { macros: expander.list_macros() })
+        }
+    }
 }
+
+#[cfg(test)]
+mod tests;
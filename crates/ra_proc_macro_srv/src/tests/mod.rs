@@ -0,0 +1,40 @@
+//! proc-macro tests
+
+mod utils;
+
+use ra_proc_macro::{ExpansionTask, ListMacrosTask, ProcMacroKind};
+
+use crate::{expand_task, list_macros};
+
+#[test]
+fn list_serde_derive_macros() {
+    let lib = utils::dylib_path("serde_derive", "1.0.104");
+    let res = list_macros(&ListMacrosTask { lib }).unwrap();
+    assert_eq!(
+        res.macros,
+        vec![
+            ("Serialize".to_string(), ProcMacroKind::CustomDerive),
+            ("Deserialize".to_string(), ProcMacroKind::CustomDerive),
+        ]
+    );
+}
+
+#[test]
+fn expand_serde_derive() {
+    let lib = utils::dylib_path("serde_derive", "1.0.104");
+    let (macro_body, _) = ra_mbe::parse_to_token_tree("struct Foo { bar: u32 }").unwrap();
+    let task =
+        ExpansionTask { macro_body, macro_name: "Serialize".to_string(), attributes: None, lib };
+    let res = expand_task(&task).unwrap();
+    let expansion = res.expansion.to_string();
+    assert!(expansion.contains("impl"), "{}", expansion);
+    assert!(expansion.contains("Serialize for Foo"), "{}", expansion);
+}
+
+#[test]
+fn unknown_macro_is_an_error() {
+    let lib = utils::dylib_path("serde_derive", "1.0.104");
+    let (macro_body, _) = ra_mbe::parse_to_token_tree("struct Foo;").unwrap();
+    let task = ExpansionTask { macro_body, macro_name: "Debug".to_string(), attributes: None, lib };
+    assert_eq!(expand_task(&task), Err("proc macro `Debug` is not exported".to_string()));
+}
@@ -0,0 +1,30 @@
+//! Finds the proc macro dylibs built for the dev-dependencies.
+
+use std::{path::PathBuf, process::Command};
+
+use cargo_metadata::{parse_messages, Message};
+
+/// Builds the dependencies of this crate and returns the dylib of the given
+/// proc macro crate.
+pub fn dylib_path(crate_name: &str, version: &str) -> PathBuf {
+    let name = format!("{} {} ", crate_name, version);
+    let output = Command::new("cargo")
+        .args(&["check", "--tests", "--message-format", "json"])
+        .output()
+        .unwrap()
+        .stdout;
+
+    for message in parse_messages(output.as_slice()) {
+        match message.unwrap() {
+            Message::CompilerArtifact(artifact)
+                if artifact.target.kind.iter().any(|it| it == "proc-macro")
+                    && artifact.package_id.repr.starts_with(&name) =>
+            {
+                return artifact.filenames[0].clone();
+            }
+            _ => (),
+        }
+    }
+
+    panic!("no proc macro dylib found for {}", name);
+}
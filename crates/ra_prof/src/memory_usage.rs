@@ -17,7 +17,21 @@ impl MemoryUsage {
         }
     }
 
-    #[cfg(any(not(feature = "jemalloc"), target_env = "msvc"))]
+    #[cfg(all(any(not(feature = "jemalloc"), target_env = "msvc"), target_os = "linux"))]
+    pub fn current() -> MemoryUsage {
+        // Without jemalloc we can't tell how much is allocated, but the kernel
+        // still knows the resident set size.
+        let resident = std::fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| {
+                let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+                line.split_whitespace().nth(1)?.parse::<usize>().ok()
+            })
+            .unwrap_or(0);
+        MemoryUsage { allocated: Bytes(0), resident: Bytes(resident * 1024) }
+    }
+
+    #[cfg(all(any(not(feature = "jemalloc"), target_env = "msvc"), not(target_os = "linux")))]
     pub fn current() -> MemoryUsage {
         MemoryUsage { allocated: Bytes(0), resident: Bytes(0) }
     }
@@ -32,6 +46,16 @@ impl fmt::Display for MemoryUsage {
 #[derive(Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub struct Bytes(usize);
 
+impl Bytes {
+    pub fn from_megabytes(megabytes: usize) -> Bytes {
+        Bytes(megabytes * 1024 * 1024)
+    }
+
+    pub fn megabytes(self) -> usize {
+        self.0 / 1024 / 1024
+    }
+}
+
 impl fmt::Display for Bytes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let bytes = self.0;
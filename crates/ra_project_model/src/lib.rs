@@ -330,6 +330,8 @@ impl ProjectWorkspace {
                                 opts
                             };
                             let mut env = Env::default();
+                            env.set("CARGO_PKG_NAME", cargo[pkg].name.clone());
+                            env.set("CARGO_PKG_VERSION", cargo[pkg].version.clone());
                             let mut extern_source = ExternSource::default();
                             if let Some(out_dir) = &cargo[pkg].out_dir {
                                 // FIXME: We probably mangle non UTF-8 paths here, figure out a better solution
@@ -98,62 +98,170 @@ pub enum InsertPosition<T> {
     After(T),
 }
 
+/// A structural difference between two syntax trees, computed by [`diff`].
+///
+/// All elements on the left-hand side (replaced and deleted elements, and the
+/// anchors of insertions) belong to the old tree, all inserted and replacing
+/// elements belong to the new one.
+#[derive(Debug, Default)]
 pub struct TreeDiff {
     replacements: FxHashMap<SyntaxElement, SyntaxElement>,
+    deletions: Vec<SyntaxElement>,
+    insertions: Vec<(TreeDiffInsertPos, Vec<SyntaxElement>)>,
+}
+
+/// Where elements are inserted into the old tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeDiffInsertPos {
+    After(SyntaxElement),
+    AsFirstChild(SyntaxNode),
+}
+
+impl TreeDiffInsertPos {
+    fn offset(&self) -> TextUnit {
+        match self {
+            TreeDiffInsertPos::After(it) => it.text_range().end(),
+            TreeDiffInsertPos::AsFirstChild(it) => it.text_range().start(),
+        }
+    }
 }
 
 impl TreeDiff {
     pub fn into_text_edit(&self, builder: &mut TextEditBuilder) {
+        // Insertions must go first: several of them can share an offset, and
+        // the builder keeps edits at the same offset in their original order.
+        for (pos, elements) in self.insertions.iter() {
+            builder.insert(pos.offset(), elements.iter().map(|it| it.to_string()).collect())
+        }
         for (from, to) in self.replacements.iter() {
             builder.replace(from.text_range(), to.to_string())
         }
+        for element in self.deletions.iter() {
+            builder.delete(element.text_range())
+        }
+    }
+
+    pub fn replacements(&self) -> &FxHashMap<SyntaxElement, SyntaxElement> {
+        &self.replacements
+    }
+
+    pub fn deletions(&self) -> &[SyntaxElement] {
+        &self.deletions
+    }
+
+    pub fn insertions(&self) -> &[(TreeDiffInsertPos, Vec<SyntaxElement>)] {
+        &self.insertions
     }
 
     pub fn is_empty(&self) -> bool {
-        self.replacements.is_empty()
+        self.replacements.is_empty() && self.deletions.is_empty() && self.insertions.is_empty()
+    }
+
+    fn insert(&mut self, pos: TreeDiffInsertPos, element: SyntaxElement) {
+        match self.insertions.last_mut() {
+            Some((last_pos, elements)) if *last_pos == pos => elements.push(element),
+            _ => self.insertions.push((pos, vec![element])),
+        }
     }
 }
 
-/// Finds minimal the diff, which, applied to `from`, will result in `to`.
+/// Finds a minimal diff, which, applied to `from`, will result in `to`.
 ///
-/// Specifically, returns a map whose keys are descendants of `from` and values
-/// are descendants of `to`, such that  `replace_descendants(from, map) == to`.
+/// Children of nodes of the same kind are matched up in order. Unmatched
+/// children on the left are deleted, unmatched children on the right are
+/// inserted, and differing pairs are diffed recursively, or replaced if they
+/// are of different kinds.
 ///
-/// A trivial solution is a singleton map `{ from: to }`, but this function
-/// tries to find a more fine-grained diff.
+/// A trivial solution is a single replacement of `from` by `to`, but this
+/// function tries to find a more fine-grained diff.
 pub fn diff(from: &SyntaxNode, to: &SyntaxNode) -> TreeDiff {
-    let mut buf = FxHashMap::default();
-    // FIXME: this is both horrible inefficient and gives larger than
-    // necessary diff. I bet there's a cool algorithm to diff trees properly.
-    go(&mut buf, from.clone().into(), to.clone().into());
-    return TreeDiff { replacements: buf };
-
-    fn go(
-        buf: &mut FxHashMap<SyntaxElement, SyntaxElement>,
-        lhs: SyntaxElement,
-        rhs: SyntaxElement,
-    ) {
-        if lhs.kind() == rhs.kind()
+    let mut diff = TreeDiff::default();
+    go(&mut diff, from.clone().into(), to.clone().into());
+    return diff;
+
+    fn go(diff: &mut TreeDiff, lhs: SyntaxElement, rhs: SyntaxElement) {
+        if syntax_element_eq(&lhs, &rhs) {
+            return;
+        }
+        let (lhs, rhs) = match (lhs, rhs) {
+            (NodeOrToken::Node(lhs), NodeOrToken::Node(rhs)) if lhs.kind() == rhs.kind() => {
+                (lhs, rhs)
+            }
+            (lhs, rhs) => {
+                diff.replacements.insert(lhs, rhs);
+                return;
+            }
+        };
+
+        let lhs_children = lhs.children_with_tokens().collect::<Vec<_>>();
+        let rhs_children = rhs.children_with_tokens().collect::<Vec<_>>();
+        let (mut i, mut j) = (0, 0);
+        let mut last_lhs: Option<SyntaxElement> = None;
+        while i < lhs_children.len() || j < rhs_children.len() {
+            let insert_pos = || match &last_lhs {
+                Some(it) => TreeDiffInsertPos::After(it.clone()),
+                None => TreeDiffInsertPos::AsFirstChild(lhs.clone()),
+            };
+            match (lhs_children.get(i), rhs_children.get(j)) {
+                (Some(lhs_child), Some(rhs_child)) if syntax_element_eq(lhs_child, rhs_child) => {
+                    i += 1;
+                    j += 1;
+                }
+                (Some(lhs_child), Some(rhs_child)) => {
+                    // If the left child shows up later on the right, everything
+                    // before it was inserted; if the right child shows up later
+                    // on the left, everything before it was deleted.
+                    let inserted = rhs_children[j + 1..]
+                        .iter()
+                        .position(|it| syntax_element_eq(lhs_child, it));
+                    let deleted = lhs_children[i + 1..]
+                        .iter()
+                        .position(|it| syntax_element_eq(it, rhs_child));
+                    match (inserted, deleted) {
+                        (Some(n), _) => {
+                            let pos = insert_pos();
+                            for element in &rhs_children[j..=j + n] {
+                                diff.insert(pos.clone(), element.clone());
+                            }
+                            j += n + 1;
+                            continue;
+                        }
+                        (None, Some(n)) => {
+                            diff.deletions.extend(lhs_children[i..=i + n].iter().cloned());
+                            i += n + 1;
+                        }
+                        (None, None) => {
+                            go(diff, lhs_child.clone(), rhs_child.clone());
+                            i += 1;
+                            j += 1;
+                        }
+                    }
+                }
+                (Some(lhs_child), None) => {
+                    diff.deletions.push(lhs_child.clone());
+                    i += 1;
+                }
+                (None, Some(rhs_child)) => {
+                    diff.insert(insert_pos(), rhs_child.clone());
+                    j += 1;
+                    continue;
+                }
+                (None, None) => unreachable!(),
+            }
+            last_lhs = Some(lhs_children[i - 1].clone());
+        }
+    }
+
+    fn syntax_element_eq(lhs: &SyntaxElement, rhs: &SyntaxElement) -> bool {
+        lhs.kind() == rhs.kind()
             && lhs.text_range().len() == rhs.text_range().len()
-            && match (&lhs, &rhs) {
+            && match (lhs, rhs) {
                 (NodeOrToken::Node(lhs), NodeOrToken::Node(rhs)) => {
                     lhs.green() == rhs.green() || lhs.text() == rhs.text()
                 }
                 (NodeOrToken::Token(lhs), NodeOrToken::Token(rhs)) => lhs.text() == rhs.text(),
                 _ => false,
             }
-        {
-            return;
-        }
-        if let (Some(lhs), Some(rhs)) = (lhs.as_node(), rhs.as_node()) {
-            if lhs.children_with_tokens().count() == rhs.children_with_tokens().count() {
-                for (lhs, rhs) in lhs.children_with_tokens().zip(rhs.children_with_tokens()) {
-                    go(buf, lhs, rhs)
-                }
-                return;
-            }
-        }
-        buf.insert(lhs, rhs);
     }
 }
 
@@ -368,3 +476,53 @@ fn to_green_element(element: SyntaxElement) -> NodeOrToken<rowan::GreenNode, row
         NodeOrToken::Token(it) => it.green().clone().into(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ra_text_edit::TextEditBuilder;
+
+    use crate::{AstNode, SourceFile};
+
+    use super::diff;
+
+    fn check_diff(from: &str, to: &str, expected: (usize, usize, usize)) {
+        let from_node = SourceFile::parse(from).tree().syntax().clone();
+        let to_node = SourceFile::parse(to).tree().syntax().clone();
+        let diff = diff(&from_node, &to_node);
+
+        let mut builder = TextEditBuilder::default();
+        diff.into_text_edit(&mut builder);
+        assert_eq!(builder.finish().apply(from), to);
+
+        let actual = (diff.insertions().len(), diff.deletions().len(), diff.replacements().len());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn diff_unchanged() {
+        check_diff("fn foo() {}", "fn foo() {}", (0, 0, 0));
+    }
+
+    #[test]
+    fn diff_insert_item() {
+        check_diff("use foo;\n\nfn main() {}", "use foo;\nuse bar;\n\nfn main() {}", (1, 0, 0));
+        check_diff("fn main() {}", "use foo;\nfn main() {}", (1, 0, 0));
+        check_diff("fn main() {}", "fn main() {}\nfn foo() {}", (1, 0, 0));
+    }
+
+    #[test]
+    fn diff_delete_item() {
+        check_diff("use foo;\nuse bar;\n\nfn main() {}", "use foo;\n\nfn main() {}", (0, 2, 0));
+    }
+
+    #[test]
+    fn diff_replace_token() {
+        check_diff("fn foo() { 1 + 2 }", "fn foo() { 1 - 2 }", (0, 0, 1));
+        check_diff("fn foo() -> u32 { 92 }", "fn bar() -> u32 { 92 }", (0, 0, 1));
+    }
+
+    #[test]
+    fn diff_mixed() {
+        check_diff("fn foo(a: u32) { a + 1 }", "fn foo(a: u32, b: u32) { a + b }", (1, 0, 1));
+    }
+}
@@ -21,7 +21,7 @@ pub use self::{
         AttrKind, FieldKind, PathSegmentKind, SelfParamKind, SlicePatComponents, StructKind,
         TypeBoundKind, VisibilityKind,
     },
-    generated::{nodes::*, tokens::*},
+    generated::{nodes::*, tokens::*, visit},
     tokens::*,
     traits::*,
 };
@@ -78,6 +78,14 @@ mod support {
     pub(super) fn token<T: AstToken>(parent: &SyntaxNode) -> Option<T> {
         parent.children_with_tokens().filter_map(|it| it.into_token()).find_map(T::cast)
     }
+
+    pub(super) fn nth_child<N: AstNode>(parent: &SyntaxNode, n: usize) -> Option<N> {
+        parent.children().filter_map(N::cast).nth(n)
+    }
+
+    pub(super) fn nth_token<T: AstToken>(parent: &SyntaxNode, n: usize) -> Option<T> {
+        parent.children_with_tokens().filter_map(|it| it.into_token()).filter_map(T::cast).nth(n)
+    }
 }
 
 /// An iterator over `SyntaxNode` children of a particular AST type.
@@ -318,3 +326,78 @@ where
     assert_eq!("for<'a> F", pred.type_ref().unwrap().syntax().text().to_string());
     assert_bound("Fn(&'a str)", bounds.next());
 }
+
+#[test]
+fn test_nth_children_and_tokens() {
+    let file = SourceFile::parse("fn f() { let _ = |x| a[i] + b; }").ok().unwrap();
+
+    let bin_expr = file.syntax().descendants().find_map(BinExpr::cast).unwrap();
+    assert_eq!(bin_expr.lhs().unwrap().syntax().text(), "a[i]");
+    assert_eq!(bin_expr.op_kind(), Some(BinOp::Addition));
+    assert_eq!(bin_expr.rhs().unwrap().syntax().text(), "b");
+
+    let index_expr = file.syntax().descendants().find_map(IndexExpr::cast).unwrap();
+    assert_eq!(index_expr.base().unwrap().syntax().text(), "a");
+    assert_eq!(index_expr.index().unwrap().syntax().text(), "i");
+
+    let param_list = file.syntax().descendants().find_map(ParamList::cast).unwrap();
+    assert!(param_list.l_paren_token().is_some());
+    let closure_params = file.syntax().descendants().filter_map(ParamList::cast).nth(1).unwrap();
+    assert_eq!(closure_params.l_pipe_token().unwrap().syntax().text_range().start(), 17.into());
+    assert_eq!(closure_params.r_pipe_token().unwrap().syntax().text_range().start(), 19.into());
+}
+
+#[test]
+fn test_generated_make() {
+    let expr = |text: &str| make::try_parse_expr(text).unwrap();
+    let ty = make::path_type_from_text("u32");
+
+    assert_eq!(make::index_expr(expr("xs"), expr("0")).to_string(), "xs[0]");
+    assert_eq!(make::index_expr(expr("&xs"), expr("a + b")).to_string(), "(&xs)[a + b]");
+    assert_eq!(make::cast_expr(expr("x"), ty.clone().into()).to_string(), "x as u32");
+    assert_eq!(make::cast_expr(expr("a + b"), ty.clone().into()).to_string(), "(a + b) as u32");
+    assert_eq!(make::try_expr(expr("f()?")).to_string(), "f()??");
+    assert_eq!(make::ref_expr(expr("a.b")).to_string(), "&a.b");
+    assert_eq!(make::ref_expr(expr("a || b")).to_string(), "&(a || b)");
+    assert_eq!(make::array_expr(vec![expr("1"), expr("2")]).to_string(), "[1, 2]");
+    assert_eq!(make::array_type(ty.into(), expr("4")).to_string(), "[u32; 4]");
+    assert_eq!(make::return_expr(None).to_string(), "return");
+    assert_eq!(make::return_expr(Some(expr("92"))).to_string(), "return 92");
+    assert_eq!(make::paren_pat(make::placeholder_pat().into()).to_string(), "(_)");
+    assert_eq!(make::arg_list(vec![expr("a"), expr("b")]).to_string(), "(a, b)");
+    assert_eq!(make::match_arm_from_text("_ => ()").to_string(), "_ => ()");
+}
+
+#[test]
+fn test_visitor() {
+    use visit::Visitor;
+
+    #[derive(Default)]
+    struct Names {
+        fns: Vec<std::string::String>,
+        calls: Vec<std::string::String>,
+    }
+
+    impl Visitor for Names {
+        fn visit_fn_def(&mut self, node: &FnDef) {
+            self.fns.push(node.name().unwrap().text().to_string());
+            self.walk(node.syntax());
+        }
+        fn visit_call_expr(&mut self, node: &CallExpr) {
+            self.calls.push(node.expr().unwrap().syntax().to_string());
+            self.walk(node.syntax());
+        }
+        // Don't look into nested modules.
+        fn visit_module(&mut self, _node: &Module) {}
+    }
+
+    let file = SourceFile::parse(
+        "fn main() { foo(bar()); fn inner() {} }\nmod m { fn hidden() { baz() } }",
+    )
+    .ok()
+    .unwrap();
+    let mut names = Names::default();
+    names.visit(file.syntax());
+    assert_eq!(names.fns, vec!["main", "inner"]);
+    assert_eq!(names.calls, vec!["foo", "bar"]);
+}
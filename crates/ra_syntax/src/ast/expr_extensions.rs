@@ -1,7 +1,7 @@
 //! Various extension methods to ast Expr Nodes, which are hard to code-generate.
 
 use crate::{
-    ast::{self, child_opt, children, AstChildren, AstNode},
+    ast::{self, child_opt, children, AstChildren, AstNode, AstToken},
     SmolStr,
     SyntaxKind::*,
     SyntaxToken, T,
@@ -48,6 +48,14 @@ impl ast::IfExpr {
     }
 }
 
+impl ast::Condition {
+    /// Whether this condition chains `let`s with `&&`, like
+    /// `let Some(x) = a && x > 0`. These have no pattern of their own.
+    pub fn is_let_chain(&self) -> bool {
+        self.pat().is_none() && self.syntax().descendants().any(|it| it.kind() == LET_EXPR)
+    }
+}
+
 impl ast::RefExpr {
     pub fn is_mut(&self) -> bool {
         self.syntax().children_with_tokens().any(|n| n.kind() == T![mut])
@@ -70,17 +78,13 @@ pub enum PrefixOp {
 
 impl ast::PrefixExpr {
     pub fn op_kind(&self) -> Option<PrefixOp> {
-        match self.op_token()?.kind() {
+        match self.op_token()?.syntax().kind() {
             T![*] => Some(PrefixOp::Deref),
             T![!] => Some(PrefixOp::Not),
             T![-] => Some(PrefixOp::Neg),
             _ => None,
         }
     }
-
-    pub fn op_token(&self) -> Option<SyntaxToken> {
-        self.syntax().first_child_or_token()?.into_token()
-    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -166,59 +170,46 @@ impl BinOp {
 
 impl ast::BinExpr {
     pub fn op_details(&self) -> Option<(SyntaxToken, BinOp)> {
-        self.syntax().children_with_tokens().filter_map(|it| it.into_token()).find_map(|c| {
-            let bin_op = match c.kind() {
-                T![||] => BinOp::BooleanOr,
-                T![&&] => BinOp::BooleanAnd,
-                T![==] => BinOp::EqualityTest,
-                T![!=] => BinOp::NegatedEqualityTest,
-                T![<=] => BinOp::LesserEqualTest,
-                T![>=] => BinOp::GreaterEqualTest,
-                T![<] => BinOp::LesserTest,
-                T![>] => BinOp::GreaterTest,
-                T![+] => BinOp::Addition,
-                T![*] => BinOp::Multiplication,
-                T![-] => BinOp::Subtraction,
-                T![/] => BinOp::Division,
-                T![%] => BinOp::Remainder,
-                T![<<] => BinOp::LeftShift,
-                T![>>] => BinOp::RightShift,
-                T![^] => BinOp::BitwiseXor,
-                T![|] => BinOp::BitwiseOr,
-                T![&] => BinOp::BitwiseAnd,
-                T![=] => BinOp::Assignment,
-                T![+=] => BinOp::AddAssign,
-                T![/=] => BinOp::DivAssign,
-                T![*=] => BinOp::MulAssign,
-                T![%=] => BinOp::RemAssign,
-                T![>>=] => BinOp::ShrAssign,
-                T![<<=] => BinOp::ShlAssign,
-                T![-=] => BinOp::SubAssign,
-                T![|=] => BinOp::BitOrAssign,
-                T![&=] => BinOp::BitAndAssign,
-                T![^=] => BinOp::BitXorAssign,
-                _ => return None,
-            };
-            Some((c, bin_op))
-        })
+        let token = self.op_token()?.syntax().clone();
+        let bin_op = match token.kind() {
+            T![||] => BinOp::BooleanOr,
+            T![&&] => BinOp::BooleanAnd,
+            T![==] => BinOp::EqualityTest,
+            T![!=] => BinOp::NegatedEqualityTest,
+            T![<=] => BinOp::LesserEqualTest,
+            T![>=] => BinOp::GreaterEqualTest,
+            T![<] => BinOp::LesserTest,
+            T![>] => BinOp::GreaterTest,
+            T![+] => BinOp::Addition,
+            T![*] => BinOp::Multiplication,
+            T![-] => BinOp::Subtraction,
+            T![/] => BinOp::Division,
+            T![%] => BinOp::Remainder,
+            T![<<] => BinOp::LeftShift,
+            T![>>] => BinOp::RightShift,
+            T![^] => BinOp::BitwiseXor,
+            T![|] => BinOp::BitwiseOr,
+            T![&] => BinOp::BitwiseAnd,
+            T![=] => BinOp::Assignment,
+            T![+=] => BinOp::AddAssign,
+            T![/=] => BinOp::DivAssign,
+            T![*=] => BinOp::MulAssign,
+            T![%=] => BinOp::RemAssign,
+            T![>>=] => BinOp::ShrAssign,
+            T![<<=] => BinOp::ShlAssign,
+            T![-=] => BinOp::SubAssign,
+            T![|=] => BinOp::BitOrAssign,
+            T![&=] => BinOp::BitAndAssign,
+            T![^=] => BinOp::BitXorAssign,
+            _ => return None,
+        };
+        Some((token, bin_op))
     }
 
     pub fn op_kind(&self) -> Option<BinOp> {
         self.op_details().map(|t| t.1)
     }
 
-    pub fn op_token(&self) -> Option<SyntaxToken> {
-        self.op_details().map(|t| t.0)
-    }
-
-    pub fn lhs(&self) -> Option<ast::Expr> {
-        children(self).next()
-    }
-
-    pub fn rhs(&self) -> Option<ast::Expr> {
-        children(self).nth(1)
-    }
-
     pub fn sub_exprs(&self) -> (Option<ast::Expr>, Option<ast::Expr>) {
         let mut children = children(self);
         let first = children.next();
@@ -252,10 +243,6 @@ impl ast::RangeExpr {
         self.op_details().map(|t| t.2)
     }
 
-    pub fn op_token(&self) -> Option<SyntaxToken> {
-        self.op_details().map(|t| t.1)
-    }
-
     pub fn start(&self) -> Option<ast::Expr> {
         let op_ix = self.op_details()?.0;
         self.syntax()
@@ -273,15 +260,6 @@ impl ast::RangeExpr {
     }
 }
 
-impl ast::IndexExpr {
-    pub fn base(&self) -> Option<ast::Expr> {
-        children(self).next()
-    }
-    pub fn index(&self) -> Option<ast::Expr> {
-        children(self).nth(1)
-    }
-}
-
 pub enum ArrayExprKind {
     Repeat { initializer: Option<ast::Expr>, repeat: Option<ast::Expr> },
     ElementList(AstChildren<ast::Expr>),
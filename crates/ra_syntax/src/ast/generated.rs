@@ -4,3 +4,10 @@
 pub(super) mod nodes;
 #[rustfmt::skip]
 pub(super) mod tokens;
+#[rustfmt::skip]
+pub(super) mod make;
+#[rustfmt::skip]
+pub mod visit;
+#[cfg(feature = "serialize_tree")]
+#[rustfmt::skip]
+mod serialize;
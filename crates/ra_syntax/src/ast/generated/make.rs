@@ -0,0 +1,324 @@
+//! Generated file, do not edit by hand, see `xtask/src/codegen`
+
+use crate::ast::{
+    self,
+    make::{ast_from_text, operand_text},
+};
+use itertools::Itertools;
+pub fn fn_def_from_text(text: &str) -> ast::FnDef { ast_from_text(text) }
+pub fn ret_type_from_text(text: &str) -> ast::RetType {
+    ast_from_text(&format!("fn f() {} {{}}", text))
+}
+pub fn struct_def_from_text(text: &str) -> ast::StructDef { ast_from_text(text) }
+pub fn union_def_from_text(text: &str) -> ast::UnionDef { ast_from_text(text) }
+pub fn enum_def_from_text(text: &str) -> ast::EnumDef { ast_from_text(text) }
+pub fn trait_def_from_text(text: &str) -> ast::TraitDef { ast_from_text(text) }
+pub fn module_from_text(text: &str) -> ast::Module { ast_from_text(text) }
+pub fn const_def_from_text(text: &str) -> ast::ConstDef { ast_from_text(text) }
+pub fn static_def_from_text(text: &str) -> ast::StaticDef { ast_from_text(text) }
+pub fn type_alias_def_from_text(text: &str) -> ast::TypeAliasDef { ast_from_text(text) }
+pub fn impl_def_from_text(text: &str) -> ast::ImplDef { ast_from_text(text) }
+pub fn paren_type_from_text(text: &str) -> ast::ParenType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn tuple_type_from_text(text: &str) -> ast::TupleType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn never_type_from_text(text: &str) -> ast::NeverType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn path_type_from_text(text: &str) -> ast::PathType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn pointer_type_from_text(text: &str) -> ast::PointerType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn array_type_from_text(text: &str) -> ast::ArrayType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn slice_type_from_text(text: &str) -> ast::SliceType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn reference_type_from_text(text: &str) -> ast::ReferenceType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn placeholder_type_from_text(text: &str) -> ast::PlaceholderType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn fn_pointer_type_from_text(text: &str) -> ast::FnPointerType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn for_type_from_text(text: &str) -> ast::ForType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn impl_trait_type_from_text(text: &str) -> ast::ImplTraitType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn dyn_trait_type_from_text(text: &str) -> ast::DynTraitType {
+    ast_from_text(&format!("type T = {};", text))
+}
+pub fn tuple_expr_from_text(text: &str) -> ast::TupleExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn array_expr_from_text(text: &str) -> ast::ArrayExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn paren_expr_from_text(text: &str) -> ast::ParenExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn path_expr_from_text(text: &str) -> ast::PathExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn lambda_expr_from_text(text: &str) -> ast::LambdaExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn if_expr_from_text(text: &str) -> ast::IfExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn loop_expr_from_text(text: &str) -> ast::LoopExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn try_block_expr_from_text(text: &str) -> ast::TryBlockExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn for_expr_from_text(text: &str) -> ast::ForExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn while_expr_from_text(text: &str) -> ast::WhileExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn continue_expr_from_text(text: &str) -> ast::ContinueExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn break_expr_from_text(text: &str) -> ast::BreakExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn label_from_text(text: &str) -> ast::Label {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn block_expr_from_text(text: &str) -> ast::BlockExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn return_expr_from_text(text: &str) -> ast::ReturnExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn call_expr_from_text(text: &str) -> ast::CallExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn method_call_expr_from_text(text: &str) -> ast::MethodCallExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn index_expr_from_text(text: &str) -> ast::IndexExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn field_expr_from_text(text: &str) -> ast::FieldExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn await_expr_from_text(text: &str) -> ast::AwaitExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn try_expr_from_text(text: &str) -> ast::TryExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn cast_expr_from_text(text: &str) -> ast::CastExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn ref_expr_from_text(text: &str) -> ast::RefExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn prefix_expr_from_text(text: &str) -> ast::PrefixExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn box_expr_from_text(text: &str) -> ast::BoxExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn let_expr_from_text(text: &str) -> ast::LetExpr {
+    ast_from_text(&format!("fn f() {{ if {} && true {{}} }}", text))
+}
+pub fn range_expr_from_text(text: &str) -> ast::RangeExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn bin_expr_from_text(text: &str) -> ast::BinExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn literal_from_text(text: &str) -> ast::Literal {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn match_expr_from_text(text: &str) -> ast::MatchExpr {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn match_arm_list_from_text(text: &str) -> ast::MatchArmList {
+    ast_from_text(&format!("fn f() {{ match () {} }}", text))
+}
+pub fn match_arm_from_text(text: &str) -> ast::MatchArm {
+    ast_from_text(&format!("fn f() {{ match () {{ {} }} }}", text))
+}
+pub fn record_lit_from_text(text: &str) -> ast::RecordLit {
+    ast_from_text(&format!("const C: () = {};", text))
+}
+pub fn record_field_from_text(text: &str) -> ast::RecordField {
+    ast_from_text(&format!("fn f() {{ S {{ {} }} }}", text))
+}
+pub fn or_pat_from_text(text: &str) -> ast::OrPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn paren_pat_from_text(text: &str) -> ast::ParenPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn ref_pat_from_text(text: &str) -> ast::RefPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn box_pat_from_text(text: &str) -> ast::BoxPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn bind_pat_from_text(text: &str) -> ast::BindPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn placeholder_pat_from_text(text: &str) -> ast::PlaceholderPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn dot_dot_pat_from_text(text: &str) -> ast::DotDotPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn path_pat_from_text(text: &str) -> ast::PathPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn slice_pat_from_text(text: &str) -> ast::SlicePat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn range_pat_from_text(text: &str) -> ast::RangePat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn literal_pat_from_text(text: &str) -> ast::LiteralPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn macro_pat_from_text(text: &str) -> ast::MacroPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn const_block_pat_from_text(text: &str) -> ast::ConstBlockPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn record_pat_from_text(text: &str) -> ast::RecordPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn tuple_struct_pat_from_text(text: &str) -> ast::TupleStructPat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn tuple_pat_from_text(text: &str) -> ast::TuplePat {
+    ast_from_text(&format!("fn f({}: ()) {{}}", text))
+}
+pub fn visibility_from_text(text: &str) -> ast::Visibility {
+    ast_from_text(&format!("{} fn f() {{}}", text))
+}
+pub fn name_from_text(text: &str) -> ast::Name { ast_from_text(&format!("mod {};", text)) }
+pub fn name_ref_from_text(text: &str) -> ast::NameRef {
+    ast_from_text(&format!("fn f() {{ {}; }}", text))
+}
+pub fn macro_call_from_text(text: &str) -> ast::MacroCall { ast_from_text(text) }
+pub fn attr_from_text(text: &str) -> ast::Attr { ast_from_text(&format!("{} fn f() {{}}", text)) }
+pub fn type_param_list_from_text(text: &str) -> ast::TypeParamList {
+    ast_from_text(&format!("fn f{}() {{}}", text))
+}
+pub fn type_param_from_text(text: &str) -> ast::TypeParam {
+    ast_from_text(&format!("fn f<{}>() {{}}", text))
+}
+pub fn const_param_from_text(text: &str) -> ast::ConstParam {
+    ast_from_text(&format!("fn f<{}>() {{}}", text))
+}
+pub fn lifetime_param_from_text(text: &str) -> ast::LifetimeParam {
+    ast_from_text(&format!("fn f<{}>() {{}}", text))
+}
+pub fn type_bound_from_text(text: &str) -> ast::TypeBound {
+    ast_from_text(&format!("fn f<T: {}>() {{}}", text))
+}
+pub fn where_pred_from_text(text: &str) -> ast::WherePred {
+    ast_from_text(&format!("fn f() where {} {{}}", text))
+}
+pub fn where_clause_from_text(text: &str) -> ast::WhereClause {
+    ast_from_text(&format!("fn f() {} {{}}", text))
+}
+pub fn expr_stmt_from_text(text: &str) -> ast::ExprStmt {
+    ast_from_text(&format!("fn f() {{ {} }}", text))
+}
+pub fn let_stmt_from_text(text: &str) -> ast::LetStmt {
+    ast_from_text(&format!("fn f() {{ {} }}", text))
+}
+pub fn condition_from_text(text: &str) -> ast::Condition {
+    ast_from_text(&format!("fn f() {{ while {} {{}} }}", text))
+}
+pub fn param_list_from_text(text: &str) -> ast::ParamList {
+    ast_from_text(&format!("fn f{} {{}}", text))
+}
+pub fn param_from_text(text: &str) -> ast::Param { ast_from_text(&format!("fn f({}) {{}}", text)) }
+pub fn use_item_from_text(text: &str) -> ast::UseItem { ast_from_text(text) }
+pub fn use_tree_from_text(text: &str) -> ast::UseTree { ast_from_text(&format!("use {};", text)) }
+pub fn extern_crate_item_from_text(text: &str) -> ast::ExternCrateItem { ast_from_text(text) }
+pub fn arg_list_from_text(text: &str) -> ast::ArgList {
+    ast_from_text(&format!("fn f() {{ f{}; }}", text))
+}
+pub fn path_segment_from_text(text: &str) -> ast::PathSegment {
+    ast_from_text(&format!("use {};", text))
+}
+pub fn type_arg_from_text(text: &str) -> ast::TypeArg {
+    ast_from_text(&format!("type T = S<{}>;", text))
+}
+pub fn assoc_type_arg_from_text(text: &str) -> ast::AssocTypeArg {
+    ast_from_text(&format!("type T = S<{}>;", text))
+}
+pub fn lifetime_arg_from_text(text: &str) -> ast::LifetimeArg {
+    ast_from_text(&format!("type T = S<{}>;", text))
+}
+pub fn const_arg_from_text(text: &str) -> ast::ConstArg {
+    ast_from_text(&format!("type T = S<{}>;", text))
+}
+pub fn extern_block_from_text(text: &str) -> ast::ExternBlock { ast_from_text(text) }
+pub fn arg_list(args: impl IntoIterator<Item = ast::Expr>) -> ast::ArgList {
+    arg_list_from_text(&format!("({})", args.into_iter().join(", ")))
+}
+pub fn array_expr(exprs: impl IntoIterator<Item = ast::Expr>) -> ast::ArrayExpr {
+    array_expr_from_text(&format!("[{}]", exprs.into_iter().join(", ")))
+}
+pub fn array_type(type_ref: ast::TypeRef, expr: ast::Expr) -> ast::ArrayType {
+    array_type_from_text(&format!("[{}; {}]", type_ref, expr))
+}
+pub fn await_expr(expr: ast::Expr) -> ast::AwaitExpr {
+    await_expr_from_text(&format!("{}.await", operand_text(&expr)))
+}
+pub fn box_expr(expr: ast::Expr) -> ast::BoxExpr {
+    box_expr_from_text(&format!("box {}", operand_text(&expr)))
+}
+pub fn cast_expr(expr: ast::Expr, type_ref: ast::TypeRef) -> ast::CastExpr {
+    cast_expr_from_text(&format!("{} as {}", operand_text(&expr), type_ref))
+}
+pub fn field_expr(expr: ast::Expr, name_ref: ast::NameRef) -> ast::FieldExpr {
+    field_expr_from_text(&format!("{}.{}", operand_text(&expr), name_ref))
+}
+pub fn index_expr(base: ast::Expr, index: ast::Expr) -> ast::IndexExpr {
+    index_expr_from_text(&format!("{}[{}]", operand_text(&base), index))
+}
+pub fn paren_expr(expr: ast::Expr) -> ast::ParenExpr {
+    paren_expr_from_text(&format!("({})", expr))
+}
+pub fn paren_pat(pat: ast::Pat) -> ast::ParenPat { paren_pat_from_text(&format!("({})", pat)) }
+pub fn paren_type(type_ref: ast::TypeRef) -> ast::ParenType {
+    paren_type_from_text(&format!("({})", type_ref))
+}
+pub fn ref_expr(expr: ast::Expr) -> ast::RefExpr {
+    ref_expr_from_text(&format!("&{}", operand_text(&expr)))
+}
+pub fn ref_pat(pat: ast::Pat) -> ast::RefPat { ref_pat_from_text(&format!("&{}", pat)) }
+pub fn ret_type(type_ref: ast::TypeRef) -> ast::RetType {
+    ret_type_from_text(&format!("-> {}", type_ref))
+}
+pub fn return_expr(expr: Option<ast::Expr>) -> ast::ReturnExpr {
+    return_expr_from_text(&format!("return {}", expr.map_or(String::new(), |it| it.to_string())))
+}
+pub fn slice_pat(args: impl IntoIterator<Item = ast::Pat>) -> ast::SlicePat {
+    slice_pat_from_text(&format!("[{}]", args.into_iter().join(", ")))
+}
+pub fn slice_type(type_ref: ast::TypeRef) -> ast::SliceType {
+    slice_type_from_text(&format!("[{}]", type_ref))
+}
+pub fn try_expr(expr: ast::Expr) -> ast::TryExpr {
+    try_expr_from_text(&format!("{}?", operand_text(&expr)))
+}
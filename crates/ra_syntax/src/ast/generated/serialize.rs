@@ -0,0 +1,712 @@
+//! Generated file, do not edit by hand, see `xtask/src/codegen`
+
+use crate::{
+    ast::{self, AstNode},
+    SerializeTree,
+};
+use serde::{Serialize, Serializer};
+impl Serialize for ast::SourceFile {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::FnDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RetType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::StructDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::UnionDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordFieldDefList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordFieldDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TupleFieldDefList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TupleFieldDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::EnumDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::EnumVariantList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::EnumVariant {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TraitDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Module {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ItemList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ConstDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::StaticDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TypeAliasDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ImplDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ParenType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TupleType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::NeverType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::PathType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::PointerType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ArrayType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::SliceType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ReferenceType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::PlaceholderType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::FnPointerType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ForType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ImplTraitType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::DynTraitType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TupleExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ArrayExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ParenExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::PathExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::LambdaExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::IfExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::LoopExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TryBlockExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ForExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::WhileExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ContinueExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::BreakExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Label {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::BlockExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ReturnExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::CallExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MethodCallExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::IndexExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::FieldExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::AwaitExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TryExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::CastExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RefExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::PrefixExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::BoxExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::LetExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RangeExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::BinExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Literal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MatchExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MatchArmList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MatchArm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MatchGuard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordLit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordFieldList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::OrPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ParenPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RefPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::BoxPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::BindPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::PlaceholderPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::DotDotPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::PathPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::SlicePat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RangePat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::LiteralPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MacroPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ConstBlockPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordFieldPatList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordFieldPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TupleStructPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TuplePat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Visibility {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Name {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::NameRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MacroCall {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Attr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TokenTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TypeParamList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TypeParam {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ConstParam {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::LifetimeParam {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TypeBound {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TypeBoundList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::WherePred {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::WhereClause {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Abi {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ExprStmt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::LetStmt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::LetElse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Condition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Block {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ParamList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::SelfParam {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Param {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::UseItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::UseTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Alias {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::UseTreeList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ExternCrateItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ArgList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::PathSegment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TypeArgList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TypeArg {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::AssocTypeArg {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::LifetimeArg {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ConstArg {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MacroItems {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MacroStmts {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ExternItemList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ExternBlock {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MetaItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::MacroDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::NominalDef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::GenericParam {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::GenericArg {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::TypeRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ModuleItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ImplItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::ExternItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Expr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Pat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::RecordInnerPat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::AttrInput {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::Stmt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
+impl Serialize for ast::FieldDefList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTree(self.syntax()).serialize(serializer)
+    }
+}
@@ -0,0 +1,340 @@
+//! Generated file, do not edit by hand, see `xtask/src/codegen`
+
+use crate::{
+    ast::{self, AstNode},
+    SyntaxKind::*,
+    SyntaxNode,
+};
+#[doc = r" A visitor over the syntax tree, with a method for each kind of node."]
+#[doc = r""]
+#[doc = r" The default implementations just visit the children of the node, so"]
+#[doc = r" an implementation only needs to override the methods for the nodes it"]
+#[doc = r" is interested in, and can stop the traversal by not calling `walk`."]
+pub trait Visitor {
+    #[doc = r" Calls the `visit_*` method for the kind of `node`."]
+    fn visit(&mut self, node: &SyntaxNode) {
+        match node.kind() {
+            SOURCE_FILE => self.visit_source_file(&ast::SourceFile { syntax: node.clone() }),
+            FN_DEF => self.visit_fn_def(&ast::FnDef { syntax: node.clone() }),
+            RET_TYPE => self.visit_ret_type(&ast::RetType { syntax: node.clone() }),
+            STRUCT_DEF => self.visit_struct_def(&ast::StructDef { syntax: node.clone() }),
+            UNION_DEF => self.visit_union_def(&ast::UnionDef { syntax: node.clone() }),
+            RECORD_FIELD_DEF_LIST => {
+                self.visit_record_field_def_list(&ast::RecordFieldDefList { syntax: node.clone() })
+            }
+            RECORD_FIELD_DEF => {
+                self.visit_record_field_def(&ast::RecordFieldDef { syntax: node.clone() })
+            }
+            TUPLE_FIELD_DEF_LIST => {
+                self.visit_tuple_field_def_list(&ast::TupleFieldDefList { syntax: node.clone() })
+            }
+            TUPLE_FIELD_DEF => {
+                self.visit_tuple_field_def(&ast::TupleFieldDef { syntax: node.clone() })
+            }
+            ENUM_DEF => self.visit_enum_def(&ast::EnumDef { syntax: node.clone() }),
+            ENUM_VARIANT_LIST => {
+                self.visit_enum_variant_list(&ast::EnumVariantList { syntax: node.clone() })
+            }
+            ENUM_VARIANT => self.visit_enum_variant(&ast::EnumVariant { syntax: node.clone() }),
+            TRAIT_DEF => self.visit_trait_def(&ast::TraitDef { syntax: node.clone() }),
+            MODULE => self.visit_module(&ast::Module { syntax: node.clone() }),
+            ITEM_LIST => self.visit_item_list(&ast::ItemList { syntax: node.clone() }),
+            CONST_DEF => self.visit_const_def(&ast::ConstDef { syntax: node.clone() }),
+            STATIC_DEF => self.visit_static_def(&ast::StaticDef { syntax: node.clone() }),
+            TYPE_ALIAS_DEF => {
+                self.visit_type_alias_def(&ast::TypeAliasDef { syntax: node.clone() })
+            }
+            IMPL_DEF => self.visit_impl_def(&ast::ImplDef { syntax: node.clone() }),
+            PAREN_TYPE => self.visit_paren_type(&ast::ParenType { syntax: node.clone() }),
+            TUPLE_TYPE => self.visit_tuple_type(&ast::TupleType { syntax: node.clone() }),
+            NEVER_TYPE => self.visit_never_type(&ast::NeverType { syntax: node.clone() }),
+            PATH_TYPE => self.visit_path_type(&ast::PathType { syntax: node.clone() }),
+            POINTER_TYPE => self.visit_pointer_type(&ast::PointerType { syntax: node.clone() }),
+            ARRAY_TYPE => self.visit_array_type(&ast::ArrayType { syntax: node.clone() }),
+            SLICE_TYPE => self.visit_slice_type(&ast::SliceType { syntax: node.clone() }),
+            REFERENCE_TYPE => {
+                self.visit_reference_type(&ast::ReferenceType { syntax: node.clone() })
+            }
+            PLACEHOLDER_TYPE => {
+                self.visit_placeholder_type(&ast::PlaceholderType { syntax: node.clone() })
+            }
+            FN_POINTER_TYPE => {
+                self.visit_fn_pointer_type(&ast::FnPointerType { syntax: node.clone() })
+            }
+            FOR_TYPE => self.visit_for_type(&ast::ForType { syntax: node.clone() }),
+            IMPL_TRAIT_TYPE => {
+                self.visit_impl_trait_type(&ast::ImplTraitType { syntax: node.clone() })
+            }
+            DYN_TRAIT_TYPE => {
+                self.visit_dyn_trait_type(&ast::DynTraitType { syntax: node.clone() })
+            }
+            TUPLE_EXPR => self.visit_tuple_expr(&ast::TupleExpr { syntax: node.clone() }),
+            ARRAY_EXPR => self.visit_array_expr(&ast::ArrayExpr { syntax: node.clone() }),
+            PAREN_EXPR => self.visit_paren_expr(&ast::ParenExpr { syntax: node.clone() }),
+            PATH_EXPR => self.visit_path_expr(&ast::PathExpr { syntax: node.clone() }),
+            LAMBDA_EXPR => self.visit_lambda_expr(&ast::LambdaExpr { syntax: node.clone() }),
+            IF_EXPR => self.visit_if_expr(&ast::IfExpr { syntax: node.clone() }),
+            LOOP_EXPR => self.visit_loop_expr(&ast::LoopExpr { syntax: node.clone() }),
+            TRY_BLOCK_EXPR => {
+                self.visit_try_block_expr(&ast::TryBlockExpr { syntax: node.clone() })
+            }
+            FOR_EXPR => self.visit_for_expr(&ast::ForExpr { syntax: node.clone() }),
+            WHILE_EXPR => self.visit_while_expr(&ast::WhileExpr { syntax: node.clone() }),
+            CONTINUE_EXPR => self.visit_continue_expr(&ast::ContinueExpr { syntax: node.clone() }),
+            BREAK_EXPR => self.visit_break_expr(&ast::BreakExpr { syntax: node.clone() }),
+            LABEL => self.visit_label(&ast::Label { syntax: node.clone() }),
+            BLOCK_EXPR => self.visit_block_expr(&ast::BlockExpr { syntax: node.clone() }),
+            RETURN_EXPR => self.visit_return_expr(&ast::ReturnExpr { syntax: node.clone() }),
+            CALL_EXPR => self.visit_call_expr(&ast::CallExpr { syntax: node.clone() }),
+            METHOD_CALL_EXPR => {
+                self.visit_method_call_expr(&ast::MethodCallExpr { syntax: node.clone() })
+            }
+            INDEX_EXPR => self.visit_index_expr(&ast::IndexExpr { syntax: node.clone() }),
+            FIELD_EXPR => self.visit_field_expr(&ast::FieldExpr { syntax: node.clone() }),
+            AWAIT_EXPR => self.visit_await_expr(&ast::AwaitExpr { syntax: node.clone() }),
+            TRY_EXPR => self.visit_try_expr(&ast::TryExpr { syntax: node.clone() }),
+            CAST_EXPR => self.visit_cast_expr(&ast::CastExpr { syntax: node.clone() }),
+            REF_EXPR => self.visit_ref_expr(&ast::RefExpr { syntax: node.clone() }),
+            PREFIX_EXPR => self.visit_prefix_expr(&ast::PrefixExpr { syntax: node.clone() }),
+            BOX_EXPR => self.visit_box_expr(&ast::BoxExpr { syntax: node.clone() }),
+            LET_EXPR => self.visit_let_expr(&ast::LetExpr { syntax: node.clone() }),
+            RANGE_EXPR => self.visit_range_expr(&ast::RangeExpr { syntax: node.clone() }),
+            BIN_EXPR => self.visit_bin_expr(&ast::BinExpr { syntax: node.clone() }),
+            LITERAL => self.visit_literal(&ast::Literal { syntax: node.clone() }),
+            MATCH_EXPR => self.visit_match_expr(&ast::MatchExpr { syntax: node.clone() }),
+            MATCH_ARM_LIST => {
+                self.visit_match_arm_list(&ast::MatchArmList { syntax: node.clone() })
+            }
+            MATCH_ARM => self.visit_match_arm(&ast::MatchArm { syntax: node.clone() }),
+            MATCH_GUARD => self.visit_match_guard(&ast::MatchGuard { syntax: node.clone() }),
+            RECORD_LIT => self.visit_record_lit(&ast::RecordLit { syntax: node.clone() }),
+            RECORD_FIELD_LIST => {
+                self.visit_record_field_list(&ast::RecordFieldList { syntax: node.clone() })
+            }
+            RECORD_FIELD => self.visit_record_field(&ast::RecordField { syntax: node.clone() }),
+            OR_PAT => self.visit_or_pat(&ast::OrPat { syntax: node.clone() }),
+            PAREN_PAT => self.visit_paren_pat(&ast::ParenPat { syntax: node.clone() }),
+            REF_PAT => self.visit_ref_pat(&ast::RefPat { syntax: node.clone() }),
+            BOX_PAT => self.visit_box_pat(&ast::BoxPat { syntax: node.clone() }),
+            BIND_PAT => self.visit_bind_pat(&ast::BindPat { syntax: node.clone() }),
+            PLACEHOLDER_PAT => {
+                self.visit_placeholder_pat(&ast::PlaceholderPat { syntax: node.clone() })
+            }
+            DOT_DOT_PAT => self.visit_dot_dot_pat(&ast::DotDotPat { syntax: node.clone() }),
+            PATH_PAT => self.visit_path_pat(&ast::PathPat { syntax: node.clone() }),
+            SLICE_PAT => self.visit_slice_pat(&ast::SlicePat { syntax: node.clone() }),
+            RANGE_PAT => self.visit_range_pat(&ast::RangePat { syntax: node.clone() }),
+            LITERAL_PAT => self.visit_literal_pat(&ast::LiteralPat { syntax: node.clone() }),
+            MACRO_PAT => self.visit_macro_pat(&ast::MacroPat { syntax: node.clone() }),
+            CONST_BLOCK_PAT => {
+                self.visit_const_block_pat(&ast::ConstBlockPat { syntax: node.clone() })
+            }
+            RECORD_PAT => self.visit_record_pat(&ast::RecordPat { syntax: node.clone() }),
+            RECORD_FIELD_PAT_LIST => {
+                self.visit_record_field_pat_list(&ast::RecordFieldPatList { syntax: node.clone() })
+            }
+            RECORD_FIELD_PAT => {
+                self.visit_record_field_pat(&ast::RecordFieldPat { syntax: node.clone() })
+            }
+            TUPLE_STRUCT_PAT => {
+                self.visit_tuple_struct_pat(&ast::TupleStructPat { syntax: node.clone() })
+            }
+            TUPLE_PAT => self.visit_tuple_pat(&ast::TuplePat { syntax: node.clone() }),
+            VISIBILITY => self.visit_visibility(&ast::Visibility { syntax: node.clone() }),
+            NAME => self.visit_name(&ast::Name { syntax: node.clone() }),
+            NAME_REF => self.visit_name_ref(&ast::NameRef { syntax: node.clone() }),
+            MACRO_CALL => self.visit_macro_call(&ast::MacroCall { syntax: node.clone() }),
+            ATTR => self.visit_attr(&ast::Attr { syntax: node.clone() }),
+            TOKEN_TREE => self.visit_token_tree(&ast::TokenTree { syntax: node.clone() }),
+            TYPE_PARAM_LIST => {
+                self.visit_type_param_list(&ast::TypeParamList { syntax: node.clone() })
+            }
+            TYPE_PARAM => self.visit_type_param(&ast::TypeParam { syntax: node.clone() }),
+            CONST_PARAM => self.visit_const_param(&ast::ConstParam { syntax: node.clone() }),
+            LIFETIME_PARAM => {
+                self.visit_lifetime_param(&ast::LifetimeParam { syntax: node.clone() })
+            }
+            TYPE_BOUND => self.visit_type_bound(&ast::TypeBound { syntax: node.clone() }),
+            TYPE_BOUND_LIST => {
+                self.visit_type_bound_list(&ast::TypeBoundList { syntax: node.clone() })
+            }
+            WHERE_PRED => self.visit_where_pred(&ast::WherePred { syntax: node.clone() }),
+            WHERE_CLAUSE => self.visit_where_clause(&ast::WhereClause { syntax: node.clone() }),
+            ABI => self.visit_abi(&ast::Abi { syntax: node.clone() }),
+            EXPR_STMT => self.visit_expr_stmt(&ast::ExprStmt { syntax: node.clone() }),
+            LET_STMT => self.visit_let_stmt(&ast::LetStmt { syntax: node.clone() }),
+            LET_ELSE => self.visit_let_else(&ast::LetElse { syntax: node.clone() }),
+            CONDITION => self.visit_condition(&ast::Condition { syntax: node.clone() }),
+            BLOCK => self.visit_block(&ast::Block { syntax: node.clone() }),
+            PARAM_LIST => self.visit_param_list(&ast::ParamList { syntax: node.clone() }),
+            SELF_PARAM => self.visit_self_param(&ast::SelfParam { syntax: node.clone() }),
+            PARAM => self.visit_param(&ast::Param { syntax: node.clone() }),
+            USE_ITEM => self.visit_use_item(&ast::UseItem { syntax: node.clone() }),
+            USE_TREE => self.visit_use_tree(&ast::UseTree { syntax: node.clone() }),
+            ALIAS => self.visit_alias(&ast::Alias { syntax: node.clone() }),
+            USE_TREE_LIST => self.visit_use_tree_list(&ast::UseTreeList { syntax: node.clone() }),
+            EXTERN_CRATE_ITEM => {
+                self.visit_extern_crate_item(&ast::ExternCrateItem { syntax: node.clone() })
+            }
+            ARG_LIST => self.visit_arg_list(&ast::ArgList { syntax: node.clone() }),
+            PATH => self.visit_path(&ast::Path { syntax: node.clone() }),
+            PATH_SEGMENT => self.visit_path_segment(&ast::PathSegment { syntax: node.clone() }),
+            TYPE_ARG_LIST => self.visit_type_arg_list(&ast::TypeArgList { syntax: node.clone() }),
+            TYPE_ARG => self.visit_type_arg(&ast::TypeArg { syntax: node.clone() }),
+            ASSOC_TYPE_ARG => {
+                self.visit_assoc_type_arg(&ast::AssocTypeArg { syntax: node.clone() })
+            }
+            LIFETIME_ARG => self.visit_lifetime_arg(&ast::LifetimeArg { syntax: node.clone() }),
+            CONST_ARG => self.visit_const_arg(&ast::ConstArg { syntax: node.clone() }),
+            MACRO_ITEMS => self.visit_macro_items(&ast::MacroItems { syntax: node.clone() }),
+            MACRO_STMTS => self.visit_macro_stmts(&ast::MacroStmts { syntax: node.clone() }),
+            EXTERN_ITEM_LIST => {
+                self.visit_extern_item_list(&ast::ExternItemList { syntax: node.clone() })
+            }
+            EXTERN_BLOCK => self.visit_extern_block(&ast::ExternBlock { syntax: node.clone() }),
+            META_ITEM => self.visit_meta_item(&ast::MetaItem { syntax: node.clone() }),
+            MACRO_DEF => self.visit_macro_def(&ast::MacroDef { syntax: node.clone() }),
+            _ => self.walk(node),
+        }
+    }
+    #[doc = r" Visits the children of `node`."]
+    fn walk(&mut self, node: &SyntaxNode) {
+        for child in node.children() {
+            self.visit(&child);
+        }
+    }
+    fn visit_source_file(&mut self, node: &ast::SourceFile) { self.walk(node.syntax()) }
+    fn visit_fn_def(&mut self, node: &ast::FnDef) { self.walk(node.syntax()) }
+    fn visit_ret_type(&mut self, node: &ast::RetType) { self.walk(node.syntax()) }
+    fn visit_struct_def(&mut self, node: &ast::StructDef) { self.walk(node.syntax()) }
+    fn visit_union_def(&mut self, node: &ast::UnionDef) { self.walk(node.syntax()) }
+    fn visit_record_field_def_list(&mut self, node: &ast::RecordFieldDefList) {
+        self.walk(node.syntax())
+    }
+    fn visit_record_field_def(&mut self, node: &ast::RecordFieldDef) { self.walk(node.syntax()) }
+    fn visit_tuple_field_def_list(&mut self, node: &ast::TupleFieldDefList) {
+        self.walk(node.syntax())
+    }
+    fn visit_tuple_field_def(&mut self, node: &ast::TupleFieldDef) { self.walk(node.syntax()) }
+    fn visit_enum_def(&mut self, node: &ast::EnumDef) { self.walk(node.syntax()) }
+    fn visit_enum_variant_list(&mut self, node: &ast::EnumVariantList) { self.walk(node.syntax()) }
+    fn visit_enum_variant(&mut self, node: &ast::EnumVariant) { self.walk(node.syntax()) }
+    fn visit_trait_def(&mut self, node: &ast::TraitDef) { self.walk(node.syntax()) }
+    fn visit_module(&mut self, node: &ast::Module) { self.walk(node.syntax()) }
+    fn visit_item_list(&mut self, node: &ast::ItemList) { self.walk(node.syntax()) }
+    fn visit_const_def(&mut self, node: &ast::ConstDef) { self.walk(node.syntax()) }
+    fn visit_static_def(&mut self, node: &ast::StaticDef) { self.walk(node.syntax()) }
+    fn visit_type_alias_def(&mut self, node: &ast::TypeAliasDef) { self.walk(node.syntax()) }
+    fn visit_impl_def(&mut self, node: &ast::ImplDef) { self.walk(node.syntax()) }
+    fn visit_paren_type(&mut self, node: &ast::ParenType) { self.walk(node.syntax()) }
+    fn visit_tuple_type(&mut self, node: &ast::TupleType) { self.walk(node.syntax()) }
+    fn visit_never_type(&mut self, node: &ast::NeverType) { self.walk(node.syntax()) }
+    fn visit_path_type(&mut self, node: &ast::PathType) { self.walk(node.syntax()) }
+    fn visit_pointer_type(&mut self, node: &ast::PointerType) { self.walk(node.syntax()) }
+    fn visit_array_type(&mut self, node: &ast::ArrayType) { self.walk(node.syntax()) }
+    fn visit_slice_type(&mut self, node: &ast::SliceType) { self.walk(node.syntax()) }
+    fn visit_reference_type(&mut self, node: &ast::ReferenceType) { self.walk(node.syntax()) }
+    fn visit_placeholder_type(&mut self, node: &ast::PlaceholderType) { self.walk(node.syntax()) }
+    fn visit_fn_pointer_type(&mut self, node: &ast::FnPointerType) { self.walk(node.syntax()) }
+    fn visit_for_type(&mut self, node: &ast::ForType) { self.walk(node.syntax()) }
+    fn visit_impl_trait_type(&mut self, node: &ast::ImplTraitType) { self.walk(node.syntax()) }
+    fn visit_dyn_trait_type(&mut self, node: &ast::DynTraitType) { self.walk(node.syntax()) }
+    fn visit_tuple_expr(&mut self, node: &ast::TupleExpr) { self.walk(node.syntax()) }
+    fn visit_array_expr(&mut self, node: &ast::ArrayExpr) { self.walk(node.syntax()) }
+    fn visit_paren_expr(&mut self, node: &ast::ParenExpr) { self.walk(node.syntax()) }
+    fn visit_path_expr(&mut self, node: &ast::PathExpr) { self.walk(node.syntax()) }
+    fn visit_lambda_expr(&mut self, node: &ast::LambdaExpr) { self.walk(node.syntax()) }
+    fn visit_if_expr(&mut self, node: &ast::IfExpr) { self.walk(node.syntax()) }
+    fn visit_loop_expr(&mut self, node: &ast::LoopExpr) { self.walk(node.syntax()) }
+    fn visit_try_block_expr(&mut self, node: &ast::TryBlockExpr) { self.walk(node.syntax()) }
+    fn visit_for_expr(&mut self, node: &ast::ForExpr) { self.walk(node.syntax()) }
+    fn visit_while_expr(&mut self, node: &ast::WhileExpr) { self.walk(node.syntax()) }
+    fn visit_continue_expr(&mut self, node: &ast::ContinueExpr) { self.walk(node.syntax()) }
+    fn visit_break_expr(&mut self, node: &ast::BreakExpr) { self.walk(node.syntax()) }
+    fn visit_label(&mut self, node: &ast::Label) { self.walk(node.syntax()) }
+    fn visit_block_expr(&mut self, node: &ast::BlockExpr) { self.walk(node.syntax()) }
+    fn visit_return_expr(&mut self, node: &ast::ReturnExpr) { self.walk(node.syntax()) }
+    fn visit_call_expr(&mut self, node: &ast::CallExpr) { self.walk(node.syntax()) }
+    fn visit_method_call_expr(&mut self, node: &ast::MethodCallExpr) { self.walk(node.syntax()) }
+    fn visit_index_expr(&mut self, node: &ast::IndexExpr) { self.walk(node.syntax()) }
+    fn visit_field_expr(&mut self, node: &ast::FieldExpr) { self.walk(node.syntax()) }
+    fn visit_await_expr(&mut self, node: &ast::AwaitExpr) { self.walk(node.syntax()) }
+    fn visit_try_expr(&mut self, node: &ast::TryExpr) { self.walk(node.syntax()) }
+    fn visit_cast_expr(&mut self, node: &ast::CastExpr) { self.walk(node.syntax()) }
+    fn visit_ref_expr(&mut self, node: &ast::RefExpr) { self.walk(node.syntax()) }
+    fn visit_prefix_expr(&mut self, node: &ast::PrefixExpr) { self.walk(node.syntax()) }
+    fn visit_box_expr(&mut self, node: &ast::BoxExpr) { self.walk(node.syntax()) }
+    fn visit_let_expr(&mut self, node: &ast::LetExpr) { self.walk(node.syntax()) }
+    fn visit_range_expr(&mut self, node: &ast::RangeExpr) { self.walk(node.syntax()) }
+    fn visit_bin_expr(&mut self, node: &ast::BinExpr) { self.walk(node.syntax()) }
+    fn visit_literal(&mut self, node: &ast::Literal) { self.walk(node.syntax()) }
+    fn visit_match_expr(&mut self, node: &ast::MatchExpr) { self.walk(node.syntax()) }
+    fn visit_match_arm_list(&mut self, node: &ast::MatchArmList) { self.walk(node.syntax()) }
+    fn visit_match_arm(&mut self, node: &ast::MatchArm) { self.walk(node.syntax()) }
+    fn visit_match_guard(&mut self, node: &ast::MatchGuard) { self.walk(node.syntax()) }
+    fn visit_record_lit(&mut self, node: &ast::RecordLit) { self.walk(node.syntax()) }
+    fn visit_record_field_list(&mut self, node: &ast::RecordFieldList) { self.walk(node.syntax()) }
+    fn visit_record_field(&mut self, node: &ast::RecordField) { self.walk(node.syntax()) }
+    fn visit_or_pat(&mut self, node: &ast::OrPat) { self.walk(node.syntax()) }
+    fn visit_paren_pat(&mut self, node: &ast::ParenPat) { self.walk(node.syntax()) }
+    fn visit_ref_pat(&mut self, node: &ast::RefPat) { self.walk(node.syntax()) }
+    fn visit_box_pat(&mut self, node: &ast::BoxPat) { self.walk(node.syntax()) }
+    fn visit_bind_pat(&mut self, node: &ast::BindPat) { self.walk(node.syntax()) }
+    fn visit_placeholder_pat(&mut self, node: &ast::PlaceholderPat) { self.walk(node.syntax()) }
+    fn visit_dot_dot_pat(&mut self, node: &ast::DotDotPat) { self.walk(node.syntax()) }
+    fn visit_path_pat(&mut self, node: &ast::PathPat) { self.walk(node.syntax()) }
+    fn visit_slice_pat(&mut self, node: &ast::SlicePat) { self.walk(node.syntax()) }
+    fn visit_range_pat(&mut self, node: &ast::RangePat) { self.walk(node.syntax()) }
+    fn visit_literal_pat(&mut self, node: &ast::LiteralPat) { self.walk(node.syntax()) }
+    fn visit_macro_pat(&mut self, node: &ast::MacroPat) { self.walk(node.syntax()) }
+    fn visit_const_block_pat(&mut self, node: &ast::ConstBlockPat) { self.walk(node.syntax()) }
+    fn visit_record_pat(&mut self, node: &ast::RecordPat) { self.walk(node.syntax()) }
+    fn visit_record_field_pat_list(&mut self, node: &ast::RecordFieldPatList) {
+        self.walk(node.syntax())
+    }
+    fn visit_record_field_pat(&mut self, node: &ast::RecordFieldPat) { self.walk(node.syntax()) }
+    fn visit_tuple_struct_pat(&mut self, node: &ast::TupleStructPat) { self.walk(node.syntax()) }
+    fn visit_tuple_pat(&mut self, node: &ast::TuplePat) { self.walk(node.syntax()) }
+    fn visit_visibility(&mut self, node: &ast::Visibility) { self.walk(node.syntax()) }
+    fn visit_name(&mut self, node: &ast::Name) { self.walk(node.syntax()) }
+    fn visit_name_ref(&mut self, node: &ast::NameRef) { self.walk(node.syntax()) }
+    fn visit_macro_call(&mut self, node: &ast::MacroCall) { self.walk(node.syntax()) }
+    fn visit_attr(&mut self, node: &ast::Attr) { self.walk(node.syntax()) }
+    fn visit_token_tree(&mut self, node: &ast::TokenTree) { self.walk(node.syntax()) }
+    fn visit_type_param_list(&mut self, node: &ast::TypeParamList) { self.walk(node.syntax()) }
+    fn visit_type_param(&mut self, node: &ast::TypeParam) { self.walk(node.syntax()) }
+    fn visit_const_param(&mut self, node: &ast::ConstParam) { self.walk(node.syntax()) }
+    fn visit_lifetime_param(&mut self, node: &ast::LifetimeParam) { self.walk(node.syntax()) }
+    fn visit_type_bound(&mut self, node: &ast::TypeBound) { self.walk(node.syntax()) }
+    fn visit_type_bound_list(&mut self, node: &ast::TypeBoundList) { self.walk(node.syntax()) }
+    fn visit_where_pred(&mut self, node: &ast::WherePred) { self.walk(node.syntax()) }
+    fn visit_where_clause(&mut self, node: &ast::WhereClause) { self.walk(node.syntax()) }
+    fn visit_abi(&mut self, node: &ast::Abi) { self.walk(node.syntax()) }
+    fn visit_expr_stmt(&mut self, node: &ast::ExprStmt) { self.walk(node.syntax()) }
+    fn visit_let_stmt(&mut self, node: &ast::LetStmt) { self.walk(node.syntax()) }
+    fn visit_let_else(&mut self, node: &ast::LetElse) { self.walk(node.syntax()) }
+    fn visit_condition(&mut self, node: &ast::Condition) { self.walk(node.syntax()) }
+    fn visit_block(&mut self, node: &ast::Block) { self.walk(node.syntax()) }
+    fn visit_param_list(&mut self, node: &ast::ParamList) { self.walk(node.syntax()) }
+    fn visit_self_param(&mut self, node: &ast::SelfParam) { self.walk(node.syntax()) }
+    fn visit_param(&mut self, node: &ast::Param) { self.walk(node.syntax()) }
+    fn visit_use_item(&mut self, node: &ast::UseItem) { self.walk(node.syntax()) }
+    fn visit_use_tree(&mut self, node: &ast::UseTree) { self.walk(node.syntax()) }
+    fn visit_alias(&mut self, node: &ast::Alias) { self.walk(node.syntax()) }
+    fn visit_use_tree_list(&mut self, node: &ast::UseTreeList) { self.walk(node.syntax()) }
+    fn visit_extern_crate_item(&mut self, node: &ast::ExternCrateItem) { self.walk(node.syntax()) }
+    fn visit_arg_list(&mut self, node: &ast::ArgList) { self.walk(node.syntax()) }
+    fn visit_path(&mut self, node: &ast::Path) { self.walk(node.syntax()) }
+    fn visit_path_segment(&mut self, node: &ast::PathSegment) { self.walk(node.syntax()) }
+    fn visit_type_arg_list(&mut self, node: &ast::TypeArgList) { self.walk(node.syntax()) }
+    fn visit_type_arg(&mut self, node: &ast::TypeArg) { self.walk(node.syntax()) }
+    fn visit_assoc_type_arg(&mut self, node: &ast::AssocTypeArg) { self.walk(node.syntax()) }
+    fn visit_lifetime_arg(&mut self, node: &ast::LifetimeArg) { self.walk(node.syntax()) }
+    fn visit_const_arg(&mut self, node: &ast::ConstArg) { self.walk(node.syntax()) }
+    fn visit_macro_items(&mut self, node: &ast::MacroItems) { self.walk(node.syntax()) }
+    fn visit_macro_stmts(&mut self, node: &ast::MacroStmts) { self.walk(node.syntax()) }
+    fn visit_extern_item_list(&mut self, node: &ast::ExternItemList) { self.walk(node.syntax()) }
+    fn visit_extern_block(&mut self, node: &ast::ExternBlock) { self.walk(node.syntax()) }
+    fn visit_meta_item(&mut self, node: &ast::MetaItem) { self.walk(node.syntax()) }
+    fn visit_macro_def(&mut self, node: &ast::MacroDef) { self.walk(node.syntax()) }
+}
@@ -5,6 +5,8 @@ use stdx::format_to;
 
 use crate::{ast, AstNode, SourceFile, SyntaxKind, SyntaxNode, SyntaxToken};
 
+pub use super::generated::make::*;
+
 pub fn name(text: &str) -> ast::Name {
     ast_from_text(&format!("mod {};", text))
 }
@@ -100,6 +102,12 @@ pub fn expr_empty_block() -> ast::Expr {
 pub fn expr_unimplemented() -> ast::Expr {
     expr_from_text("unimplemented!()")
 }
+pub fn expr_todo() -> ast::Expr {
+    expr_from_text("todo!()")
+}
+pub fn expr_default() -> ast::Expr {
+    expr_from_text("Default::default()")
+}
 pub fn expr_path(path: ast::Path) -> ast::Expr {
     expr_from_text(&path.to_string())
 }
@@ -126,7 +134,7 @@ fn expr_from_text(text: &str) -> ast::Expr {
     ast_from_text(&format!("const C: () = {};", text))
 }
 
-pub fn try_expr_from_text(text: &str) -> Option<ast::Expr> {
+pub fn try_parse_expr(text: &str) -> Option<ast::Expr> {
     try_ast_from_text(&format!("const C: () = {};", text))
 }
 
@@ -295,7 +303,28 @@ pub fn add_newlines(amount_of_newlines: usize, t: impl AstNode) -> ast::SourceFi
     ast_from_text(&format!("{}{}", newlines, t.syntax()))
 }
 
-fn ast_from_text<N: AstNode>(text: &str) -> N {
+/// The text of `expr` as the operand of a prefix or postfix operator, which is
+/// put in parentheses unless it binds tighter than any operator.
+pub(super) fn operand_text(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::PathExpr(_)
+        | ast::Expr::Literal(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::TupleExpr(_)
+        | ast::Expr::ArrayExpr(_)
+        | ast::Expr::RecordLit(_)
+        | ast::Expr::MacroCall(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::AwaitExpr(_) => expr.to_string(),
+        _ => format!("({})", expr),
+    }
+}
+
+pub(super) fn ast_from_text<N: AstNode>(text: &str) -> N {
     let parse = SourceFile::parse(text);
     let node = parse.tree().syntax().descendants().find_map(N::cast).unwrap();
     let node = node.syntax().clone();
@@ -21,9 +21,12 @@
 
 mod syntax_node;
 mod syntax_error;
+mod edition;
 mod parsing;
 mod validation;
 mod ptr;
+#[cfg(feature = "serialize_tree")]
+mod serialize;
 #[cfg(test)]
 mod tests;
 
@@ -39,9 +42,12 @@ use stdx::format_to;
 
 use crate::syntax_node::GreenNode;
 
+#[cfg(feature = "serialize_tree")]
+pub use crate::serialize::SerializeTree;
 pub use crate::{
     algo::InsertPosition,
     ast::{AstNode, AstToken},
+    edition::{Edition, ParseEditionError},
     parsing::{lex_single_syntax_kind, lex_single_valid_syntax_kind, tokenize, Token},
     ptr::{AstPtr, SyntaxNodePtr},
     syntax_error::SyntaxError,
@@ -49,7 +55,7 @@ pub use crate::{
         Direction, NodeOrToken, SyntaxElement, SyntaxNode, SyntaxToken, SyntaxTreeBuilder,
     },
 };
-pub use ra_parser::{SyntaxKind, T};
+pub use ra_parser::{SyntaxKind, SyntaxKindSet, T};
 pub use rowan::{SmolStr, SyntaxText, TextRange, TextUnit, TokenAtOffset, WalkEvent};
 
 /// `Parse` is the result of the parsing: a syntax tree and a collection of
@@ -159,6 +165,13 @@ impl SourceFile {
         assert_eq!(root.kind(), SyntaxKind::SOURCE_FILE);
         Parse { green, errors: Arc::new(errors), _ty: PhantomData }
     }
+
+    /// Reports syntax which is not available in `edition`. Parsing itself is
+    /// edition-independent, as a file can belong to crates of different
+    /// editions.
+    pub fn edition_errors(&self, edition: Edition) -> Vec<SyntaxError> {
+        validation::validate_edition(self.syntax(), edition)
+    }
 }
 
 /// Matches a `SyntaxNode` against an `ast` type.
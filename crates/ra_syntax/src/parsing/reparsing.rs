@@ -3,8 +3,8 @@
 //! We use two simple strategies for this:
 //!   - if the edit modifies only a single token (like changing an identifier's
 //!     letter), we replace only this token.
-//!   - otherwise, we search for the nearest `{}` block which contains the edit
-//!     and try to parse only this block.
+//!   - otherwise, we search for the nearest `{}` block, or `{}`, `()` or `[]`
+//!     token tree, which contains the edit and try to parse only this node.
 
 use ra_parser::Reparser;
 use ra_text_edit::AtomTextEdit;
@@ -18,7 +18,7 @@ use crate::{
     },
     syntax_node::{GreenNode, GreenToken, NodeOrToken, SyntaxElement, SyntaxNode},
     SyntaxError,
-    SyntaxKind::*,
+    SyntaxKind::{self, *},
     TextRange, TextUnit, T,
 };
 
@@ -44,7 +44,7 @@ fn reparse_token<'node>(
     let prev_token = algo::find_covering_element(root, edit.delete).as_token()?.clone();
     let prev_token_kind = prev_token.kind();
     match prev_token_kind {
-        WHITESPACE | COMMENT | IDENT | STRING | RAW_STRING => {
+        WHITESPACE | COMMENT | IDENT | STRING | RAW_STRING | BYTE_STRING | RAW_BYTE_STRING => {
             if prev_token_kind == WHITESPACE || prev_token_kind == COMMENT {
                 // removing a new line may extends previous token
                 let deleted_range = edit.delete - prev_token.text_range().start();
@@ -94,7 +94,7 @@ fn reparse_block<'node>(
     let text = get_text_after_edit(node.clone().into(), edit);
 
     let (tokens, new_lexer_errors) = tokenize(&text);
-    if !is_balanced(&tokens) {
+    if !is_balanced(&tokens, node.first_child_or_token()?.kind()) {
         return None;
     }
 
@@ -140,27 +140,36 @@ fn find_reparsable_node(node: &SyntaxNode, range: TextRange) -> Option<(SyntaxNo
     })
 }
 
-fn is_balanced(tokens: &[Token]) -> bool {
-    if tokens.is_empty()
-        || tokens.first().unwrap().kind != T!['{']
-        || tokens.last().unwrap().kind != T!['}']
+fn is_balanced(tokens: &[Token], open: SyntaxKind) -> bool {
+    const BRACES: &[(SyntaxKind, SyntaxKind)] = &[(T!['{'], T!['}'])];
+    const ALL_DELIMITERS: &[(SyntaxKind, SyntaxKind)] =
+        &[(T!['{'], T!['}']), (T!['('], T![')']), (T!['['], T![']'])];
+
+    let close = match ALL_DELIMITERS.iter().find(|(it, _)| *it == open) {
+        Some(&(_, close)) => close,
+        None => return false,
+    };
+    if tokens.len() < 2
+        || tokens.first().unwrap().kind != open
+        || tokens.last().unwrap().kind != close
     {
         return false;
     }
-    let mut balance = 0usize;
+    // The parsers of braced nodes recover from stray parentheses and brackets
+    // without leaving the node, but a token tree ends at the first unmatched
+    // closing delimiter of any kind.
+    let delimiters = if open == T!['{'] { BRACES } else { ALL_DELIMITERS };
+    let mut stack = Vec::new();
     for t in &tokens[1..tokens.len() - 1] {
-        match t.kind {
-            T!['{'] => balance += 1,
-            T!['}'] => {
-                balance = match balance.checked_sub(1) {
-                    Some(b) => b,
-                    None => return false,
-                }
+        if let Some(&(_, close)) = delimiters.iter().find(|(open, _)| *open == t.kind) {
+            stack.push(close);
+        } else if delimiters.iter().any(|(_, close)| *close == t.kind) {
+            if stack.pop() != Some(t.kind) {
+                return false;
             }
-            _ => (),
         }
     }
-    balance == 0
+    stack.is_empty()
 }
 
 fn merge_errors(
@@ -309,7 +318,35 @@ fn foo() {
 }
 ",
             "123",
-            14,
+            2,
+        );
+        do_check(
+            r"
+fn foo() {
+    foo!(a, <|>b<|>)
+}
+",
+            "c, d",
+            6,
+        );
+        do_check(
+            r"
+#[derive(Clone<|><|>)]
+struct S;
+",
+            ", Copy",
+            7,
+        );
+        do_check(
+            r"
+fn foo() {
+    match x {
+        1 => (),<|><|>
+    }
+}
+",
+            "\n        2 => (),",
+            24,
         );
         do_check(
             r"
@@ -9,7 +9,7 @@ use crate::{
     syntax_node::GreenNode,
     SmolStr, SyntaxError,
     SyntaxKind::{self, *},
-    SyntaxTreeBuilder, TextRange, TextUnit,
+    SyntaxTreeBuilder, TextRange, TextUnit, T,
 };
 
 /// Bridges the parser with our specific syntax tree representation.
@@ -22,6 +22,9 @@ pub(crate) struct TextTreeSink<'a> {
     token_pos: usize,
     state: State,
     inner: SyntaxTreeBuilder,
+    /// Opening delimiters without a matching closing one, for each node which
+    /// is being built.
+    open_delimiters: Vec<Vec<(SyntaxKind, TextRange)>>,
 }
 
 enum State {
@@ -34,7 +37,7 @@ impl<'a> TreeSink for TextTreeSink<'a> {
     fn token(&mut self, kind: SyntaxKind, n_tokens: u8) {
         match mem::replace(&mut self.state, State::Normal) {
             State::PendingStart => unreachable!(),
-            State::PendingFinish => self.inner.finish_node(),
+            State::PendingFinish => self.finish_inner_node(),
             State::Normal => (),
         }
         self.eat_trivias();
@@ -49,12 +52,12 @@ impl<'a> TreeSink for TextTreeSink<'a> {
     fn start_node(&mut self, kind: SyntaxKind) {
         match mem::replace(&mut self.state, State::Normal) {
             State::PendingStart => {
-                self.inner.start_node(kind);
+                self.start_inner_node(kind);
                 // No need to attach trivias to previous node: there is no
                 // previous node.
                 return;
             }
-            State::PendingFinish => self.inner.finish_node(),
+            State::PendingFinish => self.finish_inner_node(),
             State::Normal => (),
         }
 
@@ -74,14 +77,14 @@ impl<'a> TreeSink for TextTreeSink<'a> {
             n_attached_trivias(kind, leading_trivias)
         };
         self.eat_n_trivias(n_trivias - n_attached_trivias);
-        self.inner.start_node(kind);
+        self.start_inner_node(kind);
         self.eat_n_trivias(n_attached_trivias);
     }
 
     fn finish_node(&mut self) {
         match mem::replace(&mut self.state, State::PendingFinish) {
             State::PendingStart => unreachable!(),
-            State::PendingFinish => self.inner.finish_node(),
+            State::PendingFinish => self.finish_inner_node(),
             State::Normal => (),
         }
     }
@@ -100,6 +103,7 @@ impl<'a> TextTreeSink<'a> {
             token_pos: 0,
             state: State::PendingStart,
             inner: SyntaxTreeBuilder::default(),
+            open_delimiters: Vec::new(),
         }
     }
 
@@ -107,7 +111,7 @@ impl<'a> TextTreeSink<'a> {
         match mem::replace(&mut self.state, State::Normal) {
             State::PendingFinish => {
                 self.eat_trivias();
-                self.inner.finish_node()
+                self.finish_inner_node()
             }
             State::PendingStart | State::Normal => unreachable!(),
         }
@@ -115,6 +119,21 @@ impl<'a> TextTreeSink<'a> {
         self.inner.finish_raw()
     }
 
+    fn start_inner_node(&mut self, kind: SyntaxKind) {
+        self.open_delimiters.push(Vec::new());
+        self.inner.start_node(kind);
+    }
+
+    /// Reports the opening delimiters of the node which were never closed.
+    /// The parser only reports the place where it gave up looking for the
+    /// closing delimiter, which is often the end of the file.
+    fn finish_inner_node(&mut self) {
+        for (_, range) in self.open_delimiters.pop().unwrap_or_default() {
+            self.inner.push_error(SyntaxError::new("unclosed delimiter, opened here", range));
+        }
+        self.inner.finish_node();
+    }
+
     fn eat_trivias(&mut self) {
         while let Some(&token) = self.tokens.get(self.token_pos) {
             if !token.kind.is_trivia() {
@@ -137,8 +156,29 @@ impl<'a> TextTreeSink<'a> {
         let text: SmolStr = self.text[range].into();
         self.text_pos += len;
         self.token_pos += n_tokens;
+        self.track_delimiter(kind, range);
         self.inner.token(kind, text);
     }
+
+    fn track_delimiter(&mut self, kind: SyntaxKind, range: TextRange) {
+        let open_delimiters = match self.open_delimiters.last_mut() {
+            Some(it) => it,
+            None => return,
+        };
+        let opening = match kind {
+            T!['{'] | T!['('] | T!['['] => {
+                open_delimiters.push((kind, range));
+                return;
+            }
+            T!['}'] => T!['{'],
+            T![')'] => T!['('],
+            T![']'] => T!['['],
+            _ => return,
+        };
+        if let Some(idx) = open_delimiters.iter().rposition(|&(it, _)| it == opening) {
+            open_delimiters.truncate(idx);
+        }
+    }
 }
 
 fn n_attached_trivias<'a>(
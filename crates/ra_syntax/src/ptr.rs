@@ -21,6 +21,12 @@ impl SyntaxNodePtr {
         SyntaxNodePtr { range: node.text_range(), kind: node.kind() }
     }
 
+    /// Creates a pointer from its parts, for example when reading a pointer
+    /// which was saved to disk.
+    pub fn from_raw(kind: SyntaxKind, range: TextRange) -> SyntaxNodePtr {
+        SyntaxNodePtr { range, kind }
+    }
+
     pub fn to_node(self, root: &SyntaxNode) -> SyntaxNode {
         assert!(root.parent().is_none());
         successors(Some(root.clone()), |node| {
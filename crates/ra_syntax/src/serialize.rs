@@ -0,0 +1,93 @@
+//! `serde` support for syntax trees, for tools which want to consume them as
+//! JSON and the like. Enabled by the `serialize_tree` feature.
+//!
+//! Trees are serialized losslessly: each node is a map with its `kind`, its
+//! `range` and its `children`, and each token a map with its `kind`, `range`
+//! and `text`. Kinds are the names of the `SyntaxKind` variants, ranges are
+//! `[start, end]` offsets in bytes:
+//!
+//! ```text
+//! { "kind": "NAME", "range": [3, 6], "children": [
+//!     { "kind": "IDENT", "range": [3, 6], "text": "foo" }
+//! ] }
+//! ```
+//!
+//! AST nodes serialize the same way as their syntax nodes, the impls are
+//! generated in `ast/generated/serialize.rs`.
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{NodeOrToken, SyntaxElement, SyntaxNode, SyntaxToken, TextRange};
+
+/// Serializes a syntax node with all of its descendants.
+#[derive(Debug, Clone, Copy)]
+pub struct SerializeTree<'a>(pub &'a SyntaxNode);
+
+impl Serialize for SerializeTree<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let node = self.0;
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("kind", &format!("{:?}", node.kind()))?;
+        map.serialize_entry("range", &range(node.text_range()))?;
+        map.serialize_entry("children", &Children(node))?;
+        map.end()
+    }
+}
+
+struct Children<'a>(&'a SyntaxNode);
+
+impl Serialize for Children<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.children_with_tokens().map(Element))
+    }
+}
+
+struct Element(SyntaxElement);
+
+impl Serialize for Element {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0 {
+            NodeOrToken::Node(node) => SerializeTree(node).serialize(serializer),
+            NodeOrToken::Token(token) => serialize_token(token, serializer),
+        }
+    }
+}
+
+fn serialize_token<S: Serializer>(token: &SyntaxToken, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(3))?;
+    map.serialize_entry("kind", &format!("{:?}", token.kind()))?;
+    map.serialize_entry("range", &range(token.text_range()))?;
+    map.serialize_entry("text", token.text().as_str())?;
+    map.end()
+}
+
+fn range(range: TextRange) -> [usize; 2] {
+    [range.start().to_usize(), range.end().to_usize()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast, AstNode, SourceFile};
+
+    use super::SerializeTree;
+
+    #[test]
+    fn serialize_tree() {
+        let file = SourceFile::parse("fn foo() {}").ok().unwrap();
+        let name = file.syntax().descendants().find_map(ast::Name::cast).unwrap();
+        assert_eq!(
+            serde_json::to_string(&SerializeTree(name.syntax())).unwrap(),
+            r#"{"kind":"NAME","range":[3,6],"children":[{"kind":"IDENT","range":[3,6],"text":"foo"}]}"#
+        );
+        assert_eq!(
+            serde_json::to_value(&name).unwrap(),
+            serde_json::to_value(SerializeTree(name.syntax())).unwrap()
+        );
+
+        let item = file.syntax().descendants().find_map(ast::ModuleItem::cast).unwrap();
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(json["kind"], "FN_DEF");
+        assert_eq!(json["children"][0]["text"], "fn");
+        assert_eq!(json["children"].as_array().unwrap().len(), 6);
+    }
+}
@@ -72,4 +72,8 @@ impl SyntaxTreeBuilder {
     pub fn error(&mut self, error: ra_parser::ParseError, text_pos: TextUnit) {
         self.errors.push(SyntaxError::new_at_offset(error.0, text_pos))
     }
+
+    pub(crate) fn push_error(&mut self, error: SyntaxError) {
+        self.errors.push(error)
+    }
 }
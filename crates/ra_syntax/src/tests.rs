@@ -5,7 +5,7 @@ use std::{
 
 use test_utils::{collect_rust_files, dir_tests, project_dir, read_text};
 
-use crate::{fuzz, tokenize, SourceFile, SyntaxError, TextRange, TextUnit, Token};
+use crate::{fuzz, tokenize, Edition, SourceFile, SyntaxError, TextRange, TextUnit, Token};
 
 #[test]
 fn lexer_tests() {
@@ -38,6 +38,45 @@ fn main() {
     assert!(parse.ok().is_ok());
 }
 
+#[test]
+fn edition_2015_validation() {
+    let code = r##"
+async fn foo() {
+    let x = try { bar().await };
+    let y = async move || {};
+}
+"##;
+
+    assert!(SourceFile::parse(code).ok().is_ok());
+    let parse = SourceFile::parse(code);
+    assert!(parse.tree().edition_errors(Edition::Edition2018).is_empty());
+    let errors = parse.tree().edition_errors(Edition::Edition2015);
+    let errors = errors.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        vec![
+            "`async` is not available in Rust 2015",
+            "`try` is not available in Rust 2015",
+            "`await` is not available in Rust 2015",
+            "`async` is not available in Rust 2015",
+        ]
+    );
+}
+
+#[test]
+fn edition_2018_validation() {
+    let code = r##"
+fn foo(a: Box<Display + Send>, b: Box<dyn Display + Send>, c: Box<Display>) {}
+"##;
+
+    assert!(SourceFile::parse(code).ok().is_ok());
+    let parse = SourceFile::parse(code);
+    assert!(parse.tree().edition_errors(Edition::Edition2015).is_empty());
+    let errors = parse.tree().edition_errors(Edition::Edition2018);
+    let errors = errors.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+    assert_eq!(errors, vec!["Trait objects without `dyn` are deprecated in Rust 2018"]);
+}
+
 #[test]
 fn parser_tests() {
     dir_tests(&test_data_dir(), &["parser/inline/ok", "parser/ok"], "rast", |text, path| {
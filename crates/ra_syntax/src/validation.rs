@@ -1,12 +1,18 @@
 //! FIXME: write short doc here
 
 mod block;
+mod doc_comments;
 
 use rustc_lexer::unescape;
 
 use crate::{
-    ast, match_ast, AstNode, SyntaxError,
-    SyntaxKind::{BYTE, BYTE_STRING, CHAR, CONST_DEF, FN_DEF, INT_NUMBER, STRING, TYPE_ALIAS_DEF},
+    ast, match_ast, AstNode, AstToken, Direction, Edition, SyntaxError,
+    SyntaxKind::{
+        self, ATTR, AWAIT_EXPR, BIN_EXPR, BLOCK, BLOCK_EXPR, BYTE, BYTE_STRING, CHAR, COMMENT,
+        EXTERN_ITEM_LIST, FN_DEF, IF_EXPR, INT_NUMBER, ITEM_LIST, LAMBDA_EXPR, L_CURLY,
+        MATCH_ARM_LIST, PATH, RANGE_EXPR, SHEBANG, SOURCE_FILE, STRING, TRY_EXPR, TYPE_BOUND,
+        WHERE_PRED, WHITESPACE,
+    },
     SyntaxNode, SyntaxToken, TextUnit, T,
 };
 
@@ -80,8 +86,6 @@ fn rustc_unescape_error_to_string(err: unescape::EscapeError) -> &'static str {
 pub(crate) fn validate(root: &SyntaxNode) -> Vec<SyntaxError> {
     // FIXME:
     // * Add validation of character literal containing only a single char
-    // * Add validation of `crate` keyword not appearing in the middle of the symbol path
-    // * Add validation of doc comments are being attached to nodes
     // * Remove validation of unterminated literals (it is already implemented in `tokenize()`)
 
     let mut errors = Vec::new();
@@ -93,11 +97,22 @@ pub(crate) fn validate(root: &SyntaxNode) -> Vec<SyntaxError> {
                 ast::FieldExpr(it) => validate_numeric_name(it.name_ref(), &mut errors),
                 ast::RecordField(it) => validate_numeric_name(it.name_ref(), &mut errors),
                 ast::Visibility(it) => validate_visibility(it, &mut errors),
+                ast::PathSegment(it) => validate_crate_keyword_in_path_segment(it, &mut errors),
                 ast::RangeExpr(it) => validate_range_expr(it, &mut errors),
+                ast::RangePat(it) => validate_range_pat(it, &mut errors),
+                ast::SlicePat(it) => validate_rest_pats(it.args(), "slice", &mut errors),
+                ast::TuplePat(it) => validate_rest_pats(it.args(), "tuple", &mut errors),
+                ast::TupleStructPat(it) => validate_rest_pats(it.args(), "tuple struct", &mut errors),
+                ast::BindPat(it) => validate_bind_pat(it, &mut errors),
+                ast::Attr(it) => validate_attr(it, &mut errors),
+                ast::FnDef(it) => validate_fn_modifiers(it, &mut errors),
+                ast::LambdaExpr(it) => validate_lambda_modifiers(it, &mut errors),
+                ast::TypeBoundList(it) => validate_type_bound_list(it, &mut errors),
                 _ => (),
             }
         }
     }
+    doc_comments::validate_doc_comments(root, &mut errors);
     errors
 }
 
@@ -198,9 +213,8 @@ fn validate_visibility(vis: ast::Visibility, errors: &mut Vec<SyntaxError>) {
         Some(it) => it,
         None => return,
     };
-    match parent.kind() {
-        FN_DEF | CONST_DEF | TYPE_ALIAS_DEF => (),
-        _ => return,
+    if !SyntaxKind::IMPL_ITEMS.contains(parent.kind()) {
+        return;
     }
 
     let impl_def = match parent.parent().and_then(|it| it.parent()).and_then(ast::ImplDef::cast) {
@@ -212,6 +226,47 @@ fn validate_visibility(vis: ast::Visibility, errors: &mut Vec<SyntaxError>) {
     }
 }
 
+fn validate_crate_keyword_in_path_segment(
+    segment: ast::PathSegment,
+    errors: &mut Vec<SyntaxError>,
+) {
+    let crate_token = match segment
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == T![crate])
+    {
+        Some(it) => it,
+        None => return,
+    };
+
+    let path = segment.parent_path();
+    let is_first_segment = path.qualifier().is_none() && segment.coloncolon_token().is_none();
+    if is_first_segment && !is_in_prefixed_use_tree(&path) {
+        return;
+    }
+    errors.push(SyntaxError::new(
+        "`crate` can only be used in absolute paths",
+        crate_token.text_range(),
+    ));
+
+    // In `use foo::{crate::bar}`, `crate` is not the first segment of the
+    // resulting path, even though it starts its own `ast::Path`.
+    fn is_in_prefixed_use_tree(path: &ast::Path) -> bool {
+        let top_path = path.syntax().ancestors().take_while(|it| it.kind() == PATH).last();
+        let use_tree = match top_path.and_then(|it| it.parent()).and_then(ast::UseTree::cast) {
+            Some(it) => it,
+            None => return false,
+        };
+        use_tree
+            .syntax()
+            .ancestors()
+            .skip(1)
+            .filter_map(ast::UseTree::cast)
+            .any(|it| it.path().is_some())
+    }
+}
+
 fn validate_range_expr(expr: ast::RangeExpr, errors: &mut Vec<SyntaxError>) {
     if expr.op_kind() == Some(ast::RangeOp::Inclusive) && expr.end().is_none() {
         errors.push(SyntaxError::new(
@@ -220,3 +275,201 @@ fn validate_range_expr(expr: ast::RangeExpr, errors: &mut Vec<SyntaxError>) {
         ));
     }
 }
+
+fn validate_range_pat(pat: ast::RangePat, errors: &mut Vec<SyntaxError>) {
+    let is_inclusive = match pat.range_separator_token() {
+        Some(ast::RangeSeparator::Dotdoteq(_)) | Some(ast::RangeSeparator::Dotdotdot(_)) => true,
+        Some(ast::RangeSeparator::Dotdot(_)) | None => false,
+    };
+    if is_inclusive && pat.end().is_none() {
+        errors.push(SyntaxError::new(
+            "An inclusive range pattern must have an end pattern",
+            pat.syntax().text_range(),
+        ));
+    }
+}
+
+fn validate_rest_pats(
+    pats: ast::AstChildren<ast::Pat>,
+    pat_kind: &str,
+    errors: &mut Vec<SyntaxError>,
+) {
+    let rest_pats = pats.filter(|pat| match pat {
+        ast::Pat::DotDotPat(_) => true,
+        ast::Pat::BindPat(it) => match it.pat() {
+            Some(ast::Pat::DotDotPat(_)) => true,
+            _ => false,
+        },
+        _ => false,
+    });
+    for pat in rest_pats.skip(1) {
+        errors.push(SyntaxError::new(
+            format!("`..` can only be used once per {} pattern", pat_kind),
+            pat.syntax().text_range(),
+        ));
+    }
+}
+
+fn validate_attr(attr: ast::Attr, errors: &mut Vec<SyntaxError>) {
+    let parent = match attr.syntax().parent() {
+        Some(it) => it,
+        None => return,
+    };
+    match attr.kind() {
+        ast::AttrKind::Inner => {
+            let is_at_start = attr
+                .syntax()
+                .siblings_with_tokens(Direction::Prev)
+                .skip(1)
+                .all(|it| matches!(it.kind(), WHITESPACE | COMMENT | ATTR | L_CURLY | SHEBANG));
+            let is_in_container = matches!(
+                parent.kind(),
+                SOURCE_FILE | ITEM_LIST | EXTERN_ITEM_LIST | BLOCK | MATCH_ARM_LIST
+            );
+            if !is_at_start || !is_in_container {
+                errors.push(SyntaxError::new(
+                    "Inner attributes are only allowed at the start of a file, module or block",
+                    attr.syntax().text_range(),
+                ));
+            }
+        }
+        ast::AttrKind::Outer => {
+            // Attributes on expression statements and tail expressions are
+            // already reported by the parser.
+            if parent.parent().map(|it| it.kind()) == Some(BLOCK) {
+                return;
+            }
+            let expr_kind = match parent.kind() {
+                BIN_EXPR => "binary",
+                RANGE_EXPR => "range",
+                IF_EXPR => "`if`",
+                _ => return,
+            };
+            errors.push(SyntaxError::new(
+                format!("Attributes are not allowed on {} expressions", expr_kind),
+                attr.syntax().text_range(),
+            ));
+        }
+    }
+}
+
+fn validate_bind_pat(pat: ast::BindPat, errors: &mut Vec<SyntaxError>) {
+    let (ref_kw, mut_kw) = match (pat.ref_kw_token(), pat.mut_kw_token()) {
+        (Some(ref_kw), Some(mut_kw)) => (ref_kw, mut_kw),
+        _ => return,
+    };
+    let ref_range = ref_kw.syntax().text_range();
+    let mut_range = mut_kw.syntax().text_range();
+    if mut_range.start() < ref_range.start() {
+        errors.push(SyntaxError::new(
+            "The order of `mut` and `ref` is incorrect, use `ref mut`",
+            mut_range.extend_to(&ref_range),
+        ));
+    }
+}
+
+fn validate_fn_modifiers(fn_def: ast::FnDef, errors: &mut Vec<SyntaxError>) {
+    let const_kw = fn_def.const_kw_token().map(|it| it.syntax().clone());
+    let async_kw = fn_def.async_kw_token().map(|it| it.syntax().clone());
+    let unsafe_kw = fn_def.unsafe_kw_token().map(|it| it.syntax().clone());
+
+    if let (Some(const_kw), Some(async_kw)) = (&const_kw, &async_kw) {
+        errors.push(SyntaxError::new(
+            "Functions cannot be both `const` and `async`",
+            const_kw.text_range().extend_to(&async_kw.text_range()),
+        ));
+    }
+
+    let container = match fn_def.syntax().parent() {
+        Some(it) => it,
+        None => return,
+    };
+    if container.kind() == EXTERN_ITEM_LIST {
+        for kw in const_kw.iter().chain(async_kw.iter()).chain(unsafe_kw.iter()) {
+            errors.push(SyntaxError::new(
+                format!("Functions in `extern` blocks cannot be `{}`", kw.text()),
+                kw.text_range(),
+            ));
+        }
+        return;
+    }
+
+    let in_trait = match container.parent() {
+        Some(it) if container.kind() == ITEM_LIST => {
+            ast::TraitDef::can_cast(it.kind())
+                || ast::ImplDef::cast(it).map_or(false, |it| it.target_trait().is_some())
+        }
+        _ => false,
+    };
+    if in_trait {
+        for kw in const_kw.iter().chain(async_kw.iter()) {
+            errors.push(SyntaxError::new(
+                format!("Functions in traits cannot be `{}`", kw.text()),
+                kw.text_range(),
+            ));
+        }
+    }
+}
+
+fn validate_lambda_modifiers(lambda: ast::LambdaExpr, errors: &mut Vec<SyntaxError>) {
+    if let Some(const_kw) = lambda.const_kw_token() {
+        errors.push(SyntaxError::new("Closures cannot be `const`", const_kw.syntax().text_range()));
+    }
+}
+
+fn validate_type_bound_list(list: ast::TypeBoundList, errors: &mut Vec<SyntaxError>) {
+    let in_where_clause = list.syntax().parent().map(|it| it.kind()) == Some(WHERE_PRED);
+    let mut prev_kind = None;
+    for element in list.syntax().children_with_tokens().filter(|it| !it.kind().is_trivia()) {
+        if element.kind() == T![+] && prev_kind != Some(TYPE_BOUND) {
+            let message =
+                if in_where_clause { "Missing bound in where clause" } else { "Missing bound" };
+            errors.push(SyntaxError::new(message, element.text_range()));
+        }
+        prev_kind = Some(element.kind());
+    }
+}
+
+/// Reports syntax which is not available in the given edition: keywords which
+/// are not reserved in Rust 2015, as the lexer always produces them, and trait
+/// objects without `dyn` in Rust 2018. These are checked after parsing.
+pub(crate) fn validate_edition(root: &SyntaxNode, edition: Edition) -> Vec<SyntaxError> {
+    match edition {
+        Edition::Edition2015 => validate_edition_2015(root),
+        Edition::Edition2018 => validate_edition_2018(root),
+    }
+}
+
+fn validate_edition_2015(root: &SyntaxNode) -> Vec<SyntaxError> {
+    let mut errors = Vec::new();
+    for token in root.descendants_with_tokens().filter_map(|it| it.into_token()) {
+        let is_2018_syntax = match token.kind() {
+            T![async] => matches!(token.parent().kind(), FN_DEF | LAMBDA_EXPR | BLOCK_EXPR),
+            T![await] => token.parent().kind() == AWAIT_EXPR,
+            T![try] => token.parent().kind() == TRY_EXPR,
+            _ => false,
+        };
+        if is_2018_syntax {
+            errors.push(SyntaxError::new(
+                format!("`{}` is not available in Rust 2015", token.text()),
+                token.text_range(),
+            ));
+        }
+    }
+    errors
+}
+
+/// A bare trait object is only recognizable by its syntax if it has several
+/// bounds, like `Foo + Send`; a single path might as well be a type.
+fn validate_edition_2018(root: &SyntaxNode) -> Vec<SyntaxError> {
+    root.descendants()
+        .filter_map(ast::DynTraitType::cast)
+        .filter(|it| it.dyn_kw_token().is_none())
+        .map(|it| {
+            SyntaxError::new(
+                "Trait objects without `dyn` are deprecated in Rust 2018",
+                it.syntax().text_range(),
+            )
+        })
+        .collect()
+}
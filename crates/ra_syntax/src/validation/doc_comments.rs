@@ -0,0 +1,118 @@
+//! Logic for validating that doc comments are attached to something they can
+//! document.
+
+use crate::{
+    ast::{self, AstToken, CommentPlacement},
+    SyntaxElement, SyntaxError,
+    SyntaxKind::{self, *},
+    SyntaxKindSet, SyntaxNode, SyntaxToken,
+};
+
+pub(crate) fn validate_doc_comments(root: &SyntaxNode, errors: &mut Vec<SyntaxError>) {
+    for token in root.descendants_with_tokens().filter_map(|it| it.into_token()) {
+        let comment = match ast::Comment::cast(token) {
+            Some(it) => it,
+            None => continue,
+        };
+        let is_attached = match doc_placement(&comment) {
+            Some(CommentPlacement::Outer) => is_outer_doc_attached(comment.syntax()),
+            Some(CommentPlacement::Inner) => is_inner_doc_attached(comment.syntax()),
+            None => continue,
+        };
+        if !is_attached {
+            errors.push(SyntaxError::new(
+                "doc comment is not attached to anything",
+                comment.syntax().text_range(),
+            ));
+        }
+    }
+}
+
+/// Like `comment.kind().doc`, but treats `////` and `/***` as ordinary
+/// comments, as rustc does. Comments inside token trees are ignored, as they
+/// are checked after macro expansion.
+fn doc_placement(comment: &ast::Comment) -> Option<CommentPlacement> {
+    let placement = comment.kind().doc?;
+    let text = comment.text();
+    if text.starts_with("////") || text.starts_with("/***") || text == "/**/" {
+        return None;
+    }
+    if comment.syntax().parent().kind() == TOKEN_TREE {
+        return None;
+    }
+    Some(placement)
+}
+
+/// An outer doc comment (`///`, `/** */`) documents either the item it is a
+/// leading child of, or the item following it.
+fn is_outer_doc_attached(comment: &SyntaxToken) -> bool {
+    if is_documentable_kind(comment.parent().kind()) && is_leading(&comment.clone().into()) {
+        return true;
+    }
+    next_non_trivia_sibling(comment).map_or(false, |it| is_documentable(&it))
+}
+
+/// An inner doc comment (`//!`, `/*! */`) documents the enclosing file, module,
+/// block or impl, and must come before any of its items or statements.
+fn is_inner_doc_attached(comment: &SyntaxToken) -> bool {
+    let mut element: SyntaxElement = comment.clone().into();
+    // Comments right before an item are attached to the item node by the
+    // parser, so check the position of the item instead.
+    let parent = comment.parent();
+    if is_documentable_kind(parent.kind()) && is_leading(&element) {
+        element = parent.into();
+    }
+    let container = match element.parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if !matches!(container.kind(), SOURCE_FILE | ITEM_LIST | EXTERN_ITEM_LIST | BLOCK) {
+        return false;
+    }
+    is_leading(&element)
+}
+
+/// Whether `element` is preceded only by trivia, attributes, the opening
+/// brace, or a shebang.
+fn is_leading(element: &SyntaxElement) -> bool {
+    let mut prev = element.prev_sibling_or_token();
+    while let Some(it) = prev {
+        if !matches!(it.kind(), WHITESPACE | COMMENT | ATTR | L_CURLY | SHEBANG) {
+            return false;
+        }
+        prev = it.prev_sibling_or_token();
+    }
+    true
+}
+
+fn next_non_trivia_sibling(token: &SyntaxToken) -> Option<SyntaxElement> {
+    let mut next = token.next_sibling_or_token();
+    while let Some(it) = next {
+        if !it.kind().is_trivia() {
+            return Some(it);
+        }
+        next = it.next_sibling_or_token();
+    }
+    None
+}
+
+fn is_documentable(element: &SyntaxElement) -> bool {
+    match element {
+        SyntaxElement::Node(node) if node.kind() == EXPR_STMT => {
+            node.first_child().map_or(false, |it| it.kind() == MACRO_CALL)
+        }
+        SyntaxElement::Node(node) => is_documentable_kind(node.kind()),
+        SyntaxElement::Token(_) => false,
+    }
+}
+
+fn is_documentable_kind(kind: SyntaxKind) -> bool {
+    const DOCUMENTABLE: SyntaxKindSet = SyntaxKind::ITEMS
+        .union(SyntaxKind::GENERIC_PARAMS)
+        .union(SyntaxKindSet::singleton(ENUM_VARIANT))
+        .union(SyntaxKindSet::singleton(MACRO_DEF))
+        .union(SyntaxKindSet::singleton(RECORD_FIELD_DEF))
+        .union(SyntaxKindSet::singleton(TUPLE_FIELD_DEF))
+        .union(SyntaxKindSet::singleton(MATCH_ARM));
+    DOCUMENTABLE.contains(kind)
+}
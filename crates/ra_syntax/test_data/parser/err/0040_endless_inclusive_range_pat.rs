@@ -0,0 +1,7 @@
+fn main() {
+    match 92 {
+        0..= => (),
+        1... => (),
+        [2..=, 3] => (),
+    }
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let mut ref a = ();
+    let ref mut b = ();
+    let (mut ref c, ref mut d) = ();
+}
@@ -0,0 +1,12 @@
+use crate::foo;
+use foo::crate::bar;
+use ::crate::foo;
+use foo::{crate::bar};
+use {crate::foo, crate::bar::{self, baz}};
+pub(in crate::foo) struct S;
+pub(in foo::crate) struct T;
+fn main() {
+    let _ = crate::foo::bar();
+    let _ = foo::crate::bar();
+    let _: <crate::S as foo::crate::Tr>::Out;
+}
@@ -0,0 +1,22 @@
+//! Crate docs
+
+/// Documented function
+fn foo() {
+    //! Function docs
+    let x = 1;
+    /// Not attached to a statement
+    let y = 2;
+    /// Attached to a macro call
+    println!("{}", y);
+    /// Not attached at the end of a block
+}
+
+//! Not at the start of the file
+struct S {
+    /// Field docs
+    field: u32,
+    /** Not attached to anything */
+}
+
+//// Just a comment
+/// Not attached at the end of the file
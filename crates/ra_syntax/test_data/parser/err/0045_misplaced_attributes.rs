@@ -0,0 +1,12 @@
+#![allow(unused)]
+
+fn a() {
+    #![allow(dead_code)]
+    let x = 1;
+    #![allow(unused_variables)]
+    let y = #[attr] 1 + 2;
+    foo(#[attr] 1..2, #[attr] 3);
+}
+
+#![allow(unused)]
+fn b() {}
@@ -0,0 +1,18 @@
+const async fn foo() {}
+trait T {
+    const fn bar();
+    async fn baz();
+}
+impl T for () {
+    async fn baz() {}
+}
+impl S {
+    const async unsafe fn qux() {}
+}
+extern "C" {
+    unsafe fn quux();
+}
+fn main() {
+    let _ = const || ();
+    let _ = const async move |x: i32| x;
+}
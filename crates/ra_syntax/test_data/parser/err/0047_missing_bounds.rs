@@ -0,0 +1,3 @@
+fn f<T>() where T: + Copy { let x = 1; }
+fn g<T: Copy + + Clone>() {}
+fn h<T>() where T: Copy +, { let x = 1; }
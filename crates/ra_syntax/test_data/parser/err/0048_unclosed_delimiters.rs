@@ -0,0 +1,9 @@
+fn foo() {
+    let x = (1, 2;
+    bar();
+}
+
+fn baz() {
+    if true {
+        quux([1, 2);
+    }
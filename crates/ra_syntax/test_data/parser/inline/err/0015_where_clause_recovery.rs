@@ -0,0 +1,2 @@
+fn foo<T>() where T: 92, T: Copy { let x = 1; }
+fn bar<T>() where T: Copy ), T: Clone { let x = 1; }
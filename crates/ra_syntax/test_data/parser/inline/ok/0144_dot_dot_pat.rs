@@ -7,10 +7,6 @@ fn main() {
     let (a, ..,) = ();
     let Tuple(a, ..) = ();
     let Tuple(a, ..,) = ();
-    let (.., ..) = ();
-    let Tuple(.., ..) = ();
-    let (.., a, ..) = ();
-    let Tuple(.., a, ..) = ();
     //
     // Slices
     //
@@ -19,7 +15,4 @@ fn main() {
     let [head, tail @ ..] = ();
     let [head, .., cons] = ();
     let [head, mid @ .., cons] = ();
-    let [head, .., .., cons] = ();
-    let [head, .., mid, tail @ ..] = ();
-    let [head, .., mid, .., cons] = ();
 }
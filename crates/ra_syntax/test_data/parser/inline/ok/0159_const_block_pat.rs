@@ -0,0 +1,7 @@
+fn main() {
+    match 92 {
+        const { N + 1 } => (),
+        const { 0 }..=const { 10 } => (),
+        _ => (),
+    }
+}
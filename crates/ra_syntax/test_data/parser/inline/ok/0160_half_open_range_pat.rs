@@ -0,0 +1,7 @@
+fn main() {
+    match 92 {
+        0..10 => (),
+        10.. => (),
+        -5..=-1 | 20.. => (),
+    }
+}
@@ -0,0 +1,6 @@
+fn foo() {
+    if let Some(x) = a && let Some(y) = x && y > 0 {}
+    if a && let Some(b) = c {}
+    while let Some(x) = it.next() && x != 0 {}
+    if let Some(x) = a || b {}
+}
@@ -0,0 +1,4 @@
+fn foo() {
+    let Some(x) = opt else { return };
+    let Ok(y): Result = res else { return };
+}
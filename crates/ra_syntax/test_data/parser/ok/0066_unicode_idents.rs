@@ -0,0 +1,6 @@
+fn föö() {
+    let λ = 1;
+    let 名前 = λ;
+}
+
+struct Ñandú { größe: u32 }
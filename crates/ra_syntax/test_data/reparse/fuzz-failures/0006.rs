@@ -0,0 +1,5 @@
+9
+0
+ c
+foo!(a, b);
+bar();
@@ -0,0 +1,5 @@
+10
+0
+{
+foo!(a, (b), c);
+bar();
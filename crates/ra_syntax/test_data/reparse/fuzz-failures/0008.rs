@@ -0,0 +1,5 @@
+8
+2
+](
+foo![a, b];
+bar(x);
@@ -0,0 +1,8 @@
+18
+0
+
+	fn g() {}
+mod m {
+	fn f() {}
+}
+bar();
@@ -0,0 +1,6 @@
+19
+0
+ 2 => { 3 }
+match x {
+	1 => (),
+}
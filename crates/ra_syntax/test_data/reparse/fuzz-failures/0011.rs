@@ -0,0 +1,6 @@
+17
+2
+{
+match x {
+	1 => (),
+}
@@ -17,6 +17,7 @@ pub(crate) struct Args {
 pub(crate) enum Command {
     Parse {
         no_dump: bool,
+        json: bool,
     },
     Symbols,
     Highlight {
@@ -29,6 +30,7 @@ pub(crate) enum Command {
         with_deps: bool,
         path: PathBuf,
         load_output_dirs: bool,
+        compare_with_rustc: Option<PathBuf>,
     },
     Bench {
         path: PathBuf,
@@ -80,14 +82,16 @@ USAGE:
 
 FLAGS:
     -h, --help       Prints help inforamtion
-        --no-dump"
+        --no-dump
+        --json       Dumps the syntax tree as JSON"
                     );
                     return Ok(Err(HelpPrinted));
                 }
 
                 let no_dump = matches.contains("--no-dump");
+                let json = matches.contains("--json");
                 matches.finish().or_else(handle_extra_flags)?;
-                Command::Parse { no_dump }
+                Command::Parse { no_dump, json }
             }
             "symbols" => {
                 if matches.contains(["-h", "--help"]) {
@@ -146,6 +150,8 @@ FLAGS:
 
 OPTIONS:
     -o <ONLY>
+        --compare-with-rustc <DIR>  Compare the types of local bindings with rustc's, as
+                                    recorded in `-Zsave-analysis` output in DIR
 
 ARGS:
     <PATH>"
@@ -158,6 +164,8 @@ ARGS:
                 let only: Option<String> = matches.opt_value_from_str(["-o", "--only"])?;
                 let with_deps: bool = matches.contains("--with-deps");
                 let load_output_dirs = matches.contains("--load-output-dirs");
+                let compare_with_rustc: Option<PathBuf> =
+                    matches.opt_value_from_str("--compare-with-rustc")?;
                 let path = {
                     let mut trailing = matches.free()?;
                     if trailing.len() != 1 {
@@ -166,7 +174,15 @@ ARGS:
                     trailing.pop().unwrap().into()
                 };
 
-                Command::Stats { randomize, memory_usage, only, with_deps, path, load_output_dirs }
+                Command::Stats {
+                    randomize,
+                    memory_usage,
+                    only,
+                    with_deps,
+                    path,
+                    load_output_dirs,
+                    compare_with_rustc,
+                }
             }
             "analysis-bench" => {
                 if matches.contains(["-h", "--help"]) {
@@ -15,7 +15,7 @@ fn main() -> Result<()> {
         Err(HelpPrinted) => return Ok(()),
     };
     match args.command {
-        args::Command::Parse { no_dump } => cli::parse(no_dump)?,
+        args::Command::Parse { no_dump, json } => cli::parse(no_dump, json)?,
         args::Command::Symbols => cli::symbols()?,
         args::Command::Highlight { rainbow } => cli::highlight(rainbow)?,
         args::Command::Stats {
@@ -25,6 +25,7 @@ fn main() -> Result<()> {
             with_deps,
             path,
             load_output_dirs,
+            compare_with_rustc,
         } => cli::analysis_stats(
             args.verbosity,
             memory_usage,
@@ -33,6 +34,7 @@ fn main() -> Result<()> {
             with_deps,
             randomize,
             load_output_dirs,
+            compare_with_rustc.as_deref(),
         )?,
 
         args::Command::Bench { path, what, load_output_dirs } => {
@@ -56,9 +58,16 @@ fn run_server() -> Result<()> {
     log::info!("lifecycle: server started");
 
     let (connection, io_threads) = Connection::stdio();
-    let server_capabilities = serde_json::to_value(rust_analyzer::server_capabilities()).unwrap();
+    let mut server_capabilities =
+        serde_json::to_value(rust_analyzer::server_capabilities()).unwrap();
+    // `lsp_types` doesn't know about pull diagnostics yet, neither the server
+    // capability nor the client one.
+    server_capabilities["diagnosticProvider"] =
+        serde_json::json!({ "interFileDependencies": true, "workspaceDiagnostics": false });
 
     let initialize_params = connection.initialize(server_capabilities)?;
+    let pull_diagnostics =
+        initialize_params.pointer("/capabilities/textDocument/diagnostic").is_some();
     let initialize_params =
         from_json::<lsp_types::InitializeParams>("InitializeParams", initialize_params)?;
 
@@ -85,6 +94,16 @@ fn run_server() -> Result<()> {
         if let Some(caps) = &initialize_params.capabilities.text_document {
             config.update_caps(caps);
         }
+        config.client_caps.pull_diagnostics = pull_diagnostics;
+        if let Some(value) = initialize_params
+            .capabilities
+            .experimental
+            .as_ref()
+            .and_then(|it| it.get("resolveCodeAction"))
+            .and_then(|it| it.as_bool())
+        {
+            config.client_caps.resolve_code_action = value;
+        }
         config
     };
 
@@ -67,12 +67,15 @@ pub fn server_capabilities() -> ServerCapabilities {
                     token_modifiers: semantic_tokens::SUPPORTED_MODIFIERS.to_vec(),
                 },
 
-                document_provider: Some(SemanticTokensDocumentProvider::Bool(true)),
+                document_provider: Some(SemanticTokensDocumentProvider::Edits {
+                    edits: Some(true),
+                }),
                 range_provider: Some(true),
                 work_done_progress_options: Default::default(),
             }
             .into(),
         ),
-        experimental: Default::default(),
+        // `lsp_types` doesn't know about `monikerProvider` yet.
+        experimental: Some(serde_json::json!({ "monikerProvider": true })),
     }
 }
@@ -0,0 +1,243 @@
+//! Adding dependencies to `Cargo.toml` for crates which are `use`d, but are
+//! not in the crate graph.
+//!
+//! We don't talk to the network here: the crate is only offered if cargo has
+//! already cached it in the local copy of the registry index.
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use lsp_types::{CodeAction, Command, Position, Range, TextEdit, Url, WorkspaceEdit};
+use ra_ide::FileId;
+use ra_project_model::ProjectWorkspace;
+use serde::Deserialize;
+use serde_json::to_value;
+
+use crate::{world::WorldSnapshot, Result};
+
+/// Returns the manifest of the package the file belongs to.
+pub(crate) fn manifest_for_file(world: &WorldSnapshot, file_id: FileId) -> Result<Option<PathBuf>> {
+    let &crate_id = match world.analysis().crate_for(file_id)?.first() {
+        Some(crate_id) => crate_id,
+        None => return Ok(None),
+    };
+    let file_id = world.analysis().crate_root(crate_id)?;
+    let path = world.file_id_to_path(file_id);
+    let res = world.workspaces.iter().find_map(|ws| match ws {
+        ProjectWorkspace::Cargo { cargo, .. } => {
+            let tgt = cargo.target_by_root(&path)?;
+            Some(cargo[cargo[tgt].package].manifest.clone())
+        }
+        ProjectWorkspace::Json { .. } => None,
+    });
+    Ok(res)
+}
+
+/// Creates a code action which adds `name = "version"` to the dependencies
+/// in `manifest` and reloads the workspace afterwards.
+pub(crate) fn add_dependency_action(
+    manifest: &Path,
+    manifest_text: &str,
+    name: &str,
+    version: &str,
+) -> Result<CodeAction> {
+    let (position, new_text) = dependency_insertion(manifest_text, name, version);
+    let url = Url::from_file_path(manifest)
+        .map_err(|_| format!("can't convert path to url: {}", manifest.display()))?;
+
+    let mut changes = HashMap::new();
+    changes.insert(url.clone(), vec![TextEdit::new(Range::new(position, position), new_text)]);
+    let edit = WorkspaceEdit { changes: Some(changes), document_changes: None };
+
+    let title = format!("Add `{} = \"{}\"` to Cargo.toml", name, version);
+    let command = Command {
+        title: title.clone(),
+        command: "rust-analyzer.saveAndReload".to_string(),
+        arguments: Some(vec![to_value(url)?]),
+    };
+    Ok(CodeAction {
+        title,
+        kind: Some("quickfix".to_string()),
+        diagnostics: None,
+        edit: Some(edit),
+        command: Some(command),
+        is_preferred: None,
+    })
+}
+
+/// Finds the newest version of `name` in the local registry index cache,
+/// skipping yanked and pre-release versions.
+///
+/// The lookup is remembered for the session, so crates fetched by cargo in
+/// the meantime only show up after the workspace is reloaded.
+pub(crate) fn find_cached_version(world: &WorldSnapshot, name: &str) -> Option<String> {
+    if !name.is_ascii() {
+        return None;
+    }
+    world
+        .registry_versions
+        .lock()
+        .entry(name.to_string())
+        .or_insert_with(|| scan_registry_index(name))
+        .clone()
+}
+
+fn scan_registry_index(name: &str) -> Option<String> {
+    let index_dir = cargo_home()?.join("registry").join("index");
+    let cache_path = index_cache_path(name);
+    fs::read_dir(index_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| fs::read(entry.path().join(".cache").join(&cache_path)).ok())
+        .flat_map(|data| cached_versions(&data))
+        .max_by_key(|(_, key)| *key)
+        .map(|(version, _)| version)
+}
+
+fn cargo_home() -> Option<PathBuf> {
+    if let Some(home) = env::var_os("CARGO_HOME") {
+        return Some(home.into());
+    }
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".cargo"))
+}
+
+/// The path of a crate within the index, as used by cargo: `1/a`, `2/ab`,
+/// `3/a/abc` and `ab/cd/abcd` for longer names.
+fn index_cache_path(name: &str) -> PathBuf {
+    let name = name.to_lowercase();
+    match name.len() {
+        1 => Path::new("1").join(&name),
+        2 => Path::new("2").join(&name),
+        3 => Path::new("3").join(&name[..1]).join(&name),
+        _ => Path::new(&name[..2]).join(&name[2..4]).join(&name),
+    }
+}
+
+/// The cache file is a small header followed by NUL-separated pairs of a
+/// version and the JSON line from the index for that version.
+fn cached_versions(data: &[u8]) -> Vec<(String, (u64, u64, u64))> {
+    #[derive(Deserialize)]
+    struct IndexEntry {
+        vers: String,
+        #[serde(default)]
+        yanked: bool,
+    }
+
+    data.split(|&b| b == 0)
+        .filter(|chunk| chunk.starts_with(b"{"))
+        .filter_map(|chunk| serde_json::from_slice::<IndexEntry>(chunk).ok())
+        .filter(|entry| !entry.yanked)
+        .filter_map(|entry| {
+            let key = release_version(&entry.vers)?;
+            Some((entry.vers, key))
+        })
+        .collect()
+}
+
+fn release_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.split('+').next()?;
+    if version.contains('-') {
+        return None;
+    }
+    let mut parts = version.split('.').map(|it| it.parse::<u64>().ok());
+    let res = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(res)
+}
+
+/// Returns the position at which the dependency should be inserted, and the
+/// text to insert. The entry goes after the last entry of the
+/// `[dependencies]` table, which is created if it doesn't exist.
+fn dependency_insertion(manifest: &str, name: &str, version: &str) -> (Position, String) {
+    let entry = format!("{} = \"{}\"\n", name, version);
+    let lines = manifest.lines().collect::<Vec<_>>();
+    let header = lines.iter().position(|line| line.trim() == "[dependencies]");
+
+    let (line, mut text) = match header {
+        Some(header) => {
+            let mut end = header + 1;
+            for (idx, line) in lines.iter().enumerate().skip(header + 1) {
+                let line = line.trim();
+                if line.starts_with('[') {
+                    break;
+                }
+                if !line.is_empty() && !line.starts_with('#') {
+                    end = idx + 1;
+                }
+            }
+            (end, entry)
+        }
+        None => {
+            let separator = if manifest.trim().is_empty() { "" } else { "\n" };
+            (lines.len(), format!("{}[dependencies]\n{}", separator, entry))
+        }
+    };
+
+    if line == lines.len() && !manifest.is_empty() && !manifest.ends_with('\n') {
+        // There's no line to insert before, so append to the last one.
+        let last = lines[line - 1];
+        text.insert(0, '\n');
+        let character = last.encode_utf16().count() as u64;
+        return (Position::new(line as u64 - 1, character), text);
+    }
+    (Position::new(line as u64, 0), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_insertion(before: &str, after: &str) {
+        let (position, text) = dependency_insertion(before, "foo", "1.2.3");
+        let mut lines = before.split('\n').map(String::from).collect::<Vec<_>>();
+        let line = &mut lines[position.line as usize];
+        line.insert_str(position.character as usize, &text);
+        assert_eq!(lines.join("\n"), after);
+    }
+
+    #[test]
+    fn test_dependency_insertion() {
+        check_insertion(
+            "[package]\nname = \"bar\"\n\n[dependencies]\nbaz = \"1\"\n\n[dev-dependencies]\n",
+            "[package]\nname = \"bar\"\n\n[dependencies]\nbaz = \"1\"\nfoo = \"1.2.3\"\n\n[dev-dependencies]\n",
+        );
+        check_insertion(
+            "[package]\nname = \"bar\"\n\n[dependencies]\n",
+            "[package]\nname = \"bar\"\n\n[dependencies]\nfoo = \"1.2.3\"\n",
+        );
+        check_insertion(
+            "[package]\nname = \"bar\"\n",
+            "[package]\nname = \"bar\"\n\n[dependencies]\nfoo = \"1.2.3\"\n",
+        );
+        check_insertion(
+            "[package]\nname = \"bar\"",
+            "[package]\nname = \"bar\"\n\n[dependencies]\nfoo = \"1.2.3\"\n",
+        );
+    }
+
+    #[test]
+    fn test_index_cache_path() {
+        assert_eq!(index_cache_path("a"), Path::new("1/a"));
+        assert_eq!(index_cache_path("ab"), Path::new("2/ab"));
+        assert_eq!(index_cache_path("abc"), Path::new("3/a/abc"));
+        assert_eq!(index_cache_path("Serde"), Path::new("se/rd/serde"));
+    }
+
+    #[test]
+    fn test_cached_versions() {
+        let data = b"\x01\x01\x00\x00\x00etag\x00\
+            1.0.0\x00{\"name\":\"foo\",\"vers\":\"1.0.0\",\"yanked\":false}\x00\
+            1.10.0\x00{\"name\":\"foo\",\"vers\":\"1.10.0\",\"yanked\":false}\x00\
+            1.2.0\x00{\"name\":\"foo\",\"vers\":\"1.2.0\",\"yanked\":false}\x00\
+            2.0.0\x00{\"name\":\"foo\",\"vers\":\"2.0.0\",\"yanked\":true}\x00\
+            2.1.0-beta\x00{\"name\":\"foo\",\"vers\":\"2.1.0-beta\",\"yanked\":false}\x00";
+        let newest = cached_versions(data).into_iter().max_by_key(|(_, key)| *key);
+        assert_eq!(newest.map(|(version, _)| version), Some("1.10.0".to_string()));
+    }
+}
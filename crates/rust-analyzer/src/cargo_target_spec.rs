@@ -1,5 +1,7 @@
 //! See `CargoTargetSpec`
 
+use std::path::PathBuf;
+
 use ra_ide::{FileId, RunnableKind, TestId};
 use ra_project_model::{self, ProjectWorkspace, TargetKind};
 
@@ -11,6 +13,8 @@ use crate::{world::WorldSnapshot, Result};
 /// build/test/run the target.
 pub(crate) struct CargoTargetSpec {
     pub(crate) package: String,
+    pub(crate) package_name: String,
+    pub(crate) manifest_path: PathBuf,
     pub(crate) target: String,
     pub(crate) target_kind: TargetKind,
 }
@@ -76,8 +80,11 @@ impl CargoTargetSpec {
         let res = world.workspaces.iter().find_map(|ws| match ws {
             ProjectWorkspace::Cargo { cargo, .. } => {
                 let tgt = cargo.target_by_root(&path)?;
+                let package = &cargo[cargo[tgt].package];
                 Some(CargoTargetSpec {
-                    package: cargo.package_flag(&cargo[cargo[tgt].package]),
+                    package: cargo.package_flag(package),
+                    package_name: package.name.clone(),
+                    manifest_path: package.manifest.clone(),
                     target: cargo[tgt].name.clone(),
                     target_kind: cargo[tgt].kind,
                 })
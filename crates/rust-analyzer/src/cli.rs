@@ -4,6 +4,7 @@ mod load_cargo;
 mod analysis_stats;
 mod analysis_bench;
 mod progress_report;
+mod save_analysis;
 
 use std::io::Read;
 
@@ -35,11 +36,15 @@ impl Verbosity {
     }
 }
 
-pub fn parse(no_dump: bool) -> Result<()> {
+pub fn parse(no_dump: bool, json: bool) -> Result<()> {
     let _p = profile("parsing");
     let file = file()?;
     if !no_dump {
-        println!("{:#?}", file.syntax());
+        if json {
+            println!("{}", serde_json::to_string(&file)?);
+        } else {
+            println!("{:#?}", file.syntax());
+        }
     }
     std::mem::forget(file);
     Ok(())
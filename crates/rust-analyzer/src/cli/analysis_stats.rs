@@ -7,15 +7,20 @@ use hir::{
     db::{AstDatabase, DefDatabase, HirDatabase},
     original_range, AssocItem, Crate, HasSource, HirDisplay, ModuleDef,
 };
-use hir_def::FunctionId;
+use hir_def::{expr::Pat, FunctionId};
 use hir_ty::{Ty, TypeWalk};
 use itertools::Itertools;
 use ra_db::SourceDatabaseExt;
-use ra_syntax::AstNode;
+use ra_syntax::{ast, AstNode};
 use rand::{seq::SliceRandom, thread_rng};
 use stdx::format_to;
 
-use crate::cli::{load_cargo::load_cargo, progress_report::ProgressReport, Result, Verbosity};
+use crate::cli::{
+    load_cargo::load_cargo,
+    progress_report::ProgressReport,
+    save_analysis::{self, RustcTypes},
+    Result, Verbosity,
+};
 
 pub fn analysis_stats(
     verbosity: Verbosity,
@@ -25,7 +30,18 @@ pub fn analysis_stats(
     with_deps: bool,
     randomize: bool,
     load_output_dirs: bool,
+    compare_with_rustc: Option<&Path>,
 ) -> Result<()> {
+    let rustc_types = match compare_with_rustc {
+        Some(dir) => {
+            let types = RustcTypes::load(dir)?;
+            if types.is_empty() {
+                eprintln!("no local bindings found in save-analysis data in {}", dir.display());
+            }
+            Some(types)
+        }
+        None => None,
+    };
     let db_load_time = Instant::now();
     let (mut host, roots) = load_cargo(path, load_output_dirs)?;
     let db = host.raw_database();
@@ -108,6 +124,8 @@ pub fn analysis_stats(
     let mut num_exprs_unknown = 0;
     let mut num_exprs_partially_unknown = 0;
     let mut num_type_mismatches = 0;
+    let mut num_rustc_compared = 0;
+    let mut rustc_divergences: Vec<(String, usize)> = Vec::new();
     for f in funcs {
         let name = f.name(db);
         let full_name = f
@@ -228,6 +246,55 @@ pub fn analysis_stats(
                 }
             }
         }
+        if let Some(rustc_types) = &rustc_types {
+            let mut num_divergences = 0;
+            let (_, sm) = db.body_with_source_map(f_id.into());
+            for (pat_id, pat) in body.pats.iter() {
+                if !matches!(pat, Pat::Bind { .. }) {
+                    continue;
+                }
+                let src = match sm.pat_syntax(pat_id) {
+                    Ok(src) => src,
+                    Err(_) => continue,
+                };
+                let root = db.parse_or_expand(src.file_id).unwrap();
+                let name = match src.value.left().map(|it| it.to_node(&root)) {
+                    Some(ast::Pat::BindPat(it)) => match it.name() {
+                        Some(name) => name,
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                let range = original_range(db, src.with_value(name.syntax()));
+                let path = db.file_relative_path(range.file_id);
+                let rustc_ty =
+                    match rustc_types.get(path.as_str(), range.range.start().to_usize() as u32) {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                num_rustc_compared += 1;
+                let ty = inference_result[pat_id].display(db).to_string();
+                if save_analysis::normalize(&ty) != rustc_ty {
+                    num_divergences += 1;
+                    if verbosity.is_verbose() {
+                        let line_index = host.analysis().file_line_index(range.file_id).unwrap();
+                        let start = line_index.line_col(range.range.start());
+                        bar.println(format!(
+                            "{} {}:{}: {}: rustc infers {}, we infer {}",
+                            path,
+                            start.line + 1,
+                            start.col_utf16,
+                            name.syntax().text(),
+                            rustc_ty,
+                            ty
+                        ));
+                    }
+                }
+            }
+            if num_divergences > 0 {
+                rustc_divergences.push((full_name.clone(), num_divergences));
+            }
+        }
         if verbosity.is_spammy() {
             bar.println(format!(
                 "In {}: {} exprs, {} unknown, {} partial",
@@ -252,6 +319,19 @@ pub fn analysis_stats(
         if num_exprs > 0 { num_exprs_partially_unknown * 100 / num_exprs } else { 100 }
     );
     println!("Type mismatches: {}", num_type_mismatches);
+    if rustc_types.is_some() {
+        let num_divergent: usize = rustc_divergences.iter().map(|(_, n)| n).sum();
+        println!(
+            "Bindings compared with rustc: {}, divergent: {} ({}%)",
+            num_rustc_compared,
+            num_divergent,
+            if num_rustc_compared > 0 { num_divergent * 100 / num_rustc_compared } else { 0 }
+        );
+        rustc_divergences.sort_by(|(_, a), (_, b)| b.cmp(a));
+        for (name, n) in rustc_divergences.iter().take(10) {
+            println!("{:>6} {}", n, name);
+        }
+    }
     println!("Inference: {:?}, {}", inference_time.elapsed(), ra_prof::memory_usage());
     println!("Total: {:?}, {}", analysis_time.elapsed(), ra_prof::memory_usage());
 
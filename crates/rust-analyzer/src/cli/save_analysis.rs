@@ -0,0 +1,123 @@
+//! Reads the types rustc inferred for local bindings from `-Zsave-analysis`
+//! output, so that `analysis-stats` can compare them with ours.
+//!
+//! The data is produced with
+//! `RUSTFLAGS=-Zsave-analysis cargo +nightly check`, which writes one JSON file
+//! per crate into `target/debug/deps/save-analysis`.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct SaveAnalysis {
+    defs: Vec<Def>,
+}
+
+#[derive(Deserialize)]
+struct Def {
+    kind: String,
+    span: Span,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct Span {
+    file_name: String,
+    byte_start: u32,
+}
+
+/// Types of local bindings as printed by rustc, indexed by the byte offset of
+/// the binding's name.
+#[derive(Default)]
+pub(crate) struct RustcTypes {
+    by_offset: FxHashMap<u32, Vec<(String, String)>>,
+}
+
+impl RustcTypes {
+    pub(crate) fn load(dir: &Path) -> Result<RustcTypes> {
+        let mut res = RustcTypes::default();
+        for entry in fs::read_dir(dir).with_context(|| format!("can't read {}", dir.display()))? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |it| it != "json") {
+                continue;
+            }
+            let text = fs::read_to_string(&path)?;
+            let analysis: SaveAnalysis = serde_json::from_str(&text)
+                .with_context(|| format!("invalid save-analysis file {}", path.display()))?;
+            for def in analysis.defs {
+                if def.kind != "Local" {
+                    continue;
+                }
+                // The value looks like `mut x: Vec<i32>`.
+                let ty = match def.value.find(": ") {
+                    Some(idx) => def.value[idx + 2..].to_string(),
+                    None => continue,
+                };
+                res.by_offset
+                    .entry(def.span.byte_start)
+                    .or_default()
+                    .push((def.span.file_name, normalize(&ty)));
+            }
+        }
+        Ok(res)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.by_offset.is_empty()
+    }
+
+    /// Looks up the type of the binding at `offset` in the file at `path`.
+    ///
+    /// rustc reports file names relative to the directory it was invoked in,
+    /// while `path` is relative to the source root, so files are matched by
+    /// suffix.
+    pub(crate) fn get(&self, path: &str, offset: u32) -> Option<&str> {
+        self.by_offset
+            .get(&offset)?
+            .iter()
+            .find(|(file_name, _)| file_name.ends_with(path))
+            .map(|(_, ty)| ty.as_str())
+    }
+}
+
+/// Strips module paths from a type, so that `std::vec::Vec<i32>` compares
+/// equal to the way we display it, `Vec<i32>`.
+pub(crate) fn normalize(ty: &str) -> String {
+    let mut res = String::with_capacity(ty.len());
+    let mut segment = String::new();
+    let mut chars = ty.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            segment.clear();
+        } else {
+            res.push_str(&segment);
+            segment.clear();
+            if c != ' ' || !res.ends_with(',') {
+                res.push(c);
+            }
+        }
+    }
+    res.push_str(&segment);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize;
+
+    #[test]
+    fn normalize_strips_paths() {
+        assert_eq!(normalize("std::vec::Vec<i32>"), "Vec<i32>");
+        assert_eq!(
+            normalize("std::collections::HashMap<std::string::String, (u8, &str)>"),
+            "HashMap<String,(u8,&str)>"
+        );
+        assert_eq!(normalize("&mut [foo::Bar; 4]"), "&mut [Bar; 4]");
+    }
+}
@@ -7,9 +7,14 @@
 //! configure the server itself, feature flags are passed into analysis, and
 //! tweak things like automatic insertion of `()` in completions.
 
+use std::{fs, path::PathBuf};
+
 use lsp_types::TextDocumentClientCapabilities;
 use ra_flycheck::FlycheckConfig;
-use ra_ide::{CompletionConfig, InlayHintsConfig};
+use ra_ide::{
+    AssistConfig, BodyWrapper, CompletionConfig, Dictionary, InlayHintsConfig, Snippet,
+    SnippetScope, SpellCheckConfig,
+};
 use ra_project_model::CargoConfig;
 use serde::Deserialize;
 
@@ -19,7 +24,15 @@ pub struct Config {
 
     pub with_sysroot: bool,
     pub publish_diagnostics: bool,
+    /// Hints for misspelled words, `None` if disabled.
+    pub spell_check: Option<SpellCheckConfig>,
     pub lru_capacity: Option<usize>,
+    /// Whether the symbols of the workspace are saved to the target directory,
+    /// to speed up symbol search in the next session. Off by default.
+    pub symbol_cache: bool,
+    /// Resident memory, in megabytes, above which the server switches to a
+    /// low memory mode instead of growing until it is killed.
+    pub memory_limit: Option<usize>,
     pub proc_macro_srv: Option<String>,
     pub files: FilesConfig,
     pub notifications: NotificationsConfig,
@@ -30,6 +43,7 @@ pub struct Config {
 
     pub inlay_hints: InlayHintsConfig,
     pub completion: CompletionConfig,
+    pub assist: AssistConfig,
     pub call_info_full: bool,
 }
 
@@ -37,6 +51,11 @@ pub struct Config {
 pub struct FilesConfig {
     pub watcher: FilesWatcher,
     pub exclude: Vec<String>,
+    /// Directories which are not loaded at all. Relative paths are resolved
+    /// against the workspace folders.
+    pub exclude_dirs: Vec<PathBuf>,
+    /// Names of workspace packages which are not loaded at all.
+    pub exclude_crates: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +86,14 @@ pub enum RustfmtConfig {
 pub struct ClientCapsConfig {
     pub location_link: bool,
     pub line_folding_only: bool,
+    /// The client asks for the diagnostics of a document with
+    /// `textDocument/diagnostic`, so they are not published.
+    pub pull_diagnostics: bool,
+    /// The client computes the edit of a code action with
+    /// `rust-analyzer/resolveCodeAction` once it is picked, so code actions
+    /// are sent without edits. Set by the experimental `resolveCodeAction`
+    /// capability.
+    pub resolve_code_action: bool,
 }
 
 impl Default for Config {
@@ -76,9 +103,17 @@ impl Default for Config {
 
             with_sysroot: true,
             publish_diagnostics: true,
+            spell_check: None,
             lru_capacity: None,
+            symbol_cache: false,
+            memory_limit: None,
             proc_macro_srv: None,
-            files: FilesConfig { watcher: FilesWatcher::Notify, exclude: Vec::new() },
+            files: FilesConfig {
+                watcher: FilesWatcher::Notify,
+                exclude: Vec::new(),
+                exclude_dirs: Vec::new(),
+                exclude_crates: Vec::new(),
+            },
             notifications: NotificationsConfig {
                 workspace_loaded: true,
                 cargo_toml_not_found: true,
@@ -102,7 +137,9 @@ impl Default for Config {
                 enable_postfix_completions: true,
                 add_call_parenthesis: true,
                 add_call_argument_snippets: true,
+                snippets: Vec::new(),
             },
+            assist: AssistConfig::default(),
             call_info_full: true,
         }
     }
@@ -120,10 +157,28 @@ impl Config {
         set(value, "/withSysroot", &mut self.with_sysroot);
         set(value, "/featureFlags/lsp.diagnostics", &mut self.publish_diagnostics);
         set(value, "/lruCapacity", &mut self.lru_capacity);
+        set(value, "/symbolCache/enable", &mut self.symbol_cache);
+        set(value, "/memoryLimit", &mut self.memory_limit);
+        if let Some(true) = get(value, "/diagnostics/spellCheck/enable") {
+            let dictionary = match get::<PathBuf>(value, "/diagnostics/spellCheck/dictionary") {
+                Some(path) => match fs::read_to_string(&path) {
+                    Ok(text) => Dictionary::from_word_list(&text),
+                    Err(e) => {
+                        log::error!("failed to read dictionary {}: {}", path.display(), e);
+                        Dictionary::default()
+                    }
+                },
+                None => Dictionary::default(),
+            };
+            self.spell_check = Some(SpellCheckConfig { dictionary });
+        }
         self.files.watcher = match get(value, "/files/watcher") {
             Some("client") => FilesWatcher::Client,
             Some("notify") | _ => FilesWatcher::Notify
         };
+        set(value, "/files/exclude", &mut self.files.exclude);
+        set(value, "/files/excludeDirs", &mut self.files.exclude_dirs);
+        set(value, "/files/excludeCrates", &mut self.files.exclude_crates);
         set(value, "/notifications/workspaceLoaded", &mut self.notifications.workspace_loaded);
         set(value, "/notifications/cargoTomlNotFound", &mut self.notifications.cargo_toml_not_found);
 
@@ -180,10 +235,55 @@ impl Config {
         set(value, "/completion/postfix/enable", &mut self.completion.enable_postfix_completions);
         set(value, "/completion/addCallParenthesis", &mut self.completion.add_call_parenthesis);
         set(value, "/completion/addCallArgumentSnippets", &mut self.completion.add_call_argument_snippets);
+        if let Some(snippets) = get::<Vec<SnippetConfig>>(value, "/completion/snippets") {
+            self.completion.snippets = snippets
+                .into_iter()
+                .filter_map(|it| {
+                    let scope = match it.scope.as_deref() {
+                        None | Some("expr") => SnippetScope::Expr,
+                        Some("item") => SnippetScope::Item,
+                        Some("postfix") => SnippetScope::Postfix,
+                        Some(scope) => {
+                            log::error!("unknown scope {:?} of snippet {:?}", scope, it.label);
+                            return None;
+                        }
+                    };
+                    Some(Snippet {
+                        label: it.label,
+                        body: it.body,
+                        description: it.description,
+                        scope,
+                    })
+                })
+                .collect();
+        }
+        if let Some(wrappers) = get::<Vec<BodyWrapperConfig>>(value, "/assist/bodyWrappers") {
+            self.assist.body_wrappers = wrappers
+                .into_iter()
+                .map(|it| BodyWrapper { name: it.name, template: it.template, make_sync: it.make_sync })
+                .collect();
+        }
         set(value, "/callInfo/full", &mut self.call_info_full);
 
         log::info!("Config::update() = {:#?}", self);
 
+        #[derive(Deserialize)]
+        struct SnippetConfig {
+            label: String,
+            body: String,
+            description: Option<String>,
+            scope: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct BodyWrapperConfig {
+            name: String,
+            template: String,
+            #[serde(default)]
+            make_sync: bool,
+        }
+
         fn get<'a, T: Deserialize<'a>>(value: &'a serde_json::Value, pointer: &str) -> Option<T> {
             value.pointer(pointer).and_then(|it| T::deserialize(it).ok())
         }
@@ -488,8 +488,30 @@ impl TryConvWith<&WorldSnapshot> for SourceChange {
             }
         };
         let mut document_changes: Vec<DocumentChangeOperation> = Vec::new();
-        for resource_op in self.file_system_edits.try_conv_with(world)? {
-            document_changes.push(DocumentChangeOperation::Op(resource_op));
+        for file_system_edit in self.file_system_edits {
+            // LSP can only create empty files, so the contents are inserted by
+            // an edit of the new file.
+            let contents_edit = match &file_system_edit {
+                FileSystemEdit::CreateFile { source_root, path, initial_contents }
+                    if !initial_contents.is_empty() =>
+                {
+                    let uri = world.path_to_uri(*source_root, path)?;
+                    let start = Position::new(0, 0);
+                    Some(TextDocumentEdit {
+                        text_document: VersionedTextDocumentIdentifier { uri, version: None },
+                        edits: vec![lsp_types::TextEdit::new(
+                            Range::new(start, start),
+                            initial_contents.clone(),
+                        )],
+                    })
+                }
+                _ => None,
+            };
+            document_changes
+                .push(DocumentChangeOperation::Op(file_system_edit.try_conv_with(world)?));
+            if let Some(edit) = contents_edit {
+                document_changes.push(DocumentChangeOperation::Edit(edit));
+            }
         }
         for text_document_edit in self.source_file_edits.try_conv_with(world)? {
             document_changes.push(DocumentChangeOperation::Edit(text_document_edit));
@@ -521,7 +543,7 @@ impl TryConvWith<&WorldSnapshot> for FileSystemEdit {
     type Output = ResourceOp;
     fn try_conv_with(self, world: &WorldSnapshot) -> Result<ResourceOp> {
         let res = match self {
-            FileSystemEdit::CreateFile { source_root, path } => {
+            FileSystemEdit::CreateFile { source_root, path, .. } => {
                 let uri = world.path_to_uri(source_root, &path)?;
                 ResourceOp::Create(CreateFile { uri, options: None })
             }
@@ -4,6 +4,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use lsp_types::{CodeActionOrCommand, Diagnostic, Range};
 use ra_ide::FileId;
+use ra_syntax::TextUnit;
 
 pub type CheckFixes = Arc<HashMap<FileId, Vec<Fix>>>;
 
@@ -25,6 +26,53 @@ pub enum DiagnosticTask {
     ClearCheck,
     AddCheck(FileId, Diagnostic, Vec<CodeActionOrCommand>),
     SetNative(FileId, Vec<Diagnostic>),
+    /// Replaces the native diagnostics inside of the range of a single item.
+    SetNativeInRange(FileId, Range, Vec<Diagnostic>),
+    /// Moves the native diagnostics after an edit along with the text.
+    ShiftNative(FileId, EditedLines),
+}
+
+/// The part of a file which was replaced by an edit, found by comparing the
+/// texts before and after the edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditedLines {
+    /// The offset of the first changed character.
+    pub offset: TextUnit,
+    /// The offset the replaced text ends at, in the old text.
+    pub old_end: TextUnit,
+    /// The offset the inserted text ends at, in the new text.
+    pub new_end: TextUnit,
+    /// The line the replaced text ends on, in the old text.
+    pub old_end_line: u64,
+    /// The line the inserted text ends on, in the new text.
+    pub new_end_line: u64,
+}
+
+impl EditedLines {
+    pub fn between(old_text: &str, new_text: &str) -> EditedLines {
+        let prefix = old_text
+            .chars()
+            .zip(new_text.chars())
+            .take_while(|(old, new)| old == new)
+            .map(|(it, _)| it.len_utf8())
+            .sum::<usize>();
+        let (old_rest, new_rest) = (&old_text.as_bytes()[prefix..], &new_text.as_bytes()[prefix..]);
+        let suffix = old_rest
+            .iter()
+            .rev()
+            .zip(new_rest.iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let (old_end, new_end) = (old_text.len() - suffix, new_text.len() - suffix);
+        let lines = |text: &[u8]| text.iter().filter(|&&it| it == b'\n').count() as u64;
+        EditedLines {
+            offset: TextUnit::from_usize(prefix),
+            old_end: TextUnit::from_usize(old_end),
+            new_end: TextUnit::from_usize(new_end),
+            old_end_line: lines(&old_text.as_bytes()[..old_end]),
+            new_end_line: lines(&new_text.as_bytes()[..new_end]),
+        }
+    }
 }
 
 impl DiagnosticCollection {
@@ -58,6 +106,33 @@ impl DiagnosticCollection {
         self.native.insert(file_id, diagnostics);
     }
 
+    pub fn set_native_diagnostics_in_range(
+        &mut self,
+        file_id: FileId,
+        range: Range,
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        let native = self.native.entry(file_id).or_default();
+        native.retain(|it| !is_subrange(range, it.range));
+        native.extend(diagnostics);
+    }
+
+    /// Moves the native diagnostics starting after the edited lines, so that
+    /// they stay in place after an edit until they are recomputed.
+    pub fn shift_native_diagnostics(&mut self, file_id: FileId, edited: EditedLines) {
+        let delta = edited.new_end_line as i64 - edited.old_end_line as i64;
+        if delta == 0 {
+            return;
+        }
+        for diagnostic in self.native.get_mut(&file_id).into_iter().flatten() {
+            let range = &mut diagnostic.range;
+            if range.start.line > edited.old_end_line {
+                range.start.line = (range.start.line as i64 + delta) as u64;
+                range.end.line = (range.end.line as i64 + delta) as u64;
+            }
+        }
+    }
+
     pub fn diagnostics_for(&self, file_id: FileId) -> impl Iterator<Item = &Diagnostic> {
         let native = self.native.get(&file_id).into_iter().flatten();
         let check = self.check.get(&file_id).into_iter().flatten();
@@ -75,6 +150,14 @@ impl DiagnosticCollection {
                 self.set_native_diagnostics(file_id, diagnostics);
                 vec![file_id]
             }
+            DiagnosticTask::SetNativeInRange(file_id, range, diagnostics) => {
+                self.set_native_diagnostics_in_range(file_id, range, diagnostics);
+                vec![file_id]
+            }
+            DiagnosticTask::ShiftNative(file_id, edited) => {
+                self.shift_native_diagnostics(file_id, edited);
+                vec![file_id]
+            }
         }
     }
 }
@@ -85,3 +168,121 @@ fn are_diagnostics_equal(left: &Diagnostic, right: &Diagnostic) -> bool {
         && left.range == right.range
         && left.message == right.message
 }
+
+fn is_subrange(range: Range, subrange: Range) -> bool {
+    let start = |it: Range| (it.start.line, it.start.character);
+    let end = |it: Range| (it.end.line, it.end.character);
+    start(range) <= start(subrange) && end(subrange) <= end(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::Position;
+
+    use super::*;
+
+    fn diagnostic(start_line: u64, end_line: u64) -> Diagnostic {
+        let range = Range::new(Position::new(start_line, 0), Position::new(end_line, 1));
+        Diagnostic::new_simple(range, String::new())
+    }
+
+    fn native_lines(diagnostics: &DiagnosticCollection) -> Vec<(u64, u64)> {
+        diagnostics
+            .diagnostics_for(FileId(0))
+            .map(|it| (it.range.start.line, it.range.end.line))
+            .collect()
+    }
+
+    #[test]
+    fn edited_lines() {
+        let edited = EditedLines::between("fn a() {}\nfn b() {}\n", "fn a() {\n}\nfn b() {}\n");
+        assert_eq!(
+            edited,
+            EditedLines {
+                offset: TextUnit::from(8),
+                old_end: TextUnit::from(8),
+                new_end: TextUnit::from(9),
+                old_end_line: 0,
+                new_end_line: 1,
+            }
+        );
+
+        let edited = EditedLines::between("a\nb\nc\nd\n", "a\nd\n");
+        assert_eq!(
+            edited,
+            EditedLines {
+                offset: TextUnit::from(2),
+                old_end: TextUnit::from(6),
+                new_end: TextUnit::from(2),
+                old_end_line: 3,
+                new_end_line: 1,
+            }
+        );
+
+        let edited = EditedLines::between("λ\nx", "λ\ny");
+        assert_eq!(
+            edited,
+            EditedLines {
+                offset: TextUnit::from(3),
+                old_end: TextUnit::from(4),
+                new_end: TextUnit::from(4),
+                old_end_line: 1,
+                new_end_line: 1,
+            }
+        );
+
+        let edited = EditedLines::between("same", "same");
+        assert_eq!(
+            edited,
+            EditedLines {
+                offset: TextUnit::from(4),
+                old_end: TextUnit::from(4),
+                new_end: TextUnit::from(4),
+                old_end_line: 0,
+                new_end_line: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn shift_native_diagnostics() {
+        let mut diagnostics = DiagnosticCollection::default();
+        diagnostics.set_native_diagnostics(
+            FileId(0),
+            vec![diagnostic(0, 0), diagnostic(2, 2), diagnostic(4, 5)],
+        );
+
+        let inserted = EditedLines {
+            offset: TextUnit::from(0),
+            old_end: TextUnit::from(4),
+            new_end: TextUnit::from(8),
+            old_end_line: 2,
+            new_end_line: 4,
+        };
+        diagnostics.shift_native_diagnostics(FileId(0), inserted);
+        assert_eq!(native_lines(&diagnostics), vec![(0, 0), (2, 2), (6, 7)]);
+
+        let removed = EditedLines {
+            offset: TextUnit::from(0),
+            old_end: TextUnit::from(6),
+            new_end: TextUnit::from(0),
+            old_end_line: 3,
+            new_end_line: 0,
+        };
+        diagnostics.shift_native_diagnostics(FileId(0), removed);
+        assert_eq!(native_lines(&diagnostics), vec![(0, 0), (2, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn set_native_diagnostics_in_range() {
+        let mut diagnostics = DiagnosticCollection::default();
+        diagnostics.set_native_diagnostics(
+            FileId(0),
+            vec![diagnostic(0, 0), diagnostic(2, 2), diagnostic(4, 5)],
+        );
+
+        let item = Range::new(Position::new(1, 0), Position::new(3, 0));
+        diagnostics.set_native_diagnostics_in_range(FileId(0), item, vec![diagnostic(1, 1)]);
+        assert_eq!(native_lines(&diagnostics), vec![(0, 0), (4, 5), (1, 1)]);
+    }
+}
@@ -20,6 +20,7 @@ macro_rules! eprintln {
 mod vfs_glob;
 mod caps;
 mod cargo_target_spec;
+mod cargo_deps;
 mod conv;
 mod main_loop;
 mod markdown;
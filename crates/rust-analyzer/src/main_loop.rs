@@ -8,21 +8,21 @@ pub(crate) mod pending_requests;
 use std::{
     env,
     error::Error,
-    fmt, panic,
+    fmt, mem, panic,
     path::PathBuf,
     sync::Arc,
     time::{Duration, Instant},
 };
 
-use crossbeam_channel::{never, select, unbounded, RecvError, Sender};
+use crossbeam_channel::{at, never, select, tick, unbounded, RecvError, Sender};
 use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
 use lsp_types::{
     NumberOrString, WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressCreateParams,
     WorkDoneProgressEnd, WorkDoneProgressReport,
 };
 use ra_flycheck::{url_from_path_with_drive_lowercasing, CheckTask};
-use ra_ide::{Canceled, FileId, LibraryData, SourceRootId};
-use ra_prof::profile;
+use ra_ide::{Canceled, FileId, FilePosition, LibraryData, SourceRootId};
+use ra_prof::{memory_usage, profile, Bytes};
 use ra_project_model::{PackageRoot, ProjectWorkspace};
 use ra_vfs::{VfsFile, VfsTask, Watch};
 use relative_path::RelativePathBuf;
@@ -32,13 +32,13 @@ use threadpool::ThreadPool;
 
 use crate::{
     config::{Config, FilesWatcher},
-    diagnostics::DiagnosticTask,
+    diagnostics::{DiagnosticTask, EditedLines},
     main_loop::{
         pending_requests::{PendingRequest, PendingRequests},
         subscriptions::Subscriptions,
     },
     req,
-    world::{WorldSnapshot, WorldState},
+    world::{self, WorldSnapshot, WorldState},
     Result,
 };
 
@@ -64,6 +64,12 @@ impl fmt::Display for LspError {
 
 impl Error for LspError {}
 
+/// How long to wait after the last change before re-validating whole files.
+const IDLE_DIAGNOSTICS_DELAY: Duration = Duration::from_millis(500);
+
+/// How often the resident memory is compared against `Config::memory_limit`.
+const MEMORY_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
 pub fn main_loop(ws_roots: Vec<PathBuf>, config: Config, connection: Connection) -> Result<()> {
     log::info!("initial config: {:#?}", config);
 
@@ -170,8 +176,11 @@ pub fn main_loop(ws_roots: Vec<PathBuf>, config: Config, connection: Connection)
     {
         let task_sender = task_sender;
         let libdata_sender = libdata_sender;
+        let memory_check = tick(MEMORY_CHECK_INTERVAL);
+        warn_if_memory_limit_unsupported(&world_state.config, &connection.sender);
         loop {
             log::trace!("selecting");
+            let idle = loop_state.idle_deadline.map_or_else(never, at);
             let event = select! {
                 recv(&connection.receiver) -> msg => match msg {
                     Ok(msg) => Event::Msg(msg),
@@ -186,7 +195,9 @@ pub fn main_loop(ws_roots: Vec<PathBuf>, config: Config, connection: Connection)
                 recv(world_state.flycheck.as_ref().map_or(&never(), |it| &it.task_recv)) -> task => match task {
                     Ok(task) => Event::CheckWatcher(task),
                     Err(RecvError) => return Err("check watcher died".into()),
-                }
+                },
+                recv(idle) -> _ => Event::Idle,
+                recv(world_state.config.memory_limit.map_or(&never(), |_| &memory_check)) -> _ => Event::MemoryCheck,
             };
             if let Event::Msg(Message::Request(req)) = &event {
                 if connection.handle_shutdown(&req)? {
@@ -215,6 +226,8 @@ pub fn main_loop(ws_roots: Vec<PathBuf>, config: Config, connection: Connection)
     drop(pool);
     log::info!("...threadpool has finished");
 
+    world_state.save_symbol_cache();
+
     let vfs = Arc::try_unwrap(world_state.vfs).expect("all snapshots should be dead");
     drop(vfs);
 
@@ -234,6 +247,8 @@ enum Event {
     Vfs(VfsTask),
     Lib(LibraryData),
     CheckWatcher(CheckTask),
+    Idle,
+    MemoryCheck,
 }
 
 impl fmt::Debug for Event {
@@ -270,6 +285,8 @@ impl fmt::Debug for Event {
             Event::Vfs(it) => fmt::Debug::fmt(it, f),
             Event::Lib(it) => fmt::Debug::fmt(it, f),
             Event::CheckWatcher(it) => fmt::Debug::fmt(it, f),
+            Event::Idle => f.write_str("Idle"),
+            Event::MemoryCheck => f.write_str("MemoryCheck"),
         }
     }
 }
@@ -289,6 +306,12 @@ struct LoopState {
     roots_scanned: usize,
     roots_total: usize,
     configuration_request_id: Option<RequestId>,
+    /// The files edited since diagnostics were last updated, with their
+    /// texts from before the edits.
+    edits: Vec<(FileId, Arc<String>)>,
+    /// Only the diagnostics of the edited items are up to date, the whole
+    /// files are re-validated once the server is idle at this point.
+    idle_deadline: Option<Instant>,
 }
 
 impl LoopState {
@@ -335,6 +358,16 @@ fn loop_turn(
             loop_state.roots_scanned += 1;
         }
         Event::CheckWatcher(task) => on_check_task(task, world_state, task_sender)?,
+        Event::Idle => {
+            loop_state.idle_deadline = None;
+            update_file_notifications_on_threadpool(
+                pool,
+                world_state.snapshot(),
+                task_sender.clone(),
+                loop_state.subscriptions.subscriptions(),
+            )
+        }
+        Event::MemoryCheck => check_memory_usage(&connection.sender, world_state),
         Event::Msg(msg) => match msg {
             Message::Request(req) => on_request(
                 world_state,
@@ -367,6 +400,9 @@ fn loop_turn(
                             if let Some(new_config) = configs.get(0) {
                                 let mut config = world_state.config.clone();
                                 config.update(&new_config);
+                                if world_state.config.memory_limit.is_none() {
+                                    warn_if_memory_limit_unsupported(&config, &connection.sender);
+                                }
                                 world_state.update_configuration(config);
                             }
                         }
@@ -411,11 +447,26 @@ fn loop_turn(
         if let Some(flycheck) = &world_state.flycheck {
             flycheck.update();
         }
-        pool.execute({
-            let subs = loop_state.subscriptions.subscriptions();
-            let snap = world_state.snapshot();
-            move || snap.analysis().prime_caches(subs).unwrap_or_else(|_: Canceled| ())
-        });
+        if !world_state.low_memory {
+            pool.execute({
+                let subs = loop_state.subscriptions.subscriptions();
+                let snap = world_state.snapshot();
+                move || snap.analysis().prime_caches(subs).unwrap_or_else(|_: Canceled| ())
+            });
+            // The cache is saved on shutdown as well, but this way the index
+            // survives sessions which end without one.
+            if let Some(path) = world_state.symbol_cache_path() {
+                let analysis = world_state.analysis_host.analysis();
+                pool.execute(move || world::write_symbol_cache(&path, &analysis));
+            }
+        }
+        if world_state.config.client_caps.pull_diagnostics {
+            // The diagnostics pulled while loading didn't know about all of
+            // the crates yet.
+            let request =
+                request_new::<req::WorkspaceDiagnosticRefresh>(loop_state.next_request_id(), ());
+            connection.sender.send(request.into()).unwrap();
+        }
     }
 
     if show_progress {
@@ -423,12 +474,26 @@ fn loop_turn(
     }
 
     if state_changed {
-        update_file_notifications_on_threadpool(
-            pool,
-            world_state.snapshot(),
-            task_sender.clone(),
-            loop_state.subscriptions.subscriptions(),
-        )
+        let edits = mem::take(&mut loop_state.edits);
+        if edits.is_empty() {
+            loop_state.idle_deadline = None;
+            update_file_notifications_on_threadpool(
+                pool,
+                world_state.snapshot(),
+                task_sender.clone(),
+                loop_state.subscriptions.subscriptions(),
+            )
+        } else {
+            // Typing only affects the edited item in most cases, the rest of
+            // the diagnostics are recomputed once the user stops typing.
+            loop_state.idle_deadline = Some(Instant::now() + IDLE_DIAGNOSTICS_DELAY);
+            update_item_diagnostics_on_threadpool(
+                pool,
+                world_state.snapshot(),
+                task_sender.clone(),
+                edits,
+            )
+        }
     }
 
     let loop_duration = loop_start.elapsed();
@@ -446,6 +511,70 @@ fn loop_turn(
     Ok(())
 }
 
+/// Switches to the low memory mode when the resident memory goes above
+/// `Config::memory_limit`, and back once it is comfortably below the limit.
+fn check_memory_usage(sender: &Sender<Message>, world_state: &mut WorldState) {
+    let usage = memory_usage();
+    let resident = usage.resident;
+    let limit = match world_state.config.memory_limit {
+        Some(it) => Bytes::from_megabytes(it),
+        None => return,
+    };
+
+    if !world_state.low_memory {
+        if resident <= limit {
+            return;
+        }
+        log::warn!("resident memory {} is above the limit of {}", resident, limit);
+        world_state.set_low_memory(true);
+        world_state.collect_garbage();
+        show_message(
+            req::MessageType::Warning,
+            format!(
+                "rust-analyzer uses {} of memory, more than the configured limit of {}. \
+                 Workspace symbol search and auto-import are disabled until the usage goes down.",
+                resident, limit
+            ),
+            sender,
+        );
+        return;
+    }
+
+    // Leave some headroom, so that we don't flip between the modes on every
+    // check. The allocator rarely gives freed memory back to the system, so
+    // the resident memory only tells us that the usage went down if that's
+    // all we know.
+    let back_below_limit = if usage.allocated != Bytes::default() {
+        usage.allocated.megabytes() * 10 <= limit.megabytes() * 9
+    } else {
+        resident <= limit
+    };
+    if !back_below_limit {
+        return;
+    }
+    log::info!("resident memory {} is back below the limit", resident);
+    world_state.set_low_memory(false);
+    show_message(
+        req::MessageType::Info,
+        "rust-analyzer memory usage is back below the limit, all features are enabled again",
+        sender,
+    );
+}
+
+/// The memory usage can't be measured on every platform, in which case the
+/// limit would be silently ignored.
+fn warn_if_memory_limit_unsupported(config: &Config, sender: &Sender<Message>) {
+    if config.memory_limit.is_some() && memory_usage().resident == Bytes::default() {
+        log::warn!("can't measure the memory usage, ignoring the memory limit");
+        show_message(
+            req::MessageType::Warning,
+            "rust-analyzer can't measure its memory usage on this platform, \
+             `rust-analyzer.memoryLimit` has no effect",
+            sender,
+        );
+    }
+}
+
 fn on_task(
     task: Task,
     msg_sender: &Sender<Message>,
@@ -506,13 +635,17 @@ fn on_request(
         .on::<req::GotoTypeDefinition>(handlers::handle_goto_type_definition)?
         .on::<req::ParentModule>(handlers::handle_parent_module)?
         .on::<req::Runnables>(handlers::handle_runnables)?
+        .on::<req::EvaluateFunction>(handlers::handle_evaluate_function)?
         .on::<req::Completion>(handlers::handle_completion)?
         .on::<req::CodeActionRequest>(handlers::handle_code_action)?
+        .on::<req::ResolveCodeAction>(handlers::handle_resolve_code_action)?
         .on::<req::CodeLensRequest>(handlers::handle_code_lens)?
         .on::<req::CodeLensResolve>(handlers::handle_code_lens_resolve)?
         .on::<req::FoldingRangeRequest>(handlers::handle_folding_range)?
         .on::<req::SignatureHelpRequest>(handlers::handle_signature_help)?
         .on::<req::HoverRequest>(handlers::handle_hover)?
+        .on::<req::MonikerRequest>(handlers::handle_moniker)?
+        .on::<req::DocumentDiagnosticRequest>(handlers::handle_document_diagnostic)?
         .on::<req::PrepareRenameRequest>(handlers::handle_prepare_rename)?
         .on::<req::Rename>(handlers::handle_rename)?
         .on::<req::References>(handlers::handle_references)?
@@ -523,8 +656,10 @@ fn on_request(
         .on::<req::CallHierarchyIncomingCalls>(handlers::handle_call_hierarchy_incoming)?
         .on::<req::CallHierarchyOutgoingCalls>(handlers::handle_call_hierarchy_outgoing)?
         .on::<req::SemanticTokensRequest>(handlers::handle_semantic_tokens)?
+        .on::<req::SemanticTokensEditsRequest>(handlers::handle_semantic_tokens_edits)?
         .on::<req::SemanticTokensRangeRequest>(handlers::handle_semantic_tokens_range)?
         .on::<req::Ssr>(handlers::handle_ssr)?
+        .on::<req::RenameConflicts>(handlers::handle_rename_conflicts)?
         .finish();
     Ok(())
 }
@@ -560,7 +695,11 @@ fn on_notification(
             if let Some(file_id) =
                 state.vfs.write().add_file_overlay(&path, params.text_document.text)
             {
-                loop_state.subscriptions.add_sub(FileId(file_id.0));
+                // Manifests are not part of the analysis, there's nothing to
+                // publish for them.
+                if !path.ends_with("Cargo.toml") {
+                    loop_state.subscriptions.add_sub(FileId(file_id.0));
+                }
             }
             return Ok(());
         }
@@ -572,6 +711,10 @@ fn on_notification(
             let path = uri.to_file_path().map_err(|()| format!("invalid uri: {}", uri))?;
             let text =
                 params.content_changes.pop().ok_or_else(|| "empty changes".to_string())?.text;
+            let file_id = state.vfs.read().path2file(&path).map(|it| FileId(it.0));
+            if let Some(file_id) = file_id.filter(|it| !state.manifests.contains_key(it)) {
+                record_edit(state, loop_state, file_id);
+            }
             state.vfs.write().change_file_overlay(path.as_path(), text);
             return Ok(());
         }
@@ -593,6 +736,8 @@ fn on_notification(
             if let Some(file_id) = state.vfs.write().remove_file_overlay(path.as_path()) {
                 loop_state.subscriptions.remove_sub(FileId(file_id.0));
             }
+            state.sent_semantic_tokens.lock().remove(&uri);
+            state.diagnostics_cache.lock().remove(&uri);
             let params =
                 req::PublishDiagnosticsParams { uri, diagnostics: Vec::new(), version: None };
             let not = notification_new::<req::PublishDiagnostics>(params);
@@ -890,7 +1035,7 @@ fn update_file_notifications_on_threadpool(
     subscriptions: Vec<FileId>,
 ) {
     log::trace!("updating notifications for {:?}", subscriptions);
-    if world.config.publish_diagnostics {
+    if publishes_native_diagnostics(&world) {
         pool.execute(move || {
             for file_id in subscriptions {
                 match handlers::publish_diagnostics(&world, file_id) {
@@ -908,6 +1053,58 @@ fn update_file_notifications_on_threadpool(
     }
 }
 
+fn update_item_diagnostics_on_threadpool(
+    pool: &ThreadPool,
+    world: WorldSnapshot,
+    task_sender: Sender<Task>,
+    edits: Vec<(FileId, Arc<String>)>,
+) {
+    log::trace!("updating item diagnostics for {:?}", edits.iter().map(|(it, _)| it));
+    if publishes_native_diagnostics(&world) {
+        pool.execute(move || {
+            for (file_id, old_text) in edits {
+                let new_text = match world.analysis().file_text(file_id) {
+                    Ok(it) => it,
+                    Err(Canceled { .. }) => return,
+                };
+                let edited = EditedLines::between(&old_text, &new_text);
+                let task = DiagnosticTask::ShiftNative(file_id, edited);
+                task_sender.send(Task::Diagnostic(task)).unwrap();
+
+                let position = FilePosition { file_id, offset: edited.offset };
+                match handlers::publish_item_diagnostics(&world, position) {
+                    Err(e) => {
+                        if !is_canceled(&e) {
+                            log::error!("failed to compute diagnostics: {:?}", e);
+                        }
+                    }
+                    Ok(task) => {
+                        task_sender.send(Task::Diagnostic(task)).unwrap();
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Native diagnostics are only pushed to clients which don't pull them.
+fn publishes_native_diagnostics(world: &WorldSnapshot) -> bool {
+    world.config.publish_diagnostics && !world.config.client_caps.pull_diagnostics
+}
+
+/// Remembers the text of the file before it was changed. Where the file was
+/// changed is found on the thread pool by comparing it with the new text.
+fn record_edit(state: &mut WorldState, loop_state: &mut LoopState, file_id: FileId) {
+    // Only the first edit since the last update matters, as the change is
+    // applied to the database before the next one comes in.
+    if loop_state.edits.iter().any(|(it, _)| *it == file_id) {
+        return;
+    }
+    if let Ok(old_text) = state.analysis_host.analysis().file_text(file_id) {
+        loop_state.edits.push((file_id, old_text));
+    }
+}
+
 pub fn show_message(typ: req::MessageType, message: impl Into<String>, sender: &Sender<Message>) {
     let message = message.into();
     let params = req::ShowMessageParams { typ, message };
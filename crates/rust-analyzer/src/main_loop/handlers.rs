@@ -3,8 +3,10 @@
 //! `ra_ide` crate.
 
 use std::{
+    fs,
     io::Write as _,
     process::{self, Stdio},
+    sync::atomic::{AtomicU32, Ordering},
 };
 
 use lsp_server::ErrorCode;
@@ -13,16 +15,18 @@ use lsp_types::{
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
     CodeAction, CodeActionResponse, CodeLens, Command, CompletionItem, Diagnostic,
     DocumentFormattingParams, DocumentHighlight, DocumentSymbol, FoldingRange, FoldingRangeParams,
-    Hover, HoverContents, Location, MarkupContent, MarkupKind, Position, PrepareRenameResponse,
-    Range, RenameParams, SemanticTokensParams, SemanticTokensRangeParams,
-    SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation, TextDocumentIdentifier,
-    TextEdit, WorkspaceEdit,
+    Hover, HoverContents, Location, MarkupContent, MarkupKind, NumberOrString, Position,
+    PrepareRenameResponse, Range, RenameParams, SemanticTokens, SemanticTokensEditResult,
+    SemanticTokensEdits, SemanticTokensEditsParams, SemanticTokensParams,
+    SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
+    TextDocumentIdentifier, TextEdit, WorkspaceEdit,
 };
 use ra_ide::{
-    Assist, AssistId, FileId, FilePosition, FileRange, Query, RangeInfo, Runnable, RunnableKind,
-    SearchScope,
+    AssistId, FileId, FilePosition, FileRange, HighlightedRange, LineIndex, Query, RangeInfo,
+    Runnable, RunnableKind, SearchScope, UnresolvedAssist,
 };
 use ra_prof::profile;
+use ra_project_model::TargetKind;
 use ra_syntax::{AstNode, SyntaxKind, TextRange, TextUnit};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
@@ -30,17 +34,18 @@ use serde_json::to_value;
 use stdx::format_to;
 
 use crate::{
+    cargo_deps,
     cargo_target_spec::CargoTargetSpec,
     config::RustfmtConfig,
     conv::{
         to_call_hierarchy_item, to_location, Conv, ConvWith, FoldConvCtx, MapConvWith, TryConvWith,
         TryConvWithToVec,
     },
-    diagnostics::DiagnosticTask,
+    diagnostics::{DiagnosticTask, EditedLines},
     from_json,
     req::{self, InlayHint, InlayHintsParams},
-    semantic_tokens::SemanticTokensBuilder,
-    world::WorldSnapshot,
+    semantic_tokens::{self, SemanticTokensBuilder, SentSemanticTokens},
+    world::{target_dir, WorldSnapshot},
     LspError, Result,
 };
 
@@ -256,6 +261,11 @@ pub fn handle_workspace_symbol(
     params: req::WorkspaceSymbolParams,
 ) -> Result<Option<Vec<SymbolInformation>>> {
     let _p = profile("handle_workspace_symbol");
+    if world.low_memory {
+        // Searching the whole workspace would bring back all of the syntax
+        // trees that were just evicted.
+        return Ok(None);
+    }
     let all_symbols = params.query.contains('#');
     let libs = params.query.contains('*');
     let query = {
@@ -389,6 +399,72 @@ pub fn handle_runnables(
     Ok(res)
 }
 
+pub fn handle_evaluate_function(
+    world: WorldSnapshot,
+    params: req::EvaluateFunctionParams,
+) -> Result<req::Runnable> {
+    let _p = profile("handle_evaluate_function");
+    let file_id = params.text_document.try_conv_with(&world)?;
+    let line_index = world.analysis().file_line_index(file_id)?;
+    let offset = params.position.conv_with(&line_index);
+    let cannot_evaluate = |message| LspError::new(ErrorCode::InvalidRequest as i32, message);
+    let harness = world
+        .analysis()
+        .function_harness(FilePosition { file_id, offset }, &params.args)?
+        .map_err(cannot_evaluate)?;
+    let spec = match CargoTargetSpec::for_file(&world, file_id)? {
+        Some(it) if it.target_kind == TargetKind::Lib => it,
+        _ => {
+            let message = format!(
+                "`{}` can't be evaluated: only functions of library crates can be called from \
+                 a scratch crate",
+                harness.name
+            );
+            return Err(cannot_evaluate(message).into());
+        }
+    };
+    let package_dir = spec.manifest_path.parent().unwrap_or(spec.manifest_path.as_path());
+    let workspace_root = world.workspace_root_for(file_id).unwrap_or(package_dir);
+
+    // The harness is a scratch package which depends on the package of the
+    // function, so that we never have to touch the user's sources. Every
+    // evaluation gets its own package, with its own binary, so that
+    // evaluations running at the same time don't overwrite each other.
+    static EVALUATION_ID: AtomicU32 = AtomicU32::new(0);
+    let scratch_name =
+        format!("ra-evaluate-{}-{}", process::id(), EVALUATION_ID.fetch_add(1, Ordering::SeqCst));
+    let target_dir = target_dir(workspace_root);
+    let scratch_dir = target_dir.join("rust-analyzer/evaluate").join(&scratch_name);
+    let manifest = format!(
+        "[package]\nname = {:?}\nversion = \"0.0.0\"\nedition = \"2018\"\n\
+         publish = false\n\n[dependencies]\n{} = {{ path = {:?} }}\n\n[workspace]\n",
+        scratch_name,
+        spec.package_name,
+        package_dir.to_string_lossy(),
+    );
+    fs::create_dir_all(scratch_dir.join("src"))?;
+    fs::write(scratch_dir.join("Cargo.toml"), manifest)?;
+    fs::write(scratch_dir.join("src/main.rs"), &harness.main)?;
+
+    let mut env = FxHashMap::default();
+    // Reuse the build of the workspace instead of compiling everything again.
+    env.insert("CARGO_TARGET_DIR".to_string(), target_dir.to_string_lossy().to_string());
+    Ok(req::Runnable {
+        range: Default::default(),
+        label: format!("evaluate {}({})", harness.name, params.args.trim()),
+        bin: "cargo".to_string(),
+        args: vec![
+            "run".to_string(),
+            "--quiet".to_string(),
+            "--manifest-path".to_string(),
+            scratch_dir.join("Cargo.toml").to_string_lossy().to_string(),
+        ],
+        extra_args: Vec::new(),
+        env,
+        cwd: Some(workspace_root.to_string_lossy().to_string()),
+    })
+}
+
 pub fn handle_completion(
     world: WorldSnapshot,
     params: req::CompletionParams,
@@ -493,6 +569,32 @@ pub fn handle_hover(
     Ok(Some(res))
 }
 
+pub fn handle_moniker(
+    world: WorldSnapshot,
+    params: req::TextDocumentPositionParams,
+) -> Result<Option<Vec<req::Moniker>>> {
+    let _p = profile("handle_moniker");
+    let position = params.try_conv_with(&world)?;
+    let info = match world.analysis().moniker(position)? {
+        None => return Ok(None),
+        Some(info) => info,
+    };
+    let res = info
+        .info
+        .into_iter()
+        .map(|it| req::Moniker {
+            scheme: "rust-analyzer".to_string(),
+            identifier: it.identifier.to_string(),
+            unique: req::UniquenessLevel::Scheme,
+            kind: Some(match it.kind {
+                ra_ide::MonikerKind::Import => req::MonikerKind::Import,
+                ra_ide::MonikerKind::Export => req::MonikerKind::Export,
+            }),
+        })
+        .collect();
+    Ok(Some(res))
+}
+
 pub fn handle_prepare_rename(
     world: WorldSnapshot,
     params: req::TextDocumentPositionParams,
@@ -535,6 +637,25 @@ pub fn handle_rename(world: WorldSnapshot, params: RenameParams) -> Result<Optio
     Ok(Some(source_change_req.workspace_edit))
 }
 
+pub fn handle_rename_conflicts(
+    world: WorldSnapshot,
+    params: RenameParams,
+) -> Result<Vec<req::RenameConflict>> {
+    let _p = profile("handle_rename_conflicts");
+    let position = params.text_document_position.try_conv_with(&world)?;
+    world
+        .analysis()
+        .rename_conflicts(position, &*params.new_name)?
+        .into_iter()
+        .map(|conflict| {
+            let file_id = conflict.file_range.file_id;
+            let line_index = world.analysis().file_line_index(file_id)?;
+            let location = to_location(file_id, conflict.file_range.range, &world, &line_index)?;
+            Ok(req::RenameConflict { location, message: conflict.message })
+        })
+        .collect()
+}
+
 pub fn handle_references(
     world: WorldSnapshot,
     params: req::ReferenceParams,
@@ -658,12 +779,14 @@ pub fn handle_formatting(
     }]))
 }
 
-fn create_single_code_action(assist: Assist, world: &WorldSnapshot) -> Result<CodeAction> {
-    let arg = to_value(assist.source_change.try_conv_with(world)?)?;
-    let title = assist.label;
+fn create_single_code_action(
+    assist: UnresolvedAssist,
+    command: &str,
+    arg: serde_json::Value,
+) -> Result<CodeAction> {
     let command = Command {
-        title: title.clone(),
-        command: "rust-analyzer.applySourceChange".to_string(),
+        title: assist.label.clone(),
+        command: command.to_string(),
         arguments: Some(vec![arg]),
     };
 
@@ -674,7 +797,7 @@ fn create_single_code_action(assist: Assist, world: &WorldSnapshot) -> Result<Co
     };
 
     Ok(CodeAction {
-        title,
+        title: assist.label,
         kind,
         diagnostics: None,
         edit: None,
@@ -729,8 +852,67 @@ pub fn handle_code_action(
         res.push(fix.action.clone());
     }
 
-    let mut grouped_assists: FxHashMap<String, (usize, Vec<Assist>)> = FxHashMap::default();
-    for assist in world.analysis().assists(FileRange { file_id, range })?.into_iter() {
+    for (crate_range, crate_name) in world.analysis().unresolved_crates(file_id)? {
+        if crate_range.intersection(&range).is_none() {
+            continue;
+        }
+        let manifest = match cargo_deps::manifest_for_file(&world, file_id)? {
+            Some(it) => it,
+            None => break,
+        };
+        let manifest_text = match world.manifest_text(&manifest) {
+            Some(it) => it,
+            None => break,
+        };
+        if let Some(version) = cargo_deps::find_cached_version(&world, &crate_name) {
+            let action = cargo_deps::add_dependency_action(
+                &manifest,
+                &manifest_text,
+                &crate_name,
+                &version,
+            )?;
+            res.push(action.into());
+        }
+    }
+
+    // Clients which support it get only the labels of the assists, and the
+    // edit of the picked one is computed by `handle_resolve_code_action`.
+    // Other clients get the edits upfront.
+    let frange = FileRange { file_id, range };
+    let resolve_lazily = world.config.client_caps.resolve_code_action;
+    let mut assists = Vec::new();
+    if resolve_lazily {
+        for assist in world.analysis().unresolved_assists(frange, &world.config.assist)? {
+            let arg = to_value(req::ResolveCodeActionParams {
+                text_document: params.text_document.clone(),
+                range: params.range,
+                id: assist.id.0.to_string(),
+                label: assist.label.clone(),
+            })?;
+            assists.push((assist, arg));
+        }
+    } else {
+        for assist in world.analysis().assists(frange, &world.config.assist)? {
+            let arg = to_value(assist.source_change.try_conv_with(&world)?)?;
+            let assist = UnresolvedAssist {
+                id: assist.id,
+                label: assist.label,
+                group_label: assist.group_label,
+            };
+            assists.push((assist, arg));
+        }
+    }
+    let (apply_command, select_command) = if resolve_lazily {
+        ("rust-analyzer.resolveAndApplyCodeAction", "rust-analyzer.selectAndResolveCodeAction")
+    } else {
+        ("rust-analyzer.applySourceChange", "rust-analyzer.selectAndApplySourceChange")
+    };
+
+    let mut grouped_assists: FxHashMap<
+        String,
+        (usize, Vec<(UnresolvedAssist, serde_json::Value)>),
+    > = FxHashMap::default();
+    for (assist, arg) in assists {
         match &assist.group_label {
             Some(label) => grouped_assists
                 .entry(label.to_owned())
@@ -741,28 +923,23 @@ pub fn handle_code_action(
                     (idx, Vec::new())
                 })
                 .1
-                .push(assist),
+                .push((assist, arg)),
             None => {
-                res.push(create_single_code_action(assist, &world)?.into());
+                res.push(create_single_code_action(assist, apply_command, arg)?.into());
             }
         }
     }
 
     for (group_label, (idx, assists)) in grouped_assists {
         if assists.len() == 1 {
-            res[idx] =
-                create_single_code_action(assists.into_iter().next().unwrap(), &world)?.into();
+            let (assist, arg) = assists.into_iter().next().unwrap();
+            res[idx] = create_single_code_action(assist, apply_command, arg)?.into();
         } else {
             let title = group_label;
-
-            let mut arguments = Vec::with_capacity(assists.len());
-            for assist in assists {
-                arguments.push(to_value(assist.source_change.try_conv_with(&world)?)?);
-            }
-
+            let arguments = assists.into_iter().map(|(_, arg)| arg).collect();
             let command = Some(Command {
                 title: title.clone(),
-                command: "rust-analyzer.selectAndApplySourceChange".to_string(),
+                command: select_command.to_string(),
                 arguments: Some(vec![serde_json::Value::Array(arguments)]),
             });
             res[idx] = CodeAction {
@@ -780,6 +957,23 @@ pub fn handle_code_action(
     Ok(Some(res))
 }
 
+pub fn handle_resolve_code_action(
+    world: WorldSnapshot,
+    params: req::ResolveCodeActionParams,
+) -> Result<Option<req::SourceChange>> {
+    let _p = profile("handle_resolve_code_action");
+    let file_id = params.text_document.try_conv_with(&world)?;
+    let line_index = world.analysis().file_line_index(file_id)?;
+    let range = params.range.conv_with(&line_index);
+    let assist = world.analysis().resolve_assist(
+        FileRange { file_id, range },
+        &world.config.assist,
+        &params.id,
+        &params.label,
+    )?;
+    assist.map(|it| it.source_change.try_conv_with(&world)).transpose()
+}
+
 pub fn handle_code_lens(
     world: WorldSnapshot,
     params: req::CodeLensParams,
@@ -941,13 +1135,115 @@ pub fn handle_ssr(world: WorldSnapshot, params: req::SsrParams) -> Result<req::S
 
 pub fn publish_diagnostics(world: &WorldSnapshot, file_id: FileId) -> Result<DiagnosticTask> {
     let _p = profile("publish_diagnostics");
+    let diagnostics = native_diagnostics(world, file_id)?;
+    Ok(DiagnosticTask::SetNative(file_id, diagnostics))
+}
+
+pub fn handle_document_diagnostic(
+    world: WorldSnapshot,
+    params: req::DocumentDiagnosticParams,
+) -> Result<req::DocumentDiagnosticReport> {
+    let _p = profile("handle_document_diagnostic");
+    let file_id = params.text_document.try_conv_with(&world)?;
+    let uri = params.text_document.uri;
+    let is_previous = |result_id: &String| params.previous_result_id.as_ref() == Some(result_id);
+
+    // Nothing changed since the client pulled the diagnostics last time.
+    if let Some((revision, result_id, _)) = world.diagnostics_cache.lock().get(&uri) {
+        if *revision == world.revision && is_previous(result_id) {
+            return Ok(req::DocumentDiagnosticReport::Unchanged { result_id: result_id.clone() });
+        }
+    }
+
+    let diagnostics = if world.config.publish_diagnostics {
+        native_diagnostics(&world, file_id)?
+    } else {
+        vec![]
+    };
+
+    // Something changed, but maybe not the diagnostics of this file.
+    let mut cache = world.diagnostics_cache.lock();
+    let unchanged = match cache.get(&uri) {
+        Some((_, result_id, cached)) if is_previous(result_id) && *cached == diagnostics => {
+            Some(result_id.clone())
+        }
+        _ => None,
+    };
+    let result_id = unchanged.clone().unwrap_or_else(semantic_tokens::next_result_id);
+    cache.insert(uri, (world.revision, result_id.clone(), diagnostics.clone()));
+    match unchanged {
+        Some(result_id) => Ok(req::DocumentDiagnosticReport::Unchanged { result_id }),
+        None => Ok(req::DocumentDiagnosticReport::Full {
+            result_id: Some(result_id),
+            items: diagnostics,
+        }),
+    }
+}
+
+fn native_diagnostics(world: &WorldSnapshot, file_id: FileId) -> Result<Vec<Diagnostic>> {
     let line_index = world.analysis().file_line_index(file_id)?;
-    let diagnostics: Vec<Diagnostic> = world
-        .analysis()
-        .diagnostics(file_id)?
+    let mut diagnostics = to_lsp_diagnostics(world.analysis().diagnostics(file_id)?, &line_index);
+    diagnostics.extend(spelling_diagnostics(world, file_id, None, &line_index)?);
+    Ok(diagnostics)
+}
+
+/// Recomputes only the diagnostics of the item which was edited, falling back
+/// to the whole file if the edit is not inside of an item.
+pub fn publish_item_diagnostics(
+    world: &WorldSnapshot,
+    position: FilePosition,
+) -> Result<DiagnosticTask> {
+    let _p = profile("publish_item_diagnostics");
+    let item = match world.analysis().item_diagnostics(position)? {
+        Some(it) => it,
+        None => return publish_diagnostics(world, position.file_id),
+    };
+    let line_index = world.analysis().file_line_index(position.file_id)?;
+    let mut diagnostics = to_lsp_diagnostics(item.info, &line_index);
+    diagnostics.extend(spelling_diagnostics(
+        world,
+        position.file_id,
+        Some(item.range),
+        &line_index,
+    )?);
+    Ok(DiagnosticTask::SetNativeInRange(
+        position.file_id,
+        item.range.conv_with(&line_index),
+        diagnostics,
+    ))
+}
+
+/// Hints for misspelled words, if they are enabled. They get a code of their
+/// own, so that clients can tell them apart from the other diagnostics.
+fn spelling_diagnostics(
+    world: &WorldSnapshot,
+    file_id: FileId,
+    range: Option<TextRange>,
+    line_index: &LineIndex,
+) -> Result<Vec<Diagnostic>> {
+    let config = match &world.config.spell_check {
+        Some(it) => it,
+        None => return Ok(Vec::new()),
+    };
+    let mut hints = world.analysis().spelling_diagnostics(file_id, config)?;
+    if let Some(range) = range {
+        hints.retain(|it| it.range.is_subrange(&range));
+    }
+    let mut res = to_lsp_diagnostics(hints, line_index);
+    for it in res.iter_mut() {
+        it.code = Some(NumberOrString::String("spelling".to_string()));
+    }
+    Ok(res)
+}
+
+fn to_lsp_diagnostics(
+    diagnostics: Vec<ra_ide::Diagnostic>,
+    line_index: &LineIndex,
+) -> Vec<Diagnostic> {
+    diagnostics
         .into_iter()
         .map(|d| Diagnostic {
-            range: d.range.conv_with(&line_index),
+            range: d.range.conv_with(line_index),
             severity: Some(d.severity.conv()),
             code: None,
             source: Some("rust-analyzer".to_string()),
@@ -955,8 +1251,7 @@ pub fn publish_diagnostics(world: &WorldSnapshot, file_id: FileId) -> Result<Dia
             related_information: None,
             tags: None,
         })
-        .collect();
-    Ok(DiagnosticTask::SetNative(file_id, diagnostics))
+        .collect()
 }
 
 fn to_lsp_runnable(
@@ -1098,12 +1393,110 @@ pub fn handle_semantic_tokens(
     let _p = profile("handle_semantic_tokens");
 
     let file_id = params.text_document.try_conv_with(&world)?;
+    let text = world.analysis().file_text(file_id)?;
+    let highlights = world.analysis().highlight(file_id)?;
+    let tokens = semantic_tokens_for_highlights(&world, file_id, &highlights)?;
+
+    // Remember the tokens so that the next response can be delta encoded.
+    let sent = SentSemanticTokens { text, highlights, tokens: tokens.clone() };
+    world.sent_semantic_tokens.lock().insert(params.text_document.uri, sent);
+
+    Ok(Some(tokens.into()))
+}
+
+pub fn handle_semantic_tokens_edits(
+    world: WorldSnapshot,
+    params: SemanticTokensEditsParams,
+) -> Result<Option<SemanticTokensEditResult>> {
+    let _p = profile("handle_semantic_tokens_edits");
+
+    let file_id = params.text_document.try_conv_with(&world)?;
+    let text = world.analysis().file_text(file_id)?;
+
+    let cached = world.sent_semantic_tokens.lock().remove(&params.text_document.uri);
+    let cached =
+        cached.filter(|it| it.tokens.result_id.as_ref() == Some(&params.previous_result_id));
+    let highlights = match &cached {
+        // Only the items touched by the edit are highlighted again. If the
+        // document itself didn't change, a change to another file may have
+        // affected any part of it, so everything is highlighted again.
+        Some(cached) if cached.text != text => {
+            rehighlight_edited_items(&world, file_id, &cached.text, &text, &cached.highlights)?
+        }
+        _ => world.analysis().highlight(file_id)?,
+    };
+    let tokens = semantic_tokens_for_highlights(&world, file_id, &highlights)?;
+
+    let sent = SentSemanticTokens { text, highlights, tokens: tokens.clone() };
+    world.sent_semantic_tokens.lock().insert(params.text_document.uri, sent);
+
+    match cached {
+        Some(cached) => {
+            let edits = semantic_tokens::diff_tokens(&cached.tokens.data, &tokens.data);
+            Ok(Some(SemanticTokensEdits { result_id: tokens.result_id, edits }.into()))
+        }
+        // The client refers to tokens we no longer have, send all of them.
+        None => Ok(Some(tokens.into())),
+    }
+}
+
+/// Highlights the top-level items touched by the edit turning `old_text` into
+/// the current text of the file, and moves the highlights of the items around
+/// them along with the text.
+fn rehighlight_edited_items(
+    world: &WorldSnapshot,
+    file_id: FileId,
+    old_text: &str,
+    new_text: &str,
+    old_highlights: &[HighlightedRange],
+) -> Result<Vec<HighlightedRange>> {
+    let edited = EditedLines::between(old_text, new_text);
+    let edited_range = TextRange::from_to(edited.offset, edited.new_end);
+
+    let file = world.analysis().parse(file_id)?;
+    let mut items = file
+        .syntax()
+        .children_with_tokens()
+        .map(|it| it.text_range())
+        .filter(|it| it.start() <= edited_range.end() && edited_range.start() <= it.end());
+    let region = match items.next() {
+        Some(first) => {
+            let last = items.last().unwrap_or(first);
+            TextRange::from_to(first.start(), last.end())
+        }
+        None => TextRange::offset_len(0.into(), TextUnit::of_str(new_text)),
+    };
+    // Where the region ends in the old text, everything after it is unchanged.
+    let old_region_end = region.end() - edited.new_end + edited.old_end;
+
+    let mut res: Vec<HighlightedRange> =
+        old_highlights.iter().filter(|it| it.range.end() <= region.start()).cloned().collect();
+    let frange = FileRange { file_id, range: region };
+    res.extend(
+        world
+            .analysis()
+            .highlight_range(frange)?
+            .into_iter()
+            .filter(|it| region.contains(it.range.start())),
+    );
+    res.extend(old_highlights.iter().filter(|it| it.range.start() >= old_region_end).map(|it| {
+        let start = it.range.start() + edited.new_end - edited.old_end;
+        HighlightedRange { range: TextRange::offset_len(start, it.range.len()), ..it.clone() }
+    }));
+    Ok(res)
+}
+
+fn semantic_tokens_for_highlights(
+    world: &WorldSnapshot,
+    file_id: FileId,
+    highlights: &[HighlightedRange],
+) -> Result<SemanticTokens> {
     let text = world.analysis().file_text(file_id)?;
     let line_index = world.analysis().file_line_index(file_id)?;
 
     let mut builder = SemanticTokensBuilder::default();
 
-    for highlight_range in world.analysis().highlight(file_id)?.into_iter() {
+    for highlight_range in highlights {
         let (token_index, modifier_bitset) = highlight_range.highlight.conv();
         for mut range in line_index.lines(highlight_range.range) {
             if text[range].ends_with('\n') {
@@ -1114,9 +1507,7 @@ pub fn handle_semantic_tokens(
         }
     }
 
-    let tokens = builder.build();
-
-    Ok(Some(tokens.into()))
+    Ok(builder.build())
 }
 
 pub fn handle_semantic_tokens_range(
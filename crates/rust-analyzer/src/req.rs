@@ -1,6 +1,6 @@
 //! Defines `rust-analyzer` specific custom messages.
 
-use lsp_types::{Location, Position, Range, TextDocumentIdentifier};
+use lsp_types::{Diagnostic, Location, Position, Range, RenameParams, TextDocumentIdentifier};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
@@ -12,10 +12,11 @@ pub use lsp_types::{
     DocumentSymbolResponse, FileSystemWatcher, Hover, InitializeResult, MessageType,
     PartialResultParams, ProgressParams, ProgressParamsValue, ProgressToken,
     PublishDiagnosticsParams, ReferenceParams, Registration, RegistrationParams, SelectionRange,
-    SelectionRangeParams, SemanticTokensParams, SemanticTokensRangeParams,
-    SemanticTokensRangeResult, SemanticTokensResult, ServerCapabilities, ShowMessageParams,
-    SignatureHelp, SymbolKind, TextDocumentEdit, TextDocumentPositionParams, TextEdit,
-    WorkDoneProgressParams, WorkspaceEdit, WorkspaceSymbolParams,
+    SelectionRangeParams, SemanticTokensEditResult, SemanticTokensEditsParams,
+    SemanticTokensParams, SemanticTokensRangeParams, SemanticTokensRangeResult,
+    SemanticTokensResult, ServerCapabilities, ShowMessageParams, SignatureHelp, SymbolKind,
+    TextDocumentEdit, TextDocumentPositionParams, TextEdit, WorkDoneProgressParams, WorkspaceEdit,
+    WorkspaceSymbolParams,
 };
 
 pub enum AnalyzerStatus {}
@@ -144,6 +145,25 @@ pub struct Runnable {
     pub cwd: Option<String>,
 }
 
+pub enum EvaluateFunction {}
+
+impl Request for EvaluateFunction {
+    type Params = EvaluateFunctionParams;
+    /// A runnable which prints the result, to be executed by the client. If
+    /// the function can't be evaluated, the error message says why.
+    type Result = Runnable;
+    const METHOD: &'static str = "rust-analyzer/evaluateFunction";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateFunctionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Position,
+    /// The arguments of the call, separated by commas.
+    pub args: String,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SourceChange {
@@ -152,6 +172,25 @@ pub struct SourceChange {
     pub cursor_position: Option<TextDocumentPositionParams>,
 }
 
+pub enum ResolveCodeAction {}
+
+impl Request for ResolveCodeAction {
+    type Params = ResolveCodeActionParams;
+    type Result = Option<SourceChange>;
+    const METHOD: &'static str = "rust-analyzer/resolveCodeAction";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveCodeActionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    pub id: String,
+    /// The assists of a group can share the id, so they are told apart by
+    /// the label.
+    pub label: String,
+}
+
 pub enum InlayHints {}
 
 impl Request for InlayHints {
@@ -194,3 +233,92 @@ pub struct SsrParams {
     pub query: String,
     pub parse_only: bool,
 }
+
+pub enum RenameConflicts {}
+
+impl Request for RenameConflicts {
+    type Params = RenameParams;
+    type Result = Vec<RenameConflict>;
+    const METHOD: &'static str = "rust-analyzer/renameConflicts";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameConflict {
+    pub location: Location,
+    pub message: String,
+}
+
+/// `textDocument/moniker`, which is not yet part of `lsp_types`.
+pub enum MonikerRequest {}
+
+impl Request for MonikerRequest {
+    type Params = TextDocumentPositionParams;
+    type Result = Option<Vec<Moniker>>;
+    const METHOD: &'static str = "textDocument/moniker";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Moniker {
+    pub scheme: String,
+    pub identifier: String,
+    pub unique: UniquenessLevel,
+    pub kind: Option<MonikerKind>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UniquenessLevel {
+    Document,
+    Project,
+    Group,
+    Scheme,
+    Global,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MonikerKind {
+    Import,
+    Export,
+    Local,
+}
+
+/// `textDocument/diagnostic`, which is not yet part of `lsp_types`.
+pub enum DocumentDiagnosticRequest {}
+
+impl Request for DocumentDiagnosticRequest {
+    type Params = DocumentDiagnosticParams;
+    type Result = DocumentDiagnosticReport;
+    const METHOD: &'static str = "textDocument/diagnostic";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentDiagnosticParams {
+    pub text_document: TextDocumentIdentifier,
+    pub identifier: Option<String>,
+    /// The result id of the diagnostics the client already has.
+    pub previous_result_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DocumentDiagnosticReport {
+    #[serde(rename_all = "camelCase")]
+    Full { result_id: Option<String>, items: Vec<Diagnostic> },
+    /// The diagnostics with `result_id` are still up to date.
+    #[serde(rename_all = "camelCase")]
+    Unchanged { result_id: String },
+}
+
+/// `workspace/diagnostic/refresh`, asks the client to pull the diagnostics of
+/// all open documents again.
+pub enum WorkspaceDiagnosticRefresh {}
+
+impl Request for WorkspaceDiagnosticRefresh {
+    type Params = ();
+    type Result = ();
+    const METHOD: &'static str = "workspace/diagnostic/refresh";
+}
@@ -1,8 +1,18 @@
 //! Semantic Tokens helpers
 
-use std::ops;
-
-use lsp_types::{Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens};
+use std::{
+    ops,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use lsp_types::{
+    Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+    SemanticTokensEdit,
+};
+use ra_ide::HighlightedRange;
 
 pub(crate) const ATTRIBUTE: SemanticTokenType = SemanticTokenType::new("attribute");
 pub(crate) const BUILTIN_TYPE: SemanticTokenType = SemanticTokenType::new("builtinType");
@@ -110,10 +120,90 @@ impl SemanticTokensBuilder {
     }
 
     pub fn build(self) -> SemanticTokens {
-        SemanticTokens { result_id: None, data: self.data }
+        SemanticTokens { result_id: Some(next_result_id()), data: self.data }
+    }
+}
+
+/// The semantic tokens last sent for a document, along with the text and the
+/// highlights they were computed from, so that the next delta request only has
+/// to highlight the edited part of the file again.
+pub struct SentSemanticTokens {
+    pub text: Arc<String>,
+    pub highlights: Vec<HighlightedRange>,
+    pub tokens: SemanticTokens,
+}
+
+/// A fresh id for a result sent to the client, like semantic tokens or
+/// pulled diagnostics.
+pub(crate) fn next_result_id() -> String {
+    static RESULT_ID: AtomicU32 = AtomicU32::new(0);
+    RESULT_ID.fetch_add(1, Ordering::SeqCst).to_string()
+}
+
+/// Computes the edits turning `old` into `new`.
+///
+/// Edits usually touch a single place of the file, so we only strip the
+/// common prefix and suffix and replace whatever remains in between.
+pub(crate) fn diff_tokens(old: &[SemanticToken], new: &[SemanticToken]) -> Vec<SemanticTokensEdit> {
+    let prefix = old.iter().zip(new).take_while(|(old, new)| old == new).count();
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = old.iter().rev().zip(new.iter().rev()).take_while(|(old, new)| old == new).count();
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+
+    if old.is_empty() && new.is_empty() {
+        return Vec::new();
     }
+    // The edits apply to the flattened token array, where each token is five integers.
+    vec![SemanticTokensEdit {
+        start: 5 * prefix as u32,
+        delete_count: 5 * old.len() as u32,
+        data: Some(new.to_vec()),
+    }]
 }
 
 pub fn type_index(type_: SemanticTokenType) -> u32 {
     SUPPORTED_TYPES.iter().position(|it| *it == type_).unwrap() as u32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(delta_line: u32, delta_start: u32) -> SemanticToken {
+        SemanticToken {
+            delta_line,
+            delta_start,
+            length: 1,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_diff_insert_in_the_middle() {
+        let old = [token(0, 1), token(1, 2), token(0, 3)];
+        let new = [token(0, 1), token(1, 2), token(2, 1), token(0, 3)];
+        let edits = diff_tokens(&old, &new);
+        assert_eq!(
+            edits,
+            vec![SemanticTokensEdit { start: 10, delete_count: 0, data: Some(vec![token(2, 1)]) }]
+        );
+    }
+
+    #[test]
+    fn test_diff_replace_and_remove() {
+        let old = [token(0, 1), token(1, 2), token(3, 4), token(0, 3)];
+        let new = [token(0, 1), token(5, 5), token(0, 3)];
+        let edits = diff_tokens(&old, &new);
+        assert_eq!(
+            edits,
+            vec![SemanticTokensEdit { start: 5, delete_count: 10, data: Some(vec![token(5, 5)]) }]
+        );
+    }
+
+    #[test]
+    fn test_diff_unchanged() {
+        let tokens = [token(0, 1), token(1, 2)];
+        assert!(diff_tokens(&tokens, &tokens).is_empty());
+    }
+}
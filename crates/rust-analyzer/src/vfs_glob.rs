@@ -1,12 +1,14 @@
 //! Exclusion rules for vfs.
 //!
 //! By default, we include only `.rs` files, and skip some know offenders like
-//! `/target` or `/node_modules` altogether.
+//! `/target` or `/node_modules` altogether. The `Cargo.toml` of members can be
+//! included as well, so that their text is at hand.
 //!
-//! It's also possible to add custom exclusion globs.
+//! It's also possible to add custom exclusion globs and directories.
 
 use globset::{GlobSet, GlobSetBuilder};
 use ra_vfs::{Filter, RelativePath};
+use relative_path::RelativePathBuf;
 
 pub use globset::{Glob, GlobBuilder};
 
@@ -15,12 +17,19 @@ const IGNORED_FOR_NON_MEMBERS: &[&str] = &["examples/**", "tests/**", "benches/*
 
 pub struct RustPackageFilterBuilder {
     is_member: bool,
+    include_manifest: bool,
     exclude: GlobSetBuilder,
+    exclude_dirs: Vec<RelativePathBuf>,
 }
 
 impl Default for RustPackageFilterBuilder {
     fn default() -> RustPackageFilterBuilder {
-        RustPackageFilterBuilder { is_member: false, exclude: GlobSetBuilder::new() }
+        RustPackageFilterBuilder {
+            is_member: false,
+            include_manifest: false,
+            exclude: GlobSetBuilder::new(),
+            exclude_dirs: Vec::new(),
+        }
     }
 }
 
@@ -30,6 +39,12 @@ impl RustPackageFilterBuilder {
         self
     }
 
+    /// Includes the `Cargo.toml` at the root of member packages.
+    pub fn include_manifest(mut self, include_manifest: bool) -> RustPackageFilterBuilder {
+        self.include_manifest = include_manifest;
+        self
+    }
+
     pub fn exclude(mut self, globs: impl IntoIterator<Item = Glob>) -> RustPackageFilterBuilder {
         for glob in globs.into_iter() {
             self.exclude.add(glob);
@@ -37,8 +52,19 @@ impl RustPackageFilterBuilder {
         self
     }
 
+    /// Excludes the given directories, relative to the package root. Unlike
+    /// globs, these are matched literally.
+    pub fn exclude_dirs(
+        mut self,
+        dirs: impl IntoIterator<Item = RelativePathBuf>,
+    ) -> RustPackageFilterBuilder {
+        self.exclude_dirs.extend(dirs);
+        self
+    }
+
     pub fn into_vfs_filter(self) -> Box<dyn Filter> {
-        let RustPackageFilterBuilder { is_member, mut exclude } = self;
+        let RustPackageFilterBuilder { is_member, include_manifest, mut exclude, exclude_dirs } =
+            self;
         for &glob in ALWAYS_IGNORED {
             exclude.add(Glob::new(glob).unwrap());
         }
@@ -47,21 +73,34 @@ impl RustPackageFilterBuilder {
                 exclude.add(Glob::new(glob).unwrap());
             }
         }
-        Box::new(RustPackageFilter { exclude: exclude.build().unwrap() })
+        let include_manifest = include_manifest && is_member;
+        Box::new(RustPackageFilter {
+            exclude: exclude.build().unwrap(),
+            exclude_dirs,
+            include_manifest,
+        })
     }
 }
 
 struct RustPackageFilter {
     exclude: GlobSet,
+    exclude_dirs: Vec<RelativePathBuf>,
+    include_manifest: bool,
+}
+
+/// Whether the file is the manifest at the root of a package.
+pub(crate) fn is_manifest(file_path: &RelativePath) -> bool {
+    file_path.as_str() == "Cargo.toml"
 }
 
 impl Filter for RustPackageFilter {
     fn include_dir(&self, dir_path: &RelativePath) -> bool {
         !self.exclude.is_match(dir_path.as_str())
+            && !self.exclude_dirs.iter().any(|it| it.as_relative_path() == dir_path)
     }
 
     fn include_file(&self, file_path: &RelativePath) -> bool {
-        file_path.extension() == Some("rs")
+        file_path.extension() == Some("rs") || (self.include_manifest && is_manifest(file_path))
     }
 }
 
@@ -95,4 +134,30 @@ fn test_globs() {
         .into_vfs_filter();
 
     assert!(!filter.include_dir(RelativePath::new("src/llvm-project/clang")));
+
+    let filter = RustPackageFilterBuilder::default()
+        .set_member(true)
+        .exclude_dirs(std::iter::once(RelativePathBuf::from("generated/[huge]")))
+        .into_vfs_filter();
+
+    assert!(filter.include_dir(RelativePath::new("generated")));
+    assert!(filter.include_dir(RelativePath::new("generated/h")));
+    assert!(!filter.include_dir(RelativePath::new("generated/[huge]")));
+
+    let filter = RustPackageFilterBuilder::default().set_member(true).into_vfs_filter();
+    assert!(filter.include_file(RelativePath::new("src/lib.rs")));
+    assert!(!filter.include_file(RelativePath::new("Cargo.toml")));
+
+    let filter = RustPackageFilterBuilder::default()
+        .set_member(true)
+        .include_manifest(true)
+        .into_vfs_filter();
+    assert!(filter.include_file(RelativePath::new("Cargo.toml")));
+    assert!(!filter.include_file(RelativePath::new("sub/Cargo.toml")));
+
+    let filter = RustPackageFilterBuilder::default()
+        .set_member(false)
+        .include_manifest(true)
+        .into_vfs_filter();
+    assert!(!filter.include_file(RelativePath::new("Cargo.toml")));
 }
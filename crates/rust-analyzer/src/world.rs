@@ -4,16 +4,18 @@
 //! Each tick provides an immutable snapshot of the state as `WorldSnapshot`.
 
 use std::{
+    env, fs,
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use crossbeam_channel::{unbounded, Receiver};
-use lsp_types::Url;
-use parking_lot::RwLock;
+use lsp_types::{Diagnostic, Url};
+use parking_lot::{Mutex, RwLock};
 use ra_flycheck::{url_from_path_with_drive_lowercasing, Flycheck, FlycheckConfig};
 use ra_ide::{
     Analysis, AnalysisChange, AnalysisHost, CrateGraph, FileId, LibraryData, SourceRootId,
+    SymbolCache,
 };
 use ra_project_model::{get_rustc_cfg_options, ProcMacroClient, ProjectWorkspace};
 use ra_vfs::{LineEndings, RootEntry, Vfs, VfsChange, VfsFile, VfsRoot, VfsTask, Watch};
@@ -21,15 +23,19 @@ use relative_path::RelativePathBuf;
 use stdx::format_to;
 
 use crate::{
-    config::Config,
+    config::{Config, FilesConfig},
     diagnostics::{CheckFixes, DiagnosticCollection},
     main_loop::pending_requests::{CompletedRequest, LatestRequests},
-    vfs_glob::{Glob, RustPackageFilterBuilder},
+    semantic_tokens::SentSemanticTokens,
+    vfs_glob::{is_manifest, Glob, RustPackageFilterBuilder},
     LspError, Result,
 };
 use ra_db::ExternSourceId;
 use rustc_hash::{FxHashMap, FxHashSet};
 
+/// How many syntax trees are kept in the low memory mode.
+const LOW_MEMORY_LRU_CAPACITY: usize = 16;
+
 fn create_flycheck(workspaces: &[ProjectWorkspace], config: &FlycheckConfig) -> Option<Flycheck> {
     // FIXME: Figure out the multi-workspace situation
     workspaces
@@ -48,6 +54,31 @@ fn create_flycheck(workspaces: &[ProjectWorkspace], config: &FlycheckConfig) ->
         })
 }
 
+/// Collects the directories excluded from analysis, either explicitly or
+/// because they are the root of an excluded package.
+fn excluded_dirs(
+    folder_roots: &[PathBuf],
+    workspaces: &[ProjectWorkspace],
+    config: &FilesConfig,
+) -> Vec<PathBuf> {
+    let explicit = config.exclude_dirs.iter().flat_map(|dir| {
+        if dir.is_absolute() {
+            vec![dir.clone()]
+        } else {
+            folder_roots.iter().map(|root| root.join(dir)).collect()
+        }
+    });
+    let packages = workspaces.iter().flat_map(|ws| match ws {
+        ProjectWorkspace::Cargo { cargo, .. } => cargo
+            .packages()
+            .filter(|&pkg| config.exclude_crates.contains(&cargo[pkg].name))
+            .map(|pkg| cargo[pkg].root().to_path_buf())
+            .collect(),
+        ProjectWorkspace::Json { .. } => Vec::new(),
+    });
+    explicit.chain(packages).collect()
+}
+
 /// `WorldState` is the primary mutable state of the language server
 ///
 /// The most interesting components are `vfs`, which stores a consistent
@@ -64,6 +95,23 @@ pub struct WorldState {
     pub latest_requests: Arc<RwLock<LatestRequests>>,
     pub flycheck: Option<Flycheck>,
     pub diagnostics: DiagnosticCollection,
+    /// The last semantic tokens sent for each open document, used to delta
+    /// encode the next response and to only highlight the edited items again.
+    pub sent_semantic_tokens: Arc<Mutex<FxHashMap<Url, SentSemanticTokens>>>,
+    /// The last diagnostics pulled for each open document, with the revision
+    /// they were computed in and their result id.
+    pub diagnostics_cache: Arc<Mutex<FxHashMap<Url, (u64, String, Vec<Diagnostic>)>>>,
+    /// Bumped whenever the analysis or the config changes, so that results
+    /// computed in the same revision can be reused.
+    pub revision: u64,
+    /// The text of the `Cargo.toml` of the members. They are tracked by the
+    /// VFS, but kept out of the analysis.
+    pub manifests: Arc<FxHashMap<FileId, Arc<String>>>,
+    /// The newest version of each crate found in the local registry index.
+    pub registry_versions: Arc<Mutex<FxHashMap<String, Option<String>>>>,
+    /// Set while the resident memory is above `Config::memory_limit`: caches
+    /// are dropped eagerly and workspace-wide features are turned off.
+    pub low_memory: bool,
 }
 
 /// An immutable snapshot of the world's state at a point in time.
@@ -73,6 +121,12 @@ pub struct WorldSnapshot {
     pub analysis: Analysis,
     pub latest_requests: Arc<RwLock<LatestRequests>>,
     pub check_fixes: CheckFixes,
+    pub sent_semantic_tokens: Arc<Mutex<FxHashMap<Url, SentSemanticTokens>>>,
+    pub diagnostics_cache: Arc<Mutex<FxHashMap<Url, (u64, String, Vec<Diagnostic>)>>>,
+    pub revision: u64,
+    pub registry_versions: Arc<Mutex<FxHashMap<String, Option<String>>>>,
+    pub low_memory: bool,
+    manifests: Arc<FxHashMap<FileId, Arc<String>>>,
     vfs: Arc<RwLock<Vfs>>,
 }
 
@@ -91,23 +145,35 @@ impl WorldState {
             workspaces.iter().flat_map(ProjectWorkspace::out_dirs).collect();
 
         let roots: Vec<_> = {
-            let create_filter = |is_member| {
+            let exclude_dirs = excluded_dirs(&folder_roots, &workspaces, &config.files);
+            let create_filter = |root: &Path, is_member| {
+                let exclude_dirs = exclude_dirs
+                    .iter()
+                    .filter_map(|dir| dir.strip_prefix(root).ok())
+                    .filter_map(|dir| RelativePathBuf::from_path(dir).ok());
                 RustPackageFilterBuilder::default()
                     .set_member(is_member)
+                    .include_manifest(true)
                     .exclude(exclude_globs.iter().cloned())
+                    .exclude_dirs(exclude_dirs)
                     .into_vfs_filter()
             };
+            let root_entry = |path: &Path, is_member| {
+                if exclude_dirs.iter().any(|dir| path.starts_with(dir)) {
+                    return None;
+                }
+                Some(RootEntry::new(path.to_owned(), create_filter(path, is_member)))
+            };
             folder_roots
                 .iter()
-                .map(|path| RootEntry::new(path.clone(), create_filter(true)))
-                .chain(workspaces.iter().flat_map(ProjectWorkspace::to_roots).map(|pkg_root| {
-                    RootEntry::new(pkg_root.path().to_owned(), create_filter(pkg_root.is_member()))
-                }))
+                .filter_map(|path| root_entry(path, true))
                 .chain(
-                    extern_dirs
+                    workspaces
                         .iter()
-                        .map(|path| RootEntry::new(path.to_owned(), create_filter(false))),
+                        .flat_map(ProjectWorkspace::to_roots)
+                        .filter_map(|pkg_root| root_entry(pkg_root.path(), pkg_root.is_member())),
                 )
+                .chain(extern_dirs.iter().filter_map(|path| root_entry(path, false)))
                 .collect()
         };
 
@@ -176,6 +242,14 @@ impl WorldState {
             });
         change.set_crate_graph(crate_graph);
 
+        if config.symbol_cache {
+            if let Some(cache) =
+                symbol_cache_path(&folder_roots).and_then(|it| load_symbol_cache(&it))
+            {
+                change.set_symbol_cache(cache);
+            }
+        }
+
         let flycheck = config.check.as_ref().and_then(|c| create_flycheck(&workspaces, c));
 
         let mut analysis_host = AnalysisHost::new(lru_capacity);
@@ -190,11 +264,39 @@ impl WorldState {
             latest_requests: Default::default(),
             flycheck,
             diagnostics: Default::default(),
+            sent_semantic_tokens: Default::default(),
+            diagnostics_cache: Default::default(),
+            revision: 0,
+            manifests: Default::default(),
+            registry_versions: Default::default(),
+            low_memory: false,
+        }
+    }
+
+    /// Where the symbols of the local files are saved, if they are saved at
+    /// all.
+    pub fn symbol_cache_path(&self) -> Option<PathBuf> {
+        if !self.config.symbol_cache {
+            return None;
+        }
+        symbol_cache_path(&self.roots)
+    }
+
+    /// Saves the symbols of the local files, to be loaded by the next session.
+    pub fn save_symbol_cache(&self) {
+        if let Some(path) = self.symbol_cache_path() {
+            write_symbol_cache(&path, &self.analysis_host.analysis());
         }
     }
 
     pub fn update_configuration(&mut self, config: Config) {
-        self.analysis_host.update_lru_capacity(config.lru_capacity);
+        self.revision += 1;
+        if config.memory_limit.is_none() {
+            self.low_memory = false;
+        }
+        if !self.low_memory {
+            self.analysis_host.update_lru_capacity(config.lru_capacity);
+        }
         if config.check != self.config.check {
             self.flycheck =
                 config.check.as_ref().and_then(|it| create_flycheck(&self.workspaces, it));
@@ -215,6 +317,7 @@ impl WorldState {
         }
         let mut libs = Vec::new();
         let mut change = AnalysisChange::new();
+        let manifests = Arc::make_mut(&mut self.manifests);
         for c in changes {
             match c {
                 VfsChange::AddRoot { root, files } => {
@@ -223,6 +326,10 @@ impl WorldState {
                     if is_local {
                         *roots_scanned += 1;
                         for (file, path, text) in files {
+                            if is_manifest(&path) {
+                                manifests.insert(FileId(file.0), text);
+                                continue;
+                            }
                             change.add_file(SourceRootId(root.0), FileId(file.0), path, text);
                         }
                     } else {
@@ -233,18 +340,26 @@ impl WorldState {
                         libs.push((SourceRootId(root.0), files));
                     }
                 }
+                VfsChange::AddFile { file, path, text, .. } if is_manifest(&path) => {
+                    manifests.insert(FileId(file.0), text);
+                }
                 VfsChange::AddFile { root, file, path, text } => {
                     change.add_file(SourceRootId(root.0), FileId(file.0), path, text);
                 }
+                VfsChange::RemoveFile { file, path, .. } if is_manifest(&path) => {
+                    manifests.remove(&FileId(file.0));
+                }
                 VfsChange::RemoveFile { root, file, path } => {
                     change.remove_file(SourceRootId(root.0), FileId(file.0), path)
                 }
-                VfsChange::ChangeFile { file, text } => {
-                    change.change_file(FileId(file.0), text);
-                }
+                VfsChange::ChangeFile { file, text } => match manifests.get_mut(&FileId(file.0)) {
+                    Some(manifest) => *manifest = text,
+                    None => change.change_file(FileId(file.0), text),
+                },
             }
         }
         self.analysis_host.apply_change(change);
+        self.revision += 1;
         Some(libs)
     }
 
@@ -252,19 +367,39 @@ impl WorldState {
         let mut change = AnalysisChange::new();
         change.add_library(data);
         self.analysis_host.apply_change(change);
+        self.revision += 1;
     }
 
     pub fn snapshot(&self) -> WorldSnapshot {
+        let mut config = self.config.clone();
+        if self.low_memory {
+            config.assist.import_search = false;
+        }
         WorldSnapshot {
-            config: self.config.clone(),
+            config,
             workspaces: Arc::clone(&self.workspaces),
             analysis: self.analysis_host.analysis(),
             vfs: Arc::clone(&self.vfs),
             latest_requests: Arc::clone(&self.latest_requests),
             check_fixes: Arc::clone(&self.diagnostics.check_fixes),
+            sent_semantic_tokens: Arc::clone(&self.sent_semantic_tokens),
+            diagnostics_cache: Arc::clone(&self.diagnostics_cache),
+            revision: self.revision,
+            registry_versions: Arc::clone(&self.registry_versions),
+            low_memory: self.low_memory,
+            manifests: Arc::clone(&self.manifests),
         }
     }
 
+    /// In the low memory mode only a few syntax trees are kept around, so
+    /// that the usage stays down without dropping all caches on every change.
+    pub fn set_low_memory(&mut self, low_memory: bool) {
+        self.low_memory = low_memory;
+        let lru_capacity =
+            if low_memory { Some(LOW_MEMORY_LRU_CAPACITY) } else { self.config.lru_capacity };
+        self.analysis_host.update_lru_capacity(lru_capacity);
+    }
+
     pub fn maybe_collect_garbage(&mut self) {
         self.analysis_host.maybe_collect_garbage()
     }
@@ -307,6 +442,12 @@ impl WorldSnapshot {
         self.vfs.read().file2path(VfsFile(id.0))
     }
 
+    /// The text of a member's `Cargo.toml`, as seen by the VFS.
+    pub fn manifest_text(&self, path: &Path) -> Option<Arc<String>> {
+        let file = self.vfs.read().path2file(path)?;
+        self.manifests.get(&FileId(file.0)).cloned()
+    }
+
     pub fn file_line_endings(&self, id: FileId) -> LineEndings {
         self.vfs.read().file_line_endings(VfsFile(id.0))
     }
@@ -344,3 +485,43 @@ impl WorldSnapshot {
         self.workspaces.iter().find_map(|ws| ws.workspace_root_for(&path))
     }
 }
+
+/// The target directory of the workspace at `root`, which is
+/// `$CARGO_TARGET_DIR` if set, like for cargo itself.
+pub(crate) fn target_dir(root: &Path) -> PathBuf {
+    match env::var_os("CARGO_TARGET_DIR") {
+        Some(it) => root.join(it),
+        None => root.join("target"),
+    }
+}
+
+fn symbol_cache_path(folder_roots: &[PathBuf]) -> Option<PathBuf> {
+    Some(target_dir(folder_roots.first()?).join("rust-analyzer/symbols"))
+}
+
+/// Writes the symbols of the current local files to `path`. Files which were
+/// changed or removed since the cache was loaded are left out.
+pub fn write_symbol_cache(path: &Path, analysis: &Analysis) {
+    let cache = match analysis.symbol_cache() {
+        Ok(it) => it,
+        Err(_) => return,
+    };
+    let res = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(path, cache.to_text()));
+    match res {
+        Ok(()) => log::info!("saved symbols of {} files to {}", cache.len(), path.display()),
+        Err(e) => log::error!("failed to save symbol cache to {}: {}", path.display(), e),
+    }
+}
+
+fn load_symbol_cache(path: &Path) -> Option<SymbolCache> {
+    let text = fs::read_to_string(path).ok()?;
+    let cache = SymbolCache::from_text(&text);
+    match &cache {
+        Some(it) => log::info!("loaded symbols of {} files from {}", it.len(), path.display()),
+        None => log::warn!("ignoring invalid symbol cache {}", path.display()),
+    }
+    cache
+}
@@ -9,7 +9,8 @@ use lsp_types::{
 };
 use rust_analyzer::req::{
     CodeActionParams, CodeActionRequest, Completion, CompletionParams, DidOpenTextDocument,
-    Formatting, GotoDefinition, OnEnter, Runnables, RunnablesParams,
+    DocumentDiagnosticParams, DocumentDiagnosticRequest, Formatting, GotoDefinition, OnEnter,
+    ResolveCodeAction, ResolveCodeActionParams, Runnables, RunnablesParams,
 };
 use serde_json::json;
 use tempfile::TempDir;
@@ -460,6 +461,57 @@ fn main() {{}}
     );
 }
 
+#[test]
+fn resolves_code_action_edit() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- src/lib.rs
+fn foo() {}
+"#,
+    );
+    server.wait_until_workspace_is_loaded();
+    server.request::<ResolveCodeAction>(
+        ResolveCodeActionParams {
+            text_document: server.doc_id("src/lib.rs"),
+            range: Range::new(Position::new(0, 3), Position::new(0, 3)),
+            id: "add_inline".to_string(),
+            label: "Add `#[inline]`".to_string(),
+        },
+        json!({
+          "cursorPosition": {
+            "position": { "character": 9, "line": 0 },
+            "textDocument": { "uri": "file:///[..]src/lib.rs" }
+          },
+          "label": "Add `#[inline]`",
+          "workspaceEdit": {
+            "documentChanges": [
+              {
+                "edits": [
+                  {
+                    "newText": "#[inline]\n",
+                    "range": {
+                      "end": { "character": 0, "line": 0 },
+                      "start": { "character": 0, "line": 0 }
+                    }
+                  }
+                ],
+                "textDocument": { "uri": "file:///[..]src/lib.rs", "version": null }
+              }
+            ]
+          }
+        }),
+    );
+}
+
 #[test]
 fn diagnostics_dont_block_typing() {
     if skip_slow_tests() {
@@ -532,6 +584,43 @@ fn main() {{}}
     assert!(elapsed.as_millis() < 2000, "typing enter took {:?}", elapsed);
 }
 
+#[test]
+fn pulls_diagnostics_with_result_ids() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = Project::with_fixture(
+        r#"
+//- Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- src/lib.rs
+mod bar;
+"#,
+    )
+    .with_config(|config| config.client_caps.pull_diagnostics = true)
+    .server();
+    server.wait_until_workspace_is_loaded();
+
+    let params = |previous_result_id| DocumentDiagnosticParams {
+        text_document: server.doc_id("src/lib.rs"),
+        identifier: None,
+        previous_result_id,
+    };
+    let res = server.send_request::<DocumentDiagnosticRequest>(params(None));
+    assert_eq!(res["kind"], "full");
+    assert_eq!(res["items"][0]["message"], "unresolved module");
+
+    let result_id = res["resultId"].as_str().unwrap().to_string();
+    server.request::<DocumentDiagnosticRequest>(
+        params(Some(result_id.clone())),
+        json!({ "kind": "unchanged", "resultId": result_id }),
+    );
+}
+
 #[test]
 fn preserves_dos_line_endings() {
     if skip_slow_tests() {
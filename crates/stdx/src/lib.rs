@@ -1,6 +1,6 @@
 //! Missing batteries for standard libraries.
 
-use std::{cell::Cell, fmt};
+use std::{cell::Cell, cmp, fmt};
 
 #[inline(always)]
 pub fn is_ci() -> bool {
@@ -26,6 +26,37 @@ macro_rules! format_to {
     };
 }
 
+/// Returns the candidates which are close to `name`, closest first, using the
+/// same distance limit as rustc: a third of the name's length.
+pub fn similar_names(name: &str, candidates: impl IntoIterator<Item = String>) -> Vec<String> {
+    let max_dist = cmp::max(name.len(), 3) / 3;
+    let mut res = candidates
+        .into_iter()
+        .filter(|it| it != name)
+        .map(|it| (levenshtein_distance(name, &it), it))
+        .filter(|(dist, _)| *dist <= max_dist)
+        .collect::<Vec<_>>();
+    res.sort();
+    res.dedup();
+    res.into_iter().map(|(_, it)| it).collect()
+}
+
+pub fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev_row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = prev_row[j] + if a_char == *b_char { 0 } else { 1 };
+            let deletion = prev_row[j + 1] + 1;
+            let insertion = row[j] + 1;
+            row.push(cmp::min(substitution, cmp::min(deletion, insertion)));
+        }
+        prev_row = row;
+    }
+    prev_row[b.len()]
+}
+
 pub trait SepBy: Sized {
     /// Returns an `impl fmt::Display`, which joins elements via a separator.
     fn sep_by<'a>(self, sep: &'a str) -> SepByBuilder<'a, Self>;
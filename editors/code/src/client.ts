@@ -1,5 +1,6 @@
 import * as lc from 'vscode-languageclient';
 import * as vscode from 'vscode';
+import * as ra from './rust-analyzer-api';
 
 import { CallHierarchyFeature } from 'vscode-languageclient/lib/callHierarchy.proposed';
 import { SemanticTokensFeature, DocumentSemanticsTokensSignature } from 'vscode-languageclient/lib/semanticTokens.proposed';
@@ -31,6 +32,27 @@ export async function createClient(serverPath: string, cwd: string): Promise<lc.
                 const res = await next(document, token);
                 if (res === undefined) throw new Error('busy');
                 return res;
+            },
+            // Renames which would change the meaning of the code are only
+            // applied after the user had a look at the conflicts.
+            async provideRenameEdits(document: vscode.TextDocument, position: vscode.Position, newName: string, token: vscode.CancellationToken, next: lc.ProvideRenameEditsSignature) {
+                const conflicts = await res.sendRequest(ra.renameConflicts, {
+                    textDocument: { uri: document.uri.toString() },
+                    position: res.code2ProtocolConverter.asPosition(position),
+                    newName,
+                }, token);
+                if (conflicts.length > 0) {
+                    const detail = conflicts
+                        .map(it => `line ${it.location.range.start.line + 1}: ${it.message}`)
+                        .join('\n');
+                    const choice = await vscode.window.showWarningMessage(
+                        `Renaming to \`${newName}\` changes the meaning of the code:\n${detail}`,
+                        { modal: true },
+                        'Rename Anyway',
+                    );
+                    if (choice !== 'Rename Anyway') return undefined;
+                }
+                return next(document, position, newName, token);
             }
         } as any
     };
@@ -78,6 +100,19 @@ export async function createClient(serverPath: string, cwd: string): Promise<lc.
     // implementations are still in the "proposed" category for 3.16.
     res.registerFeature(new CallHierarchyFeature(res));
     res.registerFeature(new SemanticTokensFeature(res));
+    res.registerFeature(new ExperimentalFeatures());
 
     return res;
 }
+
+// Advertises the rust-analyzer specific extensions of the protocol we support.
+class ExperimentalFeatures implements lc.StaticFeature {
+    fillClientCapabilities(capabilities: lc.ClientCapabilities): void {
+        const caps: any = capabilities.experimental ?? {};
+        // Code actions are resolved with `rust-analyzer/resolveCodeAction`.
+        caps.resolveCodeAction = true;
+        capabilities.experimental = caps;
+    }
+    initialize(_capabilities: lc.ServerCapabilities, _documentSelector: lc.DocumentSelector | undefined): void {
+    }
+}
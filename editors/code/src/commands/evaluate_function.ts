@@ -0,0 +1,41 @@
+import * as vscode from 'vscode';
+import * as ra from '../rust-analyzer-api';
+
+import { Ctx, Cmd } from '../ctx';
+import { createTask } from './runnables';
+
+// Calls the function under the cursor with the given arguments through a
+// scratch binary, and shows the result in a terminal.
+export function evaluateFunction(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const args = await vscode.window.showInputBox({
+            prompt: "Enter the arguments of the function, for example '1, \"foo\"'",
+        });
+        if (args === undefined) return;
+
+        let runnable: ra.Runnable;
+        try {
+            runnable = await client.sendRequest(ra.evaluateFunction, {
+                textDocument: { uri: editor.document.uri.toString() },
+                position: client.code2ProtocolConverter.asPosition(editor.selection.active),
+                args,
+            });
+        } catch (e) {
+            // The server explains why the function can't be evaluated.
+            vscode.window.showErrorMessage(e.message);
+            return;
+        }
+
+        const task = createTask(runnable);
+        task.presentationOptions = {
+            reveal: vscode.TaskRevealKind.Always,
+            panel: vscode.TaskPanelKind.Dedicated,
+            clear: true,
+        };
+        await vscode.tasks.executeTask(task);
+    };
+}
@@ -13,6 +13,7 @@ export * from './parent_module';
 export * from './syntax_tree';
 export * from './expand_macro';
 export * from './runnables';
+export * from './evaluate_function';
 export * from './ssr';
 export * from './server_version';
 
@@ -40,14 +41,31 @@ export function applySourceChange(ctx: Ctx): Cmd {
     };
 }
 
-export function selectAndApplySourceChange(ctx: Ctx): Cmd {
-    return async (changes: ra.SourceChange[]) => {
-        if (changes.length === 1) {
-            await sourceChange.applySourceChange(ctx, changes[0]);
-        } else if (changes.length > 0) {
-            const selectedChange = await vscode.window.showQuickPick(changes);
-            if (!selectedChange) return;
-            await sourceChange.applySourceChange(ctx, selectedChange);
+export function resolveAndApplyCodeAction(ctx: Ctx): Cmd {
+    return async (params: ra.ResolveCodeActionParams) => {
+        const change = await ctx.client.sendRequest(ra.resolveCodeAction, params);
+        if (!change) return;
+        await sourceChange.applySourceChange(ctx, change);
+    };
+}
+
+export function selectAndResolveCodeAction(ctx: Ctx): Cmd {
+    return async (actions: ra.ResolveCodeActionParams[]) => {
+        const selected = actions.length === 1
+            ? actions[0]
+            : await vscode.window.showQuickPick(actions);
+        if (!selected) return;
+        await resolveAndApplyCodeAction(ctx)(selected);
+    };
+}
+
+export function saveAndReload(_ctx: Ctx): Cmd {
+    return async (uri: string) => {
+        const path = vscode.Uri.parse(uri).fsPath;
+        const document = vscode.workspace.textDocuments.find(it => it.uri.fsPath === path);
+        if (document?.isDirty) {
+            await document.save();
         }
+        await vscode.commands.executeCommand('rust-analyzer.reload');
     };
 }
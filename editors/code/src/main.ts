@@ -78,6 +78,7 @@ export async function activate(context: vscode.ExtensionContext) {
     ctx.registerCommand('syntaxTree', commands.syntaxTree);
     ctx.registerCommand('expandMacro', commands.expandMacro);
     ctx.registerCommand('run', commands.run);
+    ctx.registerCommand('evaluateFunction', commands.evaluateFunction);
 
     defaultOnEnter.dispose();
     ctx.registerCommand('onEnter', commands.onEnter);
@@ -90,7 +91,9 @@ export async function activate(context: vscode.ExtensionContext) {
     ctx.registerCommand('debugSingle', commands.debugSingle);
     ctx.registerCommand('showReferences', commands.showReferences);
     ctx.registerCommand('applySourceChange', commands.applySourceChange);
-    ctx.registerCommand('selectAndApplySourceChange', commands.selectAndApplySourceChange);
+    ctx.registerCommand('resolveAndApplyCodeAction', commands.resolveAndApplyCodeAction);
+    ctx.registerCommand('selectAndResolveCodeAction', commands.selectAndResolveCodeAction);
+    ctx.registerCommand('saveAndReload', commands.saveAndReload);
 
     ctx.pushCleanup(activateTaskProvider(workspaceFolder));
 
@@ -86,6 +86,21 @@ export interface Runnable {
 }
 export const runnables = request<RunnablesParams, Vec<Runnable>>("runnables");
 
+export interface EvaluateFunctionParams {
+    textDocument: lc.TextDocumentIdentifier;
+    position: lc.Position;
+    args: string;
+}
+export const evaluateFunction = request<EvaluateFunctionParams, Runnable>("evaluateFunction");
+
+export interface ResolveCodeActionParams {
+    textDocument: lc.TextDocumentIdentifier;
+    range: lc.Range;
+    id: string;
+    label: string;
+}
+export const resolveCodeAction = request<ResolveCodeActionParams, Option<SourceChange>>("resolveCodeAction");
+
 export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint;
 
 export namespace InlayHint {
@@ -115,6 +130,13 @@ export interface SsrParams {
 export const ssr = request<SsrParams, SourceChange>("ssr");
 
 
+export interface RenameConflict {
+    location: lc.Location;
+    message: string;
+}
+export const renameConflicts = request<lc.RenameParams, Vec<RenameConflict>>("renameConflicts");
+
+
 export const publishDecorations = notification<PublishDecorationsParams>("publishDecorations");
 
 
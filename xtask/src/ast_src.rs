@@ -1,5 +1,11 @@
 //! Defines input for code generation process.
 
+use std::collections::{HashMap, HashSet};
+
+use anyhow::bail;
+
+use crate::Result;
+
 pub(crate) struct KindsSrc<'a> {
     pub(crate) punct: &'a [(&'a str, &'a str)],
     pub(crate) keywords: &'a [&'a str],
@@ -139,6 +145,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "RANGE_PAT",
         "LITERAL_PAT",
         "MACRO_PAT",
+        "CONST_BLOCK_PAT",
         // atoms
         "TUPLE_EXPR",
         "ARRAY_EXPR",
@@ -148,6 +155,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "IF_EXPR",
         "WHILE_EXPR",
         "CONDITION",
+        "LET_EXPR",
         "LOOP_EXPR",
         "FOR_EXPR",
         "CONTINUE_EXPR",
@@ -202,6 +210,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "NAME",
         "NAME_REF",
         "LET_STMT",
+        "LET_ELSE",
         "EXPR_STMT",
         "TYPE_PARAM_LIST",
         "LIFETIME_PARAM",
@@ -224,607 +233,295 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
     ],
 };
 
-pub(crate) struct AstSrc<'a> {
-    pub(crate) nodes: &'a [AstNodeSrc<'a>],
-    pub(crate) enums: &'a [AstEnumSrc<'a>],
-    pub(crate) token_enums: &'a [AstEnumSrc<'a>],
+impl KindsSrc<'_> {
+    /// Returns the name of the token type for a token in the grammar, like
+    /// `FnKw` for `fn` or `Ident` for `ident`.
+    fn token_name(&self, text: &str) -> Option<String> {
+        if let Some((_, kind)) = self.punct.iter().find(|(punct, _)| *punct == text) {
+            return Some(to_pascal_case(kind));
+        }
+        if self.keywords.contains(&text) || self.contextual_keywords.contains(&text) {
+            return Some(to_pascal_case(&format!("{}_kw", text)));
+        }
+        self.literals
+            .iter()
+            .chain(self.tokens.iter())
+            .find(|kind| kind.to_lowercase() == text)
+            .map(|kind| to_pascal_case(kind))
+    }
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct AstSrc {
+    pub(crate) nodes: Vec<AstNodeSrc>,
+    pub(crate) enums: Vec<AstEnumSrc>,
+    pub(crate) token_enums: Vec<AstEnumSrc>,
 }
 
-pub(crate) struct AstNodeSrc<'a> {
-    pub(crate) name: &'a str,
-    pub(crate) traits: &'a [&'a str],
-    pub(crate) fields: &'a [(&'a str, FieldSrc<&'a str>)],
+#[derive(Debug)]
+pub(crate) struct AstNodeSrc {
+    pub(crate) name: String,
+    pub(crate) traits: Vec<String>,
+    pub(crate) fields: Vec<(String, FieldSrc<String>)>,
 }
 
+#[derive(Debug)]
 pub(crate) enum FieldSrc<T> {
     Shorthand,
     Optional(T),
     Many(T),
+    /// The `n`th child of the type, for nodes with several children of the
+    /// same type, like the operands of a binary expression.
+    Nth(T, usize),
 }
 
-pub(crate) struct AstEnumSrc<'a> {
-    pub(crate) name: &'a str,
-    pub(crate) traits: &'a [&'a str],
-    pub(crate) variants: &'a [&'a str],
+#[derive(Debug)]
+pub(crate) struct AstEnumSrc {
+    pub(crate) name: String,
+    pub(crate) traits: Vec<String>,
+    pub(crate) variants: Vec<String>,
 }
 
-macro_rules! ast_nodes {
-    ($(
-        struct $name:ident$(: $($trait:ident),*)? {
-            $($field_name:ident $(: $ty:tt)?),*$(,)?
-        }
-    )*) => {
-        [$(
-            AstNodeSrc {
-                name: stringify!($name),
-                traits: &[$($(stringify!($trait)),*)?],
-                fields: &[$(
-                    (stringify!($field_name), field_ty!($field_name $($ty)?))
-                ),*],
-
+impl AstSrc {
+    /// Parses the grammar in `rust.ungram`, see the comment at the top of the
+    /// file for the syntax.
+    pub(crate) fn parse(text: &str, kinds: &KindsSrc<'_>) -> Result<AstSrc> {
+        let lexemes = lex(text)?;
+        let mut res = AstSrc::default();
+        let mut defined = HashSet::new();
+
+        let mut start = 0;
+        while start < lexemes.len() {
+            let end = lexemes[start + 1..]
+                .iter()
+                .position(|it| it.starts_rule())
+                .map_or(lexemes.len(), |it| start + 1 + it);
+            let rule = &lexemes[start..end];
+            start = end;
+
+            let (name, traits, body) = parse_header(rule)?;
+            if !defined.insert(name.clone()) {
+                bail!("line {}: `{}` is defined twice", rule[0].line, name);
             }
-        ),*]
-    };
+            if body.iter().any(|it| it.token == Token::Pipe) {
+                let variants = parse_variants(&name, body, kinds)?;
+                match variants {
+                    Variants::Nodes(variants) => {
+                        res.enums.push(AstEnumSrc { name, traits, variants })
+                    }
+                    Variants::Tokens(_) if !traits.is_empty() => {
+                        bail!("line {}: token enum `{}` can't have traits", rule[0].line, name)
+                    }
+                    Variants::Tokens(variants) => {
+                        res.token_enums.push(AstEnumSrc { name, traits, variants })
+                    }
+                }
+            } else {
+                let fields = parse_fields(&name, body, kinds)?;
+                res.nodes.push(AstNodeSrc { name, traits, fields });
+            }
+        }
+        Ok(res)
+    }
 }
 
-macro_rules! field_ty {
-    ($field_name:ident) => {
-        FieldSrc::Shorthand
-    };
-    ($field_name:ident [$ty:ident]) => {
-        FieldSrc::Many(stringify!($ty))
-    };
-    ($field_name:ident $ty:ident) => {
-        FieldSrc::Optional(stringify!($ty))
-    };
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    /// A quoted token, like `'fn'`.
+    Token(String),
+    Colon,
+    Comma,
+    Eq,
+    Star,
+    Pipe,
 }
 
-macro_rules! ast_enums {
-    ($(
-        enum $name:ident $(: $($trait:ident),*)? {
-            $($variant:ident),*$(,)?
-        }
-    )*) => {
-        [$(
-            AstEnumSrc {
-                name: stringify!($name),
-                traits: &[$($(stringify!($trait)),*)?],
-                variants: &[$(stringify!($variant)),*],
-
-            }
-        ),*]
-    };
+#[derive(Debug)]
+struct Lexeme {
+    token: Token,
+    line: usize,
+    column: usize,
 }
 
-pub(crate) const AST_SRC: AstSrc = AstSrc {
-    nodes: &ast_nodes! {
-        struct SourceFile: ModuleItemOwner, FnDefOwner, AttrsOwner {
-            modules: [Module],
-        }
-
-        struct FnDef: VisibilityOwner, NameOwner, TypeParamsOwner, DocCommentsOwner, AttrsOwner {
-            Abi,
-            ConstKw,
-            DefaultKw,
-            AsyncKw,
-            UnsafeKw,
-            FnKw,
-            ParamList,
-            RetType,
-            body: BlockExpr,
-            Semi
-        }
-
-        struct RetType { ThinArrow, TypeRef }
-
-        struct StructDef: VisibilityOwner, NameOwner, TypeParamsOwner, AttrsOwner, DocCommentsOwner {
-            StructKw,
-            FieldDefList,
-            Semi
-        }
-
-        struct UnionDef: VisibilityOwner, NameOwner, TypeParamsOwner, AttrsOwner, DocCommentsOwner {
-            UnionKw,
-            RecordFieldDefList,
-        }
-
-        struct RecordFieldDefList { LCurly, fields: [RecordFieldDef], RCurly }
-        struct RecordFieldDef: VisibilityOwner, NameOwner, AttrsOwner, DocCommentsOwner, TypeAscriptionOwner { }
-
-        struct TupleFieldDefList { LParen, fields: [TupleFieldDef], RParen }
-        struct TupleFieldDef: VisibilityOwner, AttrsOwner {
-            TypeRef,
-        }
-
-        struct EnumDef: VisibilityOwner, NameOwner, TypeParamsOwner, AttrsOwner, DocCommentsOwner {
-            EnumKw,
-            variant_list: EnumVariantList,
-        }
-        struct EnumVariantList {
-            LCurly,
-            variants: [EnumVariant],
-            RCurly
-        }
-        struct EnumVariant: VisibilityOwner, NameOwner, DocCommentsOwner, AttrsOwner {
-            FieldDefList,
-            Eq,
-            Expr
-        }
-
-        struct TraitDef: VisibilityOwner, NameOwner, AttrsOwner, DocCommentsOwner, TypeParamsOwner, TypeBoundsOwner {
-            UnsafeKw,
-            AutoKw,
-            TraitKw,
-            ItemList,
-        }
-
-        struct Module: VisibilityOwner, NameOwner, AttrsOwner, DocCommentsOwner {
-            ModKw,
-            ItemList,
-            Semi
-        }
-
-        struct ItemList: FnDefOwner, ModuleItemOwner {
-            LCurly,
-            impl_items: [ImplItem],
-            RCurly
-        }
-
-        struct ConstDef: VisibilityOwner, NameOwner, TypeParamsOwner, AttrsOwner, DocCommentsOwner, TypeAscriptionOwner {
-            DefaultKw,
-            ConstKw,
-            Eq,
-            body: Expr,
-            Semi
-        }
-
-        struct StaticDef: VisibilityOwner, NameOwner, TypeParamsOwner, AttrsOwner, DocCommentsOwner, TypeAscriptionOwner {
-            StaticKw,
-            MutKw,
-            Eq,
-            body: Expr,
-            Semi
-        }
-
-        struct TypeAliasDef: VisibilityOwner, NameOwner, TypeParamsOwner, AttrsOwner, DocCommentsOwner, TypeBoundsOwner {
-            DefaultKw,
-            TypeKw,
-            Eq,
-            TypeRef,
-            Semi
-        }
-
-        struct ImplDef: TypeParamsOwner, AttrsOwner {
-            DefaultKw,
-            ConstKw,
-            UnsafeKw,
-            ImplKw,
-            Excl,
-            ForKw,
-            ItemList,
-        }
-
-        struct ParenType { LParen, TypeRef, RParen }
-        struct TupleType { LParen, fields: [TypeRef], RParen }
-        struct NeverType { Excl }
-        struct PathType { Path }
-        struct PointerType { Star, ConstKw, MutKw, TypeRef }
-        struct ArrayType { LBrack, TypeRef, Semi, Expr, RBrack }
-        struct SliceType { LBrack, TypeRef, RBrack }
-        struct ReferenceType { Amp, Lifetime, MutKw, TypeRef }
-        struct PlaceholderType { Underscore }
-        struct FnPointerType { Abi, UnsafeKw, FnKw, ParamList, RetType }
-        struct ForType { ForKw, TypeParamList, TypeRef }
-        struct ImplTraitType: TypeBoundsOwner { ImplKw }
-        struct DynTraitType: TypeBoundsOwner { DynKw }
-
-        struct TupleExpr: AttrsOwner { LParen, exprs: [Expr], RParen }
-        struct ArrayExpr: AttrsOwner { LBrack, exprs: [Expr], Semi, RBrack }
-        struct ParenExpr: AttrsOwner { LParen, Expr, RParen }
-        struct PathExpr  { Path }
-        struct LambdaExpr: AttrsOwner {
-            StaticKw,
-            AsyncKw,
-            MoveKw,
-            ParamList,
-            RetType,
-            body: Expr,
-        }
-        struct IfExpr: AttrsOwner { IfKw, Condition }
-        struct LoopExpr: AttrsOwner, LoopBodyOwner { LoopKw }
-        struct TryBlockExpr: AttrsOwner { TryKw, body: BlockExpr }
-        struct ForExpr: AttrsOwner, LoopBodyOwner {
-            ForKw,
-            Pat,
-            InKw,
-            iterable: Expr,
-        }
-        struct WhileExpr: AttrsOwner, LoopBodyOwner { WhileKw, Condition }
-        struct ContinueExpr: AttrsOwner { ContinueKw, Lifetime }
-        struct BreakExpr: AttrsOwner { BreakKw, Lifetime, Expr }
-        struct Label { Lifetime }
-        struct BlockExpr: AttrsOwner { Label, UnsafeKw, Block  }
-        struct ReturnExpr: AttrsOwner { Expr }
-        struct CallExpr: ArgListOwner { Expr }
-        struct MethodCallExpr: AttrsOwner, ArgListOwner {
-            Expr, Dot, NameRef, TypeArgList,
-        }
-        struct IndexExpr: AttrsOwner { LBrack, RBrack }
-        struct FieldExpr: AttrsOwner { Expr, Dot, NameRef }
-        struct AwaitExpr: AttrsOwner { Expr, Dot, AwaitKw }
-        struct TryExpr: AttrsOwner { TryKw, Expr }
-        struct CastExpr: AttrsOwner { Expr, AsKw, TypeRef }
-        struct RefExpr: AttrsOwner { Amp, RawKw, MutKw, Expr }
-        struct PrefixExpr: AttrsOwner { PrefixOp, Expr }
-        struct BoxExpr: AttrsOwner { BoxKw, Expr }
-        struct RangeExpr: AttrsOwner { RangeOp }
-        struct BinExpr: AttrsOwner { BinOp }
-        struct Literal { LiteralToken }
-
-        struct MatchExpr: AttrsOwner { MatchKw, Expr, MatchArmList }
-        struct MatchArmList: AttrsOwner { LCurly, arms: [MatchArm], RCurly }
-        struct MatchArm: AttrsOwner {
-            pat: Pat,
-            guard: MatchGuard,
-            FatArrow,
-            Expr,
-        }
-        struct MatchGuard { IfKw, Expr }
-
-        struct RecordLit { Path, RecordFieldList}
-        struct RecordFieldList {
-            LCurly,
-            fields: [RecordField],
-            Dotdot,
-            spread: Expr,
-            RCurly
-        }
-        struct RecordField: AttrsOwner { NameRef, Colon, Expr }
-
-        struct OrPat { pats: [Pat] }
-        struct ParenPat { LParen, Pat, RParen }
-        struct RefPat { Amp, MutKw, Pat }
-        struct BoxPat { BoxKw, Pat }
-        struct BindPat: AttrsOwner, NameOwner { RefKw, MutKw, At, Pat }
-        struct PlaceholderPat { Underscore }
-        struct DotDotPat { Dotdot }
-        struct PathPat { Path }
-        struct SlicePat { LBrack, args: [Pat], RBrack }
-        struct RangePat { RangeSeparator }
-        struct LiteralPat { Literal }
-        struct MacroPat { MacroCall }
-
-        struct RecordPat { RecordFieldPatList, Path }
-        struct RecordFieldPatList {
-            LCurly,
-            pats: [RecordInnerPat],
-            record_field_pats: [RecordFieldPat],
-            bind_pats: [BindPat],
-            Dotdot,
-            RCurly
-        }
-        struct RecordFieldPat: AttrsOwner, NameOwner { Colon, Pat }
-
-        struct TupleStructPat { Path, LParen, args: [Pat], RParen }
-        struct TuplePat { LParen, args: [Pat], RParen }
-
-        struct Visibility { PubKw, SuperKw, SelfKw, CrateKw }
-        struct Name { Ident }
-        struct NameRef { NameRefToken }
-
-        struct MacroCall: NameOwner, AttrsOwner,DocCommentsOwner {
-            Path, Excl, TokenTree, Semi
-        }
-        struct Attr { Pound, Excl, LBrack, Path, Eq, input: AttrInput, RBrack }
-        struct TokenTree {}
-        struct TypeParamList {
-            LAngle,
-            generic_params: [GenericParam],
-            type_params: [TypeParam],
-            lifetime_params: [LifetimeParam],
-            const_params: [ConstParam],
-            RAngle
-        }
-        struct TypeParam: NameOwner, AttrsOwner, TypeBoundsOwner {
-            Eq,
-            default_type: TypeRef,
-        }
-        struct ConstParam: NameOwner, AttrsOwner, TypeAscriptionOwner {
-            Eq,
-            default_val: Expr,
-        }
-        struct LifetimeParam: AttrsOwner { Lifetime}
-        struct TypeBound { Lifetime, /* Question,  */ ConstKw, /* Question,  */ TypeRef}
-        struct TypeBoundList { bounds: [TypeBound] }
-        struct WherePred: TypeBoundsOwner { Lifetime, TypeRef }
-        struct WhereClause { WhereKw, predicates: [WherePred] }
-        struct Abi { String }
-        struct ExprStmt: AttrsOwner { Expr, Semi }
-        struct LetStmt: AttrsOwner, TypeAscriptionOwner {
-            LetKw,
-            Pat,
-            Eq,
-            initializer: Expr,
-            Semi,
-        }
-        struct Condition { LetKw, Pat, Eq, Expr }
-        struct Block: AttrsOwner, ModuleItemOwner {
-            LCurly,
-            statements: [Stmt],
-            Expr,
-            RCurly,
-        }
-        struct ParamList {
-            LParen,
-            SelfParam,
-            params: [Param],
-            RParen
-        }
-        struct SelfParam: TypeAscriptionOwner, AttrsOwner { Amp, Lifetime, SelfKw }
-        struct Param: TypeAscriptionOwner, AttrsOwner {
-            Pat,
-            Dotdotdot
-        }
-        struct UseItem: AttrsOwner, VisibilityOwner {
-            UseKw,
-            UseTree,
-        }
-        struct UseTree {
-            Path, Star, UseTreeList, Alias
-        }
-        struct Alias: NameOwner { AsKw }
-        struct UseTreeList { LCurly, use_trees: [UseTree], RCurly }
-        struct ExternCrateItem: AttrsOwner, VisibilityOwner {
-            ExternKw, CrateKw, NameRef, Alias,
-        }
-        struct ArgList {
-            LParen,
-            args: [Expr],
-            RParen
-        }
-        struct Path {
-            segment: PathSegment,
-            qualifier: Path,
-        }
-        struct PathSegment {
-            Coloncolon, LAngle, NameRef, TypeArgList, ParamList, RetType, PathType, RAngle
-        }
-        struct TypeArgList {
-            Coloncolon,
-            LAngle,
-            generic_args: [GenericArg],
-            type_args: [TypeArg],
-            lifetime_args: [LifetimeArg],
-            assoc_type_args: [AssocTypeArg],
-            const_args: [ConstArg],
-            RAngle
-        }
-        struct TypeArg { TypeRef }
-        struct AssocTypeArg : TypeBoundsOwner { NameRef, Eq, TypeRef }
-        struct LifetimeArg { Lifetime }
-        struct ConstArg { Literal, Eq, BlockExpr }
-
-        struct MacroItems: ModuleItemOwner, FnDefOwner { }
-
-        struct MacroStmts {
-            statements: [Stmt],
-            Expr,
-        }
-
-        struct ExternItemList: FnDefOwner, ModuleItemOwner {
-            LCurly,
-            extern_items: [ExternItem],
-            RCurly
-        }
-
-        struct ExternBlock {
-            Abi,
-            ExternItemList
-        }
-
-        struct MetaItem {
-            Path, Eq, AttrInput, nested_meta_items: [MetaItem]
-        }
-
-        struct MacroDef {
-            Name, TokenTree
-        }
-    },
-    enums: &ast_enums! {
-        enum NominalDef: NameOwner, TypeParamsOwner, AttrsOwner {
-            StructDef, EnumDef, UnionDef,
-        }
-
-        enum GenericParam {
-            LifetimeParam,
-            TypeParam,
-            ConstParam
-        }
-
-        enum GenericArg {
-            LifetimeArg,
-            TypeArg,
-            ConstArg,
-            AssocTypeArg
-        }
-
-        enum TypeRef {
-            ParenType,
-            TupleType,
-            NeverType,
-            PathType,
-            PointerType,
-            ArrayType,
-            SliceType,
-            ReferenceType,
-            PlaceholderType,
-            FnPointerType,
-            ForType,
-            ImplTraitType,
-            DynTraitType,
-        }
-
-        enum ModuleItem: NameOwner, AttrsOwner, VisibilityOwner {
-            StructDef,
-            UnionDef,
-            EnumDef,
-            FnDef,
-            TraitDef,
-            TypeAliasDef,
-            ImplDef,
-            UseItem,
-            ExternCrateItem,
-            ConstDef,
-            StaticDef,
-            Module,
-            MacroCall,
-            ExternBlock
-        }
-
-        /* impl blocks can also contain MacroCall */
-        enum ImplItem: NameOwner, AttrsOwner {
-            FnDef, TypeAliasDef, ConstDef
-        }
-
-        /* extern blocks can also contain MacroCall */
-        enum ExternItem: NameOwner, AttrsOwner, VisibilityOwner {
-            FnDef, StaticDef
-        }
-
-        enum Expr: AttrsOwner {
-            TupleExpr,
-            ArrayExpr,
-            ParenExpr,
-            PathExpr,
-            LambdaExpr,
-            IfExpr,
-            LoopExpr,
-            ForExpr,
-            WhileExpr,
-            ContinueExpr,
-            BreakExpr,
-            Label,
-            BlockExpr,
-            ReturnExpr,
-            MatchExpr,
-            RecordLit,
-            CallExpr,
-            IndexExpr,
-            MethodCallExpr,
-            FieldExpr,
-            AwaitExpr,
-            TryExpr,
-            TryBlockExpr,
-            CastExpr,
-            RefExpr,
-            PrefixExpr,
-            RangeExpr,
-            BinExpr,
-            Literal,
-            MacroCall,
-            BoxExpr,
-        }
-
-        enum Pat {
-            OrPat,
-            ParenPat,
-            RefPat,
-            BoxPat,
-            BindPat,
-            PlaceholderPat,
-            DotDotPat,
-            PathPat,
-            RecordPat,
-            TupleStructPat,
-            TuplePat,
-            SlicePat,
-            RangePat,
-            LiteralPat,
-            MacroPat,
-        }
-
-        enum RecordInnerPat {
-            RecordFieldPat,
-            BindPat
-        }
+impl Lexeme {
+    /// Rules start with an unindented name, the following lines of the rule
+    /// are indented or start with `|`.
+    fn starts_rule(&self) -> bool {
+        self.column == 0 && matches!(self.token, Token::Ident(_))
+    }
+}
 
-        enum AttrInput { Literal, TokenTree }
-        enum Stmt {
-            LetStmt,
-            ExprStmt,
-            // macro calls are parsed as expression statements */
-        }
+fn lex(text: &str) -> Result<Vec<Lexeme>> {
+    let mut res = Vec::new();
+    for (line_idx, line) in text.lines().enumerate() {
+        let line_no = line_idx + 1;
+        let mut column = 0;
+        while let Some(c) = line[column..].chars().next() {
+            let (token, len) = match c {
+                _ if c.is_whitespace() => {
+                    column += c.len_utf8();
+                    continue;
+                }
+                '/' if line[column..].starts_with("//") => break,
+                ':' => (Token::Colon, 1),
+                ',' => (Token::Comma, 1),
+                '=' => (Token::Eq, 1),
+                '*' => (Token::Star, 1),
+                '|' => (Token::Pipe, 1),
+                '\'' => match line[column + 1..].find('\'') {
+                    Some(len) if len > 0 => {
+                        let text = &line[column + 1..column + 1 + len];
+                        (Token::Token(text.to_string()), len + 2)
+                    }
+                    _ => bail!("line {}: unterminated token", line_no),
+                },
+                _ if c.is_ascii_alphabetic() || c == '_' => {
+                    let len = line[column..]
+                        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                        .unwrap_or(line.len() - column);
+                    (Token::Ident(line[column..column + len].to_string()), len)
+                }
+                _ => bail!("line {}: unexpected `{}`", line_no, c),
+            };
+            res.push(Lexeme { token, line: line_no, column });
+            column += len;
+        }
+    }
+    Ok(res)
+}
 
-        enum FieldDefList {
-            RecordFieldDefList,
-            TupleFieldDefList,
+/// Parses `Name: Trait, OtherTrait =`, returns the rest of the rule.
+fn parse_header(rule: &[Lexeme]) -> Result<(String, Vec<String>, &[Lexeme])> {
+    let line = rule[0].line;
+    let name = match &rule[0].token {
+        Token::Ident(name) => name.clone(),
+        _ => unreachable!(),
+    };
+    let mut traits = Vec::new();
+    let mut rest = &rule[1..];
+    if rest.first().map(|it| &it.token) == Some(&Token::Colon) {
+        loop {
+            match rest.get(1).map(|it| &it.token) {
+                Some(Token::Ident(name)) => traits.push(name.clone()),
+                _ => bail!("line {}: expected a trait in `{}`", line, name),
+            }
+            rest = &rest[2..];
+            if rest.first().map(|it| &it.token) != Some(&Token::Comma) {
+                break;
+            }
         }
-    },
-
-    token_enums: &ast_enums! {
-        enum LeftDelimiter { LParen, LBrack, LCurly }
-        enum RightDelimiter { RParen, RBrack, RCurly }
-        enum RangeSeparator { Dotdot, Dotdotdot, Dotdoteq}
+    }
+    match rest.first().map(|it| &it.token) {
+        Some(Token::Eq) => Ok((name, traits, &rest[1..])),
+        _ => bail!("line {}: expected `=` after `{}`", line, name),
+    }
+}
 
-        enum BinOp {
-            Pipepipe,
-            Ampamp,
-            Eqeq,
-            Neq,
-            Lteq,
-            Gteq,
-            LAngle,
-            RAngle,
-            Plus,
-            Star,
-            Minus,
-            Slash,
-            Percent,
-            Shl,
-            Shr,
-            Caret,
-            Pipe,
-            Amp,
-            Eq,
-            Pluseq,
-            Slasheq,
-            Stareq,
-            Percenteq,
-            Shreq,
-            Shleq,
-            Minuseq,
-            Pipeeq,
-            Ampeq,
-            Careteq,
-        }
+enum Variants {
+    Nodes(Vec<String>),
+    Tokens(Vec<String>),
+}
 
-        enum PrefixOp {
-            Minus,
-            Excl,
-            Star
+fn parse_variants(name: &str, body: &[Lexeme], kinds: &KindsSrc<'_>) -> Result<Variants> {
+    let mut nodes = Vec::new();
+    let mut tokens = Vec::new();
+    for variant in body.split(|it| it.token == Token::Pipe) {
+        match variant {
+            [Lexeme { token: Token::Ident(node), .. }] => nodes.push(node.clone()),
+            [Lexeme { token: Token::Token(text), line, .. }] => {
+                tokens.push(token_name(kinds, text, *line)?)
+            }
+            _ => {
+                let line = variant.first().map_or(body[0].line, |it| it.line);
+                bail!("line {}: a variant of `{}` must be a single node or token", line, name)
+            }
         }
+    }
+    match (nodes.is_empty(), tokens.is_empty()) {
+        (false, true) => Ok(Variants::Nodes(nodes)),
+        (true, false) => Ok(Variants::Tokens(tokens)),
+        _ => bail!("line {}: `{}` mixes nodes and tokens", body[0].line, name),
+    }
+}
 
-        enum RangeOp {
-            Dotdot,
-            Dotdoteq
+fn parse_fields(
+    name: &str,
+    mut body: &[Lexeme],
+    kinds: &KindsSrc<'_>,
+) -> Result<Vec<(String, FieldSrc<String>)>> {
+    // (label, type, is a list)
+    let mut fields: Vec<(Option<String>, String, bool)> = Vec::new();
+    while let Some(first) = body.first() {
+        let line = first.line;
+        let label = match (&first.token, body.get(1).map(|it| &it.token)) {
+            (Token::Ident(label), Some(Token::Colon)) => {
+                body = &body[2..];
+                Some(label.clone())
+            }
+            _ => None,
+        };
+        let ty = match body.first().map(|it| &it.token) {
+            Some(Token::Ident(ty)) => ty.clone(),
+            Some(Token::Token(text)) => token_name(kinds, text, line)?,
+            _ => bail!("line {}: expected a child of `{}`", line, name),
+        };
+        body = &body[1..];
+        let many = body.first().map(|it| &it.token) == Some(&Token::Star);
+        if many {
+            body = &body[1..];
+            if label.is_none() {
+                bail!("line {}: list of `{}` in `{}` needs a label", line, ty, name);
+            }
         }
+        fields.push((label, ty, many));
+    }
+
+    // An accessor for a single child returns the first child of its type, so
+    // several children of the same type are accessed by their position.
+    let mut counts = HashMap::new();
+    for (_, ty, _) in fields.iter().filter(|(_, _, many)| !many) {
+        *counts.entry(ty.clone()).or_insert(0) += 1;
+    }
+    let mut positions = HashMap::new();
+    let mut res = Vec::new();
+    for (label, ty, many) in fields {
+        let field = match label {
+            Some(label) if many => (label, FieldSrc::Many(ty)),
+            Some(label) if counts[&ty] > 1 => {
+                let n = positions.entry(ty.clone()).or_insert(0);
+                *n += 1;
+                (label, FieldSrc::Nth(ty, *n - 1))
+            }
+            Some(label) => (label, FieldSrc::Optional(ty)),
+            None if counts[&ty] > 1 => {
+                bail!("`{}` has several children of type `{}`, they need labels", name, ty)
+            }
+            None => (ty, FieldSrc::Shorthand),
+        };
+        res.push(field);
+    }
+    Ok(res)
+}
 
-        enum LiteralToken {
-            IntNumber,
-            FloatNumber,
-            String,
-            RawString,
-            TrueKw,
-            FalseKw,
-            ByteString,
-            RawByteString,
-            Char,
-            Byte
-        }
+fn token_name(kinds: &KindsSrc<'_>, text: &str, line: usize) -> Result<String> {
+    kinds
+        .token_name(text)
+        .ok_or_else(|| anyhow::format_err!("line {}: unknown token `'{}'`", line, text))
+}
 
-        enum NameRefToken {
-            Ident,
-            IntNumber
-        }
-    },
-};
+pub(crate) fn to_pascal_case(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    let mut prev_is_underscore = true;
+    for c in s.chars() {
+        if c == '_' {
+            prev_is_underscore = true;
+        } else if prev_is_underscore {
+            buf.push(c.to_ascii_uppercase());
+            prev_is_underscore = false;
+        } else {
+            buf.push(c.to_ascii_lowercase());
+        }
+    }
+    buf
+}
@@ -22,9 +22,14 @@ const GRAMMAR_DIR: &str = "crates/ra_parser/src/grammar";
 const OK_INLINE_TESTS_DIR: &str = "crates/ra_syntax/test_data/parser/inline/ok";
 const ERR_INLINE_TESTS_DIR: &str = "crates/ra_syntax/test_data/parser/inline/err";
 
+const AST_GRAMMAR: &str = "xtask/src/codegen/rust.ungram";
 const SYNTAX_KINDS: &str = "crates/ra_parser/src/syntax_kind/generated.rs";
 const AST_NODES: &str = "crates/ra_syntax/src/ast/generated/nodes.rs";
 const AST_TOKENS: &str = "crates/ra_syntax/src/ast/generated/tokens.rs";
+const AST_MAKE: &str = "crates/ra_syntax/src/ast/generated/make.rs";
+const AST_HAND_WRITTEN_MAKE: &str = "crates/ra_syntax/src/ast/make.rs";
+const AST_VISIT: &str = "crates/ra_syntax/src/ast/generated/visit.rs";
+const AST_SERIALIZE: &str = "crates/ra_syntax/src/ast/generated/serialize.rs";
 
 const ASSISTS_DIR: &str = "crates/ra_assists/src/handlers";
 const ASSISTS_TESTS: &str = "crates/ra_assists/src/doc_tests/generated.rs";
@@ -6,30 +6,53 @@
 use std::{
     borrow::Cow,
     collections::{BTreeSet, HashSet},
+    fs,
 };
 
+use anyhow::{bail, format_err, Context};
+
 use proc_macro2::{Punct, Spacing};
 use quote::{format_ident, quote};
 
 use crate::{
-    ast_src::{AstSrc, FieldSrc, KindsSrc, AST_SRC, KINDS_SRC},
+    ast_src::{to_pascal_case, AstSrc, FieldSrc, KindsSrc, KINDS_SRC},
     codegen::{self, update, Mode},
     project_root, Result,
 };
 
 pub fn generate_syntax(mode: Mode) -> Result<()> {
+    let grammar_file = project_root().join(codegen::AST_GRAMMAR);
+    let grammar = fs::read_to_string(&grammar_file)?;
+    let grammar = AstSrc::parse(&grammar, &KINDS_SRC)
+        .with_context(|| format!("failed to parse `{}`", grammar_file.display()))?;
+    validate_grammar(KINDS_SRC, &grammar)?;
+
     let syntax_kinds_file = project_root().join(codegen::SYNTAX_KINDS);
-    let syntax_kinds = generate_syntax_kinds(KINDS_SRC)?;
+    let syntax_kinds = generate_syntax_kinds(KINDS_SRC, &grammar)?;
     update(syntax_kinds_file.as_path(), &syntax_kinds, mode)?;
 
     let ast_nodes_file = project_root().join(codegen::AST_NODES);
-    let contents = generate_nodes(KINDS_SRC, AST_SRC)?;
+    let contents = generate_nodes(KINDS_SRC, &grammar)?;
     update(ast_nodes_file.as_path(), &contents, mode)?;
 
     let ast_tokens_file = project_root().join(codegen::AST_TOKENS);
-    let contents = generate_tokens(KINDS_SRC, AST_SRC)?;
+    let contents = generate_tokens(KINDS_SRC, &grammar)?;
     update(ast_tokens_file.as_path(), &contents, mode)?;
 
+    let ast_make_file = project_root().join(codegen::AST_MAKE);
+    let hand_written_make =
+        fs::read_to_string(project_root().join(codegen::AST_HAND_WRITTEN_MAKE))?;
+    let contents = generate_make(KINDS_SRC, &grammar, &hand_written_make)?;
+    update(ast_make_file.as_path(), &contents, mode)?;
+
+    let ast_visit_file = project_root().join(codegen::AST_VISIT);
+    let contents = generate_visit(&grammar)?;
+    update(ast_visit_file.as_path(), &contents, mode)?;
+
+    let ast_serialize_file = project_root().join(codegen::AST_SERIALIZE);
+    let contents = generate_serialize(&grammar)?;
+    update(ast_serialize_file.as_path(), &contents, mode)?;
+
     Ok(())
 }
 
@@ -40,7 +63,7 @@ struct ElementKinds {
     has_tokens: bool,
 }
 
-fn generate_tokens(kinds: KindsSrc<'_>, grammar: AstSrc<'_>) -> Result<String> {
+fn generate_tokens(kinds: KindsSrc<'_>, grammar: &AstSrc) -> Result<String> {
     let all_token_kinds: Vec<_> = kinds
         .punct
         .into_iter()
@@ -148,7 +171,7 @@ fn generate_tokens(kinds: KindsSrc<'_>, grammar: AstSrc<'_>) -> Result<String> {
     })
 }
 
-fn generate_nodes(kinds: KindsSrc<'_>, grammar: AstSrc<'_>) -> Result<String> {
+fn generate_nodes(kinds: KindsSrc<'_>, grammar: &AstSrc) -> Result<String> {
     let all_token_kinds: Vec<_> = kinds
         .punct
         .into_iter()
@@ -173,7 +196,7 @@ fn generate_nodes(kinds: KindsSrc<'_>, grammar: AstSrc<'_>) -> Result<String> {
         token_kinds.insert(name);
     }
 
-    for en in grammar.token_enums {
+    for en in grammar.token_enums.iter() {
         token_kinds.insert(en.name.to_string());
     }
 
@@ -191,7 +214,7 @@ fn generate_nodes(kinds: KindsSrc<'_>, grammar: AstSrc<'_>) -> Result<String> {
                 _ => format_ident!("{}", name),
             };
             let ty = match field {
-                FieldSrc::Optional(ty) | FieldSrc::Many(ty) => ty,
+                FieldSrc::Optional(ty) | FieldSrc::Many(ty) | FieldSrc::Nth(ty, _) => ty,
                 FieldSrc::Shorthand => name,
             };
 
@@ -205,6 +228,24 @@ fn generate_nodes(kinds: KindsSrc<'_>, grammar: AstSrc<'_>) -> Result<String> {
                         }
                     }
                 }
+                FieldSrc::Nth(_, n) => {
+                    let n = proc_macro2::Literal::usize_unsuffixed(*n);
+                    let is_token = token_kinds.contains(&ty.to_string());
+                    if is_token {
+                        let method_name = format_ident!("{}_token", method_name);
+                        quote! {
+                            pub fn #method_name(&self) -> Option<#ty> {
+                                support::nth_token(&self.syntax, #n)
+                            }
+                        }
+                    } else {
+                        quote! {
+                            pub fn #method_name(&self) -> Option<#ty> {
+                                support::nth_child(&self.syntax, #n)
+                            }
+                        }
+                    }
+                }
                 FieldSrc::Optional(_) | FieldSrc::Shorthand => {
                     let is_token = token_kinds.contains(&ty.to_string());
                     if is_token {
@@ -319,17 +360,6 @@ fn generate_nodes(kinds: KindsSrc<'_>, grammar: AstSrc<'_>) -> Result<String> {
             }
         });
 
-    let defined_nodes: HashSet<_> = grammar.nodes.iter().map(|node| node.name).collect();
-
-    for node in kinds
-        .nodes
-        .iter()
-        .map(|kind| to_pascal_case(*kind))
-        .filter(|name| !defined_nodes.contains(&**name))
-    {
-        eprintln!("Warning: node {} not defined in ast source", node);
-    }
-
     let ast = quote! {
         use crate::{
             SyntaxNode, SyntaxKind::{self, *},
@@ -347,7 +377,447 @@ fn generate_nodes(kinds: KindsSrc<'_>, grammar: AstSrc<'_>) -> Result<String> {
     Ok(pretty)
 }
 
-fn generate_syntax_kinds(grammar: KindsSrc<'_>) -> Result<String> {
+/// Rules which aren't children of other nodes in the grammar, but are used by
+/// the hand-written code.
+const ROOTS: &[&str] = &[
+    "SourceFile",
+    "MacroItems",
+    "MacroStmts",
+    "MacroDef",
+    "NominalDef",
+    "LeftDelimiter",
+    "RightDelimiter",
+];
+
+/// Children accessed by the hand-written methods of the traits.
+const TRAIT_CHILDREN: &[(&str, &[&str])] = &[
+    ("ArgListOwner", &["ArgList"]),
+    ("AttrsOwner", &["Attr"]),
+    ("FnDefOwner", &["FnDef"]),
+    ("LoopBodyOwner", &["BlockExpr", "Label"]),
+    ("ModuleItemOwner", &["ModuleItem"]),
+    ("NameOwner", &["Name"]),
+    ("TypeAscriptionOwner", &["TypeRef"]),
+    ("TypeBoundsOwner", &["TypeBoundList"]),
+    ("TypeParamsOwner", &["TypeParamList", "WhereClause"]),
+    ("VisibilityOwner", &["Visibility"]),
+];
+
+/// Checks that the grammar describes exactly the nodes produced by the parser,
+/// and that every rule of the grammar is defined and used.
+fn validate_grammar(kinds: KindsSrc<'_>, grammar: &AstSrc) -> Result<()> {
+    let mut errors = Vec::new();
+
+    let node_kinds: HashSet<String> = kinds.nodes.iter().map(|kind| to_pascal_case(kind)).collect();
+    let nodes: HashSet<&str> = grammar.nodes.iter().map(|node| node.name.as_str()).collect();
+    for node in grammar.nodes.iter().filter(|node| !node_kinds.contains(&node.name)) {
+        errors.push(format!("node `{}` is not a syntax kind", node.name));
+    }
+    let mut undefined_kinds =
+        node_kinds.iter().filter(|kind| !nodes.contains(kind.as_str())).collect::<Vec<_>>();
+    undefined_kinds.sort();
+    for kind in undefined_kinds {
+        errors.push(format!("node `{}` is not defined in the grammar", kind));
+    }
+
+    let token_names = token_names(&kinds);
+    let defined: HashSet<&str> = grammar
+        .nodes
+        .iter()
+        .map(|node| node.name.as_str())
+        .chain(grammar.enums.iter().chain(grammar.token_enums.iter()).map(|en| en.name.as_str()))
+        .collect();
+
+    let mut used: HashSet<&str> = ROOTS.iter().copied().collect();
+    let traits = grammar
+        .nodes
+        .iter()
+        .map(|node| &node.traits)
+        .chain(grammar.enums.iter().map(|en| &en.traits))
+        .flatten();
+    for trait_name in traits {
+        if let Some((_, children)) = TRAIT_CHILDREN.iter().find(|(name, _)| name == trait_name) {
+            used.extend(children.iter().copied());
+        }
+    }
+    for node in grammar.nodes.iter() {
+        for (name, field) in node.fields.iter() {
+            let ty = match field {
+                FieldSrc::Shorthand => name,
+                FieldSrc::Optional(ty) | FieldSrc::Many(ty) | FieldSrc::Nth(ty, _) => ty,
+            };
+            if !defined.contains(ty.as_str()) && !token_names.contains(ty) {
+                errors.push(format!("`{}` in `{}` is not defined in the grammar", ty, node.name));
+            }
+            used.insert(ty);
+        }
+    }
+    for en in grammar.enums.iter() {
+        for variant in en.variants.iter() {
+            if !nodes.contains(variant.as_str()) {
+                errors.push(format!("variant `{}` of `{}` is not a node", variant, en.name));
+            }
+            used.insert(variant);
+        }
+    }
+
+    let unused = grammar
+        .nodes
+        .iter()
+        .map(|node| &node.name)
+        .chain(grammar.enums.iter().chain(grammar.token_enums.iter()).map(|en| &en.name))
+        .filter(|name| !used.contains(name.as_str()));
+    for name in unused {
+        errors.push(format!("`{}` is not used in the grammar", name));
+    }
+
+    if !errors.is_empty() {
+        bail!("invalid grammar:\n{}", errors.join("\n"));
+    }
+    Ok(())
+}
+
+/// The names of the token types, like `FnKw` or `Ident`.
+fn token_names(kinds: &KindsSrc<'_>) -> HashSet<String> {
+    kinds
+        .punct
+        .iter()
+        .map(|(_, kind)| to_pascal_case(kind))
+        .chain(
+            kinds
+                .keywords
+                .iter()
+                .chain(kinds.contextual_keywords.iter())
+                .map(|kw| to_pascal_case(&format!("{}_kw", kw))),
+        )
+        .chain(kinds.literals.iter().chain(kinds.tokens.iter()).map(|kind| to_pascal_case(kind)))
+        .collect()
+}
+
+/// Source text in which the nodes of an enum are parsed by `make`, `{}` is
+/// replaced with the text of the node.
+const MAKE_ENUM_CONTEXTS: &[(&str, &str)] = &[
+    ("ModuleItem", "{}"),
+    ("Expr", "const C: () = {};"),
+    ("Pat", "fn f({}: ()) {{}}"),
+    ("TypeRef", "type T = {};"),
+    ("Stmt", "fn f() {{ {} }}"),
+    ("GenericParam", "fn f<{}>() {{}}"),
+    ("GenericArg", "type T = S<{}>;"),
+];
+
+/// Like `MAKE_ENUM_CONTEXTS`, for nodes which are not in such enums or which
+/// can only be parsed in a more specific context than their enum's.
+const MAKE_NODE_CONTEXTS: &[(&str, &str)] = &[
+    ("ArgList", "fn f() {{ f{}; }}"),
+    ("Attr", "{} fn f() {{}}"),
+    ("Condition", "fn f() {{ while {} {{}} }}"),
+    ("LetExpr", "fn f() {{ if {} && true {{}} }}"),
+    ("MatchArm", "fn f() {{ match () {{ {} }} }}"),
+    ("MatchArmList", "fn f() {{ match () {} }}"),
+    ("Name", "mod {};"),
+    ("NameRef", "fn f() {{ {}; }}"),
+    ("Param", "fn f({}) {{}}"),
+    ("ParamList", "fn f{} {{}}"),
+    ("PathSegment", "use {};"),
+    ("RecordField", "fn f() {{ S {{ {} }} }}"),
+    ("RetType", "fn f() {} {{}}"),
+    ("TypeBound", "fn f<T: {}>() {{}}"),
+    ("TypeParamList", "fn f{}() {{}}"),
+    ("UseTree", "use {};"),
+    ("Visibility", "{} fn f() {{}}"),
+    ("WhereClause", "fn f() {} {{}}"),
+    ("WherePred", "fn f() where {} {{}}"),
+];
+
+/// The text of the nodes for the `make` constructors with a parameter per
+/// field. `{field}` is replaced with the child, `{field?}` with an optional
+/// child and `{field*sep}` with a list of children separated by `sep`.
+/// `{(field)}` is an operand expression, which is put in parentheses unless
+/// it binds tighter than any operator.
+const MAKE_TEMPLATES: &[(&str, &str)] = &[
+    ("ArgList", "({args*, })"),
+    ("ArrayExpr", "[{exprs*, }]"),
+    ("ArrayType", "[{type_ref}; {expr}]"),
+    ("AwaitExpr", "{(expr)}.await"),
+    ("BoxExpr", "box {(expr)}"),
+    ("CastExpr", "{(expr)} as {type_ref}"),
+    ("FieldExpr", "{(expr)}.{name_ref}"),
+    ("IndexExpr", "{(base)}[{index}]"),
+    ("ParenExpr", "({expr})"),
+    ("ParenPat", "({pat})"),
+    ("ParenType", "({type_ref})"),
+    ("RefExpr", "&{(expr)}"),
+    ("RefPat", "&{pat}"),
+    ("RetType", "-> {type_ref}"),
+    ("ReturnExpr", "return {expr?}"),
+    ("SlicePat", "[{args*, }]"),
+    ("SliceType", "[{type_ref}]"),
+    ("TryExpr", "{(expr)}?"),
+];
+
+/// Generates `ast::make` constructors: every node which can be parsed in a
+/// context gets a `_from_text` constructor, and the nodes in
+/// `MAKE_TEMPLATES` get a constructor from their children.
+fn generate_make(kinds: KindsSrc<'_>, grammar: &AstSrc, hand_written: &str) -> Result<String> {
+    let token_names = token_names(&kinds);
+    let context = |node: &str| {
+        MAKE_NODE_CONTEXTS
+            .iter()
+            .find(|(name, _)| *name == node)
+            .or_else(|| {
+                MAKE_ENUM_CONTEXTS.iter().find(|(en, _)| {
+                    grammar
+                        .enums
+                        .iter()
+                        .any(|it| it.name == *en && it.variants.iter().any(|v| v == node))
+                })
+            })
+            .map(|(_, context)| *context)
+    };
+
+    let mut fns = Vec::new();
+    let mut fn_names = Vec::new();
+    for node in grammar.nodes.iter() {
+        let context = match context(&node.name) {
+            Some(it) => it,
+            None => continue,
+        };
+        let name = format_ident!("{}", node.name);
+        let from_text = format_ident!("{}_from_text", to_lower_snake_case(&node.name));
+        fn_names.push(from_text.to_string());
+        let text = if context == "{}" { quote!(text) } else { quote!(&format!(#context, text)) };
+        fns.push(quote! {
+            pub fn #from_text(text: &str) -> ast::#name {
+                ast_from_text(#text)
+            }
+        });
+    }
+
+    for &(node_name, template) in MAKE_TEMPLATES {
+        let node = grammar
+            .nodes
+            .iter()
+            .find(|node| node.name == node_name)
+            .ok_or_else(|| format_err!("`make` template for undefined node `{}`", node_name))?;
+        if context(node_name).is_none() {
+            bail!("`make` template for `{}`, which can't be parsed on its own", node_name);
+        }
+
+        let mut format_str = String::new();
+        let mut params = Vec::new();
+        let mut args = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let end = start
+                + rest[start..]
+                    .find('}')
+                    .ok_or_else(|| format_err!("unclosed `{{` in template of `{}`", node_name))?;
+            format_str.push_str(&rest[..start]);
+            format_str.push_str("{}");
+            let placeholder = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+
+            let is_operand = placeholder.starts_with('(') && placeholder.ends_with(')');
+            let placeholder =
+                if is_operand { &placeholder[1..placeholder.len() - 1] } else { placeholder };
+
+            let (field_name, list_sep, optional) = match placeholder.find('*') {
+                Some(idx) => (&placeholder[..idx], Some(&placeholder[idx + 1..]), false),
+                None if placeholder.ends_with('?') => {
+                    (&placeholder[..placeholder.len() - 1], None, true)
+                }
+                None => (placeholder, None, false),
+            };
+            let (ty, many) = node
+                .fields
+                .iter()
+                .find_map(|(name, field)| match field {
+                    FieldSrc::Shorthand if to_lower_snake_case(name) == field_name => {
+                        Some((name, false))
+                    }
+                    FieldSrc::Optional(ty) | FieldSrc::Nth(ty, _) if name == field_name => {
+                        Some((ty, false))
+                    }
+                    FieldSrc::Many(ty) if name == field_name => Some((ty, true)),
+                    _ => None,
+                })
+                .ok_or_else(|| format_err!("`{}` has no field `{}`", node_name, field_name))?;
+            if many != list_sep.is_some() {
+                bail!(
+                    "list field `{}` of `{}` must be written as `{{{}*sep}}`",
+                    field_name,
+                    node_name,
+                    field_name
+                );
+            }
+            if is_operand && (many || optional || ty != "Expr") {
+                bail!("operand `{}` of `{}` must be a single expression", field_name, node_name);
+            }
+
+            let param = format_ident!("{}", field_name);
+            let ty = format_ident!("{}", ty);
+            let is_token = token_names.contains(&ty.to_string());
+            match (list_sep, optional, is_token) {
+                (_, _, true) if many || optional => {
+                    bail!(
+                        "token field `{}` of `{}` can't be optional or a list",
+                        field_name,
+                        node_name
+                    )
+                }
+                (_, _, true) => {
+                    params.push(quote!(#param: &str));
+                    args.push(quote!(#param));
+                }
+                (Some(sep), _, _) => {
+                    params.push(quote!(#param: impl IntoIterator<Item = ast::#ty>));
+                    args.push(quote!(#param.into_iter().join(#sep)));
+                }
+                (None, true, _) => {
+                    params.push(quote!(#param: Option<ast::#ty>));
+                    args.push(quote!(#param.map_or(String::new(), |it| it.to_string())));
+                }
+                (None, false, _) => {
+                    params.push(quote!(#param: ast::#ty));
+                    if is_operand {
+                        args.push(quote!(operand_text(&#param)));
+                    } else {
+                        args.push(quote!(#param));
+                    }
+                }
+            }
+        }
+        format_str.push_str(rest);
+
+        let name = format_ident!("{}", node_name);
+        let fn_name = format_ident!("{}", to_lower_snake_case(node_name));
+        fn_names.push(fn_name.to_string());
+        let from_text = format_ident!("{}_from_text", to_lower_snake_case(node_name));
+        fns.push(quote! {
+            pub fn #fn_name(#(#params),*) -> ast::#name {
+                #from_text(&format!(#format_str, #(#args),*))
+            }
+        });
+    }
+
+    // The generated constructors are glob-imported into `make.rs`, so the
+    // hand-written ones would silently shadow them.
+    for name in fn_names {
+        if hand_written.contains(&format!("pub fn {}(", name)) {
+            bail!("`make::{}` is both generated and hand-written", name);
+        }
+    }
+
+    crate::reformat(quote! {
+        use itertools::Itertools;
+
+        use crate::ast::{self, make::{ast_from_text, operand_text}};
+
+        #(#fns)*
+    })
+}
+
+fn generate_visit(grammar: &AstSrc) -> Result<String> {
+    let (kinds, (names, methods)): (Vec<_>, (Vec<_>, Vec<_>)) = grammar
+        .nodes
+        .iter()
+        .map(|node| {
+            let kind = format_ident!("{}", to_upper_snake_case(&node.name));
+            let name = format_ident!("{}", node.name);
+            let method = format_ident!("visit_{}", to_lower_snake_case(&node.name));
+            (kind, (name, method))
+        })
+        .unzip();
+
+    crate::reformat(quote! {
+        use crate::{
+            ast::{self, AstNode},
+            SyntaxKind::*,
+            SyntaxNode,
+        };
+
+        /// A visitor over the syntax tree, with a method for each kind of node.
+        ///
+        /// The default implementations just visit the children of the node, so
+        /// an implementation only needs to override the methods for the nodes it
+        /// is interested in, and can stop the traversal by not calling `walk`.
+        pub trait Visitor {
+            /// Calls the `visit_*` method for the kind of `node`.
+            fn visit(&mut self, node: &SyntaxNode) {
+                match node.kind() {
+                    #(#kinds => self.#methods(&ast::#names { syntax: node.clone() }),)*
+                    _ => self.walk(node),
+                }
+            }
+
+            /// Visits the children of `node`.
+            fn walk(&mut self, node: &SyntaxNode) {
+                for child in node.children() {
+                    self.visit(&child);
+                }
+            }
+
+            #(
+                fn #methods(&mut self, node: &ast::#names) {
+                    self.walk(node.syntax())
+                }
+            )*
+        }
+    })
+}
+
+fn generate_serialize(grammar: &AstSrc) -> Result<String> {
+    let names = grammar
+        .nodes
+        .iter()
+        .map(|it| &it.name)
+        .chain(grammar.enums.iter().map(|it| &it.name))
+        .map(|name| format_ident!("{}", name));
+
+    crate::reformat(quote! {
+        use serde::{Serialize, Serializer};
+
+        use crate::{ast::{self, AstNode}, SerializeTree};
+
+        #(
+            impl Serialize for ast::#names {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    SerializeTree(self.syntax()).serialize(serializer)
+                }
+            }
+        )*
+    })
+}
+
+/// Sets of node kinds generated from the enums of the grammar, as pairs of the
+/// name of the set and the name of the enum.
+const KIND_SETS: &[(&str, &str)] = &[
+    ("ITEMS", "ModuleItem"),
+    ("IMPL_ITEMS", "ImplItem"),
+    ("EXPRESSIONS", "Expr"),
+    ("PATTERNS", "Pat"),
+    ("TYPES", "TypeRef"),
+    ("STATEMENTS", "Stmt"),
+    ("GENERIC_PARAMS", "GenericParam"),
+];
+
+/// Tokens which can start an item, apart from attributes, macro calls and
+/// contextual keywords.
+const ITEM_FIRST: &[&str] = &[
+    "async", "const", "crate", "enum", "extern", "fn", "impl", "macro", "mod", "pub", "static",
+    "struct", "trait", "type", "unsafe", "use",
+];
+
+/// Tokens which can start an expression, apart from literals. The parser sees
+/// `::` and `..` as two tokens each, hence `:` and `.`.
+const EXPR_FIRST: &[&str] = &[
+    "(", "{", "[", "|", "&", "*", "!", ".", "-", ":", "<", "async", "box", "break", "continue",
+    "crate", "for", "if", "loop", "match", "move", "return", "self", "super", "try", "unsafe",
+    "while", "IDENT", "LIFETIME", "L_DOLLAR",
+];
+
+fn generate_syntax_kinds(grammar: KindsSrc<'_>, ast: &AstSrc) -> Result<String> {
     let (single_byte_tokens_values, single_byte_tokens): (Vec<_>, Vec<_>) = grammar
         .punct
         .iter()
@@ -386,6 +856,34 @@ fn generate_syntax_kinds(grammar: KindsSrc<'_>) -> Result<String> {
 
     let nodes = grammar.nodes.iter().map(|name| format_ident!("{}", name)).collect::<Vec<_>>();
 
+    let kind_sets = KIND_SETS.iter().map(|&(set_name, enum_name)| {
+        let en = ast
+            .enums
+            .iter()
+            .find(|en| en.name == enum_name)
+            .unwrap_or_else(|| panic!("no enum `{}` in the grammar", enum_name));
+        let kinds = en.variants.iter().map(|variant| {
+            let kind = to_upper_snake_case(variant);
+            assert!(grammar.nodes.contains(&kind.as_str()), "`{}` is not a node kind", kind);
+            format_ident!("{}", kind)
+        });
+        let set_name = format_ident!("{}", set_name);
+        let doc = format!("The kinds of `ast::{}` nodes.", enum_name);
+        quote! {
+            #[doc = #doc]
+            pub const #set_name: SyntaxKindSet =
+                SyntaxKindSet::EMPTY #(.union(SyntaxKindSet::singleton(#kinds)))*;
+        }
+    });
+
+    let literal_first = ["true", "false"]
+        .iter()
+        .chain(grammar.literals)
+        .map(|token| token_kind(&grammar, token))
+        .collect::<Vec<_>>();
+    let item_first = ITEM_FIRST.iter().map(|token| token_kind(&grammar, token));
+    let expr_first = EXPR_FIRST.iter().map(|token| token_kind(&grammar, token));
+
     let ast = quote! {
         #![allow(bad_style, missing_docs, unreachable_pub)]
         /// The kind of syntax node, e.g. `IDENT`, `USE_KW`, or `STRUCT_DEF`.
@@ -409,8 +907,11 @@ fn generate_syntax_kinds(grammar: KindsSrc<'_>) -> Result<String> {
             __LAST,
         }
         use self::SyntaxKind::*;
+        use super::SyntaxKindSet;
 
         impl SyntaxKind {
+            #(#kind_sets)*
+
             pub fn is_keyword(self) -> bool {
                 match self {
                     #(#all_keywords)|* => true,
@@ -454,11 +955,52 @@ fn generate_syntax_kinds(grammar: KindsSrc<'_>) -> Result<String> {
             #((#punctuation_values) => { $crate::SyntaxKind::#punctuation };)*
             #((#all_keywords_idents) => { $crate::SyntaxKind::#all_keywords };)*
         }
+
+        /// Sets of tokens for the parser, derived from the syntax kinds so that
+        /// they don't go stale when new tokens are added.
+        #[allow(dead_code)]
+        pub(crate) mod token_sets {
+            use super::SyntaxKind::*;
+            use crate::TokenSet;
+
+            /// All keywords, including the contextual ones.
+            pub(crate) const KEYWORDS: TokenSet =
+                TokenSet::EMPTY #(.union(TokenSet::singleton(#all_keywords)))*;
+
+            /// Literals, including `true` and `false`.
+            pub(crate) const LITERAL_FIRST: TokenSet =
+                TokenSet::EMPTY #(.union(TokenSet::singleton(#literal_first)))*;
+
+            /// Tokens which can start an item, apart from attributes, macro
+            /// calls and contextual keywords.
+            pub(crate) const ITEM_FIRST: TokenSet =
+                TokenSet::EMPTY #(.union(TokenSet::singleton(#item_first)))*;
+
+            /// Tokens which can start an expression.
+            pub(crate) const EXPR_FIRST: TokenSet =
+                LITERAL_FIRST #(.union(TokenSet::singleton(#expr_first)))*;
+        }
     };
 
     crate::reformat(ast)
 }
 
+/// The kind of a token given by its text, or by its kind for tokens without
+/// fixed text.
+fn token_kind(grammar: &KindsSrc<'_>, token: &str) -> proc_macro2::Ident {
+    let name = if let Some((punct, name)) = grammar.punct.iter().find(|(it, _)| *it == token) {
+        assert!(punct.len() == 1, "the parser sees `{}` as several tokens", punct);
+        name.to_string()
+    } else if grammar.keywords.contains(&token) || grammar.contextual_keywords.contains(&token) {
+        format!("{}_KW", to_upper_snake_case(token))
+    } else if grammar.literals.contains(&token) || grammar.tokens.contains(&token) {
+        token.to_string()
+    } else {
+        panic!("`{}` is not a token", token)
+    };
+    format_ident!("{}", name)
+}
+
 fn to_upper_snake_case(s: &str) -> String {
     let mut buf = String::with_capacity(s.len());
     let mut prev = false;
@@ -486,19 +1028,3 @@ fn to_lower_snake_case(s: &str) -> String {
     }
     buf
 }
-
-fn to_pascal_case(s: &str) -> String {
-    let mut buf = String::with_capacity(s.len());
-    let mut prev_is_underscore = true;
-    for c in s.chars() {
-        if c == '_' {
-            prev_is_underscore = true;
-        } else if prev_is_underscore {
-            buf.push(c.to_ascii_uppercase());
-            prev_is_underscore = false;
-        } else {
-            buf.push(c.to_ascii_lowercase());
-        }
-    }
-    buf
-}
@@ -20,7 +20,16 @@ fn rust_files_are_tidy() {
 }
 
 fn check_todo(path: &Path, text: &str) {
-    if path.ends_with("tests/cli.rs") {
+    let need_todo = &[
+        // This file itself obviously needs to use todo (<- like this!).
+        "tests/cli.rs",
+        // Some of our assists and fixes generate `todo!()` placeholders.
+        "ra_syntax/src/ast/make.rs",
+        "ra_ide/src/diagnostics.rs",
+        "ra_assists/src/handlers/add_default_impl.rs",
+        "ra_assists/src/doc_tests/generated.rs",
+    ];
+    if need_todo.iter().any(|p| path.ends_with(p)) {
         return;
     }
     if text.contains("TODO") || text.contains("TOOD") || text.contains("todo!") {